@@ -0,0 +1,64 @@
+use std::{env, path::PathBuf, str::FromStr};
+
+use alloy_primitives::B256;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::mev::attestation::{AttestationSigner, SignedVictimAttestation};
+use clap::Parser;
+use eyre::{eyre, WrapErr};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct Attest {
+    /// Start block
+    #[arg(long, short)]
+    pub start_block:     u64,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:       u64,
+    /// File to write the signed attestations to as json
+    #[arg(long, short, default_value = "victim-attestations.json")]
+    pub output:          PathBuf,
+    /// Chain id of the EIP-712 domain
+    #[arg(long, default_value = "1")]
+    pub chain_id:        u64,
+    /// File holding the hex private key of the signer. If omitted the key is
+    /// read from `BRONTES_ATTESTATION_KEY`. The key isn't taken as an
+    /// argument so it doesn't end up in the shell history or process list
+    #[arg(long)]
+    pub signer_key_file: Option<PathBuf>,
+}
+
+impl Attest {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let key = match &self.signer_key_file {
+            Some(path) => std::fs::read_to_string(path)
+                .wrap_err_with(|| format!("failed to read signer key {}", path.display()))?,
+            None => env::var("BRONTES_ATTESTATION_KEY").map_err(|_| {
+                eyre!("no signer key, use --signer-key-file or BRONTES_ATTESTATION_KEY")
+            })?,
+        };
+        let signer = AttestationSigner::new(B256::from_str(key.trim())?, self.chain_id)?;
+
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+        let attestations = libmdbx
+            .try_fetch_curated_mev_blocks(Some(self.start_block), self.end_block)?
+            .iter()
+            .flat_map(|block| block.mev.iter())
+            .map(|bundle| signer.attest_bundle(bundle))
+            .collect::<eyre::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<SignedVictimAttestation>>();
+
+        std::fs::write(&self.output, serde_json::to_string_pretty(&attestations)?)?;
+        println!(
+            "signed {} victim attestations as {:?}, written to {}",
+            attestations.len(),
+            signer.signer(),
+            self.output.display()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use std::{env, path::Path};
+
+use brontes_core::beacon_client::BeaconClient;
+use brontes_types::{
+    db::traits::DBWriter, init_thread_pools, traits::TracingProvider,
+    unordered_buffer_map::BrontesStreamExt,
+};
+use clap::Parser;
+use eyre::eyre;
+use futures::StreamExt;
+
+use crate::{
+    cli::{get_env_vars, get_tracing_provider, load_database, static_object},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct BeaconInfoArgs {
+    /// Start Block
+    #[arg(long, short)]
+    pub start_block:     u64,
+    /// Block to fetch beacon data to (exclusive)
+    #[arg(long, short)]
+    pub end_block:       u64,
+    /// Beacon node rest api endpoint, defaults to `BEACON_ENDPOINT`
+    #[arg(long)]
+    pub beacon_endpoint: Option<String>,
+}
+
+impl BeaconInfoArgs {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let db_path = get_env_vars()?;
+        init_thread_pools(10);
+
+        let beacon_endpoint = self
+            .beacon_endpoint
+            .or_else(|| env::var("BEACON_ENDPOINT").ok())
+            .ok_or_else(|| eyre!("no beacon endpoint set, use --beacon-endpoint"))?;
+        let beacon = static_object(BeaconClient::new(beacon_endpoint));
+
+        let libmdbx = static_object(
+            load_database(&ctx.task_executor, brontes_db_endpoint, None, None, None, None).await?,
+        );
+        let tracer = static_object(get_tracing_provider(
+            Path::new(&db_path),
+            10,
+            ctx.task_executor.clone(),
+        )?);
+
+        let start_block = self.start_block;
+        let amount = (self.end_block - start_block) as f64;
+
+        futures::stream::iter(start_block..self.end_block)
+            .unordered_buffer_map(20, |block| async move {
+                if block % 5000 == 0 {
+                    tracing::info!(
+                        "beacon data {:.2}% done",
+                        (block - start_block) as f64 / amount * 100.0
+                    );
+                }
+
+                let res = async {
+                    let header = tracer
+                        .header_by_number(block)
+                        .await?
+                        .ok_or_else(|| eyre!("no header for block {block}"))?;
+                    let parent_block = block.saturating_sub(1);
+                    let parent = tracer
+                        .header_by_number(parent_block)
+                        .await?
+                        .ok_or_else(|| eyre!("no header for block {parent_block}"))?;
+
+                    let info = beacon
+                        .fetch_block_info(block, header.timestamp, parent.timestamp)
+                        .await?;
+                    libmdbx.write_beacon_info(block, info).await
+                }
+                .await;
+
+                if let Err(e) = res {
+                    tracing::error!(err=%e, block, "failed to fetch beacon data");
+                }
+            })
+            .collect::<Vec<_>>()
+            .await;
+
+        Ok(())
+    }
+}
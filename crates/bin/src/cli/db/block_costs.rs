@@ -0,0 +1,60 @@
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::db::block_costs::{BlockCost, BlockCostSummary};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct BlockCosts {
+    /// Start block
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Print the summary as json
+    #[arg(long, default_value = "false")]
+    pub json:        bool,
+}
+
+impl BlockCosts {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        let costs = libmdbx.fetch_block_costs(self.start_block, self.end_block + 1)?;
+        let summary = BlockCostSummary::new(costs.iter().map(|(_, cost)| cost));
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+            return Ok(())
+        }
+
+        println!(
+            "{} of {} blocks have a recorded cost",
+            summary.blocks,
+            self.end_block + 1 - self.start_block
+        );
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Cost", "Total", "Mean/Block", "Max"]);
+        table.add_row(cost_row("Trace RPC calls", &summary, |c| c.trace_rpc_calls));
+        table.add_row(cost_row("Trace bytes", &summary, |c| c.trace_bytes));
+        table.add_row(cost_row("Classification (us)", &summary, |c| c.classification_us));
+        table.add_row(cost_row("Inspection cpu (us)", &summary, |c| c.inspection_us));
+        table.add_row(cost_row("Inspectors skipped", &summary, |c| c.inspectors_skipped));
+        table.add_row(cost_row("DB bytes written", &summary, |c| c.db_bytes_written));
+        println!("{table}");
+
+        Ok(())
+    }
+}
+
+fn cost_row(name: &str, summary: &BlockCostSummary, field: impl Fn(&BlockCost) -> u64) -> Row {
+    Row::from(vec![
+        Cell::new(name),
+        Cell::new(field(&summary.total)),
+        Cell::new(format!("{:.2}", summary.mean(&field))),
+        Cell::new(field(&summary.max)),
+    ])
+}
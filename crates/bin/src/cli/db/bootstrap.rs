@@ -0,0 +1,58 @@
+use std::path::{Path, PathBuf};
+
+use brontes_database::libmdbx::{RemoteSnapshot, SnapshotManifest};
+use clap::Parser;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct Bootstrap {
+    /// rclone remote and path the snapshot is published under, e.g.
+    /// `s3:brontes-snapshots/mainnet`
+    #[arg(long)]
+    pub remote:       String,
+    /// Directory the snapshot is downloaded to before it's unpacked, defaults
+    /// to the temp dir
+    #[arg(long)]
+    pub download_dir: Option<PathBuf>,
+    /// Only print the manifest of the published snapshot
+    #[arg(long, default_value = "false")]
+    pub manifest:     bool,
+}
+
+impl Bootstrap {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let download_dir = self
+            .download_dir
+            .unwrap_or_else(|| std::env::temp_dir().join("brontes-db-snapshot"));
+        let snapshot = RemoteSnapshot::new(self.remote, download_dir)?;
+
+        if self.manifest {
+            let manifest = snapshot.fetch_manifest()?;
+            println!("{}", serde_json::to_string_pretty(&manifest)?);
+            for reason in manifest.incompatibilities() {
+                println!("incompatible: {reason}");
+            }
+            return Ok(())
+        }
+
+        let db_path = Path::new(&brontes_db_endpoint);
+        if db_path.join("mdbx.dat").exists() {
+            eyre::bail!(
+                "a db already exists at {brontes_db_endpoint}, bootstrap into an empty directory"
+            )
+        }
+
+        let SnapshotManifest { tip_block, .. } = snapshot.bootstrap(db_path)?;
+        // opening the db creates the tables added since the snapshot was published
+        load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        println!(
+            "bootstrapped the db up to block {tip_block}, run with `--from-snapshot-tip` to \
+             resume indexing from block {}",
+            tip_block + 1
+        );
+
+        Ok(())
+    }
+}
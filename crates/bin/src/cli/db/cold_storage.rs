@@ -0,0 +1,63 @@
+use brontes_database::libmdbx::ColdStorage;
+use clap::{Parser, Subcommand};
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::eyre;
+
+use crate::{
+    cli::{load_libmdbx, ColdStorageArgs},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct ColdStorageCmd {
+    #[clap(subcommand)]
+    pub command: ColdStorageCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ColdStorageCommand {
+    /// Moves the traces and cex data of every full range older than
+    /// `--cold-keep-blocks` to the remote
+    Offload {
+        #[clap(flatten)]
+        args: ColdStorageArgs,
+    },
+    /// Lists the ranges that have been moved to the remote
+    List,
+}
+
+impl ColdStorageCmd {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint.clone())?;
+
+        match self.command {
+            ColdStorageCommand::Offload { args } => {
+                let config = args.config(&brontes_db_endpoint).ok_or_else(|| {
+                    eyre!(
+                        "--cold-storage-remote and --cold-storage-endpoint are required to offload"
+                    )
+                })?;
+                let segments = ColdStorage::new(config)?.offload(&libmdbx.db)?;
+
+                println!("moved {} ranges to cold storage", segments.len());
+            }
+            ColdStorageCommand::List => {
+                let segments = libmdbx.fetch_cold_segments()?;
+
+                let mut table = ComfyTable::new();
+                table.set_header(["Start Block", "End Block", "Object", "Size (MB)"]);
+                for (start_block, segment) in &segments {
+                    table.add_row(Row::from(vec![
+                        Cell::new(start_block),
+                        Cell::new(segment.end_block),
+                        Cell::new(&segment.object),
+                        Cell::new(format!("{:.1}", segment.bytes as f64 / 1_000_000.0)),
+                    ]));
+                }
+                println!("{table}");
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -13,7 +13,9 @@ pub struct Clear {
         value_delimiter = ',',
         default_value = "CexPrice,DexPrice,CexTrades,BlockInfo,InitializedState,MevBlocks,\
                          TokenDecimals,AddressToProtocolInfo,PoolCreationBlocks,Builder,\
-                         AddressMeta,SearcherEOAs,SearcherContracts,SubGraphs,TxTraces"
+                         AddressMeta,SearcherEOAs,SearcherContracts,SubGraphs,TxTraces,\
+                         BeaconBlocks,FeeHistory,BlockOrderFlows,BuilderPayments,\
+                         SearcherOrderFlows"
     )]
     pub tables:                  Vec<Tables>,
     /// Mark metadata as uninitialized in the initialized state table
@@ -66,7 +68,32 @@ impl Clear {
                 AddressMeta,
                 SearcherEOAs,
                 SearcherContracts,
-                TxTraces
+                TxTraces,
+                BeaconBlocks,
+                FeeHistory,
+                BlockOrderFlows,
+                BuilderPayments,
+                SearcherOrderFlows,
+                BundleOverrides,
+                BlockCosts,
+                BlockSearcherStatistics,
+                SearcherStatistics,
+                ColdSegments,
+                BundleTags,
+                InspectorArtifacts,
+                ScheduledJobRuns,
+                BlockChecksums,
+                BundleRepricings,
+                RunProvenances,
+                WalletFundings,
+                PoolPriceHistory,
+                MevReceipts,
+                Suppressions,
+                FrontendLosses,
+                TokenMeta,
+                ConfigReloads,
+                PublishCheckpoints,
+                MevRollups
             )
         });
 
@@ -53,6 +53,31 @@ impl Insert {
             SearcherEOAs,
             SearcherContracts,
             InitializedState,
+            BeaconBlocks,
+            FeeHistory,
+            BlockOrderFlows,
+            BuilderPayments,
+            SearcherOrderFlows,
+            BundleOverrides,
+            BlockCosts,
+            BlockSearcherStatistics,
+            SearcherStatistics,
+            ColdSegments,
+            BundleTags,
+            InspectorArtifacts,
+            ScheduledJobRuns,
+            BlockChecksums,
+            BundleRepricings,
+            RunProvenances,
+            WalletFundings,
+            PoolPriceHistory,
+            MevReceipts,
+            Suppressions,
+            FrontendLosses,
+            TokenMeta,
+            ConfigReloads,
+            PublishCheckpoints,
+            MevRollups,
             PoolCreationBlocks = &self.key,
             &self.value
         );
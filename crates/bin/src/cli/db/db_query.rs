@@ -77,7 +77,32 @@ impl DatabaseQuery {
                     AddressMeta,
                     SearcherEOAs,
                     SearcherContracts,
-                    TxTraces
+                    TxTraces,
+                    BeaconBlocks,
+                    FeeHistory,
+                    BlockOrderFlows,
+                    BuilderPayments,
+                    SearcherOrderFlows,
+                    BundleOverrides,
+                    BlockCosts,
+                    BlockSearcherStatistics,
+                    SearcherStatistics,
+                    ColdSegments,
+                    BundleTags,
+                    InspectorArtifacts,
+                    ScheduledJobRuns,
+                    BlockChecksums,
+                    BundleRepricings,
+                    RunProvenances,
+                    WalletFundings,
+                    PoolPriceHistory,
+                    MevReceipts,
+                    Suppressions,
+                    FrontendLosses,
+                    TokenMeta,
+                    ConfigReloads,
+                    PublishCheckpoints,
+                    MevRollups
                 );
             } else {
                 match_table!(
@@ -97,6 +122,31 @@ impl DatabaseQuery {
                     SearcherEOAs,
                     SearcherContracts,
                     TxTraces,
+                    BeaconBlocks,
+                    FeeHistory,
+                    BlockOrderFlows,
+                    BuilderPayments,
+                    SearcherOrderFlows,
+                    BundleOverrides,
+                    BlockCosts,
+                    BlockSearcherStatistics,
+                    SearcherStatistics,
+                    ColdSegments,
+                    BundleTags,
+                    InspectorArtifacts,
+                    ScheduledJobRuns,
+                    BlockChecksums,
+                    BundleRepricings,
+                    RunProvenances,
+                    WalletFundings,
+                    PoolPriceHistory,
+                    MevReceipts,
+                    Suppressions,
+                    FrontendLosses,
+                    TokenMeta,
+                    ConfigReloads,
+                    PublishCheckpoints,
+                    MevRollups,
                     PoolCreationBlocks = &self.key
                 );
             }
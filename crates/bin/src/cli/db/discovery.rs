@@ -47,7 +47,7 @@ impl DiscoveryFill {
             static_object(load_read_only_database(&ctx.task_executor, brontes_db_endpoint).await?);
 
         let tracer =
-            get_tracing_provider(Path::new(&db_path), max_tasks as u64, ctx.task_executor.clone());
+            get_tracing_provider(Path::new(&db_path), max_tasks as u64, ctx.task_executor.clone())?;
 
         let parser = static_object(DParser::new(metrics_tx, libmdbx, tracer.clone()).await);
 
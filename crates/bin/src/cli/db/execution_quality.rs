@@ -0,0 +1,88 @@
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::mev::{
+    execution_quality::{execution_quality_by_router, RouterExecutionQuality},
+    BundleData,
+};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct ExecutionQuality {
+    /// Start block, if omitted starts at the first processed block
+    #[arg(long, short)]
+    pub start_block: Option<u64>,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Number of routers to show
+    #[arg(long, default_value = "20")]
+    pub limit:       usize,
+    /// Print the report as json
+    #[arg(long, default_value = "false")]
+    pub json:        bool,
+}
+
+impl ExecutionQuality {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+        let blocks = libmdbx.try_fetch_curated_mev_blocks(self.start_block, self.end_block)?;
+
+        let executions = blocks
+            .iter()
+            .flat_map(|block| block.mev.iter())
+            .flat_map(|bundle| match &bundle.data {
+                BundleData::Sandwich(s) => s.victim_execution.as_slice(),
+                BundleData::JitSandwich(s) => s.victim_execution.as_slice(),
+                _ => &[],
+            });
+
+        let mut report = execution_quality_by_router(executions);
+        report.truncate(self.limit);
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(())
+        }
+
+        self.print_report(libmdbx, report);
+
+        Ok(())
+    }
+
+    fn print_report<DB: LibmdbxReader>(&self, db: &DB, report: Vec<RouterExecutionQuality>) {
+        let bps = |value: Option<f64>| value.map(|v| format!("{v:.2}")).unwrap_or_default();
+
+        let mut table = ComfyTable::new();
+        table.set_header([
+            "Router",
+            "Name",
+            "Victim Swaps",
+            "vs Pre-Frontrun (bps)",
+            "vs CEX (bps)",
+        ]);
+        for quality in report {
+            let name = db
+                .try_fetch_address_metadata(quality.router)
+                .ok()
+                .flatten()
+                .and_then(|metadata| metadata.nametag.or(metadata.entity_name))
+                .or_else(|| {
+                    db.get_protocol(quality.router)
+                        .ok()
+                        .map(|protocol| protocol.to_string())
+                })
+                .unwrap_or_default();
+
+            table.add_row(Row::from(vec![
+                Cell::new(format!("{:?}", quality.router)),
+                Cell::new(name),
+                Cell::new(quality.victim_swaps),
+                Cell::new(bps(quality.mean_improvement_vs_pre_frontrun_bps)),
+                Cell::new(bps(quality.mean_improvement_vs_cex_bps)),
+            ]));
+        }
+        println!("{table}");
+    }
+}
@@ -1,37 +1,60 @@
-use std::sync::Arc;
+use std::{
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+};
 
-use brontes_database::{parquet::ParquetExporter, Tables};
+use brontes_database::{
+    libmdbx::{LibmdbxReadWriter, LibmdbxReader},
+    parquet::ParquetExporter,
+    Tables,
+};
+use brontes_types::db::export_filter::ExportFilter;
 use clap::Parser;
 use futures::future::join_all;
 use tokio::task::spawn;
 use tracing::error;
 
 use crate::{
-    cli::{load_libmdbx, static_object},
+    cli::{load_libmdbx, static_object, ExportFilterArgs},
     runner::CliContext,
 };
 #[derive(Debug, Parser)]
 pub struct Export {
     /// Optional tables to exports, if omitted will export all supported tables
     #[arg(long, short, default_values = &["MevBlocks", "AddressMeta", "SearcherContracts", "Builder"], value_delimiter = ',', ignore_case=true)]
-    pub tables:      Vec<Tables>,
+    pub tables:        Vec<Tables>,
     /// Optional Start Block, if omitted it will export the entire range to
     /// parquet
     #[arg(long, short)]
-    pub start_block: Option<u64>,
+    pub start_block:   Option<u64>,
     /// Optional End Block
     #[arg(long, short)]
-    pub end_block:   Option<u64>,
+    pub end_block:     Option<u64>,
     /// Optional path, will default to "data_exports/"
     #[arg(long, short)]
-    pub path:        Option<String>,
+    pub path:          Option<String>,
+    /// Export the mev blocks as json lines instead of parquet
+    #[arg(long, default_value = "false")]
+    pub json:          bool,
+    /// Address lists to leave out of or redact in the export
+    #[clap(flatten)]
+    pub export_filter: ExportFilterArgs,
 }
 
 impl Export {
     pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
         let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?);
-        let exporter =
-            Arc::new(ParquetExporter::new(self.start_block, self.end_block, self.path, libmdbx));
+        let filter = self.export_filter.load()?;
+
+        if self.json {
+            return self.export_json(libmdbx, filter.as_deref())
+        }
+
+        let exporter = Arc::new(
+            ParquetExporter::new(self.start_block, self.end_block, self.path, libmdbx)
+                .with_filter(filter),
+        );
 
         let futures = self.tables.into_iter().map(|t| {
             let exporter = exporter.clone();
@@ -56,4 +79,31 @@ impl Export {
 
         Ok(())
     }
+
+    /// Writes the mev blocks to `mev_blocks.jsonl`, one block per line
+    fn export_json(
+        &self,
+        libmdbx: &LibmdbxReadWriter,
+        filter: Option<&ExportFilter>,
+    ) -> eyre::Result<()> {
+        let mev_blocks = match self.end_block {
+            Some(end_block) => libmdbx.try_fetch_mev_blocks(self.start_block, end_block)?,
+            None => libmdbx.fetch_all_mev_blocks(self.start_block)?,
+        };
+
+        let dir = PathBuf::from(self.path.as_deref().unwrap_or("data_exports/"));
+        std::fs::create_dir_all(&dir)?;
+        let mut file = BufWriter::new(std::fs::File::create(dir.join("mev_blocks.jsonl"))?);
+
+        for mut block in mev_blocks {
+            if let Some(filter) = filter {
+                filter.apply(&mut block);
+            }
+            serde_json::to_writer(&mut file, &block)?;
+            file.write_all(b"\n")?;
+        }
+        file.flush()?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,45 @@
+use std::path::PathBuf;
+
+use brontes_core::flat_files::traces::write_block_traces;
+use brontes_database::libmdbx::LibmdbxReader;
+use clap::Parser;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct ExportTraces {
+    /// Start block
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Directory the trace files are written to, read by `brontes run
+    /// --trace-files-dir`
+    #[arg(long)]
+    pub dir:         PathBuf,
+}
+
+impl ExportTraces {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        let mut missing = 0;
+        for block in self.start_block..=self.end_block {
+            match libmdbx.load_trace(block) {
+                Ok(traces) => write_block_traces(&self.dir, block, traces)?,
+                Err(e) => {
+                    tracing::warn!(block, %e, "no traces to export");
+                    missing += 1;
+                }
+            }
+        }
+        println!(
+            "exported the traces of {} blocks to {}, {missing} had none",
+            self.end_block - self.start_block + 1 - missing,
+            self.dir.display()
+        );
+
+        Ok(())
+    }
+}
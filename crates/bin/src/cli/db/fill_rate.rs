@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::{
+    db::bundle_submissions::{
+        FillRate as Rate, FillRateReport, SearcherFillRate, StrategyFillRate, SubmittedBundle,
+    },
+    FastHashMap, FastHashSet,
+};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct FillRate {
+    /// File of submitted bundles, one json object per line with the
+    /// `block_number`, `searcher` and `tx_hashes` of the bundle and optionally
+    /// the `builder` and `relay` it was sent to
+    #[arg(long)]
+    pub submissions: PathBuf,
+    /// Start block, if omitted starts at the first block with a submission
+    #[arg(long, short)]
+    pub start_block: Option<u64>,
+    /// End block (inclusive), if omitted ends at the last block with a
+    /// submission
+    #[arg(long, short)]
+    pub end_block:   Option<u64>,
+    /// Number of searchers to show
+    #[arg(long, default_value = "20")]
+    pub limit:       usize,
+    /// Print the report as json
+    #[arg(long, default_value = "false")]
+    pub json:        bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    total:      Rate,
+    strategies: Vec<StrategyFillRate>,
+    searchers:  Vec<SearcherFillRate>,
+}
+
+impl FillRate {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        let submissions: FastHashMap<u64, Vec<SubmittedBundle>> =
+            SubmittedBundle::from_json_lines_file(&self.submissions)?
+                .into_iter()
+                .filter(|s| {
+                    self.start_block
+                        .map_or(true, |start| s.block_number >= start)
+                })
+                .filter(|s| self.end_block.map_or(true, |end| s.block_number <= end))
+                .into_group_map_by(|s| s.block_number)
+                .into_iter()
+                .collect();
+
+        let (Some(start), Some(end)) =
+            (submissions.keys().min().copied(), submissions.keys().max().copied())
+        else {
+            println!("no submissions in the block range");
+            return Ok(())
+        };
+
+        let mut report = FillRateReport::default();
+        for block in libmdbx.try_fetch_curated_mev_blocks(Some(start), end)? {
+            let block_number = block.block.block_number;
+            let Some(block_submissions) = submissions.get(&block_number) else { continue };
+
+            let block_txs = match libmdbx.load_trace(block_number) {
+                Ok(traces) => traces
+                    .into_iter()
+                    .map(|trace| trace.tx_hash)
+                    .collect::<FastHashSet<_>>(),
+                Err(e) => {
+                    tracing::warn!(block_number, err=%e, "no traces for block, skipping");
+                    continue
+                }
+            };
+
+            report.add_block(block_submissions, &block_txs, &block.mev);
+        }
+
+        let strategies = report.by_strategy();
+        let mut searchers = report.by_searcher();
+        searchers.truncate(self.limit);
+
+        if self.json {
+            let report = Report { total: report.total(), strategies, searchers };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(())
+        }
+
+        let total = report.total();
+        println!(
+            "{} of {} submitted bundles landed ({:.2}%)",
+            total.landed,
+            total.submitted,
+            total.fill_rate() * 100.0
+        );
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Strategy", "Submitted", "Landed", "Fill Rate"]);
+        for strategy in strategies {
+            table.add_row(fill_rate_row(
+                strategy
+                    .mev_type
+                    .map_or_else(|| "Undetected".to_string(), |t| t.to_string()),
+                &strategy.fill_rate,
+            ));
+        }
+        println!("{table}");
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Searcher", "Submitted", "Landed", "Fill Rate"]);
+        for searcher in searchers {
+            table.add_row(fill_rate_row(format!("{:?}", searcher.searcher), &searcher.fill_rate));
+        }
+        println!("{table}");
+
+        Ok(())
+    }
+}
+
+fn fill_rate_row(name: String, rate: &Rate) -> Row {
+    Row::from(vec![
+        Cell::new(name),
+        Cell::new(rate.submitted),
+        Cell::new(rate.landed),
+        Cell::new(format!("{:.2}%", rate.fill_rate() * 100.0)),
+    ])
+}
@@ -0,0 +1,97 @@
+use alloy_primitives::Address;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::db::frontend_losses::{frontend_loss_summary, FrontendLossSummary};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct FrontendLosses {
+    /// Start block
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Sum the losses over intervals of this many blocks instead of over the
+    /// whole range, e.g. 7200 for daily losses
+    #[arg(long)]
+    pub interval:    Option<u64>,
+    /// Number of frontends to show per interval
+    #[arg(long, default_value = "20")]
+    pub limit:       usize,
+    /// Print the summary as json
+    #[arg(long, default_value = "false")]
+    pub json:        bool,
+}
+
+impl FrontendLosses {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        let blocks = libmdbx.fetch_frontend_losses(self.start_block, self.end_block + 1)?;
+        let mut summary = frontend_loss_summary(&blocks, self.interval);
+        // sorted by loss within each interval
+        let mut shown = 0;
+        let mut interval = None;
+        summary.retain(|s| {
+            if interval != Some(s.start_block) {
+                interval = Some(s.start_block);
+                shown = 0;
+            }
+            shown += 1;
+            shown <= self.limit
+        });
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&summary)?);
+            return Ok(())
+        }
+
+        self.print_summary(&libmdbx, summary);
+
+        Ok(())
+    }
+
+    fn print_summary<DB: LibmdbxReader>(&self, db: &DB, summary: Vec<FrontendLossSummary>) {
+        let mut table = ComfyTable::new();
+        table.set_header(["Start Block", "Router", "Name", "Referrer", "Victim Txs", "Loss (USD)"]);
+        for loss in summary {
+            table.add_row(Row::from(vec![
+                Cell::new(loss.start_block),
+                Cell::new(format!("{:?}", loss.router)),
+                Cell::new(name(db, loss.router)),
+                Cell::new(
+                    loss.referrer
+                        .map(|referrer| {
+                            let name = name(db, referrer);
+                            if name.is_empty() {
+                                format!("{referrer:?}")
+                            } else {
+                                name
+                            }
+                        })
+                        .unwrap_or_default(),
+                ),
+                Cell::new(loss.victim_txs),
+                Cell::new(format!("{:.2}", loss.loss_usd)),
+            ]));
+        }
+        println!("{table}");
+    }
+}
+
+/// Name of the frontend from its address metadata or classified protocol
+fn name<DB: LibmdbxReader>(db: &DB, address: Address) -> String {
+    db.try_fetch_address_metadata(address)
+        .ok()
+        .flatten()
+        .and_then(|metadata| metadata.nametag.or(metadata.entity_name))
+        .or_else(|| {
+            db.get_protocol(address)
+                .ok()
+                .map(|protocol| protocol.to_string())
+        })
+        .unwrap_or_default()
+}
@@ -70,11 +70,13 @@ impl Init {
         init_thread_pools(10);
         let task_executor = ctx.task_executor;
 
-        let libmdbx =
-            static_object(load_database(&task_executor, brontes_db_endpoint, None, None).await?);
+        let libmdbx = static_object(
+            load_database(&task_executor, brontes_db_endpoint, None, None, None, None).await?,
+        );
         let clickhouse = static_object(load_clickhouse(Default::default(), None).await?);
 
-        let tracer = Arc::new(get_tracing_provider(Path::new(&db_path), 10, task_executor.clone()));
+        let tracer =
+            Arc::new(get_tracing_provider(Path::new(&db_path), 10, task_executor.clone())?);
 
         if self.init_libmdbx {
             // currently inits all tables
@@ -0,0 +1,61 @@
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::db::inspector_artifacts::ArtifactKind;
+use clap::Parser;
+use itertools::Itertools;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct InspectorArtifacts {
+    /// Start block
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Only the artifacts of the inspector, by its id
+    #[arg(long)]
+    pub inspector:   Option<String>,
+    /// Only the artifacts of the kind
+    #[arg(long)]
+    pub kind:        Option<ArtifactKind>,
+    /// Print the artifacts as json lines
+    #[arg(long, default_value = "false")]
+    pub json:        bool,
+}
+
+impl InspectorArtifacts {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        for (block, artifacts) in
+            libmdbx.fetch_inspector_artifacts(self.start_block, self.end_block + 1)?
+        {
+            for artifact in artifacts.artifacts.iter().filter(|artifact| {
+                self.inspector
+                    .as_ref()
+                    .map_or(true, |inspector| &artifact.inspector == inspector)
+                    && self.kind.map_or(true, |kind| artifact.kind == kind)
+            }) {
+                if self.json {
+                    println!("{}", serde_json::json!({ "block": block, "artifact": artifact }));
+                    continue
+                }
+
+                println!(
+                    "{block} {} {} [{}] {}",
+                    artifact.inspector,
+                    artifact.kind.as_ref(),
+                    artifact
+                        .tx_hashes
+                        .iter()
+                        .map(|tx| format!("{tx:?}"))
+                        .join(", "),
+                    artifact.detail
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::{
+    db::leaderboard::{MevLeaderboard, ProtocolRanking, SearcherRanking},
+    mev::MevType,
+};
+use clap::{Parser, ValueEnum};
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::eyre;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Ranking {
+    /// Top searchers by net profit
+    Profit,
+    /// Top searchers by bundle count
+    Bundles,
+    /// Top searchers by net profit for a single mev type, requires --mev-type
+    MevType,
+    /// Protocols whose users lost the most to mev
+    Protocols,
+}
+
+#[derive(Debug, Parser)]
+pub struct Leaderboard {
+    /// Start block, if omitted starts at the first processed block
+    #[arg(long, short)]
+    pub start_block: Option<u64>,
+    /// End block (inclusive)
+    #[arg(long, short, required_unless_present = "all_time")]
+    pub end_block:   Option<u64>,
+    /// Rank searchers over all processed blocks using the running searcher
+    /// statistics instead of aggregating the mev blocks. Doesn't support
+    /// protocol rankings
+    #[arg(long, default_value = "false", conflicts_with_all = ["start_block", "end_block"])]
+    pub all_time:    bool,
+    #[arg(long, value_enum, default_value = "profit")]
+    pub by:          Ranking,
+    #[arg(long)]
+    pub mev_type:    Option<MevType>,
+    #[arg(long, default_value = "10")]
+    pub limit:       usize,
+    /// Print the leaderboard as json
+    #[arg(long, default_value = "false")]
+    pub json:        bool,
+}
+
+impl Leaderboard {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+        let leaderboard = if self.all_time {
+            if matches!(self.by, Ranking::Protocols) {
+                return Err(eyre!("--all-time doesn't support protocol rankings"))
+            }
+            MevLeaderboard::from_searcher_stats(libmdbx.fetch_all_searcher_stats()?)
+        } else {
+            libmdbx.fetch_mev_leaderboard(self.start_block, self.end_block.unwrap())?
+        };
+
+        match self.by {
+            Ranking::Profit => {
+                self.print_searchers(leaderboard.top_searchers_by_profit(self.limit))
+            }
+            Ranking::Bundles => {
+                self.print_searchers(leaderboard.top_searchers_by_bundles(self.limit))
+            }
+            Ranking::MevType => {
+                let mev_type = self
+                    .mev_type
+                    .ok_or_else(|| eyre!("--by mev-type requires --mev-type"))?;
+                self.print_searchers(leaderboard.top_searchers_by_type(mev_type, self.limit))
+            }
+            Ranking::Protocols => {
+                self.print_protocols(leaderboard.top_victimized_protocols(self.limit))
+            }
+        }
+    }
+
+    fn print_searchers(&self, rankings: Vec<SearcherRanking>) -> eyre::Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&rankings)?);
+            return Ok(())
+        }
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Rank", "Searcher", "Bundles", "Profit (USD)", "Bribes (USD)"]);
+        for (rank, ranking) in rankings.into_iter().enumerate() {
+            table.add_row(Row::from(vec![
+                Cell::new(rank + 1),
+                Cell::new(ranking.searcher),
+                Cell::new(ranking.bundles),
+                Cell::new(format!("{:.2}", ranking.profit_usd)),
+                Cell::new(format!("{:.2}", ranking.bribe_usd)),
+            ]));
+        }
+        println!("{table}");
+
+        Ok(())
+    }
+
+    fn print_protocols(&self, rankings: Vec<ProtocolRanking>) -> eyre::Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&rankings)?);
+            return Ok(())
+        }
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Rank", "Protocol", "Bundles", "Victim Txs", "Extracted (USD)"]);
+        for (rank, ranking) in rankings.into_iter().enumerate() {
+            table.add_row(Row::from(vec![
+                Cell::new(rank + 1),
+                Cell::new(ranking.protocol),
+                Cell::new(ranking.bundles),
+                Cell::new(ranking.victim_txs),
+                Cell::new(format!("{:.2}", ranking.extracted_usd)),
+            ]));
+        }
+        println!("{table}");
+
+        Ok(())
+    }
+}
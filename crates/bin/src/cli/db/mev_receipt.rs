@@ -0,0 +1,30 @@
+use alloy_primitives::B256;
+use brontes_database::libmdbx::LibmdbxReader;
+use clap::Parser;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct MevReceipt {
+    /// Hashes of the transactions
+    #[arg(required = true)]
+    pub tx_hashes:    Vec<B256>,
+    /// Block the transactions landed in. Without a receipt, a transaction of
+    /// a processed block was neither, otherwise its verdict is unknown
+    #[arg(long)]
+    pub block_number: Option<u64>,
+}
+
+impl MevReceipt {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        // one json receipt per line, in the order of the hashes
+        for tx_hash in self.tx_hashes {
+            let receipt = libmdbx.fetch_mev_receipt(tx_hash, self.block_number)?;
+            println!("{}", serde_json::to_string(&receipt)?);
+        }
+
+        Ok(())
+    }
+}
@@ -2,9 +2,16 @@ use clap::{Parser, Subcommand};
 mod r2_uploader;
 mod snapshot;
 use crate::runner::CliContext;
+mod attest;
+#[cfg(feature = "relay-clients")]
+mod beacon_info;
+mod block_costs;
+mod bootstrap;
+#[cfg(feature = "cex-ingestion")]
 mod cex_data;
 #[cfg(feature = "local-clickhouse")]
 mod clickhouse_download;
+mod cold_storage;
 mod db_clear;
 mod db_insert;
 mod db_query;
@@ -12,13 +19,38 @@ mod db_query;
 mod discovery;
 #[cfg(feature = "local-clickhouse")]
 mod ensure_test_traces;
+mod execution_quality;
+#[cfg(feature = "parquet")]
 mod export;
+mod export_traces;
+mod fill_rate;
+mod frontend_losses;
 mod init;
+mod inspector_artifacts;
+mod leaderboard;
+mod mev_receipt;
+mod overrides;
+mod pool_twap;
+#[cfg(feature = "parquet")]
+mod publish;
+mod publish_snapshot;
+mod replay_log;
+mod reprice;
+mod scheduled_jobs;
+mod searcher_funding;
+mod searcher_stats;
+mod suppressions;
 mod table_stats;
+mod tags;
 #[cfg(feature = "local-clickhouse")]
 mod tip_tracer;
+mod token_metadata;
 mod trace_range;
+mod upgrade_bundles;
+#[cfg(feature = "cex-ingestion")]
 pub mod utils;
+mod value_split;
+mod verify;
 
 #[derive(Debug, Parser)]
 pub struct Database {
@@ -41,7 +73,13 @@ pub enum DatabaseCommands {
     /// --feature local-clickhouse)
     #[command(name = "generate-traces")]
     TraceRange(trace_range::TraceArgs),
+    /// Fetches slot and proposer data from a beacon node and stores it in
+    /// libmdbx
+    #[cfg(feature = "relay-clients")]
+    #[command(name = "beacon-info")]
+    BeaconInfo(beacon_info::BeaconInfoArgs),
     /// Fetches Cex data from the Sorella DB
+    #[cfg(feature = "cex-ingestion")]
     #[command(name = "cex-query")]
     CexData(cex_data::CexDB),
     /// Fetch data from the api and insert it into
@@ -51,14 +89,112 @@ pub enum DatabaseCommands {
     /// Libmbdx Table Stats
     #[command(name = "table-stats")]
     TableStats(table_stats::Stats),
+    /// Top searchers and most victimized protocols over a block range
+    #[command(name = "leaderboard")]
+    Leaderboard(leaderboard::Leaderboard),
+    /// Signs EIP-712 attestations of the losses of sandwich victims and
+    /// writes them to a json file
+    #[command(name = "attest-victims")]
+    AttestVictims(attest::Attest),
+    /// Execution quality of sandwich victims per router, relative to the
+    /// pre-frontrun pool state and the best CEX quote
+    #[command(name = "execution-quality")]
+    ExecutionQuality(execution_quality::ExecutionQuality),
+    /// Sandwich victim losses per frontend, by the router victims swapped
+    /// through and the affiliate their aggregator calls were tagged with
+    #[command(name = "frontend-losses")]
+    FrontendLosses(frontend_losses::FrontendLosses),
+    /// How often submitted bundles landed, per detected strategy and searcher
+    #[command(name = "fill-rate")]
+    FillRate(fill_rate::FillRate),
+    /// Processing cost of a block range, in node calls, trace bytes, cpu time
+    /// and db bytes written
+    #[command(name = "block-costs")]
+    BlockCosts(block_costs::BlockCosts),
+    /// Debug artifacts the inspectors recorded over a block range, with
+    /// `brontes run --debug-artifacts`
+    #[command(name = "inspector-artifacts")]
+    InspectorArtifacts(inspector_artifacts::InspectorArtifacts),
+    /// Checks or rebuilds the running per searcher statistics
+    #[command(name = "searcher-stats")]
+    SearcherStats(searcher_stats::SearcherStatsCmd),
+    /// Moves the traces and cex data of old blocks to an object store and
+    /// lists the moved ranges
+    #[command(name = "cold-storage")]
+    ColdStorage(cold_storage::ColdStorageCmd),
+    /// Last runs of the maintenance jobs scheduled by `brontes run`
+    #[command(name = "scheduled-jobs")]
+    ScheduledJobs(scheduled_jobs::ScheduledJobs),
+    /// Append-only manual corrections of classified bundles
+    #[command(name = "override")]
+    Override(overrides::Overrides),
+    /// Append-only list of contracts whose bundles are suppressed as known
+    /// false positives
+    #[command(name = "suppressions")]
+    Suppressions(suppressions::Suppressions),
+    /// Tags bundles and queries bundles by their tags
+    #[command(name = "tags")]
+    Tags(tags::Tags),
+    /// Token names, logos and spam scores, and scoring tokens against the node
+    #[command(name = "token-metadata")]
+    TokenMetadata(token_metadata::TokenMetadataCmd),
+    /// Split of the extracted value between searchers, builders, liquidity
+    /// providers and protocols over a block range
+    #[command(name = "value-split")]
+    ValueSplit(value_split::ValueSplit),
+    /// Recomputes the checksums of the results of a block range and reports
+    /// blocks whose results are corrupt or differ from another db
+    #[command(name = "verify")]
+    Verify(verify::Verify),
+    /// Writes the traces of a block range to flat files, for backfills that
+    /// read traces without a node
+    #[command(name = "export-traces")]
+    ExportTraces(export_traces::ExportTraces),
+    /// Rewrites the stored mev blocks in the current versioned layout, with
+    /// their checksums
+    #[command(name = "upgrade-bundles")]
+    UpgradeBundles(upgrade_bundles::UpgradeBundles),
+    /// Recomputes the usd values of the bundles of a block range with the
+    /// current price data, keeping the previous values
+    #[command(name = "reprice")]
+    Reprice(reprice::Reprice),
+    /// Lists where searcher eoas were funded from, as traced when they were
+    /// first seen
+    #[command(name = "searcher-funding")]
+    SearcherFunding(searcher_funding::SearcherFunding),
+    /// Time weighted average price of a pool over a block range, from the
+    /// prices of its swaps
+    #[command(name = "pool-twap")]
+    PoolTwap(pool_twap::PoolTwap),
+    /// Whether transactions extracted mev, were sandwiched or neither, as
+    /// compact json receipts
+    #[command(name = "mev-receipt")]
+    MevReceipt(mev_receipt::MevReceipt),
     /// Export libmbdx data to parquet
+    #[cfg(feature = "parquet")]
     #[command(name = "export")]
     Export(export::Export),
+    /// Periodically publishes finalized mev data as parquet to a public
+    /// bucket, optionally with hashed addresses
+    #[cfg(feature = "parquet")]
+    #[command(name = "publish")]
+    Publish(publish::Publish),
+    /// Rebuilds libmdbx tables from a write log
+    #[command(name = "replay-log")]
+    ReplayLog(replay_log::ReplayLog),
     /// Downloads a database snapshot. Without specified blocks, it fetches
     /// the full range. With start/end blocks, it downloads that range and
     /// merges it into the current database.
     #[command(name = "download-snapshot")]
     DownloadSnapshot(snapshot::Snapshot),
+    /// Downloads the latest snapshot published to an object store with
+    /// `publish-snapshot`, verifying its schema and checksum
+    #[command(name = "bootstrap")]
+    Bootstrap(bootstrap::Bootstrap),
+    /// Publishes a snapshot of the db to an object store, with a manifest of
+    /// its schema, tip block and checksum
+    #[command(name = "publish-snapshot")]
+    PublishSnapshot(publish_snapshot::PublishSnapshot),
     #[cfg(feature = "local-clickhouse")]
     /// Downloads the db data from clickhouse
     #[command(name = "download-clickhouse")]
@@ -91,10 +227,43 @@ impl Database {
             DatabaseCommands::Init(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
             DatabaseCommands::DbClear(cmd) => cmd.execute(brontes_db_endpoint).await,
             DatabaseCommands::UploadSnapshot(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::Leaderboard(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::AttestVictims(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::ExecutionQuality(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::FrontendLosses(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::FillRate(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::BlockCosts(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::InspectorArtifacts(cmd) => {
+                cmd.execute(brontes_db_endpoint, ctx).await
+            }
+            DatabaseCommands::SearcherStats(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::ColdStorage(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::ScheduledJobs(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::Override(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::Suppressions(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::Tags(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::TokenMetadata(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::ValueSplit(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::Verify(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::ExportTraces(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::UpgradeBundles(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::Reprice(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::SearcherFunding(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::PoolTwap(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::MevReceipt(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            #[cfg(feature = "parquet")]
             DatabaseCommands::Export(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            #[cfg(feature = "parquet")]
+            DatabaseCommands::Publish(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::ReplayLog(cmd) => cmd.execute(brontes_db_endpoint).await,
             DatabaseCommands::TableStats(cmd) => cmd.execute(brontes_db_endpoint),
             DatabaseCommands::DownloadSnapshot(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::Bootstrap(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            DatabaseCommands::PublishSnapshot(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            #[cfg(feature = "cex-ingestion")]
             DatabaseCommands::CexData(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
+            #[cfg(feature = "relay-clients")]
+            DatabaseCommands::BeaconInfo(cmd) => cmd.execute(brontes_db_endpoint, ctx).await,
             #[cfg(feature = "local-clickhouse")]
             DatabaseCommands::DownloadClickhouse(cmd) => {
                 cmd.execute(brontes_db_endpoint, ctx).await
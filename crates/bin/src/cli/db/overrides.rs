@@ -0,0 +1,150 @@
+use alloy_primitives::{Address, B256};
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::{db::overrides::BundleOverride, mev::MevType};
+use clap::{Args, Parser, Subcommand};
+use eyre::eyre;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct Overrides {
+    #[clap(subcommand)]
+    pub command: OverrideCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum OverrideCommand {
+    /// Marks a bundle as not being mev
+    FalsePositive {
+        #[command(flatten)]
+        bundle: BundleArgs,
+        #[command(flatten)]
+        audit:  AuditArgs,
+    },
+    /// Adds a bundle brontes missed
+    FalseNegative {
+        #[command(flatten)]
+        bundle:     BundleArgs,
+        #[arg(long)]
+        mev_type:   MevType,
+        #[arg(long)]
+        searcher:   Address,
+        #[arg(long, default_value = "0")]
+        profit_usd: f64,
+        #[command(flatten)]
+        audit:      AuditArgs,
+    },
+    /// Changes the mev type of a bundle
+    Reclassify {
+        #[command(flatten)]
+        bundle:   BundleArgs,
+        #[arg(long)]
+        mev_type: MevType,
+        #[command(flatten)]
+        audit:    AuditArgs,
+    },
+    /// Attributes a bundle to a different searcher
+    Recluster {
+        #[command(flatten)]
+        bundle:   BundleArgs,
+        #[arg(long)]
+        searcher: Address,
+        #[command(flatten)]
+        audit:    AuditArgs,
+    },
+    /// Revokes an earlier override
+    Revoke {
+        /// Id of the override to revoke
+        #[arg(long)]
+        id:    u64,
+        #[command(flatten)]
+        audit: AuditArgs,
+    },
+    /// Lists all overrides, including revoked ones
+    List {
+        /// Only list the overrides of this block
+        #[arg(long)]
+        block: Option<u64>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct BundleArgs {
+    /// Block of the bundle
+    #[arg(long)]
+    pub block:   u64,
+    /// Transaction hash of the bundle
+    #[arg(long)]
+    pub tx_hash: B256,
+}
+
+#[derive(Debug, Args)]
+pub struct AuditArgs {
+    /// Who made the override
+    #[arg(long)]
+    pub author: String,
+    /// Why the override was made
+    #[arg(long)]
+    pub reason: String,
+}
+
+impl Overrides {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        let bundle_override = match self.command {
+            OverrideCommand::FalsePositive { bundle, audit } => BundleOverride::false_positive(
+                bundle.block,
+                bundle.tx_hash,
+                audit.author,
+                audit.reason,
+            ),
+            OverrideCommand::FalseNegative { bundle, mev_type, searcher, profit_usd, audit } => {
+                BundleOverride::false_negative(
+                    bundle.block,
+                    bundle.tx_hash,
+                    mev_type,
+                    searcher,
+                    profit_usd,
+                    audit.author,
+                    audit.reason,
+                )
+            }
+            OverrideCommand::Reclassify { bundle, mev_type, audit } => BundleOverride::reclassify(
+                bundle.block,
+                bundle.tx_hash,
+                mev_type,
+                audit.author,
+                audit.reason,
+            ),
+            OverrideCommand::Recluster { bundle, searcher, audit } => BundleOverride::recluster(
+                bundle.block,
+                bundle.tx_hash,
+                searcher,
+                audit.author,
+                audit.reason,
+            ),
+            OverrideCommand::Revoke { id, audit } => {
+                let overrides = libmdbx.fetch_bundle_overrides()?;
+                let (_, revoked) = overrides
+                    .iter()
+                    .find(|(override_id, _)| *override_id == id)
+                    .ok_or_else(|| eyre!("no override with id {id}"))?;
+                BundleOverride::revoke(id, revoked, audit.author, audit.reason)
+            }
+            OverrideCommand::List { block } => {
+                libmdbx
+                    .fetch_bundle_overrides()?
+                    .into_iter()
+                    .filter(|(_, o)| block.map_or(true, |block| o.block_number == block))
+                    .for_each(|(id, o)| println!("#{id} {o}"));
+                return Ok(())
+            }
+        };
+
+        let id = libmdbx.append_bundle_override(bundle_override).await?;
+        println!("recorded override #{id}");
+
+        Ok(())
+    }
+}
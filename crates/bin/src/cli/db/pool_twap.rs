@@ -0,0 +1,77 @@
+use alloy_primitives::Address;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::db::pool_prices::pool_twap;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct PoolTwap {
+    /// Address of the pool
+    #[arg(long)]
+    pub pool:        Address,
+    /// Start block
+    #[arg(long, short)]
+    pub start_block: u64,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Also print the pool's price in each block it was swapped in
+    #[arg(long, default_value = "false")]
+    pub series:      bool,
+    /// Print the twap as json
+    #[arg(long, default_value = "false")]
+    pub json:        bool,
+}
+
+impl PoolTwap {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        let blocks = libmdbx.fetch_pool_prices(self.start_block, self.end_block + 1)?;
+        let Some(twap) = pool_twap(&blocks, self.pool) else {
+            println!(
+                "no swaps through {:?} in blocks {}..={}",
+                self.pool, self.start_block, self.end_block
+            );
+            return Ok(())
+        };
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&twap)?);
+            return Ok(())
+        }
+
+        println!("{:?} ({:?} / {:?})", twap.pool, twap.token1, twap.token0);
+        let mut table = ComfyTable::new();
+        table.set_header(["TWAP", "Min", "Max", "Blocks Swapped", "Seconds"]);
+        table.add_row(Row::from(vec![
+            Cell::new(format!("{:.8}", twap.twap)),
+            Cell::new(format!("{:.8}", twap.min)),
+            Cell::new(format!("{:.8}", twap.max)),
+            Cell::new(twap.observations),
+            Cell::new(twap.duration),
+        ]));
+        println!("{table}");
+
+        if self.series {
+            let mut table = ComfyTable::new();
+            table.set_header(["Block", "Timestamp", "Close", "VWAP", "Volume", "Swaps"]);
+            for (block, prices) in &blocks {
+                let Some(observation) = prices.observation(self.pool) else { continue };
+                table.add_row(Row::from(vec![
+                    Cell::new(block),
+                    Cell::new(prices.timestamp),
+                    Cell::new(format!("{:.8}", observation.close)),
+                    Cell::new(format!("{:.8}", observation.vwap)),
+                    Cell::new(format!("{:.4}", observation.volume0)),
+                    Cell::new(observation.swap_count),
+                ]));
+            }
+            println!("{table}");
+        }
+
+        Ok(())
+    }
+}
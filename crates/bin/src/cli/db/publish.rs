@@ -0,0 +1,83 @@
+use std::{env, path::PathBuf, time::Duration};
+
+use alloy_primitives::keccak256;
+use brontes_database::parquet::{
+    anonymize::AddressAnonymizer,
+    publish::{ParquetPublisher, PublishConfig},
+};
+use clap::Parser;
+use eyre::eyre;
+
+use crate::{
+    cli::{load_libmdbx, static_object, ExportFilterArgs},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct Publish {
+    /// rclone remote to publish to, e.g. an s3 or gcs remote
+    #[arg(long)]
+    pub remote:         String,
+    /// Path on the remote, usually `<bucket>/<prefix>`
+    #[arg(long)]
+    pub remote_path:    String,
+    /// Local directory used to stage the parquet files before uploading
+    #[arg(long, default_value = "publish-staging")]
+    pub staging_dir:    PathBuf,
+    /// Block to start publishing from, if omitted continues after the last
+    /// published block
+    #[arg(long, short)]
+    pub start_block:    Option<u64>,
+    /// Publish new blocks every interval (in seconds), if omitted publishes
+    /// once and exits
+    #[arg(long)]
+    pub interval:       Option<u64>,
+    /// Only publish blocks that are at least this many blocks behind the most
+    /// recent processed block
+    #[arg(long, default_value = "64")]
+    pub finality_depth: u64,
+    /// Replace searcher, builder, proposer and victim addresses with a salted
+    /// hash
+    #[arg(long, default_value = "false")]
+    pub hash_addresses: bool,
+    /// Salt for the address hashes, defaults to `BRONTES_PUBLISH_SALT`
+    #[arg(long)]
+    pub salt:           Option<String>,
+    /// Address lists to leave out of or redact in the published data
+    #[clap(flatten)]
+    pub export_filter:  ExportFilterArgs,
+}
+
+impl Publish {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let anonymizer = self
+            .hash_addresses
+            .then(|| {
+                self.salt
+                    .or_else(|| env::var("BRONTES_PUBLISH_SALT").ok())
+                    .map(|salt| AddressAnonymizer::new(keccak256(salt)))
+                    .ok_or_else(|| {
+                        eyre!(
+                            "--hash-addresses requires a salt, use --salt or BRONTES_PUBLISH_SALT"
+                        )
+                    })
+            })
+            .transpose()?;
+
+        let libmdbx = static_object(load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?);
+
+        let config = PublishConfig {
+            remote: self.remote,
+            remote_path: self.remote_path,
+            staging_dir: self.staging_dir,
+            finality_depth: self.finality_depth,
+            anonymizer,
+            filter: self.export_filter.load()?,
+        };
+
+        ParquetPublisher::new(libmdbx, config, self.start_block)
+            .await?
+            .run(self.interval.map(Duration::from_secs))
+            .await
+    }
+}
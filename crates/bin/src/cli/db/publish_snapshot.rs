@@ -0,0 +1,39 @@
+use std::path::{Path, PathBuf};
+
+use brontes_database::libmdbx::RemoteSnapshot;
+use clap::Parser;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct PublishSnapshot {
+    /// rclone remote and path to publish the snapshot under, e.g.
+    /// `s3:brontes-snapshots/mainnet`
+    #[arg(long)]
+    pub remote:      String,
+    /// Directory the snapshot tarball is written to before it's uploaded,
+    /// defaults to the temp dir
+    #[arg(long)]
+    pub staging_dir: Option<PathBuf>,
+}
+
+impl PublishSnapshot {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let staging_dir = self
+            .staging_dir
+            .unwrap_or_else(|| std::env::temp_dir().join("brontes-db-snapshot"));
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint.clone())?;
+
+        let manifest = RemoteSnapshot::new(self.remote, staging_dir)?
+            .publish(&libmdbx.db, Path::new(&brontes_db_endpoint))?;
+
+        println!(
+            "published snapshot up to block {} ({:.1} MB), checksum {:?}",
+            manifest.tip_block,
+            manifest.bytes as f64 / 1_000_000.0,
+            manifest.checksum
+        );
+
+        Ok(())
+    }
+}
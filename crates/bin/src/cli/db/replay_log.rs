@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use brontes_database::{
+    libmdbx::{write_log::replay_write_log, Libmdbx},
+    Tables,
+};
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub struct ReplayLog {
+    /// Write log to replay, as written by `brontes run --write-log`
+    #[arg(long)]
+    pub path:   PathBuf,
+    /// Only replay writes to these tables, if omitted all tables are replayed
+    #[arg(long, short, value_delimiter = ',')]
+    pub tables: Option<Vec<Tables>>,
+}
+
+impl ReplayLog {
+    pub async fn execute(self, brontes_db_endpoint: String) -> eyre::Result<()> {
+        let db = Libmdbx::init_db(brontes_db_endpoint, None)?;
+        replay_write_log(&db, &self.path, self.tables.as_deref())?;
+
+        Ok(())
+    }
+}
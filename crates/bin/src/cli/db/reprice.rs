@@ -0,0 +1,114 @@
+use alloy_primitives::Address;
+use brontes_database::libmdbx::{append_bundle_repricings, LibmdbxReader};
+use brontes_types::{
+    constants::USDT_ADDRESS_STRING,
+    db::repricing::{reprice_bundle, BundlePricing, BundleRepricing, REPRICING_METHODOLOGY},
+    FastHashMap,
+};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct Reprice {
+    /// Start block, if omitted starts at the first processed block
+    #[arg(long, short)]
+    pub start_block: Option<u64>,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Token the usd values are priced in
+    #[arg(long, short, default_value = USDT_ADDRESS_STRING)]
+    pub quote_asset: Address,
+    /// Print the new values without storing them
+    #[arg(long, default_value = "false")]
+    pub dry_run:     bool,
+    /// Max number of repriced bundles to print
+    #[arg(long, default_value = "20")]
+    pub limit:       usize,
+}
+
+impl Reprice {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+        let blocks = libmdbx.try_fetch_mev_blocks(self.start_block, self.end_block)?;
+        let stored = libmdbx
+            .fetch_bundle_repricings(self.start_block, self.end_block)?
+            .into_iter()
+            .collect::<FastHashMap<_, _>>();
+        let repriced_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        let mut runs = Vec::new();
+        let mut skipped = 0;
+        for block in &blocks {
+            let block_number = block.block.block_number;
+            let metadata = match libmdbx.get_metadata(block_number, self.quote_asset) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    tracing::warn!(block = block_number, %e, "no metadata to reprice with");
+                    skipped += block.mev.len();
+                    continue
+                }
+            };
+
+            let mut block_runs = Vec::new();
+            for bundle in &block.mev {
+                let Some(repriced) = reprice_bundle(bundle, &metadata, self.quote_asset) else {
+                    skipped += 1;
+                    continue
+                };
+                let previous = stored
+                    .get(&block_number)
+                    .and_then(|repricings| repricings.latest(bundle.header.bundle_id))
+                    .map(|latest| latest.repriced)
+                    .unwrap_or_else(|| BundlePricing::of(&bundle.header));
+
+                block_runs.push(BundleRepricing {
+                    bundle_id: bundle.header.bundle_id,
+                    methodology: REPRICING_METHODOLOGY.to_string(),
+                    repriced_at,
+                    previous,
+                    repriced,
+                });
+            }
+
+            if !self.dry_run {
+                append_bundle_repricings(&libmdbx.db, block_number, block_runs.clone())?;
+            }
+            runs.extend(block_runs);
+        }
+
+        println!(
+            "repriced {} bundles with {REPRICING_METHODOLOGY}, skipped {skipped}{}",
+            runs.len(),
+            if self.dry_run { " (dry run, nothing stored)" } else { "" }
+        );
+        if runs.is_empty() {
+            return Ok(())
+        }
+
+        runs.sort_by(|a, b| {
+            let change =
+                |r: &BundleRepricing| (r.repriced.profit_usd - r.previous.profit_usd).abs();
+            change(b).total_cmp(&change(a))
+        });
+        let mut table = ComfyTable::new();
+        table.set_header(["Bundle", "Previous Profit", "Profit", "Previous Bribe", "Bribe"]);
+        for run in runs.iter().take(self.limit) {
+            table.add_row(Row::from(vec![
+                Cell::new(format!("{:?}", run.bundle_id)),
+                Cell::new(format!("{:.2}", run.previous.profit_usd)),
+                Cell::new(format!("{:.2}", run.repriced.profit_usd)),
+                Cell::new(format!("{:.2}", run.previous.bribe_usd)),
+                Cell::new(format!("{:.2}", run.repriced.bribe_usd)),
+            ]));
+        }
+        println!("{table}");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use brontes_database::libmdbx::fetch_job_runs;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct ScheduledJobs {}
+
+impl ScheduledJobs {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        let mut table = ComfyTable::new();
+        table.set_header([
+            "Job",
+            "Last Started",
+            "Last Succeeded",
+            "Consecutive Failures",
+            "Last Error",
+        ]);
+        for run in fetch_job_runs(&libmdbx.db)? {
+            table.add_row(Row::from(vec![
+                Cell::new(&run.name),
+                Cell::new(run.last_started),
+                Cell::new(
+                    run.last_succeeded
+                        .map(|t| t.to_string())
+                        .unwrap_or_default(),
+                ),
+                Cell::new(run.consecutive_failures),
+                Cell::new(run.last_error.unwrap_or_default()),
+            ]));
+        }
+        println!("{table}");
+
+        Ok(())
+    }
+}
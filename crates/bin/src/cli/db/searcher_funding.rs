@@ -0,0 +1,109 @@
+use alloy_primitives::Address;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::db::wallet_funding::{FundingSource, FundingTrace};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use itertools::Itertools;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct SearcherFunding {
+    /// Only searchers funded by this kind of source: cex, tornado, searcher or
+    /// unknown
+    #[arg(long)]
+    pub source: Option<String>,
+    /// Only searchers whose funding passed through this address
+    #[arg(long)]
+    pub funder: Option<Address>,
+    /// Print the full funding path of this searcher eoa
+    #[arg(long, conflicts_with_all = ["source", "funder"])]
+    pub eoa:    Option<Address>,
+    /// Max number of searchers to print
+    #[arg(long, default_value = "50")]
+    pub limit:  usize,
+}
+
+impl SearcherFunding {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        if let Some(eoa) = self.eoa {
+            let Some(trace) = libmdbx
+                .try_fetch_searcher_eoa_info(eoa)?
+                .and_then(|info| info.funding)
+            else {
+                println!("no funding trace for {eoa:?}");
+                return Ok(())
+            };
+
+            println!("{eoa:?} funded by {}", describe_source(&trace.source));
+            let mut table = ComfyTable::new();
+            table.set_header(["Funder", "Block", "Tx", "Eth"]);
+            for edge in &trace.path {
+                table.add_row(Row::from(vec![
+                    Cell::new(format!("{:?}", edge.funder)),
+                    Cell::new(edge.block_number),
+                    Cell::new(format!("{:?}", edge.tx_hash)),
+                    Cell::new(format!("{:.4}", edge.value)),
+                ]));
+            }
+            println!("{table}");
+            return Ok(())
+        }
+
+        let traced = libmdbx
+            .fetch_all_searcher_eoa_info()?
+            .into_iter()
+            .filter_map(|(eoa, info)| Some((eoa, info.funding?)))
+            .filter(|(_, trace)| {
+                self.source
+                    .as_deref()
+                    .map_or(true, |kind| trace.source.kind().eq_ignore_ascii_case(kind))
+            })
+            .filter(|(_, trace)| {
+                self.funder
+                    .map_or(true, |funder| trace.path.iter().any(|edge| edge.funder == funder))
+            })
+            .collect_vec();
+
+        println!("{} searcher eoas", traced.len());
+        for (kind, count) in traced
+            .iter()
+            .counts_by(|(_, trace)| trace.source.kind())
+            .into_iter()
+            .sorted_by(|a, b| b.1.cmp(&a.1))
+        {
+            println!("  {kind}: {count}");
+        }
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Searcher", "Source", "Origin", "Hops"]);
+        for (eoa, trace) in traced.iter().take(self.limit) {
+            table.add_row(Row::from(vec![
+                Cell::new(format!("{eoa:?}")),
+                Cell::new(describe_source(&trace.source)),
+                Cell::new(origin(trace)),
+                Cell::new(trace.path.len()),
+            ]));
+        }
+        println!("{table}");
+
+        Ok(())
+    }
+}
+
+fn describe_source(source: &FundingSource) -> String {
+    match source {
+        FundingSource::Cex(name) if !name.is_empty() => format!("cex ({name})"),
+        FundingSource::Searcher(searcher) => format!("searcher ({searcher:?})"),
+        source => source.kind().to_string(),
+    }
+}
+
+fn origin(trace: &FundingTrace) -> String {
+    trace
+        .origin()
+        .map(|origin| format!("{origin:?}"))
+        .unwrap_or_else(|| "-".to_string())
+}
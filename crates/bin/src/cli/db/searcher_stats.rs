@@ -0,0 +1,107 @@
+use brontes_database::libmdbx::{aggregate_searcher_stats, rebuild_searcher_stats, LibmdbxReader};
+use brontes_types::FastHashMap;
+use clap::{Parser, Subcommand};
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::eyre;
+use itertools::Itertools;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct SearcherStatsCmd {
+    #[clap(subcommand)]
+    pub command: SearcherStatsCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SearcherStatsCommand {
+    /// Recomputes the statistics from the curated mev blocks and reports the
+    /// searchers whose stored statistics differ
+    Check {
+        /// Max number of mismatches to print
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+    /// Recomputes the statistics from the curated mev blocks and replaces the
+    /// stored ones in a single transaction
+    Rebuild,
+}
+
+impl SearcherStatsCmd {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        match self.command {
+            SearcherStatsCommand::Check { limit } => {
+                let expected = aggregate_searcher_stats(&libmdbx.db)?;
+                let stored = libmdbx
+                    .fetch_all_searcher_stats()?
+                    .into_iter()
+                    .collect::<FastHashMap<_, _>>();
+
+                let mismatches = expected
+                    .searchers
+                    .keys()
+                    .chain(stored.keys())
+                    .unique()
+                    .filter(|searcher| {
+                        match (expected.searchers.get(searcher), stored.get(searcher)) {
+                            (Some(expected), Some(stored)) => !expected.approx_eq(stored),
+                            _ => true,
+                        }
+                    })
+                    .copied()
+                    .collect_vec();
+
+                println!(
+                    "checked {} searchers over {} blocks, {} mismatched",
+                    expected.searchers.len(),
+                    expected.blocks.len(),
+                    mismatches.len()
+                );
+                if mismatches.is_empty() {
+                    return Ok(())
+                }
+
+                let mut table = ComfyTable::new();
+                table.set_header([
+                    "Searcher",
+                    "Bundles (expected/stored)",
+                    "Profit USD (expected/stored)",
+                    "Last Block (expected/stored)",
+                ]);
+                for searcher in mismatches.iter().take(limit) {
+                    let expected = expected
+                        .searchers
+                        .get(searcher)
+                        .cloned()
+                        .unwrap_or_default();
+                    let stored = stored.get(searcher).cloned().unwrap_or_default();
+                    table.add_row(Row::from(vec![
+                        Cell::new(searcher),
+                        Cell::new(format!("{}/{}", expected.totals.bundles, stored.totals.bundles)),
+                        Cell::new(format!(
+                            "{:.2}/{:.2}",
+                            expected.totals.profit_usd, stored.totals.profit_usd
+                        )),
+                        Cell::new(format!(
+                            "{}/{}",
+                            expected.last_active_block, stored.last_active_block
+                        )),
+                    ]));
+                }
+                println!("{table}");
+
+                Err(eyre!(
+                    "searcher statistics are inconsistent, run `brontes db searcher-stats rebuild`"
+                ))
+            }
+            SearcherStatsCommand::Rebuild => {
+                let (searchers, blocks) = rebuild_searcher_stats(&libmdbx.db)?;
+
+                println!("rebuilt statistics of {searchers} searchers over {blocks} blocks");
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use alloy_primitives::Address;
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::{
+    db::suppression::{SuppressionChange, SuppressionKind, SuppressionRule},
+    mev::MevType,
+    Protocol,
+};
+use clap::{Parser, Subcommand};
+use eyre::eyre;
+
+use super::overrides::AuditArgs;
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct Suppressions {
+    #[clap(subcommand)]
+    pub command: SuppressionCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SuppressionCommand {
+    /// Suppresses the bundles of a contract from the next processed block
+    Add {
+        /// Contract or eoa whose bundles are suppressed, also matched against
+        /// the pools the searcher swapped through
+        #[arg(long)]
+        address:   Address,
+        /// Only suppress bundles trading through these protocols
+        #[arg(long = "protocol", value_delimiter = ',')]
+        protocols: Vec<Protocol>,
+        /// Only suppress bundles of these mev types
+        #[arg(long = "mev-type", value_delimiter = ',')]
+        mev_types: Vec<MevType>,
+        #[command(flatten)]
+        audit:     AuditArgs,
+    },
+    /// Lifts a rule
+    Remove {
+        /// Id of the change that added the rule
+        #[arg(long)]
+        id:    u64,
+        #[command(flatten)]
+        audit: AuditArgs,
+    },
+    /// Lists the rules in effect
+    List,
+    /// Lists every change made to the list
+    History {
+        /// Only list the changes to rules of this address
+        #[arg(long)]
+        address: Option<Address>,
+    },
+}
+
+impl Suppressions {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        let change = match self.command {
+            SuppressionCommand::Add { address, protocols, mev_types, audit } => {
+                SuppressionChange::add(
+                    SuppressionRule { address, protocols, mev_types },
+                    audit.author,
+                    audit.reason,
+                )
+            }
+            SuppressionCommand::Remove { id, audit } => {
+                let list = libmdbx.fetch_suppression_list()?;
+                if !list.rules().iter().any(|(rule_id, _)| *rule_id == id) {
+                    return Err(eyre!("no rule in effect was added by change #{id}"))
+                }
+                let changes = libmdbx.fetch_suppression_changes()?;
+                let (_, removed) = changes
+                    .iter()
+                    .find(|(change_id, _)| *change_id == id)
+                    .ok_or_else(|| eyre!("no change with id {id}"))?;
+                SuppressionChange::remove(id, removed, audit.author, audit.reason)
+            }
+            SuppressionCommand::List => {
+                libmdbx
+                    .fetch_suppression_list()?
+                    .rules()
+                    .iter()
+                    .for_each(|(id, rule)| println!("#{id} {rule}"));
+                return Ok(())
+            }
+            SuppressionCommand::History { address } => {
+                libmdbx
+                    .fetch_suppression_changes()?
+                    .into_iter()
+                    .filter(|(_, c)| address.map_or(true, |address| c.rule.address == address))
+                    .for_each(|(id, c)| println!("#{id} {c}"));
+                return Ok(())
+            }
+        };
+
+        let kind = change.kind;
+        let id = libmdbx.append_suppression_change(change).await?;
+        match kind {
+            SuppressionKind::Add => println!("added rule #{id}"),
+            SuppressionKind::Remove => println!("recorded removal #{id}"),
+        }
+
+        Ok(())
+    }
+}
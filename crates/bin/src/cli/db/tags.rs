@@ -0,0 +1,123 @@
+use alloy_primitives::B256;
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::db::bundle_tags::{BundleTag, TagFilter};
+use clap::{Parser, Subcommand};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct Tags {
+    #[clap(subcommand)]
+    pub command: TagCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TagCommand {
+    /// Tags a bundle
+    Add {
+        /// Block of the bundle
+        #[arg(long)]
+        block:   u64,
+        /// Transaction hash of the bundle
+        #[arg(long)]
+        tx_hash: B256,
+        #[arg(long)]
+        tag:     String,
+        /// Who added the tag
+        #[arg(long)]
+        author:  String,
+    },
+    /// Removes a tag from a bundle. Detector tags come back when the block is
+    /// reprocessed
+    Remove {
+        /// Block of the bundle
+        #[arg(long)]
+        block:   u64,
+        /// Transaction hash of the bundle
+        #[arg(long)]
+        tx_hash: B256,
+        #[arg(long)]
+        tag:     String,
+    },
+    /// Lists the tags of the blocks in the range
+    List {
+        #[arg(long, short)]
+        start_block: u64,
+        /// Inclusive
+        #[arg(long, short)]
+        end_block:   u64,
+    },
+    /// Lists the bundles of the range matching the tags
+    Query {
+        #[arg(long, short)]
+        start_block: u64,
+        /// Inclusive
+        #[arg(long, short)]
+        end_block:   u64,
+        /// Tags the bundles must all have
+        #[arg(long = "with", value_delimiter = ',')]
+        with:        Vec<String>,
+        /// Tags the bundles must not have
+        #[arg(long = "without", value_delimiter = ',')]
+        without:     Vec<String>,
+    },
+}
+
+impl Tags {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+
+        match self.command {
+            TagCommand::Add { block, tx_hash, tag, author } => {
+                if libmdbx
+                    .tag_bundle(block, BundleTag::manual(tx_hash, tag, author))
+                    .await?
+                {
+                    println!("tagged bundle {tx_hash:?}");
+                } else {
+                    println!("bundle {tx_hash:?} already has the tag");
+                }
+            }
+            TagCommand::Remove { block, tx_hash, tag } => {
+                if libmdbx.untag_bundle(block, tx_hash, tag).await? {
+                    println!("removed tag from bundle {tx_hash:?}");
+                } else {
+                    println!("bundle {tx_hash:?} doesn't have the tag");
+                }
+            }
+            TagCommand::List { start_block, end_block } => {
+                for (block, tags) in libmdbx.fetch_bundle_tags(start_block, end_block + 1)? {
+                    for tag in tags.tags {
+                        println!(
+                            "{block} {:?} {} ({}{})",
+                            tag.tx_hash,
+                            tag.tag,
+                            tag.source.as_ref(),
+                            tag.author
+                                .map(|author| format!(" by {author}"))
+                                .unwrap_or_default()
+                        );
+                    }
+                }
+            }
+            TagCommand::Query { start_block, end_block, with, without } => {
+                let filter = TagFilter { all: with, none: without };
+                let blocks =
+                    libmdbx.fetch_tagged_mev_blocks(Some(start_block), end_block, &filter)?;
+                for block in blocks {
+                    for bundle in block.mev {
+                        println!(
+                            "{} {:?} {} ${:.2}",
+                            bundle.header.block_number,
+                            bundle.header.tx_hash,
+                            bundle.header.mev_type,
+                            bundle.header.profit_usd
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alloy_primitives::Address;
+use brontes_core::token_metadata::{recent_token_holders, score_token};
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::{
+    db::token_metadata::{TokenList, TokenMetadata},
+    init_thread_pools,
+    unordered_buffer_map::BrontesStreamExt,
+    FastHashMap,
+};
+use clap::{Parser, Subcommand};
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use futures::StreamExt;
+use itertools::Itertools;
+
+use crate::{
+    cli::{get_env_vars, get_tracing_provider, load_database, load_libmdbx, static_object},
+    runner::CliContext,
+};
+
+#[derive(Debug, Parser)]
+pub struct TokenMetadataCmd {
+    #[clap(subcommand)]
+    pub command: TokenMetadataCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TokenMetadataCommand {
+    /// Shows the metadata and spam assessment of tokens
+    Show {
+        /// Tokens to show
+        #[arg(long, value_delimiter = ',')]
+        tokens: Vec<Address>,
+        /// Print the metadata as json
+        #[arg(long, default_value = "false")]
+        json:   bool,
+    },
+    /// Lists the tokens scored at least this high
+    List {
+        /// Lowest spam score listed
+        #[arg(long, default_value_t = brontes_types::db::token_metadata::UNTRADEABLE_SPAM_SCORE)]
+        min_score: u8,
+        /// Print the metadata as json
+        #[arg(long, default_value = "false")]
+        json:      bool,
+    },
+    /// Scores tokens against the node by simulating a sell, a transfer from a
+    /// recent holder into the token's pool, and measuring the share of their
+    /// supply the pools hold
+    Score {
+        /// Tokens to score, defaults to every token with a pool or with
+        /// metadata
+        #[arg(long, value_delimiter = ',')]
+        tokens:        Vec<Address>,
+        /// Block to score the tokens at
+        #[arg(long, short)]
+        block:         u64,
+        /// Curated list in the token lists format, listed tokens take their
+        /// logo from it and are never scored as spam
+        #[arg(long)]
+        token_list:    Option<PathBuf>,
+        /// Chain of the token list entries to use
+        #[arg(long, default_value = "1")]
+        chain_id:      u64,
+        /// Blocks up to the scoring block searched for the holders a sell is
+        /// simulated from
+        #[arg(long, default_value = "100")]
+        holder_blocks: u64,
+        /// Tokens scored at once
+        #[arg(long, default_value = "20")]
+        concurrent:    usize,
+    },
+}
+
+impl TokenMetadataCmd {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        match self.command {
+            TokenMetadataCommand::Show { tokens, json } => {
+                let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+                let metadata = tokens
+                    .into_iter()
+                    .filter_map(|token| {
+                        Some((token, libmdbx.try_fetch_token_metadata(token).ok().flatten()?))
+                    })
+                    .collect_vec();
+                print_metadata(metadata, json)
+            }
+            TokenMetadataCommand::List { min_score, json } => {
+                let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+                let metadata = libmdbx
+                    .fetch_all_token_metadata()?
+                    .into_iter()
+                    .filter(|(_, metadata)| metadata.spam.score >= min_score)
+                    .sorted_by(|a, b| b.1.spam.score.cmp(&a.1.spam.score))
+                    .collect_vec();
+                print_metadata(metadata, json)
+            }
+            TokenMetadataCommand::Score {
+                tokens,
+                block,
+                token_list,
+                chain_id,
+                holder_blocks,
+                concurrent,
+            } => {
+                score(
+                    brontes_db_endpoint,
+                    ctx,
+                    tokens,
+                    block,
+                    token_list,
+                    chain_id,
+                    holder_blocks,
+                    concurrent,
+                )
+                .await
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn score(
+    brontes_db_endpoint: String,
+    ctx: CliContext,
+    tokens: Vec<Address>,
+    block: u64,
+    token_list: Option<PathBuf>,
+    chain_id: u64,
+    holder_blocks: u64,
+    concurrent: usize,
+) -> eyre::Result<()> {
+    let db_path = get_env_vars()?;
+    init_thread_pools(10);
+
+    let libmdbx = static_object(
+        load_database(&ctx.task_executor, brontes_db_endpoint, None, None, None, None).await?,
+    );
+    let tracer = static_object(Arc::new(get_tracing_provider(
+        Path::new(&db_path),
+        10,
+        ctx.task_executor.clone(),
+    )?));
+    let token_list = static_object(
+        token_list
+            .map(|path| TokenList::from_json_file(path, chain_id))
+            .transpose()?,
+    );
+
+    let mut pools: FastHashMap<Address, Vec<Address>> = FastHashMap::default();
+    for ((pool, _), pair) in libmdbx.protocols_created_before(block)? {
+        pools.entry(pair.0).or_default().push(pool);
+        pools.entry(pair.1).or_default().push(pool);
+    }
+    let tokens = if tokens.is_empty() {
+        libmdbx
+            .fetch_all_token_metadata()?
+            .into_iter()
+            .map(|(token, _)| token)
+            .chain(pools.keys().copied())
+            .filter(|token| !token.is_zero())
+            .unique()
+            .collect_vec()
+    } else {
+        tokens
+    };
+    let pools = static_object(pools);
+    let holders = static_object(recent_token_holders(tracer, block, holder_blocks).await?);
+    tracing::info!(tokens = tokens.len(), block, "scoring tokens");
+
+    let scored = futures::stream::iter(tokens)
+        .unordered_buffer_map(concurrent, |token| async move {
+            let token_pools = pools.get(&token).map(Vec::as_slice).unwrap_or_default();
+            let token_holders = holders.get(&token).map(Vec::as_slice).unwrap_or_default();
+            let metadata = match score_token(
+                tracer,
+                token,
+                block,
+                token_pools,
+                token_holders,
+                token_list.as_ref(),
+            )
+            .await
+            {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    tracing::warn!(?token, err=%e, "failed to score token");
+                    return false
+                }
+            };
+            if let Err(e) = libmdbx.write_token_metadata(token, metadata).await {
+                tracing::error!(?token, err=%e, "failed to write token metadata");
+                return false
+            }
+            true
+        })
+        .filter(|scored| futures::future::ready(*scored))
+        .count()
+        .await;
+    tracing::info!(scored, "scored tokens");
+
+    Ok(())
+}
+
+fn print_metadata(metadata: Vec<(Address, TokenMetadata)>, json: bool) -> eyre::Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(&metadata)?);
+        return Ok(())
+    }
+
+    let mut table = ComfyTable::new();
+    table.set_header(["Token", "Symbol", "Name", "Decimals", "Listed", "Spam Score", "Signals"]);
+    for (token, metadata) in metadata {
+        table.add_row(Row::from(vec![
+            Cell::new(format!("{token:?}")),
+            Cell::new(&metadata.symbol),
+            Cell::new(&metadata.name),
+            Cell::new(metadata.decimals),
+            Cell::new(metadata.listed),
+            Cell::new(metadata.spam.score),
+            Cell::new(
+                metadata
+                    .spam
+                    .signals
+                    .iter()
+                    .map(|signal| format!("{signal:?}"))
+                    .join(", "),
+            ),
+        ]));
+    }
+    println!("{table}");
+
+    Ok(())
+}
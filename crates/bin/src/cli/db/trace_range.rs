@@ -42,11 +42,11 @@ impl TraceArgs {
             .spawn_critical("metrics", metrics_listener);
 
         let libmdbx = static_object(
-            load_database(&ctx.task_executor, brontes_db_endpoint, None, None).await?,
+            load_database(&ctx.task_executor, brontes_db_endpoint, None, None, None, None).await?,
         );
 
         let tracer =
-            get_tracing_provider(Path::new(&db_path), max_tasks, ctx.task_executor.clone());
+            get_tracing_provider(Path::new(&db_path), max_tasks, ctx.task_executor.clone())?;
 
         let parser = static_object(DParser::new(metrics_tx, libmdbx, tracer.clone()).await);
 
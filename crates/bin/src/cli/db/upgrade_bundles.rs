@@ -0,0 +1,25 @@
+use brontes_database::libmdbx::upgrade_mev_blocks;
+use clap::Parser;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct UpgradeBundles {}
+
+impl UpgradeBundles {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+        let upgrade = upgrade_mev_blocks(&libmdbx.db)?;
+        println!("rewrote {} mev blocks in the current layout", upgrade.upgraded);
+
+        if !upgrade.undecodable.is_empty() {
+            eyre::bail!(
+                "{} mev blocks match none of the known layouts and were left untouched: {:?}",
+                upgrade.undecodable.len(),
+                upgrade.undecodable
+            )
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+use brontes_database::libmdbx::LibmdbxReader;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct ValueSplit {
+    /// Start block, if omitted starts at the first processed block
+    #[arg(long, short)]
+    pub start_block: Option<u64>,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Print the report as json
+    #[arg(long, default_value = "false")]
+    pub json:        bool,
+}
+
+impl ValueSplit {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+        let report = libmdbx.fetch_value_split_report(self.start_block, self.end_block)?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return Ok(())
+        }
+
+        let total = report.total_usd();
+        let share = |usd: f64| if total == 0.0 { 0.0 } else { usd / total * 100.0 };
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Recipient", "Value (USD)", "Share (%)"]);
+        for (recipient, usd) in [
+            ("Searchers", report.searcher_usd),
+            ("Builders", report.builder_usd),
+            ("Liquidity providers", report.lp_fees_usd),
+            ("Protocols", report.protocol_fees_usd),
+        ] {
+            table.add_row(Row::from(vec![
+                Cell::new(recipient),
+                Cell::new(format!("{usd:.2}")),
+                Cell::new(format!("{:.2}", share(usd))),
+            ]));
+        }
+        println!("{} bundles, ${total:.2} extracted", report.bundles);
+        println!("{table}");
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Protocol", "Bundles", "LP Fees (USD)"]);
+        for revenue in report.protocols {
+            table.add_row(Row::from(vec![
+                Cell::new(revenue.protocol),
+                Cell::new(revenue.bundles),
+                Cell::new(format!("{:.2}", revenue.lp_fees_usd)),
+            ]));
+        }
+        println!("{table}");
+
+        Ok(())
+    }
+}
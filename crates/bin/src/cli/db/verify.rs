@@ -0,0 +1,82 @@
+use brontes_database::libmdbx::{
+    diff_block_checksums, fetch_block_checksums, verify_block_checksums, ChecksumMismatch,
+    LibmdbxReader,
+};
+use brontes_types::db::block_checksum::BlockChecksum;
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::eyre;
+
+use crate::{cli::load_libmdbx, runner::CliContext};
+
+#[derive(Debug, Parser)]
+pub struct Verify {
+    /// Start block, if omitted starts at the first processed block
+    #[arg(long, short)]
+    pub start_block: Option<u64>,
+    /// End block (inclusive)
+    #[arg(long, short)]
+    pub end_block:   u64,
+    /// Path of another brontes db to compare the results with, such as a
+    /// replica or a db the range was re-run into
+    #[arg(long)]
+    pub against:     Option<String>,
+    /// Max number of mismatched blocks to print
+    #[arg(long, default_value = "20")]
+    pub limit:       usize,
+}
+
+impl Verify {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let libmdbx = load_libmdbx(&ctx.task_executor, brontes_db_endpoint)?;
+        let blocks = libmdbx.try_fetch_mev_blocks(self.start_block, self.end_block)?;
+        let stored = fetch_block_checksums(
+            &libmdbx.db,
+            self.start_block.unwrap_or_default(),
+            self.end_block,
+        )?;
+
+        let mut mismatches = verify_block_checksums(&blocks, &stored)?;
+        if let Some(against) = self.against {
+            let other = load_libmdbx(&ctx.task_executor, against)?;
+            let other_blocks = other.try_fetch_mev_blocks(self.start_block, self.end_block)?;
+            mismatches.extend(diff_block_checksums(&blocks, &other_blocks)?);
+        }
+        mismatches.sort_by_key(|mismatch| mismatch.block());
+
+        println!("verified {} blocks, {} mismatched", blocks.len(), mismatches.len());
+        if mismatches.is_empty() {
+            return Ok(())
+        }
+
+        let mut table = ComfyTable::new();
+        table.set_header(["Block", "Mismatch", "Expected", "Found"]);
+        for mismatch in mismatches.iter().take(self.limit) {
+            let (kind, expected, found) = match mismatch {
+                ChecksumMismatch::Missing { .. } => ("missing checksum", None, None),
+                ChecksumMismatch::Corrupt { stored, recomputed, .. } => {
+                    ("corrupt", Some(*stored), Some(*recomputed))
+                }
+                ChecksumMismatch::Outdated { .. } => {
+                    ("outdated layout, run `brontes db upgrade-bundles`", None, None)
+                }
+                ChecksumMismatch::Diverged { ours, theirs, .. } => ("diverged", *ours, *theirs),
+            };
+            table.add_row(Row::from(vec![
+                Cell::new(mismatch.block()),
+                Cell::new(kind),
+                Cell::new(fmt_checksum(expected)),
+                Cell::new(fmt_checksum(found)),
+            ]));
+        }
+        println!("{table}");
+
+        Err(eyre!("{} blocks failed verification", mismatches.len()))
+    }
+}
+
+fn fmt_checksum(checksum: Option<BlockChecksum>) -> String {
+    checksum
+        .map(|c| format!("{} ({} bundles)", c.checksum, c.bundle_count))
+        .unwrap_or_else(|| "-".to_string())
+}
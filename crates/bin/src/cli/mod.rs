@@ -2,9 +2,12 @@ use clap::{Parser, Subcommand};
 
 mod db;
 mod misc;
+mod preflight;
 mod run;
+mod tx;
 mod utils;
 mod version_data;
+pub use run::RunArgs;
 pub use utils::*;
 pub use version_data::*;
 
@@ -36,4 +39,8 @@ pub enum Commands {
     /// Brontes database commands
     #[command(name = "db")]
     Database(db::Database),
+    /// Classify a single transaction, print its annotated action tree and
+    /// run the inspectors over it
+    #[command(name = "tx")]
+    Tx(tx::TxArgs),
 }
@@ -0,0 +1,504 @@
+//! Preflight checks run by `brontes run --dry-run`.
+//!
+//! Long backfills tend to fail hours in on problems that are visible up front:
+//! a node that can't trace the requested blocks, a database written by an
+//! incompatible version, missing cex data or a disk that fills up. The dry run
+//! checks all of these against the run's configuration and prints a pass/fail
+//! report without processing any blocks.
+use std::{collections::BTreeMap, path::Path};
+
+use brontes_database::{
+    clickhouse::{cex_config::CexDownloadConfig, ClickhouseHandle},
+    libmdbx::{stored_layout_versions, LibmdbxInit, LibmdbxReadWriter},
+    Tables,
+};
+use brontes_inspect::Inspectors;
+use brontes_types::{
+    db::bundle_versions::layout_versions, traits::TracingProvider, BrontesTaskExecutor,
+};
+use comfy_table::{Cell, Color, Row, Table as ComfyTable};
+use human_bytes::human_bytes;
+use reth_db::is_database_empty;
+use reth_primitives::{Address, BlockId};
+
+use super::{load_clickhouse, run::RunArgs};
+use crate::RangeType;
+
+/// Used to project the disk usage of a range when the database is still empty
+const DEFAULT_BYTES_PER_BLOCK: f64 = 512.0 * 1024.0;
+/// Warn once a run is projected to use more than this share of the free space
+const DISK_WARN_RATIO: f64 = 0.8;
+
+const CEX_INSPECTORS: [Inspectors; 3] =
+    [Inspectors::CexDex, Inspectors::CexDexMarkout, Inspectors::JitCexDex];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug)]
+pub struct CheckResult {
+    pub name:   &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+#[derive(Debug, Default)]
+pub struct PreflightReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl PreflightReport {
+    fn pass(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.push(name, CheckStatus::Pass, detail)
+    }
+
+    fn warn(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.push(name, CheckStatus::Warn, detail)
+    }
+
+    fn fail(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.push(name, CheckStatus::Fail, detail)
+    }
+
+    fn push(&mut self, name: &'static str, status: CheckStatus, detail: impl Into<String>) {
+        self.checks
+            .push(CheckResult { name, status, detail: detail.into() });
+    }
+
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != CheckStatus::Fail)
+    }
+
+    pub fn print(&self) {
+        let mut table = ComfyTable::new();
+        table.set_header(["Check", "Status", "Detail"]);
+        for check in &self.checks {
+            let status = match check.status {
+                CheckStatus::Pass => Cell::new("PASS").fg(Color::Green),
+                CheckStatus::Warn => Cell::new("WARN").fg(Color::Yellow),
+                CheckStatus::Fail => Cell::new("FAIL").fg(Color::Red),
+            };
+            table.add_row(Row::from(vec![Cell::new(check.name), status, Cell::new(&check.detail)]));
+        }
+        println!("{table}");
+    }
+}
+
+/// Runs every preflight check, later checks are skipped when the ones they
+/// depend on fail
+pub async fn run_preflight(
+    args: &RunArgs,
+    brontes_db_endpoint: &str,
+    executor: BrontesTaskExecutor,
+) -> PreflightReport {
+    let mut report = PreflightReport::default();
+
+    let Some((range_type, quote_asset)) = check_config(args, &mut report) else { return report };
+    let tip = check_tracer(args, &range_type, executor, &mut report).await;
+    let clickhouse_ok = check_clickhouse(args, &range_type, tip, quote_asset, &mut report).await;
+
+    let ranges = requested_ranges(&range_type, tip);
+    let Some(libmdbx) = open_db(brontes_db_endpoint, &mut report) else {
+        // everything in the range is fetched into the new database
+        let blocks = ranges.iter().map(|(start, end)| end - start + 1).sum();
+        check_disk_space(brontes_db_endpoint, None, blocks, &mut report);
+        return report
+    };
+
+    check_layout_versions(&libmdbx, &mut report);
+    check_db_layout(&libmdbx, &mut report);
+    let missing_blocks = check_data_coverage(args, &libmdbx, &ranges, clickhouse_ok, &mut report);
+    check_disk_space(brontes_db_endpoint, Some(&libmdbx), missing_blocks, &mut report);
+
+    report
+}
+
+fn check_config(args: &RunArgs, report: &mut PreflightReport) -> Option<(RangeType, Address)> {
+    let config = args
+        .check_proper_range()
+        .and_then(|_| args.get_range_type())
+        .and_then(|range| Ok((range, args.quote_asset.parse::<Address>()?)));
+
+    match config {
+        Ok(config) => {
+            report.pass("config", "block range and quote asset are valid");
+            Some(config)
+        }
+        Err(e) => {
+            report.fail("config", e.to_string());
+            None
+        }
+    }
+}
+
+/// Checks the node is reachable and can trace the first block of the run,
+/// which also verifies it keeps enough history for the range. Returns the
+/// node's tip
+async fn check_tracer(
+    args: &RunArgs,
+    range_type: &RangeType,
+    executor: BrontesTaskExecutor,
+    report: &mut PreflightReport,
+) -> Option<u64> {
+    #[cfg(feature = "local-reth")]
+    let Ok(db_path) = std::env::var("DB_PATH") else {
+        report.fail("tracer", "DB_PATH is not set");
+        return None
+    };
+    #[cfg(not(feature = "local-reth"))]
+    let db_path = String::new();
+    let tracer = match super::get_tracing_provider(Path::new(&db_path), 1, executor) {
+        Ok(tracer) => tracer,
+        Err(e) => {
+            report.fail("tracer", format!("{e:#}"));
+            return None
+        }
+    };
+
+    #[cfg(feature = "local-reth")]
+    let tip = tracer.best_block_number();
+    #[cfg(not(feature = "local-reth"))]
+    let tip = tracer.best_block_number().await;
+
+    let tip = match tip {
+        Ok(tip) => tip,
+        Err(e) => {
+            report.fail("tracer", format!("node is unreachable: {e}"));
+            return None
+        }
+    };
+
+    let first_block = match range_type {
+        RangeType::SingleRange { start_block, .. } => {
+            start_block.unwrap_or(tip.saturating_sub(args.behind_tip))
+        }
+        RangeType::MultipleRanges(ranges) => ranges.iter().map(|(start, _)| *start).min()?,
+    };
+
+    if first_block > tip {
+        report.fail("tracer", format!("start block {first_block} is past the node's tip {tip}"));
+        return Some(tip)
+    }
+
+    match tracer
+        .replay_block_transactions(BlockId::from(first_block))
+        .await
+    {
+        Ok(Some(_)) => report.pass("tracer", format!("traced block {first_block}, node tip {tip}")),
+        Ok(None) => report.fail(
+            "tracer",
+            format!("node has no traces for block {first_block}, it may be pruned"),
+        ),
+        Err(e) => report.fail(
+            "tracer",
+            format!(
+                "failed to trace block {first_block}, an archive node with the trace api is \
+                 required: {e}"
+            ),
+        ),
+    }
+
+    Some(tip)
+}
+
+async fn check_clickhouse(
+    args: &RunArgs,
+    range_type: &RangeType,
+    tip: Option<u64>,
+    quote_asset: Address,
+    report: &mut PreflightReport,
+) -> bool {
+    let block = match range_type {
+        RangeType::SingleRange { start_block: Some(start), .. } => Some(*start),
+        RangeType::SingleRange { .. } => tip.map(|tip| tip.saturating_sub(args.behind_tip)),
+        RangeType::MultipleRanges(ranges) => ranges.first().map(|(start, _)| *start),
+    };
+    let Some(block) = block else {
+        report.warn("clickhouse", "skipped, no block to query without a node tip");
+        return false
+    };
+
+    let config = CexDownloadConfig::new((1, 1), args.cex_exchanges.clone());
+    let clickhouse = match load_clickhouse(config, args.run_id).await {
+        Ok(clickhouse) => clickhouse,
+        Err(e) => {
+            report.fail("clickhouse", format!("failed to connect: {e}"));
+            return false
+        }
+    };
+
+    match clickhouse.get_metadata(block, quote_asset).await {
+        Ok(_) => {
+            report.pass("clickhouse", format!("fetched metadata for block {block}"));
+            true
+        }
+        Err(e) => {
+            report.fail("clickhouse", format!("failed to fetch metadata for block {block}: {e}"));
+            false
+        }
+    }
+}
+
+/// Opens the database read only, the dry run must not create or modify it.
+/// Returns `None` when there's no database yet
+fn open_db(brontes_db_endpoint: &str, report: &mut PreflightReport) -> Option<LibmdbxReadWriter> {
+    if is_database_empty(brontes_db_endpoint) {
+        report.pass("libmdbx", "database will be created");
+        return None
+    }
+
+    match LibmdbxReadWriter::init_db_read_only(brontes_db_endpoint) {
+        Ok(libmdbx) => Some(libmdbx),
+        Err(e) => {
+            report.fail("libmdbx", format!("failed to open database: {e}"));
+            None
+        }
+    }
+}
+
+/// Compares the layout versions the stored mev blocks were written with to
+/// the ones this binary reads
+fn check_layout_versions(libmdbx: &LibmdbxReadWriter, report: &mut PreflightReport) {
+    let stored = match stored_layout_versions(&libmdbx.db) {
+        Ok(stored) => stored,
+        Err(e) => {
+            report.fail("layout versions", format!("failed to read stored versions: {e}"));
+            return
+        }
+    };
+
+    let (newer, older) = compare_layout_versions(&stored, &layout_versions());
+    if !newer.is_empty() {
+        report.fail("layout versions", format!("written by a newer release: {}", newer.join(", ")));
+    } else if !older.is_empty() {
+        report.warn(
+            "layout versions",
+            format!(
+                "older layouts are upcast when read, `brontes db upgrade-bundles` rewrites them: \
+                 {}",
+                older.join(", ")
+            ),
+        );
+    } else {
+        report.pass("layout versions", "stored layouts match this release");
+    }
+}
+
+/// Splits the stored layouts into those newer than or unknown to this binary
+/// and those older than it
+fn compare_layout_versions(
+    stored: &BTreeMap<String, u16>,
+    current: &BTreeMap<String, u16>,
+) -> (Vec<String>, Vec<String>) {
+    let mut newer = vec![];
+    let mut older = vec![];
+    for (layout, version) in stored {
+        match current.get(layout) {
+            Some(current) if version > current => {
+                newer.push(format!("{layout} v{version}, this release reads up to v{current}"))
+            }
+            Some(current) if version < current => {
+                older.push(format!("{layout} v{version}, current v{current}"))
+            }
+            Some(_) => {}
+            None => newer.push(format!("unknown layout {layout}")),
+        }
+    }
+
+    (newer, older)
+}
+
+/// Decodes the ends of every table with validation, so tables written with an
+/// outdated layout are caught before the run reads them
+fn check_db_layout(libmdbx: &LibmdbxReadWriter, report: &mut PreflightReport) {
+    let outdated = Tables::ALL
+        .iter()
+        .filter_map(|table| {
+            table
+                .check_layout(&libmdbx.db)
+                .err()
+                .map(|e| format!("{} ({e})", table.name()))
+        })
+        .collect::<Vec<_>>();
+
+    if outdated.is_empty() {
+        report.pass("libmdbx layout", "all tables decode");
+    } else {
+        report.fail("libmdbx layout", format!("tables that don't decode: {}", outdated.join(", ")));
+    }
+}
+
+/// Reports the blocks of the range that are missing data and returns how many
+/// blocks have no metadata yet
+fn check_data_coverage(
+    args: &RunArgs,
+    libmdbx: &LibmdbxReadWriter,
+    ranges: &[(u64, u64)],
+    clickhouse_ok: bool,
+    report: &mut PreflightReport,
+) -> u64 {
+    if ranges.is_empty() {
+        report.warn("data coverage", "skipped, the run's range is unknown");
+        return 0
+    }
+
+    let mut missing = [Tables::BlockInfo, Tables::CexPrice, Tables::CexTrades, Tables::DexPrice]
+        .map(|table| (table, 0u64));
+    for &(start, end) in ranges {
+        let state = match libmdbx.state_to_initialize(start, end) {
+            Ok(state) => state,
+            Err(e) => {
+                report.fail("data coverage", format!("failed to read initialized state: {e}"));
+                return 0
+            }
+        };
+
+        for (table, count) in &mut missing {
+            *count += state
+                .ranges_to_init
+                .get(table)
+                .map(|ranges| {
+                    ranges
+                        .iter()
+                        .map(|range| (range.end() - range.start() + 1) as u64)
+                        .sum::<u64>()
+                })
+                .unwrap_or_default();
+        }
+    }
+
+    let runs_cex = args
+        .inspectors
+        .as_ref()
+        .map_or(true, |inspectors| CEX_INSPECTORS.iter().any(|i| inspectors.contains(i)));
+
+    for (table, count) in missing {
+        let name = match table {
+            Tables::BlockInfo => "block metadata",
+            Tables::CexPrice => "cex quotes",
+            Tables::CexTrades => "cex trades",
+            _ => "dex prices",
+        };
+
+        if count == 0 {
+            report.pass("data coverage", format!("{name} present for the whole range"));
+        } else if table == Tables::DexPrice {
+            if args.force_no_dex_pricing {
+                report.warn(
+                    "data coverage",
+                    format!("{name} missing for {count} blocks, usd pnl will be missing"),
+                );
+            } else {
+                report.pass(
+                    "data coverage",
+                    format!("{name} missing for {count} blocks, computed during the run"),
+                );
+            }
+        } else if matches!(table, Tables::CexPrice | Tables::CexTrades) && !runs_cex {
+            report.pass("data coverage", format!("{name} missing for {count} blocks, unused"));
+        } else if clickhouse_ok {
+            report.warn(
+                "data coverage",
+                format!("{name} missing for {count} blocks, downloaded before the run"),
+            );
+        } else {
+            report.fail(
+                "data coverage",
+                format!("{name} missing for {count} blocks and clickhouse is unavailable"),
+            );
+        }
+    }
+
+    missing[0].1
+}
+
+fn check_disk_space(
+    brontes_db_endpoint: &str,
+    libmdbx: Option<&LibmdbxReadWriter>,
+    missing_blocks: u64,
+    report: &mut PreflightReport,
+) {
+    // the database directory may not exist yet, its closest existing parent
+    // is on the same disk
+    let existing = Path::new(brontes_db_endpoint)
+        .ancestors()
+        .find(|path| path.exists())
+        .unwrap_or(Path::new("."));
+    let free = match fs2::available_space(existing) {
+        Ok(free) => free as f64,
+        Err(e) => {
+            report.warn("disk space", format!("failed to read free space: {e}"));
+            return
+        }
+    };
+
+    // estimate from what the database already holds per block
+    let stored_blocks = libmdbx
+        .and_then(|libmdbx| {
+            libmdbx
+                .db
+                .view_db(|tx| Ok(tx.entries::<brontes_database::InitializedState>()?))
+                .ok()
+        })
+        .unwrap_or_default();
+    let db_size = fs_extra::dir::get_size(brontes_db_endpoint).unwrap_or_default() as f64;
+    let bytes_per_block = if stored_blocks > 0 && db_size > 0.0 {
+        db_size / stored_blocks as f64
+    } else {
+        DEFAULT_BYTES_PER_BLOCK
+    };
+
+    let projected = bytes_per_block * missing_blocks as f64;
+    let detail = format!(
+        "projected {} for {missing_blocks} blocks, {} free",
+        human_bytes(projected),
+        human_bytes(free)
+    );
+
+    if projected > free {
+        report.fail("disk space", detail);
+    } else if projected > free * DISK_WARN_RATIO {
+        report.warn("disk space", detail);
+    } else {
+        report.pass("disk space", detail);
+    }
+}
+
+fn requested_ranges(range_type: &RangeType, tip: Option<u64>) -> Vec<(u64, u64)> {
+    match range_type {
+        RangeType::MultipleRanges(ranges) => ranges.clone(),
+        RangeType::SingleRange { start_block: Some(start), end_block, back_from_tip } => end_block
+            .or_else(|| tip.map(|tip| tip.saturating_sub(*back_from_tip)))
+            .filter(|end| end >= start)
+            .map(|end| vec![(*start, end)])
+            .unwrap_or_default(),
+        // at tip only, there's no backfill to check
+        RangeType::SingleRange { start_block: None, .. } => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_layout_versions() {
+        let current = layout_versions();
+        assert_eq!(compare_layout_versions(&current, &current), (vec![], vec![]));
+
+        let mut stored = BTreeMap::new();
+        stored.insert("mev_block".to_string(), 0);
+        stored.insert("bundle_header".to_string(), current["bundle_header"] + 1);
+        stored.insert("bundle_data.NewKind".to_string(), 1);
+
+        let (newer, older) = compare_layout_versions(&stored, &current);
+        assert_eq!(newer.len(), 2);
+        assert_eq!(older.len(), 1);
+    }
+}
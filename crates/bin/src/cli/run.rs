@@ -1,22 +1,57 @@
-use std::{path::Path, time::Duration};
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use brontes_core::decoding::Parser as DParser;
-use brontes_database::clickhouse::cex_config::CexDownloadConfig;
+use brontes_core::{
+    decoding::Parser as DParser,
+    flat_files::{era::LAST_PRE_MERGE_BLOCK, FlatFileProvider},
+};
+use brontes_database::{
+    clickhouse::cex_config::CexDownloadConfig,
+    libmdbx::{record_run_provenance, SnapshotManifest},
+};
 use brontes_inspect::Inspectors;
+#[cfg(feature = "tui")]
+use brontes_metrics::dashboard::RecentErrors;
 use brontes_metrics::ParserMetricsListener;
+#[cfg(feature = "local-clickhouse")]
+use brontes_types::db::traits::DBWriter;
 use brontes_types::{
+    action_filter::ActionFilter,
     constants::USDT_ADDRESS_STRING,
-    db::cex::{trades::CexDexTradeConfig, CexExchange},
+    db::{
+        cex::{quotes::QuoteSanityConfig, trades::CexDexTradeConfig, CexExchange},
+        metadata_override::{LabelExperiment, MetadataOverride},
+        nft_floor::{NftFloorPriceSource, StaticNftFloorPrices},
+        run_provenance::RunProvenance,
+    },
     db_write_trigger::{backup_server_heartbeat, start_hr_monitor, HeartRateMonitor},
-    init_thread_pools, UnboundedYapperReceiver,
+    init_thread_pools,
+    live_config::{set_live_config, LiveConfig},
+    tree::spill::MemoryBound,
+    UnboundedYapperReceiver,
 };
 use clap::Parser;
 use tokio::sync::mpsc::unbounded_channel;
 
-use super::{determine_max_tasks, get_env_vars, load_clickhouse, load_database, static_object};
+use super::{
+    determine_max_tasks, get_env_vars, load_clickhouse, load_database, preflight::run_preflight,
+    static_object, with_export_filter, ColdStorageArgs, ExportFilterArgs, SchedulerArgs,
+};
+#[cfg(feature = "cex-ingestion")]
+use crate::misc::cex_backfill::CexBackfill;
+#[cfg(feature = "server")]
+use crate::misc::{
+    http::api_token, live_stream::spawn_live_stream_server, query_api::spawn_query_api,
+};
 use crate::{
     banner::rain,
-    cli::{get_tracing_provider, init_inspectors, load_tip_database},
+    cli::{get_tracing_provider, init_inspectors, load_tip_database, SHORT_VERSION},
+    misc::config_reload::spawn_config_reloader,
     runner::CliContext,
     BrontesRunConfig, MevProcessor, RangeType,
 };
@@ -27,32 +62,44 @@ const SECONDS_TO_US_FLOAT: f64 = 1_000_000.0;
 pub struct RunArgs {
     /// Optional Start Block, if omitted it will run at tip until killed
     #[arg(long, short)]
-    pub start_block:          Option<u64>,
+    pub start_block:             Option<u64>,
     /// Optional End Block, if omitted it will run historically & at tip until
     /// killed
     #[arg(long, short)]
-    pub end_block:            Option<u64>,
+    pub end_block:               Option<u64>,
     /// Optional Multiple Ranges, format: "start1-end1 start2-end2 ..."
     /// Use this if you want to specify the exact, non continuous block ranges
     /// you want to run
     #[arg(long, num_args = 1.., value_delimiter = ' ')]
-    pub ranges:               Option<Vec<String>>,
+    pub ranges:                  Option<Vec<String>>,
+    /// Start from the block after the tip of the snapshot the db was
+    /// bootstrapped from with `brontes db bootstrap`
+    #[arg(long, default_value_t = false, conflicts_with_all = ["start_block", "ranges"])]
+    pub from_snapshot_tip:       bool,
     /// Optional Max Tasks, if omitted it will default to 80% of the number of
     /// physical cores on your machine
     #[arg(long, short)]
-    pub max_tasks:            Option<u64>,
+    pub max_tasks:               Option<u64>,
     /// Optional minimum batch size
     #[arg(long, default_value = "500")]
-    pub min_batch_size:       u64,
+    pub min_batch_size:          u64,
+    /// Blocks of a range that are inspected concurrently. Results are still
+    /// written in block order
+    #[arg(long, default_value = "5")]
+    pub concurrent_blocks:       usize,
     /// Optional quote asset, if omitted it will default to USDT
     #[arg(long, short, default_value = USDT_ADDRESS_STRING)]
-    pub quote_asset:          String,
+    pub quote_asset:             String,
     /// Inspectors to run. If omitted it defaults to running all inspectors
     #[arg(long, short, value_delimiter = ',')]
-    pub inspectors:           Option<Vec<Inspectors>>,
+    pub inspectors:              Option<Vec<Inspectors>>,
+    /// Blocks a liquidity position can be held between its mint and its burn
+    /// and still be found as jit liquidity by the `JitExtended` inspector
+    #[arg(long, default_value = "3")]
+    pub jit_max_hold_blocks:     usize,
     /// Time window arguments for cex data downloads
     #[clap(flatten)]
-    pub time_window_args:     TimeWindowArgs,
+    pub time_window_args:        TimeWindowArgs,
     /// CEX exchanges to consider for cex-dex analysis
     #[arg(
         long,
@@ -60,44 +107,152 @@ pub struct RunArgs {
         default_value = "Binance,Coinbase,Okex,BybitSpot,Kucoin",
         value_delimiter = ','
     )]
-    pub cex_exchanges:        Vec<CexExchange>,
+    pub cex_exchanges:           Vec<CexExchange>,
+    /// Cex quotes whose mid price deviates more than this fraction from the
+    /// median across exchanges are rejected before inspection
+    #[arg(long, default_value = "0.05")]
+    pub cex_quote_max_deviation: f64,
+    /// Exchanges that need to quote a pair for its quotes to be cross-checked
+    #[arg(long, default_value = "3")]
+    pub cex_quote_min_exchanges: usize,
+    /// Inspect with the cex quotes as stored, without the cross-exchange
+    /// sanity checks
+    #[arg(long, default_value = "false")]
+    pub no_cex_quote_sanity:     bool,
     /// Force DEX price calculation for every block, ignoring existing database
     /// values.
     #[arg(long, short, default_value = "false")]
-    pub force_dex_pricing:    bool,
+    pub force_dex_pricing:       bool,
     /// Disables DEX pricing. Inspectors needing DEX prices will only calculate
     /// token PnL, not USD PnL, if DEX pricing is unavailable in the
     /// database.
     #[arg(long, default_value = "false")]
-    pub force_no_dex_pricing: bool,
+    pub force_no_dex_pricing:    bool,
     /// Number of blocks to lag behind the chain tip when processing.
     #[arg(long, default_value = "10")]
-    pub behind_tip:           u64,
+    pub behind_tip:              u64,
     /// Legacy, run in CLI only mode (no TUI) - will output progress bars to
     /// stdout
     #[arg(long, default_value = "true")]
-    pub cli_only:             bool,
+    pub cli_only:                bool,
     /// Export metrics
     #[arg(long, default_value = "false")]
-    pub with_metrics:         bool,
+    pub with_metrics:            bool,
     /// Wether or not to use a fallback server.
     #[arg(long, default_value_t = false)]
-    pub enable_fallback:      bool,
+    pub enable_fallback:         bool,
     /// Address of the fallback server.
     /// Triggers database writes if the main connection fails, preventing data
     /// loss.
     #[arg(long)]
-    pub fallback_server:      Option<String>,
+    pub fallback_server:         Option<String>,
     /// Set a custom run ID used when inserting data into the Clickhouse
     ///
     /// If omitted, the ID will be automatically incremented from the last run
     /// stored in the Clickhouse database.
     #[arg(long, short)]
-    pub run_id:               Option<u64>,
+    pub run_id:                  Option<u64>,
+    /// Append every write transaction to libmdbx to this log before it
+    /// commits, so the tables can be rebuilt with `brontes db replay-log`. A
+    /// log written with other layout versions has to be rotated first
+    #[arg(long)]
+    pub write_log:               Option<PathBuf>,
+    /// Check the configuration, node, databases, data coverage and disk space
+    /// for the run, print a report and exit without processing any blocks.
+    /// The database is only read, it isn't created or modified
+    #[arg(long, default_value_t = false)]
+    pub dry_run:                 bool,
+    /// Json file of NFT collection address to floor price in ETH. NFTs that
+    /// move in a bundle are valued at these floor prices, without it they
+    /// are left unpriced
+    #[arg(long)]
+    pub nft_floor_prices:        Option<PathBuf>,
+    /// Record the inspectors' debug artifacts, the candidates they considered,
+    /// why they were rejected and the prices used, so they can be queried
+    /// with `brontes db inspector-artifacts`
+    #[arg(long, default_value_t = false)]
+    pub debug_artifacts:         bool,
+    /// Label the blocks of the run with the experiment, inspectors read it
+    /// from the block's custom metadata
+    #[arg(long)]
+    pub experiment_label:        Option<String>,
+    /// Transactions of a block held in memory at once. Blocks with more are
+    /// spilled to disk and processed in chunks of this size
+    #[arg(long)]
+    pub max_txs_in_memory:       Option<usize>,
+    /// Transactions of the previous chunk the inspectors see again at the
+    /// start of the next one, so mev crossing a chunk boundary is found
+    #[arg(long, default_value = "50")]
+    pub chunk_overlap:           usize,
+    /// Directory the traces of large blocks are spilled to, defaults to the
+    /// temp dir
+    #[arg(long)]
+    pub spill_dir:               Option<PathBuf>,
+    /// Push the partial results of each block over a websocket at this
+    /// address as its stages complete. Only used when following the chain
+    /// tip
+    #[cfg(feature = "server")]
+    #[arg(long)]
+    pub live_stream_addr:        Option<SocketAddr>,
+    /// Directory of ERA1 archives to read the headers and receipts of the
+    /// blocks they cover from, instead of the node. ERA1 archives end at the
+    /// merge, so the run has to end at or before block 15537393. State reads
+    /// still go to the node
+    #[arg(long)]
+    pub era_dir:                 Option<PathBuf>,
+    /// Directory of trace files written by `db export-traces` to read the
+    /// traces of the blocks they cover from, instead of the node
+    #[arg(long)]
+    pub trace_files_dir:         Option<PathBuf>,
+    /// Toml file of protocols, pools and tokens to include in or exclude from
+    /// the inspectors' analysis, see `config/action_filter.toml`
+    #[arg(long)]
+    pub action_filter:           Option<PathBuf>,
+    /// Toml file of the settings that can be changed while the indexer runs,
+    /// see `config/live_config.toml`. Takes the place of `--action-filter`
+    /// and the cex quote sanity flags, and is read again on SIGHUP
+    #[arg(long, conflicts_with = "action_filter")]
+    pub live_config:             Option<PathBuf>,
+    /// Serve `POST /reload`, which reloads the live config, and `GET /config`
+    /// at this address. Both need the `BRONTES_API_TOKEN` as a bearer token
+    #[cfg(feature = "server")]
+    #[arg(long, requires = "live_config")]
+    pub config_reload_addr:      Option<SocketAddr>,
+    /// Serve the read api over the stored results at this address, see
+    /// `crates/bin/src/misc/query_api.rs` for its routes. Making overrides
+    /// through it needs the `BRONTES_API_TOKEN` as a bearer token
+    #[cfg(feature = "server")]
+    #[arg(long)]
+    pub query_api_addr:          Option<SocketAddr>,
+    /// Address lists to leave out of or redact in the clickhouse inserts
+    #[clap(flatten)]
+    pub export_filter:           ExportFilterArgs,
+    /// Moving the traces and cex data of old blocks off the local db
+    #[clap(flatten)]
+    pub cold_storage:            ColdStorageArgs,
+    /// Maintenance jobs run inside the indexer
+    #[clap(flatten)]
+    pub scheduler:               SchedulerArgs,
+    /// Publishing the results of each block to kafka
+    #[cfg(feature = "kafka")]
+    #[clap(flatten)]
+    pub kafka:                   crate::misc::kafka_sink::KafkaArgs,
 
     /// shows a cool display at startup
     #[arg(long, short, default_value_t = false)]
     pub waterfall: bool,
+
+    /// Replace the log output with an interactive progress dashboard showing
+    /// throughput, queue depths, inspector timings, bundle counts and recent
+    /// errors. Implies `--with-metrics`
+    #[cfg(feature = "tui")]
+    #[arg(long, default_value_t = false)]
+    pub tui:           bool,
+    /// Sink for warnings and errors shown on the dashboard, set when the
+    /// tracing subscriber is initialized
+    #[cfg(feature = "tui")]
+    #[arg(skip)]
+    pub recent_errors: Option<RecentErrors>,
 }
 
 impl RunArgs {
@@ -106,12 +261,47 @@ impl RunArgs {
         brontes_db_endpoint: String,
         ctx: CliContext,
     ) -> eyre::Result<()> {
+        if self.from_snapshot_tip {
+            let manifest =
+                SnapshotManifest::read_local(&brontes_db_endpoint)?.ok_or_else(|| {
+                    eyre::eyre!(
+                        "the db at {brontes_db_endpoint} wasn't bootstrapped from a snapshot"
+                    )
+                })?;
+            self.start_block = Some(manifest.tip_block + 1);
+            tracing::info!(start_block = ?self.start_block, "resuming from the snapshot tip");
+        }
+
+        if self.dry_run {
+            let report = run_preflight(&self, &brontes_db_endpoint, ctx.task_executor).await;
+            report.print();
+            return if report.passed() {
+                Ok(())
+            } else {
+                Err(eyre::eyre!("preflight checks failed"))
+            }
+        }
+
         self.check_proper_range()?;
 
+        let live_config = self.load_live_config()?;
+        set_live_config(live_config.clone());
+        let memory_bound = self.max_txs_in_memory.map(|max_txs_in_memory| MemoryBound {
+            max_txs_in_memory,
+            chunk_overlap: self.chunk_overlap,
+            spill_dir: self.spill_dir.clone().unwrap_or_else(std::env::temp_dir),
+        });
+
         if self.waterfall {
             rain();
         }
 
+        #[cfg(feature = "tui")]
+        if self.tui {
+            self.with_metrics = true;
+            self.cli_only = false;
+        }
+
         let snapshot_mode = !cfg!(feature = "local-clickhouse");
         tracing::info!(%snapshot_mode);
 
@@ -134,11 +324,60 @@ impl RunArgs {
 
         task_executor.spawn_critical("metrics", metrics_listener);
 
+        #[cfg(not(feature = "server"))]
+        let live_events = None;
+        #[cfg(feature = "server")]
+        let live_events = match self.live_stream_addr {
+            Some(_) if self.end_block.is_some() || self.ranges.is_some() => {
+                tracing::warn!("the live stream is only served when following the chain tip");
+                None
+            }
+            Some(addr) => Some(spawn_live_stream_server(addr, &task_executor).await?),
+            None => None,
+        };
+
+        #[cfg(feature = "tui")]
+        if let Some(recent_errors) = self.recent_errors.take().filter(|_| self.tui) {
+            crate::misc::dashboard::spawn_dashboard(&task_executor, recent_errors);
+        }
+
         let hr = self.try_start_fallback_server().await;
 
         tracing::info!(target: "brontes", "starting database initialization at: '{}'", brontes_db_endpoint);
-        let libmdbx =
-            static_object(load_database(&task_executor, brontes_db_endpoint, hr, None).await?);
+        let cold_storage = self.cold_storage.config(&brontes_db_endpoint);
+        let db = load_database(
+            &task_executor,
+            brontes_db_endpoint,
+            hr,
+            None,
+            self.write_log.clone(),
+            cold_storage,
+        )
+        .await?;
+        let db = with_export_filter(db, self.export_filter.load()?);
+        #[cfg(feature = "local-clickhouse")]
+        let raw_db = db.inner.db.clone();
+        #[cfg(not(feature = "local-clickhouse"))]
+        let raw_db = db.db.clone();
+        let run_id = record_run_provenance(
+            &raw_db,
+            self.run_provenance(live_config.action_filter.as_ref())?,
+        )?;
+        tracing::info!(run_id, "recorded the run's configuration");
+        let libmdbx = static_object(db);
+        if let Some(path) = self.live_config.clone() {
+            spawn_config_reloader(
+                path,
+                #[cfg(feature = "server")]
+                self.config_reload_addr,
+                libmdbx,
+                &task_executor,
+            )?;
+        }
+        #[cfg(feature = "server")]
+        if let Some(addr) = self.query_api_addr {
+            spawn_query_api(addr, libmdbx, api_token().ok(), &task_executor)?;
+        }
 
         let tip = static_object(load_tip_database(libmdbx)?);
         tracing::info!(target: "brontes", "initialized libmdbx database");
@@ -170,17 +409,56 @@ impl RunArgs {
 
         let trade_config = self.time_window_args.trade_config();
 
+        let nft_floor_prices = self
+            .nft_floor_prices
+            .as_ref()
+            .map(StaticNftFloorPrices::from_json_file)
+            .transpose()?
+            .map(|floors| Arc::new(floors) as Arc<dyn NftFloorPriceSource>);
+
+        let metadata_overrides = self
+            .experiment_label
+            .clone()
+            .map(|label| Arc::new(LabelExperiment(label)) as Arc<dyn MetadataOverride>)
+            .into_iter()
+            .collect();
+
+        if self.jit_max_hold_blocks == 0 {
+            eyre::bail!("--jit-max-hold-blocks must be at least one block");
+        }
         let inspectors = init_inspectors(
             quote_asset,
             libmdbx,
             self.inspectors,
             self.cex_exchanges,
             trade_config,
+            self.jit_max_hold_blocks,
             self.with_metrics,
         );
 
-        let tracer =
-            get_tracing_provider(Path::new(&reth_db_path), max_tasks, task_executor.clone());
+        let tracer = FlatFileProvider::new(
+            get_tracing_provider(Path::new(&reth_db_path), max_tasks, task_executor.clone())?,
+            self.era_dir.clone(),
+            self.trace_files_dir.clone(),
+        )?;
+
+        let scheduler = self.scheduler.scheduler();
+        #[cfg(feature = "cex-ingestion")]
+        let scheduler = match self.scheduler.cex_backfill_blocks {
+            Some(lookback_blocks) => scheduler.with_job(Arc::new(CexBackfill {
+                libmdbx,
+                clickhouse,
+                tracer: Arc::new(tracer.clone()),
+                lookback_blocks,
+                interval: Duration::from_secs(60 * 60),
+            })),
+            None => scheduler,
+        };
+        scheduler.spawn(raw_db.clone(), &task_executor);
+
+        #[cfg(feature = "kafka")]
+        self.kafka
+            .spawn(raw_db, libmdbx, tracer.clone(), &task_executor)?;
         let parser = static_object(DParser::new(metrics_tx, libmdbx, tracer.clone()).await);
 
         let executor = task_executor.clone();
@@ -191,6 +469,7 @@ impl RunArgs {
                     range_type,
                     max_tasks,
                     self.min_batch_size,
+                    self.concurrent_blocks,
                     quote_asset,
                     self.force_dex_pricing,
                     self.force_no_dex_pricing,
@@ -204,6 +483,11 @@ impl RunArgs {
                     snapshot_mode,
                     load_window,
                 )
+                .with_nft_floor_prices(nft_floor_prices)
+                .with_metadata_overrides(metadata_overrides)
+                .with_memory_bound(memory_bound)
+                .with_debug_artifacts(self.debug_artifacts)
+                .with_live_events(live_events)
                 .build(task_executor, shutdown)
                 .await
                 .map_err(|e| {
@@ -252,6 +536,53 @@ impl RunArgs {
         }
     }
 
+    fn load_live_config(&self) -> eyre::Result<LiveConfig> {
+        if let Some(path) = &self.live_config {
+            let config = LiveConfig::from_toml_file(path)?;
+            tracing::info!(?config, "loaded the live config");
+            return Ok(config)
+        }
+
+        let config = LiveConfig {
+            action_filter:       self.load_action_filter()?,
+            cex_quote_sanity:    QuoteSanityConfig {
+                max_deviation: self.cex_quote_max_deviation,
+                min_exchanges: self.cex_quote_min_exchanges,
+            },
+            no_cex_quote_sanity: self.no_cex_quote_sanity,
+        };
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    fn load_action_filter(&self) -> eyre::Result<Option<ActionFilter>> {
+        let Some(path) = &self.action_filter else { return Ok(None) };
+        let filter = toml::from_str(&std::fs::read_to_string(path)?)?;
+        tracing::info!(?filter, "scoping the inspectors with the action filter");
+
+        Ok(Some(filter))
+    }
+
+    fn run_provenance(&self, action_filter: Option<&ActionFilter>) -> eyre::Result<RunProvenance> {
+        Ok(RunProvenance {
+            started_at:    std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            version:       SHORT_VERSION.to_string(),
+            start_block:   self.start_block,
+            end_block:     self.end_block,
+            inspectors:    self
+                .inspectors
+                .iter()
+                .flatten()
+                .map(|inspector| inspector.to_string())
+                .collect(),
+            action_filter: action_filter.map(serde_json::to_string).transpose()?,
+        })
+    }
+
     /// the time window in seconds for downloading
     fn load_time_window(&self) -> usize {
         self.time_window_args
@@ -261,14 +592,42 @@ impl RunArgs {
             .max(self.time_window_args.max_optimistic_post) as usize
     }
 
-    fn check_proper_range(&self) -> eyre::Result<()> {
+    pub(crate) fn check_proper_range(&self) -> eyre::Result<()> {
         if let (Some(start), Some(end)) = (&self.start_block, &self.end_block) {
             if start > end {
                 return Err(eyre::eyre!("start block must be less than end block"))
             }
         }
+        if self.era_dir.is_some() {
+            self.check_pre_merge_range()?;
+        }
         Ok(())
     }
+
+    /// ERA1 archives end at the merge, so a run reading from them can't go
+    /// past it
+    fn check_pre_merge_range(&self) -> eyre::Result<()> {
+        let last_block = match (&self.ranges, self.end_block) {
+            (Some(ranges), _) => parse_ranges(ranges)
+                .map_err(|e| eyre::eyre!(e))?
+                .into_iter()
+                .map(|(_, end)| end)
+                .max(),
+            (None, end_block) => end_block,
+        };
+
+        match last_block {
+            Some(block) if block <= LAST_PRE_MERGE_BLOCK => Ok(()),
+            Some(block) => Err(eyre::eyre!(
+                "--era-dir only covers blocks up to the merge at {LAST_PRE_MERGE_BLOCK}, the run \
+                 ends at {block}"
+            )),
+            None => Err(eyre::eyre!(
+                "--era-dir only covers blocks up to the merge at {LAST_PRE_MERGE_BLOCK}, it can't \
+                 be used when following the chain tip"
+            )),
+        }
+    }
 }
 
 fn parse_ranges(ranges: &[String]) -> Result<Vec<(u64, u64)>, String> {
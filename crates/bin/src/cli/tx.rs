@@ -0,0 +1,400 @@
+//! Sandbox mode for a single transaction.
+//!
+//! Traces and classifies only the given transaction, prints the annotated
+//! action tree and runs the inspectors over it. Useful for debugging a
+//! misclassification without having to process the whole block.
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use alloy_primitives::{Address, TxHash};
+use brontes_classifier::Classifier;
+use brontes_core::decoding::Parser as DParser;
+use brontes_inspect::{
+    composer::run_block_inspection, jit::DEFAULT_JIT_MAX_HOLD_BLOCKS, Inspectors,
+};
+use brontes_types::{
+    constants::{USDT_ADDRESS_STRING, WETH_ADDRESS},
+    db::{
+        cex::CexExchange, dex::PriceAt, metadata::Metadata, token_info::TokenInfoWithAddress,
+        traits::LibmdbxReader,
+    },
+    normalized_actions::Action,
+    pair::Pair,
+    structured_trace::TraceActions,
+    tree::{BlockTree, Node, Root},
+    BlockData, MultiBlockData, ToFloatNearest, ToScaledRational,
+};
+use clap::Parser;
+use colored::Colorize;
+use eyre::eyre;
+use malachite::Rational;
+use tokio::sync::mpsc::unbounded_channel;
+
+use super::{get_env_vars, get_tracing_provider, init_inspectors, load_database, static_object};
+use crate::runner::CliContext;
+
+#[derive(Debug, Parser)]
+pub struct TxArgs {
+    /// Hash of the transaction to analyze
+    pub tx_hash:       TxHash,
+    /// Optional quote asset, if omitted it will default to USDT
+    #[arg(long, short, default_value = USDT_ADDRESS_STRING)]
+    pub quote_asset:   String,
+    /// Inspectors to run. If omitted it defaults to running all inspectors
+    #[arg(long, short, value_delimiter = ',')]
+    pub inspectors:    Option<Vec<Inspectors>>,
+    /// CEX exchanges to consider for cex-dex analysis
+    #[arg(
+        long,
+        short,
+        default_value = "Binance,Coinbase,Okex,BybitSpot,Kucoin",
+        value_delimiter = ','
+    )]
+    pub cex_exchanges: Vec<CexExchange>,
+    /// Only print the action tree, skip running the inspectors
+    #[arg(long, default_value = "false")]
+    pub tree_only:     bool,
+    /// Write the call graph of the transaction to this file in Graphviz dot
+    /// format, render it with `dot -Tsvg`
+    #[arg(long)]
+    pub dot:           Option<PathBuf>,
+    /// Write the call graph of the transaction to this file as json
+    #[arg(long)]
+    pub graph_json:    Option<PathBuf>,
+}
+
+impl TxArgs {
+    pub async fn execute(self, brontes_db_endpoint: String, ctx: CliContext) -> eyre::Result<()> {
+        let db_path = get_env_vars()?;
+        let quote_asset = self.quote_asset.parse()?;
+
+        // kept alive so the parser and classifier can send their updates
+        let (metrics_tx, _metrics_rx) = unbounded_channel();
+        let (pricing_tx, _pricing_rx) = unbounded_channel();
+
+        let libmdbx = static_object(
+            load_database(&ctx.task_executor, brontes_db_endpoint, None, None, None, None).await?,
+        );
+        let tracer = get_tracing_provider(Path::new(&db_path), 10, ctx.task_executor.clone())?;
+        let parser = DParser::new(metrics_tx, libmdbx, tracer).await;
+
+        let (block, tx_idx) = parser.get_tracer().block_and_tx_index(self.tx_hash).await?;
+        let (mut traces, header) = parser
+            .execute(block, 0, None)
+            .await
+            .ok_or_else(|| eyre!("failed to trace block {block}"))?;
+
+        if tx_idx >= traces.len() {
+            return Err(eyre!("no trace found for {:?} in block {block}", self.tx_hash))
+        }
+        let trace = traces.swap_remove(tx_idx);
+
+        let classifier = Classifier::new(libmdbx, pricing_tx, parser.get_tracer());
+        let tree = Arc::new(
+            classifier
+                .build_block_tree(vec![trace], header, false)
+                .await?,
+        );
+
+        let metadata = Arc::new(
+            libmdbx
+                .get_metadata(block, quote_asset)
+                .or_else(|_| libmdbx.get_metadata_no_dex_price(block, quote_asset))?,
+        );
+
+        print_tree(&tree, &metadata, quote_asset, libmdbx);
+        self.write_graph(&tree, libmdbx)?;
+
+        if self.tree_only {
+            return Ok(())
+        }
+
+        let inspectors = init_inspectors(
+            quote_asset,
+            libmdbx,
+            self.inspectors,
+            self.cex_exchanges,
+            Default::default(),
+            DEFAULT_JIT_MAX_HOLD_BLOCKS,
+            false,
+        );
+        let data = MultiBlockData {
+            per_block_data: vec![BlockData { metadata, tree }],
+            blocks:         1,
+        };
+        let results = run_block_inspection(inspectors, data, libmdbx);
+
+        if results.mev_details.is_empty() {
+            println!("{}", "No MEV found for transaction".bold());
+        }
+        for bundle in results.mev_details {
+            println!("{bundle}");
+        }
+
+        Ok(())
+    }
+}
+
+impl TxArgs {
+    fn write_graph<DB: LibmdbxReader>(
+        &self,
+        tree: &BlockTree<Action>,
+        db: &DB,
+    ) -> eyre::Result<()> {
+        if self.dot.is_none() && self.graph_json.is_none() {
+            return Ok(())
+        }
+
+        let graph = tree
+            .tx_graph(self.tx_hash, |address| {
+                db.try_fetch_address_metadata(address)
+                    .ok()
+                    .flatten()
+                    .and_then(|meta| meta.describe())
+            })
+            .ok_or_else(|| eyre!("no tree for {:?}", self.tx_hash))?;
+
+        if let Some(path) = &self.dot {
+            std::fs::write(path, graph.to_dot())?;
+        }
+        if let Some(path) = &self.graph_json {
+            std::fs::write(path, serde_json::to_string_pretty(&graph)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn print_tree<DB: LibmdbxReader>(
+    tree: &BlockTree<Action>,
+    metadata: &Metadata,
+    quote_asset: Address,
+    db: &DB,
+) {
+    for root in tree.roots() {
+        let pricing = UsdPricing { metadata, quote_asset, tx_idx: root.position };
+
+        println!(
+            "{} {:?} (block {} tx index {})",
+            "Transaction".bold(),
+            root.tx_hash,
+            metadata.block_num,
+            root.position
+        );
+        if let Some(info) = tree.get_tx_info(root.tx_hash, db) {
+            println!("  eoa: {:?} mev contract: {:?}", info.eoa, info.mev_contract);
+        }
+        println!("  gas: {}", root.gas_details);
+        println!();
+
+        print_node(root, &root.head, 0, &pricing);
+    }
+}
+
+fn print_node(root: &Root<Action>, node: &Node, depth: usize, pricing: &UsdPricing<'_>) {
+    let indent = "  ".repeat(depth);
+
+    for action in root.data_store.get_ref(node.data).into_iter().flatten() {
+        println!("{indent}[{}] {}", node.index, describe_action(action, pricing));
+    }
+
+    for child in &node.inner {
+        print_node(root, child, depth + 1, pricing);
+    }
+}
+
+struct UsdPricing<'a> {
+    metadata:    &'a Metadata,
+    quote_asset: Address,
+    tx_idx:      usize,
+}
+
+impl UsdPricing<'_> {
+    fn value(&self, token: Address, amount: &Rational) -> String {
+        let price = if token == self.quote_asset {
+            Some(Rational::from(1))
+        } else {
+            self.metadata.dex_quotes.as_ref().and_then(|quotes| {
+                quotes
+                    .price_at(Pair(token, self.quote_asset), self.tx_idx)
+                    .map(|price| price.get_price(PriceAt::After))
+            })
+        };
+
+        price
+            .map(|price| {
+                format!("${:.2}", (price * amount).to_float())
+                    .green()
+                    .to_string()
+            })
+            .unwrap_or_else(|| "$?".dimmed().to_string())
+    }
+}
+
+fn describe_action(action: &Action, pricing: &UsdPricing<'_>) -> String {
+    let amount = |token: &TokenInfoWithAddress, amount: &Rational| {
+        format!(
+            "{:.6} {} ({})",
+            amount.clone().to_float(),
+            token.symbol,
+            pricing.value(token.address, amount)
+        )
+    };
+
+    match action {
+        Action::Swap(s) => format!(
+            "{} {} {} -> {} pool: {:?}",
+            "Swap".bold().cyan(),
+            s.protocol,
+            amount(&s.token_in, &s.amount_in),
+            amount(&s.token_out, &s.amount_out),
+            s.pool
+        ),
+        Action::SwapWithFee(s) => format!(
+            "{} {} {} -> {} fee: {} pool: {:?}",
+            "SwapWithFee".bold().cyan(),
+            s.protocol,
+            amount(&s.token_in, &s.amount_in),
+            amount(&s.token_out, &s.amount_out),
+            amount(&s.fee_token, &s.fee_amount),
+            s.pool
+        ),
+        Action::Transfer(t) => format!(
+            "{} {} {:?} -> {:?}",
+            "Transfer".bold().blue(),
+            amount(&t.token, &t.amount),
+            t.from,
+            t.to
+        ),
+        Action::EthTransfer(e) => {
+            let value = e.value.to_scaled_rational(18);
+            format!(
+                "{} {:.6} ETH ({}) {:?} -> {:?}{}",
+                "EthTransfer".bold().blue(),
+                value.clone().to_float(),
+                pricing.value(WETH_ADDRESS, &value),
+                e.from,
+                e.to,
+                if e.coinbase_transfer { " (coinbase)" } else { "" }
+            )
+        }
+        Action::NftTransfer(n) => format!(
+            "{} {} {:?} ids: {} {:?} -> {:?}",
+            "NftTransfer".bold().blue(),
+            n.standard,
+            n.collection,
+            n.token_ids
+                .iter()
+                .zip(&n.amounts)
+                .map(|(id, amount)| format!("{id} x{amount}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            n.from,
+            n.to
+        ),
+        Action::Mint(m) => format!(
+            "{} {} {} pool: {:?}",
+            "Mint".bold().magenta(),
+            m.protocol,
+            m.token
+                .iter()
+                .zip(&m.amount)
+                .map(|(t, a)| amount(t, a))
+                .collect::<Vec<_>>()
+                .join(", "),
+            m.pool
+        ),
+        Action::Burn(b) => format!(
+            "{} {} {} pool: {:?}",
+            "Burn".bold().magenta(),
+            b.protocol,
+            b.token
+                .iter()
+                .zip(&b.amount)
+                .map(|(t, a)| amount(t, a))
+                .collect::<Vec<_>>()
+                .join(", "),
+            b.pool
+        ),
+        Action::Collect(c) => format!(
+            "{} {} {} pool: {:?}",
+            "Collect".bold().magenta(),
+            c.protocol,
+            c.token
+                .iter()
+                .zip(&c.amount)
+                .map(|(t, a)| amount(t, a))
+                .collect::<Vec<_>>()
+                .join(", "),
+            c.pool
+        ),
+        Action::Liquidation(l) => format!(
+            "{} {} debt: {} collateral: {} debtor: {:?}",
+            "Liquidation".bold().red(),
+            l.protocol,
+            amount(&l.debt_asset, &l.covered_debt),
+            amount(&l.collateral_asset, &l.liquidated_collateral),
+            l.debtor
+        ),
+        Action::FlashLoan(f) => format!(
+            "{} {} {} ({} child actions)",
+            "FlashLoan".bold().yellow(),
+            f.protocol,
+            f.assets
+                .iter()
+                .zip(&f.amounts)
+                .map(|(t, a)| amount(t, a))
+                .collect::<Vec<_>>()
+                .join(", "),
+            f.child_actions.len()
+        ),
+        Action::Fill(f) => format!(
+            "{} {} {} -> {} maker: {:?} taker: {:?}",
+            "Fill".bold().green(),
+            f.protocol,
+            amount(&f.maker_token, &f.maker_amount),
+            amount(&f.taker_token, &f.taker_amount),
+            f.maker,
+            f.taker
+        ),
+        Action::Batch(b) => format!(
+            "{} {} {} user swaps, solver: {:?}",
+            "Batch".bold().yellow(),
+            b.protocol,
+            b.user_swaps.len(),
+            b.solver
+        ),
+        Action::Aggregator(a) => format!(
+            "{} {} {} child actions, recipient: {:?}",
+            "Aggregator".bold().yellow(),
+            a.protocol,
+            a.child_actions.len(),
+            a.recipient
+        ),
+        Action::NewPool(p) => {
+            format!("{} {} {:?}", "NewPool".bold(), p.protocol, p.pool_address)
+        }
+        Action::PoolConfigUpdate(p) => {
+            format!("{} {} {:?}", "PoolConfigUpdate".bold(), p.protocol, p.pool_address)
+        }
+        Action::SelfDestruct(s) => format!("{} {:?}", "SelfDestruct".bold(), s.get_address()),
+        Action::Unclassified(trace) => {
+            let calldata = trace.get_calldata();
+            let selector = calldata
+                .get(0..4)
+                .map(|s| format!("0x{}", alloy_primitives::hex::encode(s)))
+                .unwrap_or_else(|| "0x".to_string());
+
+            format!(
+                "{} {:?} -> {:?} {}",
+                "Call".dimmed(),
+                trace.get_from_addr(),
+                trace.get_to_address(),
+                selector
+            )
+        }
+        Action::Revert => "Revert".red().to_string(),
+    }
+}
@@ -1,8 +1,13 @@
-use std::{env, path::Path};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use alloy_primitives::Address;
+use alloy_primitives::{keccak256, Address, B256};
 #[cfg(not(feature = "local-reth"))]
-use brontes_core::local_provider::LocalProvider;
+use brontes_core::failover_provider::{EndpointConfig, FailoverProvider};
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::clickhouse_config;
 #[cfg(feature = "local-clickhouse")]
@@ -15,7 +20,14 @@ use brontes_database::clickhouse::ClickhouseMiddleware;
 use brontes_database::clickhouse::ReadOnlyMiddleware;
 #[cfg(feature = "local-clickhouse")]
 use brontes_database::clickhouse::{dbms::BrontesClickhouseData, ClickhouseBuffered};
-use brontes_database::{clickhouse::cex_config::CexDownloadConfig, libmdbx::LibmdbxReadWriter};
+use brontes_database::{
+    clickhouse::cex_config::CexDownloadConfig,
+    libmdbx::{
+        ArtifactRetention, ColdStorage, ColdStorageConfig, FundingRetention, LibmdbxReadWriter,
+        MevRollupJob, Scheduler, SchedulerConfig, SearcherStatsRebuild, DEFAULT_COLD_SEGMENT_SIZE,
+        DEFAULT_MAX_CACHED_SEGMENTS,
+    },
+};
 use brontes_inspect::{Inspector, Inspectors};
 use brontes_metrics::inspectors::OutlierMetrics;
 #[cfg(feature = "local-clickhouse")]
@@ -23,13 +35,18 @@ use brontes_types::UnboundedYapperReceiver;
 use brontes_types::{
     db::{
         cex::{trades::CexDexTradeConfig, CexExchange},
+        export_filter::ExportFilter,
         traits::LibmdbxReader,
     },
     db_write_trigger::HeartRateMonitor,
     mev::Bundle,
     BrontesTaskExecutor,
 };
+use clap::Args;
+#[cfg(not(feature = "local-reth"))]
+use eyre::WrapErr;
 use itertools::Itertools;
+use reqwest::Url;
 #[cfg(feature = "local-reth")]
 use reth_tracing_ext::TracingClient;
 use strum::IntoEnumIterator;
@@ -41,8 +58,12 @@ pub async fn load_database(
     db_endpoint: String,
     _: Option<HeartRateMonitor>,
     _: Option<u64>,
+    write_log: Option<PathBuf>,
+    cold_storage: Option<ColdStorageConfig>,
 ) -> eyre::Result<LibmdbxReadWriter> {
-    LibmdbxReadWriter::init_db(db_endpoint, None, executor, true)
+    let db =
+        LibmdbxReadWriter::init_db_with_write_log(db_endpoint, None, executor, true, write_log)?;
+    with_cold_storage(db, executor, cold_storage)
 }
 
 #[cfg(not(feature = "local-clickhouse"))]
@@ -57,8 +78,12 @@ pub async fn load_database(
     db_endpoint: String,
     hr: Option<HeartRateMonitor>,
     run_id: Option<u64>,
+    write_log: Option<PathBuf>,
+    cold_storage: Option<ColdStorageConfig>,
 ) -> eyre::Result<ClickhouseMiddleware<LibmdbxReadWriter>> {
-    let inner = LibmdbxReadWriter::init_db(db_endpoint, None, executor, true)?;
+    let inner =
+        LibmdbxReadWriter::init_db_with_write_log(db_endpoint, None, executor, true, write_log)?;
+    let inner = with_cold_storage(inner, executor, cold_storage)?;
 
     let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
     spawn_db_writer_thread(executor, rx, hr);
@@ -68,6 +93,25 @@ pub async fn load_database(
     Ok(ClickhouseMiddleware::new(clickhouse, inner.into()))
 }
 
+/// Has the clickhouse inserts leave out or redact the addresses of the filter
+#[cfg(feature = "local-clickhouse")]
+pub fn with_export_filter(
+    mut db: ClickhouseMiddleware<LibmdbxReadWriter>,
+    filter: Option<Arc<ExportFilter>>,
+) -> ClickhouseMiddleware<LibmdbxReadWriter> {
+    db.client.export_filter = filter;
+    db
+}
+
+/// Without clickhouse there are no inserts to filter
+#[cfg(not(feature = "local-clickhouse"))]
+pub fn with_export_filter(
+    db: LibmdbxReadWriter,
+    _: Option<Arc<ExportFilter>>,
+) -> LibmdbxReadWriter {
+    db
+}
+
 /// This version is used when `local-clickhouse`
 /// is enabled this also will set
 /// a config in the clickhouse to ensure that
@@ -90,6 +134,20 @@ pub async fn load_read_only_database(
     Ok(ReadOnlyMiddleware::new(clickhouse, inner))
 }
 
+/// Attaches the cold storage to the db and starts moving old ranges to it
+fn with_cold_storage(
+    db: LibmdbxReadWriter,
+    executor: &BrontesTaskExecutor,
+    config: Option<ColdStorageConfig>,
+) -> eyre::Result<LibmdbxReadWriter> {
+    let Some(config) = config else { return Ok(db) };
+    info!(remote = %config.remote, keep_blocks = config.keep_blocks, "using cold storage");
+
+    let db = db.with_cold_storage(ColdStorage::new(config)?);
+    db.spawn_cold_offload(executor)?;
+    Ok(db)
+}
+
 pub fn load_libmdbx(
     executor: &BrontesTaskExecutor,
     db_endpoint: String,
@@ -119,12 +177,34 @@ pub async fn load_clickhouse(
     Ok(ClickhouseHttpClient::new(clickhouse_api, clickhouse_api_key).await)
 }
 
+/// Builds a provider over the endpoints in `RETH_ENDPOINTS`, a comma separated
+/// list of `<http(s) url | ipc path>[|<max requests per second>]`. Falls back
+/// to the single `RETH_ENDPOINT` and `RETH_PORT` pair when it isn't set.
 #[cfg(not(feature = "local-reth"))]
-pub fn get_tracing_provider(_: &Path, _: u64, _: BrontesTaskExecutor) -> LocalProvider {
-    let db_endpoint = env::var("RETH_ENDPOINT").expect("No db Endpoint in .env");
-    let db_port = env::var("RETH_PORT").expect("No DB port.env");
-    let url = format!("{db_endpoint}:{db_port}");
-    LocalProvider::new(url, 5)
+pub fn get_tracing_provider(
+    _: &Path,
+    _: u64,
+    executor: BrontesTaskExecutor,
+) -> eyre::Result<FailoverProvider> {
+    let endpoints = match env::var("RETH_ENDPOINTS") {
+        Ok(endpoints) => endpoints
+            .split(',')
+            .filter(|endpoint| !endpoint.trim().is_empty())
+            .map(|endpoint| endpoint.parse::<EndpointConfig>())
+            .collect::<eyre::Result<Vec<_>>>()
+            .wrap_err("invalid endpoint in RETH_ENDPOINTS")?,
+        Err(_) => {
+            let db_endpoint = env::var("RETH_ENDPOINT").wrap_err("RETH_ENDPOINT is not set")?;
+            let db_port = env::var("RETH_PORT").wrap_err("RETH_PORT is not set")?;
+            vec![format!("{db_endpoint}:{db_port}")
+                .parse()
+                .wrap_err("invalid RETH_ENDPOINT")?]
+        }
+    };
+
+    let provider = FailoverProvider::new(endpoints, 5)?;
+    provider.spawn_health_checks(&executor, None);
+    Ok(provider)
 }
 
 #[cfg(feature = "local-reth")]
@@ -132,8 +212,8 @@ pub fn get_tracing_provider(
     db_path: &Path,
     tracing_tasks: u64,
     executor: BrontesTaskExecutor,
-) -> TracingClient {
-    TracingClient::new(db_path, tracing_tasks, executor.clone())
+) -> eyre::Result<TracingClient> {
+    Ok(TracingClient::new(db_path, tracing_tasks, executor.clone()))
 }
 
 pub fn determine_max_tasks(max_tasks: Option<u64>) -> u64 {
@@ -156,6 +236,7 @@ pub fn init_inspectors<DB: LibmdbxReader>(
     inspectors: Option<Vec<Inspectors>>,
     cex_exchanges: Vec<CexExchange>,
     trade_config: CexDexTradeConfig,
+    jit_max_hold_blocks: usize,
     metrics: bool,
 ) -> &'static [&'static dyn Inspector<Result = Vec<Bundle>>] {
     let mut res = Vec::new();
@@ -169,6 +250,7 @@ pub fn init_inspectors<DB: LibmdbxReader>(
             db,
             &cex_exchanges,
             trade_config,
+            jit_max_hold_blocks,
             metrics.clone(),
         ));
     }
@@ -200,3 +282,170 @@ fn spawn_db_writer_thread(
     .run(shutdown);
     tracing::info!("started writer");
 }
+
+/// Redaction of mev data leaving brontes through exports and clickhouse
+/// inserts
+#[derive(Debug, Clone, Default, Args)]
+pub struct ExportFilterArgs {
+    /// File of addresses, one per line, whose bundles are left out of exports
+    /// and clickhouse inserts
+    #[arg(long)]
+    pub exclude_addresses: Option<PathBuf>,
+    /// File of addresses, one per line, that are replaced with a salted hash
+    /// in exports and clickhouse inserts
+    #[arg(long)]
+    pub redact_addresses:  Option<PathBuf>,
+    /// Salt for the hashes of redacted addresses, defaults to
+    /// `BRONTES_REDACTION_SALT`
+    #[arg(long)]
+    pub redaction_salt:    Option<String>,
+}
+
+impl ExportFilterArgs {
+    pub fn load(&self) -> eyre::Result<Option<Arc<ExportFilter>>> {
+        if self.exclude_addresses.is_none() && self.redact_addresses.is_none() {
+            return Ok(None)
+        }
+
+        let salt = if self.redact_addresses.is_some() {
+            self.redaction_salt
+                .clone()
+                .or_else(|| env::var("BRONTES_REDACTION_SALT").ok())
+                .map(keccak256)
+                .ok_or_else(|| {
+                    eyre::eyre!(
+                        "--redact-addresses requires a salt, use --redaction-salt or \
+                         BRONTES_REDACTION_SALT"
+                    )
+                })?
+        } else {
+            B256::ZERO
+        };
+
+        let filter = ExportFilter::from_files(
+            self.exclude_addresses.as_deref(),
+            self.redact_addresses.as_deref(),
+            salt,
+        )?;
+        info!(
+            excluded = ?self.exclude_addresses,
+            redacted = ?self.redact_addresses,
+            "loaded export filter"
+        );
+
+        Ok(Some(Arc::new(filter)))
+    }
+}
+
+/// Moving the traces and cex data of old blocks to an object store
+#[derive(Debug, Clone, Args)]
+pub struct ColdStorageArgs {
+    /// rclone remote and path to move the traces and cex data of old blocks
+    /// to, e.g. `r2:brontes-cold`
+    #[arg(long, requires = "cold_storage_endpoint")]
+    pub cold_storage_remote:   Option<String>,
+    /// Http endpoint serving the remote's objects, reads of moved blocks
+    /// download them back from it
+    #[arg(long, requires = "cold_storage_remote")]
+    pub cold_storage_endpoint: Option<Url>,
+    /// Directory fetched ranges are unpacked to, defaults to `cold-cache` in
+    /// the db directory
+    #[arg(long)]
+    pub cold_storage_cache:    Option<PathBuf>,
+    /// Fetched ranges kept in the cache directory
+    #[arg(long, default_value_t = DEFAULT_MAX_CACHED_SEGMENTS)]
+    pub cold_cache_segments:   usize,
+    /// Blocks behind the latest traced block that are kept locally
+    #[arg(long, default_value = "216000")]
+    pub cold_keep_blocks:      u64,
+    /// Blocks per range moved to the remote
+    #[arg(long, default_value_t = DEFAULT_COLD_SEGMENT_SIZE)]
+    pub cold_segment_size:     u64,
+}
+
+impl ColdStorageArgs {
+    pub fn config(&self, brontes_db_endpoint: &str) -> Option<ColdStorageConfig> {
+        let remote = self.cold_storage_remote.clone()?;
+        let endpoint = self.cold_storage_endpoint.clone()?;
+        let cache_dir = self
+            .cold_storage_cache
+            .clone()
+            .unwrap_or_else(|| Path::new(brontes_db_endpoint).join("cold-cache"));
+
+        Some(ColdStorageConfig {
+            remote,
+            endpoint,
+            cache_dir,
+            max_cached_segments: self.cold_cache_segments,
+            keep_blocks: self.cold_keep_blocks,
+            segment_size: self.cold_segment_size,
+            poll_interval: Duration::from_secs(600),
+        })
+    }
+}
+
+/// Maintenance jobs the indexer runs on a schedule. Jobs are only registered
+/// when their flag is set
+#[derive(Debug, Clone, Args)]
+pub struct SchedulerArgs {
+    /// Once a day, delete the inspector artifacts of blocks more than this
+    /// many blocks behind the latest block with artifacts
+    #[arg(long)]
+    pub artifact_retention_blocks:    Option<u64>,
+    /// Once a day, delete the wallet funding edges more than this many blocks
+    /// behind the newest one
+    #[arg(long, default_value = "216000")]
+    pub funding_retention_blocks:     u64,
+    /// Rebuild the searcher statistics from the mev blocks every this many
+    /// hours
+    #[arg(long)]
+    pub rebuild_searcher_stats_hours: Option<u64>,
+    /// Roll the searcher statistics up into windows of about a day of blocks
+    /// every this many hours
+    #[arg(long)]
+    pub mev_rollup_hours:             Option<u64>,
+    /// Once an hour, download the cex data of the blocks up to this many
+    /// blocks behind the db's last block that are missing it
+    #[cfg(feature = "cex-ingestion")]
+    #[arg(long)]
+    pub cex_backfill_blocks:          Option<u64>,
+    /// Largest random delay in seconds added to each scheduled run
+    #[arg(long, default_value = "300")]
+    pub scheduler_jitter_secs:        u64,
+    /// Retries of a failed scheduled run, with doubling backoff
+    #[arg(long, default_value = "3")]
+    pub scheduler_max_retries:        u32,
+}
+
+impl SchedulerArgs {
+    pub fn scheduler(&self) -> Scheduler {
+        let mut scheduler = Scheduler::new(SchedulerConfig {
+            jitter: Duration::from_secs(self.scheduler_jitter_secs),
+            max_retries: self.scheduler_max_retries,
+            ..Default::default()
+        });
+
+        if let Some(keep_blocks) = self.artifact_retention_blocks {
+            scheduler = scheduler.with_job(Arc::new(ArtifactRetention {
+                keep_blocks,
+                interval: Duration::from_secs(24 * 60 * 60),
+            }));
+        }
+        scheduler = scheduler.with_job(Arc::new(FundingRetention {
+            keep_blocks: self.funding_retention_blocks,
+            interval:    Duration::from_secs(24 * 60 * 60),
+        }));
+        if let Some(hours) = self.rebuild_searcher_stats_hours {
+            scheduler = scheduler.with_job(Arc::new(SearcherStatsRebuild {
+                interval: Duration::from_secs(hours * 60 * 60),
+            }));
+        }
+        if let Some(hours) = self.mev_rollup_hours {
+            scheduler = scheduler.with_job(Arc::new(MevRollupJob {
+                interval: Duration::from_secs(hours * 60 * 60),
+            }));
+        }
+
+        scheduler
+    }
+}
@@ -5,6 +5,7 @@ use std::ops::RangeInclusive;
 
 use brontes_database::libmdbx::StateToInitialize;
 use brontes_metrics::{
+    classifier::BindingCacheMetrics,
     pricing::DexPricingMetrics,
     range::{FinishedRange, GlobalRangeMetrics},
 };
@@ -24,12 +25,17 @@ use std::{
 };
 
 use alloy_primitives::Address;
-use brontes_classifier::Classifier;
+use brontes_classifier::{binding_cache::ProtocolBindings, Classifier};
 use brontes_core::decoding::{Parser, TracingProvider};
 use brontes_database::libmdbx::LibmdbxInit;
 use brontes_inspect::Inspector;
 use brontes_pricing::{BrontesBatchPricer, GraphManager, LoadState};
-use brontes_types::{BrontesTaskExecutor, FastHashMap, UnboundedYapperReceiver};
+use brontes_types::{
+    db::{metadata_override::MetadataOverride, nft_floor::NftFloorPriceSource},
+    live_events::LiveEvents,
+    tree::spill::MemoryBound,
+    BrontesTaskExecutor, FastHashMap, UnboundedYapperReceiver,
+};
 use futures::{stream::FuturesUnordered, Future, StreamExt};
 use indicatif::MultiProgress;
 use itertools::Itertools;
@@ -51,6 +57,7 @@ pub struct BrontesRunConfig<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseH
     pub range_type: RangeType,
     pub max_tasks: u64,
     pub min_batch_size: u64,
+    pub concurrent_blocks: usize,
     pub quote_asset: Address,
     pub force_dex_pricing: bool,
     pub force_no_dex_pricing: bool,
@@ -63,6 +70,12 @@ pub struct BrontesRunConfig<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseH
     pub metrics: bool,
     pub is_snapshot: bool,
     pub cex_window: usize,
+    pub nft_floor_prices: Option<Arc<dyn NftFloorPriceSource>>,
+    pub metadata_overrides: Vec<Arc<dyn MetadataOverride>>,
+    pub memory_bound: Option<MemoryBound>,
+    pub debug_artifacts: bool,
+    pub live_events: Option<LiveEvents>,
+    protocol_bindings: Arc<ProtocolBindings>,
     _p: PhantomData<P>,
 }
 
@@ -74,6 +87,7 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
         range_type: RangeType,
         max_tasks: u64,
         min_batch_size: u64,
+        concurrent_blocks: usize,
         quote_asset: Address,
         force_dex_pricing: bool,
         force_no_dex_pricing: bool,
@@ -91,6 +105,7 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             clickhouse,
             range_type,
             min_batch_size,
+            concurrent_blocks,
             max_tasks,
             force_dex_pricing,
             parser,
@@ -103,10 +118,52 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             tip_db,
             is_snapshot,
             cex_window,
+            nft_floor_prices: None,
+            metadata_overrides: vec![],
+            memory_bound: None,
+            debug_artifacts: false,
+            live_events: None,
+            protocol_bindings: Arc::new(ProtocolBindings::new(
+                metrics.then(BindingCacheMetrics::default),
+            )),
             _p: PhantomData,
         }
     }
 
+    /// Values NFT transfers in bundle deltas at the collection floor prices
+    /// given by `source`
+    pub fn with_nft_floor_prices(mut self, source: Option<Arc<dyn NftFloorPriceSource>>) -> Self {
+        self.nft_floor_prices = source;
+        self
+    }
+
+    /// Applies the overrides, in order, to the metadata of every block before
+    /// it is inspected
+    pub fn with_metadata_overrides(mut self, overrides: Vec<Arc<dyn MetadataOverride>>) -> Self {
+        self.metadata_overrides = overrides;
+        self
+    }
+
+    /// Builds the trees of blocks larger than the bound in chunks, and
+    /// inspects them in chunks
+    pub fn with_memory_bound(mut self, bound: Option<MemoryBound>) -> Self {
+        self.memory_bound = bound;
+        self
+    }
+
+    /// Has the inspectors record their debug artifacts, which are stored with
+    /// the block's results
+    pub fn with_debug_artifacts(mut self, debug_artifacts: bool) -> Self {
+        self.debug_artifacts = debug_artifacts;
+        self
+    }
+
+    /// Sends the partial results of each block as its stages complete
+    pub fn with_live_events(mut self, live_events: Option<LiveEvents>) -> Self {
+        self.live_events = live_events;
+        self
+    }
+
     pub async fn build(
         self,
         executor: BrontesTaskExecutor,
@@ -315,6 +372,7 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
                         self.inspectors,
                         prgrs_bar,
                         metrics,
+                        self.concurrent_blocks,
                     )
                 }
             },
@@ -378,9 +436,14 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
     ) -> StateCollector<T, DB, CH> {
         let shutdown = Arc::new(AtomicBool::new(false));
         let (tx, rx) = unbounded_channel();
-        let classifier = static_object(Classifier::new(self.libmdbx, tx, self.parser.get_tracer()));
+        let classifier = static_object(
+            Classifier::new(self.libmdbx, tx, self.parser.get_tracer())
+                .with_memory_bound(self.memory_bound.clone())
+                .with_protocol_bindings(self.protocol_bindings.clone()),
+        );
 
         let pairs = self.libmdbx.protocols_created_before(start_block).unwrap();
+        self.protocol_bindings.prewarm(pairs.keys().copied());
 
         let rest_pairs = self
             .libmdbx
@@ -422,7 +485,11 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             self.force_no_dex_pricing,
             data_req,
             self.cex_window,
-        );
+        )
+        .with_nft_floor_prices(self.nft_floor_prices.clone())
+        .with_metadata_overrides(self.metadata_overrides.clone())
+        .with_debug_artifacts(self.debug_artifacts)
+        .with_live_events(self.live_events.clone());
 
         let block_window_size = self
             .inspectors
@@ -442,6 +509,9 @@ impl<T: TracingProvider, DB: LibmdbxInit, CH: ClickhouseHandle, P: Processor>
             window,
             self.quote_asset,
         )
+        // when following the tip, the latency of each block matters, so each
+        // transaction is classified as soon as it's traced
+        .with_incremental_trees(tip)
     }
 
     async fn init_block_range_tables(
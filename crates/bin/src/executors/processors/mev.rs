@@ -1,63 +1,178 @@
-#[cfg(feature = "local-clickhouse")]
 use std::sync::Arc;
 
+use alloy_primitives::Address;
 use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
 use brontes_inspect::{
     composer::{run_block_inspection, ComposerResults},
     Inspector,
 };
+use brontes_metrics::dashboard::record_bundle;
 #[cfg(feature = "local-clickhouse")]
 use brontes_types::frontend_prunes::{
     remove_burn_transfers, remove_collect_transfers, remove_mint_transfers, remove_swap_transfers,
 };
-#[cfg(feature = "local-clickhouse")]
-use brontes_types::normalized_actions::Action;
-#[cfg(feature = "local-clickhouse")]
-use brontes_types::tree::BlockTree;
 use brontes_types::{
-    db::block_analysis::BlockAnalysis,
+    db::{
+        block_analysis::BlockAnalysis,
+        bundle_tags::BundleTag,
+        fee_history::BlockFees,
+        frontend_losses::BlockFrontendLosses,
+        metadata::Metadata,
+        pool_prices::BlockPoolPrices,
+        searcher::SearcherInfo,
+        wallet_funding::{trace_wallet_funding, DbFundingLookup, FundingEdge, FundingSource},
+    },
     execute_on,
+    live_events::LiveEvent,
     mev::{Bundle, MevBlock, MevType},
-    BlockData, MultiBlockData,
+    normalized_actions::Action,
+    tree::BlockTree,
+    BlockData, FastHashMap, MultiBlockData,
 };
 use tracing::debug;
 
-use crate::Processor;
+use crate::{misc::alerts::send_watchlist_alerts, Processor};
 
 #[derive(Debug, Clone, Copy)]
 pub struct MevProcessor;
 
+pub struct MevResults {
+    metadata: Arc<Metadata>,
+    tree:     Arc<BlockTree<Action>>,
+    /// `None` if the block has no transactions
+    composer: Option<ComposerResults>,
+}
+
 impl Processor for MevProcessor {
     type InspectType = Vec<Bundle>;
+    type Results = MevResults;
 
-    async fn process_results<DB: DBWriter + LibmdbxReader>(
+    async fn inspect<DB: DBWriter + LibmdbxReader>(
         db: &'static DB,
         inspectors: &'static [&dyn Inspector<Result = Self::InspectType>],
         data: MultiBlockData,
-    ) {
-        let last = data.get_most_recent_block().clone();
-        let BlockData { metadata, tree } = last;
+    ) -> Self::Results {
+        let BlockData { metadata, tree } = data.get_most_recent_block().clone();
+
+        let composer = if tree.tx_roots.is_empty() {
+            None
+        } else {
+            Some(execute_on!(async_inspect, { run_block_inspection(inspectors, data, db) }).await)
+        };
+        if let Some(composer) = &composer {
+            metadata.emit_live_event(|| LiveEvent::Composed {
+                mev_block: Box::new(composer.block_details.clone()),
+                bundles:   composer.mev_details.clone(),
+            });
+        }
+
+        MevResults { metadata, tree, composer }
+    }
+
+    async fn write_results<DB: DBWriter + LibmdbxReader>(db: &'static DB, results: Self::Results) {
+        // the bytes the results take up in the db are counted against the block
+        match results.metadata.cost.clone() {
+            Some(cost) => cost.scope(write_mev_results(db, results)).await,
+            None => write_mev_results(db, results).await,
+        }
+    }
+}
+
+async fn write_mev_results<DB: DBWriter + LibmdbxReader>(db: &'static DB, results: MevResults) {
+    let MevResults { metadata, tree, composer } = results;
+    if let Err(e) = db
+        .write_dex_quotes(metadata.block_num, metadata.dex_quotes.clone())
+        .await
+    {
+        tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert dex pricing and state into db");
+    }
+
+    if let Err(e) = db
+        .write_block_fees(metadata.block_num, BlockFees::from_tree(&tree))
+        .await
+    {
+        tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert block fees into db");
+    }
+
+    let fundings = FundingEdge::from_tree(tree.clone());
+    if let Err(e) = db
+        .write_wallet_fundings(
+            fundings
+                .iter()
+                .map(|(address, edge)| (*address, edge.clone()))
+                .collect(),
+        )
+        .await
+    {
+        tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert wallet fundings into db");
+    }
+
+    if let Err(e) = db
+        .write_pool_prices(metadata.block_num, BlockPoolPrices::from_tree(tree.clone()))
+        .await
+    {
+        tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert pool prices into db");
+    }
+
+    // attributed before the tree is consumed by the clickhouse insert
+    if let Some(frontend_losses) = composer
+        .as_ref()
+        .map(|composer| BlockFrontendLosses::from_bundles(&tree, &composer.mev_details))
+        .filter(|losses| !losses.is_empty())
+    {
         if let Err(e) = db
-            .write_dex_quotes(metadata.block_num, metadata.dex_quotes.clone())
+            .write_frontend_losses(metadata.block_num, frontend_losses)
             .await
         {
-            tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert dex pricing and state into db");
+            tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert frontend losses into db");
         }
+    }
 
-        #[cfg(feature = "local-clickhouse")]
+    #[cfg(feature = "local-clickhouse")]
+    {
+        let inner_tree = Arc::unwrap_or_clone(tree);
+        insert_tree(db, inner_tree, metadata.block_num).await;
+    }
+
+    if let Some(composer) = &composer {
+        send_watchlist_alerts(&metadata.live_config, metadata.block_num, &composer.mev_details);
+    }
+
+    if let Some(ComposerResults {
+        block_details, mev_details, block_analysis, bundle_tags, ..
+    }) = composer
+    {
+        insert_mev_results(db, block_details, mev_details, block_analysis, bundle_tags, &fundings)
+            .await;
+    }
+
+    if let Some(artifacts) = metadata.take_inspector_artifacts() {
+        if let Err(e) = db
+            .write_inspector_artifacts(metadata.block_num, artifacts)
+            .await
         {
-            let inner_tree = Arc::unwrap_or_clone(tree.clone());
-            insert_tree(db, inner_tree, metadata.block_num).await;
+            tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert inspector artifacts into db");
         }
+    }
 
-        if tree.tx_roots.is_empty() {
-            return
+    // written last so the cost includes the bytes of the results above
+    if let Some(cost) = metadata.cost.clone() {
+        if let Err(e) = db.write_block_cost(metadata.block_num, cost).await {
+            tracing::error!(err=%e, block_num=metadata.block_num, "failed to insert block cost into db");
         }
+    }
 
-        let ComposerResults { block_details, mev_details, block_analysis, .. } =
-            execute_on!(async_inspect, { run_block_inspection(inspectors, data, db) }).await;
-
-        insert_mev_results(db, block_details, mev_details, block_analysis).await;
+    // only waits on the writer when someone is listening for the marker
+    if metadata
+        .live_events
+        .as_ref()
+        .is_some_and(|live_events| live_events.has_subscribers())
+    {
+        if let Err(e) = db.flush_writes().await {
+            tracing::error!(err=%e, block_num=metadata.block_num, "failed to commit the block's results");
+        } else {
+            metadata.emit_live_event(|| LiveEvent::Committed);
+        }
     }
 }
 
@@ -82,6 +197,8 @@ async fn insert_mev_results<DB: DBWriter + LibmdbxReader>(
     block_details: MevBlock,
     mev_details: Vec<Bundle>,
     analysis: BlockAnalysis,
+    bundle_tags: Vec<BundleTag>,
+    fundings: &FastHashMap<Address, FundingEdge>,
 ) {
     debug!(
         target: "brontes::results",
@@ -90,7 +207,7 @@ async fn insert_mev_results<DB: DBWriter + LibmdbxReader>(
     );
 
     let block_number = block_details.block_number;
-    output_mev_and_update_searcher_info(database, &mev_details).await;
+    output_mev_and_update_searcher_info(database, &mev_details, fundings).await;
 
     // Attempt to save the MEV block details
     if let Err(e) = database
@@ -103,6 +220,12 @@ async fn insert_mev_results<DB: DBWriter + LibmdbxReader>(
             block_number
         );
     }
+    if let Err(e) = database
+        .write_detected_bundle_tags(block_number, bundle_tags)
+        .await
+    {
+        tracing::error!(err=%e, %block_number, "failed to insert bundle tags into db");
+    }
     if let Err(e) = database.write_block_analysis(analysis).await {
         tracing::error!(
             "Failed to insert block analysis data into db: {:?} at block: {}",
@@ -114,13 +237,19 @@ async fn insert_mev_results<DB: DBWriter + LibmdbxReader>(
 async fn output_mev_and_update_searcher_info<DB: DBWriter + LibmdbxReader>(
     database: &DB,
     mev_details: &Vec<Bundle>,
+    fundings: &FastHashMap<Address, FundingEdge>,
 ) {
+    let funding_lookup = DbFundingLookup { db: database, block_edges: fundings };
+    // the eoa infos written for this block are still queued for the writer
+    let mut block_eoa_infos: FastHashMap<Address, SearcherInfo> = FastHashMap::default();
+
     for mev in mev_details {
         debug!(
             target: "brontes::results",
             "mev details\n {}",
             mev.to_string()
         );
+        record_bundle(mev.header.mev_type);
 
         if mev.header.mev_type == MevType::Unknown || mev.header.mev_type == MevType::SearcherTx {
             continue
@@ -130,22 +259,75 @@ async fn output_mev_and_update_searcher_info<DB: DBWriter + LibmdbxReader>(
             .try_fetch_searcher_info(mev.header.eoa, mev.header.mev_contract)
             .expect("Failed to fetch searcher info from the database");
 
-        let mut eoa_info = eoa_info.unwrap_or_default();
+        let mut eoa_info = block_eoa_infos
+            .remove(&mev.header.eoa)
+            .or(eoa_info)
+            .unwrap_or_default();
         let mut contract_info = contract_info.unwrap_or_default();
 
-        eoa_info.update_with_bundle(&mev.header);
-        contract_info.update_with_bundle(&mev.header);
+        if eoa_info.funding.is_none() {
+            match trace_wallet_funding(&funding_lookup, mev.header.eoa) {
+                Ok(trace) => {
+                    // the funder runs this eoa too
+                    if let FundingSource::Searcher(funder) = trace.source {
+                        if !eoa_info.sibling_searchers.contains(&funder) {
+                            eoa_info.sibling_searchers.push(funder);
+                        }
+                        link_sibling(database, &mut block_eoa_infos, funder, mev.header.eoa).await;
+                    }
+                    eoa_info.funding = Some(trace);
+                }
+                Err(e) => tracing::error!(eoa=?mev.header.eoa, %e, "failed to trace eoa funding"),
+            }
+        }
+
+        eoa_info.update_with_bundle(mev);
+        contract_info.update_with_bundle(mev);
 
         if let Err(e) = database
             .write_searcher_info(
                 mev.header.eoa,
                 mev.header.mev_contract,
-                eoa_info,
+                eoa_info.clone(),
                 Some(contract_info),
             )
             .await
         {
             tracing::error!("Failed to update searcher info in the database: {:?}", e);
         }
+        block_eoa_infos.insert(mev.header.eoa, eoa_info);
+    }
+}
+
+/// Adds the eoa to the siblings of the searcher that funded it
+async fn link_sibling<DB: DBWriter + LibmdbxReader>(
+    database: &DB,
+    block_eoa_infos: &mut FastHashMap<Address, SearcherInfo>,
+    funder: Address,
+    eoa: Address,
+) {
+    let funder_info = match block_eoa_infos.remove(&funder) {
+        Some(info) => Some(info),
+        None => match database.try_fetch_searcher_eoa_info(funder) {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::error!(?funder, %e, "failed to fetch the funding searcher's info");
+                return
+            }
+        },
+    };
+    let mut funder_info = funder_info.unwrap_or_default();
+    if funder_info.sibling_searchers.contains(&eoa) {
+        block_eoa_infos.insert(funder, funder_info);
+        return
+    }
+
+    funder_info.sibling_searchers.push(eoa);
+    if let Err(e) = database
+        .write_searcher_eoa_info(funder, funder_info.clone())
+        .await
+    {
+        tracing::error!(?funder, %e, "failed to link the funded eoa to its searcher");
     }
+    block_eoa_infos.insert(funder, funder_info);
 }
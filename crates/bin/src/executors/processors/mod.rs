@@ -6,12 +6,32 @@ use brontes_types::MultiBlockData;
 use futures::Future;
 pub use mev::*;
 
+/// Processing of a block is split into inspection and writing the results, so
+/// that multiple blocks can be inspected concurrently while their results are
+/// still written in block order.
 pub trait Processor: Send + Sync + 'static + Unpin + Copy + Clone {
     type InspectType: Send + Sync + Unpin;
+    type Results: Send + Unpin + 'static;
 
-    fn process_results<DB: DBWriter + LibmdbxReader>(
+    fn inspect<DB: DBWriter + LibmdbxReader>(
         db: &'static DB,
         inspectors: &'static [&dyn Inspector<Result = Self::InspectType>],
         data: MultiBlockData,
+    ) -> impl Future<Output = Self::Results> + Send;
+
+    fn write_results<DB: DBWriter + LibmdbxReader>(
+        db: &'static DB,
+        results: Self::Results,
     ) -> impl Future<Output = ()> + Send;
+
+    fn process_results<DB: DBWriter + LibmdbxReader>(
+        db: &'static DB,
+        inspectors: &'static [&dyn Inspector<Result = Self::InspectType>],
+        data: MultiBlockData,
+    ) -> impl Future<Output = ()> + Send {
+        async move {
+            let results = Self::inspect(db, inspectors, data).await;
+            Self::write_results(db, results).await
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::VecDeque,
     marker::PhantomData,
     pin::Pin,
     task::{Context, Poll},
@@ -12,7 +13,7 @@ use brontes_database::{
 use brontes_inspect::Inspector;
 use brontes_metrics::range::GlobalRangeMetrics;
 use brontes_types::MultiBlockData;
-use futures::{pin_mut, stream::FuturesUnordered, Future, StreamExt};
+use futures::{pin_mut, stream::FuturesOrdered, Future, FutureExt, StreamExt};
 use reth_tasks::shutdown::GracefulShutdown;
 use tracing::debug;
 
@@ -20,6 +21,7 @@ use super::shared::state_collector::StateCollector;
 use crate::{executors::ProgressBar, Processor};
 
 type InsertFutures = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+type InspectFutures<R> = Pin<Box<dyn Future<Output = R> + Send + 'static>>;
 
 pub struct RangeExecutorWithPricing<
     T: TracingProvider,
@@ -27,16 +29,21 @@ pub struct RangeExecutorWithPricing<
     CH: ClickhouseHandle,
     P: Processor,
 > {
-    id:             usize,
-    collector:      StateCollector<T, DB, CH>,
-    insert_futures: FuturesUnordered<InsertFutures>,
-    current_block:  u64,
-    end_block:      u64,
-    libmdbx:        &'static DB,
-    inspectors:     &'static [&'static dyn Inspector<Result = P::InspectType>],
-    progress_bar:   Option<ProgressBar>,
-    global_metrics: Option<GlobalRangeMetrics>,
-    _p:             PhantomData<P>,
+    id:                    usize,
+    collector:             StateCollector<T, DB, CH>,
+    /// Blocks that are being inspected, yielded in block order
+    inspect_futures:       FuturesOrdered<InspectFutures<P::Results>>,
+    /// Inspected blocks waiting for the previous block to be written
+    pending_writes:        VecDeque<P::Results>,
+    write_future:          Option<InsertFutures>,
+    max_concurrent_blocks: usize,
+    current_block:         u64,
+    end_block:             u64,
+    libmdbx:               &'static DB,
+    inspectors:            &'static [&'static dyn Inspector<Result = P::InspectType>],
+    progress_bar:          Option<ProgressBar>,
+    global_metrics:        Option<GlobalRangeMetrics>,
+    _p:                    PhantomData<P>,
 }
 
 impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P: Processor>
@@ -51,11 +58,15 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
         inspectors: &'static [&'static dyn Inspector<Result = P::InspectType>],
         progress_bar: Option<ProgressBar>,
         global_metrics: Option<GlobalRangeMetrics>,
+        max_concurrent_blocks: usize,
     ) -> Self {
         Self {
             id,
             collector: state_collector,
-            insert_futures: FuturesUnordered::default(),
+            inspect_futures: FuturesOrdered::default(),
+            pending_writes: VecDeque::default(),
+            write_future: None,
+            max_concurrent_blocks: max_concurrent_blocks.max(1),
             current_block: start_block,
             end_block,
             libmdbx,
@@ -79,11 +90,16 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
             },
         }
 
-        while data_batching.insert_futures.next().await.is_some() {
-            data_batching
-                .global_metrics
-                .as_ref()
-                .inspect(|m| m.finished_block(data_batching.id));
+        while let Some(results) = data_batching.inspect_futures.next().await {
+            data_batching.pending_writes.push_back(results);
+        }
+        if let Some(write) = data_batching.write_future.take() {
+            write.await;
+            data_batching.on_block_written();
+        }
+        while let Some(results) = data_batching.pending_writes.pop_front() {
+            P::write_results(data_batching.libmdbx, results).await;
+            data_batching.on_block_written();
         }
 
         drop(graceful_guard);
@@ -98,16 +114,48 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
         let metrics = self.global_metrics.clone();
         let inspectors = self.inspectors;
         let libmdbx = self.libmdbx;
-        self.insert_futures.push(Box::pin(async move {
+        self.inspect_futures.push_back(Box::pin(async move {
             if let Some(metrics) = metrics {
                 metrics
-                    .meter_processing(|| Box::pin(P::process_results(libmdbx, inspectors, data)))
+                    .meter_processing(|| Box::pin(P::inspect(libmdbx, inspectors, data)))
                     .await
             } else {
-                P::process_results(libmdbx, inspectors, data).await
+                P::inspect(libmdbx, inspectors, data).await
             }
         }));
     }
+
+    /// Writes the inspected blocks one at a time so that writes that depend on
+    /// previous blocks, such as searcher stats, are applied in block order.
+    fn poll_writes(&mut self, cx: &mut Context<'_>) {
+        loop {
+            if let Some(write) = self.write_future.as_mut() {
+                if write.poll_unpin(cx).is_pending() {
+                    return
+                }
+                self.write_future = None;
+                self.on_block_written();
+                // a block finished, so we might be able to start the next one
+                cx.waker().wake_by_ref();
+            }
+
+            let Some(results) = self.pending_writes.pop_front() else { return };
+            self.write_future = Some(Box::pin(P::write_results(self.libmdbx, results)));
+        }
+    }
+
+    fn on_block_written(&self) {
+        self.global_metrics.as_ref().inspect(|m| {
+            m.dec_inspector(self.id);
+            m.finished_block(self.id);
+        });
+    }
+
+    fn processing_blocks(&self) -> usize {
+        self.inspect_futures.len()
+            + self.pending_writes.len()
+            + self.write_future.is_some() as usize
+    }
 }
 
 impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P: Processor> Future
@@ -120,7 +168,7 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
         if !self.collector.is_collecting_state()
             && self.collector.should_process_next_block()
             && self.current_block != self.end_block
-            && self.insert_futures.len() < 5
+            && self.processing_blocks() < self.max_concurrent_blocks
         {
             cx.waker().wake_by_ref();
             let block = self.current_block;
@@ -142,7 +190,7 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
                         .inspect(|m| m.remove_pending_tree(self.id));
                     self.on_price_finish(data);
                 }
-                None if self.insert_futures.is_empty() && self.current_block == self.end_block => {
+                None if self.processing_blocks() == 0 && self.current_block == self.end_block => {
                     return Poll::Ready(())
                 }
                 None => {
@@ -152,16 +200,14 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle, P:
             }
         }
 
-        while let Poll::Ready(Some(_)) = self.insert_futures.poll_next_unpin(cx) {
-            self.global_metrics.as_ref().inspect(|m| {
-                m.dec_inspector(self.id);
-                m.finished_block(self.id);
-            });
+        while let Poll::Ready(Some(results)) = self.inspect_futures.poll_next_unpin(cx) {
+            self.pending_writes.push_back(results);
         }
+        self.poll_writes(cx);
 
         // mark complete if we are done with the range
         if self.current_block == self.end_block
-            && self.insert_futures.is_empty()
+            && self.processing_blocks() == 0
             && !self.collector.is_collecting_state()
         {
             self.collector.range_finished(cx.waker());
@@ -13,14 +13,20 @@ use alloy_primitives::Address;
 use brontes_database::clickhouse::ClickhouseHandle;
 use brontes_types::{
     db::{
+        block_costs::BlockCostRecorder,
         cex::trades::{window_loader::CexWindow, CexTradeMap},
         dex::DexQuotes,
+        lst::canonical_lst_rates,
         metadata::Metadata,
+        metadata_override::MetadataOverride,
+        nft_floor::NftFloorPriceSource,
         traits::{DBWriter, LibmdbxReader},
+        vault::{vault_share_rates, VaultShareRate},
     },
+    live_events::{LiveEvent, LiveEvents},
     normalized_actions::Action,
     traits::TracingProvider,
-    BlockData, BlockTree,
+    BlockData, BlockTree, FastHashMap, FastHashSet, TreeSearchBuilder,
 };
 use futures::{stream::FuturesOrdered, Future, Stream, StreamExt};
 use itertools::Itertools;
@@ -45,6 +51,12 @@ pub struct MetadataLoader<T: TracingProvider, CH: ClickhouseHandle> {
     cex_window_data:       CexWindow,
     always_generate_price: bool,
     force_no_dex_pricing:  bool,
+    nft_floor_prices:      Option<Arc<dyn NftFloorPriceSource>>,
+    metadata_overrides:    Vec<Arc<dyn MetadataOverride>>,
+    debug_artifacts:       bool,
+    /// Last rate of each vault share token seen in the run
+    vault_rates:           FastHashMap<Address, VaultShareRate>,
+    live_events:           Option<LiveEvents>,
 }
 
 impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
@@ -65,9 +77,156 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
             result_buf: VecDeque::new(),
             always_generate_price,
             force_no_dex_pricing,
+            nft_floor_prices: None,
+            metadata_overrides: vec![],
+            debug_artifacts: false,
+            vault_rates: FastHashMap::default(),
+            live_events: None,
         }
     }
 
+    pub fn with_nft_floor_prices(
+        mut self,
+        nft_floor_prices: Option<Arc<dyn NftFloorPriceSource>>,
+    ) -> Self {
+        self.nft_floor_prices = nft_floor_prices;
+        self
+    }
+
+    pub fn with_metadata_overrides(
+        mut self,
+        metadata_overrides: Vec<Arc<dyn MetadataOverride>>,
+    ) -> Self {
+        self.metadata_overrides = metadata_overrides;
+        self
+    }
+
+    pub fn with_debug_artifacts(mut self, debug_artifacts: bool) -> Self {
+        self.debug_artifacts = debug_artifacts;
+        self
+    }
+
+    pub fn with_live_events(mut self, live_events: Option<LiveEvents>) -> Self {
+        self.live_events = live_events;
+        self
+    }
+
+    /// Gives the block a recorder for the inspectors' debug artifacts, they're
+    /// taken from it once the block is inspected, and the sender of its live
+    /// events
+    fn attach_artifact_recorder(&self, mut data: BlockData) -> BlockData {
+        if self.debug_artifacts {
+            Arc::make_mut(&mut data.metadata).artifacts = Some(Default::default());
+        }
+        if let Some(live_events) = &self.live_events {
+            Arc::make_mut(&mut data.metadata).live_events = Some(live_events.clone());
+        }
+
+        data
+    }
+
+    /// Applies the overrides of the run, last, so they see and can replace
+    /// everything that was loaded
+    fn apply_metadata_overrides(&self, mut data: BlockData) -> BlockData {
+        if self.metadata_overrides.is_empty() {
+            return data
+        }
+
+        let metadata = Arc::make_mut(&mut data.metadata);
+        self.metadata_overrides
+            .iter()
+            .for_each(|metadata_override| metadata_override.apply(metadata));
+
+        data
+    }
+
+    /// Drops the cex quotes that disagree with the other exchanges and
+    /// records the pairs that lost quotes, with the thresholds of the active
+    /// config
+    fn sanitize_cex_quotes(&self, mut data: BlockData) -> BlockData {
+        let Some(config) = data.metadata.live_config.quote_sanity() else { return data };
+        if data.metadata.cex_quotes.quotes.is_empty() {
+            return data
+        }
+
+        let metadata = Arc::make_mut(&mut data.metadata);
+        let report = metadata.cex_quotes.sanitize(&config);
+        if !report.is_empty() {
+            tracing::debug!(
+                block = metadata.block_num,
+                rejected = report.rejected_quotes,
+                pairs = report.flagged_pairs.len(),
+                "rejected outlier cex quotes"
+            );
+        }
+        metadata.cex_quote_sanity = report;
+
+        data
+    }
+
+    /// Records every NFT collection that moved in the block, along with its
+    /// floor price if there is a source for them, so inspectors can tell NFT
+    /// deltas apart from unpriced tokens
+    fn attach_nft_floor_prices(&self, mut data: BlockData) -> BlockData {
+        let block = data.metadata.block_num;
+
+        let collections = data
+            .tree
+            .clone()
+            .collect_all(TreeSearchBuilder::default().with_action(Action::is_nft_transfer))
+            .flat_map(|(_, actions)| actions)
+            .filter_map(|action| action.try_nft_transfer().map(|nft| nft.collection))
+            .collect::<FastHashSet<_>>();
+
+        if collections.is_empty() {
+            return data
+        }
+
+        let metadata = Arc::make_mut(&mut data.metadata);
+        for collection in collections {
+            let floor = self
+                .nft_floor_prices
+                .as_ref()
+                .and_then(|source| source.floor_price_eth(collection, block));
+            metadata.nft_floor_prices.insert(collection, floor);
+        }
+
+        data
+    }
+
+    /// Reads the canonical exchange rates of the liquid staking tokens
+    /// converted through their staking contracts in the block, and of the
+    /// vault shares as of their last conversion through their vault in the
+    /// run, as vaults aren't converted through in every block
+    fn attach_conversion_rates(&mut self, mut data: BlockData) -> BlockData {
+        let swaps = data
+            .tree
+            .clone()
+            .collect_all(TreeSearchBuilder::default().with_action(Action::is_swap))
+            .flat_map(|(_, actions)| actions)
+            .filter_map(|action| action.try_swaps_merged())
+            .collect_vec();
+        let vault_conversions = data
+            .tree
+            .clone()
+            .collect_all(TreeSearchBuilder::default().with_actions([
+                Action::is_mint,
+                Action::is_burn,
+                Action::is_transfer,
+            ]))
+            .map(|(_, actions)| actions)
+            .collect_vec();
+
+        self.vault_rates
+            .extend(vault_share_rates(vault_conversions.iter().map(Vec::as_slice)));
+
+        let metadata = Arc::make_mut(&mut data.metadata);
+        metadata.lst_rates = canonical_lst_rates(&swaps);
+        metadata.vault_rates = self.vault_rates.clone();
+
+        data
+    }
+
     pub fn should_process_next_block(&self) -> bool {
         self.needs_more_data.load(Ordering::SeqCst)
             && self.dex_pricer_stream.pending_trees() < MAX_PENDING_TREES
@@ -97,20 +256,24 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
     pub fn load_metadata_for_tree<DB: LibmdbxReader + DBWriter>(
         &mut self,
         tree: BlockTree<Action>,
+        cost: BlockCostRecorder,
         libmdbx: &'static DB,
         quote_asset: Address,
     ) {
         let block = tree.header.number;
+        if let Some(live_events) = &self.live_events {
+            live_events.emit(block, || LiveEvent::TreeBuilt { transactions: tree.tx_roots.len() });
+        }
         let generate_dex_pricing = self.generate_dex_pricing(block, libmdbx);
 
         if !generate_dex_pricing && self.clickhouse.is_none() {
-            self.load_metadata_with_dex_prices(tree, libmdbx, block, quote_asset);
+            self.load_metadata_with_dex_prices(tree, cost, libmdbx, block, quote_asset);
         } else if let Some(clickhouse) = self.clickhouse {
-            self.load_metadata_from_clickhouse(tree, libmdbx, clickhouse, block, quote_asset);
+            self.load_metadata_from_clickhouse(tree, cost, libmdbx, clickhouse, block, quote_asset);
         } else if self.force_no_dex_pricing {
-            self.load_metadata_force_no_dex_pricing(tree, libmdbx, block, quote_asset);
+            self.load_metadata_force_no_dex_pricing(tree, cost, libmdbx, block, quote_asset);
         } else {
-            self.load_metadata_no_dex_pricing(tree, libmdbx, block, quote_asset);
+            self.load_metadata_no_dex_pricing(tree, cost, libmdbx, block, quote_asset);
         }
     }
 
@@ -149,6 +312,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
     fn load_metadata_no_dex_pricing<DB: LibmdbxReader>(
         &mut self,
         tree: BlockTree<Action>,
+        cost: BlockCostRecorder,
         libmdbx: &'static DB,
         block: u64,
         quote_asset: Address,
@@ -168,6 +332,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
         meta.builder_info = libmdbx
             .try_fetch_builder_info(tree.header.beneficiary)
             .expect("failed to fetch builder info table in libmdbx");
+        meta.cost = Some(cost);
 
         meta.cex_trades = self.load_cex_trades(libmdbx, block);
 
@@ -180,6 +345,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
     fn load_metadata_force_no_dex_pricing<DB: LibmdbxReader>(
         &mut self,
         tree: BlockTree<Action>,
+        cost: BlockCostRecorder,
         libmdbx: &'static DB,
         block: u64,
         quote_asset: Address,
@@ -199,6 +365,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
         meta.builder_info = libmdbx
             .try_fetch_builder_info(tree.header.beneficiary)
             .expect("failed to fetch builder info table in libmdbx");
+        meta.cost = Some(cost);
 
         let mut meta = meta.into_full_metadata(DexQuotes(vec![]));
         meta.cex_trades = self.load_cex_trades(libmdbx, block);
@@ -211,6 +378,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
     fn load_metadata_with_dex_prices<DB: LibmdbxReader>(
         &mut self,
         tree: BlockTree<Action>,
+        cost: BlockCostRecorder,
         libmdbx: &'static DB,
         block: u64,
         quote_asset: Address,
@@ -226,6 +394,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
         meta.builder_info = libmdbx
             .try_fetch_builder_info(tree.header.beneficiary)
             .expect("failed to fetch builder info table in libmdbx");
+        meta.cost = Some(cost);
 
         meta.cex_trades = self.load_cex_trades(libmdbx, block);
 
@@ -237,6 +406,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
     fn load_metadata_from_clickhouse<DB: LibmdbxReader + DBWriter>(
         &mut self,
         tree: BlockTree<Action>,
+        cost: BlockCostRecorder,
         libmdbx: &'static DB,
         clickhouse: &'static CH,
         block: u64,
@@ -295,6 +465,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> MetadataLoader<T, CH> {
 
             meta.cex_trades = Some(trades);
             meta.builder_info = builder_info;
+            meta.cost = Some(cost);
             (block, tree, meta)
         });
 
@@ -311,7 +482,11 @@ impl<T: TracingProvider, CH: ClickhouseHandle> Stream for MetadataLoader<T, CH>
     ) -> std::task::Poll<Option<Self::Item>> {
         if self.force_no_dex_pricing {
             if let Some(res) = self.result_buf.pop_front() {
-                return Poll::Ready(Some(res))
+                let res = self.attach_nft_floor_prices(self.sanitize_cex_quotes(res));
+                let res = self.attach_conversion_rates(res);
+                return Poll::Ready(Some(
+                    self.apply_metadata_overrides(self.attach_artifact_recorder(res)),
+                ))
             }
             cx.waker().wake_by_ref();
             return Poll::Pending
@@ -325,7 +500,7 @@ impl<T: TracingProvider, CH: ClickhouseHandle> Stream for MetadataLoader<T, CH>
                 .add_pending_inspection(block, tree, meta)
         }
 
-        match self.dex_pricer_stream.poll_next_unpin(cx) {
+        let res = match self.dex_pricer_stream.poll_next_unpin(cx) {
             Poll::Ready(Some((tree, metadata))) => Poll::Ready(Some(BlockData {
                 metadata: Arc::new(metadata),
                 tree:     Arc::new(tree),
@@ -338,6 +513,14 @@ impl<T: TracingProvider, CH: ClickhouseHandle> Stream for MetadataLoader<T, CH>
                     Poll::Pending
                 }
             }
-        }
+        };
+
+        res.map(|data| {
+            data.map(|data| {
+                let data = self.attach_nft_floor_prices(self.sanitize_cex_quotes(data));
+                let data = self.attach_conversion_rates(data);
+                self.apply_metadata_overrides(self.attach_artifact_recorder(data))
+            })
+        })
     }
 }
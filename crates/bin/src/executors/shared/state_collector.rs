@@ -5,15 +5,19 @@ use std::{
         Arc,
     },
     task::{Poll, Waker},
+    time::Instant,
 };
 
 use alloy_primitives::Address;
 use brontes_classifier::Classifier;
-use brontes_core::decoding::Parser;
+use brontes_core::decoding::{Parser, StreamedParserFuture};
 use brontes_database::clickhouse::ClickhouseHandle;
 use brontes_metrics::range::GlobalRangeMetrics;
 use brontes_types::{
-    db::traits::{DBWriter, LibmdbxReader},
+    db::{
+        block_costs::{trace_payload_bytes, BlockCostRecorder},
+        traits::{DBWriter, LibmdbxReader},
+    },
     normalized_actions::Action,
     structured_trace::TxTrace,
     traits::TracingProvider,
@@ -26,7 +30,8 @@ use tracing::{span, trace, Instrument, Level};
 
 use super::{metadata_loader::MetadataLoader, multi_block_window::MultiBlockWindow};
 
-type CollectionFut<'a> = Pin<Box<dyn Future<Output = eyre::Result<BlockTree<Action>>> + Send + 'a>>;
+type CollectionFut<'a> =
+    Pin<Box<dyn Future<Output = eyre::Result<(BlockTree<Action>, BlockCostRecorder)>> + Send + 'a>>;
 type ExecutionFut<'a> = Pin<Box<dyn Future<Output = Option<(Vec<TxTrace>, Header)>> + Send + 'a>>;
 
 pub struct StateCollector<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle> {
@@ -39,6 +44,9 @@ pub struct StateCollector<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH:
     collection_future: Option<CollectionFut<'static>>,
     multi_block:       MultiBlockWindow,
     quote_asset:       Address,
+    /// Classify each transaction as soon as it's traced, see
+    /// [`brontes_classifier::tree_builder::IncrementalTreeBuilder`]
+    incremental_trees: bool,
 }
 
 impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
@@ -62,9 +70,15 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
             collection_future: None,
             multi_block,
             quote_asset,
+            incremental_trees: false,
         }
     }
 
+    pub fn with_incremental_trees(mut self, incremental_trees: bool) -> Self {
+        self.incremental_trees = incremental_trees;
+        self
+    }
+
     pub fn get_shutdown(&self) -> Arc<AtomicBool> {
         self.mark_as_finished.clone()
     }
@@ -84,43 +98,110 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle>
         classifier: &'static Classifier<'static, T, DB>,
         id: usize,
         metrics: Option<GlobalRangeMetrics>,
-    ) -> eyre::Result<BlockTree<Action>> {
-        let Some((traces, header)) = fut.await else {
+    ) -> eyre::Result<(BlockTree<Action>, BlockCostRecorder)> {
+        // the calls the parser makes to the node and the traces it writes are
+        // counted against the block
+        let cost = BlockCostRecorder::default();
+        let Some((traces, header)) = cost.clone().scope(fut).await else {
             classifier.block_load_failure(block);
             return Err(eyre!("no traces found {block}"))
         };
 
         trace!("Got {} traces + header", traces.len());
+        cost.record(|cost| cost.trace_bytes += trace_payload_bytes(&traces));
 
+        let build = classifier.build_block_tree(traces, header, generate_pricing);
+        Self::classify(block, build, cost, classifier, id, metrics).await
+    }
+
+    async fn streamed_state_future(
+        generate_pricing: bool,
+        block: u64,
+        fut: StreamedParserFuture,
+        classifier: &'static Classifier<'static, T, DB>,
+        id: usize,
+        metrics: Option<GlobalRangeMetrics>,
+    ) -> eyre::Result<(BlockTree<Action>, BlockCostRecorder)> {
+        let cost = BlockCostRecorder::default();
+        let Some((traces, header)) = cost.clone().scope(fut).await else {
+            classifier.block_load_failure(block);
+            return Err(eyre!("no traces found {block}"))
+        };
+
+        // the block is still being traced while it's classified, so the node
+        // calls and writes made while streaming are scoped to the block too
+        let trace_cost = cost.clone();
+        let traces = traces.inspect(move |trace| {
+            if let Ok(trace) = trace {
+                trace_cost.record(|cost| {
+                    cost.trace_bytes += trace_payload_bytes(std::slice::from_ref(trace))
+                });
+            }
+        });
+        let build = cost.clone().scope(classifier.build_block_tree_from_stream(
+            traces,
+            header,
+            generate_pricing,
+        ));
+        Self::classify(block, build, cost, classifier, id, metrics).await
+    }
+
+    async fn classify(
+        block: u64,
+        build: impl Future<Output = eyre::Result<BlockTree<Action>>> + Send + 'static,
+        cost: BlockCostRecorder,
+        classifier: &'static Classifier<'static, T, DB>,
+        id: usize,
+        metrics: Option<GlobalRangeMetrics>,
+    ) -> eyre::Result<(BlockTree<Action>, BlockCostRecorder)> {
+        let start = Instant::now();
         let res = if let Some(metrics) = metrics {
             metrics.add_pending_tree(id);
             metrics
-                .tree_builder(id, || {
-                    Box::pin(tokio::spawn(classifier.build_block_tree(
-                        traces,
-                        header,
-                        generate_pricing,
-                    )))
-                })
+                .tree_builder(id, || Box::pin(tokio::spawn(build)))
                 .await
                 .unwrap()
         } else {
-            tokio::spawn(classifier.build_block_tree(traces, header, generate_pricing))
-                .await
-                .unwrap()
+            tokio::spawn(build).await.unwrap()
         };
+        let elapsed = start.elapsed().as_micros() as u64;
+        cost.record(|cost| cost.classification_us += elapsed);
 
-        Ok(res)
+        res.map(|tree| (tree, cost))
+            .inspect_err(|_| classifier.block_load_failure(block))
     }
 
     pub fn fetch_state_for(&mut self, block: u64, id: usize, metrics: Option<GlobalRangeMetrics>) {
-        let execute_fut = self.parser.execute(block, id, metrics.clone());
-
         let generate_pricing = self.metadata_fetcher.generate_dex_pricing(block, self.db);
-        self.collection_future = Some(Box::pin(
-            Self::state_future(generate_pricing, block, execute_fut, self.classifier, id, metrics)
-                .instrument(span!(Level::ERROR, "mev processor", block_number=%block)),
-        ))
+        let span = span!(Level::ERROR, "mev processor", block_number=%block);
+
+        self.collection_future = Some(if self.incremental_trees {
+            let execute_fut = self.parser.execute_streamed(block);
+            Box::pin(
+                Self::streamed_state_future(
+                    generate_pricing,
+                    block,
+                    execute_fut,
+                    self.classifier,
+                    id,
+                    metrics,
+                )
+                .instrument(span),
+            )
+        } else {
+            let execute_fut = self.parser.execute(block, id, metrics.clone());
+            Box::pin(
+                Self::state_future(
+                    generate_pricing,
+                    block,
+                    execute_fut,
+                    self.classifier,
+                    id,
+                    metrics,
+                )
+                .instrument(span),
+            )
+        })
     }
 
     pub fn range_finished(&self, waker: &Waker) {
@@ -141,11 +222,11 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter, CH: ClickhouseHandle> Str
     ) -> std::task::Poll<Option<Self::Item>> {
         if let Some(mut collection_future) = self.collection_future.take() {
             match collection_future.poll_unpin(cx) {
-                Poll::Ready(Ok(tree)) => {
+                Poll::Ready(Ok((tree, cost))) => {
                     let db = self.db;
                     let quote_asset = self.quote_asset;
                     self.metadata_fetcher
-                        .load_metadata_for_tree(tree, db, quote_asset);
+                        .load_metadata_for_tree(tree, cost, db, quote_asset);
 
                     cx.waker().wake_by_ref();
                 }
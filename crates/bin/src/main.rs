@@ -43,11 +43,21 @@ fn main() -> eyre::Result<()> {
 }
 
 fn run() -> eyre::Result<()> {
-    let opt = Args::parse();
+    #[cfg_attr(not(feature = "tui"), allow(unused_mut))]
+    let mut opt = Args::parse();
     let brontes_db_endpoint = opt
         .brontes_db_path
         .unwrap_or(env::var("BRONTES_DB_PATH").expect("No BRONTES_DB_PATH in .env"));
 
+    #[cfg(feature = "tui")]
+    if let Commands::Run(command) = &mut opt.command {
+        if command.tui {
+            let recent_errors = brontes_metrics::dashboard::RecentErrors::default();
+            init_tui_tracing(recent_errors.clone());
+            command.recent_errors = Some(recent_errors);
+        }
+    }
+
     init_tracing(opt.verbosity.directive());
 
     match opt.command {
@@ -61,6 +71,11 @@ fn run() -> eyre::Result<()> {
                 command.execute(brontes_db_endpoint, ctx)
             })
         }
+        Commands::Tx(command) => {
+            runner::run_command_until_exit(None, Duration::from_secs(5), |ctx| {
+                command.execute(brontes_db_endpoint, ctx)
+            })
+        }
     }
 }
 
@@ -72,3 +87,15 @@ fn init_tracing(verbosity: Directive) {
 
     brontes_tracing::init(layers);
 }
+
+/// The dashboard owns stdout, so warnings and errors are routed to it instead
+/// of being printed.
+#[cfg(feature = "tui")]
+fn init_tui_tracing(recent_errors: brontes_metrics::dashboard::RecentErrors) {
+    let layers = vec![
+        recent_errors.boxed(),
+        brontes_metrics::error_layer::BrontesErrorMetrics::default().boxed(),
+    ];
+
+    brontes_tracing::init(layers);
+}
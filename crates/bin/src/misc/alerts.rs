@@ -0,0 +1,57 @@
+//! Alerts on the bundles of watchlisted searchers, see
+//! [`brontes_types::live_config::Watchlist`]. The watchlists and destinations
+//! come from the block's snapshot of the live config, so a reload applies from
+//! the next block loaded.
+use brontes_types::{
+    live_config::{AlertDestination, LiveConfig},
+    mev::Bundle,
+};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct WatchlistAlert<'a> {
+    watchlist: &'a str,
+    block:     u64,
+    bundle:    &'a Bundle,
+}
+
+pub fn send_watchlist_alerts(config: &LiveConfig, block: u64, bundles: &[Bundle]) {
+    for bundle in bundles {
+        for (watchlist, destination) in config.watch_alerts(&bundle.header) {
+            match destination {
+                AlertDestination::Log => tracing::warn!(
+                    target: "brontes::alerts",
+                    watchlist = %watchlist.name,
+                    block,
+                    tx_hash = ?bundle.header.tx_hash,
+                    mev_type = %bundle.header.mev_type,
+                    profit_usd = bundle.header.profit_usd,
+                    "bundle of a watchlisted searcher"
+                ),
+                AlertDestination::Webhook { url } => {
+                    let alert = WatchlistAlert { watchlist: &watchlist.name, block, bundle };
+                    let body = match serde_json::to_vec(&alert) {
+                        Ok(body) => body,
+                        Err(e) => {
+                            tracing::warn!(err=%e, "failed to encode watchlist alert");
+                            continue
+                        }
+                    };
+                    let url = url.clone();
+                    tokio::spawn(async move {
+                        let res = reqwest::Client::new()
+                            .post(&url)
+                            .header(reqwest::header::CONTENT_TYPE, "application/json")
+                            .body(body)
+                            .send()
+                            .await
+                            .and_then(|res| res.error_for_status());
+                        if let Err(e) = res {
+                            tracing::warn!(%url, err=%e, "failed to send watchlist alert");
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Scheduled top-up of the cex data of recent blocks.
+//!
+//! The cex quotes and trades of a block are downloaded when its range is
+//! initialized, a block that was initialized before the exchanges' data had
+//! landed in clickhouse is left without it. The [`CexBackfill`] job looks for
+//! recent blocks missing cex data and downloads it, so reprocessing them later
+//! prices cex-dex arbs like every other block.
+use std::{sync::Arc, time::Duration};
+
+use brontes_database::{
+    clickhouse::ClickhouseHandle,
+    libmdbx::{Libmdbx, LibmdbxInit, ScheduledJob},
+    Tables,
+};
+use brontes_types::traits::TracingProvider;
+use itertools::Itertools;
+
+pub struct CexBackfill<DB: 'static, CH: 'static, T> {
+    pub libmdbx:         &'static DB,
+    pub clickhouse:      &'static CH,
+    pub tracer:          Arc<T>,
+    /// Blocks behind the db's last block that are checked for missing data
+    pub lookback_blocks: u64,
+    pub interval:        Duration,
+}
+
+impl<DB, CH, T> ScheduledJob for CexBackfill<DB, CH, T>
+where
+    DB: LibmdbxInit + Send + Sync,
+    CH: ClickhouseHandle,
+    T: TracingProvider,
+{
+    fn name(&self) -> &'static str {
+        "cex-backfill"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn run(&self, _: &Libmdbx) -> eyre::Result<()> {
+        let (_, last_block) = self.libmdbx.get_db_range()?;
+        let state = self
+            .libmdbx
+            .state_to_initialize(last_block.saturating_sub(self.lookback_blocks), last_block)?;
+
+        let handle = tokio::runtime::Handle::current();
+        for table in [Tables::CexPrice, Tables::CexTrades] {
+            let blocks = state
+                .ranges_to_init
+                .get(&table)
+                .into_iter()
+                .flatten()
+                .flat_map(|range| *range.start() as u64..=*range.end() as u64)
+                .collect_vec();
+            if blocks.is_empty() {
+                continue
+            }
+
+            tracing::info!(?table, blocks = blocks.len(), "backfilling cex data");
+            handle.block_on(self.libmdbx.initialize_table_arbitrary(
+                self.clickhouse,
+                self.tracer.clone(),
+                table,
+                blocks,
+                Arc::new(vec![]),
+                false,
+            ))?;
+        }
+
+        Ok(())
+    }
+}
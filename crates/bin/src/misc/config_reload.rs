@@ -0,0 +1,157 @@
+//! Reloads the live config while the indexer runs, see
+//! [`brontes_types::live_config`].
+//!
+//! The config file is read again on `SIGHUP`, and on a `POST /reload` to the
+//! reload endpoint when it's served with the `server` feature, which answers
+//! with the outcome of the reload as json. `GET /config` returns the active
+//! config. Both need the `BRONTES_API_TOKEN` as a bearer token. Reloads are
+//! made one at a time and each of them, applied or rejected, is recorded in the
+//! `ConfigReloads` table.
+use std::path::{Path, PathBuf};
+#[cfg(feature = "server")]
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use brontes_types::{
+    db::{config_reload::ConfigReload, traits::DBWriter},
+    live_config::{live_config, set_live_config, LiveConfig},
+    BrontesTaskExecutor,
+};
+#[cfg(feature = "server")]
+use eyre::WrapErr;
+#[cfg(feature = "server")]
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::{mpsc, oneshot},
+};
+
+#[cfg(feature = "server")]
+use super::http::{api_token, is_authorized, json_response, status_response};
+
+/// A reload to make, with what triggered it and where to send its outcome
+type ReloadRequest = (&'static str, Option<oneshot::Sender<ConfigReload>>);
+
+pub fn spawn_config_reloader<DB: DBWriter>(
+    path: PathBuf,
+    #[cfg(feature = "server")] api_addr: Option<SocketAddr>,
+    db: &'static DB,
+    executor: &BrontesTaskExecutor,
+) -> eyre::Result<()> {
+    let (tx, mut rx) = mpsc::channel::<ReloadRequest>(16);
+
+    let mut sighup = signal(SignalKind::hangup())?;
+    let sighup_tx = tx.clone();
+    executor.spawn(async move {
+        while sighup.recv().await.is_some() {
+            if sighup_tx.send(("sighup", None)).await.is_err() {
+                break
+            }
+        }
+    });
+
+    #[cfg(feature = "server")]
+    if let Some(addr) = api_addr {
+        serve_reload_api(addr, api_token()?, tx, executor)?;
+    }
+
+    tracing::info!(path=%path.display(), "reloading the live config on SIGHUP");
+    executor.spawn(async move {
+        while let Some((trigger, respond)) = rx.recv().await {
+            let reload = reload(&path, trigger);
+            if let Err(e) = db.write_config_reload(reload.clone()).await {
+                tracing::warn!(err=%e, "failed to record the config reload");
+            }
+            if let Some(respond) = respond {
+                let _ = respond.send(reload);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Reads the config and swaps it in if it's valid
+fn reload(path: &Path, trigger: &'static str) -> ConfigReload {
+    let mut reload = ConfigReload {
+        reloaded_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default(),
+        trigger: trigger.to_string(),
+        path: path.display().to_string(),
+        ..Default::default()
+    };
+
+    match LiveConfig::from_toml_file(path) {
+        Ok(config) => {
+            reload.changes = live_config().changes(&config);
+            set_live_config(config);
+            tracing::info!(trigger, changes=?reload.changes, "reloaded the live config");
+        }
+        Err(e) => {
+            tracing::error!(trigger, err=%e, "rejected the live config, keeping the active one");
+            reload.error = Some(e.to_string());
+        }
+    }
+
+    reload
+}
+
+#[cfg(feature = "server")]
+fn serve_reload_api(
+    addr: SocketAddr,
+    token: String,
+    tx: mpsc::Sender<ReloadRequest>,
+    executor: &BrontesTaskExecutor,
+) -> eyre::Result<()> {
+    let token = Arc::new(token);
+    let make_svc = make_service_fn(move |_| {
+        let (tx, token) = (tx.clone(), token.clone());
+        let service = service_fn(move |req| handle(req, token.clone(), tx.clone()));
+        async move { Ok::<_, Infallible>(service) }
+    });
+    let server = Server::try_bind(&addr)
+        .wrap_err("could not bind the config reload endpoint")?
+        .serve(make_svc);
+
+    executor.spawn(async move {
+        if let Err(e) = server.await {
+            tracing::error!(err=%e, "config reload endpoint stopped");
+        }
+    });
+    tracing::info!(%addr, "serving the config reload endpoint");
+
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+async fn handle(
+    req: Request<Body>,
+    token: Arc<String>,
+    tx: mpsc::Sender<ReloadRequest>,
+) -> Result<Response<Body>, Infallible> {
+    if !is_authorized(&req, &token) {
+        return Ok(status_response(StatusCode::UNAUTHORIZED))
+    }
+
+    match (req.method(), req.uri().path()) {
+        (&Method::GET, "/config") => Ok(json_response(StatusCode::OK, &*live_config())),
+        (&Method::POST, "/reload") => {
+            let (respond, outcome) = oneshot::channel();
+            if tx.send(("api", Some(respond))).await.is_err() {
+                return Ok(status_response(StatusCode::SERVICE_UNAVAILABLE))
+            }
+            let Ok(reload) = outcome.await else {
+                return Ok(status_response(StatusCode::SERVICE_UNAVAILABLE))
+            };
+
+            let status =
+                if reload.applied() { StatusCode::OK } else { StatusCode::UNPROCESSABLE_ENTITY };
+            Ok(json_response(status, &reload))
+        }
+        _ => Ok(status_response(StatusCode::NOT_FOUND)),
+    }
+}
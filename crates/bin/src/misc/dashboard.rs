@@ -0,0 +1,202 @@
+use std::{
+    io::{self, Stdout},
+    time::Duration,
+};
+
+use brontes_metrics::dashboard::{DashboardSnapshot, RecentErrors};
+use brontes_types::BrontesTaskExecutor;
+use crossterm::{
+    cursor::{Hide, Show},
+    execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen},
+};
+use futures::pin_mut;
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Row, Table},
+    Frame, Terminal,
+};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Spawns the terminal dashboard. It redraws every second from the metrics
+/// registry until the graceful shutdown signal fires, at which point the
+/// terminal is handed back.
+///
+/// The terminal is intentionally kept out of raw mode so ctrl-c still reaches
+/// the process as a signal and shuts brontes down as usual.
+pub fn spawn_dashboard(executor: &BrontesTaskExecutor, recent_errors: RecentErrors) {
+    executor.spawn_with_graceful_shutdown_signal(|shutdown| async move {
+        let mut dashboard = match Dashboard::new(recent_errors) {
+            Ok(dashboard) => dashboard,
+            Err(e) => {
+                tracing::error!(err=%e, "failed to start dashboard");
+                return
+            }
+        };
+
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        pin_mut!(shutdown);
+
+        loop {
+            tokio::select! {
+                guard = &mut shutdown => {
+                    drop(dashboard);
+                    drop(guard);
+                    break
+                }
+                _ = interval.tick() => {
+                    if let Err(e) = dashboard.draw() {
+                        tracing::error!(err=%e, "failed to draw dashboard");
+                        break
+                    }
+                }
+            }
+        }
+    });
+}
+
+struct Dashboard {
+    terminal:      Terminal<CrosstermBackend<Stdout>>,
+    recent_errors: RecentErrors,
+    snapshot:      DashboardSnapshot,
+}
+
+impl Dashboard {
+    fn new(recent_errors: RecentErrors) -> io::Result<Self> {
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, Hide)?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout))?;
+
+        Ok(Self { terminal, recent_errors, snapshot: DashboardSnapshot::gather(None) })
+    }
+
+    fn draw(&mut self) -> io::Result<()> {
+        self.snapshot = DashboardSnapshot::gather(Some(&self.snapshot));
+        let errors = self.recent_errors.events();
+        let snapshot = &self.snapshot;
+
+        self.terminal
+            .draw(|frame| render(frame, snapshot, &errors))
+            .map(|_| ())
+    }
+}
+
+impl Drop for Dashboard {
+    fn drop(&mut self) {
+        let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen, Show);
+    }
+}
+
+fn render(frame: &mut Frame, snapshot: &DashboardSnapshot, errors: &[String]) {
+    let [progress, body, errors_area] = split(
+        Direction::Vertical,
+        frame.size(),
+        [Constraint::Length(3), Constraint::Min(10), Constraint::Length(12)],
+    );
+    let [stages, inspectors, bundles] = split(
+        Direction::Horizontal,
+        body,
+        [Constraint::Percentage(34), Constraint::Percentage(33), Constraint::Percentage(33)],
+    );
+
+    frame.render_widget(progress_gauge(snapshot), progress);
+    frame.render_widget(stage_overview(snapshot), stages);
+    frame.render_widget(inspector_timings(snapshot), inspectors);
+    frame.render_widget(bundle_counts(snapshot), bundles);
+    frame.render_widget(recent_errors(errors), errors_area);
+}
+
+fn split<const N: usize>(
+    direction: Direction,
+    area: Rect,
+    constraints: [Constraint; N],
+) -> [Rect; N] {
+    let chunks = Layout::default()
+        .direction(direction)
+        .constraints(constraints)
+        .split(area);
+
+    std::array::from_fn(|i| chunks[i])
+}
+
+fn titled(title: &str) -> Block<'_> {
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .title_style(Style::default().add_modifier(Modifier::BOLD))
+}
+
+fn progress_gauge(snapshot: &DashboardSnapshot) -> Gauge<'static> {
+    let label = match snapshot.progress() {
+        Some(_) => format!(
+            "{}/{} blocks | {:.2} blocks/s",
+            snapshot.completed_blocks, snapshot.total_blocks, snapshot.blocks_per_sec
+        ),
+        None => {
+            format!(
+                "{} blocks | {:.2} blocks/s",
+                snapshot.completed_blocks, snapshot.blocks_per_sec
+            )
+        }
+    };
+
+    Gauge::default()
+        .block(titled("Progress"))
+        .gauge_style(Style::default().fg(Color::Cyan))
+        .ratio(snapshot.progress().unwrap_or_default())
+        .label(label)
+}
+
+fn stage_overview(snapshot: &DashboardSnapshot) -> Paragraph<'static> {
+    let lines = vec![
+        Line::from(format!("pending trees (pricing/metadata): {}", snapshot.pending_trees)),
+        Line::from(format!("inspector runs in flight:         {}", snapshot.active_inspectors)),
+        Line::from(format!("db write queue:                   {}", snapshot.db_write_queue)),
+        Line::from(""),
+        Line::from(format!("avg block tracing:   {:>10.2} ms", snapshot.avg_tracing_ms)),
+        Line::from(format!("avg tree building:   {:>10.2} ms", snapshot.avg_classification_ms)),
+    ];
+
+    Paragraph::new(lines).block(titled("Stages"))
+}
+
+fn inspector_timings(snapshot: &DashboardSnapshot) -> Table<'static> {
+    let rows = snapshot
+        .inspector_timings
+        .iter()
+        .map(|(inspector, ms)| Row::new(vec![inspector.clone(), format!("{ms:.2} ms")]));
+
+    Table::new(rows, [Constraint::Percentage(60), Constraint::Percentage(40)])
+        .header(
+            Row::new(vec!["Inspector", "Avg Runtime"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(titled("Inspector Timings"))
+}
+
+fn bundle_counts(snapshot: &DashboardSnapshot) -> Table<'static> {
+    let rows = snapshot
+        .bundle_counts
+        .iter()
+        .map(|(mev_type, count)| Row::new(vec![mev_type.clone(), count.to_string()]));
+
+    Table::new(rows, [Constraint::Percentage(60), Constraint::Percentage(40)])
+        .header(
+            Row::new(vec!["Mev Type", "Bundles"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(titled("Bundles Found"))
+}
+
+fn recent_errors(errors: &[String]) -> List<'static> {
+    let items = errors.iter().map(|line| {
+        let color = if line.contains(" ERROR ") { Color::Red } else { Color::Yellow };
+        ListItem::new(line.clone()).style(Style::default().fg(color))
+    });
+
+    List::new(items).block(titled("Recent Errors"))
+}
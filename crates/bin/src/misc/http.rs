@@ -0,0 +1,85 @@
+//! Helpers shared by the http endpoints the indexer serves
+use hyper::{Body, Request, Response, StatusCode};
+
+/// Env var holding the bearer token of the endpoints that change the indexer's
+/// state or data
+const API_TOKEN_VAR: &str = "BRONTES_API_TOKEN";
+
+/// The bearer token the endpoints that change state require. Serving them
+/// without one is refused rather than leaving them open
+pub fn api_token() -> eyre::Result<String> {
+    std::env::var(API_TOKEN_VAR)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| eyre::eyre!("{API_TOKEN_VAR} must be set to serve endpoints that write"))
+}
+
+/// Whether the request carries the token as `Authorization: Bearer <token>`
+pub fn is_authorized<B>(req: &Request<B>, token: &str) -> bool {
+    req.headers()
+        .get(hyper::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+/// Compares without returning at the first differing byte, so the time taken
+/// doesn't leak how much of the token was guessed
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+pub fn json_response<T: serde::Serialize>(status: StatusCode, value: &T) -> Response<Body> {
+    let mut res = Response::new(Body::from(serde_json::to_vec(value).unwrap_or_default()));
+    *res.status_mut() = status;
+    res.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("application/json"),
+    );
+
+    res
+}
+
+pub fn status_response(status: StatusCode) -> Response<Body> {
+    let mut res = Response::new(Body::empty());
+    *res.status_mut() = status;
+
+    res
+}
+
+/// Value of the query parameter
+pub fn query_param<'a>(query: Option<&'a str>, name: &str) -> Option<&'a str> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(key, value)| (key == name).then_some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_authorized() {
+        let req = |auth: Option<&str>| {
+            let mut req = Request::builder();
+            if let Some(auth) = auth {
+                req = req.header(hyper::header::AUTHORIZATION, auth);
+            }
+            req.body(()).unwrap()
+        };
+
+        assert!(is_authorized(&req(Some("Bearer secret")), "secret"));
+        assert!(!is_authorized(&req(Some("Bearer secre")), "secret"));
+        assert!(!is_authorized(&req(Some("secret")), "secret"));
+        assert!(!is_authorized(&req(None), "secret"));
+    }
+
+    #[test]
+    fn test_query_param() {
+        assert_eq!(query_param(Some("min_score=40&limit=3"), "min_score"), Some("40"));
+        assert_eq!(query_param(Some("min_score=40&limit=3"), "limit"), Some("3"));
+        assert_eq!(query_param(Some("flag&limit=3"), "flag"), None);
+        assert_eq!(query_param(None, "limit"), None);
+    }
+}
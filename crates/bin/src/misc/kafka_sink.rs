@@ -0,0 +1,536 @@
+//! Publishes the results of each block to kafka.
+//!
+//! The publisher follows the committed mev blocks in the db and publishes a
+//! block once it's `kafka_finality_depth` blocks behind the chain tip, so a
+//! reorged block is never published. Blocks are published in order, each one
+//! in a single kafka transaction keyed by the block number, so consumers
+//! reading with `isolation.level=read_committed` see either all of a block's
+//! records or none of them. After a block's transaction is committed the
+//! block is stored as the sink's [`PublishCheckpoint`], a restarted indexer
+//! continues from the block after it.
+//!
+//! The checkpoint is written after the transaction is committed, a crash in
+//! between would publish the block again. On startup the tail of the blocks
+//! topic is read back and the checkpoint moved up to the last block committed
+//! there, so the blocks topic must only be written to by this indexer. The
+//! producer is idempotent and its transactional id fences off a previous
+//! instance that is still running.
+//!
+//! A transaction that fails is aborted and retried, a producer that hit a
+//! fatal error is replaced. A block that still can't be published after
+//! [`MAX_ATTEMPTS`] shuts the indexer down instead of being skipped, so the
+//! checkpoint never moves past an unpublished block.
+//!
+//! Payloads are json. When a schema id is set for a topic they're framed in
+//! the schema registry wire format, a zero magic byte followed by the big
+//! endian schema id, so consumers can decode them with the registry's json
+//! schema deserializer.
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use brontes_database::libmdbx::{
+    fetch_mev_blocks_after, fetch_publish_checkpoint, DBWriter, Libmdbx,
+};
+use brontes_types::{
+    db::{mev_block::MevBlockWithClassified, publish_checkpoint::PublishCheckpoint},
+    mev::{Bundle, MevBlock},
+    traits::TracingProvider,
+    BrontesTaskExecutor,
+};
+use clap::Args;
+use rdkafka::{
+    config::ClientConfig,
+    consumer::{BaseConsumer, Consumer},
+    error::KafkaError,
+    message::{Header, Message, OwnedHeaders},
+    producer::{BaseRecord, DefaultProducerContext, Producer, ThreadedProducer},
+    Offset, TopicPartitionList,
+};
+use serde::Serialize;
+
+/// Times a block's transaction is attempted before the publisher gives up
+const MAX_ATTEMPTS: u32 = 5;
+/// Magic byte of the schema registry wire format
+const SCHEMA_REGISTRY_MAGIC: u8 = 0;
+/// Blocks read from the db at a time
+const PUBLISH_BATCH: usize = 100;
+/// Records read back from the end of each partition of the blocks topic on
+/// startup. Each block's transaction ends with a commit marker, so this covers
+/// the last few blocks of the partition
+const TAIL_RECORDS: i64 = 64;
+
+/// Publishing the results of each block to kafka
+#[derive(Debug, Clone, Args)]
+pub struct KafkaArgs {
+    /// Comma separated kafka brokers to publish the mev blocks and bundles of
+    /// each processed block to
+    #[arg(long)]
+    pub kafka_brokers:          Option<String>,
+    /// Topic the mev blocks are published to
+    #[arg(long, default_value = "brontes-mev-blocks")]
+    pub kafka_blocks_topic:     String,
+    /// Topic the bundles are published to
+    #[arg(long, default_value = "brontes-bundles")]
+    pub kafka_bundles_topic:    String,
+    /// Transactional id of the producer. Must be unique per indexer and
+    /// stable across its restarts, the publish checkpoint is stored under it
+    #[arg(long, default_value = "brontes")]
+    pub kafka_transactional_id: String,
+    /// Schema registry id of the mev block schema, frames the payloads in the
+    /// registry's wire format
+    #[arg(long)]
+    pub kafka_block_schema_id:  Option<u32>,
+    /// Schema registry id of the bundle schema, frames the payloads in the
+    /// registry's wire format
+    #[arg(long)]
+    pub kafka_bundle_schema_id: Option<u32>,
+    /// Time the broker gives a transaction before aborting it
+    #[arg(long, default_value = "60")]
+    pub kafka_txn_timeout_secs: u64,
+    /// Blocks a block has to be behind the chain tip before it's published
+    #[arg(long, default_value = "64")]
+    pub kafka_finality_depth:   u64,
+    /// How often the publisher checks for newly committed blocks
+    #[arg(long, default_value = "12")]
+    pub kafka_poll_secs:        u64,
+}
+
+impl KafkaArgs {
+    /// Starts publishing the committed blocks if brokers are set
+    pub fn spawn<T: TracingProvider, DB: DBWriter>(
+        &self,
+        raw_db: Arc<Libmdbx>,
+        db: &'static DB,
+        tracer: T,
+        executor: &BrontesTaskExecutor,
+    ) -> eyre::Result<()> {
+        let Some(brokers) = &self.kafka_brokers else { return Ok(()) };
+        let sink = KafkaSink::new(brokers, self)?;
+        let publisher = KafkaPublisher {
+            sink: Some(sink),
+            sink_name: self.kafka_transactional_id.clone(),
+            raw_db,
+            db,
+            tracer,
+            finality_depth: self.kafka_finality_depth,
+            poll_interval: Duration::from_secs(self.kafka_poll_secs),
+            published_up_to: None,
+        };
+        tracing::info!(%brokers, "publishing block results to kafka");
+
+        let shutdown_executor = executor.clone();
+        executor.spawn_critical_with_graceful_shutdown_signal(
+            "kafka publisher",
+            |shutdown| async move {
+                tokio::select! {
+                    res = publisher.run() => {
+                        if let Err(e) = res {
+                            tracing::error!(err=%e, "kafka publisher stopped");
+                            shutdown_executor.trigger_shutdown("kafka publisher");
+                        }
+                    }
+                    guard = shutdown => drop(guard),
+                }
+            },
+        );
+
+        Ok(())
+    }
+}
+
+struct KafkaPublisher<T, DB: 'static> {
+    /// Moved onto the blocking pool for each transaction
+    sink:            Option<KafkaSink>,
+    /// The checkpoint is stored under the producer's transactional id
+    sink_name:       String,
+    raw_db:          Arc<Libmdbx>,
+    db:              &'static DB,
+    tracer:          T,
+    finality_depth:  u64,
+    poll_interval:   Duration,
+    published_up_to: Option<u64>,
+}
+
+impl<T: TracingProvider, DB: DBWriter> KafkaPublisher<T, DB> {
+    async fn run(mut self) -> eyre::Result<()> {
+        self.load_checkpoint().await?;
+
+        loop {
+            let finalized = self.finalized_block().await?;
+            let blocks = fetch_mev_blocks_after(
+                &self.raw_db,
+                self.published_up_to,
+                finalized,
+                PUBLISH_BATCH,
+            )?;
+            if blocks.is_empty() {
+                tokio::time::sleep(self.poll_interval).await;
+                continue
+            }
+
+            for (block_number, MevBlockWithClassified { block, mev }) in blocks {
+                self.with_sink(move |sink| sink.publish(block_number, &block, &mev))
+                    .await?;
+                self.store_checkpoint(block_number).await?;
+            }
+        }
+    }
+
+    /// Continues from the stored checkpoint or the last block committed to
+    /// the blocks topic, whichever is later
+    async fn load_checkpoint(&mut self) -> eyre::Result<()> {
+        let stored = fetch_publish_checkpoint(&self.raw_db, &self.sink_name)?
+            .map(|checkpoint| checkpoint.published_up_to);
+        let committed = self.with_sink(|sink| sink.last_committed_block()).await?;
+        self.published_up_to = stored.max(committed);
+
+        if let Some(block) = committed.filter(|committed| Some(*committed) > stored) {
+            tracing::warn!(
+                ?stored,
+                committed = block,
+                "moving the kafka checkpoint up to the last committed block"
+            );
+            self.store_checkpoint(block).await?;
+        }
+        tracing::info!(published_up_to = ?self.published_up_to, "resuming kafka publishing");
+
+        Ok(())
+    }
+
+    async fn store_checkpoint(&mut self, block: u64) -> eyre::Result<()> {
+        self.published_up_to = Some(block);
+        self.db
+            .write_publish_checkpoint(PublishCheckpoint {
+                sink:            self.sink_name.clone(),
+                published_up_to: block,
+                published_at:    SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            })
+            .await
+    }
+
+    async fn finalized_block(&self) -> eyre::Result<u64> {
+        #[cfg(feature = "local-reth")]
+        let tip = self.tracer.best_block_number()?;
+        #[cfg(not(feature = "local-reth"))]
+        let tip = self.tracer.best_block_number().await?;
+
+        Ok(finalized_block(tip, self.finality_depth))
+    }
+
+    /// Runs `f` on the blocking pool, kafka transactions block on the
+    /// broker's acks
+    async fn with_sink<R: Send + 'static>(
+        &mut self,
+        f: impl FnOnce(&mut KafkaSink) -> eyre::Result<R> + Send + 'static,
+    ) -> eyre::Result<R> {
+        let mut sink = self
+            .sink
+            .take()
+            .ok_or_else(|| eyre::eyre!("kafka sink lost by a failed publish task"))?;
+        let (sink, res) = tokio::task::spawn_blocking(move || {
+            let res = f(&mut sink);
+            (sink, res)
+        })
+        .await?;
+        self.sink = Some(sink);
+
+        res
+    }
+}
+
+struct KafkaSink {
+    config:           ClientConfig,
+    producer:         ThreadedProducer<DefaultProducerContext>,
+    transactional_id: String,
+    brokers:          String,
+    blocks_topic:     String,
+    bundles_topic:    String,
+    block_schema_id:  Option<u32>,
+    bundle_schema_id: Option<u32>,
+    timeout:          Duration,
+}
+
+/// A record of a block's transaction
+#[derive(Debug)]
+struct Record<'a> {
+    topic:    &'a str,
+    payload:  Vec<u8>,
+    mev_type: Option<String>,
+}
+
+impl KafkaSink {
+    fn new(brokers: &str, args: &KafkaArgs) -> eyre::Result<Self> {
+        let timeout = Duration::from_secs(args.kafka_txn_timeout_secs);
+        let mut config = ClientConfig::new();
+        config
+            .set("bootstrap.servers", brokers)
+            .set("transactional.id", &args.kafka_transactional_id)
+            .set("enable.idempotence", "true")
+            .set("transaction.timeout.ms", timeout.as_millis().to_string())
+            .set("compression.type", "zstd");
+        let producer = create_producer(&config, timeout)?;
+
+        Ok(Self {
+            config,
+            producer,
+            transactional_id: args.kafka_transactional_id.clone(),
+            brokers: brokers.to_string(),
+            blocks_topic: args.kafka_blocks_topic.clone(),
+            bundles_topic: args.kafka_bundles_topic.clone(),
+            block_schema_id: args.kafka_block_schema_id,
+            bundle_schema_id: args.kafka_bundle_schema_id,
+            timeout,
+        })
+    }
+
+    /// Publishes the mev block and bundles of a block in one transaction
+    fn publish(
+        &mut self,
+        block_number: u64,
+        block: &MevBlock,
+        bundles: &[Bundle],
+    ) -> eyre::Result<()> {
+        let (blocks_topic, bundles_topic) = (self.blocks_topic.clone(), self.bundles_topic.clone());
+        let records = block_records(
+            &blocks_topic,
+            &bundles_topic,
+            self.block_schema_id,
+            self.bundle_schema_id,
+            block,
+            bundles,
+        )?;
+        let key = block_number.to_string();
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let err = match self.produce(&key, &records) {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            if attempt >= MAX_ATTEMPTS {
+                self.reset(block_number, &err);
+                return Err(eyre::eyre!(
+                    "failed to publish block {block_number} after {attempt} attempts: {err}"
+                ))
+            }
+            tracing::warn!(%block_number, attempt, %err, "retrying kafka transaction");
+            self.reset(block_number, &err);
+            std::thread::sleep(Duration::from_secs(attempt as u64));
+        }
+    }
+
+    /// Discards the records of a failed transaction, leaving the producer
+    /// ready for the next one
+    fn reset(&mut self, block_number: u64, err: &KafkaError) {
+        let fatal = matches!(err, KafkaError::Transaction(e) if e.is_fatal());
+        let aborted = !fatal && {
+            match self.producer.abort_transaction(self.timeout) {
+                Ok(()) => true,
+                Err(abort_err) => {
+                    tracing::warn!(%block_number, %abort_err, "failed to abort kafka transaction");
+                    false
+                }
+            }
+        };
+        if aborted {
+            return
+        }
+
+        // the producer can't be used again after a fatal error. A new one with
+        // the same transactional id fences the old one off and aborts its
+        // open transaction
+        match create_producer(&self.config, self.timeout) {
+            Ok(producer) => self.producer = producer,
+            Err(e) => tracing::error!(%block_number, %e, "failed to recreate the kafka producer"),
+        }
+    }
+
+    fn produce(&self, key: &str, records: &[Record<'_>]) -> Result<(), KafkaError> {
+        self.producer.begin_transaction()?;
+        for record in records {
+            let mut headers =
+                OwnedHeaders::new().insert(Header { key: "block_number", value: Some(key) });
+            if let Some(mev_type) = &record.mev_type {
+                headers =
+                    headers.insert(Header { key: "mev_type", value: Some(mev_type.as_str()) });
+            }
+
+            self.producer
+                .send(
+                    BaseRecord::to(record.topic)
+                        .key(key)
+                        .payload(&record.payload)
+                        .headers(headers),
+                )
+                .map_err(|(err, _)| err)?;
+        }
+        // flushes the records of the block before committing
+        self.producer.commit_transaction(self.timeout)
+    }
+
+    /// Last block committed to the blocks topic, read back from the end of
+    /// each of its partitions
+    fn last_committed_block(&mut self) -> eyre::Result<Option<u64>> {
+        let consumer: BaseConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.brokers)
+            .set("group.id", format!("{}-checkpoint", self.transactional_id))
+            .set("enable.auto.commit", "false")
+            .set("enable.partition.eof", "true")
+            .set("isolation.level", "read_committed")
+            .create()?;
+
+        let metadata = consumer.fetch_metadata(Some(&self.blocks_topic), self.timeout)?;
+        let partitions = metadata
+            .topics()
+            .iter()
+            .flat_map(|topic| topic.partitions().iter().map(|p| p.id()))
+            .collect::<Vec<_>>();
+
+        let mut keys = Vec::new();
+        for partition in partitions {
+            let (low, high) =
+                consumer.fetch_watermarks(&self.blocks_topic, partition, self.timeout)?;
+            if high <= low {
+                continue
+            }
+
+            let mut assignment = TopicPartitionList::new();
+            assignment.add_partition_offset(
+                &self.blocks_topic,
+                partition,
+                Offset::Offset((high - TAIL_RECORDS).max(low)),
+            )?;
+            consumer.assign(&assignment)?;
+
+            while let Some(msg) = consumer.poll(self.timeout) {
+                match msg {
+                    Ok(msg) => keys.extend(msg.key().map(<[u8]>::to_vec)),
+                    Err(KafkaError::PartitionEOF(_)) => break,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        Ok(last_block_in(keys.iter().map(Vec::as_slice)))
+    }
+}
+
+fn create_producer(
+    config: &ClientConfig,
+    timeout: Duration,
+) -> eyre::Result<ThreadedProducer<DefaultProducerContext>> {
+    let producer: ThreadedProducer<DefaultProducerContext> = config.create()?;
+    // fences off earlier producers with the same transactional id and
+    // aborts the transaction they left open
+    producer.init_transactions(timeout)?;
+
+    Ok(producer)
+}
+
+/// Records of a block's transaction, the mev block first
+fn block_records<'a>(
+    blocks_topic: &'a str,
+    bundles_topic: &'a str,
+    block_schema_id: Option<u32>,
+    bundle_schema_id: Option<u32>,
+    block: &MevBlock,
+    bundles: &[Bundle],
+) -> eyre::Result<Vec<Record<'a>>> {
+    let mut records = Vec::with_capacity(bundles.len() + 1);
+    records.push(Record {
+        topic:    blocks_topic,
+        payload:  encode_payload(block_schema_id, block)?,
+        mev_type: None,
+    });
+    for bundle in bundles {
+        records.push(Record {
+            topic:    bundles_topic,
+            payload:  encode_payload(bundle_schema_id, bundle)?,
+            mev_type: Some(bundle.header.mev_type.to_string()),
+        });
+    }
+
+    Ok(records)
+}
+
+/// Json payload, framed in the schema registry wire format when the topic has
+/// a schema id
+fn encode_payload<T: Serialize>(schema_id: Option<u32>, value: &T) -> eyre::Result<Vec<u8>> {
+    let Some(schema_id) = schema_id else { return Ok(serde_json::to_vec(value)?) };
+
+    let mut payload = Vec::with_capacity(256);
+    payload.push(SCHEMA_REGISTRY_MAGIC);
+    payload.extend_from_slice(&schema_id.to_be_bytes());
+    serde_json::to_writer(&mut payload, value)?;
+
+    Ok(payload)
+}
+
+/// Latest block that can be published with the chain at `tip`
+fn finalized_block(tip: u64, finality_depth: u64) -> u64 {
+    tip.saturating_sub(finality_depth)
+}
+
+/// Highest block number among the record keys, keys that aren't a block
+/// number are ignored
+fn last_block_in<'a>(keys: impl Iterator<Item = &'a [u8]>) -> Option<u64> {
+    keys.filter_map(|key| std::str::from_utf8(key).ok()?.parse::<u64>().ok())
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::mev::{BundleHeader, MevType};
+
+    use super::*;
+
+    #[test]
+    fn test_schema_registry_framing() {
+        let block = MevBlock { block_number: 7, ..Default::default() };
+
+        let plain = encode_payload(None, &block).unwrap();
+        assert_eq!(plain, serde_json::to_vec(&block).unwrap());
+
+        let framed = encode_payload(Some(258), &block).unwrap();
+        assert_eq!(framed[..5], [SCHEMA_REGISTRY_MAGIC, 0, 0, 1, 2]);
+        assert_eq!(framed[5..], plain);
+    }
+
+    #[test]
+    fn test_block_records() {
+        let block = MevBlock { block_number: 7, ..Default::default() };
+        let bundle = Bundle {
+            header: BundleHeader { mev_type: MevType::Sandwich, ..Default::default() },
+            data:   Default::default(),
+        };
+
+        let records =
+            block_records("blocks", "bundles", None, Some(3), &block, &[bundle.clone(), bundle])
+                .unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].topic, "blocks");
+        assert_eq!(records[0].mev_type, None);
+        assert!(records[1..].iter().all(|record| record.topic == "bundles"
+            && record.mev_type.as_deref() == Some("Sandwich")
+            && record.payload[0] == SCHEMA_REGISTRY_MAGIC));
+    }
+
+    #[test]
+    fn test_finalized_block() {
+        assert_eq!(finalized_block(1_000, 64), 936);
+        assert_eq!(finalized_block(10, 64), 0);
+    }
+
+    #[test]
+    fn test_last_block_in() {
+        let keys: [&[u8]; 4] = [b"18000001", b"not a block", b"18000003", b"18000002"];
+        assert_eq!(last_block_in(keys.into_iter()), Some(18000003));
+        assert_eq!(last_block_in(std::iter::empty()), None);
+    }
+}
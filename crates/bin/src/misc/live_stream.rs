@@ -0,0 +1,77 @@
+//! Websocket server pushing the live events of the blocks being processed, see
+//! [`brontes_types::live_events`]. Every event is sent to every connected
+//! client as a json text message.
+use std::net::SocketAddr;
+
+use brontes_types::{live_events::LiveEvents, BrontesTaskExecutor};
+use futures::{SinkExt, StreamExt};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast::error::RecvError,
+};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+
+/// Events a client can fall behind by before it misses the oldest ones
+const LIVE_EVENTS_CAPACITY: usize = 10_000;
+
+/// Serves the live events of the run, the returned sender is given to the
+/// blocks being processed
+pub async fn spawn_live_stream_server(
+    addr: SocketAddr,
+    executor: &BrontesTaskExecutor,
+) -> eyre::Result<LiveEvents> {
+    let live_events = LiveEvents::new(LIVE_EVENTS_CAPACITY);
+    let listener = TcpListener::bind(addr).await?;
+    tracing::info!(%addr, "serving live events over websocket");
+
+    let client_executor = executor.clone();
+    let server_events = live_events.clone();
+    executor.spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, peer)) => {
+                    client_executor.spawn(serve_client(stream, peer, server_events.clone()));
+                }
+                Err(e) => tracing::warn!(%e, "failed to accept live stream connection"),
+            }
+        }
+    });
+
+    Ok(live_events)
+}
+
+async fn serve_client(stream: TcpStream, peer: SocketAddr, live_events: LiveEvents) {
+    // subscribed before the handshake so no events are missed in between
+    let mut events = live_events.subscribe();
+    let mut ws = match accept_async(stream).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            tracing::debug!(%peer, %e, "live stream handshake failed");
+            return
+        }
+    };
+
+    loop {
+        let event = tokio::select! {
+            event = events.recv() => event,
+            // clients only send pings and close frames
+            incoming = ws.next() => match incoming {
+                Some(Ok(msg)) if !msg.is_close() => continue,
+                _ => break,
+            },
+        };
+
+        let event = match event {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                tracing::warn!(%peer, missed, "live stream client fell behind");
+                continue
+            }
+            Err(RecvError::Closed) => break,
+        };
+        let Ok(json) = serde_json::to_string(&event) else { continue };
+        if ws.send(Message::Text(json)).await.is_err() {
+            break
+        }
+    }
+}
@@ -1,2 +1,16 @@
+pub mod alerts;
 pub mod art;
 pub mod banner;
+#[cfg(feature = "cex-ingestion")]
+pub mod cex_backfill;
+pub mod config_reload;
+#[cfg(feature = "tui")]
+pub mod dashboard;
+#[cfg(feature = "server")]
+pub mod http;
+#[cfg(feature = "kafka")]
+pub mod kafka_sink;
+#[cfg(feature = "server")]
+pub mod live_stream;
+#[cfg(feature = "server")]
+pub mod query_api;
@@ -0,0 +1,260 @@
+//! Read api over the results the indexer has stored.
+//!
+//! - `GET /tokens/{address}` returns the metadata and spam assessment of the
+//!   token, `404` when it hasn't been scored
+//! - `GET /tokens?min_score=N` lists the tokens scored at least `N`, highest
+//!   first
+//! - `GET /receipts/{tx_hash}?block=N` returns the mev receipt of the
+//!   transaction with a single lookup. Without a receipt, the optional block
+//!   the transaction landed in tells a transaction that was neither from one
+//!   whose block wasn't processed
+//! - `GET /overrides?block=N` lists the manual overrides with their ids,
+//!   including revoked ones, optionally only those of block `N`
+//! - `POST /overrides` appends the [`OverrideRequest`] in the body and returns
+//!   its id. Needs the `BRONTES_API_TOKEN` as a bearer token, without one set
+//!   overrides can only be made from the cli
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+use alloy_primitives::{Address, B256};
+use brontes_types::{
+    db::{
+        overrides::{BundleOverride, OverrideKind},
+        traits::{DBWriter, LibmdbxReader},
+    },
+    mev::MevType,
+    BrontesTaskExecutor,
+};
+use eyre::{eyre, WrapErr};
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::http::{is_authorized, json_response, query_param, status_response};
+
+/// An override made through the api, the fields a kind doesn't use are
+/// ignored
+#[derive(Debug, Deserialize)]
+pub struct OverrideRequest {
+    pub kind:         OverrideKind,
+    #[serde(default)]
+    pub block_number: u64,
+    #[serde(default)]
+    pub tx_hash:      B256,
+    pub mev_type:     Option<MevType>,
+    pub searcher:     Option<Address>,
+    pub profit_usd:   Option<f64>,
+    /// Id of the override a revocation revokes
+    pub revokes:      Option<u64>,
+    pub author:       String,
+    pub reason:       String,
+}
+
+#[derive(Debug, Serialize)]
+struct OverrideEntry {
+    id:        u64,
+    #[serde(flatten)]
+    override_: BundleOverride,
+}
+
+#[derive(Debug, Serialize)]
+struct OverrideCreated {
+    id: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// `token` is the bearer token writes need, writes are refused without one
+pub fn spawn_query_api<DB: LibmdbxReader + DBWriter>(
+    addr: SocketAddr,
+    db: &'static DB,
+    token: Option<String>,
+    executor: &BrontesTaskExecutor,
+) -> eyre::Result<()> {
+    if token.is_none() {
+        tracing::info!("BRONTES_API_TOKEN isn't set, the query api refuses overrides");
+    }
+    let token = Arc::new(token);
+    let make_svc = make_service_fn(move |_| {
+        let token = token.clone();
+        let service = service_fn(move |req| {
+            let token = token.clone();
+            async move { Ok::<_, Infallible>(handle(req, db, token.as_deref()).await) }
+        });
+        async move { Ok::<_, Infallible>(service) }
+    });
+    let server = Server::try_bind(&addr)
+        .wrap_err("could not bind the query api")?
+        .serve(make_svc);
+
+    executor.spawn(async move {
+        if let Err(e) = server.await {
+            tracing::error!(err=%e, "query api stopped");
+        }
+    });
+    tracing::info!(%addr, "serving the query api");
+
+    Ok(())
+}
+
+async fn handle<DB: LibmdbxReader + DBWriter>(
+    req: Request<Body>,
+    db: &DB,
+    token: Option<&str>,
+) -> Response<Body> {
+    let (method, path) = (req.method().clone(), req.uri().path().to_string());
+    let segments = path.trim_matches('/').split('/').collect_vec();
+
+    match (&method, segments.as_slice()) {
+        (&Method::GET, ["tokens"]) => {
+            let min_score = match query_param(req.uri().query(), "min_score")
+                .map(str::parse::<u8>)
+                .transpose()
+            {
+                Ok(min_score) => min_score.unwrap_or_default(),
+                Err(_) => return status_response(StatusCode::BAD_REQUEST),
+            };
+            db_response(db.fetch_all_token_metadata().map(|tokens| {
+                tokens
+                    .into_iter()
+                    .filter(|(_, metadata)| metadata.spam.score >= min_score)
+                    .sorted_by(|a, b| b.1.spam.score.cmp(&a.1.spam.score))
+                    .collect_vec()
+            }))
+        }
+        (&Method::GET, ["tokens", token]) => {
+            let Ok(token) = token.parse::<Address>() else {
+                return status_response(StatusCode::BAD_REQUEST)
+            };
+            match db.try_fetch_token_metadata(token) {
+                Ok(Some(metadata)) => json_response(StatusCode::OK, &metadata),
+                Ok(None) => status_response(StatusCode::NOT_FOUND),
+                Err(e) => db_response::<()>(Err(e)),
+            }
+        }
+        (&Method::GET, ["receipts", tx_hash]) => {
+            let Ok(tx_hash) = tx_hash.parse::<B256>() else {
+                return status_response(StatusCode::BAD_REQUEST)
+            };
+            let Ok(block_number) = query_param(req.uri().query(), "block")
+                .map(str::parse::<u64>)
+                .transpose()
+            else {
+                return status_response(StatusCode::BAD_REQUEST)
+            };
+            db_response(db.fetch_mev_receipt(tx_hash, block_number))
+        }
+        (&Method::GET, ["overrides"]) => {
+            let Ok(block) = query_param(req.uri().query(), "block")
+                .map(str::parse::<u64>)
+                .transpose()
+            else {
+                return status_response(StatusCode::BAD_REQUEST)
+            };
+            db_response(db.fetch_bundle_overrides().map(|overrides| {
+                overrides
+                    .into_iter()
+                    .filter(|(_, o)| block.map_or(true, |block| o.block_number == block))
+                    .map(|(id, override_)| OverrideEntry { id, override_ })
+                    .collect_vec()
+            }))
+        }
+        (&Method::POST, ["overrides"]) => {
+            if !token.is_some_and(|token| is_authorized(&req, token)) {
+                return status_response(StatusCode::UNAUTHORIZED)
+            }
+            let Ok(body) = hyper::body::to_bytes(req.into_body()).await else {
+                return status_response(StatusCode::BAD_REQUEST)
+            };
+            let bundle_override = match serde_json::from_slice::<OverrideRequest>(&body)
+                .map_err(Into::into)
+                .and_then(|request| new_override(request, db))
+            {
+                Ok(bundle_override) => bundle_override,
+                Err(e) => {
+                    return json_response(
+                        StatusCode::BAD_REQUEST,
+                        &ErrorBody { error: e.to_string() },
+                    )
+                }
+            };
+            db_response(
+                db.append_bundle_override(bundle_override)
+                    .await
+                    .map(|id| OverrideCreated { id }),
+            )
+        }
+        _ => status_response(StatusCode::NOT_FOUND),
+    }
+}
+
+fn new_override<DB: LibmdbxReader>(
+    request: OverrideRequest,
+    db: &DB,
+) -> eyre::Result<BundleOverride> {
+    let OverrideRequest {
+        kind,
+        block_number,
+        tx_hash,
+        mev_type,
+        searcher,
+        profit_usd,
+        revokes,
+        author,
+        reason,
+    } = request;
+    let missing = |field: &str| eyre!("a {} override needs a {field}", kind.as_ref());
+
+    Ok(match kind {
+        OverrideKind::FalsePositive => {
+            BundleOverride::false_positive(block_number, tx_hash, author, reason)
+        }
+        OverrideKind::FalseNegative => BundleOverride::false_negative(
+            block_number,
+            tx_hash,
+            mev_type.ok_or_else(|| missing("mev_type"))?,
+            searcher.ok_or_else(|| missing("searcher"))?,
+            profit_usd.unwrap_or_default(),
+            author,
+            reason,
+        ),
+        OverrideKind::Reclassify => BundleOverride::reclassify(
+            block_number,
+            tx_hash,
+            mev_type.ok_or_else(|| missing("mev_type"))?,
+            author,
+            reason,
+        ),
+        OverrideKind::Recluster => BundleOverride::recluster(
+            block_number,
+            tx_hash,
+            searcher.ok_or_else(|| missing("searcher"))?,
+            author,
+            reason,
+        ),
+        OverrideKind::Revoke => {
+            let id = revokes.ok_or_else(|| missing("revokes"))?;
+            let (_, revoked) = db
+                .fetch_bundle_overrides()?
+                .into_iter()
+                .find(|(override_id, _)| *override_id == id)
+                .ok_or_else(|| eyre!("no override with id {id}"))?;
+            BundleOverride::revoke(id, &revoked, author, reason)
+        }
+    })
+}
+
+fn db_response<T: serde::Serialize>(res: eyre::Result<T>) -> Response<Body> {
+    match res {
+        Ok(value) => json_response(StatusCode::OK, &value),
+        Err(e) => {
+            tracing::warn!(err=%e, "query api request failed");
+            status_response(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
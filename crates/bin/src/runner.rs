@@ -45,6 +45,40 @@ where
     Ok(())
 }
 
+/// Runs the command for a host process that owns the process wide state, such
+/// as the python bindings. Unlike [`run_command_until_exit`] no panic hook or
+/// signal handlers are installed, the command runs until it's done, a task
+/// panics or `interrupt` resolves.
+pub fn run_command_embedded<F, E>(
+    shutdown_time: Duration,
+    interrupt: impl Future<Output = ()>,
+    command: impl FnOnce(CliContext) -> F,
+) -> Result<(), E>
+where
+    F: Future<Output = Result<(), E>>,
+    E: Send + Sync + From<std::io::Error> + From<brontes_types::PanickedTaskError> + 'static,
+{
+    let tokio_runtime = tokio_runtime()?;
+    let task_manager = BrontesTaskManager::new(tokio_runtime.handle().clone(), true);
+    let context = CliContext { task_executor: task_manager.executor() };
+
+    let fut = command(context);
+    let task_manager = tokio_runtime.block_on(run_to_completion_or_panic(task_manager, async {
+        pin_mut!(fut, interrupt);
+        tokio::select! {
+            _ = interrupt => {
+                trace!(target: "brontes::cli", "interrupted by the host");
+                Ok(())
+            },
+            res = fut => res,
+        }
+    }))?;
+    task_manager.graceful_shutdown_with_timeout(shutdown_time);
+    std::thread::spawn(move || drop(tokio_runtime));
+
+    Ok(())
+}
+
 /// Creates a new default tokio multi-thread [Runtime](tokio::runtime::Runtime)
 /// with all features enabled
 pub fn tokio_runtime() -> Result<tokio::runtime::Runtime, std::io::Error> {
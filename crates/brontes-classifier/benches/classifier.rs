@@ -1,7 +1,9 @@
 use brontes_classifier::test_utils::ClassifierBenchUtils;
 use criterion::{criterion_group, criterion_main, Criterion};
+use fixture_blocks::fixture_blocks;
 use tree_operations::tree_operations;
 
+mod fixture_blocks;
 mod tree_operations;
 
 fn bench_tree_building(c: &mut Criterion) {
@@ -12,4 +14,4 @@ fn bench_tree_building(c: &mut Criterion) {
 }
 
 criterion_group!(tree, bench_tree_building);
-criterion_main!(tree, tree_operations);
+criterion_main!(tree, tree_operations, fixture_blocks);
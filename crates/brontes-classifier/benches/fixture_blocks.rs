@@ -0,0 +1,92 @@
+use brontes_classifier::test_utils::{ClassifierBenchUtils, BENCH_FIXTURE_BLOCKS};
+use brontes_types::{normalized_actions::Action, TreeSearchBuilder};
+use criterion::{criterion_group, Criterion};
+
+fn bench_fixture_tree_building(c: &mut Criterion) {
+    let utils = ClassifierBenchUtils::new();
+    for fixture in BENCH_FIXTURE_BLOCKS {
+        utils
+            .bench_block_tree_building(&format!("build {} tree", fixture.name), fixture.block, c)
+            .unwrap();
+    }
+}
+
+fn bench_fixture_tree_finalize(c: &mut Criterion) {
+    let utils = ClassifierBenchUtils::new();
+    for fixture in BENCH_FIXTURE_BLOCKS {
+        utils
+            .bench_tree_finalize(&format!("finalize {} tree", fixture.name), fixture.block, c)
+            .unwrap();
+    }
+}
+
+fn bench_fixture_collect_all(c: &mut Criterion) {
+    let utils = ClassifierBenchUtils::new();
+    for fixture in BENCH_FIXTURE_BLOCKS {
+        // the searches the inspectors run over every block
+        utils
+            .bench_tree_operations(
+                &format!("collect transfers {}", fixture.name),
+                fixture.block,
+                c,
+                |tree| {
+                    tree.collect_all(
+                        TreeSearchBuilder::default()
+                            .with_actions([Action::is_transfer, Action::is_eth_transfer]),
+                    );
+                },
+            )
+            .unwrap();
+        utils
+            .bench_tree_operations(
+                &format!("collect swaps {}", fixture.name),
+                fixture.block,
+                c,
+                |tree| {
+                    tree.collect_all(TreeSearchBuilder::default().with_actions([
+                        Action::is_swap,
+                        Action::is_fill,
+                        Action::is_transfer,
+                        Action::is_eth_transfer,
+                        Action::is_aggregator,
+                    ]));
+                },
+            )
+            .unwrap();
+        utils
+            .bench_tree_operations(
+                &format!("collect liquidity {}", fixture.name),
+                fixture.block,
+                c,
+                |tree| {
+                    tree.collect_all(TreeSearchBuilder::default().with_actions([
+                        Action::is_mint,
+                        Action::is_burn,
+                        Action::is_transfer,
+                        Action::is_eth_transfer,
+                    ]));
+                },
+            )
+            .unwrap();
+        utils
+            .bench_tree_operations(
+                &format!("collect nested swaps {}", fixture.name),
+                fixture.block,
+                c,
+                |tree| {
+                    tree.collect_all(
+                        TreeSearchBuilder::default()
+                            .with_actions([Action::is_swap, Action::is_nested_action]),
+                    );
+                },
+            )
+            .unwrap();
+    }
+}
+
+criterion_group!(
+    fixture_blocks,
+    bench_fixture_tree_building,
+    bench_fixture_tree_finalize,
+    bench_fixture_collect_all
+);
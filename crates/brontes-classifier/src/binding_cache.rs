@@ -0,0 +1,126 @@
+//! Cache of the protocols addresses are bound to.
+//!
+//! Every call frame of a block is dispatched on the protocol of the address it
+//! calls, so classifying a block takes a pool registry lookup per frame. Made
+//! against the db, each of them opens its own read transaction, which the
+//! classification threads end up contending on. [`ProtocolBindings`] keeps the
+//! pools found in a sharded map shared by the classifiers of a run.
+//!
+//! Only addresses bound to a protocol are kept, an address without one is
+//! looked up again on its next call, as it may have become a pool since. The
+//! cache is prewarmed with the pools created before the range being processed,
+//! and the pools the classifier discovers are bound as they're inserted.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use alloy_primitives::Address;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_metrics::classifier::BindingCacheMetrics;
+use brontes_types::Protocol;
+use dashmap::DashMap;
+
+#[derive(Default)]
+pub struct ProtocolBindings {
+    bindings: DashMap<Address, Protocol>,
+    /// Number of addresses cached, kept apart as counting the map locks all
+    /// of its shards
+    entries:  AtomicUsize,
+    metrics:  Option<BindingCacheMetrics>,
+}
+
+impl ProtocolBindings {
+    pub fn new(metrics: Option<BindingCacheMetrics>) -> Self {
+        Self { metrics, ..Default::default() }
+    }
+
+    /// Protocol of the address, looked up in the db on a miss
+    pub fn protocol<DB: LibmdbxReader>(&self, db: &DB, address: Address) -> Option<Protocol> {
+        if let Some(protocol) = self.bindings.get(&address) {
+            if let Some(metrics) = &self.metrics {
+                metrics.hit();
+            }
+            return Some(*protocol)
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.miss();
+        }
+
+        let protocol = db.get_protocol(address).ok()?;
+        self.bind(address, protocol);
+
+        Some(protocol)
+    }
+
+    /// Binds a pool the classifier inserted into the registry
+    pub fn bind(&self, address: Address, protocol: Protocol) {
+        self.insert_binding(address, protocol);
+        self.update_entries();
+    }
+
+    /// Binds the pools of the registry ahead of classification
+    pub fn prewarm(&self, pools: impl IntoIterator<Item = (Address, Protocol)>) {
+        pools
+            .into_iter()
+            .for_each(|(address, protocol)| self.insert_binding(address, protocol));
+        self.update_entries();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert_binding(&self, address: Address, protocol: Protocol) {
+        if self.bindings.insert(address, protocol).is_none() {
+            self.entries.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn update_entries(&self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.set_entries(self.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_database::{in_memory::InMemoryDb, libmdbx::DBWriter};
+    use brontes_types::constants::WETH_ADDRESS;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_protocol_bindings() {
+        let db = InMemoryDb::new();
+        let (pool, token, created, inserted) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            Address::repeat_byte(4),
+        );
+        db.insert_pool(10, pool, &[token, WETH_ADDRESS], None, Protocol::UniswapV2)
+            .await
+            .unwrap();
+
+        let bindings = ProtocolBindings::default();
+        assert_eq!(bindings.protocol(&db, pool), Some(Protocol::UniswapV2));
+        assert_eq!(bindings.protocol(&db, token), None);
+        assert_eq!(bindings.protocol(&db, created), None);
+        assert_eq!(bindings.protocol(&db, inserted), None);
+
+        bindings.bind(created, Protocol::UniswapV3);
+        assert_eq!(bindings.protocol(&db, created), Some(Protocol::UniswapV3));
+
+        // a pool inserted without going through the cache is found once it's in
+        // the registry
+        db.insert_pool(11, inserted, &[token, WETH_ADDRESS], None, Protocol::UniswapV2)
+            .await
+            .unwrap();
+        assert_eq!(bindings.protocol(&db, inserted), Some(Protocol::UniswapV2));
+
+        assert_eq!(bindings.len(), 3);
+    }
+}
@@ -150,7 +150,8 @@ action_impl!(
             recipient: call_data.recipient,
             pool: pool_id_to_address(call_data.poolId),
             token: tokens,
-            amount: amounts
+            amount: amounts,
+            position_id: None,
         })
     }
 );
@@ -173,7 +174,8 @@ action_impl!(
             recipient: call_data.recipient,
             pool: pool_id_to_address(call_data.poolId),
             token: tokens,
-            amount: amounts
+            amount: amounts,
+            position_id: None,
         })
     }
 );
@@ -359,6 +361,7 @@ mod tests {
             amount:      vec![U256::from_str("1935117712922949743")
                 .unwrap()
                 .to_scaled_rational(18)],
+            position_id: None,
         });
 
         classifier_utils
@@ -424,6 +427,7 @@ mod tests {
                     .unwrap()
                     .to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
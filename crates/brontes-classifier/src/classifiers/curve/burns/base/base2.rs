@@ -40,6 +40,7 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -80,6 +81,7 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -123,6 +125,7 @@ action_impl!(
             recipient: info.from_address,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
@@ -42,6 +42,7 @@ action_impl!(
             recipient: info.from_address,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
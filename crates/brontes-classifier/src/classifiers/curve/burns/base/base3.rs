@@ -38,6 +38,7 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -76,6 +77,7 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -119,6 +121,7 @@ action_impl!(
             recipient: info.from_address,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
@@ -39,6 +39,7 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -77,6 +78,7 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -69,6 +69,7 @@ mod tests {
                 U256::from(253273).to_scaled_rational(8),
                 U256::from(2022770990903219_u128).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -129,6 +130,7 @@ mod tests {
                 U256::from(50000000).to_scaled_rational(8),
                 U256::from(0).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -174,6 +176,7 @@ mod tests {
             pool:        Address::new(hex!("7fC77b5c7614E1533320Ea6DDc2Eb61fa00A9714")),
             token:       vec![token],
             amount:      vec![U256::from(38855798316741927_u128).to_scaled_rational(8)],
+            position_id: None,
         });
 
         classifier_utils
@@ -41,6 +41,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -82,6 +83,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -122,6 +124,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -163,6 +166,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -204,6 +208,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
@@ -249,6 +254,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
@@ -308,6 +314,7 @@ mod tests {
                 U256::from(125377210391915440945_u128).to_scaled_rational(18),
                 U256::from(2121542034308448729_u128).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -362,6 +369,7 @@ mod tests {
                 U256::from(5782689815360000000000_u128).to_scaled_rational(18),
                 U256::from(60598295710000000000_u128).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -407,6 +415,7 @@ mod tests {
             pool:        Address::new(hex!("A77d09743F77052950C4eb4e6547E9665299BecD")),
             token:       vec![token],
             amount:      vec![U256::from(1976026334539568105482_u128).to_scaled_rational(18)],
+            position_id: None,
         });
 
         classifier_utils
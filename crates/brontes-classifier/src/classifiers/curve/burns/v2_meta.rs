@@ -41,6 +41,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -82,6 +83,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -123,6 +125,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -164,6 +167,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -205,6 +209,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
@@ -250,6 +255,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
@@ -309,6 +315,7 @@ mod tests {
                 U256::from(627992358239302043763875_u128).to_scaled_rational(18),
                 U256::from(579890756974932941933194_u128).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -354,6 +361,7 @@ mod tests {
             pool:        Address::new(hex!("892D701d94a43bDBCB5eA28891DaCA2Fa22A690b")),
             token:       vec![token],
             amount:      vec![U256::from(183708410783845567136_u128).to_scaled_rational(18)],
+            position_id: None,
         });
 
         classifier_utils
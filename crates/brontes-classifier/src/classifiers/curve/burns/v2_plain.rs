@@ -40,6 +40,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -80,6 +81,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -120,6 +122,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -160,6 +163,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
@@ -204,6 +208,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
@@ -249,6 +254,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: vec![token_info],
             amount: vec![amt],
+            position_id: None,
         })
 
     }
@@ -308,6 +314,7 @@ mod tests {
                 U256::from(7558238951551444616838_u128).to_scaled_rational(18),
                 U256::from(33415347097773187822792_u128).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -362,6 +369,7 @@ mod tests {
                 U256::from(827904920210000000000000_u128).to_scaled_rational(18),
                 U256::from(332024620000000000000000_u128).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -407,6 +415,7 @@ mod tests {
             pool:        Address::new(hex!("9D0464996170c6B9e75eED71c68B99dDEDf279e8")),
             token:       vec![token],
             amount:      vec![U256::from(915720089431618525538_u128).to_scaled_rational(18)],
+            position_id: None,
         });
 
         classifier_utils
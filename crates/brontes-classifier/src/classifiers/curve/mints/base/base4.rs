@@ -37,6 +37,7 @@ action_impl!(
             recipient: info.from_address,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
 
     }
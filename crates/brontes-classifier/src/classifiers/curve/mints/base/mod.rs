@@ -65,6 +65,7 @@ mod tests {
                 U256::from(27506).to_scaled_rational(8),
                 U256::from(0).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -39,6 +39,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
     }
 );
@@ -79,6 +80,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
     }
 );
@@ -139,6 +141,7 @@ mod tests {
                 U256::from(0_u128).to_scaled_rational(18),
                 U256::from(100000000000000000000_u128).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
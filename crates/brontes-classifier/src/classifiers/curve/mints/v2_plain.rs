@@ -37,6 +37,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
     }
 );
@@ -74,6 +75,7 @@ action_impl!(
             recipient: info.msg_sender,
             token: tokens,
             amount: token_amts,
+            position_id: None,
         })
     }
 );
@@ -132,6 +134,7 @@ mod tests {
                 U256::from(2503890709681717311281_u128).to_scaled_rational(18),
                 U256::from(798080784008874713734_u128).to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -141,7 +141,8 @@ action_impl!(
             recipient: call_data.to,
             pool: info.target_address,
             token,
-            amount
+            amount,
+            position_id: None,
         })
     }
 );
@@ -181,7 +182,8 @@ action_impl!(
             recipient: call_data.to,
             pool: info.target_address,
             token,
-            amount
+            amount,
+            position_id: None,
         })
     }
 );
@@ -243,6 +245,7 @@ mod tests {
                     .unwrap()
                     .to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -298,6 +301,7 @@ mod tests {
                     .unwrap()
                     .to_scaled_rational(18),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -0,0 +1,34 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    constants::CBETH_ADDRESS, normalized_actions::NormalizedMint, structured_trace::CallInfo,
+    Protocol, ToScaledRational,
+};
+
+// cbETH is minted by Coinbase for ETH staked with it off chain, so a deposit
+// has no ETH leg on chain. It's classified as the minter issuing the cbETH to
+// the depositor.
+action_impl!(
+    Protocol::CoinbaseCbEth,
+    crate::CoinbaseCbEth::mintCall,
+    Mint,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: mintCall,
+    db_tx: &DB| {
+        let cbeth_info = db_tx.try_fetch_token_info(CBETH_ADDRESS)?;
+        let amount = call_data._amount.to_scaled_rational(cbeth_info.decimals);
+
+        Ok(NormalizedMint {
+            protocol: Protocol::CoinbaseCbEth,
+            trace_index: info.trace_idx,
+            from: info.msg_sender,
+            recipient: call_data._to,
+            pool: info.target_address,
+            token: vec![cbeth_info],
+            amount: vec![amount],
+            position_id: None,
+        })
+    }
+);
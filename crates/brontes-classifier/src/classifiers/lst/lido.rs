@@ -0,0 +1,136 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    constants::{ETH_ADDRESS, STETH_ADDRESS, WSTETH_ADDRESS},
+    normalized_actions::NormalizedSwap,
+    structured_trace::CallInfo,
+    Protocol, ToScaledRational,
+};
+
+action_impl!(
+    Protocol::LidoStEth,
+    crate::LidoStEth::submitCall,
+    Swap,
+    [],
+    |info: CallInfo, db_tx: &DB| {
+        let eth_info = db_tx.try_fetch_token_info(ETH_ADDRESS)?;
+        let steth_info = db_tx.try_fetch_token_info(STETH_ADDRESS)?;
+
+        // stETH is minted 1:1 for the staked ETH, the return value is in shares
+        let amount_in = info.msg_value.to_scaled_rational(eth_info.decimals);
+        let amount_out = amount_in.clone();
+
+        Ok(NormalizedSwap {
+            protocol: Protocol::LidoStEth,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: info.from_address,
+            pool: info.target_address,
+            token_in: eth_info,
+            token_out: steth_info,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::LidoWstEth,
+    crate::LidoWstEth::wrapCall,
+    Swap,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: wrapCall,
+    return_data: wrapReturn,
+    db_tx: &DB| {
+        let steth_info = db_tx.try_fetch_token_info(STETH_ADDRESS)?;
+        let wsteth_info = db_tx.try_fetch_token_info(WSTETH_ADDRESS)?;
+
+        let amount_in = call_data._stETHAmount.to_scaled_rational(steth_info.decimals);
+        let amount_out = return_data._0.to_scaled_rational(wsteth_info.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: Protocol::LidoWstEth,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: info.from_address,
+            pool: info.target_address,
+            token_in: steth_info,
+            token_out: wsteth_info,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::LidoWstEth,
+    crate::LidoWstEth::unwrapCall,
+    Swap,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: unwrapCall,
+    return_data: unwrapReturn,
+    db_tx: &DB| {
+        let wsteth_info = db_tx.try_fetch_token_info(WSTETH_ADDRESS)?;
+        let steth_info = db_tx.try_fetch_token_info(STETH_ADDRESS)?;
+
+        let amount_in = call_data._wstETHAmount.to_scaled_rational(wsteth_info.decimals);
+        let amount_out = return_data._0.to_scaled_rational(steth_info.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: Protocol::LidoWstEth,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: info.from_address,
+            pool: info.target_address,
+            token_in: wsteth_info,
+            token_out: steth_info,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::LidoWithdrawalQueue,
+    crate::LidoWithdrawalQueue::claimWithdrawalCall,
+    Swap,
+    [WithdrawalClaimed],
+    logs: true,
+    |
+    info: CallInfo,
+    log_data: LidoWithdrawalQueueClaimWithdrawalCallLogs,
+    db_tx: &DB| {
+        let claimed = log_data.withdrawal_claimed_field?;
+
+        let steth_info = db_tx.try_fetch_token_info(STETH_ADDRESS)?;
+        let eth_info = db_tx.try_fetch_token_info(ETH_ADDRESS)?;
+
+        // the stETH was locked in the queue when the withdrawal was requested and
+        // is redeemed 1:1, the claim pays out the ETH
+        let amount_out = claimed.amountOfETH.to_scaled_rational(eth_info.decimals);
+        let amount_in = amount_out.clone();
+
+        Ok(NormalizedSwap {
+            protocol: Protocol::LidoWithdrawalQueue,
+            trace_index: info.trace_idx,
+            from: claimed.owner,
+            recipient: claimed.receiver,
+            pool: info.target_address,
+            token_in: steth_info,
+            token_out: eth_info,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+        })
+    }
+);
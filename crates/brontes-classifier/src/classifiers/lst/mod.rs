@@ -0,0 +1,9 @@
+#[allow(non_snake_case)]
+mod lido;
+pub use lido::*;
+
+mod rocket_pool;
+pub use rocket_pool::*;
+
+mod coinbase;
+pub use coinbase::*;
@@ -0,0 +1,78 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    constants::{ETH_ADDRESS, RETH_ADDRESS},
+    normalized_actions::NormalizedSwap,
+    structured_trace::CallInfo,
+    Protocol, ToScaledRational,
+};
+
+action_impl!(
+    Protocol::RocketPool,
+    crate::RocketPoolRETH::burnCall,
+    Swap,
+    [TokensBurned],
+    call_data: true,
+    logs: true,
+    |
+    info: CallInfo,
+    call_data: burnCall,
+    log_data: RocketPoolBurnCallLogs,
+    db_tx: &DB| {
+        let burned = log_data.tokens_burned_field?;
+
+        let reth_info = db_tx.try_fetch_token_info(RETH_ADDRESS)?;
+        let eth_info = db_tx.try_fetch_token_info(ETH_ADDRESS)?;
+
+        // rETH is redeemed at the rate of the pool, the log has the ETH paid out
+        let amount_in = call_data._rethAmount.to_scaled_rational(reth_info.decimals);
+        let amount_out = burned.ethAmount.to_scaled_rational(eth_info.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: Protocol::RocketPool,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: burned.from,
+            pool: info.target_address,
+            token_in: reth_info,
+            token_out: eth_info,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::RocketPool,
+    crate::RocketPoolRETH::mintCall,
+    Swap,
+    [TokensMinted],
+    logs: true,
+    |
+    info: CallInfo,
+    log_data: RocketPoolMintCallLogs,
+    db_tx: &DB| {
+        let minted = log_data.tokens_minted_field?;
+
+        let eth_info = db_tx.try_fetch_token_info(ETH_ADDRESS)?;
+        let reth_info = db_tx.try_fetch_token_info(RETH_ADDRESS)?;
+
+        // the deposit pool mints the rETH once it has taken the deposit, the log
+        // has the ETH deposited and the rETH minted for it
+        let amount_in = minted.ethAmount.to_scaled_rational(eth_info.decimals);
+        let amount_out = minted.amount.to_scaled_rational(reth_info.decimals);
+
+        Ok(NormalizedSwap {
+            protocol: Protocol::RocketPool,
+            trace_index: info.trace_idx,
+            from: minted.to,
+            recipient: minted.to,
+            pool: info.target_address,
+            token_in: eth_info,
+            token_out: reth_info,
+            amount_in,
+            amount_out,
+            msg_value: info.msg_value,
+        })
+    }
+);
@@ -29,6 +29,12 @@ pub use maker::*;
 pub mod zerox;
 pub use zerox::*;
 
+pub mod lst;
+pub use lst::*;
+
+pub mod vault;
+pub use vault::*;
+
 pub mod cowswap;
 pub use cowswap::*;
 
@@ -81,6 +87,11 @@ action_dispatch!(
     UniswapV3MintCall,
     UniswapV3BurnCall,
     UniswapV3CollectCall,
+    UniswapV3CollectProtocolCall,
+    UniswapV3PositionManagerMintCall,
+    UniswapV3PositionManagerIncreaseLiquidityCall,
+    UniswapV3PositionManagerDecreaseLiquidityCall,
+    UniswapV3PositionManagerCollectCall,
     SushiSwapV3SwapCall,
     SushiSwapV3MintCall,
     SushiSwapV3BurnCall,
@@ -170,6 +181,13 @@ action_dispatch!(
     OneInchV5UnoswapToWithPermitCall,
     OneInchV5UniswapV3SwapToCall,
     OneInchV5UniswapV3SwapToWithPermitCall,
+    OneInchV5FillOrderCall,
+    OneInchV5FillOrderToCall,
+    OneInchV5FillOrderToWithPermitCall,
+    OneInchV5FillOrderRFQCall,
+    OneInchV5FillOrderRFQToCall,
+    OneInchV5FillOrderRFQToWithPermitCall,
+    OneInchV5FillOrderRFQCompactCall,
     OneInchFusionSettleOrdersCall,
     ClipperExchangeSwapCall,
     ClipperExchangeSellEthForTokenCall,
@@ -208,5 +226,16 @@ action_dispatch!(
     DodoSellSharesCall,
     DodoSellBaseCall,
     DodoSellQuoteCall,
-    DodoFlashLoanCall
+    DodoFlashLoanCall,
+    LidoStEthSubmitCall,
+    LidoWstEthWrapCall,
+    LidoWstEthUnwrapCall,
+    LidoWithdrawalQueueClaimWithdrawalCall,
+    RocketPoolBurnCall,
+    RocketPoolMintCall,
+    CoinbaseCbEthMintCall,
+    ERC4626VaultDepositCall,
+    ERC4626VaultMintCall,
+    ERC4626VaultWithdrawCall,
+    ERC4626VaultRedeemCall
 );
@@ -1,6 +1,10 @@
 use brontes_macros::action_impl;
 use brontes_pricing::Protocol;
-use brontes_types::{normalized_actions::NormalizedAggregator, structured_trace::CallInfo};
+use brontes_types::{
+    normalized_actions::{NormalizedAggregator, NormalizedFill},
+    structured_trace::CallInfo,
+    ToScaledRational,
+};
 
 action_impl!(
     Protocol::OneInchV5,
@@ -20,29 +24,8 @@ action_impl!(
             to: info.target_address
                 , recipient: dst_receiver,
             child_actions: vec![],
-            msg_value: info.msg_value
-        })
-    }
-);
-
-action_impl!(
-    Protocol::OneInchV5,
-    crate::OneInchAggregationRouterV5::fillOrderToCall,
-    Aggregator,
-    [..OrderFilled],
-    call_data: true,
-    |
-    info: CallInfo,
-    call_data: fillOrderToCall,
-    _db_tx: &DB | {
-        let recipient = call_data.order_.receiver;
-        return Ok(NormalizedAggregator {
-            protocol: Protocol::OneInchV5,
-            trace_index: info.trace_idx,
-            from: info.from_address,
-            to: info.target_address,
-            recipient,
-            child_actions: vec![],
+            min_amount_out: Some(call_data.desc.minReturnAmount),
+            referrer: info.referrer,
             msg_value: info.msg_value
         })
     }
@@ -55,13 +38,15 @@ action_impl!(
     [],
     |info: CallInfo, _db_tx: &DB| {
         return Ok(NormalizedAggregator {
-            protocol:      Protocol::OneInchV5,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::OneInchV5,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: None,
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -84,6 +69,8 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.outputAmount),
+            referrer: info.referrer,
             msg_value: info.msg_value
         })
     }
@@ -107,6 +94,8 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.minReturn),
+            referrer: info.referrer,
             msg_value: info.msg_value
         })
     }
@@ -130,6 +119,8 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.minReturn),
+            referrer: info.referrer,
             msg_value: info.msg_value
         })
     }
@@ -153,6 +144,8 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.minReturn),
+            referrer: info.referrer,
             msg_value: info.msg_value
         })
     }
@@ -176,6 +169,8 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.minReturn),
+            referrer: info.referrer,
             msg_value: info.msg_value
         })
     }
@@ -199,11 +194,261 @@ action_impl!(
             to: info.target_address,
             recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.outputAmount),
+            referrer: info.referrer,
             msg_value: info.msg_value
         })
     }
 );
 
+// Limit order fills, classified as fills of the order's maker by the caller
+action_impl!(
+    Protocol::OneInchV5,
+    crate::OneInchAggregationRouterV5::fillOrderCall,
+    Fill,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: fillOrderCall,
+    return_data: fillOrderReturn,
+    db_tx: &DB| {
+        let maker_token = db_tx.try_fetch_token_info(call_data.order.makerAsset)?;
+        let taker_token = db_tx.try_fetch_token_info(call_data.order.takerAsset)?;
+        let recipient = if call_data.order.receiver.is_zero() {
+            call_data.order.maker
+        } else {
+            call_data.order.receiver
+        };
+
+        Ok(NormalizedFill {
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            order_hash: return_data._2,
+            maker: call_data.order.maker,
+            recipient,
+            taker: info.msg_sender,
+            settlement: info.target_address,
+            maker_amount: return_data._0.to_scaled_rational(maker_token.decimals),
+            taker_amount: return_data._1.to_scaled_rational(taker_token.decimals),
+            maker_token,
+            taker_token,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV5,
+    crate::OneInchAggregationRouterV5::fillOrderToCall,
+    Fill,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: fillOrderToCall,
+    return_data: fillOrderToReturn,
+    db_tx: &DB| {
+        let maker_token = db_tx.try_fetch_token_info(call_data.order_.makerAsset)?;
+        let taker_token = db_tx.try_fetch_token_info(call_data.order_.takerAsset)?;
+        let recipient = if call_data.order_.receiver.is_zero() {
+            call_data.order_.maker
+        } else {
+            call_data.order_.receiver
+        };
+
+        Ok(NormalizedFill {
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            order_hash: return_data.orderHash,
+            maker: call_data.order_.maker,
+            recipient,
+            taker: info.msg_sender,
+            settlement: info.target_address,
+            maker_amount: return_data.actualMakingAmount.to_scaled_rational(maker_token.decimals),
+            taker_amount: return_data.actualTakingAmount.to_scaled_rational(taker_token.decimals),
+            maker_token,
+            taker_token,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV5,
+    crate::OneInchAggregationRouterV5::fillOrderToWithPermitCall,
+    Fill,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: fillOrderToWithPermitCall,
+    return_data: fillOrderToWithPermitReturn,
+    db_tx: &DB| {
+        let maker_token = db_tx.try_fetch_token_info(call_data.order.makerAsset)?;
+        let taker_token = db_tx.try_fetch_token_info(call_data.order.takerAsset)?;
+        let recipient = if call_data.order.receiver.is_zero() {
+            call_data.order.maker
+        } else {
+            call_data.order.receiver
+        };
+
+        Ok(NormalizedFill {
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            order_hash: return_data._2,
+            maker: call_data.order.maker,
+            recipient,
+            taker: info.msg_sender,
+            settlement: info.target_address,
+            maker_amount: return_data._0.to_scaled_rational(maker_token.decimals),
+            taker_amount: return_data._1.to_scaled_rational(taker_token.decimals),
+            maker_token,
+            taker_token,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV5,
+    crate::OneInchAggregationRouterV5::fillOrderRFQCall,
+    Fill,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: fillOrderRFQCall,
+    return_data: fillOrderRFQReturn,
+    db_tx: &DB| {
+        let maker_token = db_tx.try_fetch_token_info(call_data.order.makerAsset)?;
+        let taker_token = db_tx.try_fetch_token_info(call_data.order.takerAsset)?;
+        // rfq orders are always paid out to the maker
+        let recipient = call_data.order.maker;
+
+        Ok(NormalizedFill {
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            order_hash: return_data._2,
+            maker: call_data.order.maker,
+            recipient,
+            taker: info.msg_sender,
+            settlement: info.target_address,
+            maker_amount: return_data._0.to_scaled_rational(maker_token.decimals),
+            taker_amount: return_data._1.to_scaled_rational(taker_token.decimals),
+            maker_token,
+            taker_token,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV5,
+    crate::OneInchAggregationRouterV5::fillOrderRFQToCall,
+    Fill,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: fillOrderRFQToCall,
+    return_data: fillOrderRFQToReturn,
+    db_tx: &DB| {
+        let maker_token = db_tx.try_fetch_token_info(call_data.order.makerAsset)?;
+        let taker_token = db_tx.try_fetch_token_info(call_data.order.takerAsset)?;
+        // rfq orders are always paid out to the maker
+        let recipient = call_data.order.maker;
+
+        Ok(NormalizedFill {
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            order_hash: return_data.orderHash,
+            maker: call_data.order.maker,
+            recipient,
+            taker: info.msg_sender,
+            settlement: info.target_address,
+            maker_amount: return_data.filledMakingAmount.to_scaled_rational(maker_token.decimals),
+            taker_amount: return_data.filledTakingAmount.to_scaled_rational(taker_token.decimals),
+            maker_token,
+            taker_token,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV5,
+    crate::OneInchAggregationRouterV5::fillOrderRFQToWithPermitCall,
+    Fill,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: fillOrderRFQToWithPermitCall,
+    return_data: fillOrderRFQToWithPermitReturn,
+    db_tx: &DB| {
+        let maker_token = db_tx.try_fetch_token_info(call_data.order.makerAsset)?;
+        let taker_token = db_tx.try_fetch_token_info(call_data.order.takerAsset)?;
+        // rfq orders are always paid out to the maker
+        let recipient = call_data.order.maker;
+
+        Ok(NormalizedFill {
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            order_hash: return_data._2,
+            maker: call_data.order.maker,
+            recipient,
+            taker: info.msg_sender,
+            settlement: info.target_address,
+            maker_amount: return_data._0.to_scaled_rational(maker_token.decimals),
+            taker_amount: return_data._1.to_scaled_rational(taker_token.decimals),
+            maker_token,
+            taker_token,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::OneInchV5,
+    crate::OneInchAggregationRouterV5::fillOrderRFQCompactCall,
+    Fill,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: fillOrderRFQCompactCall,
+    return_data: fillOrderRFQCompactReturn,
+    db_tx: &DB| {
+        let maker_token = db_tx.try_fetch_token_info(call_data.order.makerAsset)?;
+        let taker_token = db_tx.try_fetch_token_info(call_data.order.takerAsset)?;
+        // rfq orders are always paid out to the maker
+        let recipient = call_data.order.maker;
+
+        Ok(NormalizedFill {
+            protocol: Protocol::OneInchV5,
+            trace_index: info.trace_idx,
+            order_hash: return_data.orderHash,
+            maker: call_data.order.maker,
+            recipient,
+            taker: info.msg_sender,
+            settlement: info.target_address,
+            maker_amount: return_data.filledMakingAmount.to_scaled_rational(maker_token.decimals),
+            taker_amount: return_data.filledTakingAmount.to_scaled_rational(taker_token.decimals),
+            maker_token,
+            taker_token,
+            msg_value: info.msg_value,
+        })
+    }
+);
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -227,12 +472,12 @@ mod tests {
             B256::from(hex!("68603b7dce39738bc7aa9ce1cce39992965820ae39388a6d62db8d2db70132bb"));
 
         let eq_action = Action::Aggregator(NormalizedAggregator {
-            protocol:      OneInchV5,
-            trace_index:   0,
-            from:          Address::new(hex!("f4F8845ceDe63e79De1B2c3bbA395e8547FE4283")),
-            to:            Address::new(hex!("1111111254eeb25477b68fb85ed929f73a960582")),
-            recipient:     Address::new(hex!("f4F8845ceDe63e79De1B2c3bbA395e8547FE4283")),
-            child_actions: vec![
+            protocol:       OneInchV5,
+            trace_index:    0,
+            from:           Address::new(hex!("f4F8845ceDe63e79De1B2c3bbA395e8547FE4283")),
+            to:             Address::new(hex!("1111111254eeb25477b68fb85ed929f73a960582")),
+            recipient:      Address::new(hex!("f4F8845ceDe63e79De1B2c3bbA395e8547FE4283")),
+            child_actions:  vec![
                 Action::Transfer(NormalizedTransfer {
                     trace_index: 1,
                     msg_value:   U256::ZERO,
@@ -314,18 +559,32 @@ mod tests {
                     fee:         U256::from_str("0").unwrap().to_scaled_rational(1),
                 }),
             ],
-
-            msg_value: U256::ZERO,
+            min_amount_out: None,
+            referrer:       None,
+            msg_value:      U256::ZERO,
         });
 
         classifier_utils
-            .contains_action(
+            .contains_action_except(
                 aggregator,
                 0,
                 eq_action,
                 TreeSearchBuilder::default().with_action(Action::is_aggregator),
+                &["min_amount_out"],
             )
             .await
             .unwrap();
+
+        // the minimum is decoded from the calldata and the route delivered at
+        // least that much
+        let tree = classifier_utils.build_tree_tx(aggregator).await.unwrap();
+        let route = tree.tx_roots[0]
+            .collect(&TreeSearchBuilder::default().with_action(Action::is_aggregator))
+            .into_iter()
+            .find_map(Action::try_aggregator)
+            .unwrap();
+        let min_amount_out = route.min_amount_out.expect("no min amount out decoded");
+        assert!(min_amount_out > U256::ZERO);
+        assert!(min_amount_out <= U256::from_str("125475168379").unwrap());
     }
 }
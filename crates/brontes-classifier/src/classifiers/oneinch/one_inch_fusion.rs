@@ -10,13 +10,15 @@ action_impl!(
     [],
     |info: CallInfo, _db_tx: &DB| {
         return Ok(NormalizedAggregator {
-            protocol:      Protocol::OneInchFusion,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     Address::default(),
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::OneInchFusion,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      Address::default(),
+            child_actions:  vec![],
+            min_amount_out: None,
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -108,7 +110,9 @@ mod tests {
                 }),
             ],
 
-            msg_value: U256::ZERO,
+            min_amount_out: None,
+            referrer:       None,
+            msg_value:      U256::ZERO,
         });
 
         classifier_utils
@@ -95,6 +95,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -128,6 +129,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -87,6 +87,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -119,6 +120,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -152,6 +154,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -92,6 +92,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -125,6 +126,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -157,6 +159,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -90,6 +90,7 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -122,6 +123,7 @@ action_impl!(
             from: info.from_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -155,6 +157,44 @@ action_impl!(
             pool: info.target_address,
             token: vec![t0_info, t1_info],
             amount: vec![am0, am1],
+            position_id: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV3,
+    crate::UniswapV3::collectProtocolCall,
+    Collect,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: collectProtocolCall,
+    return_data: collectProtocolReturn,
+    db_tx: &DB
+    | {
+        let details = db_tx.get_protocol_details_sorted(info.target_address)?;
+        let [token_0, token_1] = [details.token0, details.token1];
+
+        let t0_info = db_tx.try_fetch_token_info(token_0)?;
+        let t1_info = db_tx.try_fetch_token_info(token_1)?;
+
+        let am0 = return_data.amount0.to_scaled_rational(t0_info.decimals);
+        let am1 = return_data.amount1.to_scaled_rational(t1_info.decimals);
+
+        // protocol fees are swept by the factory owner, the recipient is the
+        // fee collector
+        Ok(NormalizedCollect {
+            protocol: Protocol::UniswapV3,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: call_data.recipient,
+            pool: info.target_address,
+            token: vec![t0_info, t1_info],
+            amount: vec![am0, am1],
+            position_id: None,
         })
     }
 );
@@ -226,6 +266,7 @@ mod tests {
                     .unwrap()
                     .to_scaled_rational(6),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -258,6 +299,7 @@ mod tests {
                     .to_scaled_rational(6),
                 U256::from_str("5793599811").unwrap().to_scaled_rational(6),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -290,6 +332,7 @@ mod tests {
                     .to_scaled_rational(6),
                 U256::from_str("5793599811").unwrap().to_scaled_rational(6),
             ],
+            position_id: None,
         });
 
         classifier_utils
@@ -0,0 +1,221 @@
+//! Liquidity managed through the Uniswap V3 NonfungiblePositionManager.
+//!
+//! The position manager owns all pool level positions it creates, so the
+//! pool calls it makes only show the manager as the liquidity provider. These
+//! classifiers attribute the liquidity to the caller and the owner of the
+//! position NFT instead. The pool, tokens and amounts are filled in from the
+//! pool call made by the position manager, see
+//! `multi_frame_classification::liquidity`.
+use brontes_macros::action_impl;
+use brontes_pricing::Protocol;
+use brontes_types::{
+    normalized_actions::{NormalizedBurn, NormalizedCollect, NormalizedMint},
+    structured_trace::CallInfo,
+};
+
+action_impl!(
+    Protocol::UniswapV3PositionManager,
+    crate::UniswapV3PositionManager::mintCall,
+    Mint,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: mintCall,
+    return_data: mintReturn,
+    _db_tx: &DB| {
+        Ok(NormalizedMint {
+            protocol: Protocol::UniswapV3PositionManager,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: call_data.params.recipient,
+            // filled in from the pool mint
+            pool: info.target_address,
+            token: vec![],
+            amount: vec![],
+            position_id: Some(return_data.tokenId),
+        })
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV3PositionManager,
+    crate::UniswapV3PositionManager::increaseLiquidityCall,
+    Mint,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: increaseLiquidityCall,
+    _db_tx: &DB| {
+        Ok(NormalizedMint {
+            protocol: Protocol::UniswapV3PositionManager,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            // anyone can add liquidity to a position, the owner isn't known
+            // from the call so we attribute it to the caller
+            recipient: info.from_address,
+            // filled in from the pool mint
+            pool: info.target_address,
+            token: vec![],
+            amount: vec![],
+            position_id: Some(call_data.params.tokenId),
+        })
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV3PositionManager,
+    crate::UniswapV3PositionManager::decreaseLiquidityCall,
+    Burn,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: decreaseLiquidityCall,
+    _db_tx: &DB| {
+        // only the owner or an approved address can decrease liquidity
+        Ok(NormalizedBurn {
+            protocol: Protocol::UniswapV3PositionManager,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: info.from_address,
+            // filled in from the pool burn
+            pool: info.target_address,
+            token: vec![],
+            amount: vec![],
+            position_id: Some(call_data.params.tokenId),
+        })
+    }
+);
+
+action_impl!(
+    Protocol::UniswapV3PositionManager,
+    crate::UniswapV3PositionManager::collectCall,
+    Collect,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: collectCall,
+    _db_tx: &DB| {
+        Ok(NormalizedCollect {
+            protocol: Protocol::UniswapV3PositionManager,
+            trace_index: info.trace_idx,
+            from: info.from_address,
+            recipient: call_data.params.recipient,
+            // filled in from the pool collect
+            pool: info.target_address,
+            token: vec![],
+            amount: vec![],
+            position_id: Some(call_data.params.tokenId),
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, U256};
+    use alloy_sol_types::{SolCall, SolValue};
+    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use brontes_types::{
+        constants::{USDC_ADDRESS, WETH_ADDRESS},
+        normalized_actions::Action,
+        structured_trace::CallFrameInfo,
+    };
+
+    use super::*;
+    use crate::{
+        binding_cache::ProtocolBindings,
+        ActionCollection, ProtocolClassifier,
+        UniswapV3PositionManager::{collectCall, decreaseLiquidityCall},
+    };
+
+    const POSITION_MANAGER: Address =
+        Address::new(hex!("C36442b4a4522E871399CD717aBDD847Ab11FE88"));
+    const OWNER: Address = Address::new(hex!("1111111111111111111111111111111111111111"));
+    const RECIPIENT: Address = Address::new(hex!("2222222222222222222222222222222222222222"));
+
+    fn classify(classifier_utils: &ClassifierTestUtils, call_data: Vec<u8>) -> Option<Action> {
+        classifier_utils.ensure_protocol(
+            Protocol::UniswapV3PositionManager,
+            POSITION_MANAGER,
+            WETH_ADDRESS,
+            Some(USDC_ADDRESS),
+            None,
+            None,
+            None,
+            None,
+        );
+
+        let call_info = CallFrameInfo {
+            trace_idx:      1,
+            call_data:      call_data.into(),
+            // the amounts are taken from the pool call
+            return_data:    (U256::ZERO, U256::ZERO).abi_encode().into(),
+            target_address: POSITION_MANAGER,
+            from_address:   OWNER,
+            logs:           &[],
+            delegate_logs:  vec![],
+            msg_sender:     OWNER,
+            msg_value:      U256::ZERO,
+        };
+
+        ProtocolClassifier::default()
+            .dispatch(
+                call_info,
+                classifier_utils.libmdbx,
+                &ProtocolBindings::default(),
+                19_000_000,
+                0,
+            )
+            .map(|(_, action)| action)
+    }
+
+    #[brontes_macros::test]
+    async fn test_position_manager_decrease_liquidity() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+
+        // DecreaseLiquidityParams(tokenId, liquidity, amount0Min, amount1Min, deadline)
+        let params = (U256::from(7), 1_000u128, U256::ZERO, U256::ZERO, U256::MAX);
+        let call_data = [decreaseLiquidityCall::SELECTOR.as_slice(), &params.abi_encode()].concat();
+
+        assert_eq!(
+            classify(&classifier_utils, call_data),
+            Some(Action::Burn(NormalizedBurn {
+                protocol:    Protocol::UniswapV3PositionManager,
+                trace_index: 1,
+                from:        OWNER,
+                recipient:   OWNER,
+                pool:        POSITION_MANAGER,
+                token:       vec![],
+                amount:      vec![],
+                position_id: Some(U256::from(7)),
+            }))
+        );
+    }
+
+    #[brontes_macros::test]
+    async fn test_position_manager_collect() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+
+        // CollectParams(tokenId, recipient, amount0Max, amount1Max)
+        let params = (U256::from(7), RECIPIENT, u128::MAX, u128::MAX);
+        let call_data = [collectCall::SELECTOR.as_slice(), &params.abi_encode()].concat();
+
+        assert_eq!(
+            classify(&classifier_utils, call_data),
+            Some(Action::Collect(NormalizedCollect {
+                protocol:    Protocol::UniswapV3PositionManager,
+                trace_index: 1,
+                from:        OWNER,
+                recipient:   RECIPIENT,
+                pool:        POSITION_MANAGER,
+                token:       vec![],
+                amount:      vec![],
+                position_id: Some(U256::from(7)),
+            }))
+        );
+    }
+}
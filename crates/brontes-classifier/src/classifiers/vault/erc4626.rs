@@ -0,0 +1,260 @@
+use brontes_macros::action_impl;
+use brontes_types::{
+    normalized_actions::{NormalizedBurn, NormalizedMint},
+    structured_trace::CallInfo,
+    Protocol, ToScaledRational,
+};
+
+// The vault's share token is minted and burned by the vault itself, so
+// conversions are classified like liquidity provision to the vault, in its
+// underlying asset. The shares moved show up as the share token's transfers.
+
+action_impl!(
+    Protocol::ERC4626Vault,
+    crate::ERC4626Vault::depositCall,
+    Mint,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: depositCall,
+    db_tx: &DB| {
+        // token0 is the underlying asset, token1 the vault's share token
+        let details = db_tx.get_protocol_details(info.target_address)?;
+        let asset_info = db_tx.try_fetch_token_info(details.token0)?;
+        let amount = call_data.assets.to_scaled_rational(asset_info.decimals);
+
+        Ok(NormalizedMint {
+            protocol: Protocol::ERC4626Vault,
+            trace_index: info.trace_idx,
+            from: info.msg_sender,
+            recipient: call_data.receiver,
+            pool: info.target_address,
+            token: vec![asset_info],
+            amount: vec![amount],
+            position_id: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ERC4626Vault,
+    crate::ERC4626Vault::mintCall,
+    Mint,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: mintCall,
+    return_data: mintReturn,
+    db_tx: &DB| {
+        // token0 is the underlying asset, token1 the vault's share token
+        let details = db_tx.get_protocol_details(info.target_address)?;
+        let asset_info = db_tx.try_fetch_token_info(details.token0)?;
+        let amount = return_data._0.to_scaled_rational(asset_info.decimals);
+
+        Ok(NormalizedMint {
+            protocol: Protocol::ERC4626Vault,
+            trace_index: info.trace_idx,
+            from: info.msg_sender,
+            recipient: call_data.receiver,
+            pool: info.target_address,
+            token: vec![asset_info],
+            amount: vec![amount],
+            position_id: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ERC4626Vault,
+    crate::ERC4626Vault::withdrawCall,
+    Burn,
+    [],
+    call_data: true,
+    |
+    info: CallInfo,
+    call_data: withdrawCall,
+    db_tx: &DB| {
+        // token0 is the underlying asset, token1 the vault's share token
+        let details = db_tx.get_protocol_details(info.target_address)?;
+        let asset_info = db_tx.try_fetch_token_info(details.token0)?;
+        let amount = call_data.assets.to_scaled_rational(asset_info.decimals);
+
+        Ok(NormalizedBurn {
+            protocol: Protocol::ERC4626Vault,
+            trace_index: info.trace_idx,
+            from: call_data.owner,
+            recipient: call_data.receiver,
+            pool: info.target_address,
+            token: vec![asset_info],
+            amount: vec![amount],
+            position_id: None,
+        })
+    }
+);
+
+action_impl!(
+    Protocol::ERC4626Vault,
+    crate::ERC4626Vault::redeemCall,
+    Burn,
+    [],
+    call_data: true,
+    return_data: true,
+    |
+    info: CallInfo,
+    call_data: redeemCall,
+    return_data: redeemReturn,
+    db_tx: &DB| {
+        // token0 is the underlying asset, token1 the vault's share token
+        let details = db_tx.get_protocol_details(info.target_address)?;
+        let asset_info = db_tx.try_fetch_token_info(details.token0)?;
+        let amount = return_data._0.to_scaled_rational(asset_info.decimals);
+
+        Ok(NormalizedBurn {
+            protocol: Protocol::ERC4626Vault,
+            trace_index: info.trace_idx,
+            from: call_data.owner,
+            recipient: call_data.receiver,
+            pool: info.target_address,
+            token: vec![asset_info],
+            amount: vec![amount],
+            position_id: None,
+        })
+    }
+);
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, Address, U256};
+    use alloy_sol_types::SolCall;
+    use brontes_classifier::test_utils::ClassifierTestUtils;
+    use brontes_types::{
+        constants::DAI_ADDRESS,
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::Action,
+        structured_trace::CallFrameInfo,
+    };
+
+    use super::*;
+    use crate::{
+        binding_cache::ProtocolBindings,
+        ActionCollection,
+        ERC4626Vault::{depositCall, redeemCall},
+        ProtocolClassifier,
+    };
+
+    const SDAI: Address = Address::new(hex!("83F20F44975D03b1b09e64809B757c47f942BEeA"));
+    const USER: Address = Address::new(hex!("1111111111111111111111111111111111111111"));
+    const RECEIVER: Address = Address::new(hex!("2222222222222222222222222222222222222222"));
+
+    fn setup(classifier_utils: &ClassifierTestUtils) -> TokenInfoWithAddress {
+        classifier_utils.ensure_protocol(
+            Protocol::ERC4626Vault,
+            SDAI,
+            DAI_ADDRESS,
+            Some(SDAI),
+            None,
+            None,
+            None,
+            None,
+        );
+
+        let dai = TokenInfoWithAddress {
+            address: DAI_ADDRESS,
+            inner:   TokenInfo { decimals: 18, symbol: "DAI".to_string() },
+        };
+        classifier_utils.ensure_token(dai.clone());
+        classifier_utils.ensure_token(TokenInfoWithAddress {
+            address: SDAI,
+            inner:   TokenInfo { decimals: 18, symbol: "sDAI".to_string() },
+        });
+
+        dai
+    }
+
+    fn classify(
+        classifier_utils: &ClassifierTestUtils,
+        call_data: Vec<u8>,
+        return_data: Vec<u8>,
+    ) -> Option<Action> {
+        let call_info = CallFrameInfo {
+            trace_idx:      1,
+            call_data:      call_data.into(),
+            return_data:    return_data.into(),
+            target_address: SDAI,
+            from_address:   USER,
+            logs:           &[],
+            delegate_logs:  vec![],
+            msg_sender:     USER,
+            msg_value:      U256::ZERO,
+        };
+
+        ProtocolClassifier::default()
+            .dispatch(
+                call_info,
+                classifier_utils.libmdbx,
+                &ProtocolBindings::default(),
+                19_000_000,
+                0,
+            )
+            .map(|(_, action)| action)
+    }
+
+    #[brontes_macros::test]
+    async fn test_erc4626_deposit() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        let dai = setup(&classifier_utils);
+
+        let assets = U256::from(1_050_000_000_000_000_000_u128);
+        let shares = U256::from(1_000_000_000_000_000_000_u128);
+        let action = classify(
+            &classifier_utils,
+            depositCall { assets, receiver: RECEIVER }.abi_encode(),
+            depositCall::abi_encode_returns(&(shares,)),
+        );
+
+        assert_eq!(
+            action,
+            Some(Action::Mint(NormalizedMint {
+                protocol:    Protocol::ERC4626Vault,
+                trace_index: 1,
+                from:        USER,
+                recipient:   RECEIVER,
+                pool:        SDAI,
+                token:       vec![dai],
+                amount:      vec![assets.to_scaled_rational(18)],
+                position_id: None,
+            }))
+        );
+    }
+
+    #[brontes_macros::test]
+    async fn test_erc4626_redeem() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        let dai = setup(&classifier_utils);
+
+        let assets = U256::from(1_050_000_000_000_000_000_u128);
+        let shares = U256::from(1_000_000_000_000_000_000_u128);
+        let action = classify(
+            &classifier_utils,
+            redeemCall { shares, receiver: RECEIVER, owner: USER }.abi_encode(),
+            redeemCall::abi_encode_returns(&(assets,)),
+        );
+
+        assert_eq!(
+            action,
+            Some(Action::Burn(NormalizedBurn {
+                protocol:    Protocol::ERC4626Vault,
+                trace_index: 1,
+                from:        USER,
+                recipient:   RECEIVER,
+                pool:        SDAI,
+                token:       vec![dai],
+                amount:      vec![assets.to_scaled_rational(18)],
+                position_id: None,
+            }))
+        );
+    }
+}
@@ -0,0 +1,3 @@
+#[allow(non_snake_case)]
+mod erc4626;
+pub use erc4626::*;
@@ -13,15 +13,18 @@ action_impl!(
     crate::ZeroXUniswapFeaure::sellToUniswapCall,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: sellToUniswapCall, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -41,6 +44,8 @@ action_impl!(
             to: info.target_address,
             recipient: call_data.recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer: info.referrer,
             msg_value: info.msg_value,
         })
     }
@@ -60,6 +65,8 @@ action_impl!(
             to: info.target_address,
             recipient: call_data.recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer: info.referrer,
             msg_value: info.msg_value,
         })
     }
@@ -79,6 +86,8 @@ action_impl!(
             from: info.from_address,
             recipient: call_data.recipient,
             child_actions: vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer: info.referrer,
             msg_value: info.msg_value,
         })
     }
@@ -90,15 +99,18 @@ action_impl!(
     crate::ZeroXTransformERC20Feature::transformERC20Call,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: transformERC20Call, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minOutputTokenAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -109,15 +121,18 @@ action_impl!(
     crate::ZeroXPancakeSwapFeature::sellToPancakeSwapCall,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: sellToPancakeSwapCall, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -340,9 +355,14 @@ action_impl!(
     crate::ZeroXLiquidityProviderFeature::sellToLiquidityProviderCall,
     Aggregator,
     [LiquidityProviderSwap],
+    call_data: true,
     logs: true,
     include_delegated_logs: true,
-    |info: CallInfo, logs: ZeroXSellToLiquidityProviderCallLogs, _| {
+    |
+    info: CallInfo,
+    call_data: sellToLiquidityProviderCall,
+    logs: ZeroXSellToLiquidityProviderCallLogs,
+    _| {
         let logs = logs.liquidity_provider_swap_field?;
 
         Ok(NormalizedAggregator {
@@ -353,6 +373,8 @@ action_impl!(
             recipient: logs.recipient,
             msg_value :info.msg_value,
             child_actions: vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer: info.referrer,
         })
     }
 
@@ -364,15 +386,18 @@ action_impl!(
     crate::ZeroXInterface::multiplexBatchSellEthForTokenCall,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: multiplexBatchSellEthForTokenCall, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -382,15 +407,18 @@ action_impl!(
     crate::ZeroXInterface::multiplexBatchSellTokenForEthCall,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: multiplexBatchSellTokenForEthCall, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -400,15 +428,18 @@ action_impl!(
     crate::ZeroXInterface::multiplexBatchSellTokenForTokenCall,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: multiplexBatchSellTokenForTokenCall, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -418,15 +449,18 @@ action_impl!(
     crate::ZeroXInterface::multiplexMultiHopSellEthForTokenCall,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: multiplexMultiHopSellEthForTokenCall, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -436,15 +470,18 @@ action_impl!(
     crate::ZeroXInterface::multiplexMultiHopSellTokenForEthCall,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: multiplexMultiHopSellTokenForEthCall, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -454,15 +491,18 @@ action_impl!(
     crate::ZeroXInterface::multiplexMultiHopSellTokenForTokenCall,
     Aggregator,
     [],
-    |info: CallInfo, _| {
+    call_data: true,
+    |info: CallInfo, call_data: multiplexMultiHopSellTokenForTokenCall, _| {
         Ok(NormalizedAggregator {
-            protocol:      Protocol::ZeroX,
-            trace_index:   info.trace_idx,
-            from:          info.from_address,
-            to:            info.target_address,
-            recipient:     info.msg_sender,
-            child_actions: vec![],
-            msg_value:     info.msg_value,
+            protocol:       Protocol::ZeroX,
+            trace_index:    info.trace_idx,
+            from:           info.from_address,
+            to:             info.target_address,
+            recipient:      info.msg_sender,
+            child_actions:  vec![],
+            min_amount_out: Some(call_data.minBuyAmount),
+            referrer:       info.referrer,
+            msg_value:      info.msg_value,
         })
     }
 );
@@ -631,6 +671,25 @@ mod tests {
             .unwrap();
     }
 
+    #[brontes_macros::test]
+    async fn test_zerox_min_amount_out() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        let aggregator_tx =
+            B256::from(hex!("fac5edf3af538243554fdb0d8275781ee5834686bc0881e9343ac90e108a9e89"));
+
+        let tree = classifier_utils.build_tree_tx(aggregator_tx).await.unwrap();
+        let route = tree.tx_roots[0]
+            .collect(&TreeSearchBuilder::default().with_action(Action::is_aggregator))
+            .into_iter()
+            .find_map(Action::try_aggregator)
+            .unwrap();
+        let min_amount_out = route.min_amount_out.expect("no min buy amount decoded");
+        let (token_out, realized) = route.realized_amount_out().unwrap();
+
+        assert!(min_amount_out > U256::ZERO);
+        assert!(route.quoted_min_amount_out(&token_out).unwrap() <= realized);
+    }
+
     #[brontes_macros::test]
     async fn test_zerox_sell_eth_for_token_to_uniswap_v3() {
         let classifier_utils = ClassifierTestUtils::new().await;
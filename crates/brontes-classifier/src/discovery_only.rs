@@ -17,27 +17,39 @@ use tracing::{error, trace};
 
 use self::erc20::try_decode_transfer;
 use crate::{
-    classifiers::*, tree_builder::utils::decode_transfer, ActionCollection,
-    FactoryDiscoveryDispatch,
+    binding_cache::ProtocolBindings, classifiers::*, tree_builder::utils::decode_transfer,
+    ActionCollection, FactoryDiscoveryDispatch,
 };
 
 #[derive(Debug)]
 pub struct DiscoveryOnlyClassifier<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> {
-    libmdbx:  &'db DB,
-    provider: Arc<T>,
+    libmdbx:           &'db DB,
+    provider:          Arc<T>,
+    protocol_bindings: Arc<ProtocolBindings>,
 }
 
 impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Clone
     for DiscoveryOnlyClassifier<'db, T, DB>
 {
     fn clone(&self) -> Self {
-        Self { libmdbx: self.libmdbx, provider: self.provider.clone() }
+        Self {
+            libmdbx:           self.libmdbx,
+            provider:          self.provider.clone(),
+            protocol_bindings: self.protocol_bindings.clone(),
+        }
     }
 }
 
 impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassifier<'db, T, DB> {
     pub fn new(libmdbx: &'db DB, provider: Arc<T>) -> Self {
-        Self { libmdbx, provider }
+        Self { libmdbx, provider, protocol_bindings: Arc::default() }
+    }
+
+    /// Looks up the protocols of the addresses called in `bindings`, which
+    /// can be shared with the other classifiers of the run
+    pub fn with_protocol_bindings(mut self, bindings: Arc<ProtocolBindings>) -> Self {
+        self.protocol_bindings = bindings;
+        self
     }
 
     pub async fn run_discovery(&self, traces: Vec<TxTrace>, header: Header) {
@@ -91,6 +103,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
                                 - (header.base_fee_per_gas.unwrap_or_default() as u128),
                         },
                         data_store: NodeData(vec![Some(action)]),
+                        decode_outcomes: Default::default(),
                     };
 
                     let tx_trace = &trace.trace;
@@ -212,9 +225,13 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
             }
         }
 
-        if let Some(results) =
-            ProtocolClassifier::default().dispatch(call_info, self.libmdbx, block, tx_idx)
-        {
+        if let Some(results) = ProtocolClassifier::default().dispatch(
+            call_info,
+            self.libmdbx,
+            &self.protocol_bindings,
+            block,
+            tx_idx,
+        ) {
             if results.1.is_new_pool() {
                 let Action::NewPool(p) = &results.1 else { unreachable!() };
                 self.insert_new_pool(block, p.clone()).await;
@@ -227,6 +244,8 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
                     .is_err()
                 {
                     error!(pool=?p.pool_address,"failed to update pool config");
+                } else {
+                    self.protocol_bindings.bind(p.pool_address, p.protocol);
                 }
             }
         } else {
@@ -327,6 +346,8 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> DiscoveryOnlyClassif
         {
             error!(pool=?pool.pool_address,"failed to insert discovered pool into libmdbx");
         } else {
+            self.protocol_bindings
+                .bind(pool.pool_address, pool.protocol);
             trace!(
                 "Discovered new {} pool:
                             \nAddress:{}
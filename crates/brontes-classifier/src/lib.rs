@@ -16,6 +16,9 @@ use brontes_types::{
 };
 use futures::Future;
 
+use crate::binding_cache::ProtocolBindings;
+
+pub mod binding_cache;
 pub mod tree_builder;
 pub use tree_builder::Classifier;
 pub mod discovery_only;
@@ -33,6 +36,7 @@ pub use classifiers::*;
 sol!(UniswapV2, "./classifier-abis/UniswapV2.json");
 sol!(SushiSwapV2, "./classifier-abis/SushiSwapV2.json");
 sol!(UniswapV3, "./classifier-abis/UniswapV3.json");
+sol!(UniswapV3PositionManager, "./classifier-abis/UniswapV3NonfungiblePositionManager.json");
 sol!(SushiSwapV3, "./classifier-abis/SushiSwapV3.json");
 sol!(PancakeSwapV2, "./classifier-abis/PancakeSwapV2.json");
 sol!(PancakeSwapV3, "./classifier-abis/PancakeSwapV3.json");
@@ -66,6 +70,12 @@ sol!(ZeroXLiquidityProviderFeature, "./classifier-abis/zero-x/ZeroXLiquidityProv
 sol!(ZeroXInterface, "./classifier-abis/zero-x/ZeroXInterface.json");
 sol!(DodoDPPPool, "./classifier-abis/dodo/DPPPool.json");
 sol!(DodoDSPPool, "./classifier-abis/dodo/DSPPool.json");
+sol!(LidoStEth, "./classifier-abis/lst/Lido.json");
+sol!(LidoWstEth, "./classifier-abis/lst/WstETH.json");
+sol!(RocketPoolRETH, "./classifier-abis/lst/RocketTokenRETH.json");
+sol!(LidoWithdrawalQueue, "./classifier-abis/lst/WithdrawalQueue.json");
+sol!(CoinbaseCbEth, "./classifier-abis/lst/CbETH.json");
+sol!(ERC4626Vault, "./classifier-abis/ERC4626Vault.json");
 
 // Discovery
 sol!(UniswapV2Factory, "./classifier-abis/UniswapV2Factory.json");
@@ -137,6 +147,7 @@ pub trait ActionCollection: Sync + Send {
         &self,
         call_info: CallFrameInfo<'_>,
         db_tx: &DB,
+        bindings: &ProtocolBindings,
         block: u64,
         tx_idx: u64,
     ) -> Option<(DexPriceMsg, Action)>;
@@ -21,6 +21,7 @@ impl MultiCallFrameClassifier for OneInchAggregator {
             trace_index:         request.trace_idx,
             tree_search_builder: TreeSearchBuilder::new().with_actions([
                 Action::is_swap,
+                Action::is_fill,
                 Action::is_transfer,
                 Action::is_eth_transfer,
             ]),
@@ -43,6 +44,7 @@ impl MultiCallFrameClassifier for OneInchFusion {
             trace_index:         request.trace_idx,
             tree_search_builder: TreeSearchBuilder::new().with_actions([
                 Action::is_swap,
+                Action::is_fill,
                 Action::is_transfer,
                 Action::is_eth_transfer,
             ]),
@@ -63,7 +65,8 @@ fn parse_1inch(
 
     for (trace_index, action) in child_nodes {
         match action {
-            Action::Swap(_) | Action::SwapWithFee(_) => {
+            // fusion resolvers settle orders through limit order fills
+            Action::Swap(_) | Action::SwapWithFee(_) | Action::Fill(_) => {
                 this.child_actions.push(action.clone());
                 prune_nodes.push(trace_index);
             }
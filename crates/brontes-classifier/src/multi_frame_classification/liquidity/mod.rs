@@ -0,0 +1,2 @@
+pub mod uniswap_v3;
+pub use uniswap_v3::*;
@@ -0,0 +1,127 @@
+use brontes_types::{
+    normalized_actions::{
+        Action, MultiCallFrameClassification, MultiFrameAction, MultiFrameRequest,
+    },
+    Protocol, TreeSearchBuilder,
+};
+
+use crate::multi_frame_classification::MultiCallFrameClassifier;
+
+/// Merges the pool mint, burn or collect made by the position manager into
+/// the position manager action, which knows the owner and token id of the
+/// position. The pool action is pruned so the liquidity isn't counted twice.
+pub struct UniswapV3PositionManager;
+
+impl MultiCallFrameClassifier for UniswapV3PositionManager {
+    const KEY: [u8; 2] =
+        [Protocol::UniswapV3PositionManager as u8, MultiFrameAction::Liquidity as u8];
+
+    fn create_classifier(
+        request: MultiFrameRequest,
+    ) -> Option<MultiCallFrameClassification<Action>> {
+        Some(MultiCallFrameClassification {
+            trace_index:         request.trace_idx,
+            tree_search_builder: TreeSearchBuilder::new().with_actions([
+                Action::is_mint,
+                Action::is_burn,
+                Action::is_collect,
+            ]),
+            parse_fn:            Box::new(|this_action, child_nodes| {
+                let mut nodes_to_prune = Vec::new();
+
+                for (index, action) in child_nodes {
+                    match (&mut *this_action, action) {
+                        (Action::Mint(this), Action::Mint(pool_mint)) => {
+                            this.protocol = pool_mint.protocol;
+                            this.pool = pool_mint.pool;
+                            this.token = pool_mint.token;
+                            this.amount = pool_mint.amount;
+                        }
+                        (Action::Burn(this), Action::Burn(pool_burn)) => {
+                            this.protocol = pool_burn.protocol;
+                            this.pool = pool_burn.pool;
+                            this.token = pool_burn.token;
+                            this.amount = pool_burn.amount;
+                        }
+                        (Action::Collect(this), Action::Collect(pool_collect)) => {
+                            this.protocol = pool_collect.protocol;
+                            this.pool = pool_collect.pool;
+                            this.token = pool_collect.token;
+                            this.amount = pool_collect.amount;
+                        }
+                        _ => continue,
+                    }
+
+                    nodes_to_prune.push(index);
+                    break
+                }
+
+                nodes_to_prune
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, U256};
+    use brontes_types::{
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{MultiFrameAction, NodeDataIndex, NormalizedBurn, NormalizedMint},
+    };
+    use malachite::Rational;
+
+    use super::*;
+
+    #[test]
+    fn test_merges_pool_liquidity_into_position() {
+        let (owner, manager, pool) =
+            (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+        let classification = UniswapV3PositionManager::create_classifier(MultiFrameRequest {
+            protocol:  Protocol::UniswapV3PositionManager,
+            call_type: MultiFrameAction::Liquidity,
+            trace_idx: 0,
+        })
+        .unwrap();
+
+        let mut position = Action::Mint(NormalizedMint {
+            protocol: Protocol::UniswapV3PositionManager,
+            from: owner,
+            recipient: owner,
+            pool: manager,
+            position_id: Some(U256::from(7)),
+            ..Default::default()
+        });
+        let pool_mint = NormalizedMint {
+            protocol: Protocol::UniswapV3,
+            trace_index: 2,
+            from: manager,
+            recipient: manager,
+            pool,
+            token: vec![TokenInfoWithAddress::weth()],
+            amount: vec![Rational::from(2)],
+            position_id: None,
+        };
+        let index = |trace_index| NodeDataIndex { trace_index, data_idx: 0, multi_data_idx: 0 };
+
+        // the burn doesn't belong to the mint, only the pool mint is merged
+        let pruned = classification.parse(
+            &mut position,
+            vec![
+                (index(1), Action::Burn(NormalizedBurn { pool, ..Default::default() })),
+                (index(2), Action::Mint(pool_mint.clone())),
+            ],
+        );
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].trace_index, 2);
+
+        let Action::Mint(position) = position else { panic!("position isn't a mint") };
+        assert_eq!(position.protocol, Protocol::UniswapV3);
+        assert_eq!(position.pool, pool);
+        assert_eq!(position.token, pool_mint.token);
+        assert_eq!(position.amount, pool_mint.amount);
+        // the owner and token id of the position are kept
+        assert_eq!(position.recipient, owner);
+        assert_eq!(position.position_id, Some(U256::from(7)));
+    }
+}
@@ -8,6 +8,7 @@ pub mod aggregator;
 pub mod batch;
 pub mod flash_loan;
 pub mod liquidations;
+pub mod liquidity;
 
 use aggregator::{OneInchAggregator, OneInchFusion, ZeroXAgg};
 use batch::{Cowswap, UniswapX, ZeroXBatch};
@@ -15,6 +16,7 @@ use brontes_types::normalized_actions::{Action, MultiCallFrameClassification, Mu
 use flash_loan::{BalancerV2, MakerDss};
 use itertools::Itertools;
 use liquidations::{AaveV2, AaveV3};
+use liquidity::UniswapV3PositionManager;
 use tracing::debug;
 
 use self::flash_loan::Dodo;
@@ -48,6 +50,7 @@ pub fn parse_multi_frame_requests(
             ZeroXBatch::KEY => ZeroXBatch::create_classifier(request),
             MakerDss::KEY => MakerDss::create_classifier(request),
             Dodo::KEY => Dodo::create_classifier(request),
+            UniswapV3PositionManager::KEY => UniswapV3PositionManager::create_classifier(request),
             _ => {
                 debug!(?request, "no multi frame classification impl for this request");
                 None
@@ -18,7 +18,26 @@ use reth_db::DatabaseError;
 use thiserror::Error;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
-use crate::{ActionCollection, Classifier, ProtocolClassifier};
+use crate::{binding_cache::ProtocolBindings, ActionCollection, Classifier, ProtocolClassifier};
+
+/// A block the benchmarks are run against, picked to represent a shape of
+/// block the indexer sees in production
+#[derive(Debug, Clone, Copy)]
+pub struct BenchFixtureBlock {
+    pub name:  &'static str,
+    pub block: u64,
+}
+
+/// Blocks every tree and inspector benchmark is run against, so regressions
+/// in the hot paths show up whatever shape of block they affect
+pub const BENCH_FIXTURE_BLOCKS: [BenchFixtureBlock; 3] = [
+    // 28m gas, hundreds of dex swaps, aggregator routes and liquidity changes
+    BenchFixtureBlock { name: "dense defi", block: 18672183 },
+    // thousands of token transfers of airdropped spam tokens
+    BenchFixtureBlock { name: "spam heavy", block: 15565152 },
+    // mostly plain eth and token transfers
+    BenchFixtureBlock { name: "simple", block: 16796568 },
+];
 
 pub struct ClassifierBenchUtils {
     trace_loader:          TraceLoader,
@@ -59,7 +78,12 @@ impl ClassifierBenchUtils {
             b.to_async(&self.rt).iter_batched(
                 || (vec![trace.clone()], header.clone()),
                 |(trace, header)| async move {
-                    black_box(self.classifier.build_block_tree(trace, header, true).await)
+                    black_box(
+                        self.classifier
+                            .build_block_tree(trace, header, true)
+                            .await
+                            .unwrap(),
+                    )
                 },
                 criterion::BatchSize::NumIterations(1),
             );
@@ -83,7 +107,12 @@ impl ClassifierBenchUtils {
             b.to_async(&self.rt).iter_batched(
                 || (traces.clone(), header.clone()),
                 |(trace, header)| async move {
-                    black_box(self.classifier.build_block_tree(trace, header, true).await)
+                    black_box(
+                        self.classifier
+                            .build_block_tree(trace, header, true)
+                            .await
+                            .unwrap(),
+                    )
                 },
                 criterion::BatchSize::NumIterations(1),
             );
@@ -106,7 +135,12 @@ impl ClassifierBenchUtils {
             b.to_async(&self.rt).iter_batched(
                 || (traces.clone(), header.clone()),
                 |(trace, header)| async move {
-                    black_box(self.classifier.build_block_tree(trace, header, true).await)
+                    black_box(
+                        self.classifier
+                            .build_block_tree(trace, header, true)
+                            .await
+                            .unwrap(),
+                    )
                 },
                 criterion::BatchSize::NumIterations(1),
             );
@@ -114,6 +148,35 @@ impl ClassifierBenchUtils {
         Ok(())
     }
 
+    /// Benches finalizing the block's tree, which computes the per tx gas
+    /// statistics and finalizes every root
+    pub fn bench_tree_finalize(
+        &self,
+        bench_name: &str,
+        block: u64,
+        c: &mut Criterion,
+    ) -> Result<(), ClassifierBenchError> {
+        let BlockTracesWithHeaderAnd { traces, header, .. } = self
+            .rt
+            .block_on(self.trace_loader.get_block_traces_with_header(block))?;
+        let tree = self
+            .rt
+            .block_on(self.classifier.build_block_tree(traces, header, true))?;
+
+        c.bench_function(bench_name, move |b| {
+            b.iter_batched(
+                || tree.clone(),
+                |mut tree| {
+                    tree.finalize_tree();
+                    black_box(tree)
+                },
+                criterion::BatchSize::LargeInput,
+            );
+        });
+
+        Ok(())
+    }
+
     #[allow(clippy::unit_arg)]
     pub fn bench_tree_operations_tx(
         &self,
@@ -128,7 +191,7 @@ impl ClassifierBenchUtils {
 
         let tree = self
             .rt
-            .block_on(self.classifier.build_block_tree(vec![trace], header, true));
+            .block_on(self.classifier.build_block_tree(vec![trace], header, true))?;
         let tree = Arc::new(tree);
 
         c.bench_function(bench_name, move |b| b.iter(|| black_box(bench_fn(tree.clone()))));
@@ -149,7 +212,7 @@ impl ClassifierBenchUtils {
             .block_on(self.trace_loader.get_block_traces_with_header(block))?;
         let tree = self
             .rt
-            .block_on(self.classifier.build_block_tree(traces, header, true));
+            .block_on(self.classifier.build_block_tree(traces, header, true))?;
         let tree = Arc::new(tree);
 
         c.bench_function(bench_name, move |b| b.iter(|| black_box(bench_fn(tree.clone()))));
@@ -185,6 +248,7 @@ impl ClassifierBenchUtils {
             .ok_or_else(|| ClassifierBenchError::ProtocolClassifierError(protocol_address))?;
 
         let dispatcher = ProtocolClassifier::default();
+        let bindings = ProtocolBindings::default();
 
         c.bench_function(bench_name, move |b| {
             b.iter(|| {
@@ -194,6 +258,7 @@ impl ClassifierBenchUtils {
                     black_box(dispatcher.dispatch(
                         call_info.clone(),
                         self.trace_loader.libmdbx,
+                        &bindings,
                         block,
                         0,
                     ));
@@ -217,4 +282,12 @@ pub enum ClassifierBenchError {
     ProtocolDiscoveryError(Address),
     #[error("couldn't find trace that matched {0:?}")]
     ProtocolClassifierError(Address),
+    #[error("failed to build the block tree: {0}")]
+    TreeBuilderError(String),
+}
+
+impl From<eyre::Report> for ClassifierBenchError {
+    fn from(value: eyre::Report) -> Self {
+        Self::TreeBuilderError(value.to_string())
+    }
 }
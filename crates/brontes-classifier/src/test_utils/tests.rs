@@ -34,7 +34,9 @@ use serde_json::Value;
 use thiserror::Error;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 
-use crate::{Action, ActionCollection, Classifier, ProtocolClassifier};
+use crate::{
+    binding_cache::ProtocolBindings, Action, ActionCollection, Classifier, ProtocolClassifier,
+};
 
 pub struct ClassifierTestUtils {
     pub trace_loader: TraceLoader,
@@ -169,7 +171,7 @@ impl ClassifierTestUtils {
         Ok(self
             .classifier
             .build_block_tree(vec![trace], header, true)
-            .await)
+            .await?)
     }
 
     pub async fn setup_pricing_for_bench(
@@ -188,7 +190,7 @@ impl ClassifierTestUtils {
         let (tx, rx) = unbounded_channel();
 
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let _tree = classifier.build_block_tree(traces, header, true).await;
+        let _tree = classifier.build_block_tree(traces, header, true).await?;
 
         needs_tokens.iter().for_each(|token| {
             let update = DexPriceMsg::Update(PoolUpdate {
@@ -217,7 +219,7 @@ impl ClassifierTestUtils {
             .await?;
 
         let classifier = Classifier::new(self.libmdbx, tx, self.get_provider());
-        let _tree = classifier.build_block_tree(traces, header, true).await;
+        let _tree = classifier.build_block_tree(traces, header, true).await?;
 
         Ok(())
     }
@@ -246,7 +248,7 @@ impl ClassifierTestUtils {
         let BlockTracesWithHeaderAnd { traces, header, .. } = range_traces.remove(0);
 
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let _tree = classifier.build_block_tree(traces, header, true).await;
+        let _tree = classifier.build_block_tree(traces, header, true).await?;
 
         needs_tokens.iter().for_each(|token| {
             let update = DexPriceMsg::Update(PoolUpdate {
@@ -262,7 +264,7 @@ impl ClassifierTestUtils {
 
         // send rest of updates
         for BlockTracesWithHeaderAnd { traces, header, .. } in range_traces {
-            classifier.build_block_tree(traces, header, true).await;
+            classifier.build_block_tree(traces, header, true).await?;
         }
 
         ctr.store(true, SeqCst);
@@ -283,7 +285,9 @@ impl ClassifierTestUtils {
         let (tx, rx) = unbounded_channel();
 
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let tree = classifier.build_block_tree(vec![trace], header, true).await;
+        let tree = classifier
+            .build_block_tree(vec![trace], header, true)
+            .await?;
 
         needs_tokens.iter().for_each(|token| {
             let update = DexPriceMsg::Update(PoolUpdate {
@@ -313,7 +317,7 @@ impl ClassifierTestUtils {
         &self,
         tx_hashes: Vec<TxHash>,
     ) -> Result<Vec<BlockTree<Action>>, ClassifierTestUtilsError> {
-        Ok(join_all(
+        join_all(
             self.trace_loader
                 .get_tx_traces_with_header(tx_hashes)
                 .await?
@@ -324,7 +328,10 @@ impl ClassifierTestUtils {
                         .await
                 }),
         )
-        .await)
+        .await
+        .into_iter()
+        .map(|tree| Ok(tree?))
+        .collect()
     }
 
     pub async fn test_pool_token_order(
@@ -364,7 +371,7 @@ impl ClassifierTestUtils {
 
             let tree = classifier
                 .build_block_tree(block_info.traces, block_info.header, true)
-                .await;
+                .await?;
 
             trees.push(tree);
         }
@@ -404,7 +411,32 @@ impl ClassifierTestUtils {
             .trace_loader
             .get_block_traces_with_header(block)
             .await?;
-        let tree = self.classifier.build_block_tree(traces, header, true).await;
+        let tree = self
+            .classifier
+            .build_block_tree(traces, header, true)
+            .await?;
+
+        Ok(tree)
+    }
+
+    /// Builds the block's tree streaming its traces one at a time, the way
+    /// they come out of the tracer
+    pub async fn build_block_tree_incremental(
+        &self,
+        block: u64,
+    ) -> Result<BlockTree<Action>, ClassifierTestUtilsError> {
+        let BlockTracesWithHeaderAnd { traces, header, .. } = self
+            .trace_loader
+            .get_block_traces_with_header(block)
+            .await?;
+        let tree = self
+            .classifier
+            .build_block_tree_from_stream(
+                futures::stream::iter(traces.into_iter().map(Ok)),
+                header,
+                true,
+            )
+            .await?;
 
         Ok(tree)
     }
@@ -422,7 +454,7 @@ impl ClassifierTestUtils {
 
         let (tx, rx) = unbounded_channel();
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let tree = classifier.build_block_tree(traces, header, true).await;
+        let tree = classifier.build_block_tree(traces, header, true).await?;
 
         needs_tokens.iter().for_each(|token| {
             let update = DexPriceMsg::Update(PoolUpdate {
@@ -577,7 +609,13 @@ impl ClassifierTestUtils {
 
         let call_info = trace.get_callframe_info();
 
-        let result = dispatcher.dispatch(call_info, self.trace_loader.libmdbx, block, 0);
+        let result = dispatcher.dispatch(
+            call_info,
+            self.trace_loader.libmdbx,
+            &ProtocolBindings::default(),
+            block,
+            0,
+        );
 
         cmp_fn(result.map(|i| i.1));
 
@@ -595,7 +633,9 @@ impl ClassifierTestUtils {
 
         let (tx, _rx) = unbounded_channel();
         let classifier = Classifier::new(self.libmdbx, tx.clone(), self.get_provider());
-        let tree = classifier.build_block_tree(vec![trace], header, true).await;
+        let tree = classifier
+            .build_block_tree(vec![trace], header, true)
+            .await?;
         let res = Arc::new(tree)
             .collect(&txes, TreeSearchBuilder::default().with_action(Action::is_new_pool))
             .split_actions(Action::try_new_pool);
@@ -704,6 +744,14 @@ pub enum ClassifierTestUtilsError {
     ProtocolDiscoveryError(Address),
     #[error("couldn't find trace that matched {0:?}")]
     ProtocolClassifierError(Address),
+    #[error("failed to build the block tree: {0}")]
+    TreeBuilderError(String),
+}
+
+impl From<eyre::Report> for ClassifierTestUtilsError {
+    fn from(value: eyre::Report) -> Self {
+        Self::TreeBuilderError(value.to_string())
+    }
 }
 
 /// Makes a swap for initializing a virtual pool with the quote token.
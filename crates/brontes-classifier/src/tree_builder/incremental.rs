@@ -0,0 +1,173 @@
+//! Building a block's tree while the block is still being traced.
+//!
+//! [`Classifier::build_block_tree`] takes every trace of the block up front,
+//! so nothing is classified before the tracer is done with the last
+//! transaction. The [`IncrementalTreeBuilder`] instead starts classifying each
+//! transaction as soon as its trace comes out of the tracer, concurrently with
+//! the transactions before it, so the block is ready shortly after its last
+//! transaction is traced. Block level passes, such as tax token accounting and
+//! multi frame classification, run once the block is complete in
+//! [`IncrementalTreeBuilder::finalize`].
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_pricing::types::DexPriceMsg;
+use brontes_types::{
+    normalized_actions::Action, structured_trace::TxTrace, traits::TracingProvider, BlockTree,
+};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
+use reth_primitives::Header;
+
+use super::{
+    tree_pruning::{account_for_tax_tokens, remove_possible_transfer_double_counts},
+    Classifier, TxTreeResult,
+};
+
+pub struct IncrementalTreeBuilder<'c, 'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> {
+    classifier: &'c Classifier<'db, T, DB>,
+    header:     Header,
+    /// Transactions that are still being classified
+    pending:    FuturesUnordered<BoxFuture<'c, Option<TxTreeResult>>>,
+    tx_roots:   Vec<TxTreeResult>,
+    /// Traces pushed so far, including those of failed transactions
+    pushed:     usize,
+}
+
+impl<'c, 'db, T: TracingProvider, DB: LibmdbxReader + DBWriter>
+    IncrementalTreeBuilder<'c, 'db, T, DB>
+{
+    pub(crate) fn new(
+        classifier: &'c Classifier<'db, T, DB>,
+        header: Header,
+        generate_pricing: bool,
+    ) -> Self {
+        if !generate_pricing {
+            classifier
+                .pricing_update_sender
+                .send(DexPriceMsg::DisablePricingFor(header.number))
+                .unwrap();
+        }
+
+        Self { classifier, header, pending: FuturesUnordered::new(), tx_roots: vec![], pushed: 0 }
+    }
+
+    /// Starts classifying the transaction's tree. Traces have to be pushed in
+    /// block order, as a transaction can trade through pools created earlier
+    /// in the block
+    pub async fn push(&mut self, trace: TxTrace) {
+        self.classifier
+            .register_created_pools(self.header.number, std::slice::from_ref(&trace))
+            .await;
+
+        let classifier = self.classifier;
+        let header = self.header.clone();
+        let tx_idx = self.pushed;
+        self.pushed += 1;
+        self.pending
+            .push(async move { classifier.build_tx_tree(trace, &header, tx_idx).await }.boxed());
+    }
+
+    /// Number of traces pushed so far
+    pub fn pushed(&self) -> usize {
+        self.pushed
+    }
+
+    /// Waits for the next pushed transaction to be classified. Returns false
+    /// if there is nothing left to classify
+    pub async fn classify_next(&mut self) -> bool {
+        let Some(root) = self.pending.next().await else { return false };
+        self.tx_roots.extend(root);
+
+        true
+    }
+
+    /// Builds the block's tree once all of its traces are pushed
+    pub async fn finalize(mut self) -> BlockTree<Action> {
+        while self.classify_next().await {}
+        self.tx_roots.sort_by_key(|root| root.root.position);
+
+        let block_number = self.header.number;
+        let mut tree = BlockTree::new(self.header, self.tx_roots.len());
+        let further_classification_requests =
+            self.classifier
+                .process_tx_roots(self.tx_roots, &mut tree, block_number);
+
+        account_for_tax_tokens(&mut tree);
+        remove_possible_transfer_double_counts(&mut tree);
+
+        self.classifier
+            .finish_classification(&mut tree, further_classification_requests);
+        tree.finalize_tree();
+
+        tree
+    }
+}
+
+impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, DB> {
+    /// Starts the tree of a block whose traces are pushed one at a time
+    pub fn incremental_tree_builder(
+        &self,
+        header: Header,
+        generate_pricing: bool,
+    ) -> IncrementalTreeBuilder<'_, 'db, T, DB> {
+        IncrementalTreeBuilder::new(self, header, generate_pricing)
+    }
+
+    /// Builds the block's tree from its traces as they're streamed from the
+    /// tracer, classifying transactions while later ones are still being
+    /// traced. The block is complete when the stream ends, and fails on the
+    /// first trace the stream couldn't produce
+    pub async fn build_block_tree_from_stream(
+        &self,
+        traces: impl Stream<Item = eyre::Result<TxTrace>>,
+        header: Header,
+        generate_pricing: bool,
+    ) -> eyre::Result<BlockTree<Action>> {
+        let mut builder = self.incremental_tree_builder(header, generate_pricing);
+        futures::pin_mut!(traces);
+
+        loop {
+            tokio::select! {
+                trace = traces.next() => match trace {
+                    Some(trace) => builder.push(trace?).await,
+                    None => break,
+                },
+                true = builder.classify_next() => {}
+            }
+        }
+
+        Ok(builder.finalize().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use brontes_types::{BlockTree, TreeSearchBuilder};
+
+    use crate::{test_utils::ClassifierTestUtils, Action};
+
+    #[brontes_macros::test]
+    async fn test_incremental_tree_matches_batch() {
+        let classifier_utils = ClassifierTestUtils::new().await;
+        let batch = classifier_utils.build_block_tree(18500018).await.unwrap();
+        let incremental = classifier_utils
+            .build_block_tree_incremental(18500018)
+            .await
+            .unwrap();
+
+        let tx_hashes = |tree: &BlockTree<Action>| {
+            tree.roots()
+                .iter()
+                .map(|root| root.tx_hash)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tx_hashes(&batch), tx_hashes(&incremental));
+
+        let swaps = TreeSearchBuilder::default().with_action(Action::is_swap);
+        let batch_swaps = Arc::new(batch)
+            .collect_all(swaps.clone())
+            .collect::<Vec<_>>();
+        let incremental_swaps = Arc::new(incremental).collect_all(swaps).collect::<Vec<_>>();
+        assert_eq!(batch_swaps, incremental_swaps);
+    }
+}
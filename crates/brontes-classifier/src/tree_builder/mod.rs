@@ -5,24 +5,31 @@ use brontes_core::missing_token_info::load_missing_token_info;
 use brontes_pricing::types::PoolUpdate;
 use brontes_types::{
     normalized_actions::{
-        pool::NormalizedNewPool, MultiCallFrameClassification, MultiFrameRequest, NormalizedAction,
-        NormalizedEthTransfer, NormalizedTransfer,
+        pool::NormalizedNewPool, MultiCallFrameClassification, MultiFrameRequest, NftStandard,
+        NormalizedAction, NormalizedEthTransfer, NormalizedNftTransfer, NormalizedTransfer,
     },
     tree::root::NodeData,
     ToScaledRational,
 };
 use malachite::{num::basic::traits::Zero, Rational};
 
+mod incremental;
+mod pool_watcher;
 mod tree_pruning;
 pub(crate) mod utils;
 use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
 use brontes_pricing::types::DexPriceMsg;
 use brontes_types::{
-    normalized_actions::{Action, SelfdestructWithIndex},
+    normalized_actions::{Action, DecodeOutcome, DecodeSource, SelfdestructWithIndex},
     structured_trace::{TraceActions, TransactionTraceWithLogs, TxTrace},
     traits::TracingProvider,
-    tree::{BlockTree, GasDetails, Node, Root},
+    tree::{
+        spill::{MemoryBound, TraceSpill},
+        BlockTree, GasDetails, Node, Root,
+    },
+    FastHashMap,
 };
+use eyre::WrapErr;
 use futures::future::join_all;
 use itertools::Itertools;
 use malachite::num::arithmetic::traits::Abs;
@@ -31,11 +38,13 @@ use reth_rpc_types::trace::parity::{Action as TraceAction, CallType};
 use tokio::sync::mpsc::UnboundedSender;
 use tracing::{error, trace};
 use tree_pruning::{account_for_tax_tokens, remove_possible_transfer_double_counts};
-use utils::{decode_transfer, get_coinbase_transfer};
+use utils::{decode_nft_transfer, decode_transfer, get_coinbase_transfer, get_miner_payment};
 
 use self::erc20::try_decode_transfer;
+pub use self::incremental::IncrementalTreeBuilder;
 use crate::{
-    classifiers::*, multi_frame_classification::parse_multi_frame_requests, ActionCollection,
+    binding_cache::ProtocolBindings, classifiers::*,
+    multi_frame_classification::parse_multi_frame_requests, ActionCollection,
     FactoryDiscoveryDispatch,
 };
 
@@ -45,6 +54,8 @@ pub struct Classifier<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> {
     libmdbx:               &'db DB,
     provider:              Arc<T>,
     pricing_update_sender: UnboundedSender<DexPriceMsg>,
+    memory_bound:          Option<MemoryBound>,
+    protocol_bindings:     Arc<ProtocolBindings>,
 }
 
 impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, DB> {
@@ -53,7 +64,27 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         pricing_update_sender: UnboundedSender<DexPriceMsg>,
         provider: Arc<T>,
     ) -> Self {
-        Self { libmdbx, pricing_update_sender, provider }
+        Self {
+            libmdbx,
+            pricing_update_sender,
+            provider,
+            memory_bound: None,
+            protocol_bindings: Arc::default(),
+        }
+    }
+
+    /// Builds the trees of blocks with more transactions than the bound in
+    /// chunks, spilling their traces, see [`MemoryBound`]
+    pub fn with_memory_bound(mut self, bound: Option<MemoryBound>) -> Self {
+        self.memory_bound = bound;
+        self
+    }
+
+    /// Looks up the protocols of the addresses called in `bindings`, which
+    /// can be shared with the other classifiers of the run
+    pub fn with_protocol_bindings(mut self, bindings: Arc<ProtocolBindings>) -> Self {
+        self.protocol_bindings = bindings;
+        self
     }
 
     pub fn block_load_failure(&self, number: u64) {
@@ -62,12 +93,15 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
             .unwrap();
     }
 
+    /// Builds the block's tree from its traces. Only fails when the block is
+    /// too large to classify in memory and spilling its traces fails, see
+    /// [`MemoryBound`]
     pub async fn build_block_tree(
         &self,
         traces: Vec<TxTrace>,
         header: Header,
         generate_pricing: bool,
-    ) -> BlockTree<Action> {
+    ) -> eyre::Result<BlockTree<Action>> {
         let block_number = header.number;
         if !generate_pricing {
             self.pricing_update_sender
@@ -75,8 +109,18 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 .unwrap();
         }
 
-        let tx_roots = self.build_tx_trees(traces, &header).await;
+        self.register_created_pools(block_number, &traces).await;
+
+        let memory_bound = self
+            .memory_bound
+            .as_ref()
+            .filter(|bound| bound.applies_to(traces.len()));
+        let tx_roots = match memory_bound {
+            Some(bound) => self.build_tx_trees_spilled(traces, &header, bound).await?,
+            None => self.build_tx_trees(traces, &header).await,
+        };
         let mut tree = BlockTree::new(header, tx_roots.len());
+        tree.memory_bound = memory_bound.cloned();
 
         // send out all updates
         let further_classification_requests =
@@ -88,7 +132,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         self.finish_classification(&mut tree, further_classification_requests);
         tree.finalize_tree();
 
-        tree
+        Ok(tree)
     }
 
     fn process_tx_roots(
@@ -126,143 +170,62 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         results
     }
 
+    /// Builds the trees of a block too large to hold its traces next to its
+    /// tree. The traces are spilled to disk and classified a chunk at a time
+    async fn build_tx_trees_spilled(
+        &self,
+        traces: Vec<TxTrace>,
+        header: &Header,
+        bound: &MemoryBound,
+    ) -> eyre::Result<Vec<TxTreeResult>> {
+        let tx_count = traces.len();
+        let mut spill = TraceSpill::new(bound, header.number, traces)
+            .wrap_err_with(|| format!("failed to spill the traces of block {}", header.number))?;
+        tracing::info!(
+            block = header.number,
+            tx_count,
+            chunks = spill.chunk_count(),
+            "building large block in chunks"
+        );
+
+        let mut tx_roots = Vec::with_capacity(tx_count);
+        let mut first_tx_idx = 0;
+        for chunk in 0..spill.chunk_count() {
+            let traces = spill.read_chunk(chunk).wrap_err_with(|| {
+                format!(
+                    "failed to read chunk {chunk} of the spilled traces of block {}",
+                    header.number
+                )
+            })?;
+            let chunk_len = traces.len();
+            tx_roots.extend(self.build_tx_trees_from(traces, header, first_tx_idx).await);
+            first_tx_idx += chunk_len;
+        }
+
+        Ok(tx_roots)
+    }
+
     pub(crate) async fn build_tx_trees(
         &self,
         traces: Vec<TxTrace>,
         header: &Header,
+    ) -> Vec<TxTreeResult> {
+        self.build_tx_trees_from(traces, header, 0).await
+    }
+
+    /// Builds the trees of the traces, the first of which is at
+    /// `first_tx_idx` in the block
+    async fn build_tx_trees_from(
+        &self,
+        traces: Vec<TxTrace>,
+        header: &Header,
+        first_tx_idx: usize,
     ) -> Vec<TxTreeResult> {
         join_all(
             traces
                 .into_iter()
                 .enumerate()
-                .map(|(tx_idx, mut trace)| async move {
-                    // here only traces where the root tx failed are filtered out
-                    if trace.trace.is_empty() || !trace.is_success {
-                        tracing::trace!(
-                            empty = trace.trace.is_empty(),
-                            is_success = trace.is_success
-                        );
-                        return None
-                    }
-                    // post classification processing collectors
-                    let mut further_classification_requests = Vec::new();
-                    let mut pool_updates: Vec<DexPriceMsg> = Vec::new();
-
-                    let root_trace = trace.trace.remove(0);
-
-                    let address = root_trace.get_from_addr();
-                    let trace_idx = root_trace.trace_idx;
-
-                    let classification = self
-                        .process_classification(
-                            header.number,
-                            None,
-                            &NodeData(vec![]),
-                            tx_idx as u64,
-                            trace_idx,
-                            root_trace,
-                            &trace.trace,
-                            &mut further_classification_requests,
-                            &mut pool_updates,
-                        )
-                        .await;
-
-                    let node = Node::new(trace_idx, address, vec![]);
-
-                    let total_msg_value_transfers = classification
-                        .iter()
-                        .filter_map(|s| s.get_msg_value_not_eth_transfer())
-                        .collect::<Vec<NormalizedEthTransfer>>();
-
-                    let mut tx_root = Root {
-                        position: tx_idx,
-                        head: node,
-                        tx_hash: trace.tx_hash,
-                        private: false,
-                        total_msg_value_transfers,
-                        gas_details: GasDetails {
-                            coinbase_transfer:   None,
-                            gas_used:            trace.gas_used,
-                            effective_gas_price: trace.effective_price,
-                            priority_fee:        trace.effective_price
-                                - (header.base_fee_per_gas.unwrap_or_default() as u128),
-                        },
-                        data_store: NodeData(vec![Some(classification)]),
-                    };
-
-                    let tx_trace = &trace.trace;
-                    for trace in &trace.trace {
-                        let from_addr = trace.get_from_addr();
-
-                        let node = Node::new(
-                            trace.trace_idx,
-                            from_addr,
-                            trace.trace.trace_address.clone(),
-                        );
-
-                        if trace.trace.error.is_none() {
-                            if let Some(coinbase_transfer) =
-                                get_coinbase_transfer(header.beneficiary, &trace.trace.action)
-                            {
-                                if let Some(coinbase) = &mut tx_root.gas_details.coinbase_transfer {
-                                    *coinbase += coinbase_transfer;
-                                } else {
-                                    tx_root.gas_details.coinbase_transfer = Some(coinbase_transfer);
-                                }
-
-                                let classification = Action::EthTransfer(NormalizedEthTransfer {
-                                    from:              from_addr,
-                                    to:                trace.get_to_address(),
-                                    value:             trace.get_msg_value(),
-                                    trace_index:       trace.trace_idx,
-                                    coinbase_transfer: true,
-                                });
-
-                                tx_root.insert(node, vec![classification]);
-                                continue
-                            }
-                        }
-
-                        let classification = self
-                            .process_classification(
-                                header.number,
-                                Some(&tx_root.head),
-                                &tx_root.data_store,
-                                tx_idx as u64,
-                                trace.trace_idx,
-                                trace.clone(),
-                                tx_trace,
-                                &mut further_classification_requests,
-                                &mut pool_updates,
-                            )
-                            .await;
-
-                        tx_root.total_msg_value_transfers.extend(
-                            classification
-                                .iter()
-                                .filter_map(|s| s.get_msg_value_not_eth_transfer()),
-                        );
-
-                        tx_root.insert(node, classification);
-                    }
-
-                    // Here we reverse the requests to ensure that we always classify the most
-                    // nested action & its children first. This is to prevent the
-                    // case where we classify a parent action where its children also require
-                    // further classification.
-                    let tx_classification_requests = if !further_classification_requests.is_empty()
-                    {
-                        further_classification_requests.reverse();
-                        Some((tx_idx, further_classification_requests))
-                    } else {
-                        None
-                    };
-                    Some(TxTreeResult {
-                        root: tx_root,
-                        further_classification_requests: tx_classification_requests,
-                        pool_updates,
-                    })
-                }),
+                .map(|(i, trace)| self.build_tx_tree(trace, header, first_tx_idx + i)),
         )
         .await
         .into_iter()
@@ -270,6 +233,146 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         .collect_vec()
     }
 
+    /// Builds and classifies the tree of the transaction at `tx_idx` in the
+    /// block. Returns nothing for failed transactions
+    async fn build_tx_tree(
+        &self,
+        mut trace: TxTrace,
+        header: &Header,
+        tx_idx: usize,
+    ) -> Option<TxTreeResult> {
+        // here only traces where the root tx failed are filtered out
+        if trace.trace.is_empty() || !trace.is_success {
+            tracing::trace!(empty = trace.trace.is_empty(), is_success = trace.is_success);
+            return None
+        }
+        // post classification processing collectors
+        let mut further_classification_requests = Vec::new();
+        let mut pool_updates: Vec<DexPriceMsg> = Vec::new();
+        let mut decode_outcomes = FastHashMap::default();
+
+        let root_trace = trace.trace.remove(0);
+
+        let address = root_trace.get_from_addr();
+        let trace_idx = root_trace.trace_idx;
+
+        let classification = self
+            .process_classification(
+                header.number,
+                None,
+                &NodeData(vec![]),
+                tx_idx as u64,
+                trace_idx,
+                root_trace,
+                &trace.trace,
+                &mut further_classification_requests,
+                &mut pool_updates,
+                &mut decode_outcomes,
+            )
+            .await;
+
+        let node = Node::new(trace_idx, address, vec![]);
+
+        let total_msg_value_transfers = classification
+            .iter()
+            .filter_map(|s| s.get_msg_value_not_eth_transfer())
+            .collect::<Vec<NormalizedEthTransfer>>();
+
+        let mut tx_root = Root {
+            position: tx_idx,
+            head: node,
+            tx_hash: trace.tx_hash,
+            private: false,
+            total_msg_value_transfers,
+            gas_details: GasDetails {
+                coinbase_transfer:   None,
+                gas_used:            trace.gas_used,
+                effective_gas_price: trace.effective_price,
+                priority_fee:        trace.effective_price
+                    - (header.base_fee_per_gas.unwrap_or_default() as u128),
+            },
+            data_store: NodeData(vec![Some(classification)]),
+            decode_outcomes: FastHashMap::default(),
+        };
+
+        // the difficulty is zeroed from the merge on
+        let miner_era = !header.difficulty.is_zero();
+        let tx_trace = &trace.trace;
+        for trace in &trace.trace {
+            let from_addr = trace.get_from_addr();
+
+            let node = Node::new(trace.trace_idx, from_addr, trace.trace.trace_address.clone());
+
+            if trace.trace.error.is_none() {
+                if let Some(coinbase_transfer) =
+                    get_coinbase_transfer(header.beneficiary, &trace.trace.action)
+                {
+                    if let Some(coinbase) = &mut tx_root.gas_details.coinbase_transfer {
+                        *coinbase += coinbase_transfer;
+                    } else {
+                        tx_root.gas_details.coinbase_transfer = Some(coinbase_transfer);
+                    }
+
+                    let classification = Action::EthTransfer(NormalizedEthTransfer {
+                        from:              from_addr,
+                        to:                trace.get_to_address(),
+                        value:             trace.get_msg_value(),
+                        trace_index:       trace.trace_idx,
+                        coinbase_transfer: true,
+                    });
+
+                    tx_root.insert(node, vec![classification]);
+                    continue
+                }
+                if miner_era {
+                    if let Some(payment) = get_miner_payment(header.beneficiary, trace) {
+                        *tx_root.gas_details.coinbase_transfer.get_or_insert(0) += payment;
+                    }
+                }
+            }
+
+            let classification = self
+                .process_classification(
+                    header.number,
+                    Some(&tx_root.head),
+                    &tx_root.data_store,
+                    tx_idx as u64,
+                    trace.trace_idx,
+                    trace.clone(),
+                    tx_trace,
+                    &mut further_classification_requests,
+                    &mut pool_updates,
+                    &mut decode_outcomes,
+                )
+                .await;
+
+            tx_root.total_msg_value_transfers.extend(
+                classification
+                    .iter()
+                    .filter_map(|s| s.get_msg_value_not_eth_transfer()),
+            );
+
+            tx_root.insert(node, classification);
+        }
+        tx_root.decode_outcomes = decode_outcomes;
+
+        // Here we reverse the requests to ensure that we always classify the most
+        // nested action & its children first. This is to prevent the
+        // case where we classify a parent action where its children also require
+        // further classification.
+        let tx_classification_requests = if !further_classification_requests.is_empty() {
+            further_classification_requests.reverse();
+            Some((tx_idx, further_classification_requests))
+        } else {
+            None
+        };
+        Some(TxTreeResult {
+            root: tx_root,
+            further_classification_requests: tx_classification_requests,
+            pool_updates,
+        })
+    }
+
     async fn process_classification(
         &self,
         block_number: u64,
@@ -281,7 +384,9 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         full_trace: &[TransactionTraceWithLogs],
         further_classification_requests: &mut Vec<MultiFrameRequest>,
         pool_updates: &mut Vec<DexPriceMsg>,
+        decode_outcomes: &mut FastHashMap<u64, DecodeOutcome>,
     ) -> Vec<Action> {
+        let mut outcome = DecodeOutcome::default();
         let (update, classification) = self
             .classify_node(
                 block_number,
@@ -291,9 +396,14 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                 trace,
                 full_trace,
                 trace_index,
+                &mut outcome,
             )
             .await;
 
+        if !outcome.is_clean() {
+            decode_outcomes.insert(trace_index, outcome);
+        }
+
         // Here we are marking more complex actions that require data
         // that can only be retrieved by classifying it's action and
         // all subsequent child actions.
@@ -326,13 +436,14 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         trace: TransactionTraceWithLogs,
         full_trace: &[TransactionTraceWithLogs],
         trace_index: u64,
+        outcome: &mut DecodeOutcome,
     ) -> (Vec<DexPriceMsg>, Vec<Action>) {
         if trace.trace.error.is_some() {
             return (vec![], vec![Action::Revert])
         }
         let (pricing, base_action) = match trace.action_type() {
             TraceAction::Call(_) => {
-                self.classify_call(block, tx_idx, trace.clone(), full_trace, trace_index)
+                self.classify_call(block, tx_idx, trace.clone(), full_trace, trace_index, outcome)
                     .await
             }
             TraceAction::Create(_) => {
@@ -362,6 +473,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         trace: TransactionTraceWithLogs,
         full_trace: &[TransactionTraceWithLogs],
         trace_index: u64,
+        outcome: &mut DecodeOutcome,
     ) -> (Vec<DexPriceMsg>, Vec<Action>) {
         if trace.is_static_call() {
             return (vec![], vec![Action::Unclassified(trace)])
@@ -386,9 +498,13 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
             }
         }
 
-        if let Some(results) =
-            ProtocolClassifier::default().dispatch(call_info, self.libmdbx, block, tx_idx)
-        {
+        if let Some(results) = ProtocolClassifier::default().dispatch(
+            call_info,
+            self.libmdbx,
+            &self.protocol_bindings,
+            block,
+            tx_idx,
+        ) {
             if results.1.is_new_pool() {
                 let Action::NewPool(p) = &results.1 else { unreachable!() };
                 self.insert_new_pool(block, p).await;
@@ -401,12 +517,16 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                     .is_err()
                 {
                     error!(pool=?p.pool_address,"failed to update pool config");
+                } else {
+                    self.protocol_bindings.bind(p.pool_address, p.protocol);
                 }
             }
 
             (vec![results.0], vec![results.1])
+        } else if let Some(nft_transfers) = self.classify_nft_transfer(&trace, trace_index) {
+            (vec![], nft_transfers)
         } else if let Some(transfer) = self
-            .classify_transfer(tx_idx, trace_index, &trace, block)
+            .classify_transfer(tx_idx, trace_index, &trace, block, outcome)
             .await
         {
             return transfer
@@ -426,6 +546,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         trace_idx: u64,
         trace: &TransactionTraceWithLogs,
         block: u64,
+        outcome: &mut DecodeOutcome,
     ) -> Option<(Vec<DexPriceMsg>, Vec<Action>)> {
         if trace.is_delegate_call() {
             return None
@@ -445,6 +566,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         .await
         {
             Ok(mut transfer) => {
+                *outcome = DecodeOutcome::new(DecodeSource::GenericAbi).with_token(&transfer.token);
                 // go through the log to look for discrepancy of transfer amount
                 for log in &trace.logs {
                     if let Some((addr, from, to, amount)) = decode_transfer(log) {
@@ -463,6 +585,7 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                             let fee = (&log_am - &transfer.amount).abs();
                             transfer.amount = transferred_amount;
                             transfer.fee = fee;
+                            outcome.reconciled_amount = true;
                         }
                         break
                     }
@@ -498,6 +621,8 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
                         }
 
                         let token_info = self.libmdbx.try_fetch_token_info(addr).ok()?;
+                        *outcome =
+                            DecodeOutcome::new(DecodeSource::TransferLog).with_token(&token_info);
                         let amount = amount.to_scaled_rational(token_info.decimals);
                         let transfer = NormalizedTransfer {
                             amount,
@@ -525,6 +650,43 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         }
     }
 
+    /// ERC-721 `transferFrom` shares its selector with ERC-20, so NFT
+    /// transfers are told apart by the logs the collection emits rather than
+    /// by calldata.
+    fn classify_nft_transfer(
+        &self,
+        trace: &TransactionTraceWithLogs,
+        trace_index: u64,
+    ) -> Option<Vec<Action>> {
+        if trace.is_delegate_call() {
+            return None
+        }
+
+        let collection = trace.get_to_address();
+        let transfers = trace
+            .logs
+            .iter()
+            .filter_map(decode_nft_transfer)
+            .filter(|log| log.collection == collection)
+            .enumerate()
+            .map(|(i, log)| {
+                Action::NftTransfer(NormalizedNftTransfer {
+                    trace_index,
+                    from: log.from,
+                    to: log.to,
+                    collection,
+                    standard: if log.is_1155 { NftStandard::Erc1155 } else { NftStandard::Erc721 },
+                    token_ids: log.token_ids,
+                    amounts: log.amounts,
+                    // only attribute the call value once
+                    msg_value: if i == 0 { trace.get_msg_value() } else { U256::ZERO },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        (!transfers.is_empty()).then_some(transfers)
+    }
+
     fn classify_eth_transfer(
         &self,
         trace: &TransactionTraceWithLogs,
@@ -557,6 +719,16 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
             return (vec![], vec![Action::Unclassified(trace)])
         }
 
+        // pools registered ahead of classification don't need to be discovered again
+        if let Some(pool) = self.registered_pool(block, created_addr, trace_index) {
+            return match pool.clone().try_into() {
+                Ok(config) => {
+                    (vec![DexPriceMsg::DiscoveredPool(config)], vec![Action::NewPool(pool)])
+                }
+                Err(_) => (vec![], vec![]),
+            }
+        }
+
         // get the immediate parent node of this create action so that we can decode the
         // deployment function params
         let mut all_nodes = Vec::new();
@@ -643,6 +815,8 @@ impl<'db, T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'db, T, D
         {
             error!(pool=?pool.pool_address,"failed to insert discovered pool into libmdbx");
         } else {
+            self.protocol_bindings
+                .bind(pool.pool_address, pool.protocol);
             trace!("Inserting new {} pool: Address:{}", pool.protocol, pool.pool_address);
         }
     }
@@ -0,0 +1,127 @@
+//! Registers the pools factories create in a block before the block is
+//! classified.
+//!
+//! Transactions are classified concurrently, so a call to a pool created
+//! earlier in the same block can be classified before the pool's creation was
+//! discovered, leaving it unclassified. The watcher walks the creates of the
+//! block in order ahead of classification and registers every pool a factory
+//! deployed, so that all transactions of the block classify against them.
+use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
+use brontes_types::{
+    normalized_actions::pool::NormalizedNewPool,
+    structured_trace::{TraceActions, TxTrace},
+    traits::TracingProvider,
+};
+use reth_primitives::Address;
+use reth_rpc_types::trace::parity::Action as TraceAction;
+use tracing::trace;
+
+use super::Classifier;
+use crate::{classifiers::DiscoveryClassifier, FactoryDiscoveryDispatch};
+
+impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> Classifier<'_, T, DB> {
+    /// Discovers the pools created in the block and inserts them into the pool
+    /// registry
+    pub(crate) async fn register_created_pools(&self, block: u64, traces: &[TxTrace]) {
+        for tx in traces.iter().filter(|tx| tx.is_success) {
+            for create in tx
+                .trace
+                .iter()
+                .filter(|t| t.is_create() && t.trace.error.is_none())
+            {
+                let created_addr = create.get_create_output();
+                if created_addr == Address::ZERO {
+                    continue
+                }
+
+                // the factory call is one of the calls the create is nested in
+                let search_data = tx
+                    .trace
+                    .iter()
+                    .filter(|parent| {
+                        matches!(parent.trace.action, TraceAction::Call(_))
+                            && parent.trace_idx < create.trace_idx
+                            && create
+                                .trace
+                                .trace_address
+                                .starts_with(&parent.trace.trace_address)
+                    })
+                    .map(|parent| (parent.get_to_address(), parent.get_calldata()))
+                    .collect::<Vec<_>>();
+
+                if search_data.is_empty() {
+                    continue
+                }
+
+                for pool in DiscoveryClassifier::default()
+                    .dispatch(self.provider.clone(), search_data, created_addr, create.trace_idx)
+                    .await
+                {
+                    trace!(
+                        target: "brontes_classifier::discovery",
+                        pool = ?pool.pool_address,
+                        protocol = %pool.protocol,
+                        "registering pool created in block {block}"
+                    );
+                    self.insert_new_pool(block, &pool).await;
+                }
+            }
+        }
+    }
+
+    /// A pool that was registered as created at the address in this block
+    pub(crate) fn registered_pool(
+        &self,
+        block: u64,
+        address: Address,
+        trace_index: u64,
+    ) -> Option<NormalizedNewPool> {
+        let details = self.libmdbx.get_protocol_details(address).ok()?;
+        (details.init_block == block).then(|| NormalizedNewPool {
+            trace_index,
+            pool_address: address,
+            protocol: details.protocol,
+            tokens: details.get_tokens(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{hex, B256};
+    use brontes_core::TxTracesWithHeaderAnd;
+    use brontes_types::Protocol;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::*;
+    use crate::test_utils::ClassifierTestUtils;
+
+    #[brontes_macros::test]
+    async fn test_register_created_pools() {
+        let utils = ClassifierTestUtils::new().await;
+        let tx =
+            B256::new(hex!("16bba367585045f6c87ec2beca8243575d7a5891f58c1af5e70bc45de4d3e347"));
+        let pool = Address::new(hex!("082366f442ea46a608f3c2c5e7abd5f53a86125b"));
+
+        let TxTracesWithHeaderAnd { trace, block, .. } =
+            utils.get_tx_trace_with_header(tx).await.unwrap();
+
+        let (tx, _rx) = unbounded_channel();
+        let classifier = Classifier::new(utils.libmdbx, tx, utils.get_provider());
+        classifier.register_created_pools(block, &[trace]).await;
+
+        let registered = classifier.registered_pool(block, pool, 1).unwrap();
+        assert_eq!(registered.trace_index, 1);
+        assert_eq!(registered.protocol, Protocol::UniswapV2);
+        assert_eq!(
+            registered.tokens,
+            vec![
+                hex!("52c6889677E514BDD0f09E32003C15B33E88DccE").into(),
+                hex!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2").into(),
+            ]
+        );
+
+        // the pool was created in an earlier block than the one being classified
+        assert_eq!(classifier.registered_pool(block + 1, pool, 1), None);
+    }
+}
@@ -1,4 +1,5 @@
 use alloy_primitives::{Address, FixedBytes, Log, B256, U256};
+use brontes_types::{constants::WETH_ADDRESS, structured_trace::TransactionTraceWithLogs};
 use hex_literal::hex;
 use reth_rpc_types::trace::parity::Action;
 
@@ -14,6 +15,24 @@ pub(crate) fn get_coinbase_transfer(builder: Address, action: &Action) -> Option
     }
 }
 
+/// Payments to a miner that aren't a call to the coinbase. Before the merge
+/// searchers also paid miners by self destructing into the coinbase or by
+/// sending it WETH, which the miner took as ETH
+pub(crate) fn get_miner_payment(miner: Address, trace: &TransactionTraceWithLogs) -> Option<u128> {
+    if let Action::Selfdestruct(selfdestruct) = &trace.trace.action {
+        return (selfdestruct.refund_address == miner && !selfdestruct.balance.is_zero())
+            .then(|| selfdestruct.balance.to())
+    }
+
+    trace
+        .logs
+        .iter()
+        .filter_map(decode_transfer)
+        .filter(|(token, _, to, _)| *token == WETH_ADDRESS && *to == miner)
+        .map(|(.., amount)| amount.saturating_to::<u128>())
+        .reduce(|a, b| a.saturating_add(b))
+}
+
 const TRANSFER_TOPIC: B256 =
     FixedBytes(hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"));
 
@@ -31,3 +50,194 @@ pub(crate) fn decode_transfer(log: &Log) -> Option<(Address, Address, Address, U
 
     None
 }
+
+const TRANSFER_SINGLE_TOPIC: B256 =
+    FixedBytes(hex!("c3d58168c5ae7397731d063d5bbf3d657854427343f4c083240f7aacaa2d0f62"));
+
+const TRANSFER_BATCH_TOPIC: B256 =
+    FixedBytes(hex!("4a39dc06d4c0dbc64b70af90fd698a233a518aa5d07e595d983b8c0526c8f7fb"));
+
+/// A decoded ERC-721 `Transfer` or ERC-1155 `TransferSingle` /
+/// `TransferBatch` log
+pub(crate) struct NftTransferLog {
+    pub collection: Address,
+    pub from:       Address,
+    pub to:         Address,
+    pub is_1155:    bool,
+    pub token_ids:  Vec<U256>,
+    pub amounts:    Vec<U256>,
+}
+
+pub(crate) fn decode_nft_transfer(log: &Log) -> Option<NftTransferLog> {
+    let topics = log.topics();
+    let data = &log.data.data[..];
+
+    match topics.first()? {
+        // ERC-721 shares the ERC-20 signature but indexes the token id
+        topic if *topic == TRANSFER_TOPIC && topics.len() == 4 => Some(NftTransferLog {
+            collection: log.address,
+            from:       Address::from_slice(&topics[1][12..]),
+            to:         Address::from_slice(&topics[2][12..]),
+            is_1155:    false,
+            token_ids:  vec![U256::from_be_bytes(topics[3].0)],
+            amounts:    vec![U256::from(1)],
+        }),
+        topic if *topic == TRANSFER_SINGLE_TOPIC && topics.len() == 4 && data.len() == 64 => {
+            Some(NftTransferLog {
+                collection: log.address,
+                from:       Address::from_slice(&topics[2][12..]),
+                to:         Address::from_slice(&topics[3][12..]),
+                is_1155:    true,
+                token_ids:  vec![U256::try_from_be_slice(&data[..32])?],
+                amounts:    vec![U256::try_from_be_slice(&data[32..])?],
+            })
+        }
+        topic if *topic == TRANSFER_BATCH_TOPIC && topics.len() == 4 => {
+            let token_ids = decode_abi_u256_array(data, 0)?;
+            let amounts = decode_abi_u256_array(data, 32)?;
+            if token_ids.len() != amounts.len() {
+                return None
+            }
+
+            Some(NftTransferLog {
+                collection: log.address,
+                from: Address::from_slice(&topics[2][12..]),
+                to: Address::from_slice(&topics[3][12..]),
+                is_1155: true,
+                token_ids,
+                amounts,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Decodes a dynamic `uint256[]` whose offset is stored at `head` in abi
+/// encoded `data`
+fn decode_abi_u256_array(data: &[u8], head: usize) -> Option<Vec<U256>> {
+    let word = |at: usize| -> Option<usize> {
+        U256::try_from_be_slice(data.get(at..at + 32)?)?
+            .try_into()
+            .ok()
+    };
+
+    let offset = word(head).filter(|offset| *offset < data.len())?;
+    let len = word(offset).filter(|len| *len <= data.len() / 32)?;
+    (0..len)
+        .map(|i| U256::try_from_be_slice(data.get(offset + 32 * (i + 1)..offset + 32 * (i + 2))?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::LogData;
+    use reth_rpc_types::trace::parity::{SelfdestructAction, TransactionTrace};
+
+    use super::*;
+
+    fn trace(action: Action, logs: Vec<Log>) -> TransactionTraceWithLogs {
+        TransactionTraceWithLogs {
+            trace: TransactionTrace {
+                action,
+                error: None,
+                result: None,
+                subtraces: 0,
+                trace_address: vec![1],
+            },
+            logs,
+            msg_sender: Address::ZERO,
+            trace_idx: 1,
+            decoded_data: None,
+        }
+    }
+
+    #[test]
+    fn test_miner_payments() {
+        let miner = Address::repeat_byte(9);
+        let selfdestruct = |refund_address| {
+            Action::Selfdestruct(SelfdestructAction {
+                address: Address::repeat_byte(1),
+                refund_address,
+                balance: U256::from(5),
+            })
+        };
+        assert_eq!(get_miner_payment(miner, &trace(selfdestruct(miner), vec![])), Some(5));
+        assert_eq!(
+            get_miner_payment(miner, &trace(selfdestruct(Address::repeat_byte(2)), vec![])),
+            None
+        );
+
+        let transfer = |token, to: Address, amount| Log {
+            address: token,
+            data:    LogData::new_unchecked(
+                vec![TRANSFER_TOPIC, B256::ZERO, to.into_word()],
+                word(amount).to_vec().into(),
+            ),
+        };
+        let logs = vec![
+            transfer(WETH_ADDRESS, miner, 3),
+            transfer(WETH_ADDRESS, miner, 4),
+            // not weth, and weth that didn't go to the miner
+            transfer(Address::repeat_byte(3), miner, 100),
+            transfer(WETH_ADDRESS, Address::repeat_byte(2), 100),
+        ];
+        assert_eq!(
+            get_miner_payment(miner, &trace(Action::Call(Default::default()), logs)),
+            Some(7)
+        );
+        assert_eq!(
+            get_miner_payment(miner, &trace(Action::Call(Default::default()), vec![])),
+            None
+        );
+    }
+
+    fn word(value: u64) -> [u8; 32] {
+        U256::from(value).to_be_bytes()
+    }
+
+    #[test]
+    fn test_decode_nft_transfers() {
+        let collection = Address::repeat_byte(1);
+        let from = B256::left_padding_from(&[2; 20]);
+        let to = B256::left_padding_from(&[3; 20]);
+
+        let erc721 = Log {
+            address: collection,
+            data:    LogData::new_unchecked(
+                vec![TRANSFER_TOPIC, from, to, B256::from(word(7))],
+                Default::default(),
+            ),
+        };
+        let decoded = decode_nft_transfer(&erc721).unwrap();
+        assert!(!decoded.is_1155);
+        assert_eq!(decoded.to, Address::repeat_byte(3));
+        assert_eq!(decoded.token_ids, vec![U256::from(7)]);
+        // erc20 transfers have no indexed amount
+        assert!(decode_nft_transfer(&Log {
+            address: collection,
+            data:    LogData::new_unchecked(
+                vec![TRANSFER_TOPIC, from, to],
+                word(7).to_vec().into()
+            ),
+        })
+        .is_none());
+
+        // ids [1, 2] with amounts [10, 20]
+        let data = [64, 160, 2, 1, 2, 2, 10, 20]
+            .into_iter()
+            .flat_map(word)
+            .collect::<Vec<_>>();
+        let batch = Log {
+            address: collection,
+            data:    LogData::new_unchecked(
+                vec![TRANSFER_BATCH_TOPIC, B256::ZERO, from, to],
+                data.into(),
+            ),
+        };
+        let decoded = decode_nft_transfer(&batch).unwrap();
+        assert!(decoded.is_1155);
+        assert_eq!(decoded.from, Address::repeat_byte(2));
+        assert_eq!(decoded.token_ids, vec![U256::from(1), U256::from(2)]);
+        assert_eq!(decoded.amounts, vec![U256::from(10), U256::from(20)]);
+    }
+}
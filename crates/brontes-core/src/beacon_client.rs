@@ -0,0 +1,182 @@
+//! Minimal consensus layer client used to attach slot and proposer data to
+//! execution blocks.
+//!
+//! Only the standard beacon node REST api is used, so any consensus client
+//! (lighthouse, prysm, teku, ...) works as a backend.
+use std::time::Duration;
+
+use brontes_types::db::beacon::BeaconBlockInfo;
+use eyre::{eyre, WrapErr};
+use reqwest::StatusCode;
+use reth_rpc_types::beacon::BlsPublicKey;
+use serde::{de::DeserializeOwned, Deserialize};
+use tokio::sync::OnceCell;
+
+const SECONDS_PER_SLOT: u64 = 12;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct BeaconClient {
+    client:       reqwest::Client,
+    endpoint:     String,
+    genesis_time: OnceCell<u64>,
+}
+
+impl BeaconClient {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            client:       reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap(),
+            endpoint:     endpoint.into().trim_end_matches('/').to_string(),
+            genesis_time: OnceCell::new(),
+        }
+    }
+
+    /// Fetches the consensus layer data for the given execution block. The
+    /// parent timestamp is used to derive which slots were missed between the
+    /// parent block and this block.
+    pub async fn fetch_block_info(
+        &self,
+        block_number: u64,
+        timestamp: u64,
+        parent_timestamp: u64,
+    ) -> eyre::Result<BeaconBlockInfo> {
+        let slot = self.slot_for_timestamp(timestamp).await?;
+        let parent_slot = self.slot_for_timestamp(parent_timestamp).await?;
+
+        let block = self
+            .get::<BeaconBlockResponse>(&format!("/eth/v2/beacon/blocks/{slot}"))
+            .await?
+            .ok_or_else(|| eyre!("no beacon block found for slot {slot}"))?
+            .data
+            .message;
+
+        let payload_block = block
+            .body
+            .execution_payload
+            .ok_or_else(|| eyre!("slot {slot} predates the merge"))?
+            .block_number
+            .parse::<u64>()?;
+
+        if payload_block != block_number {
+            return Err(eyre!(
+                "slot {slot} contains block {payload_block}, expected block {block_number}"
+            ))
+        }
+
+        let proposer_index = block.proposer_index.parse::<u64>()?;
+        let proposer_pubkey = match self.proposer_pubkey(proposer_index).await {
+            Ok(pubkey) => pubkey,
+            Err(e) => {
+                tracing::warn!(err=%e, proposer_index, "failed to fetch proposer pubkey");
+                None
+            }
+        };
+
+        Ok(BeaconBlockInfo {
+            slot,
+            proposer_index,
+            proposer_pubkey,
+            missed_slots: (parent_slot + 1..slot).collect(),
+        })
+    }
+
+    /// Validator pubkeys never change, so we can always resolve them from the
+    /// head state, which doesn't require an archive beacon node.
+    async fn proposer_pubkey(&self, proposer_index: u64) -> eyre::Result<Option<BlsPublicKey>> {
+        Ok(self
+            .get::<ValidatorResponse>(&format!(
+                "/eth/v1/beacon/states/head/validators/{proposer_index}"
+            ))
+            .await?
+            .map(|res| res.data.validator.pubkey))
+    }
+
+    async fn slot_for_timestamp(&self, timestamp: u64) -> eyre::Result<u64> {
+        let genesis_time = *self
+            .genesis_time
+            .get_or_try_init(|| async {
+                self.get::<GenesisResponse>("/eth/v1/beacon/genesis")
+                    .await?
+                    .ok_or_else(|| eyre!("beacon node didn't return genesis"))?
+                    .data
+                    .genesis_time
+                    .parse::<u64>()
+                    .wrap_err("invalid genesis time")
+            })
+            .await?;
+
+        timestamp
+            .checked_sub(genesis_time)
+            .map(|since_genesis| since_genesis / SECONDS_PER_SLOT)
+            .ok_or_else(|| eyre!("timestamp {timestamp} predates the beacon chain"))
+    }
+
+    /// Returns `None` if the resource doesn't exist, i.e a missed slot.
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> eyre::Result<Option<T>> {
+        let res = self
+            .client
+            .get(format!("{}{path}", self.endpoint))
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None)
+        }
+
+        Ok(Some(res.error_for_status()?.json::<T>().await?))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GenesisResponse {
+    data: Genesis,
+}
+
+#[derive(Debug, Deserialize)]
+struct Genesis {
+    genesis_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockResponse {
+    data: SignedBeaconBlock,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignedBeaconBlock {
+    message: BeaconBlock,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlock {
+    proposer_index: String,
+    body:           BeaconBlockBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct BeaconBlockBody {
+    #[serde(default)]
+    execution_payload: Option<ExecutionPayload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecutionPayload {
+    block_number: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ValidatorResponse {
+    data: ValidatorData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ValidatorData {
+    validator: Validator,
+}
+
+#[derive(Debug, Deserialize)]
+struct Validator {
+    pubkey: BlsPublicKey,
+}
@@ -1,8 +1,8 @@
 use std::{pin::Pin, sync::Arc};
 
 use brontes_database::libmdbx::{DBWriter, LibmdbxReader};
-use brontes_types::structured_trace::TxTrace;
 pub use brontes_types::traits::TracingProvider;
+use brontes_types::{structured_trace::TxTrace, traits::TxTraceStream};
 use futures::Future;
 use reth_primitives::{BlockNumberOrTag, Header, B256};
 use tokio::sync::mpsc::UnboundedSender;
@@ -28,6 +28,9 @@ use reth_primitives::BlockId;
 pub type ParserFuture =
     Pin<Box<dyn Future<Output = Option<(Vec<TxTrace>, Header)>> + Send + 'static>>;
 
+pub type StreamedParserFuture =
+    Pin<Box<dyn Future<Output = Option<(TxTraceStream, Header)>> + Send + 'static>>;
+
 pub type TraceClickhouseFuture = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
 
 pub struct Parser<T: TracingProvider, DB: LibmdbxReader + DBWriter> {
@@ -82,6 +85,16 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> Parser<T, DB> {
         }
     }
 
+    /// executes the tracing of a given block, handing out each transaction's
+    /// trace as soon as it has been traced
+    pub fn execute_streamed(&self, block_num: u64) -> StreamedParserFuture {
+        // This will satisfy its lifetime scope do to the lifetime itself living longer
+        // than the process that runs brontes.
+        let parser = self.parser.clone();
+
+        Box::pin(parser.execute_block_streamed(block_num)) as StreamedParserFuture
+    }
+
     /// ensures no libmdbx write
     pub fn execute_discovery(&self, block_num: u64) -> ParserFuture {
         // This will satisfy its lifetime scope do to the lifetime itself living longer
@@ -5,9 +5,10 @@ use alloy_json_abi::JsonAbi;
 #[cfg(feature = "dyn-decode")]
 use alloy_primitives::Address;
 use brontes_metrics::trace::types::{BlockStats, TraceParseErrorKind, TransactionStats};
+use brontes_types::traits::TxTraceStream;
 #[cfg(feature = "dyn-decode")]
 use brontes_types::FastHashMap;
-use futures::future::join_all;
+use futures::{future::join_all, stream, StreamExt};
 #[cfg(feature = "dyn-decode")]
 use reth_rpc_types::trace::parity::Action;
 use reth_rpc_types::{AnyReceiptEnvelope, Log, TransactionReceipt};
@@ -146,6 +147,62 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> TraceParser<T, DB> {
         Some((traces.0, traces.2))
     }
 
+    /// Like [`Self::execute_block`], but hands out each transaction's trace as
+    /// soon as the tracer has produced it, so that the block's tree can be
+    /// built while the rest of the block is still being traced. The traces are
+    /// stored once the whole block is traced
+    #[allow(unreachable_code)]
+    pub async fn execute_block_streamed(self, block_num: u64) -> Option<(TxTraceStream, Header)> {
+        if let Some((traces, header)) = self.load_block_from_db(block_num).await {
+            tracing::debug!(%block_num, traces_in_block= traces.len(),"loaded trace for db");
+            return Some((stream::iter(traces.into_iter().map(Ok)).boxed(), header))
+        }
+        #[cfg(not(feature = "local-reth"))]
+        {
+            tracing::error!("no block found in db");
+            return None
+        }
+
+        let (receipts, stats) = self.get_receipts(block_num).await;
+        let Some(receipts) = receipts else {
+            let _ = self
+                .metrics_tx
+                .send(TraceMetricEvent::BlockMetricRecieved(stats).into());
+            return None
+        };
+        let header = self.tracer.header_by_number(block_num).await.ok()??;
+
+        let traces = match self
+            .tracer
+            .replay_block_transactions_streamed(BlockId::Number(BlockNumberOrTag::Number(
+                block_num,
+            )))
+            .await
+        {
+            Ok(traces) => traces,
+            Err(e) => {
+                let stats =
+                    BlockStats::new(block_num, Some((&Into::<TraceParseError>::into(e)).into()));
+                let _ = self
+                    .metrics_tx
+                    .send(TraceMetricEvent::BlockMetricRecieved(stats).into());
+                return None
+            }
+        };
+
+        let block = StreamedBlock {
+            parser: self,
+            block_num,
+            traces,
+            receipts: receipts.into_iter(),
+            parsed: vec![],
+            stats: BlockStats::new(block_num, None),
+        };
+        let traces = stream::unfold(Some(block), |block| async move { block?.next().await });
+
+        Some((traces.boxed(), header))
+    }
+
     #[allow(unreachable_code)]
     pub async fn execute_block_discovery(self, block_num: u64) -> Option<(Vec<TxTrace>, Header)> {
         if let Some(res) = self.load_block_from_db(block_num).await {
@@ -361,3 +418,76 @@ impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> TraceParser<T, DB> {
         (tx_trace, stats)
     }
 }
+
+/// A block whose traces are being streamed from the tracer, see
+/// [`TraceParser::execute_block_streamed`]
+struct StreamedBlock<T: TracingProvider, DB: LibmdbxReader + DBWriter> {
+    parser:    TraceParser<T, DB>,
+    block_num: u64,
+    traces:    TxTraceStream,
+    receipts:  std::vec::IntoIter<TransactionReceipt<AnyReceiptEnvelope<Log>>>,
+    /// Traces handed out so far, stored once the block is complete
+    parsed:    Vec<TxTrace>,
+    stats:     BlockStats,
+}
+
+impl<T: TracingProvider, DB: LibmdbxReader + DBWriter> StreamedBlock<T, DB> {
+    /// Fills in the next trace from its receipt. The stream ends after the
+    /// first error, so that a partially traced block is never taken for a
+    /// complete one
+    async fn next(mut self) -> Option<(eyre::Result<TxTrace>, Option<Self>)> {
+        let trace = match self.traces.next().await {
+            Some(Ok(trace)) => trace,
+            Some(Err(e)) => return Some((Err(e), None)),
+            None => {
+                self.finish().await;
+                return None
+            }
+        };
+        let Some(receipt) = self.receipts.next() else {
+            let err = eyre::eyre!(
+                "no receipt for transaction {:?} of block {}",
+                trace.tx_hash,
+                self.block_num
+            );
+            return Some((Err(err), None))
+        };
+
+        let tx_hash = trace.tx_hash;
+        let (trace, stats) = self
+            .parser
+            .parse_transaction(
+                trace,
+                #[cfg(feature = "dyn-decode")]
+                &FastHashMap::default(),
+                self.block_num,
+                tx_hash,
+                receipt.transaction_index.unwrap(),
+                receipt.gas_used,
+                receipt.effective_gas_price,
+            )
+            .await;
+        self.stats.txs.push(stats);
+        self.parsed.push(trace.clone());
+
+        Some((Ok(trace), Some(self)))
+    }
+
+    async fn finish(self) {
+        self.stats.trace();
+        let _ = self
+            .parser
+            .metrics_tx
+            .send(TraceMetricEvent::BlockMetricRecieved(self.stats).into());
+
+        if self
+            .parser
+            .libmdbx
+            .save_traces(self.block_num, self.parsed)
+            .await
+            .is_err()
+        {
+            error!(block_num = self.block_num, "failed to store traces for block");
+        }
+    }
+}
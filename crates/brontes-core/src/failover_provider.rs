@@ -0,0 +1,558 @@
+use std::{
+    fmt::Display,
+    path::PathBuf,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use alloy_rpc_types::AnyReceiptEnvelope;
+use alloy_transport::{RpcError, TransportError};
+use brontes_types::{
+    db::block_costs::record_block_cost, structured_trace::TxTrace, traits::TracingProvider,
+    BrontesTaskExecutor,
+};
+use futures::Future;
+use reqwest::Url;
+use reth_primitives::{
+    Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue, TxHash,
+    B256,
+};
+use reth_rpc_types::{
+    state::StateOverride, BlockOverrides, Log, TransactionReceipt, TransactionRequest,
+};
+use tokio::{sync::OnceCell, time::Instant};
+use tracing::{info, warn};
+
+use crate::local_provider::LocalProvider;
+
+/// Consecutive request failures before an endpoint is taken out of rotation
+/// until the next successful health check.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+/// Amount of blocks an endpoint can lag behind the best known endpoint before
+/// it is considered unhealthy.
+const MAX_BLOCK_LAG: u64 = 5;
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// Json rpc error code nodes answer reverted calls with
+const EXECUTION_ERROR_CODE: i64 = 3;
+
+/// Location of a node endpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EndpointKind {
+    Http(Url),
+    Ipc(PathBuf),
+}
+
+/// A single node endpoint along with its request budget.
+///
+/// Parsed from `<endpoint>[|<max requests per second>]`, where the endpoint is
+/// either a `http(s)://` url, an `ipc://` prefixed path or a plain path to an
+/// IPC socket. e.g `http://archive-a:8545|200` or `/tmp/reth.ipc`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointConfig {
+    pub kind:                 EndpointKind,
+    pub max_requests_per_sec: Option<u32>,
+}
+
+impl EndpointConfig {
+    pub fn new(kind: EndpointKind, max_requests_per_sec: Option<u32>) -> Self {
+        Self { kind, max_requests_per_sec }
+    }
+}
+
+impl FromStr for EndpointConfig {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (endpoint, rate_limit) = match s.trim().split_once('|') {
+            Some((endpoint, limit)) => (endpoint.trim(), Some(limit.trim().parse::<u32>()?)),
+            None => (s.trim(), None),
+        };
+
+        if endpoint.is_empty() {
+            eyre::bail!("empty endpoint in '{s}'")
+        }
+        if rate_limit == Some(0) {
+            eyre::bail!("rate limit for '{endpoint}' must be greater than zero")
+        }
+
+        let kind = if let Some(path) = endpoint.strip_prefix("ipc://") {
+            EndpointKind::Ipc(PathBuf::from(path))
+        } else if endpoint.ends_with(".ipc") || endpoint.starts_with('/') {
+            EndpointKind::Ipc(PathBuf::from(endpoint))
+        } else {
+            let url = Url::parse(endpoint)
+                .map_err(|e| eyre::eyre!("invalid endpoint url '{endpoint}': {e}"))?;
+            if !matches!(url.scheme(), "http" | "https") {
+                eyre::bail!("unsupported endpoint '{endpoint}', expected http(s) url or ipc path")
+            }
+            EndpointKind::Http(url)
+        };
+
+        Ok(Self::new(kind, rate_limit))
+    }
+}
+
+impl Display for EndpointKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(url) => write!(f, "{url}"),
+            Self::Ipc(path) => write!(f, "ipc://{}", path.display()),
+        }
+    }
+}
+
+/// Spaces out requests so that no more than the configured amount are sent
+/// per second.
+#[derive(Debug)]
+struct RateLimiter {
+    interval:  Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(max_requests_per_sec: u32) -> Self {
+        Self {
+            interval:  Duration::from_secs(1) / max_requests_per_sec,
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    async fn acquire(&self) {
+        let slot = {
+            let mut next = self.next_slot.lock().unwrap();
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    kind:                 EndpointKind,
+    retries:              u8,
+    /// connected lazily as ipc sockets need an async handshake
+    provider:             OnceCell<LocalProvider>,
+    rate_limiter:         Option<RateLimiter>,
+    healthy:              AtomicBool,
+    consecutive_failures: AtomicU32,
+}
+
+impl Endpoint {
+    fn new(config: EndpointConfig, retries: u8) -> Self {
+        Self {
+            kind: config.kind,
+            retries,
+            provider: OnceCell::new(),
+            rate_limiter: config.max_requests_per_sec.map(RateLimiter::new),
+            healthy: AtomicBool::new(true),
+            consecutive_failures: AtomicU32::new(0),
+        }
+    }
+
+    async fn provider(&self) -> eyre::Result<LocalProvider> {
+        self.provider
+            .get_or_try_init(|| async {
+                match &self.kind {
+                    EndpointKind::Http(url) => {
+                        Ok(LocalProvider::new_http(url.clone(), self.retries))
+                    }
+                    EndpointKind::Ipc(path) => {
+                        LocalProvider::new_ipc(path.clone(), self.retries).await
+                    }
+                }
+            })
+            .await
+            .cloned()
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+    }
+
+    fn record_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= MAX_CONSECUTIVE_FAILURES && self.healthy.swap(false, Ordering::Relaxed) {
+            warn!(endpoint=%self.kind, %failures, "endpoint marked unhealthy");
+        }
+    }
+}
+
+/// A [`TracingProvider`] that spreads over multiple node endpoints.
+///
+/// Requests go to the endpoint that last served a request successfully,
+/// starting with the first configured one. When a request fails it is retried
+/// on the next healthy endpoint in order of configuration, and endpoints that
+/// repeatedly fail or fall behind the chain are skipped until a background
+/// health check sees them recover. This way a single flaky archive node
+/// doesn't stall long backfills.
+#[derive(Debug, Clone)]
+pub struct FailoverProvider {
+    endpoints: Arc<Vec<Endpoint>>,
+    /// index of the endpoint that last served a request successfully
+    primary:   Arc<AtomicUsize>,
+}
+
+impl FailoverProvider {
+    pub fn new(endpoints: Vec<EndpointConfig>, retries: u8) -> eyre::Result<Self> {
+        let endpoints = endpoints
+            .into_iter()
+            .map(|config| Endpoint::new(config, retries))
+            .collect::<Vec<_>>();
+
+        if endpoints.is_empty() {
+            eyre::bail!("no tracing endpoints configured")
+        }
+
+        Ok(Self { endpoints: Arc::new(endpoints), primary: Arc::new(AtomicUsize::new(0)) })
+    }
+
+    /// Spawns a task that periodically checks every endpoint, putting
+    /// endpoints that recovered back into rotation and taking endpoints that
+    /// lag behind the chain out of it.
+    pub fn spawn_health_checks(&self, executor: &BrontesTaskExecutor, interval: Option<Duration>) {
+        let this = self.clone();
+        let interval = interval.unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL);
+
+        executor.spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                this.run_health_check().await;
+            }
+        });
+    }
+
+    pub async fn run_health_check(&self) {
+        let heads = futures::future::join_all(self.endpoints.iter().map(|endpoint| async move {
+            let provider = endpoint.provider().await.ok()?;
+            provider.best_block_number().await.ok()
+        }))
+        .await;
+
+        let best = heads.iter().flatten().copied().max().unwrap_or_default();
+
+        for (endpoint, head) in self.endpoints.iter().zip(heads) {
+            let healthy = match head {
+                Some(head) => best.saturating_sub(head) <= MAX_BLOCK_LAG,
+                None => false,
+            };
+
+            let was_healthy = endpoint.healthy.swap(healthy, Ordering::Relaxed);
+            if healthy {
+                endpoint.record_success();
+                if !was_healthy {
+                    info!(endpoint=%endpoint.kind, "endpoint back in rotation");
+                }
+            } else if was_healthy {
+                warn!(
+                    endpoint=%endpoint.kind,
+                    head=?head,
+                    best_head=%best,
+                    "endpoint failed health check"
+                );
+            }
+        }
+    }
+
+    /// Order in which endpoints are tried for a request. Healthy endpoints come
+    /// first, starting from the current primary. Unhealthy endpoints are only
+    /// used as a last resort so that a request is never dropped without at
+    /// least trying every endpoint.
+    fn attempt_order(&self) -> Vec<usize> {
+        let primary = self.primary.load(Ordering::Relaxed);
+        let len = self.endpoints.len();
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = (0..len)
+            .map(|offset| (primary + offset) % len)
+            .partition(|idx| self.endpoints[*idx].is_healthy());
+
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    async fn with_failover<R, F, Fut>(&self, f: F) -> eyre::Result<R>
+    where
+        F: Fn(LocalProvider) -> Fut,
+        Fut: Future<Output = eyre::Result<R>>,
+    {
+        let mut last_err = None;
+
+        for idx in self.attempt_order() {
+            let endpoint = &self.endpoints[idx];
+            if let Some(limiter) = &endpoint.rate_limiter {
+                limiter.acquire().await;
+            }
+
+            let res = match endpoint.provider().await {
+                Ok(provider) => f(provider).await,
+                Err(e) => Err(e),
+            };
+
+            match res {
+                Ok(res) => {
+                    endpoint.record_success();
+                    let prev = self.primary.swap(idx, Ordering::Relaxed);
+                    if prev != idx {
+                        info!(endpoint=%endpoint.kind, "failed over to endpoint");
+                    }
+                    return Ok(res)
+                }
+                // the endpoint answered, retrying the call elsewhere would only
+                // revert again
+                Err(e) if is_execution_error(&e) => {
+                    endpoint.record_success();
+                    return Err(e)
+                }
+                Err(e) => {
+                    warn!(endpoint=%endpoint.kind, err=%e, "request to endpoint failed");
+                    endpoint.record_failure();
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| eyre::eyre!("no tracing endpoints configured")))
+    }
+}
+
+/// Whether the node answered the request with an execution error, such as a
+/// reverted call, rather than failing to serve it
+fn is_execution_error(err: &eyre::Report) -> bool {
+    match err.downcast_ref::<TransportError>() {
+        Some(RpcError::ErrorResp(payload)) => {
+            payload.code == EXECUTION_ERROR_CODE || payload.message.contains("revert")
+        }
+        _ => false,
+    }
+}
+
+/// Counts a call made to the node for the block's data against the cost of
+/// the block being processed. Counted here rather than by the parser so that
+/// data served from the db, the flat files or a local node isn't counted
+fn record_rpc_call() {
+    record_block_cost(|cost| cost.trace_rpc_calls += 1);
+}
+
+#[async_trait::async_trait]
+impl TracingProvider for FailoverProvider {
+    async fn eth_call(
+        &self,
+        request: TransactionRequest,
+        block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> eyre::Result<Bytes> {
+        self.with_failover(|provider| {
+            let request = request.clone();
+            let state_overrides = state_overrides.clone();
+            let block_overrides = block_overrides.clone();
+            async move {
+                provider
+                    .eth_call(request, block_number, state_overrides, block_overrides)
+                    .await
+            }
+        })
+        .await
+    }
+
+    async fn block_hash_for_id(&self, block_num: u64) -> eyre::Result<Option<B256>> {
+        self.with_failover(|provider| async move { provider.block_hash_for_id(block_num).await })
+            .await
+    }
+
+    async fn best_block_number(&self) -> eyre::Result<u64> {
+        self.with_failover(|provider| async move { provider.best_block_number().await })
+            .await
+    }
+
+    async fn replay_block_transactions(
+        &self,
+        block_id: BlockId,
+    ) -> eyre::Result<Option<Vec<TxTrace>>> {
+        record_rpc_call();
+        self.with_failover(
+            |provider| async move { provider.replay_block_transactions(block_id).await },
+        )
+        .await
+    }
+
+    async fn block_receipts(
+        &self,
+        number: BlockNumberOrTag,
+    ) -> eyre::Result<Option<Vec<TransactionReceipt<AnyReceiptEnvelope<Log>>>>> {
+        record_rpc_call();
+        self.with_failover(|provider| async move { provider.block_receipts(number).await })
+            .await
+    }
+
+    async fn block_and_tx_index(&self, hash: TxHash) -> eyre::Result<(u64, usize)> {
+        self.with_failover(|provider| async move { provider.block_and_tx_index(hash).await })
+            .await
+    }
+
+    async fn header_by_number(&self, number: BlockNumber) -> eyre::Result<Option<Header>> {
+        record_rpc_call();
+        self.with_failover(|provider| async move { provider.header_by_number(number).await })
+            .await
+    }
+
+    async fn get_storage(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+        storage_key: B256,
+    ) -> eyre::Result<Option<StorageValue>> {
+        self.with_failover(|provider| async move {
+            provider
+                .get_storage(block_number, address, storage_key)
+                .await
+        })
+        .await
+    }
+
+    async fn get_bytecode(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+    ) -> eyre::Result<Option<Bytecode>> {
+        self.with_failover(
+            |provider| async move { provider.get_bytecode(block_number, address).await },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_json_rpc::ErrorPayload;
+    use alloy_transport::TransportErrorKind;
+
+    use super::*;
+
+    fn provider(endpoints: usize) -> FailoverProvider {
+        FailoverProvider::new(
+            (0..endpoints)
+                .map(|i| format!("http://archive-{i}:8545").parse().unwrap())
+                .collect(),
+            0,
+        )
+        .unwrap()
+    }
+
+    fn reverted() -> eyre::Report {
+        TransportError::ErrorResp(ErrorPayload {
+            code:    EXECUTION_ERROR_CODE,
+            message: "execution reverted".to_string(),
+            data:    None,
+        })
+        .into()
+    }
+
+    #[test]
+    fn test_rejects_empty_endpoints() {
+        assert!(FailoverProvider::new(vec![], 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_next_endpoint() {
+        let provider = provider(3);
+        let attempts = AtomicUsize::new(0);
+
+        // the first endpoint fails every request, the second serves them
+        for _ in 0..MAX_CONSECUTIVE_FAILURES {
+            attempts.store(0, Ordering::Relaxed);
+            provider.primary.store(0, Ordering::Relaxed);
+            let res = provider
+                .with_failover(|_| async {
+                    match attempts.fetch_add(1, Ordering::Relaxed) {
+                        0 => Err(TransportErrorKind::custom_str("connection refused").into()),
+                        attempt => Ok(attempt),
+                    }
+                })
+                .await;
+            assert_eq!(res.unwrap(), 1);
+            assert_eq!(provider.primary.load(Ordering::Relaxed), 1);
+        }
+
+        // after repeated failures the endpoint is only tried as a last resort
+        assert!(!provider.endpoints[0].is_healthy());
+        provider.primary.store(0, Ordering::Relaxed);
+        assert_eq!(provider.attempt_order(), vec![1, 2, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_returns_error_when_every_endpoint_fails() {
+        let provider = provider(2);
+        let attempts = AtomicUsize::new(0);
+
+        let res = provider
+            .with_failover(|_| async {
+                attempts.fetch_add(1, Ordering::Relaxed);
+                Err::<(), _>(TransportErrorKind::custom_str("connection refused").into())
+            })
+            .await;
+
+        assert!(res.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_call_isnt_an_endpoint_failure() {
+        let provider = provider(2);
+        let attempts = AtomicUsize::new(0);
+
+        for _ in 0..MAX_CONSECUTIVE_FAILURES {
+            let res = provider
+                .with_failover(|_| async {
+                    attempts.fetch_add(1, Ordering::Relaxed);
+                    Err::<(), _>(reverted())
+                })
+                .await;
+            assert!(is_execution_error(&res.unwrap_err()));
+        }
+
+        // every call was answered by the first endpoint, which stays healthy
+        assert_eq!(attempts.load(Ordering::Relaxed), MAX_CONSECUTIVE_FAILURES as usize);
+        assert!(provider.endpoints[0].is_healthy());
+        assert_eq!(
+            provider.endpoints[0]
+                .consecutive_failures
+                .load(Ordering::Relaxed),
+            0
+        );
+    }
+
+    #[test]
+    fn test_parse_endpoint_config() {
+        assert_eq!(
+            "http://archive-a:8545|200"
+                .parse::<EndpointConfig>()
+                .unwrap(),
+            EndpointConfig::new(
+                EndpointKind::Http(Url::parse("http://archive-a:8545").unwrap()),
+                Some(200)
+            )
+        );
+        assert_eq!(
+            "ipc:///tmp/reth.ipc".parse::<EndpointConfig>().unwrap(),
+            EndpointConfig::new(EndpointKind::Ipc(PathBuf::from("/tmp/reth.ipc")), None)
+        );
+        assert_eq!(
+            " /data/reth.ipc | 50".parse::<EndpointConfig>().unwrap(),
+            EndpointConfig::new(EndpointKind::Ipc(PathBuf::from("/data/reth.ipc")), Some(50))
+        );
+        assert!("archive-a:8545".parse::<EndpointConfig>().is_err());
+        assert!("http://archive-a:8545|0".parse::<EndpointConfig>().is_err());
+        assert!("http://archive a:8545".parse::<EndpointConfig>().is_err());
+        assert!("ws://archive-a:8546".parse::<EndpointConfig>().is_err());
+    }
+}
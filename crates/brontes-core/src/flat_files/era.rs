@@ -0,0 +1,298 @@
+//! Reader of ERA1 archives, the e2store files of the blocks before the merge
+//! with their bodies and receipts.
+//!
+//! ERA1 archives end at the merge, see [`LAST_PRE_MERGE_BLOCK`]. The blocks
+//! after it are only archived in the beacon chain's ERA files, which aren't
+//! read here, so runs reading from ERA1 archives have to end before it.
+//!
+//! Each file holds the blocks of an epoch as a sequence of entries: a version,
+//! then for each block its snappy compressed header, body, receipts and total
+//! difficulty, then the epoch's accumulator and last a block index with the
+//! offset of each block's header.
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use alloy_consensus::{Receipt, ReceiptWithBloom as RpcReceiptWithBloom};
+use alloy_rlp::Decodable;
+use alloy_rpc_types::AnyReceiptEnvelope;
+use eyre::{ensure, eyre};
+use reth_primitives::{Header, ReceiptWithBloom, TransactionSigned};
+use reth_rpc_types::{Log, TransactionReceipt};
+
+const COMPRESSED_HEADER: [u8; 2] = [0x03, 0x00];
+const COMPRESSED_BODY: [u8; 2] = [0x04, 0x00];
+const COMPRESSED_RECEIPTS: [u8; 2] = [0x05, 0x00];
+const BLOCK_INDEX: [u8; 2] = [0x66, 0x32];
+
+/// Type, length and reserved bytes in front of each entry
+const ENTRY_HEADER_LEN: u64 = 8;
+
+/// Last mainnet block before the merge and the last block ERA1 archives cover
+pub const LAST_PRE_MERGE_BLOCK: u64 = 15_537_393;
+
+/// The ERA1 archives of a directory
+#[derive(Debug, Clone)]
+pub struct EraArchives {
+    /// Sorted by their first block
+    files: Vec<EraFile>,
+}
+
+impl EraArchives {
+    /// Indexes the `.era1` files of the directory
+    pub fn open_dir(dir: &Path) -> eyre::Result<Self> {
+        let mut files = std::fs::read_dir(dir)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                (path.extension()? == "era1").then_some(path)
+            })
+            .map(EraFile::open)
+            .collect::<eyre::Result<Vec<_>>>()?;
+        files.sort_by_key(|file| file.start_block);
+        tracing::info!(
+            files = files.len(),
+            dir = %dir.display(),
+            first_block = ?files.first().map(|file| file.start_block),
+            last_block = ?files
+                .last()
+                .map(|file| (file.start_block + file.offsets.len() as u64).saturating_sub(1)),
+            "indexed era archives, the blocks outside them are read from the node"
+        );
+
+        Ok(Self { files })
+    }
+
+    pub fn contains(&self, block: u64) -> bool {
+        self.file_of(block).is_some()
+    }
+
+    pub fn read_block(&self, block: u64) -> eyre::Result<Option<EraBlock>> {
+        self.file_of(block)
+            .map(|file| file.read_block(block))
+            .transpose()
+    }
+
+    fn file_of(&self, block: u64) -> Option<&EraFile> {
+        let idx = self
+            .files
+            .partition_point(|file| file.start_block <= block)
+            .checked_sub(1)?;
+        let file = &self.files[idx];
+        file.contains(block).then_some(file)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct EraFile {
+    path:        PathBuf,
+    start_block: u64,
+    /// Offset of the header entry of each block
+    offsets:     Vec<u64>,
+}
+
+impl EraFile {
+    fn open(path: PathBuf) -> eyre::Result<Self> {
+        let mut file = File::open(&path)?;
+        let len = file.metadata()?.len();
+
+        // the index ends with the number of blocks it has offsets for
+        let mut count = [0u8; 8];
+        file.seek(SeekFrom::End(-8))?;
+        file.read_exact(&mut count)?;
+        let count = u64::from_le_bytes(count);
+
+        let index_start = count
+            .checked_mul(8)
+            .and_then(|offsets| len.checked_sub(ENTRY_HEADER_LEN + 8 + offsets + 8))
+            .ok_or_else(|| eyre!("{} has an invalid block index", path.display()))?;
+        file.seek(SeekFrom::Start(index_start))?;
+        let index = read_entry(&mut file, BLOCK_INDEX)?;
+        // the start block, an offset per block and the count
+        ensure!(
+            index.len() >= 16 && (index.len() - 16) as u64 == count * 8,
+            "{} has a truncated block index",
+            path.display()
+        );
+
+        let start_block = u64::from_le_bytes(index[..8].try_into()?);
+        let offsets = index[8..index.len() - 8]
+            .chunks_exact(8)
+            .map(|offset| {
+                let offset = i64::from_le_bytes(offset.try_into().unwrap());
+                index_start
+                    .checked_add_signed(offset)
+                    .ok_or_else(|| eyre!("{} has an invalid block offset", path.display()))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        Ok(Self { path, start_block, offsets })
+    }
+
+    fn contains(&self, block: u64) -> bool {
+        block >= self.start_block && block - self.start_block < self.offsets.len() as u64
+    }
+
+    fn read_block(&self, block: u64) -> eyre::Result<EraBlock> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offsets[(block - self.start_block) as usize]))?;
+
+        let header = read_compressed_entry(&mut file, COMPRESSED_HEADER)?;
+        let body = read_compressed_entry(&mut file, COMPRESSED_BODY)?;
+        let receipts = read_compressed_entry(&mut file, COMPRESSED_RECEIPTS)?;
+
+        let header = Header::decode(&mut header.as_slice())?;
+        ensure!(header.number == block, "expected block {block} in {}", self.path.display());
+
+        // the body is a list of the transactions and the ommers
+        let mut body = body.as_slice();
+        alloy_rlp::Header::decode(&mut body)?;
+        let transactions = Vec::<TransactionSigned>::decode(&mut body)?;
+        let receipts = Vec::<ReceiptWithBloom>::decode(&mut receipts.as_slice())?;
+        ensure!(
+            transactions.len() == receipts.len(),
+            "block {block} has {} transactions but {} receipts",
+            transactions.len(),
+            receipts.len()
+        );
+
+        Ok(EraBlock { header, transactions, receipts })
+    }
+}
+
+fn read_entry(reader: &mut impl Read, expected: [u8; 2]) -> eyre::Result<Vec<u8>> {
+    let mut header = [0u8; ENTRY_HEADER_LEN as usize];
+    reader.read_exact(&mut header)?;
+    ensure!(header[..2] == expected, "expected entry {expected:?}, found {:?}", &header[..2]);
+
+    let len = u32::from_le_bytes(header[2..6].try_into()?) as usize;
+    let mut data = vec![0u8; len];
+    reader.read_exact(&mut data)?;
+
+    Ok(data)
+}
+
+fn read_compressed_entry(reader: &mut impl Read, expected: [u8; 2]) -> eyre::Result<Vec<u8>> {
+    let compressed = read_entry(reader, expected)?;
+    let mut data = Vec::new();
+    snap::read::FrameDecoder::new(compressed.as_slice()).read_to_end(&mut data)?;
+
+    Ok(data)
+}
+
+#[derive(Debug, Clone)]
+pub struct EraBlock {
+    pub header:       Header,
+    pub transactions: Vec<TransactionSigned>,
+    pub receipts:     Vec<ReceiptWithBloom>,
+}
+
+impl EraBlock {
+    /// The receipts in the form the node returns them
+    pub fn rpc_receipts(&self) -> eyre::Result<Vec<TransactionReceipt<AnyReceiptEnvelope<Log>>>> {
+        let block_hash = self.header.hash_slow();
+        let mut cumulative_gas_used = 0;
+        let mut log_index = 0;
+
+        self.transactions
+            .iter()
+            .zip(&self.receipts)
+            .enumerate()
+            .map(|(tx_index, (tx, receipt))| {
+                let from = tx
+                    .recover_signer()
+                    .ok_or_else(|| eyre!("invalid signature on {}", tx.hash()))?;
+                let gas_used = receipt.receipt.cumulative_gas_used - cumulative_gas_used;
+                cumulative_gas_used = receipt.receipt.cumulative_gas_used;
+
+                let logs = receipt
+                    .receipt
+                    .logs
+                    .iter()
+                    .map(|log| {
+                        log_index += 1;
+                        Log {
+                            inner:             log.clone(),
+                            block_hash:        Some(block_hash),
+                            block_number:      Some(self.header.number),
+                            block_timestamp:   Some(self.header.timestamp),
+                            transaction_hash:  Some(tx.hash()),
+                            transaction_index: Some(tx_index as u64),
+                            log_index:         Some(log_index - 1),
+                            removed:           false,
+                        }
+                    })
+                    .collect();
+
+                Ok(TransactionReceipt {
+                    inner: AnyReceiptEnvelope {
+                        inner:  RpcReceiptWithBloom {
+                            receipt:    Receipt {
+                                status: receipt.receipt.success,
+                                cumulative_gas_used: receipt.receipt.cumulative_gas_used as u128,
+                                logs,
+                            },
+                            logs_bloom: receipt.bloom,
+                        },
+                        r#type: tx.tx_type() as u8,
+                    },
+                    transaction_hash: tx.hash(),
+                    transaction_index: Some(tx_index as u64),
+                    block_hash: Some(block_hash),
+                    block_number: Some(self.header.number),
+                    gas_used: gas_used as u128,
+                    effective_gas_price: tx.effective_gas_price(self.header.base_fee_per_gas),
+                    blob_gas_used: None,
+                    blob_gas_price: None,
+                    from,
+                    to: tx.to(),
+                    contract_address: tx.to().is_none().then(|| from.create(tx.nonce())),
+                    state_root: None,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn entry(kind: [u8; 2], data: &[u8]) -> Vec<u8> {
+        let mut entry = kind.to_vec();
+        entry.extend((data.len() as u32).to_le_bytes());
+        entry.extend([0, 0]);
+        entry.extend(data);
+        entry
+    }
+
+    #[test]
+    fn test_reads_block_index() {
+        // a version entry, two blocks of empty stand in entries and the index
+        let mut file = entry([0x65, 0x32], &[]);
+        let first = file.len() as i64;
+        file.extend(entry(COMPRESSED_HEADER, &[1]));
+        let second = file.len() as i64;
+        file.extend(entry(COMPRESSED_HEADER, &[2]));
+
+        let index_start = file.len() as i64;
+        let mut index = 100u64.to_le_bytes().to_vec();
+        index.extend((first - index_start).to_le_bytes());
+        index.extend((second - index_start).to_le_bytes());
+        index.extend(2u64.to_le_bytes());
+        file.extend(entry(BLOCK_INDEX, &index));
+
+        let path = std::env::temp_dir().join(format!("brontes-era-{}.era1", std::process::id()));
+        File::create(&path).unwrap().write_all(&file).unwrap();
+        let era = EraFile::open(path.clone()).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(era.start_block, 100);
+        assert_eq!(era.offsets, vec![first as u64, second as u64]);
+        assert!(era.contains(101));
+        assert!(!era.contains(102));
+    }
+}
@@ -0,0 +1,160 @@
+//! Block data read from files instead of a node.
+//!
+//! Backfills on a machine with an archive of the chain don't need to ask a
+//! node for each block. The [`FlatFileProvider`] serves headers and receipts
+//! from ERA1 archives, see [`era`], and traces from flat trace files written
+//! by `brontes db export-traces`, see [`traces`]. The rest of the pipeline is
+//! unchanged.
+//!
+//! The files don't replace the node. ERA1 archives only cover the blocks
+//! before the merge, the trace files have to be exported from a node first,
+//! and state reads, `eth_call`s, block hashes of uncovered blocks and tx
+//! lookups always go to the wrapped provider. For a machine colocated with a
+//! node that is the `local-reth` provider reading the node's datadir, so a
+//! backfill runs without rpc, but it still needs the datadir.
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_rpc_types::AnyReceiptEnvelope;
+use brontes_types::{structured_trace::TxTrace, traits::TracingProvider};
+use reth_primitives::{
+    Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue, TxHash,
+    B256,
+};
+use reth_rpc_types::{
+    state::StateOverride, BlockOverrides, Log, TransactionReceipt, TransactionRequest,
+};
+
+pub mod era;
+pub mod traces;
+
+use era::EraArchives;
+
+#[derive(Clone)]
+pub struct FlatFileProvider<T> {
+    inner:      T,
+    era:        Option<Arc<EraArchives>>,
+    traces_dir: Option<PathBuf>,
+}
+
+impl<T: TracingProvider> FlatFileProvider<T> {
+    /// Without either directory every call goes to the inner provider
+    pub fn new(
+        inner: T,
+        era_dir: Option<PathBuf>,
+        traces_dir: Option<PathBuf>,
+    ) -> eyre::Result<Self> {
+        let era = era_dir
+            .map(|dir| EraArchives::open_dir(&dir).map(Arc::new))
+            .transpose()?;
+
+        Ok(Self { inner, era, traces_dir })
+    }
+
+    fn block_number(id: BlockId) -> Option<u64> {
+        match id {
+            BlockId::Number(BlockNumberOrTag::Number(number)) => Some(number),
+            _ => None,
+        }
+    }
+
+    fn era_block(&self, number: u64) -> eyre::Result<Option<era::EraBlock>> {
+        match &self.era {
+            Some(era) if era.contains(number) => era.read_block(number),
+            _ => Ok(None),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: TracingProvider> TracingProvider for FlatFileProvider<T> {
+    async fn eth_call(
+        &self,
+        request: TransactionRequest,
+        block_number: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
+    ) -> eyre::Result<Bytes> {
+        self.inner
+            .eth_call(request, block_number, state_overrides, block_overrides)
+            .await
+    }
+
+    async fn eth_call_light(
+        &self,
+        request: TransactionRequest,
+        block_number: BlockId,
+    ) -> eyre::Result<Bytes> {
+        self.inner.eth_call_light(request, block_number).await
+    }
+
+    async fn block_hash_for_id(&self, block_num: u64) -> eyre::Result<Option<B256>> {
+        if let Some(block) = self.era_block(block_num)? {
+            return Ok(Some(block.header.hash_slow()))
+        }
+        self.inner.block_hash_for_id(block_num).await
+    }
+
+    #[cfg(feature = "local-reth")]
+    fn best_block_number(&self) -> eyre::Result<u64> {
+        self.inner.best_block_number()
+    }
+
+    #[cfg(not(feature = "local-reth"))]
+    async fn best_block_number(&self) -> eyre::Result<u64> {
+        self.inner.best_block_number().await
+    }
+
+    async fn replay_block_transactions(
+        &self,
+        block_id: BlockId,
+    ) -> eyre::Result<Option<Vec<TxTrace>>> {
+        if let (Some(dir), Some(number)) = (&self.traces_dir, Self::block_number(block_id)) {
+            if let Some(traces) = traces::read_block_traces(dir, number)? {
+                return Ok(Some(traces))
+            }
+        }
+        self.inner.replay_block_transactions(block_id).await
+    }
+
+    async fn block_receipts(
+        &self,
+        number: BlockNumberOrTag,
+    ) -> eyre::Result<Option<Vec<TransactionReceipt<AnyReceiptEnvelope<Log>>>>> {
+        if let BlockNumberOrTag::Number(block_num) = number {
+            if let Some(block) = self.era_block(block_num)? {
+                return block.rpc_receipts().map(Some)
+            }
+        }
+        self.inner.block_receipts(number).await
+    }
+
+    async fn header_by_number(&self, number: BlockNumber) -> eyre::Result<Option<Header>> {
+        if let Some(block) = self.era_block(number)? {
+            return Ok(Some(block.header))
+        }
+        self.inner.header_by_number(number).await
+    }
+
+    async fn block_and_tx_index(&self, hash: TxHash) -> eyre::Result<(u64, usize)> {
+        self.inner.block_and_tx_index(hash).await
+    }
+
+    async fn get_storage(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+        storage_key: B256,
+    ) -> eyre::Result<Option<StorageValue>> {
+        self.inner
+            .get_storage(block_number, address, storage_key)
+            .await
+    }
+
+    async fn get_bytecode(
+        &self,
+        block_number: Option<u64>,
+        address: Address,
+    ) -> eyre::Result<Option<Bytecode>> {
+        self.inner.get_bytecode(block_number, address).await
+    }
+}
@@ -0,0 +1,49 @@
+//! Flat files of block traces, one file per block with the block's traces in
+//! the encoding of the `TxTraces` table.
+use std::path::{Path, PathBuf};
+
+use brontes_types::{
+    db::traces::{TxTracesInner, TxTracesInnerRedefined},
+    structured_trace::TxTrace,
+};
+use redefined::RedefinedConvert;
+use reth_db::table::{Compress, Decompress};
+
+fn trace_file(dir: &Path, block: u64) -> PathBuf {
+    dir.join(format!("{block}.traces"))
+}
+
+pub fn write_block_traces(dir: &Path, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let data = TxTracesInnerRedefined::from_source(TxTracesInner::new(Some(traces))).compress();
+    std::fs::write(trace_file(dir, block), data)?;
+
+    Ok(())
+}
+
+/// Traces of the block, `None` if the directory has no file for it
+pub fn read_block_traces(dir: &Path, block: u64) -> eyre::Result<Option<Vec<TxTrace>>> {
+    let data = match std::fs::read(trace_file(dir, block)) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(TxTracesInnerRedefined::decompress(data)?.to_source().traces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_file_round_trip() {
+        let dir = std::env::temp_dir().join(format!("brontes-traces-{}", std::process::id()));
+        let traces = vec![TxTrace { tx_index: 3, ..Default::default() }];
+
+        write_block_traces(&dir, 7, traces).unwrap();
+        assert_eq!(read_block_traces(&dir, 7).unwrap().unwrap()[0].tx_index, 3);
+        assert_eq!(read_block_traces(&dir, 8).unwrap(), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,12 +1,18 @@
 //TODO: Write documentation for this crate
 
 #![feature(trait_alias)]
+#[cfg(feature = "relay-clients")]
+pub mod beacon_client;
 pub mod decoding;
 pub mod errors;
 pub mod executor;
 #[cfg(not(feature = "local-reth"))]
+pub mod failover_provider;
+pub mod flat_files;
+#[cfg(not(feature = "local-reth"))]
 pub mod local_provider;
 pub mod missing_token_info;
+pub mod token_metadata;
 
 #[cfg(feature = "tests")]
 pub mod test_utils;
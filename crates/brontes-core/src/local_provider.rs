@@ -1,10 +1,13 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use alloy_provider::{Provider, RootProvider};
+use alloy_rpc_client::ClientBuilder;
 use alloy_rpc_types::AnyReceiptEnvelope;
-use alloy_transport_http::Http;
+use alloy_transport::BoxTransport;
+use alloy_transport_ipc::IpcConnect;
 use brontes_types::{structured_trace::TxTrace, traits::TracingProvider};
 use itertools::Itertools;
+use reqwest::Url;
 use reth_primitives::{
     Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue, TxHash,
     B256,
@@ -15,13 +18,24 @@ use reth_rpc_types::{
 
 #[derive(Debug, Clone)]
 pub struct LocalProvider {
-    provider: Arc<RootProvider<Http<reqwest::Client>>>,
+    provider: Arc<RootProvider<BoxTransport>>,
     retries:  u8,
 }
 
 impl LocalProvider {
     pub fn new(url: String, retries: u8) -> Self {
-        Self { provider: Arc::new(RootProvider::new_http(url.parse().unwrap())), retries }
+        Self::new_http(url.parse().unwrap(), retries)
+    }
+
+    /// Connects to a node over http at the given url.
+    pub fn new_http(url: Url, retries: u8) -> Self {
+        Self { provider: Arc::new(RootProvider::new_http(url).boxed()), retries }
+    }
+
+    /// Connects to a node over the IPC socket at the given path.
+    pub async fn new_ipc(path: PathBuf, retries: u8) -> eyre::Result<Self> {
+        let client = ClientBuilder::default().ipc(IpcConnect::new(path)).await?;
+        Ok(Self { provider: Arc::new(RootProvider::new(client).boxed()), retries })
     }
 }
 
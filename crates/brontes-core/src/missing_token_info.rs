@@ -4,7 +4,7 @@ use alloy_primitives::Address;
 use alloy_sol_macro::sol;
 use alloy_sol_types::SolCall;
 use brontes_database::libmdbx::DBWriter;
-use brontes_types::make_call_request;
+use brontes_types::{db::token_metadata::TokenMetadata, make_call_request};
 use futures::{join, stream::FuturesUnordered, StreamExt};
 use tracing::error;
 
@@ -14,6 +14,7 @@ sol!(
     interface normal {
         function decimals() public view returns (uint8);
         function symbol() public view returns (string);
+        function name() public view returns (string);
     }
 );
 sol!(
@@ -29,7 +30,7 @@ pub async fn load_missing_token_info<T: TracingProvider, W: DBWriter>(
     missing_address: Address,
 ) {
     let data = query_missing_data(provider, block, missing_address).await;
-    on_decimal_query_resolution(db, data).await;
+    on_decimal_query_resolution(db, block, data).await;
 }
 
 pub async fn load_missing_token_infos<T: TracingProvider, W: DBWriter>(
@@ -44,19 +45,20 @@ pub async fn load_missing_token_infos<T: TracingProvider, W: DBWriter>(
         .for_each(|addr| pending_decimals.push(query_missing_data(provider, block, addr)));
 
     while let Some(res) = pending_decimals.next().await {
-        on_decimal_query_resolution(db, res).await;
+        on_decimal_query_resolution(db, block, res).await;
     }
 }
 
-async fn query_missing_data<T: TracingProvider>(
+pub(crate) async fn query_missing_data<T: TracingProvider>(
     provider: &Arc<T>,
     block: u64,
     missing_address: Address,
-) -> eyre::Result<(Address, u8, String)> {
-    let (decimals, symbol, symbol_autistic) = join!(
+) -> eyre::Result<(Address, u8, String, String)> {
+    let (decimals, symbol, symbol_autistic, name) = join!(
         make_call_request(normal::decimalsCall::new(()), provider, missing_address, Some(block)),
         make_call_request(normal::symbolCall::new(()), provider, missing_address, Some(block)),
-        make_call_request(autistic::symbolCall::new(()), provider, missing_address, Some(block))
+        make_call_request(autistic::symbolCall::new(()), provider, missing_address, Some(block)),
+        make_call_request(normal::nameCall::new(()), provider, missing_address, Some(block))
     );
 
     Ok(decimals.map(|d| d._0).unwrap_or_default()).map(|d| {
@@ -68,19 +70,26 @@ async fn query_missing_data<T: TracingProvider>(
                     .map(|s| String::from_utf8((s._0).to_vec()).unwrap_or_default())
                     .unwrap_or_default()
             }),
+            name.map(|n| n._0).unwrap_or_default(),
         )
     })
 }
 
 async fn on_decimal_query_resolution<W: DBWriter>(
     database: &W,
-    result: eyre::Result<(Address, u8, String)>,
+    block: u64,
+    result: eyre::Result<(Address, u8, String, String)>,
 ) {
     match result {
-        Ok((address, decimals, symbol)) => {
+        Ok((address, decimals, symbol, name)) => {
+            // assessed on its name until it's scored against the node
+            let metadata = TokenMetadata::from_name(symbol.clone(), name, decimals, block);
             if let Err(e) = database.write_token_info(address, decimals, symbol).await {
                 error!(error= %e, "failed to write token info into database");
             }
+            if let Err(e) = database.write_token_metadata(address, metadata).await {
+                error!(error= %e, "failed to write token metadata into database");
+            }
         }
         Err(e) => {
             error!(error= %e, "token info request failed");
@@ -0,0 +1,236 @@
+//! Scoring tokens against the node, see
+//! [`brontes_types::db::token_metadata`].
+use std::sync::Arc;
+
+use alloy_primitives::{b256, Address, Log, B256, U256};
+use alloy_sol_macro::sol;
+use alloy_sol_types::SolCall;
+use brontes_types::{
+    db::token_metadata::{
+        liquidity_signal, suspicious_name, SpamAssessment, SpamSignal, TokenList, TokenMetadata,
+    },
+    make_call_request, FastHashMap,
+};
+use futures::future::join_all;
+use reth_primitives::BlockNumberOrTag;
+use reth_rpc_types::{request::TransactionInput, TransactionRequest};
+
+use crate::{decoding::TracingProvider, missing_token_info::query_missing_data};
+
+sol!(
+    interface erc20 {
+        function totalSupply() public view returns (uint256);
+        function balanceOf(address owner) public view returns (uint256);
+        function transfer(address to, uint256 amount) public returns (bool);
+    }
+);
+
+const TRANSFER_TOPIC: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+/// Recent recipients of a token kept as candidate holders
+const MAX_HOLDER_CANDIDATES: usize = 8;
+
+/// Recipients of the erc20 transfers in the `blocks` blocks up to `block`, by
+/// token and most recent first. They're the candidate holders a sell of the
+/// token is simulated from
+pub async fn recent_token_holders<T: TracingProvider>(
+    provider: &Arc<T>,
+    block: u64,
+    blocks: u64,
+) -> eyre::Result<FastHashMap<Address, Vec<Address>>> {
+    let mut holders: FastHashMap<Address, Vec<Address>> = FastHashMap::default();
+    for number in (block.saturating_sub(blocks.saturating_sub(1))..=block).rev() {
+        let Some(receipts) = provider
+            .block_receipts(BlockNumberOrTag::Number(number))
+            .await?
+        else {
+            continue
+        };
+
+        for log in receipts
+            .iter()
+            .rev()
+            .flat_map(|receipt| receipt.inner.logs().iter().rev())
+        {
+            let Some((token, recipient)) = transfer_recipient(&log.inner) else { continue };
+            let token_holders = holders.entry(token).or_default();
+            if token_holders.len() < MAX_HOLDER_CANDIDATES && !token_holders.contains(&recipient) {
+                token_holders.push(recipient);
+            }
+        }
+    }
+
+    Ok(holders)
+}
+
+/// Token and recipient of an erc20 transfer log. Erc721 transfers, which have
+/// the id as a fourth topic, and mints and burns are ignored
+fn transfer_recipient(log: &Log) -> Option<(Address, Address)> {
+    let topics = log.topics();
+    if topics.len() != 3 || topics[0] != TRANSFER_TOPIC {
+        return None
+    }
+    let from = Address::from_slice(&topics[1][12..]);
+    let to = Address::from_slice(&topics[2][12..]);
+
+    (!from.is_zero() && !to.is_zero()).then_some((log.address, to))
+}
+
+/// Scores the token at the block. `pools` are the pools holding the token and
+/// `holders` candidate holders of it, see [`recent_token_holders`]. The token
+/// list provides the logo and marks curated tokens
+pub async fn score_token<T: TracingProvider>(
+    provider: &Arc<T>,
+    token: Address,
+    block: u64,
+    pools: &[Address],
+    holders: &[Address],
+    token_list: Option<&TokenList>,
+) -> eyre::Result<TokenMetadata> {
+    let (_, decimals, symbol, name) = query_missing_data(provider, block, token).await?;
+    let total_supply =
+        make_call_request(erc20::totalSupplyCall::new(()), provider, token, Some(block))
+            .await
+            .map(|supply| supply._0)
+            .unwrap_or_default();
+
+    let balances = balances_of(provider, token, block, pools.iter().copied()).await;
+
+    let mut signals = vec![];
+    if suspicious_name(&symbol, &name) {
+        signals.push(SpamSignal::SuspiciousName);
+    }
+
+    let pooled = balances
+        .iter()
+        .fold(U256::ZERO, |pooled, (_, balance)| pooled.saturating_add(*balance));
+    signals.extend(liquidity_signal(pooled.saturating_to(), total_supply.saturating_to()));
+
+    // a honeypot lets its holders buy from the pool but blocks their sells,
+    // which are transfers from the holder into the pool
+    let pool = balances
+        .into_iter()
+        .filter(|(_, balance)| *balance > U256::ZERO)
+        .max_by_key(|(_, balance)| *balance)
+        .map(|(pool, _)| pool);
+    let holder = largest_holder(
+        balances_of(
+            provider,
+            token,
+            block,
+            holders
+                .iter()
+                .copied()
+                .filter(|holder| !pools.contains(holder)),
+        )
+        .await,
+    );
+    if let (Some(pool), Some((holder, balance))) = (pool, holder) {
+        if !simulate_transfer(provider, token, holder, pool, balance / U256::from(100), block).await
+        {
+            signals.push(SpamSignal::TransferReverts);
+        }
+    }
+
+    let listed = token_list.and_then(|list| list.get(&token));
+    Ok(TokenMetadata {
+        symbol,
+        name,
+        decimals,
+        logo_uri: listed.and_then(|entry| entry.logo_uri.clone()),
+        listed: listed.is_some(),
+        spam: SpamAssessment::new(signals, listed.is_some()),
+        block,
+    })
+}
+
+async fn balances_of<T: TracingProvider>(
+    provider: &Arc<T>,
+    token: Address,
+    block: u64,
+    owners: impl Iterator<Item = Address>,
+) -> Vec<(Address, U256)> {
+    join_all(owners.map(|owner| async move {
+        let balance =
+            make_call_request(erc20::balanceOfCall::new((owner,)), provider, token, Some(block))
+                .await
+                .map(|balance| balance._0)
+                .unwrap_or_default();
+        (owner, balance)
+    }))
+    .await
+}
+
+/// Holder with the largest non zero balance
+fn largest_holder(balances: Vec<(Address, U256)>) -> Option<(Address, U256)> {
+    balances
+        .into_iter()
+        .filter(|(_, balance)| *balance > U256::ZERO)
+        .max_by_key(|(_, balance)| *balance)
+}
+
+/// Whether `from` can transfer the amount of the token to `to`
+async fn simulate_transfer<T: TracingProvider>(
+    provider: &Arc<T>,
+    token: Address,
+    from: Address,
+    to: Address,
+    amount: U256,
+    block: u64,
+) -> bool {
+    let call = erc20::transferCall::new((to, amount));
+    let req = TransactionRequest {
+        from: Some(from),
+        to: Some(token),
+        input: TransactionInput::new(call.abi_encode().into()),
+        ..Default::default()
+    };
+
+    match provider.eth_call(req, Some(block.into()), None, None).await {
+        // tokens that don't return a bool succeed with empty return data
+        Ok(res) if res.is_empty() => true,
+        Ok(res) => erc20::transferCall::abi_decode_returns(&res, false)
+            .map(|ret| ret._0)
+            .unwrap_or(false),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Bytes, LogData};
+
+    use super::*;
+
+    fn transfer_log(token: Address, topics: Vec<B256>) -> Log {
+        Log { address: token, data: LogData::new_unchecked(topics, Bytes::new()) }
+    }
+
+    #[test]
+    fn test_transfer_recipient() {
+        let token = Address::repeat_byte(1);
+        let (from, to) = (Address::repeat_byte(2), Address::repeat_byte(3));
+
+        let transfer = transfer_log(token, vec![TRANSFER_TOPIC, from.into_word(), to.into_word()]);
+        assert_eq!(transfer_recipient(&transfer), Some((token, to)));
+
+        // a mint has no holder selling yet, an erc721 transfer isn't scored
+        let mint = transfer_log(token, vec![TRANSFER_TOPIC, B256::ZERO, to.into_word()]);
+        assert_eq!(transfer_recipient(&mint), None);
+        let nft = transfer_log(
+            token,
+            vec![TRANSFER_TOPIC, from.into_word(), to.into_word(), B256::with_last_byte(7)],
+        );
+        assert_eq!(transfer_recipient(&nft), None);
+    }
+
+    #[test]
+    fn test_largest_holder() {
+        let [a, b, c] = [1, 2, 3].map(Address::repeat_byte);
+        assert_eq!(
+            largest_holder(vec![(a, U256::from(5)), (b, U256::from(9)), (c, U256::ZERO)]),
+            Some((b, U256::from(9)))
+        );
+        assert_eq!(largest_holder(vec![(c, U256::ZERO)]), None);
+    }
+}
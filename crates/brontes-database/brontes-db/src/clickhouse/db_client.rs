@@ -1,4 +1,4 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use ::clickhouse::DbRow;
 use alloy_primitives::Address;
@@ -16,6 +16,7 @@ use brontes_types::{
             BestCexPerPair,
         },
         dex::{DexQuotes, DexQuotesWithBlockNumber},
+        export_filter::ExportFilter,
         metadata::{BlockMetadata, Metadata},
         normalized_actions::TransactionRoot,
         searcher::SearcherInfo,
@@ -70,6 +71,8 @@ pub struct Clickhouse {
     pub client:              ClickhouseClient<BrontesClickhouseTables>,
     pub cex_download_config: CexDownloadConfig,
     pub buffered_insert_tx:  Option<UnboundedSender<Vec<BrontesClickhouseData>>>,
+    /// Redaction applied to mev blocks before they are inserted
+    pub export_filter:       Option<Arc<ExportFilter>>,
 }
 
 impl Clickhouse {
@@ -81,7 +84,14 @@ impl Clickhouse {
         run_id: Option<u64>,
     ) -> Self {
         let client = config.build();
-        let mut this = Self { client, cex_download_config, buffered_insert_tx, tip, run_id: 0 };
+        let mut this = Self {
+            client,
+            cex_download_config,
+            buffered_insert_tx,
+            tip,
+            run_id: 0,
+            export_filter: None,
+        };
 
         this.run_id = if let Some(run_id) = run_id {
             run_id
@@ -150,10 +160,14 @@ impl Clickhouse {
     pub async fn save_mev_blocks(
         &self,
         _block_number: u64,
-        block: MevBlock,
-        mev: Vec<Bundle>,
+        mut block: MevBlock,
+        mut mev: Vec<Bundle>,
     ) -> eyre::Result<()> {
         if let Some(tx) = self.buffered_insert_tx.as_ref() {
+            if let Some(filter) = &self.export_filter {
+                filter.apply_parts(&mut block, &mut mev);
+            }
+
             tx.send(vec![(block, self.tip, self.run_id).into()])?;
 
             let (bundle_headers, bundle_data): (Vec<_>, Vec<_>) = mev
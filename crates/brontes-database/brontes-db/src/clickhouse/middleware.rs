@@ -1,18 +1,35 @@
 use std::sync::Arc;
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 use brontes_types::{
     db::{
         address_metadata::AddressMetadata,
         address_to_protocol_info::ProtocolInfo,
+        beacon::BeaconBlockInfo,
         block_analysis::BlockAnalysis,
+        block_costs::{BlockCost, BlockCostRecorder},
         builder::BuilderInfo,
+        bundle_tags::{BlockBundleTags, BundleTag},
+        config_reload::ConfigReload,
         dex::DexQuotes,
+        fee_history::BlockFees,
+        frontend_losses::BlockFrontendLosses,
+        inspector_artifacts::BlockInspectorArtifacts,
         metadata::Metadata,
         mev_block::MevBlockWithClassified,
+        mev_receipt::MevReceipt,
+        order_flow::{BlockOrderFlow, BuilderProposerPayments, SearcherOrderFlow},
+        overrides::BundleOverride,
+        pool_prices::BlockPoolPrices,
+        publish_checkpoint::PublishCheckpoint,
+        repricing::BlockRepricings,
         searcher::SearcherInfo,
+        searcher_stats::SearcherStats,
+        suppression::{SuppressionChange, SuppressionList},
         token_info::TokenInfoWithAddress,
+        token_metadata::TokenMetadata,
         traits::{DBWriter, LibmdbxReader, ProtocolCreatedRange},
+        wallet_funding::FundingEdge,
     },
     mev::{Bundle, MevBlock},
     normalized_actions::Action,
@@ -34,7 +51,7 @@ use crate::{
 pub struct ClickhouseMiddleware<I: DBWriter> {
     #[allow(dead_code)] // on tests feature
     pub client: Clickhouse,
-    inner:      Arc<I>,
+    pub inner:  Arc<I>,
 }
 
 impl<I: DBWriter> ClickhouseMiddleware<I> {
@@ -46,8 +63,8 @@ impl<I: DBWriter> ClickhouseMiddleware<I> {
 impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
     type Inner = I;
 
-    fn inner(&self) -> &Self::Inner {
-        &self.inner
+    fn inner(&self) -> Option<&Self::Inner> {
+        Some(&*self.inner)
     }
 
     async fn write_block_analysis(&self, block_analysis: BlockAnalysis) -> eyre::Result<()> {
@@ -63,7 +80,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
             .write_dex_quotes(block_number, quotes.clone())
             .await?;
 
-        self.inner().write_dex_quotes(block_number, quotes).await
+        self.inner.write_dex_quotes(block_number, quotes).await
     }
 
     async fn write_token_info(
@@ -76,9 +93,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
             .write_token_info(address, decimals, symbol.clone())
             .await?;
 
-        self.inner()
-            .write_token_info(address, decimals, symbol)
-            .await
+        self.inner.write_token_info(address, decimals, symbol).await
     }
 
     async fn save_mev_blocks(
@@ -91,7 +106,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
             .save_mev_blocks(block_number, block.clone(), mev.clone())
             .await?;
 
-        self.inner().save_mev_blocks(block_number, block, mev).await
+        self.inner.save_mev_blocks(block_number, block, mev).await
     }
 
     async fn write_searcher_eoa_info(
@@ -103,7 +118,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
             .write_searcher_eoa_info(searcher_eoa, searcher_info.clone())
             .await?;
 
-        self.inner()
+        self.inner
             .write_searcher_eoa_info(searcher_eoa, searcher_info)
             .await
     }
@@ -117,7 +132,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
             .write_searcher_contract_info(searcher_contract, searcher_info.clone())
             .await?;
 
-        self.inner()
+        self.inner
             .write_searcher_contract_info(searcher_contract, searcher_info)
             .await
     }
@@ -131,7 +146,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
             .write_builder_info(builder_coinbase_addr, builder_info.clone())
             .await?;
 
-        self.inner()
+        self.inner
             .write_builder_info(builder_coinbase_addr, builder_info)
             .await
     }
@@ -148,7 +163,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
             .insert_pool(block, address, tokens, curve_lp_token, classifier_name)
             .await?;
 
-        self.inner()
+        self.inner
             .insert_pool(block, address, tokens, curve_lp_token, classifier_name)
             .await
     }
@@ -156,7 +171,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
     async fn insert_tree(&self, tree: BlockTree<Action>) -> eyre::Result<()> {
         self.client.insert_tree(tree.clone()).await?;
 
-        self.inner().insert_tree(tree).await?;
+        self.inner.insert_tree(tree).await?;
 
         Ok(())
     }
@@ -164,7 +179,7 @@ impl<I: DBWriter + Send + Sync> DBWriter for ClickhouseMiddleware<I> {
     async fn save_traces(&self, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
         self.client.save_traces(block, traces.clone()).await?;
 
-        self.inner().save_traces(block, traces).await
+        self.inner.save_traces(block, traces).await
     }
 }
 
@@ -333,6 +348,120 @@ impl<I: LibmdbxInit> LibmdbxReader for ClickhouseMiddleware<I> {
         self.inner.get_metadata(block_num, quote_asset)
     }
 
+    fn try_fetch_beacon_info(&self, block_num: u64) -> eyre::Result<Option<BeaconBlockInfo>> {
+        self.inner.try_fetch_beacon_info(block_num)
+    }
+
+    fn fetch_bundle_overrides(&self) -> eyre::Result<Vec<(u64, BundleOverride)>> {
+        self.inner.fetch_bundle_overrides()
+    }
+
+    fn fetch_suppression_changes(&self) -> eyre::Result<Vec<(u64, SuppressionChange)>> {
+        self.inner.fetch_suppression_changes()
+    }
+
+    fn fetch_suppression_list(&self) -> eyre::Result<SuppressionList> {
+        self.inner.fetch_suppression_list()
+    }
+
+    fn fetch_bundle_repricings(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockRepricings)>> {
+        self.inner.fetch_bundle_repricings(start_block, end_block)
+    }
+
+    fn try_fetch_wallet_funding(&self, address: Address) -> eyre::Result<Option<FundingEdge>> {
+        self.inner.try_fetch_wallet_funding(address)
+    }
+
+    fn try_fetch_mev_receipt(&self, tx_hash: B256) -> eyre::Result<Option<MevReceipt>> {
+        self.inner.try_fetch_mev_receipt(tx_hash)
+    }
+
+    fn fetch_bundle_tags(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockBundleTags)>> {
+        self.inner.fetch_bundle_tags(start_block, end_block)
+    }
+
+    fn fetch_inspector_artifacts(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockInspectorArtifacts)>> {
+        self.inner.fetch_inspector_artifacts(start_block, end_block)
+    }
+
+    fn try_fetch_block_fees(&self, block_num: u64) -> eyre::Result<Option<BlockFees>> {
+        self.inner.try_fetch_block_fees(block_num)
+    }
+
+    fn fetch_fee_history(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFees)>> {
+        self.inner.fetch_fee_history(start_block, end_block)
+    }
+
+    fn fetch_pool_prices(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockPoolPrices)>> {
+        self.inner.fetch_pool_prices(start_block, end_block)
+    }
+
+    fn fetch_frontend_losses(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFrontendLosses)>> {
+        self.inner.fetch_frontend_losses(start_block, end_block)
+    }
+
+    fn fetch_block_costs(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockCost)>> {
+        self.inner.fetch_block_costs(start_block, end_block)
+    }
+
+    fn try_fetch_builder_proposer_payments(
+        &self,
+        builder: Address,
+    ) -> eyre::Result<Option<BuilderProposerPayments>> {
+        self.inner.try_fetch_builder_proposer_payments(builder)
+    }
+
+    fn try_fetch_searcher_order_flow(
+        &self,
+        searcher: Address,
+    ) -> eyre::Result<Option<SearcherOrderFlow>> {
+        self.inner.try_fetch_searcher_order_flow(searcher)
+    }
+
+    fn try_fetch_searcher_stats(&self, searcher: Address) -> eyre::Result<Option<SearcherStats>> {
+        self.inner.try_fetch_searcher_stats(searcher)
+    }
+
+    fn fetch_all_searcher_stats(&self) -> eyre::Result<Vec<(Address, SearcherStats)>> {
+        self.inner.fetch_all_searcher_stats()
+    }
+
+    fn fetch_block_order_flows(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockOrderFlow)>> {
+        self.inner.fetch_block_order_flows(start_block, end_block)
+    }
+
     fn try_fetch_address_metadata(
         &self,
         address: Address,
@@ -351,6 +480,14 @@ impl<I: LibmdbxInit> LibmdbxReader for ClickhouseMiddleware<I> {
         self.inner.fetch_all_address_metadata()
     }
 
+    fn try_fetch_token_metadata(&self, address: Address) -> eyre::Result<Option<TokenMetadata>> {
+        self.inner.try_fetch_token_metadata(address)
+    }
+
+    fn fetch_all_token_metadata(&self) -> eyre::Result<Vec<(Address, TokenMetadata)>> {
+        self.inner.fetch_all_token_metadata()
+    }
+
     fn get_dex_quotes(&self, block: u64) -> eyre::Result<DexQuotes> {
         self.inner.get_dex_quotes(block)
     }
@@ -398,8 +535,8 @@ impl<I: DBWriter> ReadOnlyMiddleware<I> {
 impl<I: DBWriter + Send + Sync> DBWriter for ReadOnlyMiddleware<I> {
     type Inner = Self;
 
-    fn inner(&self) -> &Self::Inner {
-        self
+    fn inner(&self) -> Option<&Self::Inner> {
+        None
     }
 
     async fn write_block_analysis(&self, block_analysis: BlockAnalysis) -> eyre::Result<()> {
@@ -468,6 +605,112 @@ impl<I: DBWriter + Send + Sync> DBWriter for ReadOnlyMiddleware<I> {
             .await
     }
 
+    async fn write_beacon_info(
+        &self,
+        block_number: u64,
+        beacon_info: BeaconBlockInfo,
+    ) -> eyre::Result<()> {
+        self.inner
+            .write_beacon_info(block_number, beacon_info)
+            .await
+    }
+
+    async fn write_block_fees(&self, block_number: u64, fees: BlockFees) -> eyre::Result<()> {
+        self.inner.write_block_fees(block_number, fees).await
+    }
+
+    async fn write_pool_prices(
+        &self,
+        block_number: u64,
+        prices: BlockPoolPrices,
+    ) -> eyre::Result<()> {
+        self.inner.write_pool_prices(block_number, prices).await
+    }
+
+    async fn write_frontend_losses(
+        &self,
+        block_number: u64,
+        losses: BlockFrontendLosses,
+    ) -> eyre::Result<()> {
+        self.inner.write_frontend_losses(block_number, losses).await
+    }
+
+    async fn write_token_metadata(
+        &self,
+        address: Address,
+        metadata: TokenMetadata,
+    ) -> eyre::Result<()> {
+        self.inner.write_token_metadata(address, metadata).await
+    }
+
+    async fn write_wallet_fundings(
+        &self,
+        fundings: Vec<(Address, FundingEdge)>,
+    ) -> eyre::Result<()> {
+        self.inner.write_wallet_fundings(fundings).await
+    }
+
+    async fn write_block_cost(
+        &self,
+        block_number: u64,
+        cost: BlockCostRecorder,
+    ) -> eyre::Result<()> {
+        self.inner.write_block_cost(block_number, cost).await
+    }
+
+    async fn write_publish_checkpoint(&self, checkpoint: PublishCheckpoint) -> eyre::Result<()> {
+        self.inner.write_publish_checkpoint(checkpoint).await
+    }
+
+    async fn write_config_reload(&self, reload: ConfigReload) -> eyre::Result<()> {
+        self.inner.write_config_reload(reload).await
+    }
+
+    async fn append_bundle_override(&self, bundle_override: BundleOverride) -> eyre::Result<u64> {
+        self.inner.append_bundle_override(bundle_override).await
+    }
+
+    async fn append_suppression_change(&self, change: SuppressionChange) -> eyre::Result<u64> {
+        self.inner.append_suppression_change(change).await
+    }
+
+    async fn flush_writes(&self) -> eyre::Result<()> {
+        self.inner.flush_writes().await
+    }
+
+    async fn write_detected_bundle_tags(
+        &self,
+        block_number: u64,
+        tags: Vec<BundleTag>,
+    ) -> eyre::Result<()> {
+        self.inner
+            .write_detected_bundle_tags(block_number, tags)
+            .await
+    }
+
+    async fn write_inspector_artifacts(
+        &self,
+        block_number: u64,
+        artifacts: BlockInspectorArtifacts,
+    ) -> eyre::Result<()> {
+        self.inner
+            .write_inspector_artifacts(block_number, artifacts)
+            .await
+    }
+
+    async fn tag_bundle(&self, block_number: u64, tag: BundleTag) -> eyre::Result<bool> {
+        self.inner.tag_bundle(block_number, tag).await
+    }
+
+    async fn untag_bundle(
+        &self,
+        block_number: u64,
+        tx_hash: B256,
+        tag: String,
+    ) -> eyre::Result<bool> {
+        self.inner.untag_bundle(block_number, tx_hash, tag).await
+    }
+
     async fn insert_pool(
         &self,
         block: u64,
@@ -651,6 +894,120 @@ impl<I: LibmdbxInit> LibmdbxReader for ReadOnlyMiddleware<I> {
         self.inner.get_metadata(block_num, quote_asset)
     }
 
+    fn try_fetch_beacon_info(&self, block_num: u64) -> eyre::Result<Option<BeaconBlockInfo>> {
+        self.inner.try_fetch_beacon_info(block_num)
+    }
+
+    fn fetch_bundle_overrides(&self) -> eyre::Result<Vec<(u64, BundleOverride)>> {
+        self.inner.fetch_bundle_overrides()
+    }
+
+    fn fetch_suppression_changes(&self) -> eyre::Result<Vec<(u64, SuppressionChange)>> {
+        self.inner.fetch_suppression_changes()
+    }
+
+    fn fetch_suppression_list(&self) -> eyre::Result<SuppressionList> {
+        self.inner.fetch_suppression_list()
+    }
+
+    fn fetch_bundle_repricings(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockRepricings)>> {
+        self.inner.fetch_bundle_repricings(start_block, end_block)
+    }
+
+    fn try_fetch_wallet_funding(&self, address: Address) -> eyre::Result<Option<FundingEdge>> {
+        self.inner.try_fetch_wallet_funding(address)
+    }
+
+    fn try_fetch_mev_receipt(&self, tx_hash: B256) -> eyre::Result<Option<MevReceipt>> {
+        self.inner.try_fetch_mev_receipt(tx_hash)
+    }
+
+    fn fetch_bundle_tags(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockBundleTags)>> {
+        self.inner.fetch_bundle_tags(start_block, end_block)
+    }
+
+    fn fetch_inspector_artifacts(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockInspectorArtifacts)>> {
+        self.inner.fetch_inspector_artifacts(start_block, end_block)
+    }
+
+    fn try_fetch_block_fees(&self, block_num: u64) -> eyre::Result<Option<BlockFees>> {
+        self.inner.try_fetch_block_fees(block_num)
+    }
+
+    fn fetch_fee_history(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFees)>> {
+        self.inner.fetch_fee_history(start_block, end_block)
+    }
+
+    fn fetch_pool_prices(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockPoolPrices)>> {
+        self.inner.fetch_pool_prices(start_block, end_block)
+    }
+
+    fn fetch_frontend_losses(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFrontendLosses)>> {
+        self.inner.fetch_frontend_losses(start_block, end_block)
+    }
+
+    fn fetch_block_costs(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockCost)>> {
+        self.inner.fetch_block_costs(start_block, end_block)
+    }
+
+    fn try_fetch_builder_proposer_payments(
+        &self,
+        builder: Address,
+    ) -> eyre::Result<Option<BuilderProposerPayments>> {
+        self.inner.try_fetch_builder_proposer_payments(builder)
+    }
+
+    fn try_fetch_searcher_order_flow(
+        &self,
+        searcher: Address,
+    ) -> eyre::Result<Option<SearcherOrderFlow>> {
+        self.inner.try_fetch_searcher_order_flow(searcher)
+    }
+
+    fn try_fetch_searcher_stats(&self, searcher: Address) -> eyre::Result<Option<SearcherStats>> {
+        self.inner.try_fetch_searcher_stats(searcher)
+    }
+
+    fn fetch_all_searcher_stats(&self) -> eyre::Result<Vec<(Address, SearcherStats)>> {
+        self.inner.fetch_all_searcher_stats()
+    }
+
+    fn fetch_block_order_flows(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockOrderFlow)>> {
+        self.inner.fetch_block_order_flows(start_block, end_block)
+    }
+
     fn try_fetch_address_metadata(
         &self,
         address: Address,
@@ -669,6 +1026,14 @@ impl<I: LibmdbxInit> LibmdbxReader for ReadOnlyMiddleware<I> {
         self.inner.fetch_all_address_metadata()
     }
 
+    fn try_fetch_token_metadata(&self, address: Address) -> eyre::Result<Option<TokenMetadata>> {
+        self.inner.try_fetch_token_metadata(address)
+    }
+
+    fn fetch_all_token_metadata(&self) -> eyre::Result<Vec<(Address, TokenMetadata)>> {
+        self.inner.fetch_all_token_metadata()
+    }
+
     fn get_dex_quotes(&self, block: u64) -> eyre::Result<DexQuotes> {
         self.inner.get_dex_quotes(block)
     }
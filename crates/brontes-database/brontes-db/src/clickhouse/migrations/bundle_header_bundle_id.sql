@@ -0,0 +1,34 @@
+-- Moves an existing mev.bundle_header from the (`block_number`, `tx_hash`) key
+-- to the (`block_number`, `bundle_id`) key of tables/bundle_header.sql.
+--
+-- ClickHouse can't change the sorting key of existing columns in place, so the
+-- table is rebuilt with the new key and swapped in. Rows written before bundle
+-- ids keep their `tx_hash` as `bundle_id`, which keeps them unique under the
+-- new key. Requires the mev database to use the Atomic engine for the swap.
+
+-- 1. add the id, legacy rows read their tx hash
+ALTER TABLE mev.bundle_header ON CLUSTER eth_cluster0
+    ADD COLUMN IF NOT EXISTS `bundle_id` String DEFAULT `tx_hash` AFTER `tx_hash`;
+
+-- 2. same columns, new key
+CREATE TABLE mev.bundle_header_by_id ON CLUSTER eth_cluster0 AS mev.bundle_header
+ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/bundle_header_by_id', '{replica}', `run_id`)
+PRIMARY KEY (`block_number`, `bundle_id`)
+ORDER BY (`block_number`, `bundle_id`);
+
+-- 3. copy the rows, run on a single replica
+INSERT INTO mev.bundle_header_by_id SELECT * FROM mev.bundle_header;
+
+-- 4. swap the tables and drop the default, new rows always carry their id
+EXCHANGE TABLES mev.bundle_header AND mev.bundle_header_by_id ON CLUSTER eth_cluster0;
+
+ALTER TABLE mev.bundle_header ON CLUSTER eth_cluster0
+    MODIFY COLUMN `bundle_id` REMOVE DEFAULT;
+
+-- 5. once the row counts of both tables match, drop the old one
+-- DROP TABLE mev.bundle_header_by_id ON CLUSTER eth_cluster0 SYNC;
+
+-- Re-running a block range writes content addressed ids for its bundles. Drop
+-- the legacy rows of the range first so they don't sit next to the new ones:
+-- ALTER TABLE mev.bundle_header ON CLUSTER eth_cluster0
+--     DELETE WHERE `block_number` BETWEEN {start_block} AND {end_block} AND `bundle_id` = `tx_hash`;
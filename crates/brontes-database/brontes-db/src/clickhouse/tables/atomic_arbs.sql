@@ -20,6 +20,13 @@ CREATE TABLE mev.atomic_arbs ON CLUSTER eth_cluster0
         `effective_gas_price` UInt128
     ),
     `arb_type` String,
+    `leg_gas_details` Nested(
+        `tx_hash` String,
+        `coinbase_transfer` Nullable(UInt128),
+        `priority_fee` UInt128,
+        `gas_used` UInt128,
+        `effective_gas_price` UInt128
+    ),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/atomic_arbs', '{replica}', `run_id`)
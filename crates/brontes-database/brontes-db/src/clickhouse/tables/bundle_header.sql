@@ -3,6 +3,7 @@ CREATE TABLE mev.bundle_header ON CLUSTER eth_cluster0
     `block_number` UInt64,
     `tx_index` UInt64,
     `tx_hash` String,
+    `bundle_id` String,
     `eoa` String,
     `mev_contract` Nullable(String),
     `fund` String,
@@ -14,10 +15,28 @@ CREATE TABLE mev.bundle_header ON CLUSTER eth_cluster0
         `tx_hash` String,
         `address` String,
         `name` Nullable(String),
-        `token_deltas` Array(Tuple(Tuple(String, UInt8, String), Float64, Float64))
+        `token_deltas` Array(Tuple(Tuple(String, UInt8, String), Float64, Float64)),
+        `token_valuations` Array(String)
     ),
+    `min_action_confidence` Float64 DEFAULT 1,
+    `mean_action_confidence` Float64 DEFAULT 1,
+    `merged_submission` Bool DEFAULT false,
+    `co_bundled_txs` Array(String) DEFAULT [],
+    `searcher_value_usd` Float64 DEFAULT 0,
+    `builder_value_usd` Float64 DEFAULT 0,
+    `lp_fees` Nested (
+        `protocol` String,
+        `usd` Float64
+    ),
+    `protocol_fees_usd` Float64 DEFAULT 0,
+    `capital_source` String DEFAULT 'Inventory',
+    `flashloan_fee_usd` Float64 DEFAULT 0,
+    `capital_deployed_usd` Float64 DEFAULT 0,
+    `capital_at_risk_usd` Float64 DEFAULT 0,
+    `capital_turnover` Float64 DEFAULT 0,
+    `spam_profit_usd` Float64 DEFAULT 0,
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/bundle_header', '{replica}', `run_id`)
-PRIMARY KEY (`block_number`, `tx_hash`)
-ORDER BY (`block_number`, `tx_hash`)
+PRIMARY KEY (`block_number`, `bundle_id`)
+ORDER BY (`block_number`, `bundle_id`)
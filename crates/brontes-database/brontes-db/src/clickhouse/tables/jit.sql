@@ -49,6 +49,7 @@ CREATE TABLE mev.jit ON CLUSTER eth_cluster0
         `gas_used` UInt128,
         `effective_gas_price` UInt128
     ),
+    `backrun_block_number` UInt64 DEFAULT 0,
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/jit', '{replica}', `run_id`)
@@ -75,6 +75,20 @@ CREATE TABLE mev.jit_sandwich ON CLUSTER eth_cluster0
         `gas_used` UInt128,
         `effective_gas_price` UInt128
     ),
+    `victim_execution` Nested(
+        `tx_hash` String,
+        `router` String,
+        `pool` String,
+        `token_in` String,
+        `token_out` String,
+        `amount_in` Float64,
+        `amount_out` Float64,
+        `pre_frontrun_amount_out` Nullable(Float64),
+        `cex_amount_out` Nullable(Float64),
+        `quoted_min_amount_out` Nullable(Float64),
+        `realized_amount_out` Nullable(Float64),
+        `loss_usd` Nullable(Float64)
+    ),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/jit_sandwich', '{replica}', `run_id`)
@@ -31,6 +31,11 @@ CREATE TABLE mev.mev_blocks ON CLUSTER eth_cluster0
     `proposer_fee_recipient` Nullable(String),
     `proposer_mev_reward` Nullable(UInt128),
     `proposer_profit_usd` Nullable(Float64),
+    `slot` Nullable(UInt64),
+    `proposer_index` Nullable(UInt64),
+    `proposer_pubkey` Nullable(String),
+    `missed_slots` Nullable(UInt64),
+    `pre_merge` Bool,
     `total_mev_profit_usd` Float64,
     `possible_mev` Nested (
         `tx_hash` String,
@@ -43,6 +48,7 @@ CREATE TABLE mev.mev_blocks ON CLUSTER eth_cluster0
         `triggers.coinbase_transfer` Bool,
         `triggers.high_priority_fee` Bool
     ),
+    `skipped_inspectors` Array(String),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/mev_blocks', '{replica}', `run_id`)
@@ -57,6 +57,24 @@ CREATE TABLE mev.sandwiches ON CLUSTER eth_cluster0
         `gas_used` UInt128,
         `effective_gas_price` UInt128
     ),
+    `victim_execution` Nested(
+        `tx_hash` String,
+        `router` String,
+        `pool` String,
+        `token_in` String,
+        `token_out` String,
+        `amount_in` Float64,
+        `amount_out` Float64,
+        `pre_frontrun_amount_out` Nullable(Float64),
+        `cex_amount_out` Nullable(Float64),
+        `quoted_min_amount_out` Nullable(Float64),
+        `realized_amount_out` Nullable(Float64),
+        `loss_usd` Nullable(Float64)
+    ),
+    `victim_actions` Nested(
+        `tx_hash` String,
+        `action_type` LowCardinality(String)
+    ),
     `run_id` UInt64
 ) 
 ENGINE = ReplicatedReplacingMergeTree('/clickhouse/eth_cluster0/tables/all/mev/sandwiches', '{replica}', `run_id`)
@@ -0,0 +1,1082 @@
+//! In-memory database for tests and ephemeral analysis.
+//!
+//! [`InMemoryDb`] implements [`LibmdbxReader`] and [`DBWriter`] on top of
+//! plain maps, so it can be used anywhere the libmdbx database is without
+//! creating an on-disk environment. Data that isn't written by brontes itself
+//! (block metadata, cex quotes and trades) can be inserted directly.
+use std::collections::BTreeMap;
+
+use alloy_primitives::{Address, B256};
+use brontes_pricing::Protocol;
+use brontes_types::{
+    constants::{ETH_ADDRESS, WETH_ADDRESS},
+    db::{
+        address_metadata::AddressMetadata,
+        address_to_protocol_info::ProtocolInfo,
+        beacon::BeaconBlockInfo,
+        block_costs::{BlockCost, BlockCostRecorder},
+        builder::BuilderInfo,
+        bundle_tags::{BlockBundleTags, BundleTag},
+        cex::{quotes::CexPriceMap, trades::CexTradeMap},
+        config_reload::ConfigReload,
+        dex::DexQuotes,
+        fee_history::BlockFees,
+        frontend_losses::BlockFrontendLosses,
+        inspector_artifacts::BlockInspectorArtifacts,
+        metadata::{BlockMetadata, BlockMetadataInner, Metadata},
+        mev_block::MevBlockWithClassified,
+        mev_receipt::MevReceipt,
+        order_flow::{BlockOrderFlow, BuilderProposerPayments, SearcherOrderFlow},
+        overrides::{BundleOverride, BundleOverrides},
+        pool_prices::BlockPoolPrices,
+        publish_checkpoint::PublishCheckpoint,
+        repricing::BlockRepricings,
+        searcher::SearcherInfo,
+        searcher_stats::{BlockSearcherStats, SearcherStats},
+        suppression::SuppressionChange,
+        token_info::{TokenInfo, TokenInfoWithAddress},
+        token_metadata::TokenMetadata,
+        traits::{DBWriter, LibmdbxReader, ProtocolCreatedRange},
+        wallet_funding::FundingEdge,
+    },
+    mev::{Bundle, MevBlock},
+    normalized_actions::Action,
+    pair::Pair,
+    structured_trace::TxTrace,
+    BlockTree, FastHashMap,
+};
+use eyre::eyre;
+use parking_lot::RwLock;
+
+use crate::libmdbx::determine_eth_prices;
+
+#[derive(Debug, Default)]
+struct InMemoryTables {
+    block_info:           FastHashMap<u64, BlockMetadataInner>,
+    cex_quotes:           FastHashMap<u64, CexPriceMap>,
+    cex_trades:           FastHashMap<u64, CexTradeMap>,
+    dex_quotes:           FastHashMap<u64, DexQuotes>,
+    traces:               FastHashMap<u64, Vec<TxTrace>>,
+    tokens:               FastHashMap<Address, TokenInfo>,
+    protocols:            FastHashMap<Address, ProtocolInfo>,
+    pool_creation_blocks: BTreeMap<u64, Vec<Address>>,
+    searcher_eoas:        FastHashMap<Address, SearcherInfo>,
+    searcher_contracts:   FastHashMap<Address, SearcherInfo>,
+    builders:             FastHashMap<Address, BuilderInfo>,
+    address_meta:         FastHashMap<Address, AddressMetadata>,
+    token_metadata:       FastHashMap<Address, TokenMetadata>,
+    beacon_info:          FastHashMap<u64, BeaconBlockInfo>,
+    fee_history:          BTreeMap<u64, BlockFees>,
+    pool_prices:          BTreeMap<u64, BlockPoolPrices>,
+    frontend_losses:      BTreeMap<u64, BlockFrontendLosses>,
+    block_costs:          BTreeMap<u64, BlockCost>,
+    mev_blocks:           BTreeMap<u64, MevBlockWithClassified>,
+    block_order_flows:    BTreeMap<u64, BlockOrderFlow>,
+    builder_payments:     FastHashMap<Address, BuilderProposerPayments>,
+    searcher_order_flows: FastHashMap<Address, SearcherOrderFlow>,
+    block_searcher_stats: BTreeMap<u64, BlockSearcherStats>,
+    searcher_stats:       FastHashMap<Address, SearcherStats>,
+    bundle_overrides:     Vec<BundleOverride>,
+    suppressions:         Vec<SuppressionChange>,
+    bundle_tags:          BTreeMap<u64, BlockBundleTags>,
+    inspector_artifacts:  BTreeMap<u64, BlockInspectorArtifacts>,
+    wallet_fundings:      FastHashMap<Address, FundingEdge>,
+    mev_receipts:         FastHashMap<B256, MevReceipt>,
+    publish_checkpoints:  FastHashMap<String, PublishCheckpoint>,
+    config_reloads:       Vec<ConfigReload>,
+}
+
+impl InMemoryTables {
+    /// Rewrites the receipts of the block to follow its curated view
+    fn refresh_mev_receipts(&mut self, block_number: u64) {
+        let Some(raw) = self.mev_blocks.get(&block_number) else { return };
+        let overrides = self
+            .bundle_overrides
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(id, o)| (id as u64, o))
+            .collect();
+
+        let (receipts, stale) = MevReceipt::for_block(raw, None, overrides);
+        for tx_hash in stale {
+            self.mev_receipts.remove(&tx_hash);
+        }
+        for receipt in receipts {
+            self.mev_receipts.insert(receipt.tx_hash, receipt);
+        }
+    }
+
+    fn update_order_flow(&mut self, block_number: u64, flow: BlockOrderFlow) {
+        if let Some(previous) = self.block_order_flows.remove(&block_number) {
+            self.builder_payments
+                .entry(previous.builder)
+                .or_default()
+                .remove_block(&previous);
+            for count in &previous.searcher_bundles {
+                self.searcher_order_flows
+                    .entry(count.searcher)
+                    .or_default()
+                    .remove_bundles(previous.builder, count.bundles);
+            }
+        }
+
+        self.builder_payments
+            .entry(flow.builder)
+            .or_default()
+            .add_block(&flow);
+        for count in &flow.searcher_bundles {
+            self.searcher_order_flows
+                .entry(count.searcher)
+                .or_default()
+                .add_bundles(flow.builder, count.bundles);
+        }
+        self.block_order_flows.insert(block_number, flow);
+    }
+
+    /// Replaces the block's contribution to the searcher statistics with the
+    /// one of its curated view
+    fn refresh_searcher_stats(&mut self, block_number: u64) {
+        let Some(raw) = self.mev_blocks.get(&block_number) else { return };
+        let mut curated = raw.clone();
+        curated.curate(
+            None,
+            &BundleOverrides::new(
+                self.bundle_overrides
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .map(|(id, o)| (id as u64, o))
+                    .collect(),
+            ),
+        );
+        let block_stats = BlockSearcherStats::new(&curated.mev);
+
+        if let Some(previous) = self.block_searcher_stats.remove(&block_number) {
+            for stats in &previous.searchers {
+                self.searcher_stats
+                    .entry(stats.searcher)
+                    .or_default()
+                    .remove_block(stats);
+            }
+        }
+
+        for stats in &block_stats.searchers {
+            self.searcher_stats
+                .entry(stats.searcher)
+                .or_default()
+                .add_block(block_number, stats);
+        }
+        self.block_searcher_stats.insert(block_number, block_stats);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InMemoryDb {
+    tables: RwLock<InMemoryTables>,
+}
+
+impl InMemoryDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_block_metadata(&self, block_num: u64, metadata: BlockMetadataInner) {
+        self.tables.write().block_info.insert(block_num, metadata);
+    }
+
+    pub fn insert_cex_quotes(&self, block_num: u64, quotes: CexPriceMap) {
+        self.tables.write().cex_quotes.insert(block_num, quotes);
+    }
+
+    pub fn insert_cex_trades(&self, block_num: u64, trades: CexTradeMap) {
+        self.tables.write().cex_trades.insert(block_num, trades);
+    }
+
+    fn block_metadata(&self, block_num: u64, quote_asset: Address) -> eyre::Result<BlockMetadata> {
+        let tables = self.tables.read();
+        let block_meta = tables.block_info.get(&block_num).cloned().ok_or_else(|| {
+            eyre!("Failed to fetch Metadata's block info for block {}", block_num)
+        })?;
+        let cex_quotes = tables
+            .cex_quotes
+            .get(&block_num)
+            .cloned()
+            .unwrap_or_default();
+
+        let eth_price =
+            determine_eth_prices(&cex_quotes, block_meta.block_timestamp * 1_000_000, quote_asset);
+
+        Ok(BlockMetadata::new(
+            block_num,
+            block_meta.block_hash,
+            block_meta.block_timestamp,
+            block_meta.relay_timestamp,
+            block_meta.p2p_timestamp,
+            block_meta.proposer_fee_recipient,
+            block_meta.proposer_mev_reward,
+            eth_price.unwrap_or_default(),
+            block_meta.private_flow.into_iter().collect(),
+        )
+        .with_beacon_info(tables.beacon_info.get(&block_num).cloned()))
+    }
+
+    fn protocols_created<R: std::ops::RangeBounds<u64>>(
+        &self,
+        range: R,
+    ) -> Vec<(u64, Address, Protocol, Pair)> {
+        let tables = self.tables.read();
+        tables
+            .pool_creation_blocks
+            .range(range)
+            .flat_map(|(block, pools)| pools.iter().map(move |pool| (*block, *pool)))
+            .filter_map(|(block, pool)| {
+                let info = tables.protocols.get(&pool)?;
+                Some((block, pool, info.protocol, Pair(info.token0, info.token1)))
+            })
+            .collect()
+    }
+}
+
+impl LibmdbxReader for InMemoryDb {
+    fn get_metadata_no_dex_price(
+        &self,
+        block_num: u64,
+        quote_asset: Address,
+    ) -> eyre::Result<Metadata> {
+        let block_meta = self.block_metadata(block_num, quote_asset)?;
+        let cex_quotes = self
+            .tables
+            .read()
+            .cex_quotes
+            .get(&block_num)
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(block_meta.into_metadata(cex_quotes, None, None, None))
+    }
+
+    fn get_metadata(&self, block_num: u64, quote_asset: Address) -> eyre::Result<Metadata> {
+        let block_meta = self.block_metadata(block_num, quote_asset)?;
+        let tables = self.tables.read();
+        let cex_quotes = tables
+            .cex_quotes
+            .get(&block_num)
+            .cloned()
+            .unwrap_or_default();
+        let dex_quotes = tables
+            .dex_quotes
+            .get(&block_num)
+            .cloned()
+            .unwrap_or(DexQuotes(vec![]));
+
+        Ok(block_meta.into_metadata(cex_quotes, Some(dex_quotes), None, None))
+    }
+
+    fn has_dex_quotes(&self, block_num: u64) -> eyre::Result<bool> {
+        Ok(self.tables.read().dex_quotes.contains_key(&block_num))
+    }
+
+    fn get_dex_quotes(&self, block: u64) -> eyre::Result<DexQuotes> {
+        Ok(self
+            .tables
+            .read()
+            .dex_quotes
+            .get(&block)
+            .cloned()
+            .unwrap_or(DexQuotes(vec![])))
+    }
+
+    fn get_cex_trades(&self, block: u64) -> eyre::Result<CexTradeMap> {
+        let tables = self.tables.read();
+        let mut trades = CexTradeMap::default();
+        for current_block in block..=block + 5 {
+            let block_trades = tables
+                .cex_trades
+                .get(&current_block)
+                .cloned()
+                .ok_or_else(|| eyre!("no cex trades"))?;
+            trades.merge_in_map(block_trades);
+        }
+
+        Ok(trades)
+    }
+
+    fn try_fetch_address_metadatas(
+        &self,
+        addresses: Vec<Address>,
+    ) -> eyre::Result<FastHashMap<Address, AddressMetadata>> {
+        let tables = self.tables.read();
+        Ok(addresses
+            .into_iter()
+            .filter_map(|address| Some((address, tables.address_meta.get(&address)?.clone())))
+            .collect())
+    }
+
+    fn try_fetch_address_metadata(
+        &self,
+        address: Address,
+    ) -> eyre::Result<Option<AddressMetadata>> {
+        Ok(self.tables.read().address_meta.get(&address).cloned())
+    }
+
+    fn fetch_all_address_metadata(&self) -> eyre::Result<Vec<(Address, AddressMetadata)>> {
+        Ok(self
+            .tables
+            .read()
+            .address_meta
+            .iter()
+            .map(|(address, meta)| (*address, meta.clone()))
+            .collect())
+    }
+
+    fn try_fetch_token_metadata(&self, address: Address) -> eyre::Result<Option<TokenMetadata>> {
+        Ok(self.tables.read().token_metadata.get(&address).cloned())
+    }
+
+    fn fetch_all_token_metadata(&self) -> eyre::Result<Vec<(Address, TokenMetadata)>> {
+        Ok(self
+            .tables
+            .read()
+            .token_metadata
+            .iter()
+            .map(|(address, metadata)| (*address, metadata.clone()))
+            .collect())
+    }
+
+    fn fetch_all_searcher_eoa_info(&self) -> eyre::Result<Vec<(Address, SearcherInfo)>> {
+        Ok(self
+            .tables
+            .read()
+            .searcher_eoas
+            .iter()
+            .map(|(address, info)| (*address, info.clone()))
+            .collect())
+    }
+
+    fn fetch_all_searcher_contract_info(&self) -> eyre::Result<Vec<(Address, SearcherInfo)>> {
+        Ok(self
+            .tables
+            .read()
+            .searcher_contracts
+            .iter()
+            .map(|(address, info)| (*address, info.clone()))
+            .collect())
+    }
+
+    fn try_fetch_searcher_eoa_info(
+        &self,
+        searcher_eoa: Address,
+    ) -> eyre::Result<Option<SearcherInfo>> {
+        Ok(self.tables.read().searcher_eoas.get(&searcher_eoa).cloned())
+    }
+
+    fn try_fetch_searcher_contract_info(
+        &self,
+        searcher_contract: Address,
+    ) -> eyre::Result<Option<SearcherInfo>> {
+        Ok(self
+            .tables
+            .read()
+            .searcher_contracts
+            .get(&searcher_contract)
+            .cloned())
+    }
+
+    fn try_fetch_searcher_eoa_infos(
+        &self,
+        searcher_eoa: Vec<Address>,
+    ) -> eyre::Result<FastHashMap<Address, SearcherInfo>> {
+        let tables = self.tables.read();
+        Ok(searcher_eoa
+            .into_iter()
+            .filter_map(|eoa| Some((eoa, tables.searcher_eoas.get(&eoa)?.clone())))
+            .collect())
+    }
+
+    fn try_fetch_searcher_contract_infos(
+        &self,
+        searcher_contract: Vec<Address>,
+    ) -> eyre::Result<FastHashMap<Address, SearcherInfo>> {
+        let tables = self.tables.read();
+        Ok(searcher_contract
+            .into_iter()
+            .filter_map(|contract| {
+                Some((contract, tables.searcher_contracts.get(&contract)?.clone()))
+            })
+            .collect())
+    }
+
+    fn try_fetch_builder_info(
+        &self,
+        builder_coinbase_addr: Address,
+    ) -> eyre::Result<Option<BuilderInfo>> {
+        Ok(self
+            .tables
+            .read()
+            .builders
+            .get(&builder_coinbase_addr)
+            .cloned())
+    }
+
+    fn fetch_all_builder_info(&self) -> eyre::Result<Vec<(Address, BuilderInfo)>> {
+        Ok(self
+            .tables
+            .read()
+            .builders
+            .iter()
+            .map(|(address, info)| (*address, info.clone()))
+            .collect())
+    }
+
+    fn try_fetch_beacon_info(&self, block_num: u64) -> eyre::Result<Option<BeaconBlockInfo>> {
+        Ok(self.tables.read().beacon_info.get(&block_num).cloned())
+    }
+
+    fn fetch_bundle_overrides(&self) -> eyre::Result<Vec<(u64, BundleOverride)>> {
+        Ok(self
+            .tables
+            .read()
+            .bundle_overrides
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(id, o)| (id as u64, o))
+            .collect())
+    }
+
+    fn fetch_suppression_changes(&self) -> eyre::Result<Vec<(u64, SuppressionChange)>> {
+        Ok(self
+            .tables
+            .read()
+            .suppressions
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(id, c)| (id as u64, c))
+            .collect())
+    }
+
+    fn fetch_bundle_repricings(
+        &self,
+        _start_block: Option<u64>,
+        _end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockRepricings)>> {
+        // bundles are only repriced in the libmdbx db, by `brontes db reprice`
+        Ok(vec![])
+    }
+
+    fn try_fetch_wallet_funding(&self, address: Address) -> eyre::Result<Option<FundingEdge>> {
+        Ok(self.tables.read().wallet_fundings.get(&address).cloned())
+    }
+
+    fn try_fetch_mev_receipt(&self, tx_hash: B256) -> eyre::Result<Option<MevReceipt>> {
+        Ok(self.tables.read().mev_receipts.get(&tx_hash).cloned())
+    }
+
+    fn fetch_bundle_tags(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockBundleTags)>> {
+        Ok(self
+            .tables
+            .read()
+            .bundle_tags
+            .range(start_block..end_block)
+            .map(|(block, tags)| (*block, tags.clone()))
+            .collect())
+    }
+
+    fn fetch_inspector_artifacts(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockInspectorArtifacts)>> {
+        Ok(self
+            .tables
+            .read()
+            .inspector_artifacts
+            .range(start_block..end_block)
+            .map(|(block, artifacts)| (*block, artifacts.clone()))
+            .collect())
+    }
+
+    fn try_fetch_block_fees(&self, block_num: u64) -> eyre::Result<Option<BlockFees>> {
+        Ok(self.tables.read().fee_history.get(&block_num).cloned())
+    }
+
+    fn fetch_fee_history(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFees)>> {
+        Ok(self
+            .tables
+            .read()
+            .fee_history
+            .range(start_block..end_block)
+            .map(|(block, fees)| (*block, fees.clone()))
+            .collect())
+    }
+
+    fn fetch_pool_prices(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockPoolPrices)>> {
+        Ok(self
+            .tables
+            .read()
+            .pool_prices
+            .range(start_block..end_block)
+            .map(|(block, prices)| (*block, prices.clone()))
+            .collect())
+    }
+
+    fn fetch_frontend_losses(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFrontendLosses)>> {
+        Ok(self
+            .tables
+            .read()
+            .frontend_losses
+            .range(start_block..end_block)
+            .map(|(block, losses)| (*block, losses.clone()))
+            .collect())
+    }
+
+    fn fetch_block_costs(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockCost)>> {
+        Ok(self
+            .tables
+            .read()
+            .block_costs
+            .range(start_block..end_block)
+            .map(|(block, cost)| (*block, *cost))
+            .collect())
+    }
+
+    fn try_fetch_builder_proposer_payments(
+        &self,
+        builder: Address,
+    ) -> eyre::Result<Option<BuilderProposerPayments>> {
+        Ok(self.tables.read().builder_payments.get(&builder).cloned())
+    }
+
+    fn try_fetch_searcher_order_flow(
+        &self,
+        searcher: Address,
+    ) -> eyre::Result<Option<SearcherOrderFlow>> {
+        Ok(self
+            .tables
+            .read()
+            .searcher_order_flows
+            .get(&searcher)
+            .cloned())
+    }
+
+    fn try_fetch_searcher_stats(&self, searcher: Address) -> eyre::Result<Option<SearcherStats>> {
+        Ok(self.tables.read().searcher_stats.get(&searcher).cloned())
+    }
+
+    fn fetch_all_searcher_stats(&self) -> eyre::Result<Vec<(Address, SearcherStats)>> {
+        Ok(self
+            .tables
+            .read()
+            .searcher_stats
+            .iter()
+            .filter(|(_, stats)| !stats.is_empty())
+            .map(|(searcher, stats)| (*searcher, stats.clone()))
+            .collect())
+    }
+
+    fn fetch_block_order_flows(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockOrderFlow)>> {
+        Ok(self
+            .tables
+            .read()
+            .block_order_flows
+            .range(start_block..end_block)
+            .map(|(block, flow)| (*block, flow.clone()))
+            .collect())
+    }
+
+    fn try_fetch_token_info(&self, og_address: Address) -> eyre::Result<TokenInfoWithAddress> {
+        let address = if og_address == ETH_ADDRESS { WETH_ADDRESS } else { og_address };
+
+        let mut info = self
+            .tables
+            .read()
+            .tokens
+            .get(&address)
+            .cloned()
+            .map(|inner| TokenInfoWithAddress { inner, address: og_address })
+            .ok_or_else(|| eyre!("entry for key {:?} in TokenDecimals", address))?;
+        if og_address == ETH_ADDRESS {
+            info.symbol = "ETH".to_string();
+        }
+
+        Ok(info)
+    }
+
+    fn try_fetch_mev_blocks(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+    ) -> eyre::Result<Vec<MevBlockWithClassified>> {
+        Ok(self
+            .tables
+            .read()
+            .mev_blocks
+            .range(start_block.unwrap_or_default()..=end_block)
+            .map(|(_, block)| block.clone())
+            .collect())
+    }
+
+    fn fetch_all_mev_blocks(
+        &self,
+        start_block: Option<u64>,
+    ) -> eyre::Result<Vec<MevBlockWithClassified>> {
+        Ok(self
+            .tables
+            .read()
+            .mev_blocks
+            .range(start_block.unwrap_or_default()..)
+            .map(|(_, block)| block.clone())
+            .collect())
+    }
+
+    fn protocols_created_before(
+        &self,
+        start_block: u64,
+    ) -> eyre::Result<FastHashMap<(Address, Protocol), Pair>> {
+        Ok(self
+            .protocols_created(..=start_block)
+            .into_iter()
+            .map(|(_, pool, protocol, pair)| ((pool, protocol), pair))
+            .collect())
+    }
+
+    fn protocols_created_range(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<ProtocolCreatedRange> {
+        let mut map = ProtocolCreatedRange::default();
+        for (block, pool, protocol, pair) in self.protocols_created(start_block..end_block) {
+            map.entry(block).or_default().push((pool, protocol, pair));
+        }
+
+        Ok(map)
+    }
+
+    fn get_protocol_details(&self, address: Address) -> eyre::Result<ProtocolInfo> {
+        self.tables
+            .read()
+            .protocols
+            .get(&address)
+            .cloned()
+            .ok_or_else(|| eyre!("entry for key {:?} in AddressToProtocolInfo", address))
+    }
+
+    fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>> {
+        self.tables
+            .read()
+            .traces
+            .get(&block_num)
+            .cloned()
+            .ok_or_else(|| eyre!("missing trace for block: {}", block_num))
+    }
+}
+
+impl DBWriter for InMemoryDb {
+    type Inner = Self;
+
+    fn inner(&self) -> Option<&Self::Inner> {
+        None
+    }
+
+    async fn write_dex_quotes(
+        &self,
+        block_number: u64,
+        quotes: Option<DexQuotes>,
+    ) -> eyre::Result<()> {
+        if let Some(quotes) = quotes {
+            self.tables.write().dex_quotes.insert(block_number, quotes);
+        }
+
+        Ok(())
+    }
+
+    async fn write_token_info(
+        &self,
+        address: Address,
+        decimals: u8,
+        symbol: String,
+    ) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .tokens
+            .insert(address, TokenInfo::new(decimals, symbol));
+
+        Ok(())
+    }
+
+    async fn save_mev_blocks(
+        &self,
+        block_number: u64,
+        block: MevBlock,
+        mev: Vec<Bundle>,
+    ) -> eyre::Result<()> {
+        let mut tables = self.tables.write();
+        tables.update_order_flow(block_number, BlockOrderFlow::new(&block, &mev));
+        tables
+            .mev_blocks
+            .insert(block_number, MevBlockWithClassified { block, mev });
+        tables.refresh_mev_receipts(block_number);
+        tables.refresh_searcher_stats(block_number);
+
+        Ok(())
+    }
+
+    async fn write_searcher_info(
+        &self,
+        eoa_address: Address,
+        contract_address: Option<Address>,
+        eoa_info: SearcherInfo,
+        contract_info: Option<SearcherInfo>,
+    ) -> eyre::Result<()> {
+        let mut tables = self.tables.write();
+        tables.searcher_eoas.insert(eoa_address, eoa_info);
+        if let Some(contract_address) = contract_address {
+            tables
+                .searcher_contracts
+                .insert(contract_address, contract_info.unwrap_or_default());
+        }
+
+        Ok(())
+    }
+
+    async fn write_searcher_eoa_info(
+        &self,
+        searcher_eoa: Address,
+        searcher_info: SearcherInfo,
+    ) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .searcher_eoas
+            .insert(searcher_eoa, searcher_info);
+
+        Ok(())
+    }
+
+    async fn write_searcher_contract_info(
+        &self,
+        searcher_contract: Address,
+        searcher_info: SearcherInfo,
+    ) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .searcher_contracts
+            .insert(searcher_contract, searcher_info);
+
+        Ok(())
+    }
+
+    async fn write_builder_info(
+        &self,
+        builder_address: Address,
+        builder_info: BuilderInfo,
+    ) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .builders
+            .insert(builder_address, builder_info);
+
+        Ok(())
+    }
+
+    async fn write_beacon_info(
+        &self,
+        block_number: u64,
+        beacon_info: BeaconBlockInfo,
+    ) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .beacon_info
+            .insert(block_number, beacon_info);
+
+        Ok(())
+    }
+
+    async fn write_block_fees(&self, block_number: u64, fees: BlockFees) -> eyre::Result<()> {
+        self.tables.write().fee_history.insert(block_number, fees);
+        Ok(())
+    }
+
+    async fn write_pool_prices(
+        &self,
+        block_number: u64,
+        prices: BlockPoolPrices,
+    ) -> eyre::Result<()> {
+        self.tables.write().pool_prices.insert(block_number, prices);
+
+        Ok(())
+    }
+
+    async fn write_frontend_losses(
+        &self,
+        block_number: u64,
+        losses: BlockFrontendLosses,
+    ) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .frontend_losses
+            .insert(block_number, losses);
+
+        Ok(())
+    }
+
+    async fn write_token_metadata(
+        &self,
+        address: Address,
+        metadata: TokenMetadata,
+    ) -> eyre::Result<()> {
+        self.tables.write().token_metadata.insert(address, metadata);
+
+        Ok(())
+    }
+
+    async fn write_wallet_fundings(
+        &self,
+        fundings: Vec<(Address, FundingEdge)>,
+    ) -> eyre::Result<()> {
+        let mut tables = self.tables.write();
+        for (address, edge) in fundings {
+            tables.wallet_fundings.entry(address).or_insert(edge);
+        }
+
+        Ok(())
+    }
+
+    async fn write_block_cost(
+        &self,
+        block_number: u64,
+        cost: BlockCostRecorder,
+    ) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .block_costs
+            .insert(block_number, cost.take());
+
+        Ok(())
+    }
+
+    async fn write_publish_checkpoint(&self, checkpoint: PublishCheckpoint) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .publish_checkpoints
+            .insert(checkpoint.sink.clone(), checkpoint);
+
+        Ok(())
+    }
+
+    async fn write_config_reload(&self, reload: ConfigReload) -> eyre::Result<()> {
+        self.tables.write().config_reloads.push(reload);
+
+        Ok(())
+    }
+
+    async fn append_bundle_override(&self, bundle_override: BundleOverride) -> eyre::Result<u64> {
+        let mut tables = self.tables.write();
+        let block_number = bundle_override.block_number;
+        tables.bundle_overrides.push(bundle_override);
+        tables.refresh_mev_receipts(block_number);
+        tables.refresh_searcher_stats(block_number);
+
+        Ok(tables.bundle_overrides.len() as u64 - 1)
+    }
+
+    /// Writes are applied as they're made
+    async fn flush_writes(&self) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    async fn append_suppression_change(&self, change: SuppressionChange) -> eyre::Result<u64> {
+        let mut tables = self.tables.write();
+        tables.suppressions.push(change);
+
+        Ok(tables.suppressions.len() as u64 - 1)
+    }
+
+    async fn write_detected_bundle_tags(
+        &self,
+        block_number: u64,
+        tags: Vec<BundleTag>,
+    ) -> eyre::Result<()> {
+        self.tables
+            .write()
+            .bundle_tags
+            .entry(block_number)
+            .or_default()
+            .replace_detected(tags);
+
+        Ok(())
+    }
+
+    async fn write_inspector_artifacts(
+        &self,
+        block_number: u64,
+        artifacts: BlockInspectorArtifacts,
+    ) -> eyre::Result<()> {
+        let mut tables = self.tables.write();
+        if artifacts.is_empty() {
+            tables.inspector_artifacts.remove(&block_number);
+        } else {
+            tables.inspector_artifacts.insert(block_number, artifacts);
+        }
+
+        Ok(())
+    }
+
+    async fn tag_bundle(&self, block_number: u64, tag: BundleTag) -> eyre::Result<bool> {
+        Ok(self
+            .tables
+            .write()
+            .bundle_tags
+            .entry(block_number)
+            .or_default()
+            .add(tag))
+    }
+
+    async fn untag_bundle(
+        &self,
+        block_number: u64,
+        tx_hash: B256,
+        tag: String,
+    ) -> eyre::Result<bool> {
+        Ok(self
+            .tables
+            .write()
+            .bundle_tags
+            .get_mut(&block_number)
+            .is_some_and(|tags| tags.remove(tx_hash, &tag)))
+    }
+
+    async fn write_address_meta(
+        &self,
+        address: Address,
+        metadata: AddressMetadata,
+    ) -> eyre::Result<()> {
+        self.tables.write().address_meta.insert(address, metadata);
+
+        Ok(())
+    }
+
+    async fn insert_pool(
+        &self,
+        block: u64,
+        address: Address,
+        tokens: &[Address],
+        curve_lp_token: Option<Address>,
+        classifier_name: Protocol,
+    ) -> eyre::Result<()> {
+        let mut tokens = tokens.iter().copied();
+        let details = ProtocolInfo {
+            protocol: classifier_name,
+            init_block: block,
+            token0: tokens.next().unwrap_or_default(),
+            token1: tokens.next().unwrap_or_default(),
+            token2: tokens.next(),
+            token3: tokens.next(),
+            token4: tokens.next(),
+            curve_lp_token,
+        };
+
+        let mut tables = self.tables.write();
+        tables.protocols.insert(address, details);
+        tables
+            .pool_creation_blocks
+            .entry(block)
+            .or_default()
+            .push(address);
+
+        Ok(())
+    }
+
+    async fn save_traces(&self, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
+        self.tables.write().traces.insert(block, traces);
+
+        Ok(())
+    }
+
+    async fn insert_tree(&self, _tree: BlockTree<Action>) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    async fn write_block_analysis(
+        &self,
+        _: brontes_types::db::block_analysis::BlockAnalysis,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::mev::BundleHeader;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_round_trip() {
+        let db = InMemoryDb::new();
+        let (token, pool) = (Address::repeat_byte(1), Address::repeat_byte(2));
+
+        db.write_token_info(token, 18, "TKN".to_string())
+            .await
+            .unwrap();
+        db.insert_pool(10, pool, &[token, WETH_ADDRESS], None, Protocol::UniswapV2)
+            .await
+            .unwrap();
+
+        assert_eq!(db.try_fetch_token_decimals(token).unwrap(), 18);
+        assert_eq!(db.get_protocol(pool).unwrap(), Protocol::UniswapV2);
+        assert_eq!(db.protocols_created_before(10).unwrap().len(), 1);
+        assert!(db.protocols_created_range(11, 20).unwrap().is_empty());
+        assert!(db.load_trace(10).is_err());
+
+        let searcher = Address::repeat_byte(3);
+        let bundle = Bundle {
+            header: BundleHeader { eoa: searcher, ..Default::default() },
+            data:   Default::default(),
+        };
+        db.save_mev_blocks(10, MevBlock::default(), vec![bundle.clone()])
+            .await
+            .unwrap();
+        // reprocessing a block replaces its order flow
+        db.save_mev_blocks(10, MevBlock::default(), vec![bundle])
+            .await
+            .unwrap();
+
+        assert_eq!(db.fetch_all_mev_blocks(None).unwrap().len(), 1);
+        assert_eq!(
+            db.try_fetch_searcher_order_flow(searcher)
+                .unwrap()
+                .unwrap()
+                .total_bundles(),
+            1
+        );
+        let stats = db.try_fetch_searcher_stats(searcher).unwrap().unwrap();
+        assert_eq!((stats.totals.bundles, stats.active_blocks), (1, 1));
+    }
+}
@@ -4,7 +4,9 @@
 #![feature(noop_waker)]
 
 pub mod clickhouse;
+pub mod in_memory;
 pub mod libmdbx;
+#[cfg(feature = "parquet")]
 pub mod parquet;
 pub use libmdbx::{
     tables::*,
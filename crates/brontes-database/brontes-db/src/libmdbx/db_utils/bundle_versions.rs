@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+
+use brontes_types::db::{
+    block_checksum::BlockChecksum, mev_block::MevBlockWithClassifiedRedefined,
+};
+use itertools::Itertools;
+
+use crate::libmdbx::{
+    tables::{BlockChecksums, MevBlocks},
+    Libmdbx,
+};
+
+/// Blocks rewritten per write transaction
+const UPGRADE_BATCH: usize = 1_000;
+
+/// Outcome of [`upgrade_mev_blocks`]
+#[derive(Debug, Default)]
+pub struct MevBlocksUpgrade {
+    /// Blocks rewritten in the current layout
+    pub upgraded:    usize,
+    /// Blocks whose row matches none of the known layouts, they're left as
+    /// they are
+    pub undecodable: Vec<u64>,
+}
+
+/// Rewrites every stored mev block in the current versioned layout, with the
+/// checksum of its upcast results. Rows are only rewritten once they've been
+/// decoded and validated, an undecodable row is reported and left untouched
+pub fn upgrade_mev_blocks(db: &Libmdbx) -> eyre::Result<MevBlocksUpgrade> {
+    let blocks = db.view_db(|tx| {
+        let mut cursor = tx.cursor_read::<MevBlocks>()?;
+        let mut res = Vec::new();
+        for entry in cursor.walk(None)? {
+            res.push(entry?.0);
+        }
+
+        Ok(res)
+    })?;
+
+    let mut res = MevBlocksUpgrade::default();
+    for batch in &blocks.iter().chunks(UPGRADE_BATCH) {
+        let (upgraded, undecodable) = db.try_update_db(|tx| {
+            let (mut upgraded, mut undecodable) = (0, vec![]);
+            for block in batch {
+                match tx.get::<MevBlocks>(*block) {
+                    Ok(Some(value)) => {
+                        let checksum = BlockChecksum::of(&value)?;
+                        tx.put::<MevBlocks>(*block, value)?;
+                        tx.put::<BlockChecksums>(*block, checksum)?;
+                        upgraded += 1;
+                    }
+                    Ok(None) => {}
+                    Err(reth_db::DatabaseError::Decode) => undecodable.push(*block),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            Ok((upgraded, undecodable))
+        })?;
+        res.upgraded += upgraded;
+        res.undecodable.extend(undecodable);
+    }
+
+    Ok(res)
+}
+
+/// The newest version of each layout among the first and last stored mev
+/// blocks, keyed like [`layout_versions`]. Only the two ends are read so the
+/// check stays cheap on a large db
+///
+/// [`layout_versions`]: brontes_types::db::bundle_versions::layout_versions
+pub fn stored_layout_versions(db: &Libmdbx) -> eyre::Result<BTreeMap<String, u16>> {
+    let rows = db.view_db(|tx| {
+        let mut cursor = tx.cursor_read::<MevBlocks>()?;
+        Ok([cursor.first_raw_value()?, cursor.last_raw_value()?])
+    })?;
+
+    let mut versions = BTreeMap::new();
+    for row in rows.into_iter().flatten() {
+        for (layout, version) in MevBlockWithClassifiedRedefined::stored_layout_versions(&row)? {
+            let entry = versions.entry(layout).or_insert(version);
+            *entry = (*entry).max(version);
+        }
+    }
+
+    Ok(versions)
+}
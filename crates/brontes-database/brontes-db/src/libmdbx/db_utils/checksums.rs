@@ -0,0 +1,202 @@
+//! Verification of the stored results of a block range against their
+//! checksums, see [`BlockChecksum`].
+use std::collections::{BTreeMap, BTreeSet};
+
+use brontes_types::{
+    db::{
+        block_checksum::BlockChecksum,
+        bundle_versions::layout_versions,
+        mev_block::{MevBlockWithClassified, MevBlockWithClassifiedRedefined},
+    },
+    FastHashMap,
+};
+
+use crate::libmdbx::{tables::*, Libmdbx};
+
+/// A checksum stored for a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoredChecksum {
+    pub checksum:        BlockChecksum,
+    /// The block is stored in an older layout than the current one, it's
+    /// upcast when read so its checksum can't be recomputed
+    pub outdated_layout: bool,
+}
+
+/// Checksums stored for the blocks of the range. The end block is inclusive
+pub fn fetch_block_checksums(
+    db: &Libmdbx,
+    start_block: u64,
+    end_block: u64,
+) -> eyre::Result<Vec<(u64, StoredChecksum)>> {
+    let current = layout_versions();
+    db.view_db(|tx| {
+        let mut cursor = tx.cursor_read::<BlockChecksums>()?;
+        let mut res = Vec::new();
+
+        for entry in cursor.walk_range(start_block..=end_block)? {
+            let (block, checksum) = entry?;
+            let outdated_layout = match tx.get_raw::<MevBlocks>(block)? {
+                Some(row) => MevBlockWithClassifiedRedefined::stored_layout_versions(&row)?
+                    .iter()
+                    .any(|(layout, version)| current.get(layout).is_some_and(|c| version < c)),
+                None => false,
+            };
+            res.push((block, StoredChecksum { checksum, outdated_layout }));
+        }
+
+        Ok(res)
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChecksumMismatch {
+    /// The block has results but no checksum was stored for them
+    Missing { block: u64 },
+    /// The stored results no longer hash to the checksum stored with them
+    Corrupt { block: u64, stored: BlockChecksum, recomputed: BlockChecksum },
+    /// The results are stored in an older layout and don't hash to their
+    /// checksum once upcast, upgrading the stored blocks rewrites it
+    Outdated { block: u64 },
+    /// The results differ from the ones of the other db
+    Diverged { block: u64, ours: Option<BlockChecksum>, theirs: Option<BlockChecksum> },
+}
+
+impl ChecksumMismatch {
+    pub fn block(&self) -> u64 {
+        match self {
+            Self::Missing { block }
+            | Self::Corrupt { block, .. }
+            | Self::Outdated { block }
+            | Self::Diverged { block, .. } => *block,
+        }
+    }
+}
+
+/// Recomputes the checksums of the blocks and compares them to the stored
+/// ones. Blocks stored before checksums were written are reported as missing
+pub fn verify_block_checksums(
+    blocks: &[MevBlockWithClassified],
+    stored: &[(u64, StoredChecksum)],
+) -> eyre::Result<Vec<ChecksumMismatch>> {
+    let stored = stored.iter().copied().collect::<FastHashMap<_, _>>();
+
+    let mut mismatches = Vec::new();
+    for block in blocks {
+        let number = block.block.block_number;
+        let recomputed = BlockChecksum::of(block)?;
+        let mismatch = match stored.get(&number) {
+            None => ChecksumMismatch::Missing { block: number },
+            Some(stored) if stored.checksum == recomputed => continue,
+            Some(stored) if stored.outdated_layout => ChecksumMismatch::Outdated { block: number },
+            Some(stored) => {
+                ChecksumMismatch::Corrupt { block: number, stored: stored.checksum, recomputed }
+            }
+        };
+        mismatches.push(mismatch);
+    }
+
+    Ok(mismatches)
+}
+
+/// Compares the checksums of the blocks of two dbs over the same range,
+/// reporting the blocks that only one of them has or that differ
+pub fn diff_block_checksums(
+    ours: &[MevBlockWithClassified],
+    theirs: &[MevBlockWithClassified],
+) -> eyre::Result<Vec<ChecksumMismatch>> {
+    let checksums = |blocks: &[MevBlockWithClassified]| {
+        blocks
+            .iter()
+            .map(|block| Ok((block.block.block_number, BlockChecksum::of(block)?)))
+            .collect::<eyre::Result<BTreeMap<_, _>>>()
+    };
+    let ours = checksums(ours)?;
+    let theirs = checksums(theirs)?;
+
+    Ok(ours
+        .keys()
+        .chain(theirs.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .filter_map(|block| {
+            let (ours, theirs) = (ours.get(block).copied(), theirs.get(block).copied());
+            (ours != theirs).then_some(ChecksumMismatch::Diverged { block: *block, ours, theirs })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::mev::{Bundle, BundleHeader, MevBlock};
+
+    use super::*;
+
+    fn block(number: u64, bundles: &[u64]) -> MevBlockWithClassified {
+        MevBlockWithClassified {
+            block: MevBlock { block_number: number, ..Default::default() },
+            mev:   bundles
+                .iter()
+                .map(|tx_index| Bundle {
+                    header: BundleHeader { tx_index: *tx_index, ..Default::default() },
+                    data:   Default::default(),
+                })
+                .collect(),
+        }
+    }
+
+    fn stored_checksum(
+        block: &MevBlockWithClassified,
+        outdated_layout: bool,
+    ) -> (u64, StoredChecksum) {
+        let checksum = BlockChecksum::of(block).unwrap();
+        (block.block.block_number, StoredChecksum { checksum, outdated_layout })
+    }
+
+    #[test]
+    fn test_verify_block_checksums() {
+        let intact = block(1, &[0, 1]);
+        let corrupt = block(2, &[0]);
+        let outdated = block(3, &[0]);
+        let missing = block(4, &[]);
+        let stored = [
+            stored_checksum(&intact, false),
+            stored_checksum(&block(2, &[1]), false),
+            stored_checksum(&block(3, &[2]), true),
+        ];
+
+        let mismatches =
+            verify_block_checksums(&[intact.clone(), corrupt.clone(), outdated, missing], &stored)
+                .unwrap();
+        assert_eq!(
+            mismatches,
+            vec![
+                ChecksumMismatch::Corrupt {
+                    block:      2,
+                    stored:     stored[1].1.checksum,
+                    recomputed: BlockChecksum::of(&corrupt).unwrap(),
+                },
+                ChecksumMismatch::Outdated { block: 3 },
+                ChecksumMismatch::Missing { block: 4 },
+            ]
+        );
+
+        // an outdated layout that still hashes to its checksum verifies
+        let stored = [stored_checksum(&intact, true)];
+        assert!(verify_block_checksums(&[intact], &stored)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_diff_block_checksums() {
+        let ours = [block(1, &[0]), block(2, &[0, 1])];
+        let theirs = [block(1, &[0]), block(2, &[0]), block(3, &[])];
+
+        let blocks = diff_block_checksums(&ours, &theirs)
+            .unwrap()
+            .into_iter()
+            .map(|mismatch| mismatch.block())
+            .collect::<Vec<_>>();
+        assert_eq!(blocks, vec![2, 3]);
+    }
+}
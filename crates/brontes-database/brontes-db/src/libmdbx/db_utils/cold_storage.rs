@@ -0,0 +1,647 @@
+//! Hot/cold tiering of the heavy per block data.
+//!
+//! Traces and cex quotes and trades make up most of the db, but once a block
+//! has been processed they are only read again to re-inspect it. Ranges older
+//! than [`ColdStorageConfig::keep_blocks`] are copied into a libmdbx segment,
+//! uploaded to an rclone remote (s3, r2, gcs..) and removed from the local db.
+//! A [`ColdSegment`] stub is left in the [`ColdSegments`] table, so reads that
+//! miss locally download the segment from the remote's http endpoint into the
+//! cache dir and read from it.
+//!
+//! Segments are downloaded and unpacked in a staging dir and only moved into
+//! the cache once they're complete, so a segment in the cache is always
+//! whole. At most [`ColdStorageConfig::max_cached_segments`] are kept, the
+//! least recently read one is removed to make room for the next.
+use std::{
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use brontes_types::{db::cold_segment::ColdSegment, BrontesTaskExecutor, FastHashMap};
+use eyre::eyre;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use parking_lot::Mutex;
+use reqwest::Url;
+use tar::{Archive, Builder};
+
+use crate::{
+    libmdbx::{tables::*, Libmdbx, LibmdbxReadWriter},
+    CompressedTable,
+};
+
+pub const COLD_SEGMENT_NAME: &str = "brontes-cold-segment";
+
+/// 1 day / 12 seconds
+pub const DEFAULT_COLD_SEGMENT_SIZE: u64 = 7_200;
+
+pub const DEFAULT_MAX_CACHED_SEGMENTS: usize = 8;
+
+const COPY_BATCH_BLOCKS: u64 = 100;
+
+/// Prefix of the dirs segments are built and unpacked in
+const STAGING_PREFIX: &str = ".staging-";
+
+#[derive(Debug, Clone)]
+pub struct ColdStorageConfig {
+    /// rclone remote and path the segments are stored under, e.g.
+    /// `r2:brontes-cold`
+    pub remote:              String,
+    /// Http endpoint the remote's segments are downloaded from
+    pub endpoint:            Url,
+    /// Where fetched segments are unpacked
+    pub cache_dir:           PathBuf,
+    /// Segments kept in the cache dir
+    pub max_cached_segments: usize,
+    /// Blocks behind the highest traced block that are kept locally
+    pub keep_blocks:         u64,
+    pub segment_size:        u64,
+    /// How often the offload task checks for ranges to move
+    pub poll_interval:       Duration,
+}
+
+/// A fetched segment, its directory is removed once the last read using it
+/// is done
+struct CachedSegment {
+    db:   Option<Libmdbx>,
+    path: PathBuf,
+}
+
+impl CachedSegment {
+    fn db(&self) -> &Libmdbx {
+        self.db.as_ref().expect("only taken on drop")
+    }
+}
+
+impl Drop for CachedSegment {
+    fn drop(&mut self) {
+        // the env has to be closed before its files are removed
+        drop(self.db.take());
+        if let Err(e) = fs_extra::dir::remove(&self.path) {
+            tracing::warn!(%e, path = %self.path.display(), "failed to remove cold segment");
+        }
+    }
+}
+
+struct OpenSegment {
+    segment:   Arc<CachedSegment>,
+    last_used: u64,
+}
+
+pub struct ColdStorage {
+    config:   ColdStorageConfig,
+    /// Segments opened by reads, keyed by their start block
+    segments: Mutex<FastHashMap<u64, OpenSegment>>,
+    /// Held while a segment is fetched, so it's only fetched once without
+    /// blocking the reads of other segments
+    fetching: Mutex<FastHashMap<u64, Arc<Mutex<()>>>>,
+    /// Orders the reads of the open segments
+    reads:    AtomicU64,
+}
+
+impl std::fmt::Debug for ColdStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ColdStorage")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl ColdStorage {
+    pub fn new(config: ColdStorageConfig) -> eyre::Result<Self> {
+        if !Command::new("rclone")
+            .arg("--version")
+            .output()?
+            .status
+            .success()
+        {
+            eyre::bail!("rclone is not installed on this computer, please fix")
+        }
+
+        Self::with_cache_dir(config)
+    }
+
+    /// Segments left in the cache dir by a previous run aren't tracked, so
+    /// they're removed along with any partial download
+    fn with_cache_dir(config: ColdStorageConfig) -> eyre::Result<Self> {
+        fs_extra::dir::create_all(&config.cache_dir, false)?;
+        for entry in std::fs::read_dir(&config.cache_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(COLD_SEGMENT_NAME) || name.starts_with(STAGING_PREFIX) {
+                fs_extra::dir::remove(entry.path())?;
+            }
+        }
+
+        Ok(Self {
+            config,
+            segments: Mutex::new(FastHashMap::default()),
+            fetching: Mutex::new(FastHashMap::default()),
+            reads: AtomicU64::new(0),
+        })
+    }
+
+    pub fn config(&self) -> &ColdStorageConfig {
+        &self.config
+    }
+
+    /// Reads an entry of a tiered table from the segment holding the block,
+    /// fetching the segment if it isn't cached. Returns `None` if the block
+    /// was never offloaded
+    pub fn get<T>(&self, db: &Libmdbx, block: u64) -> eyre::Result<Option<T::DecompressedValue>>
+    where
+        T: CompressedTable<Key = u64>,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+    {
+        let Some(segment) = self.segment_for(db, block)? else { return Ok(None) };
+        segment.db().view_db(|tx| Ok(tx.get::<T>(block)?))
+    }
+
+    fn segment_for(&self, db: &Libmdbx, block: u64) -> eyre::Result<Option<Arc<CachedSegment>>> {
+        let stub = db.view_db(|tx| {
+            // the last segment starting at or before the block
+            let mut cur = tx.cursor_read::<ColdSegments>()?;
+            let entry = match cur.seek_exact(block)? {
+                Some(entry) => Some(entry),
+                None if cur.seek(block)?.is_some() => cur.prev()?,
+                None => cur.last()?,
+            };
+
+            Ok(entry.filter(|(start, segment)| segment.contains(*start, block)))
+        })?;
+        let Some((start, segment)) = stub else { return Ok(None) };
+
+        if let Some(cached) = self.cached(start) {
+            return Ok(Some(cached))
+        }
+
+        let fetch_lock = self.fetching.lock().entry(start).or_default().clone();
+        let _fetching = fetch_lock.lock();
+        // fetched by another read while this one waited
+        if let Some(cached) = self.cached(start) {
+            return Ok(Some(cached))
+        }
+
+        let res = self.fetch(start, &segment).map(|cached| {
+            let cached = Arc::new(cached);
+            self.insert(start, cached.clone());
+            cached
+        });
+        self.fetching.lock().remove(&start);
+
+        res.map(Some)
+    }
+
+    fn cached(&self, start: u64) -> Option<Arc<CachedSegment>> {
+        let mut segments = self.segments.lock();
+        let open = segments.get_mut(&start)?;
+        open.last_used = self.reads.fetch_add(1, Ordering::Relaxed);
+        Some(open.segment.clone())
+    }
+
+    /// Caches the segment, evicting the least recently read ones over the
+    /// limit. Their dirs are removed once the reads still using them are done
+    fn insert(&self, start: u64, segment: Arc<CachedSegment>) {
+        let last_used = self.reads.fetch_add(1, Ordering::Relaxed);
+        let mut evicted = vec![];
+        {
+            let mut segments = self.segments.lock();
+            segments.insert(start, OpenSegment { segment, last_used });
+            while segments.len() > self.config.max_cached_segments.max(1) {
+                let Some(lru) = segments
+                    .iter()
+                    .min_by_key(|(_, open)| open.last_used)
+                    .map(|(start, _)| *start)
+                else {
+                    break
+                };
+                evicted.extend(segments.remove(&lru));
+            }
+        }
+        // dropped outside of the lock as it can remove the segment's dir
+        drop(evicted);
+    }
+
+    fn fetch(&self, start: u64, segment: &ColdSegment) -> eyre::Result<CachedSegment> {
+        let name = segment_name(start, segment.end_block);
+        let staging = self.staging_path(&name);
+        if staging.exists() {
+            fs_extra::dir::remove(&staging)?;
+        }
+        fs_extra::dir::create_all(&staging, false)?;
+
+        tracing::info!(start, end = segment.end_block, "fetching cold segment");
+        let tarball = staging.join(&segment.object);
+        let res = download(self.config.endpoint.join(&segment.object)?, &tarball)
+            .and_then(|_| self.install(&name, &staging, &tarball, segment.bytes));
+        if res.is_err() {
+            let _ = fs_extra::dir::remove(&staging);
+        }
+
+        res
+    }
+
+    /// Unpacks a downloaded segment and moves it into the cache. The size of
+    /// the tarball is checked against the stub first so a truncated download
+    /// is never unpacked
+    fn install(
+        &self,
+        name: &str,
+        staging: &Path,
+        tarball: &Path,
+        bytes: u64,
+    ) -> eyre::Result<CachedSegment> {
+        let downloaded = std::fs::metadata(tarball)?.len();
+        if downloaded != bytes {
+            return Err(eyre!("cold segment {name} is {downloaded} bytes, expected {bytes}"))
+        }
+
+        let mut archive = Archive::new(GzDecoder::new(File::open(tarball)?));
+        archive.unpack(staging)?;
+
+        let path = self.segment_path(name);
+        if path.exists() {
+            fs_extra::dir::remove(&path)?;
+        }
+        std::fs::rename(staging.join(name), &path)?;
+        fs_extra::dir::remove(staging)?;
+
+        Ok(CachedSegment { db: Some(Libmdbx::init_db_read_only(&path)?), path })
+    }
+
+    /// Moves the tiered tables of `start_block..end_block` to the remote and
+    /// replaces them locally with a stub
+    pub fn offload_range(
+        &self,
+        db: &Libmdbx,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<ColdSegment> {
+        let name = segment_name(start_block, end_block);
+        let staging = self.staging_path(&name);
+        if staging.exists() {
+            fs_extra::dir::remove(&staging)?;
+        }
+
+        let segment_dir = staging.join(&name);
+        {
+            let segment_db = Libmdbx::init_db(&segment_dir, None)?;
+            copy_range::<TxTraces>(db, &segment_db, start_block, end_block)?;
+            copy_range::<CexPrice>(db, &segment_db, start_block, end_block)?;
+            copy_range::<CexTrades>(db, &segment_db, start_block, end_block)?;
+        }
+
+        let object = format!("{name}.tar.gz");
+        let tarball = staging.join(&object);
+        pack_segment(&segment_dir, &name, &tarball)?;
+        let bytes = std::fs::metadata(&tarball)?.len();
+        run(Command::new("rclone")
+            .arg("copyto")
+            .arg(&tarball)
+            .arg(format!("{}/{object}", self.config.remote))
+            .arg("--s3-upload-cutoff=100M")
+            .arg("--s3-chunk-size=100M"))?;
+
+        let segment = ColdSegment { end_block, object, bytes };
+        // the stub and the deletes go in one tx so a block is always either
+        // local or has a stub pointing at it
+        db.try_update_db(|tx| {
+            tx.put::<ColdSegments>(start_block, segment.clone())?;
+            for block in start_block..end_block {
+                tx.delete::<TxTraces>(block, None)?;
+                tx.delete::<CexPrice>(block, None)?;
+                tx.delete::<CexTrades>(block, None)?;
+            }
+            Ok(())
+        })?;
+
+        fs_extra::dir::remove(&staging)?;
+
+        Ok(segment)
+    }
+
+    /// Offloads every full segment older than the configured number of kept
+    /// blocks. Returns the offloaded segments
+    pub fn offload(&self, db: &Libmdbx) -> eyre::Result<Vec<ColdSegment>> {
+        let (first, last) = db.view_db(|tx| {
+            let last_offloaded = tx
+                .cursor_read::<ColdSegments>()?
+                .last()?
+                .map(|(_, segment)| segment.end_block);
+            let first_local = [
+                tx.cursor_read::<TxTraces>()?.first()?.map(|(k, _)| k),
+                tx.cursor_read::<CexPrice>()?.first()?.map(|(k, _)| k),
+                tx.cursor_read::<CexTrades>()?.first()?.map(|(k, _)| k),
+            ]
+            .into_iter()
+            .flatten()
+            .min();
+            let last = tx.cursor_read::<TxTraces>()?.last()?.map(|(k, _)| k);
+
+            Ok((last_offloaded.max(first_local), last))
+        })?;
+        let (Some(mut start), Some(last)) = (first, last) else { return Ok(vec![]) };
+
+        let cutoff = last.saturating_sub(self.config.keep_blocks);
+        let mut offloaded = vec![];
+        while start + self.config.segment_size <= cutoff {
+            let end = start + self.config.segment_size;
+            tracing::info!(start, end, "moving range to cold storage");
+            offloaded.push(self.offload_range(db, start, end)?);
+            start = end;
+        }
+
+        Ok(offloaded)
+    }
+
+    fn segment_path(&self, name: &str) -> PathBuf {
+        self.config.cache_dir.join(name)
+    }
+
+    fn staging_path(&self, name: &str) -> PathBuf {
+        self.config
+            .cache_dir
+            .join(format!("{STAGING_PREFIX}{name}"))
+    }
+}
+
+impl LibmdbxReadWriter {
+    /// Reads that miss locally fall back to the cold segments
+    pub fn with_cold_storage(mut self, cold_storage: ColdStorage) -> Self {
+        self.cold_storage = Some(Arc::new(cold_storage));
+        self
+    }
+
+    /// Reads an entry of a tiered table that isn't stored locally
+    pub(crate) fn fetch_cold<T>(&self, block: u64) -> eyre::Result<Option<T::DecompressedValue>>
+    where
+        T: CompressedTable<Key = u64>,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+    {
+        self.cold_storage
+            .as_ref()
+            .map(|cold| cold.get::<T>(&self.db, block))
+            .transpose()
+            .map(Option::flatten)
+    }
+
+    /// The stubs of the offloaded ranges, keyed by their start block
+    pub fn fetch_cold_segments(&self) -> eyre::Result<Vec<(u64, ColdSegment)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<ColdSegments>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk(None)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    /// Spawns a task that periodically moves old ranges to cold storage
+    pub fn spawn_cold_offload(&self, executor: &BrontesTaskExecutor) -> eyre::Result<()> {
+        let cold = self
+            .cold_storage
+            .clone()
+            .ok_or_else(|| eyre!("no cold storage configured"))?;
+        let db = self.db.clone();
+
+        executor.spawn_blocking(async move {
+            loop {
+                match cold.offload(&db) {
+                    Ok(segments) if !segments.is_empty() => {
+                        tracing::info!(segments = segments.len(), "moved ranges to cold storage")
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::error!(%e, "failed to move ranges to cold storage"),
+                }
+                tokio::time::sleep(cold.config.poll_interval).await;
+            }
+        });
+
+        Ok(())
+    }
+}
+
+fn copy_range<T>(from: &Libmdbx, to: &Libmdbx, start_block: u64, end_block: u64) -> eyre::Result<()>
+where
+    T: CompressedTable<Key = u64>,
+    T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+{
+    // traces of a full segment don't fit in memory, so copy in batches
+    for batch_start in (start_block..end_block).step_by(COPY_BATCH_BLOCKS as usize) {
+        let batch_end = (batch_start + COPY_BATCH_BLOCKS).min(end_block);
+        let entries = from.view_db(|tx| {
+            let mut cur = tx.cursor_read::<T>()?;
+            let mut res = Vec::new();
+            for entry in cur.walk_range(batch_start..batch_end)? {
+                res.push(entry?);
+            }
+            Ok(res)
+        })?;
+
+        to.update_db(|tx| {
+            for (key, value) in entries {
+                tx.put::<T>(key, value)?;
+            }
+            Ok::<_, reth_db::DatabaseError>(())
+        })??;
+    }
+
+    Ok(())
+}
+
+fn segment_name(start_block: u64, end_block: u64) -> String {
+    format!("{COLD_SEGMENT_NAME}-{start_block}-{end_block}")
+}
+
+/// Packs the segment's dir into a tarball with its entries under `name`
+fn pack_segment(segment_dir: &Path, name: &str, tarball: &Path) -> eyre::Result<()> {
+    let mut builder = Builder::new(GzEncoder::new(File::create(tarball)?, Compression::default()));
+    builder.append_dir_all(name, segment_dir)?;
+    builder.into_inner()?.finish()?.sync_all()?;
+
+    Ok(())
+}
+
+/// Downloads the object to `dest`, returning its size. Runs on its own thread
+/// as the blocking client can't be used from the runtime the reads are made on
+fn download(url: Url, dest: &Path) -> eyre::Result<u64> {
+    let dest = dest.to_path_buf();
+    std::thread::spawn(move || -> eyre::Result<u64> {
+        // segments take longer than the client's default timeout
+        let client = reqwest::blocking::Client::builder().timeout(None).build()?;
+        let mut response = client.get(url).send()?.error_for_status()?;
+        let mut file = File::create(&dest)?;
+        let bytes = io::copy(&mut response, &mut file)?;
+        file.sync_all()?;
+
+        Ok(bytes)
+    })
+    .join()
+    .map_err(|_| eyre!("cold segment download panicked"))?
+}
+
+pub(crate) fn run(command: &mut Command) -> eyre::Result<()> {
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "{:?} failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::db::traces::TxTracesInner;
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("brontes-{name}-{}", std::process::id()));
+        let _ = fs_extra::dir::remove(&path);
+        path
+    }
+
+    fn storage(cache_dir: PathBuf, max_cached_segments: usize) -> ColdStorage {
+        ColdStorage::with_cache_dir(ColdStorageConfig {
+            remote: "test:cold".to_string(),
+            endpoint: Url::parse("http://localhost/").unwrap(),
+            cache_dir,
+            max_cached_segments,
+            keep_blocks: 0,
+            segment_size: 10,
+            poll_interval: Duration::from_secs(1),
+        })
+        .unwrap()
+    }
+
+    /// Builds the tarball of a segment holding empty traces for its blocks
+    fn segment_tarball(dir: &Path, start: u64, end: u64) -> (String, PathBuf) {
+        let name = segment_name(start, end);
+        let segment_dir = dir.join(&name);
+        {
+            let db = Libmdbx::init_db(&segment_dir, None).unwrap();
+            db.try_update_db(|tx| {
+                for block in start..end {
+                    tx.put::<TxTraces>(block, TxTracesInner::new(Some(vec![])))?;
+                }
+                Ok(())
+            })
+            .unwrap();
+        }
+        let tarball = dir.join(format!("{name}.tar.gz"));
+        pack_segment(&segment_dir, &name, &tarball).unwrap();
+
+        (name, tarball)
+    }
+
+    #[test]
+    fn test_installs_complete_segment() {
+        let dir = temp_dir("cold-install");
+        let cold = storage(dir.join("cache"), 2);
+        let (name, tarball) = segment_tarball(&dir, 0, 10);
+        let bytes = std::fs::metadata(&tarball).unwrap().len();
+
+        let staging = cold.staging_path(&name);
+        fs_extra::dir::create_all(&staging, false).unwrap();
+        let staged = staging.join(format!("{name}.tar.gz"));
+        std::fs::copy(&tarball, &staged).unwrap();
+
+        let segment = cold.install(&name, &staging, &staged, bytes).unwrap();
+        assert!(!staging.exists());
+        assert_eq!(
+            segment
+                .db()
+                .view_db(|tx| Ok(tx.get::<TxTraces>(3)?))
+                .unwrap(),
+            Some(TxTracesInner::new(Some(vec![])))
+        );
+
+        let path = segment.path.clone();
+        drop(segment);
+        assert!(!path.exists());
+
+        fs_extra::dir::remove(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_truncated_segment_isnt_installed() {
+        let dir = temp_dir("cold-truncated");
+        let cold = storage(dir.join("cache"), 2);
+        let (name, tarball) = segment_tarball(&dir, 0, 10);
+        let tarball = std::fs::read(tarball).unwrap();
+
+        let staging = cold.staging_path(&name);
+        fs_extra::dir::create_all(&staging, false).unwrap();
+        let staged = staging.join(format!("{name}.tar.gz"));
+        let truncated = &tarball[..tarball.len() / 2];
+        std::fs::write(&staged, truncated).unwrap();
+
+        // a short download is caught by the size of the stub
+        assert!(cold
+            .install(&name, &staging, &staged, tarball.len() as u64)
+            .is_err());
+        // and one that matches the stub still has to unpack
+        assert!(cold
+            .install(&name, &staging, &staged, truncated.len() as u64)
+            .is_err());
+        assert!(!cold.segment_path(&name).exists());
+
+        fs_extra::dir::remove(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_evicts_least_recently_read_segment() {
+        let dir = temp_dir("cold-evict");
+        let cache = dir.join("cache");
+        let cold = storage(cache.clone(), 2);
+
+        let mut paths = vec![];
+        for start in [0, 10, 20] {
+            let (name, tarball) = segment_tarball(&dir, start, start + 10);
+            let bytes = std::fs::metadata(&tarball).unwrap().len();
+            let staging = cold.staging_path(&name);
+            fs_extra::dir::create_all(&staging, false).unwrap();
+            let staged = staging.join(format!("{name}.tar.gz"));
+            std::fs::copy(&tarball, &staged).unwrap();
+
+            let segment = cold.install(&name, &staging, &staged, bytes).unwrap();
+            paths.push(segment.path.clone());
+            cold.insert(start, Arc::new(segment));
+            // the first segment stays the most recently read
+            cold.cached(0).unwrap();
+        }
+
+        assert!(cold.cached(0).is_some());
+        assert!(cold.cached(10).is_none());
+        assert!(cold.cached(20).is_some());
+        assert!(paths[0].exists());
+        assert!(!paths[1].exists());
+        assert!(paths[2].exists());
+
+        // a new run doesn't pick up the segments and partial downloads left by
+        // the last one
+        let leftover = cache.join(segment_name(90, 100));
+        let partial = cold.staging_path(&segment_name(100, 110));
+        fs_extra::dir::create_all(&leftover, false).unwrap();
+        fs_extra::dir::create_all(&partial, false).unwrap();
+        storage(cache, 2);
+        assert!(!leftover.exists());
+        assert!(!partial.exists());
+
+        fs_extra::dir::remove(&dir).unwrap();
+    }
+}
@@ -33,7 +33,12 @@ pub fn merge_libmdbx_dbs(
             Builder,
             AddressToProtocolInfo,
             TokenDecimals,
-            DexPrice
+            DexPrice,
+            BeaconBlocks,
+            FeeHistory,
+            BlockOrderFlows,
+            BuilderPayments,
+            SearcherOrderFlows
             );
 
             eyre::Ok(())
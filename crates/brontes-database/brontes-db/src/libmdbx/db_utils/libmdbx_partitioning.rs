@@ -107,7 +107,10 @@ impl LibmdbxPartitioner {
                     MevBlocks,
                     InitializedState,
                     PoolCreationBlocks,
-                    TxTraces
+                    TxTraces,
+                    BeaconBlocks,
+                    FeeHistory,
+                    BlockOrderFlows
                 );
                 // manually dex pricing
                 self.parent_db
@@ -0,0 +1,31 @@
+use brontes_libmdbx::RW;
+use brontes_types::db::{mev_block::MevBlockWithClassified, mev_receipt::MevReceipt};
+
+use crate::libmdbx::{
+    tables::{BundleOverrides, BundleRepricings, MevReceipts},
+    tx::CompressedLibmdbxTx,
+};
+
+/// Rewrites the receipts of the block to follow its curated view, see
+/// [`MevReceipt::for_block`]. Called in the transaction that changes the
+/// block, its re-pricing or its overrides, so the receipts never lag behind
+pub fn refresh_mev_receipts(
+    tx: &CompressedLibmdbxTx<RW>,
+    raw: &MevBlockWithClassified,
+) -> eyre::Result<()> {
+    let mut overrides = vec![];
+    for entry in tx.cursor_read::<BundleOverrides>()?.walk(None)? {
+        overrides.push(entry?);
+    }
+    let repricings = tx.get::<BundleRepricings>(raw.block.block_number)?;
+
+    let (receipts, stale) = MevReceipt::for_block(raw, repricings.as_ref(), overrides);
+    for tx_hash in stale {
+        tx.delete::<MevReceipts>(tx_hash, None)?;
+    }
+    for receipt in receipts {
+        tx.put::<MevReceipts>(receipt.tx_hash, receipt)?;
+    }
+
+    Ok(())
+}
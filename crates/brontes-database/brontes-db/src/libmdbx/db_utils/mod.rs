@@ -5,3 +5,33 @@ pub mod libmdbx_partitioning;
 pub use libmdbx_partitioning::*;
 
 pub mod rclone_wrapper;
+
+pub mod cold_storage;
+pub use cold_storage::*;
+
+pub mod remote_snapshot;
+pub use remote_snapshot::*;
+
+pub mod scheduler;
+pub use scheduler::*;
+
+pub mod checksums;
+pub use checksums::*;
+
+pub mod bundle_versions;
+pub use bundle_versions::*;
+
+pub mod repricing;
+pub use repricing::*;
+
+pub mod mev_receipts;
+pub use mev_receipts::*;
+
+pub mod searcher_stats;
+pub use searcher_stats::*;
+
+pub mod run_provenance;
+pub use run_provenance::*;
+
+pub mod publish_checkpoint;
+pub use publish_checkpoint::*;
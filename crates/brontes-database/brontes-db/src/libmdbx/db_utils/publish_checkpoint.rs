@@ -0,0 +1,40 @@
+use brontes_types::db::{
+    mev_block::MevBlockWithClassified,
+    publish_checkpoint::{checkpoint_key, PublishCheckpoint},
+};
+
+use crate::libmdbx::{
+    tables::{MevBlocks, PublishCheckpoints},
+    Libmdbx,
+};
+
+pub fn fetch_publish_checkpoint(
+    db: &Libmdbx,
+    sink: &str,
+) -> eyre::Result<Option<PublishCheckpoint>> {
+    db.view_db(|tx| Ok(tx.get::<PublishCheckpoints>(checkpoint_key(sink))?))
+}
+
+/// Committed mev blocks after `after` up to and including `up_to`, at most
+/// `limit` of them, in block order
+pub fn fetch_mev_blocks_after(
+    db: &Libmdbx,
+    after: Option<u64>,
+    up_to: u64,
+    limit: usize,
+) -> eyre::Result<Vec<(u64, MevBlockWithClassified)>> {
+    let start = after.map_or(0, |block| block + 1);
+    if start > up_to {
+        return Ok(vec![])
+    }
+
+    db.view_db(|tx| {
+        let mut cursor = tx.cursor_read::<MevBlocks>()?;
+        let mut res = Vec::new();
+        for entry in cursor.walk_range(start..=up_to)?.take(limit) {
+            res.push(entry?);
+        }
+
+        Ok(res)
+    })
+}
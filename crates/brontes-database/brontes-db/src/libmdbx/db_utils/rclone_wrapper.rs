@@ -1,4 +1,10 @@
-use std::{fs::File, io::Write, path::PathBuf, process::Stdio, str::FromStr};
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    process::Stdio,
+    str::FromStr,
+};
 
 use eyre::eyre;
 use fs_extra::dir::{get_dir_content, CopyOptions};
@@ -203,6 +209,24 @@ impl RCloneWrapper {
         Ok(())
     }
 
+    /// Copies the contents of a local directory to `path` on the configured
+    /// remote. Works for any rclone backend, e.g. s3 or gcs.
+    pub async fn copy_dir(&self, directory: &Path, path: &str) -> eyre::Result<()> {
+        if !Command::new("rclone")
+            .arg("copy")
+            .arg(directory)
+            .arg(format!("{}:{path}", self.config_name))
+            .spawn()?
+            .wait()
+            .await?
+            .success()
+        {
+            eyre::bail!("failed to copy {} to {}:{path}", directory.display(), self.config_name)
+        }
+
+        Ok(())
+    }
+
     pub async fn tar_ball_and_upload_files(
         &self,
         partition_folder: PathBuf,
@@ -0,0 +1,359 @@
+//! Bootstrapping a db from a snapshot published to an object store.
+//!
+//! Backfilling a new db from genesis of the indexed range takes weeks, so an
+//! operator can instead start from a snapshot of another one.
+//! [`RemoteSnapshot::publish`] copies the db from a read transaction, so it can
+//! be published while brontes keeps writing to it, and uploads the copy as a
+//! single tarball to an rclone remote (s3, r2, gcs..) next to a
+//! [`SnapshotManifest`] that records the versions of the layouts it was
+//! written with, its tables, the last block up to which every block was
+//! processed and the checksum of the tarball. [`RemoteSnapshot::bootstrap`]
+//! only downloads a snapshot this binary can read, checks the tarball against
+//! the manifest before unpacking it and keeps the manifest in the db
+//! directory, so `brontes run --from-snapshot-tip` can pick up indexing from
+//! its tip.
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy_primitives::{Keccak256, B256};
+use brontes_types::db::bundle_versions::layout_versions;
+use eyre::eyre;
+use reth_db::version::db_version_file_path;
+use serde::{Deserialize, Serialize};
+
+use super::cold_storage::run;
+use crate::libmdbx::{tables::*, Libmdbx};
+
+pub const SNAPSHOT_MANIFEST: &str = "snapshot-manifest.json";
+
+const SNAPSHOT_NAME: &str = "brontes-db-snapshot";
+/// Name of mdbx's data file in the db directory
+const MDBX_DATA_FILE: &str = "mdbx.dat";
+const HASH_BUF_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Versions of the stored layouts the snapshot was written with, see
+    /// [`layout_versions`]
+    pub layout_versions: BTreeMap<String, u16>,
+    /// Names of the tables in the snapshot
+    pub tables:          Vec<String>,
+    /// Last block up to which every block has stored results
+    pub tip_block:       u64,
+    /// Unix timestamp in seconds of when the snapshot was published
+    pub created_at:      u64,
+    /// Name of the tarball, relative to the manifest
+    pub object:          String,
+    pub bytes:           u64,
+    /// Keccak256 of the tarball
+    pub checksum:        B256,
+}
+
+impl SnapshotManifest {
+    /// Reasons the snapshot can't be read by this binary
+    pub fn incompatibilities(&self) -> Vec<String> {
+        let mut reasons = vec![];
+        // rows of an older layout are upcast when read, but a newer layout or
+        // one this binary doesn't know was written by a newer binary
+        let local = layout_versions();
+        for (layout, version) in &self.layout_versions {
+            match local.get(layout) {
+                Some(local) if version > local => reasons.push(format!(
+                    "{layout} layout version {version} but this binary reads up to {local}"
+                )),
+                Some(_) => {}
+                None => reasons.push(format!("unknown layout {layout}")),
+            }
+        }
+        // tables missing from the snapshot are created empty on init, but tables
+        // this binary doesn't know were written by a newer one
+        for table in &self.tables {
+            if table.parse::<Tables>().is_err() {
+                reasons.push(format!("unknown table {table}"));
+            }
+        }
+
+        reasons
+    }
+
+    /// The manifest of the snapshot the db was bootstrapped from, if any
+    pub fn read_local(db_path: impl AsRef<Path>) -> eyre::Result<Option<Self>> {
+        let path = db_path.as_ref().join(SNAPSHOT_MANIFEST);
+        if !path.exists() {
+            return Ok(None)
+        }
+
+        Ok(Some(serde_json::from_slice(&std::fs::read(path)?)?))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteSnapshot {
+    /// rclone remote and path the snapshot is published under, e.g.
+    /// `s3:brontes-snapshots/mainnet`
+    remote:       String,
+    /// Where the tarball is written before it's uploaded or unpacked
+    download_dir: PathBuf,
+}
+
+impl RemoteSnapshot {
+    pub fn new(remote: String, download_dir: PathBuf) -> eyre::Result<Self> {
+        if !Command::new("rclone")
+            .arg("--version")
+            .output()?
+            .status
+            .success()
+        {
+            eyre::bail!("rclone is not installed on this computer, please fix")
+        }
+        fs_extra::dir::create_all(&download_dir, false)?;
+
+        Ok(Self { remote, download_dir })
+    }
+
+    /// Fetches the manifest of the published snapshot
+    pub fn fetch_manifest(&self) -> eyre::Result<SnapshotManifest> {
+        let path = self.download_dir.join(SNAPSHOT_MANIFEST);
+        run(Command::new("rclone")
+            .arg("copyto")
+            .arg(format!("{}/{SNAPSHOT_MANIFEST}", self.remote))
+            .arg(&path))?;
+        let manifest = serde_json::from_slice(&std::fs::read(&path)?)?;
+        std::fs::remove_file(&path)?;
+
+        Ok(manifest)
+    }
+
+    /// Uploads a copy of the db at `db_path` and replaces the published
+    /// manifest with its own. The manifest goes last, so a failed upload
+    /// leaves the previous snapshot in place
+    pub fn publish(&self, db: &Libmdbx, db_path: &Path) -> eyre::Result<SnapshotManifest> {
+        let tip_block = processed_tip(db)?
+            .ok_or_else(|| eyre!("the db has no processed blocks to snapshot"))?;
+
+        let object = format!("{SNAPSHOT_NAME}-{tip_block}.tar.gz");
+        let staging = self
+            .download_dir
+            .join(format!("{SNAPSHOT_NAME}-{tip_block}"));
+        if staging.exists() {
+            std::fs::remove_dir_all(&staging)?;
+        }
+        fs_extra::dir::create_all(&staging, false)?;
+
+        // tarring the live data file could catch a write half way, mdbx copies
+        // it from a read txn instead and drops the free pages on the way
+        tracing::info!(tip_block, "copying the db");
+        db.0.copy(&staging.join(MDBX_DATA_FILE), true)?;
+        let version_file = db_version_file_path(db_path);
+        if version_file.exists() {
+            std::fs::copy(&version_file, db_version_file_path(&staging))?;
+        }
+
+        let tarball = self.download_dir.join(&object);
+        let res = run(Command::new("tar")
+            .arg("-czf")
+            .arg(&tarball)
+            .arg("-C")
+            .arg(&staging)
+            .arg("."));
+        std::fs::remove_dir_all(&staging)?;
+        res?;
+
+        let manifest = SnapshotManifest {
+            layout_versions: layout_versions(),
+            tables: Tables::ALL
+                .iter()
+                .map(|table| table.name().to_string())
+                .collect(),
+            tip_block,
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            object: object.clone(),
+            bytes: filesize::file_real_size(&tarball)?,
+            checksum: file_checksum(&tarball)?,
+        };
+
+        tracing::info!(tip_block, bytes = manifest.bytes, "uploading db snapshot");
+        run(Command::new("rclone")
+            .arg("copyto")
+            .arg(&tarball)
+            .arg(format!("{}/{object}", self.remote))
+            .arg("--s3-upload-cutoff=100M")
+            .arg("--s3-chunk-size=100M"))?;
+        std::fs::remove_file(&tarball)?;
+
+        let manifest_path = self.download_dir.join(SNAPSHOT_MANIFEST);
+        std::fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+        run(Command::new("rclone")
+            .arg("copyto")
+            .arg(&manifest_path)
+            .arg(format!("{}/{SNAPSHOT_MANIFEST}", self.remote)))?;
+        std::fs::remove_file(&manifest_path)?;
+
+        Ok(manifest)
+    }
+
+    /// Downloads the published snapshot into the empty directory at `db_path`
+    /// and verifies it against its manifest before unpacking
+    pub fn bootstrap(&self, db_path: &Path) -> eyre::Result<SnapshotManifest> {
+        let manifest = self.fetch_manifest()?;
+        let incompatibilities = manifest.incompatibilities();
+        if !incompatibilities.is_empty() {
+            eyre::bail!("snapshot can't be used: {}", incompatibilities.join(", "))
+        }
+
+        fs_extra::dir::create_all(db_path, false)?;
+        // the tarball and the unpacked db are on disk at the same time
+        let available = fs2::free_space(db_path)?;
+        if available < manifest.bytes * 2 {
+            eyre::bail!(
+                "not enough storage available. \nneeded: {}mb\navailable: {}mb",
+                manifest.bytes * 2 / 1_000_000,
+                available / 1_000_000
+            )
+        }
+
+        tracing::info!(
+            tip_block = manifest.tip_block,
+            bytes = manifest.bytes,
+            "downloading db snapshot"
+        );
+        let tarball = self.download_dir.join(&manifest.object);
+        run(Command::new("rclone")
+            .arg("copyto")
+            .arg(format!("{}/{}", self.remote, manifest.object))
+            .arg(&tarball))?;
+
+        let checksum = file_checksum(&tarball)?;
+        if checksum != manifest.checksum {
+            std::fs::remove_file(&tarball)?;
+            eyre::bail!(
+                "checksum of the downloaded snapshot {checksum:?} doesn't match the manifest's \
+                 {:?}",
+                manifest.checksum
+            )
+        }
+
+        run(Command::new("tar")
+            .arg("-xzf")
+            .arg(&tarball)
+            .arg("-C")
+            .arg(db_path))?;
+        std::fs::remove_file(&tarball)?;
+        std::fs::write(db_path.join(SNAPSHOT_MANIFEST), serde_json::to_vec_pretty(&manifest)?)?;
+
+        Ok(manifest)
+    }
+}
+
+/// The last block up to which every block since the first processed one has
+/// stored results. Resuming from the last stored block would skip the blocks
+/// missing before it
+fn processed_tip(db: &Libmdbx) -> eyre::Result<Option<u64>> {
+    db.view_db(|tx| {
+        let mut cursor = tx.cursor_read::<MevBlocks>()?;
+        let (Some((first, _)), Some((last, _))) = (cursor.first()?, cursor.last()?) else {
+            return Ok(None)
+        };
+        // without gaps there is an entry for every block of the range
+        if tx.entries::<MevBlocks>()? as u64 == last - first + 1 {
+            return Ok(Some(last))
+        }
+
+        let mut keys = vec![];
+        for entry in cursor.walk(None)? {
+            keys.push(entry?.0);
+        }
+
+        Ok(contiguous_tip(keys))
+    })
+}
+
+/// The last key of the run of consecutive keys the sorted `keys` start with
+fn contiguous_tip(keys: impl IntoIterator<Item = u64>) -> Option<u64> {
+    let mut keys = keys.into_iter();
+    let mut tip = keys.next()?;
+    for key in keys {
+        if key != tip + 1 {
+            break
+        }
+        tip = key;
+    }
+
+    Some(tip)
+}
+
+fn file_checksum(path: &Path) -> eyre::Result<B256> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Keccak256::new();
+    let mut buf = vec![0u8; HASH_BUF_SIZE];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break
+        }
+        hasher.update(&buf[..read]);
+    }
+
+    Ok(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> SnapshotManifest {
+        SnapshotManifest {
+            layout_versions: layout_versions(),
+            tables:          Tables::ALL
+                .iter()
+                .map(|table| table.name().to_string())
+                .collect(),
+            tip_block:       19_000_000,
+            created_at:      1_700_000_000,
+            object:          format!("{SNAPSHOT_NAME}-19000000.tar.gz"),
+            bytes:           1_000,
+            checksum:        B256::repeat_byte(1),
+        }
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let manifest = manifest();
+        let json = serde_json::to_vec_pretty(&manifest).unwrap();
+        assert_eq!(serde_json::from_slice::<SnapshotManifest>(&json).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_incompatibilities() {
+        assert!(manifest().incompatibilities().is_empty());
+
+        // older layouts are upcast
+        let mut older = manifest();
+        older.layout_versions.insert("mev_block".to_string(), 0);
+        assert!(older.incompatibilities().is_empty());
+
+        let mut newer = manifest();
+        *newer.layout_versions.get_mut("bundle_header").unwrap() += 1;
+        newer
+            .layout_versions
+            .insert("bundle_data.Arbitrage".to_string(), 1);
+        newer.tables.push("NewTable".to_string());
+        assert_eq!(newer.incompatibilities().len(), 3);
+    }
+
+    #[test]
+    fn test_contiguous_tip() {
+        assert_eq!(contiguous_tip([]), None);
+        assert_eq!(contiguous_tip([5]), Some(5));
+        assert_eq!(contiguous_tip([5, 6, 7]), Some(7));
+        assert_eq!(contiguous_tip([5, 6, 8, 9]), Some(6));
+    }
+}
@@ -0,0 +1,30 @@
+use brontes_types::db::repricing::BundleRepricing;
+
+use crate::libmdbx::{
+    refresh_mev_receipts, refresh_searcher_stats,
+    tables::{BundleRepricings, MevBlocks},
+    Libmdbx,
+};
+
+/// Appends the re-pricing runs to the ones already stored for the block
+pub fn append_bundle_repricings(
+    db: &Libmdbx,
+    block: u64,
+    runs: Vec<BundleRepricing>,
+) -> eyre::Result<()> {
+    if runs.is_empty() {
+        return Ok(())
+    }
+
+    db.try_update_db(|tx| {
+        let mut repricings = tx.get::<BundleRepricings>(block)?.unwrap_or_default();
+        repricings.repricings.extend(runs);
+        tx.put::<BundleRepricings>(block, repricings)?;
+        if let Some(raw) = tx.get::<MevBlocks>(block)? {
+            refresh_mev_receipts(tx, &raw)?;
+            refresh_searcher_stats(tx, &raw)?;
+        }
+
+        Ok(())
+    })
+}
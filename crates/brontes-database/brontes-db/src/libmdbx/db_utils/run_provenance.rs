@@ -0,0 +1,18 @@
+use brontes_types::db::run_provenance::RunProvenance;
+
+use crate::libmdbx::{tables::RunProvenances, Libmdbx};
+
+/// Stores the run's configuration under the next run id and returns it. The id
+/// is taken in the transaction that stores the run, so runs started at the same
+/// time get distinct ids
+pub fn record_run_provenance(db: &Libmdbx, provenance: RunProvenance) -> eyre::Result<u64> {
+    db.try_update_db(|tx| {
+        let id = tx
+            .cursor_read::<RunProvenances>()?
+            .last()?
+            .map_or(0, |(id, _)| id + 1);
+        tx.put::<RunProvenances>(id, provenance)?;
+
+        Ok(id)
+    })
+}
@@ -0,0 +1,369 @@
+//! Embedded scheduler for periodic maintenance jobs.
+//!
+//! Rollups, retention and statistic rebuilds used to be run by an external
+//! cron against a stopped indexer. Jobs registered with the [`Scheduler`] run
+//! inside the long-running indexer instead: each job runs every
+//! [`ScheduledJob::interval`], delayed by a random jitter so jobs registered
+//! together don't all hit the db at once. A failed run is retried with
+//! backoff, and the outcome of every run is stored in the
+//! [`ScheduledJobRuns`] table so a restart continues the schedule.
+//!
+//! Jobs run on the blocking pool. On shutdown the scheduler stops between
+//! runs, a job that is running is let finish before the indexer exits.
+use std::{
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use brontes_types::{
+    db::scheduled_job::{job_key, JobRun},
+    BrontesTaskExecutor, FastHashMap,
+};
+use futures::pin_mut;
+use itertools::Itertools;
+use rand::Rng;
+use reth_tasks::shutdown::GracefulShutdown;
+
+use crate::libmdbx::{rebuild_searcher_stats, roll_up_mev, tables::*, Libmdbx};
+
+pub trait ScheduledJob: Send + Sync {
+    /// Unique name of the job, its runs are stored under it
+    fn name(&self) -> &'static str;
+
+    fn interval(&self) -> Duration;
+
+    /// Runs the job to completion, this is called on the blocking pool
+    fn run(&self, db: &Libmdbx) -> eyre::Result<()>;
+}
+
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// Largest random delay added to each run
+    pub jitter:        Duration,
+    /// Retries of a failed run before it is recorded as failed
+    pub max_retries:   u32,
+    /// Delay before the first retry, doubled on each following one
+    pub retry_backoff: Duration,
+    /// How often the scheduler checks for due jobs
+    pub tick:          Duration,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            jitter:        Duration::from_secs(300),
+            max_retries:   3,
+            retry_backoff: Duration::from_secs(30),
+            tick:          Duration::from_secs(60),
+        }
+    }
+}
+
+pub struct Scheduler {
+    config: SchedulerConfig,
+    jobs:   Vec<Arc<dyn ScheduledJob>>,
+}
+
+impl Scheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self { config, jobs: vec![] }
+    }
+
+    pub fn with_job(mut self, job: Arc<dyn ScheduledJob>) -> Self {
+        self.jobs.push(job);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Runs the jobs as they become due until the indexer shuts down
+    pub fn spawn(self, db: Arc<Libmdbx>, executor: &BrontesTaskExecutor) {
+        if self.is_empty() {
+            return
+        }
+        tracing::info!(jobs = ?self.jobs.iter().map(|j| j.name()).collect_vec(), "starting scheduler");
+
+        executor.spawn_with_graceful_shutdown_signal(|shutdown| async move {
+            pin_mut!(shutdown);
+            self.run(db, shutdown).await;
+            tracing::info!("scheduler stopped");
+        });
+    }
+
+    async fn run(&self, db: Arc<Libmdbx>, mut shutdown: Pin<&mut GracefulShutdown>) {
+        let mut next_runs = FastHashMap::default();
+        for job in &self.jobs {
+            let last_started = match fetch_job_run(&db, job.name()) {
+                Ok(run) => run.map(|run| run.last_started),
+                Err(e) => {
+                    tracing::error!(job = job.name(), %e, "failed to read last job run");
+                    None
+                }
+            };
+            let next = last_started.map_or(now(), |last| last + job.interval().as_secs());
+            next_runs.insert(job.name(), next + self.jitter());
+        }
+
+        loop {
+            for job in &self.jobs {
+                let started = now();
+                if next_runs[job.name()] > started {
+                    continue
+                }
+
+                let Some(result) = self.run_with_retries(job, &db, shutdown.as_mut()).await else {
+                    return
+                };
+                if let Err(e) = record_job_run(&db, job.name(), started, &result) {
+                    tracing::error!(job = job.name(), %e, "failed to record job run");
+                }
+                next_runs.insert(job.name(), started + job.interval().as_secs() + self.jitter());
+            }
+            if !sleep_unless_shutdown(self.config.tick, shutdown.as_mut()).await {
+                return
+            }
+        }
+    }
+
+    /// The outcome of the run, or `None` if the indexer shut down while
+    /// waiting to retry
+    async fn run_with_retries(
+        &self,
+        job: &Arc<dyn ScheduledJob>,
+        db: &Arc<Libmdbx>,
+        mut shutdown: Pin<&mut GracefulShutdown>,
+    ) -> Option<eyre::Result<()>> {
+        let mut backoff = self.config.retry_backoff;
+        let mut attempt = 0;
+        loop {
+            let (run_job, run_db) = (job.clone(), db.clone());
+            let result = tokio::task::spawn_blocking(move || run_job.run(&run_db))
+                .await
+                .unwrap_or_else(|e| Err(eyre::eyre!("scheduled job panicked: {e}")));
+
+            match result {
+                Ok(()) => {
+                    tracing::info!(job = job.name(), "scheduled job finished");
+                    return Some(Ok(()))
+                }
+                Err(e) if attempt < self.config.max_retries => {
+                    attempt += 1;
+                    tracing::warn!(job = job.name(), attempt, %e, "scheduled job failed, retrying");
+                    if !sleep_unless_shutdown(backoff, shutdown.as_mut()).await {
+                        return None
+                    }
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    tracing::error!(job = job.name(), %e, "scheduled job failed");
+                    return Some(Err(e))
+                }
+            }
+        }
+    }
+
+    fn jitter(&self) -> u64 {
+        rand::thread_rng().gen_range(0..=self.config.jitter.as_secs())
+    }
+}
+
+/// Sleeps for the duration, false if the indexer started shutting down
+/// instead
+async fn sleep_unless_shutdown(duration: Duration, shutdown: Pin<&mut GracefulShutdown>) -> bool {
+    tokio::select! {
+        guard = shutdown => {
+            drop(guard);
+            false
+        }
+        _ = tokio::time::sleep(duration) => true,
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+pub fn fetch_job_run(db: &Libmdbx, name: &str) -> eyre::Result<Option<JobRun>> {
+    db.view_db(|tx| Ok(tx.get::<ScheduledJobRuns>(job_key(name))?))
+}
+
+/// Last runs of every job that has run, ordered by name
+pub fn fetch_job_runs(db: &Libmdbx) -> eyre::Result<Vec<JobRun>> {
+    db.view_db(|tx| {
+        let mut cursor = tx.cursor_read::<ScheduledJobRuns>()?;
+        let mut res = Vec::new();
+
+        for entry in cursor.walk(None)? {
+            res.push(entry?.1);
+        }
+
+        Ok(res)
+    })
+    .map(|runs| {
+        runs.into_iter()
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+            .collect()
+    })
+}
+
+fn record_job_run(
+    db: &Libmdbx,
+    name: &str,
+    started: u64,
+    result: &eyre::Result<()>,
+) -> eyre::Result<()> {
+    let mut run = fetch_job_run(db, name)?.unwrap_or_else(|| JobRun::new(name));
+    run.record(started, result);
+    db.update_db(|tx| tx.put::<ScheduledJobRuns>(job_key(name), run))??;
+
+    Ok(())
+}
+
+/// Deletes the inspector artifacts of blocks more than `keep_blocks` behind the
+/// latest block that has artifacts
+pub struct ArtifactRetention {
+    pub keep_blocks: u64,
+    pub interval:    Duration,
+}
+
+impl ScheduledJob for ArtifactRetention {
+    fn name(&self) -> &'static str {
+        "artifact-retention"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn run(&self, db: &Libmdbx) -> eyre::Result<()> {
+        let expired = db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<InspectorArtifacts>()?;
+            let Some((last, _)) = cursor.last()? else { return Ok(vec![]) };
+            let cutoff = last.saturating_sub(self.keep_blocks);
+
+            let mut res = Vec::new();
+            for entry in cursor.walk_range(..cutoff)? {
+                res.push(entry?.0);
+            }
+
+            Ok(res)
+        })?;
+        if expired.is_empty() {
+            return Ok(())
+        }
+
+        db.update_db(|tx| {
+            for block in &expired {
+                tx.delete::<InspectorArtifacts>(*block, None)?;
+            }
+            Ok::<_, reth_db::DatabaseError>(())
+        })??;
+        tracing::info!(blocks = expired.len(), "pruned inspector artifacts");
+
+        Ok(())
+    }
+}
+
+/// Deletes the wallet funding edges more than `keep_blocks` behind the newest
+/// one. Every funded address gets an edge, so without this the table grows
+/// with every block, while only the recent fundings of new searcher eoas are
+/// ever traced
+pub struct FundingRetention {
+    pub keep_blocks: u64,
+    pub interval:    Duration,
+}
+
+impl ScheduledJob for FundingRetention {
+    fn name(&self) -> &'static str {
+        "funding-retention"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn run(&self, db: &Libmdbx) -> eyre::Result<()> {
+        let expired = db.view_db(|tx| {
+            let mut newest = 0;
+            for entry in tx.cursor_read::<WalletFundings>()?.walk(None)? {
+                newest = newest.max(entry?.1.block_number);
+            }
+            let cutoff = newest.saturating_sub(self.keep_blocks);
+
+            let mut res = Vec::new();
+            for entry in tx.cursor_read::<WalletFundings>()?.walk(None)? {
+                let (address, edge) = entry?;
+                if edge.block_number < cutoff {
+                    res.push(address);
+                }
+            }
+
+            Ok(res)
+        })?;
+        if expired.is_empty() {
+            return Ok(())
+        }
+
+        db.update_db(|tx| {
+            for address in &expired {
+                tx.delete::<WalletFundings>(*address, None)?;
+            }
+            Ok::<_, reth_db::DatabaseError>(())
+        })??;
+        tracing::info!(addresses = expired.len(), "pruned wallet fundings");
+
+        Ok(())
+    }
+}
+
+/// Recomputes the searcher statistics from the curated mev blocks and replaces
+/// the stored ones, see [`rebuild_searcher_stats`]
+pub struct SearcherStatsRebuild {
+    pub interval: Duration,
+}
+
+impl ScheduledJob for SearcherStatsRebuild {
+    fn name(&self) -> &'static str {
+        "searcher-stats-rebuild"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn run(&self, db: &Libmdbx) -> eyre::Result<()> {
+        let (searchers, blocks) = rebuild_searcher_stats(db)?;
+        tracing::info!(searchers, blocks, "rebuilt searcher statistics");
+
+        Ok(())
+    }
+}
+
+/// Rolls the searcher statistics up into windows of about a day of blocks,
+/// see [`roll_up_mev`]
+pub struct MevRollupJob {
+    pub interval: Duration,
+}
+
+impl ScheduledJob for MevRollupJob {
+    fn name(&self) -> &'static str {
+        "mev-rollup"
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn run(&self, db: &Libmdbx) -> eyre::Result<()> {
+        let windows = roll_up_mev(db)?;
+        tracing::info!(windows, "rolled up mev");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::{hash_map::Entry, BTreeMap};
+
+use alloy_primitives::Address;
+use brontes_libmdbx::{TransactionKind, RW};
+use brontes_types::{
+    db::{
+        mev_block::MevBlockWithClassified,
+        overrides::BundleOverrides,
+        searcher_stats::{BlockSearcherStats, MevRollup, SearcherStats, SearcherStatsAggregate},
+    },
+    FastHashMap,
+};
+
+use crate::libmdbx::{
+    tables::{
+        BlockSearcherStatistics, BundleOverrides as BundleOverridesTable, BundleRepricings,
+        MevBlocks, MevRollups, SearcherStatistics,
+    },
+    tx::CompressedLibmdbxTx,
+    Libmdbx,
+};
+
+/// Replaces the block's contribution to the searcher statistics with the one
+/// of its curated view, with the latest re-pricing and the overrides applied.
+/// Called in the transaction that changes the block, its re-pricing or its
+/// overrides, so the statistics never count a block twice or miss it
+pub fn refresh_searcher_stats(
+    tx: &CompressedLibmdbxTx<RW>,
+    raw: &MevBlockWithClassified,
+) -> eyre::Result<()> {
+    let block_number = raw.block.block_number;
+    let curated = curated_block(tx, raw)?;
+    let block_stats = BlockSearcherStats::new(&curated.mev);
+
+    let previous = tx.get::<BlockSearcherStatistics>(block_number)?;
+    let mut searchers: FastHashMap<Address, SearcherStats> = FastHashMap::default();
+    for (stats, add) in previous
+        .iter()
+        .flat_map(|p| p.searchers.iter().map(|s| (s, false)))
+        .chain(block_stats.searchers.iter().map(|s| (s, true)))
+    {
+        let searcher = match searchers.entry(stats.searcher) {
+            Entry::Occupied(o) => o.into_mut(),
+            Entry::Vacant(v) => v.insert(
+                tx.get::<SearcherStatistics>(stats.searcher)?
+                    .unwrap_or_default(),
+            ),
+        };
+        if add {
+            searcher.add_block(block_number, stats);
+        } else {
+            searcher.remove_block(stats);
+        }
+    }
+
+    tx.put::<BlockSearcherStatistics>(block_number, block_stats)?;
+    for (searcher, stats) in searchers {
+        tx.put::<SearcherStatistics>(searcher, stats)?;
+    }
+
+    Ok(())
+}
+
+fn curated_block(
+    tx: &CompressedLibmdbxTx<RW>,
+    raw: &MevBlockWithClassified,
+) -> eyre::Result<MevBlockWithClassified> {
+    let mut overrides = vec![];
+    for entry in tx.cursor_read::<BundleOverridesTable>()?.walk(None)? {
+        overrides.push(entry?);
+    }
+    let repricings = tx.get::<BundleRepricings>(raw.block.block_number)?;
+
+    let mut curated = raw.clone();
+    curated.curate(repricings.as_ref(), &BundleOverrides::new(overrides));
+
+    Ok(curated)
+}
+
+/// Recomputes the searcher statistics from the curated mev blocks
+pub fn aggregate_searcher_stats(db: &Libmdbx) -> eyre::Result<SearcherStatsAggregate> {
+    db.view_db(aggregate_searcher_stats_in)
+}
+
+/// Recomputes the searcher statistics from the curated mev blocks and
+/// replaces the stored ones. Returns the number of searchers and blocks.
+///
+/// The blocks are read in a read transaction so the writer isn't held up while
+/// they're decoded. A block written in the meantime refreshed its statistics
+/// in its own write, so the statistics of the blocks that changed since the
+/// read are taken from the table instead of the aggregate
+pub fn rebuild_searcher_stats(db: &Libmdbx) -> eyre::Result<(usize, usize)> {
+    let (aggregate, read_stats) =
+        db.view_db(|tx| Ok((aggregate_searcher_stats_in(tx)?, block_searcher_stats_in(tx)?)))?;
+
+    db.try_update_db(|tx| {
+        let mut blocks: BTreeMap<u64, BlockSearcherStats> = aggregate.blocks.into_iter().collect();
+        for (block, stats) in block_searcher_stats_in(tx)? {
+            if read_stats.get(&block) != Some(&stats) {
+                blocks.insert(block, stats);
+            }
+        }
+
+        let mut rebuilt = SearcherStatsAggregate::default();
+        for (block, stats) in blocks {
+            rebuilt.add_block_stats(block, stats);
+        }
+        let counts = (rebuilt.searchers.len(), rebuilt.blocks.len());
+
+        tx.clear::<BlockSearcherStatistics>()?;
+        tx.clear::<SearcherStatistics>()?;
+        for (block, stats) in rebuilt.blocks {
+            tx.put::<BlockSearcherStatistics>(block, stats)?;
+        }
+        for (searcher, stats) in rebuilt.searchers {
+            tx.put::<SearcherStatistics>(searcher, stats)?;
+        }
+
+        Ok(counts)
+    })
+}
+
+/// Rolls the per block statistics up into windows of
+/// [`ROLLUP_BLOCKS`](brontes_types::db::searcher_stats::ROLLUP_BLOCKS) blocks
+/// and writes the windows that changed. Returns the number of windows written
+pub fn roll_up_mev(db: &Libmdbx) -> eyre::Result<usize> {
+    let (rollups, stored) = db.view_db(|tx| {
+        let rollups = MevRollup::roll_up(block_searcher_stats_in(tx)?.iter().map(|(b, s)| (*b, s)));
+        let mut stored = BTreeMap::new();
+        for entry in tx.cursor_read::<MevRollups>()?.walk(None)? {
+            let (start, rollup) = entry?;
+            stored.insert(start, rollup);
+        }
+
+        Ok((rollups, stored))
+    })?;
+
+    db.try_update_db(|tx| {
+        for start in stored.keys().filter(|start| !rollups.contains_key(start)) {
+            tx.delete::<MevRollups>(*start, None)?;
+        }
+        let mut written = 0;
+        for (start, rollup) in rollups {
+            if stored.get(&start) != Some(&rollup) {
+                tx.put::<MevRollups>(start, rollup)?;
+                written += 1;
+            }
+        }
+
+        Ok(written)
+    })
+}
+
+fn block_searcher_stats_in<K: TransactionKind>(
+    tx: &CompressedLibmdbxTx<K>,
+) -> eyre::Result<BTreeMap<u64, BlockSearcherStats>> {
+    let mut res = BTreeMap::new();
+    for entry in tx.cursor_read::<BlockSearcherStatistics>()?.walk(None)? {
+        let (block, stats) = entry?;
+        res.insert(block, stats);
+    }
+
+    Ok(res)
+}
+
+/// Streams the blocks through the aggregate one at a time rather than loading
+/// them all, only the per block statistics are kept
+fn aggregate_searcher_stats_in<K: TransactionKind>(
+    tx: &CompressedLibmdbxTx<K>,
+) -> eyre::Result<SearcherStatsAggregate> {
+    let mut overrides = vec![];
+    for entry in tx.cursor_read::<BundleOverridesTable>()?.walk(None)? {
+        overrides.push(entry?);
+    }
+    let overrides = BundleOverrides::new(overrides);
+
+    let mut aggregate = SearcherStatsAggregate::default();
+    for entry in tx.cursor_read::<MevBlocks>()?.walk(None)? {
+        let (block_number, mut block) = entry?;
+        let repricings = tx.get::<BundleRepricings>(block_number)?;
+        block.curate(repricings.as_ref(), &overrides);
+        aggregate.add_block(&block);
+    }
+
+    Ok(aggregate)
+}
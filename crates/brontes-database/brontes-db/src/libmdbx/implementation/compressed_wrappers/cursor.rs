@@ -57,6 +57,14 @@ where
         self.0.seek_raw(key).map(|opt| opt.map(Into::into))
     }
 
+    pub fn first_raw_value(&mut self) -> Result<Option<Vec<u8>>, DatabaseError> {
+        self.0.first_raw_value()
+    }
+
+    pub fn last_raw_value(&mut self) -> Result<Option<Vec<u8>>, DatabaseError> {
+        self.0.last_raw_value()
+    }
+
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> CompressedPairResult<T> {
         self.0.next().map(|opt| opt.map(Into::into))
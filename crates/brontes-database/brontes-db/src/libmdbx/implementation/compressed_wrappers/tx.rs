@@ -1,13 +1,27 @@
+use std::sync::Arc;
+
 use brontes_libmdbx::{ffi::DBI, TransactionKind, RO, RW};
 use reth_db::{
-    table::DupSort,
+    table::{Compress, DupSort, Encode},
     transaction::{DbTx, DbTxMut},
     DatabaseError,
 };
 
 use super::cursor::CompressedCursor;
-use crate::libmdbx::{implementation::native::tx::LibmdbxTx, types::CompressedTable, DatabaseEnv};
-pub struct CompressedLibmdbxTx<K: TransactionKind>(pub(crate) LibmdbxTx<K>);
+use crate::libmdbx::{
+    implementation::native::tx::LibmdbxTx,
+    types::CompressedTable,
+    write_log::{TxWriteLog, WriteLog},
+    DatabaseEnv,
+};
+
+/// Write transactions of a database with a write log record their puts,
+/// deletes and clears and append them to the log before committing. Writes
+/// through a write cursor aren't recorded
+pub struct CompressedLibmdbxTx<K: TransactionKind>(
+    pub(crate) LibmdbxTx<K>,
+    pub(crate) Option<TxWriteLog>,
+);
 
 impl<K: TransactionKind> CompressedLibmdbxTx<K> {
     /// Gets a table database handle if it exists, otherwise creates it.
@@ -36,8 +50,26 @@ impl<K: TransactionKind> CompressedLibmdbxTx<K> {
         self.0.get::<T>(key).map(|opt| opt.map(Into::into))
     }
 
+    /// The value stored under the key as it's stored, without decoding it
+    pub fn get_raw<T>(&self, key: T::Key) -> Result<Option<Vec<u8>>, DatabaseError>
+    where
+        T: CompressedTable,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+    {
+        self.0.get_raw::<T>(key)
+    }
+
+    /// Commits the transaction. Its writes are logged before the commit and
+    /// marked committed in the log once it succeeded
     pub fn commit(self) -> Result<bool, DatabaseError> {
-        self.0.commit()
+        let Self(tx, log) = self;
+        let record = log.as_ref().map(TxWriteLog::append).transpose()?.flatten();
+        let committed = tx.commit()?;
+        if let Some(record) = record {
+            record.committed();
+        }
+
+        Ok(committed)
     }
 
     pub fn abort(self) {
@@ -71,13 +103,19 @@ impl<K: TransactionKind> CompressedLibmdbxTx<K> {
 
 impl CompressedLibmdbxTx<RO> {
     pub fn new_ro_tx(env: &DatabaseEnv) -> eyre::Result<Self, DatabaseError> {
-        Ok(Self(LibmdbxTx::new_ro_tx(env)?))
+        Ok(Self(LibmdbxTx::new_ro_tx(env)?, None))
     }
 }
 
 impl CompressedLibmdbxTx<RW> {
     pub fn new_rw_tx(env: &DatabaseEnv) -> Result<Self, DatabaseError> {
-        Ok(Self(LibmdbxTx::new_rw_tx(env)?))
+        Ok(Self(LibmdbxTx::new_rw_tx(env)?, None))
+    }
+
+    /// Records the writes of the transaction in the log
+    pub(crate) fn with_write_log(mut self, log: Option<Arc<WriteLog>>) -> Self {
+        self.1 = log.map(TxWriteLog::new);
+        self
     }
 
     pub fn append_bytes<T: CompressedTable>(
@@ -88,6 +126,9 @@ impl CompressedLibmdbxTx<RW> {
     where
         T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
     {
+        if let Some(log) = &self.1 {
+            log.put(T::NAME, key, &value);
+        }
         self.0.append_bytes::<T>(key, value)
     }
 
@@ -99,6 +140,9 @@ impl CompressedLibmdbxTx<RW> {
     where
         T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
     {
+        if let Some(log) = &self.1 {
+            log.put(T::NAME, key, &value);
+        }
         self.0.put_bytes::<T>(key, value)
     }
 
@@ -107,7 +151,13 @@ impl CompressedLibmdbxTx<RW> {
         T: CompressedTable,
         T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
     {
-        self.0.put::<T>(key, value.into())
+        let value: T::Value = value.into();
+        let Some(log) = &self.1 else { return self.0.put::<T>(key, value) };
+
+        let key = key.encode();
+        let value: Vec<u8> = value.compress().into();
+        log.put(T::NAME, key.as_ref(), &value);
+        self.0.put_bytes::<T>(key.as_ref(), value)
     }
 
     pub fn delete<T>(
@@ -119,6 +169,9 @@ impl CompressedLibmdbxTx<RW> {
         T: CompressedTable,
         T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
     {
+        if let Some(log) = &self.1 {
+            log.delete(T::NAME, key.clone().encode().as_ref());
+        }
         self.0.delete::<T>(key, value.map(Into::into))
     }
 
@@ -127,6 +180,9 @@ impl CompressedLibmdbxTx<RW> {
         T: CompressedTable,
         T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
     {
+        if let Some(log) = &self.1 {
+            log.clear(T::NAME);
+        }
         self.0.clear::<T>()
     }
 
@@ -44,6 +44,22 @@ impl<T: Table, K: TransactionKind> LibmdbxCursor<T, K> {
     pub fn seek_raw(&mut self, key: &[u8]) -> PairResult<T> {
         decode!(self.inner.set_key(key))
     }
+
+    /// The value of the first entry as it's stored, without decoding it
+    pub fn first_raw_value(&mut self) -> Result<Option<Vec<u8>>, DatabaseError> {
+        self.inner
+            .first::<(), Vec<u8>>()
+            .map(|entry| entry.map(|(_, value)| value))
+            .map_err(|e| DatabaseError::Read(e.into()))
+    }
+
+    /// The value of the last entry as it's stored, without decoding it
+    pub fn last_raw_value(&mut self) -> Result<Option<Vec<u8>>, DatabaseError> {
+        self.inner
+            .last::<(), Vec<u8>>()
+            .map(|entry| entry.map(|(_, value)| value))
+            .map_err(|e| DatabaseError::Read(e.into()))
+    }
 }
 
 /// Takes `(key, value)` from the database and decodes it appropriately.
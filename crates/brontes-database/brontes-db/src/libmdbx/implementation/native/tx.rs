@@ -115,6 +115,13 @@ impl<K: TransactionKind> LibmdbxTx<K> {
 
         Ok(LibmdbxCursor::new(inner))
     }
+
+    /// The value stored under the key as it's stored, without decoding it
+    pub(crate) fn get_raw<T: Table>(&self, key: T::Key) -> Result<Option<Vec<u8>>, DatabaseError> {
+        self.inner
+            .get::<Vec<u8>>(self.get_dbi::<T>()?, key.encode().as_ref())
+            .map_err(|e| DatabaseError::Read(e.into()))
+    }
 }
 
 impl<K: TransactionKind> DbTx for LibmdbxTx<K> {
@@ -1,6 +1,10 @@
-use std::{ops::RangeInclusive, path::Path, sync::Arc};
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 use brontes_metrics::db_reads::LibmdbxMetrics;
 use brontes_pricing::Protocol;
 use brontes_types::{
@@ -8,18 +12,35 @@ use brontes_types::{
     db::{
         address_metadata::AddressMetadata,
         address_to_protocol_info::ProtocolInfo,
+        beacon::BeaconBlockInfo,
+        block_costs::{BlockCost, BlockCostRecorder},
         builder::BuilderInfo,
+        bundle_tags::{BlockBundleTags, BundleTag},
         cex::{quotes::CexPriceMap, trades::CexTradeMap},
+        config_reload::ConfigReload,
         dex::{make_filter_key_range, DexPrices, DexQuotes},
+        fee_history::BlockFees,
+        frontend_losses::BlockFrontendLosses,
         initialized_state::{
             InitializedStateMeta, CEX_QUOTES_FLAG, CEX_TRADES_FLAG, DATA_NOT_PRESENT_NOT_AVAILABLE,
             DATA_PRESENT, DEX_PRICE_FLAG, META_FLAG,
         },
+        inspector_artifacts::BlockInspectorArtifacts,
         metadata::{BlockMetadata, BlockMetadataInner, Metadata},
         mev_block::MevBlockWithClassified,
+        mev_receipt::MevReceipt,
+        order_flow::{BlockOrderFlow, BuilderProposerPayments, SearcherOrderFlow},
+        overrides::BundleOverride,
+        pool_prices::BlockPoolPrices,
+        publish_checkpoint::PublishCheckpoint,
+        repricing::BlockRepricings,
         searcher::SearcherInfo,
+        searcher_stats::SearcherStats,
+        suppression::{SuppressionChange, SuppressionList},
         token_info::{TokenInfo, TokenInfoWithAddress},
+        token_metadata::TokenMetadata,
         traits::{DBWriter, LibmdbxReader},
+        wallet_funding::FundingEdge,
     },
     mev::{Bundle, MevBlock},
     normalized_actions::Action,
@@ -33,14 +54,20 @@ use futures::Future;
 use indicatif::ProgressBar;
 use itertools::Itertools;
 use malachite::Rational;
+use parking_lot::Mutex;
 use reth_db::table::{Compress, Encode};
 use reth_interfaces::db::LogLevel;
-use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::{
+    mpsc::{unbounded_channel, UnboundedSender},
+    oneshot,
+};
 use tracing::{info, instrument};
 
 use super::{
+    cold_storage::ColdStorage,
     libmdbx_writer::{LibmdbxWriter, StampedWriterMessage, WriterMessage},
     types::ReturnKV,
+    write_log::WriteLog,
     ReadWriteCache,
 };
 #[cfg(feature = "local-clickhouse")]
@@ -94,11 +121,17 @@ pub trait LibmdbxInit: LibmdbxReader + DBWriter {
 
 #[derive(Clone)]
 pub struct LibmdbxReadWriter {
-    pub db:  Arc<Libmdbx>,
-    pub tx:  UnboundedSender<StampedWriterMessage>,
-    metrics: Option<LibmdbxMetrics>,
+    pub db:                  Arc<Libmdbx>,
+    pub tx:                  UnboundedSender<StampedWriterMessage>,
+    metrics:                 Option<LibmdbxMetrics>,
     // 100 shards for now, might change in future
-    cache:   ReadWriteCache,
+    cache:                   ReadWriteCache,
+    /// Where traces and cex data of old ranges are fetched from once they
+    /// have been moved off the local db
+    pub(crate) cold_storage: Option<Arc<ColdStorage>>,
+    /// The suppression list with the id of the last change it was built from,
+    /// it's only rebuilt once a new change is appended
+    suppression_list:        Arc<Mutex<Option<(u64, SuppressionList)>>>,
 }
 
 impl LibmdbxReadWriter {
@@ -107,23 +140,54 @@ impl LibmdbxReadWriter {
         log_level: Option<LogLevel>,
         ex: &BrontesTaskExecutor,
         metrics: bool,
+    ) -> eyre::Result<Self> {
+        Self::init_db_with_write_log(path, log_level, ex, metrics, None::<PathBuf>)
+    }
+
+    /// If a write log path is given, every write transaction is appended to
+    /// the log before it commits. See [`WriteLog`].
+    pub fn init_db_with_write_log<P: AsRef<Path>, L: AsRef<Path>>(
+        path: P,
+        log_level: Option<LogLevel>,
+        ex: &BrontesTaskExecutor,
+        metrics: bool,
+        write_log: Option<L>,
     ) -> eyre::Result<Self> {
         // 5 gb total
         let memory_per_table_mb = 1_000;
         let (tx, rx) = unbounded_channel();
         let yapper = UnboundedYapperReceiver::new(rx, 1500, "libmdbx write channel".to_string());
         let db = Arc::new(Libmdbx::init_db(path, log_level)?);
+        if let Some(write_log) = write_log {
+            db.set_write_log(WriteLog::open(write_log)?)?;
+        }
         let shutdown = ex.get_graceful_shutdown();
 
         // start writing task on own thread
-        let writer = LibmdbxWriter::new(db.clone(), yapper, metrics);
-        writer.run(shutdown);
+        LibmdbxWriter::new(db.clone(), yapper, metrics).run(shutdown);
 
         Ok(Self {
             db,
             tx,
             metrics: metrics.then(LibmdbxMetrics::default),
             cache: ReadWriteCache::new(memory_per_table_mb, metrics),
+            cold_storage: None,
+            suppression_list: Arc::default(),
+        })
+    }
+
+    /// Opens an existing db to read it, without a writer. Writes to it fail
+    pub fn init_db_read_only<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
+        // dropping the receiver makes every write fail rather than queue
+        let (tx, _) = unbounded_channel();
+
+        Ok(Self {
+            db: Arc::new(Libmdbx::init_db_read_only(path)?),
+            tx,
+            metrics: None,
+            cache: ReadWriteCache::new(1_000, false),
+            cold_storage: None,
+            suppression_list: Arc::default(),
         })
     }
 
@@ -138,7 +202,14 @@ impl LibmdbxReadWriter {
         let writer = LibmdbxWriter::new(db.clone(), yapper, false);
         writer.run_no_shutdown();
 
-        Ok(Self { db, tx, metrics: None, cache: ReadWriteCache::new(memory_per_table_mb, false) })
+        Ok(Self {
+            db,
+            tx,
+            metrics: None,
+            cache: ReadWriteCache::new(memory_per_table_mb, false),
+            cold_storage: None,
+            suppression_list: Arc::default(),
+        })
     }
 }
 
@@ -423,14 +494,14 @@ impl LibmdbxReader for LibmdbxReadWriter {
 
     #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"load_trace")]
     fn load_trace(&self, block_num: u64) -> eyre::Result<Vec<TxTrace>> {
-        self.db.view_db(|tx| {
-            tx.get::<TxTraces>(block_num)?
-                .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))
-                .map(|i| {
-                    i.traces
-                        .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))
-                })?
-        })
+        let traces = match self.db.view_db(|tx| Ok(tx.get::<TxTraces>(block_num)?))? {
+            Some(traces) => Some(traces),
+            None => self.fetch_cold::<TxTraces>(block_num)?,
+        };
+
+        traces
+            .and_then(|i| i.traces)
+            .ok_or_else(|| eyre::eyre!("missing trace for block: {}", block_num))
     }
 
     #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"protocol_info")]
@@ -481,6 +552,7 @@ impl LibmdbxReader for LibmdbxReadWriter {
             eth_price.unwrap_or_default(),
             block_meta.private_flow.into_iter().collect(),
         )
+        .with_beacon_info(self.try_fetch_beacon_info(block_num)?)
         .into_metadata(cex_quotes, None, None, None))
     }
 
@@ -505,6 +577,7 @@ impl LibmdbxReader for LibmdbxReadWriter {
                 eth_price.unwrap_or_default(),
                 block_meta.private_flow.into_iter().collect(),
             )
+            .with_beacon_info(self.try_fetch_beacon_info(block_num)?)
             .into_metadata(cex_quotes, Some(dex_quotes), None, None)
         })
     }
@@ -812,6 +885,276 @@ impl LibmdbxReader for LibmdbxReadWriter {
         })
     }
 
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_beacon_info")]
+    fn try_fetch_beacon_info(&self, block_num: u64) -> eyre::Result<Option<BeaconBlockInfo>> {
+        self.db
+            .view_db(|tx| tx.get::<BeaconBlocks>(block_num).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_bundle_overrides")]
+    fn fetch_bundle_overrides(&self) -> eyre::Result<Vec<(u64, BundleOverride)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<BundleOverrides>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk(None)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_suppression_changes")]
+    fn fetch_suppression_changes(&self) -> eyre::Result<Vec<(u64, SuppressionChange)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<Suppressions>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk(None)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    fn fetch_suppression_list(&self) -> eyre::Result<SuppressionList> {
+        let Some(last_change) = self
+            .db
+            .view_db(|tx| Ok(tx.cursor_read::<Suppressions>()?.last()?.map(|(id, _)| id)))?
+        else {
+            return Ok(SuppressionList::default())
+        };
+
+        let mut cached = self.suppression_list.lock();
+        if let Some((id, list)) = cached.as_ref() {
+            if *id == last_change {
+                return Ok(list.clone())
+            }
+        }
+
+        let list = SuppressionList::new(self.fetch_suppression_changes()?);
+        *cached = Some((last_change, list.clone()));
+
+        Ok(list)
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_bundle_repricings")]
+    fn fetch_bundle_repricings(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockRepricings)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<BundleRepricings>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk_range(start_block.unwrap_or_default()..=end_block)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_wallet_funding")]
+    fn try_fetch_wallet_funding(&self, address: Address) -> eyre::Result<Option<FundingEdge>> {
+        self.db
+            .view_db(|tx| tx.get::<WalletFundings>(address).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_mev_receipt")]
+    fn try_fetch_mev_receipt(&self, tx_hash: B256) -> eyre::Result<Option<MevReceipt>> {
+        self.db
+            .view_db(|tx| tx.get::<MevReceipts>(tx_hash).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_bundle_tags")]
+    fn fetch_bundle_tags(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockBundleTags)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<BundleTags>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk_range(start_block..end_block)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_inspector_artifacts")]
+    fn fetch_inspector_artifacts(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockInspectorArtifacts)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<InspectorArtifacts>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk_range(start_block..end_block)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_block_fees")]
+    fn try_fetch_block_fees(&self, block_num: u64) -> eyre::Result<Option<BlockFees>> {
+        self.db
+            .view_db(|tx| tx.get::<FeeHistory>(block_num).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_fee_history")]
+    fn fetch_fee_history(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFees)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<FeeHistory>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk_range(start_block..end_block)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_pool_prices")]
+    fn fetch_pool_prices(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockPoolPrices)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<PoolPriceHistory>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk_range(start_block..end_block)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_frontend_losses")]
+    fn fetch_frontend_losses(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFrontendLosses)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<FrontendLosses>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk_range(start_block..end_block)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_block_costs")]
+    fn fetch_block_costs(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockCost)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<BlockCosts>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk_range(start_block..end_block)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_builder_proposer_payments")]
+    fn try_fetch_builder_proposer_payments(
+        &self,
+        builder: Address,
+    ) -> eyre::Result<Option<BuilderProposerPayments>> {
+        self.db
+            .view_db(|tx| tx.get::<BuilderPayments>(builder).map_err(ErrReport::from))
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_searcher_order_flow")]
+    fn try_fetch_searcher_order_flow(
+        &self,
+        searcher: Address,
+    ) -> eyre::Result<Option<SearcherOrderFlow>> {
+        self.db.view_db(|tx| {
+            tx.get::<SearcherOrderFlows>(searcher)
+                .map_err(ErrReport::from)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_searcher_stats")]
+    fn try_fetch_searcher_stats(&self, searcher: Address) -> eyre::Result<Option<SearcherStats>> {
+        self.db.view_db(|tx| {
+            tx.get::<SearcherStatistics>(searcher)
+                .map_err(ErrReport::from)
+        })
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_all_searcher_stats")]
+    fn fetch_all_searcher_stats(&self) -> eyre::Result<Vec<(Address, SearcherStats)>> {
+        let stats = self.db.export_db(
+            None,
+            |start_key, tx| {
+                let mut cur = tx.cursor_read::<SearcherStatistics>()?;
+                if let Some(key) = start_key {
+                    let _ = cur.seek(key);
+                } else {
+                    // move to first entry and make sure .next() is first
+                    let _ = cur.first();
+                    let _ = cur.prev();
+                }
+                Ok(cur)
+            },
+            |cursor| Ok(cursor.next().map(|inner| inner.map(|i| (i.0, i.1)))?),
+        )?;
+
+        // searchers whose only blocks were reprocessed without their bundles
+        Ok(stats
+            .into_iter()
+            .filter(|(_, stats)| !stats.is_empty())
+            .collect())
+    }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"fetch_block_order_flows")]
+    fn fetch_block_order_flows(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockOrderFlow)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<BlockOrderFlows>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk_range(start_block..end_block)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
+
     #[instrument(level = "error", skip_all)]
     fn fetch_all_builder_info(&self) -> eyre::Result<Vec<(Address, BuilderInfo)>> {
         self.db.export_db(
@@ -898,13 +1241,32 @@ impl LibmdbxReader for LibmdbxReadWriter {
             |cursor| Ok(cursor.next().map(|inner| inner.map(|i| (i.0, i.1)))?),
         )
     }
+
+    #[brontes_macros::metrics_call(ptr=metrics,scope,db_read,"try_fetch_token_metadata")]
+    fn try_fetch_token_metadata(&self, address: Address) -> eyre::Result<Option<TokenMetadata>> {
+        self.db
+            .view_db(|tx| tx.get::<TokenMeta>(address).map_err(ErrReport::from))
+    }
+
+    fn fetch_all_token_metadata(&self) -> eyre::Result<Vec<(Address, TokenMetadata)>> {
+        self.db.view_db(|tx| {
+            let mut cursor = tx.cursor_read::<TokenMeta>()?;
+            let mut res = Vec::new();
+
+            for entry in cursor.walk(None)? {
+                res.push(entry?);
+            }
+
+            Ok(res)
+        })
+    }
 }
 
 impl DBWriter for LibmdbxReadWriter {
     type Inner = Self;
 
-    fn inner(&self) -> &Self::Inner {
-        unreachable!()
+    fn inner(&self) -> Option<&Self::Inner> {
+        None
     }
 
     async fn write_searcher_info(
@@ -988,9 +1350,15 @@ impl DBWriter for LibmdbxReadWriter {
         block: MevBlock,
         mev: Vec<Bundle>,
     ) -> eyre::Result<()> {
-        Ok(self
-            .tx
-            .send(WriterMessage::MevBlocks { block_number, block: Box::new(block), mev }.stamp())?)
+        Ok(self.tx.send(
+            WriterMessage::MevBlocks {
+                block_number,
+                block: Box::new(block),
+                mev,
+                cost: BlockCostRecorder::current(),
+            }
+            .stamp(),
+        )?)
     }
 
     async fn write_dex_quotes(
@@ -998,9 +1366,10 @@ impl DBWriter for LibmdbxReadWriter {
         block_number: u64,
         quotes: Option<DexQuotes>,
     ) -> eyre::Result<()> {
-        Ok(self
-            .tx
-            .send(WriterMessage::DexQuotes { block_number, quotes }.stamp())?)
+        Ok(self.tx.send(
+            WriterMessage::DexQuotes { block_number, quotes, cost: BlockCostRecorder::current() }
+                .stamp(),
+        )?)
     }
 
     async fn write_token_info(
@@ -1056,9 +1425,9 @@ impl DBWriter for LibmdbxReadWriter {
     }
 
     async fn save_traces(&self, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
-        Ok(self
-            .tx
-            .send(WriterMessage::Traces { block, traces }.stamp())?)
+        Ok(self.tx.send(
+            WriterMessage::Traces { block, traces, cost: BlockCostRecorder::current() }.stamp(),
+        )?)
     }
 
     async fn write_builder_info(
@@ -1072,6 +1441,152 @@ impl DBWriter for LibmdbxReadWriter {
         )?)
     }
 
+    async fn write_beacon_info(
+        &self,
+        block_number: u64,
+        beacon_info: BeaconBlockInfo,
+    ) -> eyre::Result<()> {
+        Ok(self.tx.send(
+            WriterMessage::BeaconInfo { block_number, beacon_info: Box::new(beacon_info) }.stamp(),
+        )?)
+    }
+
+    async fn write_block_fees(&self, block_number: u64, fees: BlockFees) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::BlockFees { block_number, fees }.stamp())?)
+    }
+
+    async fn write_pool_prices(
+        &self,
+        block_number: u64,
+        prices: BlockPoolPrices,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::PoolPrices { block_number, prices }.stamp())?)
+    }
+
+    async fn write_frontend_losses(
+        &self,
+        block_number: u64,
+        losses: BlockFrontendLosses,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::FrontendLosses { block_number, losses }.stamp())?)
+    }
+
+    async fn write_token_metadata(
+        &self,
+        address: Address,
+        metadata: TokenMetadata,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::TokenMetadata { address, metadata }.stamp())?)
+    }
+
+    async fn write_wallet_fundings(
+        &self,
+        fundings: Vec<(Address, FundingEdge)>,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::WalletFundings { fundings }.stamp())?)
+    }
+
+    async fn write_block_cost(
+        &self,
+        block_number: u64,
+        cost: BlockCostRecorder,
+    ) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::BlockCost { block_number, cost }.stamp())?)
+    }
+
+    async fn write_publish_checkpoint(&self, checkpoint: PublishCheckpoint) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::PublishCheckpoint { checkpoint }.stamp())?)
+    }
+
+    async fn write_config_reload(&self, reload: ConfigReload) -> eyre::Result<()> {
+        Ok(self
+            .tx
+            .send(WriterMessage::ConfigReload { reload }.stamp())?)
+    }
+
+    /// Returns once the writer has committed the override
+    async fn append_bundle_override(&self, bundle_override: BundleOverride) -> eyre::Result<u64> {
+        let (reply, id) = oneshot::channel();
+        self.tx
+            .send(WriterMessage::BundleOverride { bundle_override, reply }.stamp())?;
+
+        id.await?
+    }
+
+    /// Returns once the writer has committed the writes sent before, the
+    /// batched inserts included
+    async fn flush_writes(&self) -> eyre::Result<()> {
+        let (reply, flushed) = oneshot::channel();
+        self.tx.send(WriterMessage::Flush { reply }.stamp())?;
+
+        flushed.await?
+    }
+
+    /// Returns once the writer has committed the change, a rule takes effect
+    /// from the next block processed
+    async fn append_suppression_change(&self, change: SuppressionChange) -> eyre::Result<u64> {
+        let (reply, id) = oneshot::channel();
+        self.tx
+            .send(WriterMessage::SuppressionChange { change, reply }.stamp())?;
+
+        id.await?
+    }
+
+    /// Tags are written directly, manual and detector tags share an entry so
+    /// each update has to read the entry in the same tx
+    async fn write_detected_bundle_tags(
+        &self,
+        block_number: u64,
+        tags: Vec<BundleTag>,
+    ) -> eyre::Result<()> {
+        self.update_bundle_tags(block_number, |block_tags| block_tags.replace_detected(tags))
+    }
+
+    /// Debug artifacts are only written when enabled, so they are written
+    /// directly instead of through the writer
+    async fn write_inspector_artifacts(
+        &self,
+        block_number: u64,
+        artifacts: BlockInspectorArtifacts,
+    ) -> eyre::Result<()> {
+        self.db.update_db(|tx| {
+            if artifacts.is_empty() {
+                tx.delete::<InspectorArtifacts>(block_number, None)?;
+            } else {
+                tx.put::<InspectorArtifacts>(block_number, artifacts)?;
+            }
+
+            Ok::<_, ErrReport>(())
+        })?
+    }
+
+    async fn tag_bundle(&self, block_number: u64, tag: BundleTag) -> eyre::Result<bool> {
+        self.update_bundle_tags(block_number, |block_tags| block_tags.add(tag))
+    }
+
+    async fn untag_bundle(
+        &self,
+        block_number: u64,
+        tx_hash: B256,
+        tag: String,
+    ) -> eyre::Result<bool> {
+        self.update_bundle_tags(block_number, |block_tags| block_tags.remove(tx_hash, &tag))
+    }
+
     /// only for internal functionality (i.e. clickhouse)
     async fn insert_tree(&self, _tree: BlockTree<Action>) -> eyre::Result<()> {
         Ok(())
@@ -1105,6 +1620,26 @@ impl LibmdbxReadWriter {
         Ok(())
     }
 
+    /// Applies the update to the tags of the block, an entry left without
+    /// tags is removed
+    fn update_bundle_tags<R>(
+        &self,
+        block_number: u64,
+        update: impl FnOnce(&mut BlockBundleTags) -> R,
+    ) -> eyre::Result<R> {
+        self.db.update_db(|tx| {
+            let mut tags = tx.get::<BundleTags>(block_number)?.unwrap_or_default();
+            let res = update(&mut tags);
+            if tags.is_empty() {
+                tx.delete::<BundleTags>(block_number, None)?;
+            } else {
+                tx.put::<BundleTags>(block_number, tags)?;
+            }
+
+            Ok::<_, ErrReport>(res)
+        })?
+    }
+
     #[cfg(feature = "local-clickhouse")]
     pub fn get_crit_table_count(&self) -> eyre::Result<ClickhouseCritTableCount> {
         let pool_creation = self.get_table_entry_count::<PoolCreationBlocks>()? as u64;
@@ -1223,32 +1758,36 @@ impl LibmdbxReadWriter {
     }
 
     pub fn fetch_trades(&self, block: u64) -> eyre::Result<CexTradeMap> {
-        self.db.view_db(|tx| {
-            tx.get::<CexTrades>(block)?
-                .ok_or_else(|| eyre::eyre!("no cex trades"))
-                .inspect_err(|_| {
-                    let _ = self.init_state_updating(
-                        block,
-                        CEX_TRADES_FLAG,
-                        DATA_NOT_PRESENT_NOT_AVAILABLE,
-                    );
-                })
-        })
-    }
-
-    pub fn fetch_cex_quotes(&self, block_num: u64) -> eyre::Result<CexPriceMap> {
-        self.db.view_db(|tx| {
-            let res = tx.get::<CexPrice>(block_num)?.unwrap_or_else(|| {
+        let trades = match self.db.view_db(|tx| Ok(tx.get::<CexTrades>(block)?))? {
+            Some(trades) => Some(trades),
+            None => self.fetch_cold::<CexTrades>(block)?,
+        };
+
+        trades
+            .ok_or_else(|| eyre::eyre!("no cex trades"))
+            .inspect_err(|_| {
                 let _ = self.init_state_updating(
-                    block_num,
-                    CEX_QUOTES_FLAG,
+                    block,
+                    CEX_TRADES_FLAG,
                     DATA_NOT_PRESENT_NOT_AVAILABLE,
                 );
-                CexPriceMap::default()
-            });
+            })
+    }
 
-            Ok(res)
-        })
+    pub fn fetch_cex_quotes(&self, block_num: u64) -> eyre::Result<CexPriceMap> {
+        let quotes = match self.db.view_db(|tx| Ok(tx.get::<CexPrice>(block_num)?))? {
+            Some(quotes) => Some(quotes),
+            None => self.fetch_cold::<CexPrice>(block_num)?,
+        };
+
+        Ok(quotes.unwrap_or_else(|| {
+            let _ = self.init_state_updating(
+                block_num,
+                CEX_QUOTES_FLAG,
+                DATA_NOT_PRESENT_NOT_AVAILABLE,
+            );
+            CexPriceMap::default()
+        }))
     }
 
     pub fn fetch_dex_quotes(&self, block_num: u64) -> eyre::Result<DexQuotes> {
@@ -1,4 +1,5 @@
 use std::{
+    collections::hash_map::Entry,
     ops::Deref,
     sync::Arc,
     task::Poll,
@@ -6,19 +7,33 @@ use std::{
 };
 
 use alloy_primitives::Address;
+use brontes_libmdbx::RW;
 use brontes_metrics::db_writer::WriterMetrics;
 use brontes_types::{
     db::{
         address_metadata::AddressMetadata,
         address_to_protocol_info::ProtocolInfo,
+        beacon::BeaconBlockInfo,
+        block_checksum::BlockChecksum,
+        block_costs::BlockCostRecorder,
         builder::BuilderInfo,
+        config_reload::ConfigReload,
         dex::{make_key, DexQuoteWithIndex, DexQuotes},
+        fee_history::BlockFees,
+        frontend_losses::BlockFrontendLosses,
         initialized_state::{DATA_PRESENT, DEX_PRICE_FLAG, TRACE_FLAG},
         mev_block::MevBlockWithClassified,
+        order_flow::{BlockOrderFlow, BuilderProposerPayments, SearcherOrderFlow},
+        overrides::BundleOverride,
         pool_creation_block::PoolsToAddresses,
+        pool_prices::BlockPoolPrices,
+        publish_checkpoint::{checkpoint_key, PublishCheckpoint},
         searcher::SearcherInfo,
+        suppression::SuppressionChange,
         token_info::TokenInfo,
+        token_metadata::TokenMetadata,
         traces::TxTracesInner,
+        wallet_funding::FundingEdge,
     },
     mev::{Bundle, MevBlock},
     structured_trace::TxTrace,
@@ -27,16 +42,18 @@ use brontes_types::{
 use futures::{pin_mut, Future};
 use itertools::Itertools;
 use reth_db::{
-    table::{Compress, Encode},
+    table::{Compress, Encode, Table},
     DatabaseError,
 };
 use reth_tasks::shutdown::GracefulShutdown;
-use tokio::sync::Notify;
+use tokio::sync::{oneshot, Notify};
 use tracing::instrument;
 
 use crate::{
     libmdbx::{
+        refresh_mev_receipts, refresh_searcher_stats,
         tables::*,
+        tx::CompressedLibmdbxTx,
         types::{LibmdbxData, ReturnKV},
         Libmdbx,
     },
@@ -53,6 +70,7 @@ pub enum WriterMessage {
     DexQuotes {
         block_number: u64,
         quotes:       Option<DexQuotes>,
+        cost:         Option<BlockCostRecorder>,
     },
     TokenInfo {
         address:  Address,
@@ -63,6 +81,7 @@ pub enum WriterMessage {
         block_number: u64,
         block:        Box<MevBlock>,
         mev:          Vec<Bundle>,
+        cost:         Option<BlockCostRecorder>,
     },
     SearcherInfo {
         eoa_address:      Address,
@@ -86,6 +105,49 @@ pub enum WriterMessage {
         address:  Address,
         metadata: Box<AddressMetadata>,
     },
+    BeaconInfo {
+        block_number: u64,
+        beacon_info:  Box<BeaconBlockInfo>,
+    },
+    BlockFees {
+        block_number: u64,
+        fees:         BlockFees,
+    },
+    PoolPrices {
+        block_number: u64,
+        prices:       BlockPoolPrices,
+    },
+    FrontendLosses {
+        block_number: u64,
+        losses:       BlockFrontendLosses,
+    },
+    TokenMetadata {
+        address:  Address,
+        metadata: TokenMetadata,
+    },
+    BlockCost {
+        block_number: u64,
+        cost:         BlockCostRecorder,
+    },
+    WalletFundings {
+        fundings: Vec<(Address, FundingEdge)>,
+    },
+    PublishCheckpoint {
+        checkpoint: PublishCheckpoint,
+    },
+    ConfigReload {
+        reload: ConfigReload,
+    },
+    /// Replies with the id the override was appended under
+    BundleOverride {
+        bundle_override: BundleOverride,
+        reply:           oneshot::Sender<eyre::Result<u64>>,
+    },
+    /// Replies with the id the change was appended under
+    SuppressionChange {
+        change: SuppressionChange,
+        reply:  oneshot::Sender<eyre::Result<u64>>,
+    },
     Pool {
         block:           u64,
         address:         Address,
@@ -96,8 +158,13 @@ pub enum WriterMessage {
     Traces {
         block:  u64,
         traces: Vec<TxTrace>,
+        cost:   Option<BlockCostRecorder>,
     },
     Init(InitTables, Arc<Notify>),
+    /// Replies once the batched inserts queued so far are committed
+    Flush {
+        reply: oneshot::Sender<eyre::Result<()>>,
+    },
 }
 
 impl WriterMessage {
@@ -242,20 +309,20 @@ impl LibmdbxWriter {
                 self.insert_pool(block, address, &tokens, curve_lp_token, classifier_name)?;
                 "pool"
             }
-            WriterMessage::Traces { block, traces } => {
-                self.save_traces(block, traces)?;
+            WriterMessage::Traces { block, traces, cost } => {
+                self.save_traces(block, traces, cost)?;
                 "traces"
             }
-            WriterMessage::DexQuotes { block_number, quotes } => {
-                self.write_dex_quotes(block_number, quotes)?;
+            WriterMessage::DexQuotes { block_number, quotes, cost } => {
+                self.write_dex_quotes(block_number, quotes, cost)?;
                 "dexquotes"
             }
             WriterMessage::TokenInfo { address, decimals, symbol } => {
                 self.write_token_info(address, decimals, symbol)?;
                 "tokeninfo"
             }
-            WriterMessage::MevBlocks { block_number, block, mev } => {
-                self.save_mev_blocks(block_number, *block, mev)?;
+            WriterMessage::MevBlocks { block_number, block, mev, cost } => {
+                self.save_mev_blocks(block_number, *block, mev, cost)?;
                 "mevblocks"
             }
             WriterMessage::BuilderInfo { builder_address, builder_info } => {
@@ -266,6 +333,42 @@ impl LibmdbxWriter {
                 self.write_address_meta(address, *metadata)?;
                 "addressmeta"
             }
+            WriterMessage::BeaconInfo { block_number, beacon_info } => {
+                self.write_beacon_info(block_number, *beacon_info)?;
+                "beaconinfo"
+            }
+            WriterMessage::BlockFees { block_number, fees } => {
+                self.write_block_fees(block_number, fees)?;
+                "blockfees"
+            }
+            WriterMessage::PoolPrices { block_number, prices } => {
+                self.write_pool_prices(block_number, prices)?;
+                "poolprices"
+            }
+            WriterMessage::FrontendLosses { block_number, losses } => {
+                self.write_frontend_losses(block_number, losses)?;
+                "frontendlosses"
+            }
+            WriterMessage::TokenMetadata { address, metadata } => {
+                self.write_token_metadata(address, metadata)?;
+                "tokenmetadata"
+            }
+            WriterMessage::BlockCost { block_number, cost } => {
+                self.write_block_cost(block_number, cost)?;
+                "blockcost"
+            }
+            WriterMessage::WalletFundings { fundings } => {
+                self.write_wallet_fundings(fundings)?;
+                "walletfundings"
+            }
+            WriterMessage::PublishCheckpoint { checkpoint } => {
+                self.write_publish_checkpoint(checkpoint)?;
+                "publishcheckpoint"
+            }
+            WriterMessage::ConfigReload { reload } => {
+                self.write_config_reload(reload)?;
+                "configreload"
+            }
             WriterMessage::SearcherInfo {
                 eoa_address,
                 contract_address,
@@ -283,11 +386,24 @@ impl LibmdbxWriter {
                 self.write_searcher_contract_info(searcher_contract, *searcher_info)?;
                 "searchercontractinfo"
             }
+            WriterMessage::BundleOverride { bundle_override, reply } => {
+                let _ = reply.send(self.append_bundle_override(bundle_override));
+                "bundleoverride"
+            }
+            WriterMessage::SuppressionChange { change, reply } => {
+                let _ = reply.send(self.append_to_audit_log::<Suppressions>(change, |_| Ok(())));
+                "suppressionchange"
+            }
             WriterMessage::Init(init, not) => {
                 init.write_data(self.db.clone())?;
                 not.notify_one();
                 "init"
             }
+            WriterMessage::Flush { reply } => {
+                self.insert_remaining();
+                let _ = reply.send(Ok(()));
+                "flush"
+            }
         };
         // Use recv_time to instrument the overall clearing duration
         self.metrics
@@ -406,29 +522,108 @@ impl LibmdbxWriter {
         block_number: u64,
         block: MevBlock,
         mev: Vec<Bundle>,
+        cost: Option<BlockCostRecorder>,
     ) -> eyre::Result<()> {
-        let data =
-            MevBlocksData::new(block_number, MevBlockWithClassified { block, mev }).into_key_val();
-        let (key, value) = Self::convert_into_save_bytes(data);
+        self.update_order_flow(block_number, BlockOrderFlow::new(&block, &mev))?;
+        let checksum = BlockChecksum::new(&block, &mev)?;
+        let block = MevBlockWithClassified { block, mev };
 
-        let entry = self.insert_queue.entry(Tables::MevBlocks).or_default();
-        entry.push((key.to_vec(), value));
+        let data = [MevBlocksData::new(block_number, block.clone())];
+        let (key, value) = Self::convert_into_save_bytes(data[0].into_key_val());
+        add_block_db_bytes(cost.as_ref(), key.len() + value.len());
 
-        if entry.len() > CLEAR_AM {
-            let data = std::mem::take(entry);
-            self.insert_batched_data::<MevBlocks>(data)?;
+        // the block and everything derived from it are written in one
+        // transaction, so readers never see one without the others
+        let start_time = Instant::now();
+        let res = self.db.try_update_db(|tx| {
+            tx.put_bytes::<MevBlocks>(&key, value)?;
+            tx.put::<BlockChecksums>(block_number, checksum)?;
+            refresh_mev_receipts(tx, &block)?;
+            refresh_searcher_stats(tx, &block)
+        });
+        self.metrics
+            .observe_write_latency(MevBlocks::NAME, start_time.elapsed());
+
+        res
+    }
+
+    /// Records the order flow of the block and updates the builder and
+    /// searcher summaries. If the block was recorded before, its previous
+    /// contribution to the summaries is removed first.
+    #[instrument(target = "libmdbx_read_write::update_order_flow", skip_all, level = "warn")]
+    fn update_order_flow(&self, block_number: u64, flow: BlockOrderFlow) -> eyre::Result<()> {
+        let tx = self.db.ro_tx()?;
+        let previous = tx.get::<BlockOrderFlows>(block_number)?;
+
+        let mut payments: FastHashMap<Address, BuilderProposerPayments> = FastHashMap::default();
+        let mut searchers: FastHashMap<Address, SearcherOrderFlow> = FastHashMap::default();
+
+        for (block_flow, add) in previous.iter().map(|p| (p, false)).chain([(&flow, true)]) {
+            let builder = match payments.entry(block_flow.builder) {
+                Entry::Occupied(o) => o.into_mut(),
+                Entry::Vacant(v) => v.insert(
+                    tx.get::<BuilderPayments>(block_flow.builder)?
+                        .unwrap_or_default(),
+                ),
+            };
+            if add {
+                builder.add_block(block_flow);
+            } else {
+                builder.remove_block(block_flow);
+            }
+
+            for count in &block_flow.searcher_bundles {
+                let searcher = match searchers.entry(count.searcher) {
+                    Entry::Occupied(o) => o.into_mut(),
+                    Entry::Vacant(v) => v.insert(
+                        tx.get::<SearcherOrderFlows>(count.searcher)?
+                            .unwrap_or_default(),
+                    ),
+                };
+                if add {
+                    searcher.add_bundles(block_flow.builder, count.bundles);
+                } else {
+                    searcher.remove_bundles(block_flow.builder, count.bundles);
+                }
+            }
         }
+        drop(tx);
+
+        self.instrumented_write::<BlockOrderFlows, BlockOrderFlowsData>(&[
+            BlockOrderFlowsData::new(block_number, flow),
+        ])
+        .expect("libmdbx write failure");
+        self.instrumented_write::<BuilderPayments, BuilderPaymentsData>(
+            &payments
+                .into_iter()
+                .map(|(builder, payments)| BuilderPaymentsData::new(builder, payments))
+                .collect_vec(),
+        )
+        .expect("libmdbx write failure");
+        self.instrumented_write::<SearcherOrderFlows, SearcherOrderFlowsData>(
+            &searchers
+                .into_iter()
+                .map(|(searcher, flow)| SearcherOrderFlowsData::new(searcher, flow))
+                .collect_vec(),
+        )
+        .expect("libmdbx write failure");
 
         Ok(())
     }
 
     #[instrument(target = "libmdbx_read_write::write_dex_quotes", skip_all, level = "warn")]
-    fn write_dex_quotes(&mut self, block_num: u64, quotes: Option<DexQuotes>) -> eyre::Result<()> {
+    fn write_dex_quotes(
+        &mut self,
+        block_num: u64,
+        quotes: Option<DexQuotes>,
+        cost: Option<BlockCostRecorder>,
+    ) -> eyre::Result<()> {
         if let Some(quotes) = quotes {
             self.init_state_updating(block_num, DEX_PRICE_FLAG)
                 .expect("libmdbx write failure");
 
             let entry = self.insert_queue.entry(Tables::DexPrice).or_default();
+            let mut bytes = 0;
 
             quotes
                 .0
@@ -445,6 +640,7 @@ impl LibmdbxWriter {
                 .for_each(|data| {
                     let data = data.into_key_val();
                     let (key, value) = Self::convert_into_save_bytes(data);
+                    bytes += key.len() + value.len();
                     entry.push((key.to_vec(), value));
                 });
 
@@ -452,6 +648,7 @@ impl LibmdbxWriter {
                 let data = std::mem::take(entry);
                 self.insert_batched_data::<DexPrice>(data)?;
             }
+            add_block_db_bytes(cost.as_ref(), bytes);
         }
 
         Ok(())
@@ -515,9 +712,15 @@ impl LibmdbxWriter {
     }
 
     #[instrument(target = "libmdbx_read_write::save_traces", skip_all, level = "warn")]
-    fn save_traces(&mut self, block: u64, traces: Vec<TxTrace>) -> eyre::Result<()> {
+    fn save_traces(
+        &mut self,
+        block: u64,
+        traces: Vec<TxTrace>,
+        cost: Option<BlockCostRecorder>,
+    ) -> eyre::Result<()> {
         let data = TxTracesData::new(block, TxTracesInner { traces: Some(traces) }).into_key_val();
         let (key, value) = Self::convert_into_save_bytes(data);
+        add_block_db_bytes(cost.as_ref(), key.len() + value.len());
 
         let entry = self.insert_queue.entry(Tables::TxTraces).or_default();
         entry.push((key.to_vec(), value));
@@ -542,6 +745,152 @@ impl LibmdbxWriter {
         Ok(())
     }
 
+    #[instrument(target = "libmdbx_read_write::write_beacon_info", skip_all, level = "warn")]
+    fn write_beacon_info(
+        &self,
+        block_number: u64,
+        beacon_info: BeaconBlockInfo,
+    ) -> eyre::Result<()> {
+        let data = BeaconBlocksData::new(block_number, beacon_info);
+        self.instrumented_write::<BeaconBlocks, BeaconBlocksData>(&[data])
+            .expect("libmdbx write failure");
+        Ok(())
+    }
+
+    #[instrument(target = "libmdbx_read_write::write_block_fees", skip_all, level = "warn")]
+    fn write_block_fees(&self, block_number: u64, fees: BlockFees) -> eyre::Result<()> {
+        let data = FeeHistoryData::new(block_number, fees);
+        self.instrumented_write::<FeeHistory, FeeHistoryData>(&[data])
+            .expect("libmdbx write failure");
+        Ok(())
+    }
+
+    #[instrument(target = "libmdbx_read_write::write_pool_prices", skip_all, level = "warn")]
+    fn write_pool_prices(&self, block_number: u64, prices: BlockPoolPrices) -> eyre::Result<()> {
+        let data = PoolPriceHistoryData::new(block_number, prices);
+        self.instrumented_write::<PoolPriceHistory, PoolPriceHistoryData>(&[data])
+            .expect("libmdbx write failure");
+        Ok(())
+    }
+
+    #[instrument(target = "libmdbx_read_write::write_frontend_losses", skip_all, level = "warn")]
+    fn write_frontend_losses(
+        &self,
+        block_number: u64,
+        losses: BlockFrontendLosses,
+    ) -> eyre::Result<()> {
+        let data = FrontendLossesData::new(block_number, losses);
+        self.instrumented_write::<FrontendLosses, FrontendLossesData>(&[data])
+            .expect("libmdbx write failure");
+        Ok(())
+    }
+
+    #[instrument(target = "libmdbx_read_write::write_token_metadata", skip_all, level = "warn")]
+    fn write_token_metadata(&self, address: Address, metadata: TokenMetadata) -> eyre::Result<()> {
+        let data = TokenMetaData::new(address, metadata);
+        self.instrumented_write::<TokenMeta, TokenMetaData>(&[data])
+            .expect("libmdbx write failure");
+        Ok(())
+    }
+
+    /// Only the first funding of an address is kept, so the addresses that
+    /// already have one are skipped in the same transaction
+    #[instrument(target = "libmdbx_read_write::write_wallet_fundings", skip_all, level = "warn")]
+    fn write_wallet_fundings(&self, fundings: Vec<(Address, FundingEdge)>) -> eyre::Result<()> {
+        let start_time = Instant::now();
+        let res = self
+            .db
+            .update_db(|tx| {
+                for (address, edge) in fundings {
+                    if tx.get::<WalletFundings>(address)?.is_none() {
+                        tx.put::<WalletFundings>(address, edge)?;
+                    }
+                }
+
+                Ok::<(), DatabaseError>(())
+            })
+            .and_then(|res| res);
+
+        self.metrics
+            .observe_write_latency(WalletFundings::NAME, start_time.elapsed());
+        if let Err(error) = res.as_ref() {
+            self.metrics
+                .increment_write_errors(WalletFundings::NAME, error);
+        }
+
+        Ok(res?)
+    }
+
+    /// Takes the block's cost once the writes of its results queued before
+    /// have added their bytes to it
+    #[instrument(target = "libmdbx_read_write::write_block_cost", skip_all, level = "warn")]
+    fn write_block_cost(&self, block_number: u64, cost: BlockCostRecorder) -> eyre::Result<()> {
+        let data = BlockCostsData::new(block_number, cost.take());
+        self.instrumented_write::<BlockCosts, BlockCostsData>(&[data])?;
+        Ok(())
+    }
+
+    fn write_publish_checkpoint(&self, checkpoint: PublishCheckpoint) -> eyre::Result<()> {
+        let data = PublishCheckpointsData::new(checkpoint_key(&checkpoint.sink), checkpoint);
+        self.instrumented_write::<PublishCheckpoints, PublishCheckpointsData>(&[data])?;
+        Ok(())
+    }
+
+    /// Appended under the id after the last one, the writer is the only one
+    /// appending so two reloads never share an id
+    fn write_config_reload(&self, reload: ConfigReload) -> eyre::Result<()> {
+        let id = self.db.view_db(|tx| {
+            Ok(tx
+                .cursor_read::<ConfigReloads>()?
+                .last()?
+                .map_or(0, |(id, _)| id + 1))
+        })?;
+        self.instrumented_write::<ConfigReloads, ConfigReloadsData>(&[ConfigReloadsData::new(
+            id, reload,
+        )])?;
+        Ok(())
+    }
+
+    /// Appends the change to the audit log under the id after the last one.
+    /// The id is read and the change written in one transaction, so changes
+    /// appended by another process never share an id. `on_append` updates
+    /// what's derived from the log in the same transaction
+    fn append_to_audit_log<T>(
+        &self,
+        change: T::DecompressedValue,
+        on_append: impl FnOnce(&CompressedLibmdbxTx<RW>) -> eyre::Result<()>,
+    ) -> eyre::Result<u64>
+    where
+        T: CompressedTable<Key = u64>,
+        T::Value: From<T::DecompressedValue> + Into<T::DecompressedValue>,
+    {
+        let start_time = Instant::now();
+        let res = self.db.try_update_db(|tx| {
+            let id = tx.cursor_read::<T>()?.last()?.map_or(0, |(id, _)| id + 1);
+            tx.put::<T>(id, change)?;
+            on_append(tx)?;
+
+            Ok(id)
+        });
+        self.metrics
+            .observe_write_latency(T::NAME, Instant::now() - start_time);
+
+        res
+    }
+
+    /// The receipts and searcher statistics of the override's block are
+    /// recomputed with it applied, in the transaction that appends it
+    fn append_bundle_override(&self, bundle_override: BundleOverride) -> eyre::Result<u64> {
+        let block_number = bundle_override.block_number;
+        self.append_to_audit_log::<BundleOverrides>(bundle_override, |tx| {
+            if let Some(raw) = tx.get::<MevBlocks>(block_number)? {
+                refresh_mev_receipts(tx, &raw)?;
+                refresh_searcher_stats(tx, &raw)?;
+            }
+            Ok(())
+        })
+    }
+
     #[instrument(target = "libmdbx_read_write::init_state_updating", skip_all, level = "warn")]
     fn init_state_updating(&mut self, block: u64, flag: u16) -> eyre::Result<()> {
         let tx = self.db.ro_tx()?;
@@ -685,3 +1034,11 @@ impl Future for LibmdbxWriter {
         Poll::Pending
     }
 }
+
+/// Adds the bytes the block's results took up in the db to its cost, if it's
+/// being processed
+fn add_block_db_bytes(cost: Option<&BlockCostRecorder>, bytes: usize) {
+    if let Some(cost) = cost {
+        cost.record(|cost| cost.db_bytes_written += bytes as u64);
+    }
+}
@@ -4,6 +4,7 @@
 use std::{
     ffi::c_int,
     path::Path,
+    sync::{Arc, OnceLock},
     time::{Duration, Instant},
 };
 pub mod db_utils;
@@ -40,6 +41,7 @@ use tracing::info;
 use self::{
     cursor::CompressedCursor,
     types::{CompressedTable, LibmdbxData},
+    write_log::WriteLog,
 };
 
 pub mod implementation;
@@ -47,6 +49,7 @@ pub use implementation::compressed_wrappers::*;
 pub mod tables;
 pub mod types;
 pub mod utils;
+pub mod write_log;
 
 #[cfg(feature = "tests")]
 pub mod test_utils;
@@ -54,7 +57,7 @@ pub mod test_utils;
 const GIGABYTE: u64 = 1024 * 1024 * 1024;
 
 #[derive(Debug)]
-pub struct Libmdbx(DatabaseEnv);
+pub struct Libmdbx(DatabaseEnv, OnceLock<Arc<WriteLog>>);
 
 #[inline]
 pub(crate) fn mdbx_result(err_code: c_int) -> eyre::Result<bool> {
@@ -98,12 +101,41 @@ impl Libmdbx {
             ))
         })?;
 
-        let this = Self(db);
+        let this = Self(db, OnceLock::new());
         this.create_tables()?;
 
         Ok(this)
     }
 
+    /// Opens an existing database in read only mode. Nothing is created or
+    /// written, opening a path without a database fails
+    pub fn init_db_read_only<P: AsRef<Path>>(path: P) -> eyre::Result<Self> {
+        let rpath = path.as_ref();
+        if is_database_empty(rpath) {
+            eyre::bail!("no database at {}", rpath.display());
+        }
+        match check_db_version_file(rpath) {
+            Ok(_) | Err(DatabaseVersionError::MissingFile) => (),
+            Err(err) => return Err(err.into()),
+        }
+
+        let db = DatabaseEnv::open(
+            rpath,
+            DatabaseEnvKind::RO,
+            DatabaseArguments::new(ClientVersion::default()),
+        )?;
+
+        Ok(Self(db, OnceLock::new()))
+    }
+
+    /// Appends every write transaction committed from now on to the log, see
+    /// [`WriteLog`]
+    pub fn set_write_log(&self, log: WriteLog) -> eyre::Result<()> {
+        self.1
+            .set(Arc::new(log))
+            .map_err(|_| eyre::eyre!("write log already set"))
+    }
+
     /// Creates all the defined tables, opens if already created
     fn create_tables(&self) -> Result<(), DatabaseError> {
         let tx = CompressedLibmdbxTx::new_rw_tx(&self.0)?;
@@ -166,6 +198,25 @@ impl Libmdbx {
         Ok(res)
     }
 
+    /// Like [`Libmdbx::update_db`] but the writes are only committed when `f`
+    /// succeeds, on an error none of them are
+    pub fn try_update_db<F, R>(&self, f: F) -> eyre::Result<R>
+    where
+        F: FnOnce(&CompressedLibmdbxTx<RW>) -> eyre::Result<R>,
+    {
+        let tx = self.rw_tx()?;
+        match f(&tx) {
+            Ok(res) => {
+                tx.commit()?;
+                Ok(res)
+            }
+            Err(e) => {
+                tx.abort();
+                Err(e)
+            }
+        }
+    }
+
     /// Used when exporting db to parquet, automatically will deal with
     /// longstanding writes.
     pub fn export_db<F, R, T, I>(
@@ -231,7 +282,7 @@ impl Libmdbx {
 
     /// returns a RW transaction
     fn rw_tx(&self) -> Result<CompressedLibmdbxTx<RW>, DatabaseError> {
-        let tx = CompressedLibmdbxTx::new_rw_tx(&self.0)?;
+        let tx = CompressedLibmdbxTx::new_rw_tx(&self.0)?.with_write_log(self.1.get().cloned());
 
         Ok(tx)
     }
@@ -6,45 +6,76 @@ use std::{
     sync::Arc,
 };
 
+use brontes_libmdbx::RW;
 use brontes_types::{
     db::{
         address_metadata::{AddressMetadata, AddressMetadataRedefined},
         address_to_protocol_info::{ProtocolInfo, ProtocolInfoRedefined},
+        beacon::{BeaconBlockInfo, BeaconBlockInfoRedefined},
+        block_checksum::{BlockChecksum, BlockChecksumRedefined},
+        block_costs::{BlockCost, BlockCostRedefined},
         builder::{BuilderInfo, BuilderInfoRedefined},
+        bundle_tags::{BlockBundleTags, BlockBundleTagsRedefined},
         cex::{
             quotes::{CexPriceMap, CexPriceMapRedefined},
             trades::{CexTradeMap, CexTradeMapRedefined},
         },
         clickhouse_serde::tx_trace::tx_traces_inner,
+        cold_segment::{ColdSegment, ColdSegmentRedefined},
+        config_reload::{ConfigReload, ConfigReloadRedefined},
         dex::{DexKey, DexQuoteWithIndex, DexQuoteWithIndexRedefined},
+        fee_history::{BlockFees, BlockFeesRedefined},
+        frontend_losses::{BlockFrontendLosses, BlockFrontendLossesRedefined},
         initialized_state::{
             InitializedStateMeta, CEX_QUOTES_FLAG, CEX_TRADES_FLAG, DEX_PRICE_FLAG, META_FLAG,
             TRACE_FLAG,
         },
+        inspector_artifacts::{BlockInspectorArtifacts, BlockInspectorArtifactsRedefined},
         metadata::{BlockMetadataInner, BlockMetadataInnerRedefined},
         mev_block::{MevBlockWithClassified, MevBlockWithClassifiedRedefined},
+        mev_receipt::{MevReceipt, MevReceiptRedefined},
+        order_flow::{
+            BlockOrderFlow, BlockOrderFlowRedefined, BuilderProposerPayments,
+            BuilderProposerPaymentsRedefined, SearcherOrderFlow, SearcherOrderFlowRedefined,
+        },
+        overrides::{BundleOverride, BundleOverrideRedefined},
         pool_creation_block::{PoolsToAddresses, PoolsToAddressesRedefined},
+        pool_prices::{BlockPoolPrices, BlockPoolPricesRedefined},
+        publish_checkpoint::{PublishCheckpoint, PublishCheckpointRedefined},
+        repricing::{BlockRepricings, BlockRepricingsRedefined},
+        run_provenance::{RunProvenance, RunProvenanceRedefined},
+        scheduled_job::{JobRun, JobRunRedefined},
         searcher::{SearcherInfo, SearcherInfoRedefined},
+        searcher_stats::{
+            BlockSearcherStats, BlockSearcherStatsRedefined, MevRollup, MevRollupRedefined,
+            SearcherStats, SearcherStatsRedefined,
+        },
+        suppression::{SuppressionChange, SuppressionChangeRedefined},
         token_info::TokenInfo,
+        token_metadata::{TokenMetadata, TokenMetadataRedefined},
         traces::{TxTracesInner, TxTracesInnerRedefined},
-        traits::LibmdbxReader,
+        wallet_funding::{FundingEdge, FundingEdgeRedefined},
     },
     serde_utils::*,
     traits::TracingProvider,
 };
 use clickhouse::DbRow;
 use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
-use reth_db::table::Table;
+use reth_db::{
+    table::{Decode, Decompress, Table},
+    DatabaseError,
+};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+#[cfg(feature = "parquet")]
+use crate::parquet::ParquetExporter;
 use crate::{
     clickhouse::ClickhouseHandle,
-    libmdbx::{types::ReturnKV, utils::protocol_info, LibmdbxData, LibmdbxReadWriter},
-    parquet::ParquetExporter,
+    libmdbx::{types::ReturnKV, utils::protocol_info, Libmdbx, LibmdbxData, LibmdbxReadWriter},
 };
 mod const_sql;
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 //
 // use brontes_types::db::initialized_state::CEX_QUOTES_FLAG;
 //
@@ -54,11 +85,11 @@ use paste::paste;
 use reth_db::TableType;
 
 use super::{
-    initialize::LibmdbxInitializer, libmdbx_writer::WriterMessage, types::IntoTableKey,
-    CompressedTable,
+    implementation::compressed_wrappers::tx::CompressedLibmdbxTx, initialize::LibmdbxInitializer,
+    libmdbx_writer::WriterMessage, types::IntoTableKey, CompressedTable,
 };
 
-pub const NUM_TABLES: usize = 14;
+pub const NUM_TABLES: usize = 39;
 
 macro_rules! tables {
     ($($table:ident),*) => {
@@ -108,6 +139,83 @@ macro_rules! tables {
                 Ok(())
             }
 
+            /// Puts an entry read back from the write log. The key and value
+            /// are decoded with the table's codec first, which validates them
+            /// and upcasts rows of an older versioned layout, and are written
+            /// in the current one
+            pub(crate) fn put_logged(
+                &self,
+                tx: &CompressedLibmdbxTx<RW>,
+                key: &[u8],
+                value: &[u8],
+            ) -> eyre::Result<()> {
+                match self {
+                    $(
+                        Tables::$table => {
+                            type Value = <$table as Table>::Value;
+                            let key = <<$table as Table>::Key as Decode>::decode(key)?;
+                            let value = <Value as Decompress>::decompress(value)?;
+                            tx.put::<$table>(key, value.into())?;
+                        }
+                    ),*
+                }
+
+                Ok(())
+            }
+
+            /// Deletes an entry read back from the write log
+            pub(crate) fn delete_logged(
+                &self,
+                tx: &CompressedLibmdbxTx<RW>,
+                key: &[u8],
+            ) -> eyre::Result<()> {
+                match self {
+                    $(
+                        Tables::$table => {
+                            let key = <<$table as Table>::Key as Decode>::decode(key)?;
+                            tx.delete::<$table>(key, None)?;
+                        }
+                    ),*
+                }
+
+                Ok(())
+            }
+
+            /// Clears the table for a clear read back from the write log
+            pub(crate) fn clear_logged(&self, tx: &CompressedLibmdbxTx<RW>) -> eyre::Result<()> {
+                match self {
+                    $(
+                        Tables::$table => tx.clear::<$table>()?,
+                    ),*
+                }
+
+                Ok(())
+            }
+
+            /// Decodes the first and last entry of the table, so tables written
+            /// with an outdated layout are caught before a run reads them. Rows
+            /// are validated against the layout when decoded. A table that
+            /// doesn't exist yet is created by the next write and passes
+            pub fn check_layout(&self, db: &Libmdbx) -> eyre::Result<()> {
+                match self {
+                    $(
+                        Tables::$table => {
+                            db.view_db(|tx| {
+                                let mut cursor = match tx.new_cursor::<$table>() {
+                                    Ok(cursor) => cursor,
+                                    Err(DatabaseError::InitCursor(_)) => return Ok(()),
+                                    Err(e) => return Err(e.into()),
+                                };
+                                cursor.first()?;
+                                cursor.last()?;
+                                Ok(())
+                            })?;
+                        }
+                    ),*
+                }
+
+                Ok(())
+            }
         }
 
         impl Display for Tables {
@@ -276,7 +384,34 @@ impl Tables {
                     )
                     .await
             }
-            Tables::SearcherEOAs | Tables::SearcherContracts | Tables::InitializedState => Ok(()),
+            Tables::SearcherEOAs
+            | Tables::SearcherContracts
+            | Tables::InitializedState
+            | Tables::BeaconBlocks
+            | Tables::FeeHistory
+            | Tables::BlockOrderFlows
+            | Tables::BuilderPayments
+            | Tables::SearcherOrderFlows
+            | Tables::BundleOverrides
+            | Tables::BlockCosts
+            | Tables::BlockSearcherStatistics
+            | Tables::SearcherStatistics
+            | Tables::ColdSegments
+            | Tables::BundleTags
+            | Tables::InspectorArtifacts
+            | Tables::ScheduledJobRuns
+            | Tables::BlockChecksums
+            | Tables::BundleRepricings
+            | Tables::RunProvenances
+            | Tables::WalletFundings
+            | Tables::PoolPriceHistory
+            | Tables::MevReceipts
+            | Tables::Suppressions
+            | Tables::FrontendLosses
+            | Tables::TokenMeta
+            | Tables::ConfigReloads
+            | Tables::PublishCheckpoints
+            | Tables::MevRollups => Ok(()),
             _ => unimplemented!("'initialize_table' not implemented for {:?}", self),
         }
     }
@@ -361,12 +496,13 @@ impl Tables {
             .unwrap()
     }
 
+    #[cfg(feature = "parquet")]
     pub async fn export_to_parquet<DB>(
         &self,
         exporter: Arc<ParquetExporter<DB>>,
     ) -> eyre::Result<()>
     where
-        DB: LibmdbxReader,
+        DB: brontes_types::db::traits::LibmdbxReader,
     {
         match self {
             Self::AddressMeta => exporter.export_address_metadata().await,
@@ -537,7 +673,32 @@ tables!(
     SearcherEOAs,
     SearcherContracts,
     InitializedState,
-    CexTrades
+    CexTrades,
+    BeaconBlocks,
+    FeeHistory,
+    BlockOrderFlows,
+    BuilderPayments,
+    SearcherOrderFlows,
+    BundleOverrides,
+    BlockCosts,
+    BlockSearcherStatistics,
+    SearcherStatistics,
+    ColdSegments,
+    BundleTags,
+    InspectorArtifacts,
+    ScheduledJobRuns,
+    BlockChecksums,
+    BundleRepricings,
+    RunProvenances,
+    WalletFundings,
+    PoolPriceHistory,
+    MevReceipts,
+    Suppressions,
+    FrontendLosses,
+    TokenMeta,
+    ConfigReloads,
+    PublishCheckpoints,
+    MevRollups
 );
 
 /// Must be in this order when defining
@@ -1031,3 +1192,456 @@ compressed_table!(
         }
     }
 );
+
+compressed_table!(
+    Table BeaconBlocks {
+        Data {
+            key: u64,
+            value: BeaconBlockInfo,
+            compressed_value: BeaconBlockInfoRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table FeeHistory {
+        Data {
+            key: u64,
+            value: BlockFees,
+            compressed_value: BlockFeesRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BlockOrderFlows {
+        Data {
+            key: u64,
+            value: BlockOrderFlow,
+            compressed_value: BlockOrderFlowRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BuilderPayments {
+        Data {
+            key: Address,
+            value: BuilderProposerPayments,
+            compressed_value: BuilderProposerPaymentsRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table SearcherOrderFlows {
+        Data {
+            key: Address,
+            value: SearcherOrderFlow,
+            compressed_value: SearcherOrderFlowRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BundleOverrides {
+        Data {
+            key: u64,
+            value: BundleOverride,
+            compressed_value: BundleOverrideRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BlockCosts {
+        Data {
+            key: u64,
+            value: BlockCost,
+            compressed_value: BlockCostRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BlockSearcherStatistics {
+        Data {
+            key: u64,
+            value: BlockSearcherStats,
+            compressed_value: BlockSearcherStatsRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table SearcherStatistics {
+        Data {
+            key: Address,
+            value: SearcherStats,
+            compressed_value: SearcherStatsRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table ColdSegments {
+        Data {
+            key: u64,
+            value: ColdSegment,
+            compressed_value: ColdSegmentRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BundleTags {
+        Data {
+            key: u64,
+            value: BlockBundleTags,
+            compressed_value: BlockBundleTagsRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table InspectorArtifacts {
+        Data {
+            key: u64,
+            value: BlockInspectorArtifacts,
+            compressed_value: BlockInspectorArtifactsRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table ScheduledJobRuns {
+        Data {
+            key: u64,
+            value: JobRun,
+            compressed_value: JobRunRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BlockChecksums {
+        Data {
+            key: u64,
+            value: BlockChecksum,
+            compressed_value: BlockChecksumRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table BundleRepricings {
+        Data {
+            key: u64,
+            value: BlockRepricings,
+            compressed_value: BlockRepricingsRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table RunProvenances {
+        Data {
+            key: u64,
+            value: RunProvenance,
+            compressed_value: RunProvenanceRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table WalletFundings {
+        Data {
+            #[serde(with = "address_string")]
+            key: Address,
+            value: FundingEdge,
+            compressed_value: FundingEdgeRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table PoolPriceHistory {
+        Data {
+            key: u64,
+            value: BlockPoolPrices,
+            compressed_value: BlockPoolPricesRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table MevReceipts {
+        Data {
+            #[serde(with = "txhash")]
+            key: B256,
+            value: MevReceipt,
+            compressed_value: MevReceiptRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table Suppressions {
+        Data {
+            key: u64,
+            value: SuppressionChange,
+            compressed_value: SuppressionChangeRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table FrontendLosses {
+        Data {
+            key: u64,
+            value: BlockFrontendLosses,
+            compressed_value: BlockFrontendLossesRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table TokenMeta {
+        Data {
+            #[serde(with = "address_string")]
+            key: Address,
+            value: TokenMetadata,
+            compressed_value: TokenMetadataRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table ConfigReloads {
+        Data {
+            key: u64,
+            value: ConfigReload,
+            compressed_value: ConfigReloadRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table PublishCheckpoints {
+        Data {
+            key: u64,
+            value: PublishCheckpoint,
+            compressed_value: PublishCheckpointRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
+
+compressed_table!(
+    Table MevRollups {
+        Data {
+            key: u64,
+            value: MevRollup,
+            compressed_value: MevRollupRedefined
+        },
+        Init {
+            init_size: None,
+            init_method: Other,
+            http_endpoint: None
+        },
+        CLI {
+            can_insert: False
+        }
+    }
+);
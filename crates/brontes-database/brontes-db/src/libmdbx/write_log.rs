@@ -0,0 +1,585 @@
+//! Append-only log of all writes committed to libmdbx.
+//!
+//! Every write transaction of a [`Libmdbx`] with a log set records its puts,
+//! deletes and clears, and appends them as one record that is synced to the
+//! log before the transaction commits. Once the commit succeeded a commit
+//! marker is appended after the record, a record without one belongs to a
+//! transaction that failed to commit and is skipped. The tables can therefore
+//! be rebuilt
+//! from the log with [`replay_write_log`] after the database got corrupted,
+//! or replayed into a fresh database after a migration instead of re-running
+//! the whole pipeline. This covers everything written through the database,
+//! the writer's batches as well as table initialization and maintenance jobs.
+//!
+//! The log starts with a header holding the stored layout versions the log
+//! was written with, as json, followed by one record per transaction:
+//!
+//! ```text
+//! header := magic | versions_len: u32 | versions
+//! record := ops: u32 | op* | commit?
+//! commit := 0: u32
+//! op     := kind: u8 | table_name_len: u8 | table_name | key? | value?
+//! key    := key_len: u32 | key
+//! value  := value_len: u32 | value
+//! ```
+//!
+//! Puts carry a key and a value, deletes a key and clears neither. Keys and
+//! values are stored in the encoding of their table and decoded with its
+//! codec on replay, so rows logged in an older versioned layout are upcast
+//! and rewritten in the current one. A log written by a release with newer
+//! layouts is rejected. A crash while appending can leave a partial record
+//! at the end of the log, which is truncated the next time the log is opened
+//! and skipped on replay. A crash between the commit and its marker loses the
+//! record of that one transaction from the log, its writes are still in the
+//! database.
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{BufReader, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use brontes_libmdbx::RW;
+use brontes_types::db::{
+    bundle_versions::layout_versions, searcher_versions::SEARCHER_INFO_VERSION,
+};
+use eyre::{eyre, WrapErr};
+use parking_lot::{Mutex, MutexGuard};
+use reth_db::DatabaseError;
+use tracing::{error, info, warn};
+
+use super::{
+    implementation::compressed_wrappers::tx::CompressedLibmdbxTx, tables::Tables, Libmdbx,
+};
+
+const MAGIC: &[u8; 8] = b"BRNTWAL2";
+
+/// Written after the record of a committed transaction. Records always hold at
+/// least one op, so it can't be mistaken for the start of a record
+const COMMITTED: u32 = 0;
+
+const PUT: u8 = 0;
+const DELETE: u8 = 1;
+const CLEAR: u8 = 2;
+
+/// The stored layout versions written in the header of the log
+pub fn log_layout_versions() -> BTreeMap<String, u16> {
+    let mut versions = layout_versions();
+    versions.insert("searcher_info".to_string(), SEARCHER_INFO_VERSION);
+    versions
+}
+
+/// A write read back from the log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogOp {
+    Put { table: Tables, key: Vec<u8>, value: Vec<u8> },
+    Delete { table: Tables, key: Vec<u8> },
+    Clear { table: Tables },
+}
+
+impl LogOp {
+    pub fn table(&self) -> Tables {
+        match self {
+            LogOp::Put { table, .. } | LogOp::Delete { table, .. } | LogOp::Clear { table } => {
+                *table
+            }
+        }
+    }
+
+    fn apply(&self, tx: &CompressedLibmdbxTx<RW>) -> eyre::Result<()> {
+        match self {
+            LogOp::Put { table, key, value } => table.put_logged(tx, key, value),
+            LogOp::Delete { table, key } => table.delete_logged(tx, key),
+            LogOp::Clear { table } => table.clear_logged(tx),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct WriteLog {
+    path: PathBuf,
+    /// Held from appending a record until its transaction committed, so that
+    /// the commit marker directly follows the record
+    file: Mutex<File>,
+}
+
+impl WriteLog {
+    /// Opens the log for appending, creating it if it doesn't exist. A partial
+    /// record left at the end of the log by a crash is truncated. A log
+    /// written with other layout versions than the current ones is rejected,
+    /// it has to be rotated before the run.
+    pub fn open(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(&path)
+            .wrap_err_with(|| format!("failed to open write log {}", path.display()))?;
+
+        if file.metadata()?.len() == 0 {
+            let versions = serde_json::to_vec(&log_layout_versions())?;
+            file.write_all(MAGIC)?;
+            file.write_all(&(versions.len() as u32).to_le_bytes())?;
+            file.write_all(&versions)?;
+            file.sync_all()?;
+        } else {
+            let mut reader = WriteLogReader::new(BufReader::new(file.try_clone()?))?;
+            if reader.versions() != &log_layout_versions() {
+                return Err(eyre!(
+                    "write log {} was written with the layout versions {:?}, rotate it before \
+                     appending with {:?}",
+                    path.display(),
+                    reader.versions(),
+                    log_layout_versions()
+                ))
+            }
+            while reader.next_record()?.is_some() {}
+
+            let valid_len = reader.position;
+            if valid_len < file.metadata()?.len() {
+                warn!(path = %path.display(), valid_len, "truncating partial record at the end of the write log");
+                file.set_len(valid_len)?;
+                file.sync_all()?;
+            }
+        }
+        file.seek(SeekFrom::End(0))?;
+
+        Ok(Self { path, file: Mutex::new(file) })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends the encoded ops of a transaction as one record and syncs it to
+    /// disk. The log stays locked until the returned record is marked
+    /// committed or dropped
+    fn append(&self, ops: u32, buf: &[u8]) -> std::io::Result<PendingRecord<'_>> {
+        let mut record = Vec::with_capacity(4 + buf.len());
+        record.extend_from_slice(&ops.to_le_bytes());
+        record.extend_from_slice(buf);
+
+        let mut file = self.file.lock();
+        file.write_all(&record)?;
+        file.sync_data()?;
+
+        Ok(PendingRecord { path: &self.path, file })
+    }
+}
+
+/// A record appended to the log whose transaction hasn't committed yet. If it
+/// is dropped without being marked committed, it's skipped on replay
+pub(crate) struct PendingRecord<'a> {
+    path: &'a Path,
+    file: MutexGuard<'a, File>,
+}
+
+impl PendingRecord<'_> {
+    /// Appends the commit marker of the record. The transaction already
+    /// committed, so a failure is only logged, the record is then skipped on
+    /// replay
+    pub(crate) fn committed(mut self) {
+        let res = self
+            .file
+            .write_all(&COMMITTED.to_le_bytes())
+            .and_then(|_| self.file.sync_data());
+        if let Err(e) = res {
+            error!(path = %self.path.display(), error = %e, "failed to mark a committed write in the write log");
+        }
+    }
+}
+
+/// The writes of a single transaction, appended to the log when it commits
+pub(crate) struct TxWriteLog {
+    log:     Arc<WriteLog>,
+    pending: Mutex<(u32, Vec<u8>)>,
+}
+
+impl TxWriteLog {
+    pub(crate) fn new(log: Arc<WriteLog>) -> Self {
+        Self { log, pending: Mutex::new((0, Vec::new())) }
+    }
+
+    pub(crate) fn put(&self, table: &str, key: &[u8], value: &[u8]) {
+        self.record(PUT, table, Some(key), Some(value))
+    }
+
+    pub(crate) fn delete(&self, table: &str, key: &[u8]) {
+        self.record(DELETE, table, Some(key), None)
+    }
+
+    pub(crate) fn clear(&self, table: &str) {
+        self.record(CLEAR, table, None, None)
+    }
+
+    fn record(&self, kind: u8, table: &str, key: Option<&[u8]>, value: Option<&[u8]>) {
+        let mut pending = self.pending.lock();
+        let (ops, buf) = &mut *pending;
+        *ops += 1;
+
+        buf.push(kind);
+        buf.push(table.len() as u8);
+        buf.extend_from_slice(table.as_bytes());
+        for bytes in key.into_iter().chain(value) {
+            buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            buf.extend_from_slice(bytes);
+        }
+    }
+
+    /// Appends the recorded writes to the log, to be marked committed once the
+    /// transaction committed. A transaction whose writes couldn't be logged
+    /// must not commit, so the failure is returned as a commit error. Returns
+    /// `None` if the transaction didn't write anything
+    pub(crate) fn append(&self) -> Result<Option<PendingRecord<'_>>, DatabaseError> {
+        let pending = self.pending.lock();
+        let (ops, buf) = &*pending;
+        if *ops == 0 {
+            return Ok(None)
+        }
+
+        self.log.append(*ops, buf).map(Some).map_err(|e| {
+            error!(path = %self.log.path().display(), error = %e, "failed to append to the write log");
+            DatabaseError::Commit(e.raw_os_error().unwrap_or(-1))
+        })
+    }
+}
+
+pub struct WriteLogReader<R: Read> {
+    reader:      R,
+    versions:    BTreeMap<String, u16>,
+    /// End of the last complete record or commit marker
+    position:    u64,
+    /// Op count of the next record, read while looking for the commit marker
+    /// of the previous one
+    next_ops:    Option<u32>,
+    /// Records skipped as their transaction didn't commit
+    uncommitted: u64,
+}
+
+impl WriteLogReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .wrap_err_with(|| format!("failed to open write log {}", path.display()))?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read> WriteLogReader<R> {
+    pub fn new(mut reader: R) -> eyre::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader
+            .read_exact(&mut magic)
+            .wrap_err("write log is missing its header")?;
+        if &magic != MAGIC {
+            return Err(eyre!("not a brontes write log, or one of an unsupported format"))
+        }
+
+        let mut len = [0u8; 4];
+        reader
+            .read_exact(&mut len)
+            .wrap_err("write log is missing its header")?;
+        let len = u32::from_le_bytes(len);
+        let mut versions = vec![0u8; len as usize];
+        reader
+            .read_exact(&mut versions)
+            .wrap_err("write log is missing its header")?;
+        let versions =
+            serde_json::from_slice(&versions).wrap_err("invalid write log layout versions")?;
+
+        Ok(Self {
+            reader,
+            versions,
+            position: (MAGIC.len() + 4) as u64 + len as u64,
+            next_ops: None,
+            uncommitted: 0,
+        })
+    }
+
+    /// The stored layout versions the log was written with
+    pub fn versions(&self) -> &BTreeMap<String, u16> {
+        &self.versions
+    }
+
+    /// Records skipped so far as their transaction didn't commit
+    pub fn uncommitted(&self) -> u64 {
+        self.uncommitted
+    }
+
+    /// Returns the writes of the next committed record, or `None` at the end
+    /// of the log. Records without a commit marker are skipped. A partial
+    /// record at the end of the log is treated as the end of the log.
+    pub fn next_record(&mut self) -> eyre::Result<Option<Vec<LogOp>>> {
+        loop {
+            let ops = match self.read_record() {
+                Ok((ops, len)) => {
+                    self.position += len;
+                    ops
+                }
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+
+            match self.read_u32() {
+                Ok(COMMITTED) => {
+                    self.position += 4;
+                    return Ok(Some(ops))
+                }
+                Ok(next_ops) => self.next_ops = Some(next_ops),
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => {}
+                Err(e) => return Err(e.into()),
+            }
+            self.uncommitted += 1;
+        }
+    }
+
+    fn read_record(&mut self) -> std::io::Result<(Vec<LogOp>, u64)> {
+        let count = match self.next_ops.take() {
+            Some(count) => count,
+            None => self.read_u32()?,
+        };
+        let mut len = 4u64;
+        let mut ops = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let mut head = [0u8; 2];
+            self.reader.read_exact(&mut head)?;
+            let [kind, name_len] = head;
+            let name = self.read_bytes(name_len as usize)?;
+            let table = std::str::from_utf8(&name)
+                .ok()
+                .and_then(|name| Tables::from_str(name).ok())
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("unknown table {}", String::from_utf8_lossy(&name)),
+                    )
+                })?;
+            len += 2 + name.len() as u64;
+
+            let op = match kind {
+                PUT => {
+                    let key = self.read_field(&mut len)?;
+                    let value = self.read_field(&mut len)?;
+                    LogOp::Put { table, key, value }
+                }
+                DELETE => LogOp::Delete { table, key: self.read_field(&mut len)? },
+                CLEAR => LogOp::Clear { table },
+                kind => {
+                    return Err(std::io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("unknown write log op {kind}"),
+                    ))
+                }
+            };
+            ops.push(op);
+        }
+
+        Ok((ops, len))
+    }
+
+    fn read_field(&mut self, len: &mut u64) -> std::io::Result<Vec<u8>> {
+        let field_len = self.read_u32()? as usize;
+        *len += 4 + field_len as u64;
+        self.read_bytes(field_len)
+    }
+
+    fn read_u32(&mut self) -> std::io::Result<u32> {
+        let mut buf = [0u8; 4];
+        self.reader.read_exact(&mut buf)?;
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn read_bytes(&mut self, len: usize) -> std::io::Result<Vec<u8>> {
+        let mut buf = vec![0u8; len];
+        self.reader.read_exact(&mut buf)?;
+        Ok(buf)
+    }
+}
+
+/// Applies all records of the log to the database in order, each in its own
+/// transaction like it was committed. If `tables` is given, only writes to
+/// these tables are replayed. Returns the number of writes that were applied.
+pub fn replay_write_log(
+    db: &Libmdbx,
+    path: impl AsRef<Path>,
+    tables: Option<&[Tables]>,
+) -> eyre::Result<u64> {
+    let mut reader = WriteLogReader::open(path)?;
+    let current = log_layout_versions();
+    for (layout, version) in reader.versions() {
+        if current.get(layout).is_some_and(|current| version > current) {
+            return Err(eyre!(
+                "write log has version {version} of the {layout} layout, newer than this release's"
+            ))
+        }
+    }
+
+    let mut written = 0u64;
+    while let Some(ops) = reader.next_record()? {
+        let ops = ops
+            .into_iter()
+            .filter(|op| tables.map_or(true, |tables| tables.contains(&op.table())))
+            .collect::<Vec<_>>();
+        if ops.is_empty() {
+            continue
+        }
+
+        db.try_update_db(|tx| {
+            for op in &ops {
+                op.apply(tx)?;
+            }
+            Ok::<_, eyre::Report>(())
+        })?;
+        written += ops.len() as u64;
+    }
+
+    info!(written, uncommitted = reader.uncommitted(), "replayed write log");
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("brontes-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_append_and_truncate_partial_record() {
+        let path = temp_log("write-log");
+
+        let log = Arc::new(WriteLog::open(&path).unwrap());
+        let tx = TxWriteLog::new(log.clone());
+        tx.put(Tables::MevBlocks.name(), &[1, 2], &[3; 10]);
+        tx.delete(Tables::FeeHistory.name(), &[4]);
+        tx.append().unwrap().unwrap().committed();
+        // a transaction that failed to commit leaves its record unmarked
+        let tx = TxWriteLog::new(log.clone());
+        tx.put(Tables::MevBlocks.name(), &[6], &[7]);
+        drop(tx.append().unwrap());
+        let tx = TxWriteLog::new(log.clone());
+        tx.clear(Tables::SearcherStatistics.name());
+        tx.append().unwrap().unwrap().committed();
+        // nothing is appended for a transaction without writes
+        assert!(TxWriteLog::new(log.clone()).append().unwrap().is_none());
+        drop(log);
+
+        // simulate a crash in the middle of appending a record
+        let len = std::fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[1, 0, 0, 0, PUT, 9, b'M', b'e', b'v'])
+            .unwrap();
+        drop(file);
+
+        let log = Arc::new(WriteLog::open(&path).unwrap());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), len);
+        let tx = TxWriteLog::new(log);
+        tx.put(Tables::TxTraces.name(), &[5], &[]);
+        tx.append().unwrap().unwrap().committed();
+
+        let mut reader = WriteLogReader::open(&path).unwrap();
+        assert_eq!(reader.versions(), &log_layout_versions());
+        assert_eq!(
+            reader.next_record().unwrap(),
+            Some(vec![
+                LogOp::Put { table: Tables::MevBlocks, key: vec![1, 2], value: vec![3; 10] },
+                LogOp::Delete { table: Tables::FeeHistory, key: vec![4] },
+            ])
+        );
+        assert_eq!(
+            reader.next_record().unwrap(),
+            Some(vec![LogOp::Clear { table: Tables::SearcherStatistics }])
+        );
+        assert_eq!(
+            reader.next_record().unwrap(),
+            Some(vec![LogOp::Put { table: Tables::TxTraces, key: vec![5], value: vec![] }])
+        );
+        assert_eq!(reader.next_record().unwrap(), None);
+        assert_eq!(reader.uncommitted(), 1);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_replays_committed_writes() {
+        use alloy_primitives::Address;
+        use brontes_types::db::token_info::TokenInfo;
+
+        use crate::libmdbx::tables::TokenDecimals;
+
+        let path = temp_log("write-log-replay");
+        let source_dir =
+            std::env::temp_dir().join(format!("brontes-replay-src-{}", std::process::id()));
+        let target_dir =
+            std::env::temp_dir().join(format!("brontes-replay-dst-{}", std::process::id()));
+
+        let source = Libmdbx::init_db(&source_dir, None).unwrap();
+        source
+            .set_write_log(WriteLog::open(&path).unwrap())
+            .unwrap();
+        let token = |byte| Address::with_last_byte(byte);
+        source
+            .update_db(|tx| {
+                tx.put::<TokenDecimals>(token(1), TokenInfo::new(18, "WETH".to_string()))?;
+                tx.put::<TokenDecimals>(token(2), TokenInfo::new(6, "USDC".to_string()))?;
+                tx.put::<TokenDecimals>(token(3), TokenInfo::new(8, "WBTC".to_string()))
+            })
+            .unwrap()
+            .unwrap();
+        source
+            .update_db(|tx| tx.delete::<TokenDecimals>(token(2), None))
+            .unwrap()
+            .unwrap();
+        // an aborted transaction isn't logged
+        source
+            .try_update_db(|tx| {
+                tx.clear::<TokenDecimals>()?;
+                Err::<(), _>(eyre!("abort"))
+            })
+            .unwrap_err();
+
+        let target = Libmdbx::init_db(&target_dir, None).unwrap();
+        assert_eq!(replay_write_log(&target, &path, None).unwrap(), 4);
+        let stored = target
+            .view_db(|tx| Ok([1, 2, 3].map(|byte| tx.get::<TokenDecimals>(token(byte)).unwrap())))
+            .unwrap();
+        assert_eq!(
+            stored,
+            [
+                Some(TokenInfo::new(18, "WETH".to_string())),
+                None,
+                Some(TokenInfo::new(8, "WBTC".to_string()))
+            ]
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_dir_all(&source_dir).unwrap();
+        std::fs::remove_dir_all(&target_dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_log_of_other_layout_versions() {
+        let path = temp_log("write-log-versions");
+
+        let mut versions = log_layout_versions();
+        *versions.get_mut("mev_block").unwrap() += 1;
+        let versions = serde_json::to_vec(&versions).unwrap();
+        let mut file = File::create(&path).unwrap();
+        file.write_all(MAGIC).unwrap();
+        file.write_all(&(versions.len() as u32).to_le_bytes())
+            .unwrap();
+        file.write_all(&versions).unwrap();
+        drop(file);
+
+        assert!(WriteLog::open(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+use alloy_primitives::{Address, B256};
+use brontes_types::{
+    db::{
+        export_filter::{for_each_participant, hash_address},
+        mev_block::MevBlockWithClassified,
+    },
+    mev::{Bundle, MevBlock},
+};
+
+/// Replaces the addresses of searchers, builders, proposers and victims with
+/// a salted hash before data is published. Pool and token addresses are left
+/// as is, as they are public protocol data.
+///
+/// The hash is stable for a given salt, so activity of the same address can
+/// still be linked across the published data set.
+#[derive(Debug, Clone, Copy)]
+pub struct AddressAnonymizer {
+    salt: B256,
+}
+
+impl AddressAnonymizer {
+    pub fn new(salt: B256) -> Self {
+        Self { salt }
+    }
+
+    pub fn hash(&self, address: Address) -> Address {
+        hash_address(self.salt, address)
+    }
+
+    pub fn anonymize_mev_block(&self, mev_block: &mut MevBlockWithClassified) {
+        self.anonymize_block(&mut mev_block.block);
+        mev_block
+            .mev
+            .iter_mut()
+            .for_each(|bundle| self.anonymize_bundle(bundle));
+    }
+
+    fn anonymize_block(&self, block: &mut MevBlock) {
+        block.builder_address = self.hash(block.builder_address);
+        block.proposer_fee_recipient = block.proposer_fee_recipient.map(|a| self.hash(a));
+    }
+
+    fn anonymize_bundle(&self, bundle: &mut Bundle) {
+        // names are labels of the address, so would defeat the hashing
+        bundle
+            .header
+            .balance_deltas
+            .iter_mut()
+            .flat_map(|tx| tx.address_deltas.iter_mut())
+            .for_each(|deltas| deltas.name = None);
+
+        for_each_participant(bundle, |address| *address = self.hash(*address));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::mev::BundleHeader;
+
+    use super::*;
+
+    #[test]
+    fn test_address_hashing() {
+        let address = Address::repeat_byte(1);
+        let anonymizer = AddressAnonymizer::new(B256::repeat_byte(2));
+
+        assert_ne!(anonymizer.hash(address), address);
+        assert_eq!(anonymizer.hash(address), anonymizer.hash(address));
+        assert_ne!(
+            anonymizer.hash(address),
+            AddressAnonymizer::new(B256::repeat_byte(3)).hash(address)
+        );
+
+        let mut bundle = Bundle {
+            header: BundleHeader {
+                eoa: address,
+                mev_contract: Some(address),
+                ..Default::default()
+            },
+            data:   Default::default(),
+        };
+        anonymizer.anonymize_bundle(&mut bundle);
+        assert_eq!(bundle.header.eoa, anonymizer.hash(address));
+        assert_eq!(bundle.header.mev_contract, Some(anonymizer.hash(address)));
+    }
+}
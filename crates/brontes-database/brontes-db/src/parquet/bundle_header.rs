@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use arrow::{
-    array::{StringArray, StringBuilder},
+    array::{BooleanArray, StringArray, StringBuilder},
     datatypes::{DataType, Field, Schema},
     error::ArrowError,
     record_batch::RecordBatch,
@@ -10,6 +10,7 @@ use brontes_types::mev::BundleHeader;
 
 use super::utils::{
     build_float64_array, build_record_batch, build_string_array, build_uint64_array,
+    get_list_string_array_from_owned,
 };
 
 pub fn bundle_headers_to_record_batch(
@@ -25,6 +26,12 @@ pub fn bundle_headers_to_record_batch(
             .map(|bh| bh.tx_hash.to_string())
             .collect(),
     );
+    let bundle_id_array = build_string_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.bundle_id.to_string())
+            .collect(),
+    );
     let eoa_array =
         build_string_array(bundle_headers.iter().map(|bh| bh.eoa.to_string()).collect());
     let mev_contract_array = get_mev_contract_array(&bundle_headers);
@@ -39,16 +46,120 @@ pub fn bundle_headers_to_record_batch(
             .map(|bh| bh.mev_type.to_string())
             .collect(),
     );
+    let min_action_confidence_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.min_action_confidence)
+            .collect(),
+    );
+    let mean_action_confidence_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.mean_action_confidence)
+            .collect(),
+    );
+    let merged_submission_array = BooleanArray::from(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.merged_submission)
+            .collect::<Vec<_>>(),
+    );
+    let co_bundled_txs_array = get_list_string_array_from_owned(
+        bundle_headers
+            .iter()
+            .map(|bh| {
+                bh.co_bundled_txs
+                    .iter()
+                    .map(|tx| tx.to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+    );
+    let searcher_value_usd_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.value_split.searcher_usd)
+            .collect(),
+    );
+    let builder_value_usd_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.value_split.builder_usd)
+            .collect(),
+    );
+    let lp_fees_usd_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.value_split.lp_fees_usd())
+            .collect(),
+    );
+    let protocol_fees_usd_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.value_split.protocol_fees_usd)
+            .collect(),
+    );
+    let capital_source_array = build_string_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.capital.source.as_ref().to_string())
+            .collect(),
+    );
+    let flashloan_fee_usd_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.capital.flashloan_fee_usd)
+            .collect(),
+    );
+    let capital_deployed_usd_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.capital.capital_deployed_usd)
+            .collect(),
+    );
+    let capital_at_risk_usd_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.capital.capital_at_risk_usd)
+            .collect(),
+    );
+    let capital_turnover_array = build_float64_array(
+        bundle_headers
+            .iter()
+            .map(|bh| bh.capital.turnover)
+            .collect(),
+    );
+    let spam_profit_usd_array =
+        build_float64_array(bundle_headers.iter().map(|bh| bh.spam_profit_usd).collect());
 
     let schema = Schema::new(vec![
         Field::new("block_number", DataType::UInt64, false),
         Field::new("tx_index", DataType::UInt64, false),
         Field::new("tx_hash", DataType::Utf8, false),
+        Field::new("bundle_id", DataType::Utf8, false),
         Field::new("eoa", DataType::Utf8, false),
         Field::new("mev_contract", DataType::Utf8, true),
         Field::new("profit_usd", DataType::Float64, false),
         Field::new("bribe_usd", DataType::Float64, false),
         Field::new("mev_type", DataType::Utf8, false),
+        Field::new("min_action_confidence", DataType::Float64, false),
+        Field::new("mean_action_confidence", DataType::Float64, false),
+        Field::new("merged_submission", DataType::Boolean, false),
+        Field::new(
+            "co_bundled_txs",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            true,
+        ),
+        Field::new("searcher_value_usd", DataType::Float64, false),
+        Field::new("builder_value_usd", DataType::Float64, false),
+        Field::new("lp_fees_usd", DataType::Float64, false),
+        Field::new("protocol_fees_usd", DataType::Float64, false),
+        Field::new("capital_source", DataType::Utf8, false),
+        Field::new("flashloan_fee_usd", DataType::Float64, false),
+        Field::new("capital_deployed_usd", DataType::Float64, false),
+        Field::new("capital_at_risk_usd", DataType::Float64, false),
+        Field::new("capital_turnover", DataType::Float64, false),
+        Field::new("spam_profit_usd", DataType::Float64, false),
     ]);
 
     build_record_batch(
@@ -57,11 +168,26 @@ pub fn bundle_headers_to_record_batch(
             Arc::new(block_number_array),
             Arc::new(tx_index_array),
             Arc::new(tx_hash_array),
+            Arc::new(bundle_id_array),
             Arc::new(eoa_array),
             Arc::new(mev_contract_array),
             Arc::new(profit_usd_array),
             Arc::new(bribe_usd_array),
             Arc::new(mev_type_array),
+            Arc::new(min_action_confidence_array),
+            Arc::new(mean_action_confidence_array),
+            Arc::new(merged_submission_array),
+            Arc::new(co_bundled_txs_array),
+            Arc::new(searcher_value_usd_array),
+            Arc::new(builder_value_usd_array),
+            Arc::new(lp_fees_usd_array),
+            Arc::new(protocol_fees_usd_array),
+            Arc::new(capital_source_array),
+            Arc::new(flashloan_fee_usd_array),
+            Arc::new(capital_deployed_usd_array),
+            Arc::new(capital_at_risk_usd_array),
+            Arc::new(capital_turnover_array),
+            Arc::new(spam_profit_usd_array),
         ],
     )
 }
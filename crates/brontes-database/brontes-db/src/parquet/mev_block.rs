@@ -2,8 +2,8 @@ use std::sync::Arc;
 
 use arrow::{
     array::{
-        Array, ArrayRef, Float64Array, Float64Builder, StringArray, StringBuilder, StructArray,
-        UInt64Builder,
+        Array, ArrayRef, BooleanArray, Float64Array, Float64Builder, StringArray, StringBuilder,
+        StructArray, UInt64Array, UInt64Builder,
     },
     datatypes::{DataType, Field, Schema},
     error::ArrowError,
@@ -70,6 +70,14 @@ pub fn mev_block_to_record_batch(mev_blocks: Vec<MevBlock>) -> Result<RecordBatc
             .collect(),
     );
 
+    let slot_array = build_optional_uint64_array(mev_blocks.iter().map(|mb| mb.slot));
+    let proposer_index_array =
+        build_optional_uint64_array(mev_blocks.iter().map(|mb| mb.proposer_index));
+    let missed_slots_array =
+        build_optional_uint64_array(mev_blocks.iter().map(|mb| mb.missed_slots));
+    let pre_merge_array =
+        BooleanArray::from(mev_blocks.iter().map(|mb| mb.pre_merge).collect::<Vec<_>>());
+
     let mev_count_array = get_mev_count_array(&mev_blocks);
     let (proposer_fee_recipient_array, proposer_profit_usd_array) =
         get_proposer_arrays(&mev_blocks);
@@ -94,6 +102,10 @@ pub fn mev_block_to_record_batch(mev_blocks: Vec<MevBlock>) -> Result<RecordBatc
             Arc::new(proposer_fee_recipient_array),
             Arc::new(proposer_mev_reward_array),
             Arc::new(proposer_profit_usd_array),
+            Arc::new(slot_array),
+            Arc::new(proposer_index_array),
+            Arc::new(missed_slots_array),
+            Arc::new(pre_merge_array),
             Arc::new(total_mev_profit_usds_array),
         ],
     )
@@ -116,6 +128,10 @@ fn build_schema(mev_count_array: &StructArray) -> Schema {
         Field::new("proposer_fee_recipient", DataType::Utf8, true),
         Field::new("proposer_mev_reward", DataType::Binary, true),
         Field::new("proposer_profit_usd", DataType::Float64, true),
+        Field::new("slot", DataType::UInt64, true),
+        Field::new("proposer_index", DataType::UInt64, true),
+        Field::new("missed_slots", DataType::UInt64, true),
+        Field::new("pre_merge", DataType::Boolean, false),
         Field::new("total_mev_profit_usd", DataType::Float64, false),
     ])
 }
@@ -193,3 +209,9 @@ fn get_proposer_arrays(mev_blocks: &Vec<MevBlock>) -> (StringArray, Float64Array
 
     (proposer_fee_recipient_builder.finish(), proposer_profit_usd_builder.finish())
 }
+
+fn build_optional_uint64_array(values: impl Iterator<Item = Option<u64>>) -> UInt64Array {
+    let mut builder = UInt64Builder::new();
+    values.for_each(|value| builder.append_option(value));
+    builder.finish()
+}
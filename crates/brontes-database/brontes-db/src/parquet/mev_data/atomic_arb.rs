@@ -13,7 +13,7 @@ use crate::parquet::{
     normalized_actions::{
         gas_details::get_gas_details_array, swaps::get_normalized_swap_list_array,
     },
-    utils::get_string_array_from_owned,
+    utils::{get_list_string_array_from_owned, get_string_array_from_owned},
 };
 
 pub fn atomic_arb_to_record_batch(atomic_arbs: Vec<AtomicArb>) -> Result<RecordBatch, ArrowError> {
@@ -41,11 +41,24 @@ pub fn atomic_arb_to_record_batch(atomic_arbs: Vec<AtomicArb>) -> Result<RecordB
             .collect_vec(),
     );
 
+    let leg_tx_hashes_array = get_list_string_array_from_owned(
+        atomic_arbs
+            .iter()
+            .map(|arb| {
+                arb.leg_tx_hashes
+                    .iter()
+                    .map(|hash| hash.to_string())
+                    .collect_vec()
+            })
+            .collect_vec(),
+    );
+
     let schema = Schema::new(vec![
         Field::new("tx_hash", DataType::Utf8, false),
         Field::new("swaps", swaps_array.data_type().clone(), false),
         Field::new("gas_details", gas_details_array.data_type().clone(), false),
         Field::new("arb_type", DataType::Utf8, false),
+        Field::new("leg_tx_hashes", leg_tx_hashes_array.data_type().clone(), true),
     ]);
 
     RecordBatch::try_new(
@@ -55,6 +68,7 @@ pub fn atomic_arb_to_record_batch(atomic_arbs: Vec<AtomicArb>) -> Result<RecordB
             Arc::new(swaps_array),
             Arc::new(gas_details_array),
             Arc::new(arb_type_array),
+            Arc::new(leg_tx_hashes_array),
         ],
     )
 }
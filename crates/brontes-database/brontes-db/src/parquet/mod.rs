@@ -1,11 +1,12 @@
 use std::{
     fs::File,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use arrow::record_batch::RecordBatch;
 use brontes_types::{
-    db::traits::LibmdbxReader,
+    db::{export_filter::ExportFilter, mev_block::MevBlockWithClassified, traits::LibmdbxReader},
     mev::{BundleData, MevType},
 };
 use chrono::Local;
@@ -22,11 +23,13 @@ use crate::Tables;
 
 #[allow(dead_code)]
 mod address_meta;
+pub mod anonymize;
 mod builder;
 mod bundle_header;
 mod mev_block;
 mod mev_data;
 mod normalized_actions;
+pub mod publish;
 mod searcher;
 pub mod utils;
 
@@ -41,6 +44,10 @@ pub struct ParquetExporter<DB: LibmdbxReader> {
     pub start_block:   Option<u64>,
     pub end_block:     Option<u64>,
     pub base_dir_path: Option<String>,
+    /// File name to use instead of the time of the export
+    pub file_name:     Option<String>,
+    /// Redaction applied to the mev blocks before they are written
+    pub filter:        Option<Arc<ExportFilter>>,
     pub db:            &'static DB,
 }
 
@@ -54,7 +61,17 @@ where
         base_dir_path: Option<String>,
         db: &'static DB,
     ) -> Self {
-        Self { start_block, end_block, base_dir_path, db }
+        Self { start_block, end_block, base_dir_path, file_name: None, filter: None, db }
+    }
+
+    pub fn with_file_name(mut self, file_name: String) -> Self {
+        self.file_name = Some(file_name);
+        self
+    }
+
+    pub fn with_filter(mut self, filter: Option<Arc<ExportFilter>>) -> Self {
+        self.filter = filter;
+        self
     }
 
     pub async fn export_mev_blocks(&self) -> Result<(), Error> {
@@ -73,6 +90,17 @@ where
             return Err(Error::msg("No MEV blocks fetched for the given range."))
         }
 
+        self.write_mev_blocks(mev_blocks).await
+    }
+
+    pub async fn write_mev_blocks(
+        &self,
+        mut mev_blocks: Vec<MevBlockWithClassified>,
+    ) -> Result<(), Error> {
+        if let Some(filter) = &self.filter {
+            mev_blocks.iter_mut().for_each(|block| filter.apply(block));
+        }
+
         let mev_blocks_iter = mev_blocks.into_iter();
         let (
             blocks,
@@ -130,18 +158,20 @@ where
         };
 
         let base_dir_path = self.base_dir_path.clone();
+        let file_name = self.file_name.clone();
 
         let mut bundle_futures = Vec::new();
 
         if !blocks.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let block_batch = mev_block_to_record_batch(blocks)
                         .wrap_err("Failed to convert MEV block data to record batch")?;
                     sync_write_parquet(
                         block_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, None)?,
+                        get_path(base_dir_path, file_name, Tables::MevBlocks, None)?,
                     )
                 }
             }));
@@ -150,12 +180,13 @@ where
         /*if !_cex_dex_arbs.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let cex_dex_batch = cex_dex_to_record_batch(_cex_dex_arbs)
                         .wrap_err("Failed to convert CEX-DEX data to record batch")?;
                     sync_write_parquet(
                         cex_dex_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::CexDexTrades))?,
+                        get_path(base_dir_path, file_name, Tables::MevBlocks, Some(MevType::CexDexTrades))?,
                     )
                 }
             }));
@@ -164,12 +195,18 @@ where
         if !atomic_arbs.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let atomic_arb_batch = atomic_arb_to_record_batch(atomic_arbs)
                         .wrap_err("Failed to convert AtomicArb data to record batch")?;
                     sync_write_parquet(
                         atomic_arb_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::AtomicArb))?,
+                        get_path(
+                            base_dir_path,
+                            file_name,
+                            Tables::MevBlocks,
+                            Some(MevType::AtomicArb),
+                        )?,
                     )
                 }
             }));
@@ -178,12 +215,13 @@ where
         if !jit.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let jit_batch = jit_to_record_batch(jit)
                         .wrap_err("Failed to convert JIT data to record batch")?;
                     sync_write_parquet(
                         jit_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::Jit))?,
+                        get_path(base_dir_path, file_name, Tables::MevBlocks, Some(MevType::Jit))?,
                     )
                 }
             }));
@@ -192,12 +230,18 @@ where
         if !sandwich.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let sandwich_batch = sandwich_to_record_batch(sandwich)
                         .wrap_err("Failed to convert Sandwich data to record batch")?;
                     sync_write_parquet(
                         sandwich_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::Sandwich))?,
+                        get_path(
+                            base_dir_path,
+                            file_name,
+                            Tables::MevBlocks,
+                            Some(MevType::Sandwich),
+                        )?,
                     )
                 }
             }));
@@ -206,12 +250,18 @@ where
         if !jit_sandwich.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let jit_sandwich_batch = jit_sandwich_to_record_batch(jit_sandwich)
                         .wrap_err("Failed to convert JIT Sandwich data to record batch")?;
                     sync_write_parquet(
                         jit_sandwich_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::JitSandwich))?,
+                        get_path(
+                            base_dir_path,
+                            file_name,
+                            Tables::MevBlocks,
+                            Some(MevType::JitSandwich),
+                        )?,
                     )
                 }
             }));
@@ -220,12 +270,18 @@ where
         if !searcher_tx.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let searcher_tx_batch = searcher_tx_to_record_batch(searcher_tx)
                         .wrap_err("Failed to convert Searcher Tx data to record batch")?;
                     sync_write_parquet(
                         searcher_tx_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::SearcherTx))?,
+                        get_path(
+                            base_dir_path,
+                            file_name,
+                            Tables::MevBlocks,
+                            Some(MevType::SearcherTx),
+                        )?,
                     )
                 }
             }));
@@ -234,12 +290,18 @@ where
         if !liquidation.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let liquidation_batch = liquidation_to_record_batch(liquidation)
                         .wrap_err("Failed to convert Liquidation data to record batch")?;
                     sync_write_parquet(
                         liquidation_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::Liquidation))?,
+                        get_path(
+                            base_dir_path,
+                            file_name,
+                            Tables::MevBlocks,
+                            Some(MevType::Liquidation),
+                        )?,
                     )
                 }
             }));
@@ -248,12 +310,18 @@ where
         if !bundle_headers.is_empty() {
             bundle_futures.push(tokio::task::spawn_blocking({
                 let base_dir_path = base_dir_path.clone();
+                let file_name = file_name.clone();
                 move || {
                     let bundle_batch = bundle_headers_to_record_batch(bundle_headers)
                         .wrap_err("Failed to convert bundle headers to record batch")?;
                     sync_write_parquet(
                         bundle_batch,
-                        get_path(base_dir_path, Tables::MevBlocks, Some(MevType::Unknown))?,
+                        get_path(
+                            base_dir_path,
+                            file_name,
+                            Tables::MevBlocks,
+                            Some(MevType::Unknown),
+                        )?,
                     )
                 }
             }));
@@ -279,7 +347,12 @@ where
 
         write_parquet(
             address_meta_batch,
-            get_path(self.base_dir_path.clone(), Tables::AddressMeta, None)?,
+            get_path(
+                self.base_dir_path.clone(),
+                self.file_name.clone(),
+                Tables::AddressMeta,
+                None,
+            )?,
         )
         .await
         .expect("Failed to write address metadata to parquet file");
@@ -303,7 +376,12 @@ where
 
         write_parquet(
             searcher_info_batch,
-            get_path(self.base_dir_path.clone(), Tables::SearcherEOAs, None)?,
+            get_path(
+                self.base_dir_path.clone(),
+                self.file_name.clone(),
+                Tables::SearcherEOAs,
+                None,
+            )?,
         )
         .await
         .expect("Failed to write searcher info to parquet file");
@@ -327,7 +405,7 @@ where
 
         write_parquet(
             builder_info_batch,
-            get_path(self.base_dir_path.clone(), Tables::Builder, None)?,
+            get_path(self.base_dir_path.clone(), self.file_name.clone(), Tables::Builder, None)?,
         )
         .await
         .expect("Failed to write builder info to parquet file");
@@ -383,6 +461,7 @@ fn sync_write_parquet(record_batch: RecordBatch, file_path: PathBuf) -> Result<(
 
 pub fn get_path(
     custom_path: Option<String>,
+    file_name: Option<String>,
     batch_type: Tables,
     mev_type: Option<MevType>,
 ) -> Result<PathBuf> {
@@ -399,6 +478,12 @@ pub fn get_path(
         path.push("bundles");
         path.push(mev_type.get_parquet_path());
     }
+
+    if let Some(file_name) = file_name {
+        std::fs::create_dir_all(&path)?;
+        return Ok(path.join(format!("{file_name}.parquet")))
+    }
+
     create_file_path(path)
 }
 
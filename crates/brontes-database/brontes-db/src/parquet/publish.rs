@@ -0,0 +1,151 @@
+//! Publishes finalized mev blocks and bundles to a public bucket.
+//!
+//! Each run exports the blocks that were processed since the last run to
+//! parquet, using the schema documented in the book, and uploads them with
+//! rclone to `<remote>:<remote_path>/<start_block>-<end_block>/`. The last
+//! published block is persisted in the staging directory so that restarts
+//! resume where they left off.
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+use brontes_types::db::{export_filter::ExportFilter, traits::LibmdbxReader};
+use eyre::WrapErr;
+use tracing::info;
+
+use super::{anonymize::AddressAnonymizer, ParquetExporter};
+use crate::libmdbx::rclone_wrapper::RCloneWrapper;
+
+const EXPORT_DIR: &str = "export";
+const LAST_PUBLISHED_FILE: &str = "last-published-block";
+
+pub struct PublishConfig {
+    /// rclone remote to upload to. Any rclone backend works, e.g. s3 or gcs
+    pub remote:         String,
+    /// Path on the remote, usually `<bucket>/<prefix>`
+    pub remote_path:    String,
+    pub staging_dir:    PathBuf,
+    /// Blocks within this distance of the most recent processed block aren't
+    /// published yet, so that reorged blocks don't end up in the public data
+    pub finality_depth: u64,
+    /// If set, participant addresses are hashed before publishing
+    pub anonymizer:     Option<AddressAnonymizer>,
+    /// Redaction of excluded and hashed address lists
+    pub filter:         Option<Arc<ExportFilter>>,
+}
+
+pub struct ParquetPublisher<DB: LibmdbxReader> {
+    db:         &'static DB,
+    rclone:     RCloneWrapper,
+    config:     PublishConfig,
+    next_block: u64,
+}
+
+impl<DB: LibmdbxReader> ParquetPublisher<DB> {
+    /// If no start block is given, continues after the last published block.
+    pub async fn new(
+        db: &'static DB,
+        config: PublishConfig,
+        start_block: Option<u64>,
+    ) -> eyre::Result<Self> {
+        let rclone = RCloneWrapper::new(config.remote.clone()).await?;
+        std::fs::create_dir_all(&config.staging_dir)?;
+
+        let next_block = match start_block {
+            Some(block) => block,
+            None => read_last_published(&config.staging_dir)?
+                .map(|block| block + 1)
+                .unwrap_or_default(),
+        };
+
+        Ok(Self { db, rclone, config, next_block })
+    }
+
+    /// Publishes once if no interval is given, otherwise publishes new blocks
+    /// on every interval until an error occurs.
+    pub async fn run(mut self, interval: Option<Duration>) -> eyre::Result<()> {
+        loop {
+            if let Some((start_block, end_block)) = self.publish_next().await? {
+                info!(start_block, end_block, "published mev data");
+            } else {
+                info!(next_block = self.next_block, "no finalized blocks to publish");
+            }
+
+            let Some(interval) = interval else { return Ok(()) };
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn publish_next(&mut self) -> eyre::Result<Option<(u64, u64)>> {
+        let mut mev_blocks = self
+            .db
+            .fetch_all_mev_blocks(Some(self.next_block))
+            .wrap_err("failed to fetch mev blocks")?;
+
+        let Some(tip) = mev_blocks.iter().map(|b| b.block.block_number).max() else {
+            return Ok(None)
+        };
+        let end_block = tip.saturating_sub(self.config.finality_depth);
+        mev_blocks.retain(|b| (self.next_block..=end_block).contains(&b.block.block_number));
+
+        let Some(start_block) = mev_blocks.iter().map(|b| b.block.block_number).min() else {
+            return Ok(None)
+        };
+
+        if let Some(anonymizer) = self.config.anonymizer {
+            mev_blocks
+                .iter_mut()
+                .for_each(|block| anonymizer.anonymize_mev_block(block));
+        }
+
+        let export_dir = self.config.staging_dir.join(EXPORT_DIR);
+        if export_dir.exists() {
+            std::fs::remove_dir_all(&export_dir)?;
+        }
+        std::fs::create_dir_all(&export_dir)?;
+
+        ParquetExporter::new(
+            Some(start_block),
+            Some(end_block),
+            Some(export_dir.to_string_lossy().into_owned()),
+            self.db,
+        )
+        .with_file_name(format!("{start_block}-{end_block}"))
+        .with_filter(self.config.filter.clone())
+        .write_mev_blocks(mev_blocks)
+        .await?;
+
+        self.rclone
+            .copy_dir(
+                &export_dir,
+                &format!("{}/{start_block}-{end_block}", self.config.remote_path),
+            )
+            .await?;
+
+        write_last_published(&self.config.staging_dir, end_block)?;
+        self.next_block = end_block + 1;
+
+        Ok(Some((start_block, end_block)))
+    }
+}
+
+fn read_last_published(staging_dir: &Path) -> eyre::Result<Option<u64>> {
+    let path = staging_dir.join(LAST_PUBLISHED_FILE);
+    if !path.exists() {
+        return Ok(None)
+    }
+
+    let block = std::fs::read_to_string(&path)?;
+    Ok(Some(
+        u64::from_str(block.trim())
+            .wrap_err_with(|| format!("invalid last published block in {}", path.display()))?,
+    ))
+}
+
+fn write_last_published(staging_dir: &Path, block: u64) -> eyre::Result<()> {
+    std::fs::write(staging_dir.join(LAST_PUBLISHED_FILE), block.to_string())?;
+    Ok(())
+}
@@ -168,6 +168,20 @@ impl Environment {
         mdbx_result(unsafe { ffi::mdbx_env_sync_ex(self.env_ptr(), force, false) })
     }
 
+    /// Copies the environment into the new file `dest`. The copy is made from
+    /// a read transaction, so it's consistent while writers continue. With
+    /// `compact` free pages are omitted and the used ones renumbered.
+    pub fn copy(&self, dest: &Path, compact: bool) -> Result<()> {
+        let dest = dest
+            .to_str()
+            .and_then(|dest| CString::new(dest).ok())
+            .ok_or(Error::Invalid)?;
+        let flags = if compact { ffi::MDBX_CP_COMPACT } else { ffi::MDBX_CP_DEFAULTS };
+        mdbx_result(unsafe { ffi::mdbx_env_copy(self.env_ptr(), dest.as_ptr(), flags) })?;
+
+        Ok(())
+    }
+
     /// Retrieves statistics about this environment.
     pub fn stat(&self) -> Result<Stat> {
         unsafe {
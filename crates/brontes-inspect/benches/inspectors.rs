@@ -1,11 +1,14 @@
-use std::str::FromStr;
+use std::{str::FromStr, sync::Arc};
 
 use alloy_primitives::{hex, B256};
-use brontes_classifier::test_utils::ClassifierTestUtils;
+use brontes_classifier::test_utils::{ClassifierTestUtils, BENCH_FIXTURE_BLOCKS};
 use brontes_inspect::{
+    jit::JitInspector,
+    sandwich::SandwichInspector,
     test_utils::{InspectorBenchUtils, USDC_ADDRESS},
-    Inspectors,
+    Inspector, Inspectors,
 };
+use brontes_types::mev::Bundle;
 use criterion::{criterion_group, criterion_main, Criterion};
 use itertools::Itertools;
 use strum::IntoEnumIterator;
@@ -145,6 +148,36 @@ fn bench_composer(c: &mut Criterion) {
         .unwrap()
 }
 
+type SharedInspector = Arc<dyn Inspector<Result = Vec<Bundle>>>;
+
+fn bench_composer_arc_handles(c: &mut Criterion) {
+    let bencher = InspectorBenchUtils::new(USDC_ADDRESS);
+    bencher
+        .bench_composer_with_handles(
+            "bench sandwich jit composer arc handles",
+            vec![
+                hex!("22ea36d516f59cc90ccc01042e20f8fba196f32b067a7e5f1510099140ae5e0a").into(),
+                hex!("72eb3269ac013cf663dde9aa11cc3295e0dfb50c7edfcf074c5c57b43611439c").into(),
+                hex!("3b4138bac9dc9fa4e39d8d14c6ecd7ec0144fe26b120ea799317aa15fa35ddcd").into(),
+                hex!("99785f7b76a9347f13591db3574506e9f718060229db2826b4925929ebaea77e").into(),
+                hex!("31dedbae6a8e44ec25f660b3cd0e04524c6476a0431ab610bb4096f82271831b").into(),
+            ],
+            0,
+            |quote, db| {
+                vec![
+                    Arc::new(SandwichInspector::new(quote, db, None)) as SharedInspector,
+                    Arc::new(JitInspector::new(quote, db, None)) as SharedInspector,
+                ]
+            },
+            vec![
+                hex!("50d1c9771902476076ecfc8b2a83ad6b9355a4c9").into(),
+                hex!("b17548c7b510427baac4e267bea62e800b247173").into(),
+            ],
+            c,
+        )
+        .unwrap()
+}
+
 fn bench_regular_block(c: &mut Criterion) {
     let bencher = InspectorBenchUtils::new(USDC_ADDRESS);
     bencher
@@ -257,6 +290,40 @@ fn bench_cex_dex_regular_block(c: &mut Criterion) {
         .unwrap()
 }
 
+fn bench_fixture_blocks_per_inspector(c: &mut Criterion) {
+    let bencher = InspectorBenchUtils::new(USDC_ADDRESS);
+    for fixture in BENCH_FIXTURE_BLOCKS {
+        for inspector in Inspectors::iter() {
+            bencher
+                .bench_inspectors_block(
+                    &format!("{inspector} {} block", fixture.name),
+                    fixture.block,
+                    0,
+                    vec![inspector],
+                    vec![hex!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2").into()],
+                    c,
+                )
+                .unwrap()
+        }
+    }
+}
+
+fn bench_fixture_blocks_composer(c: &mut Criterion) {
+    let bencher = InspectorBenchUtils::new(USDC_ADDRESS);
+    for fixture in BENCH_FIXTURE_BLOCKS {
+        bencher
+            .bench_composer_block(
+                &format!("compose {} block", fixture.name),
+                fixture.block,
+                0,
+                Inspectors::iter().collect_vec(),
+                vec![],
+                c,
+            )
+            .unwrap()
+    }
+}
+
 criterion_group!(
     inspector_specific_tx_benches,
     bench_sandwich,
@@ -265,6 +332,7 @@ criterion_group!(
     bench_backrun_10_swaps,
     bench_liquidation,
     bench_composer,
+    bench_composer_arc_handles,
 );
 
 criterion_group!(cex_dex, bench_cex_dex, bench_cex_dex_regular_block);
@@ -280,4 +348,15 @@ criterion_group!(
     bench_jit_regular_block,
 );
 
-criterion_main!(inspector_full_block_benches, inspector_specific_tx_benches, cex_dex);
+criterion_group!(
+    inspector_fixture_block_benches,
+    bench_fixture_blocks_per_inspector,
+    bench_fixture_blocks_composer
+);
+
+criterion_main!(
+    inspector_full_block_benches,
+    inspector_specific_tx_benches,
+    cex_dex,
+    inspector_fixture_block_benches
+);
@@ -16,7 +16,9 @@ use lazy_static::lazy_static;
 /// );
 /// ```
 /// In this example, `ParentMevType` is composed of `ChildMevType1` and
-/// `ChildMevType2` using a specific composition function.
+/// `ChildMevType2` using a specific composition function. Any number of child
+/// types can be listed, the composition function gets one component of each
+/// in the order they are listed.
 #[macro_export]
 macro_rules! mev_composability {
     ($($($child_mev_type:ident),+ => $parent_mev_type:ident;)+) => {
@@ -35,7 +37,9 @@ macro_rules! mev_composability {
     };
 }
 
-pub type ComposeFunction = Box<dyn Fn(Vec<Bundle>) -> Option<Bundle> + Send + Sync>;
+/// Composes the components, one of each child type in the order of the rule,
+/// into the parent mev
+pub type ComposeFunction = Box<dyn Fn(&[Bundle]) -> Option<Bundle> + Send + Sync>;
 
 pub fn get_compose_fn(mev_type: MevType) -> ComposeFunction {
     match mev_type {
@@ -110,6 +110,7 @@ define_mev_precedence!(
     Unknown, SearcherTx => CexDexTrades;
     Unknown, SearcherTx => AtomicArb;
     Unknown, SearcherTx, AtomicArb => Jit;
+    Unknown, SearcherTx, AtomicArb => JitExtended;
     Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades  => Liquidation;
     Unknown, SearcherTx, AtomicArb, CexDexQuotes,CexDexTrades  => Sandwich;
     Unknown, SearcherTx, AtomicArb, Jit, CexDexQuotes, CexDexTrades=> JitCexDex;
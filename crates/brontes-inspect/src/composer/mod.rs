@@ -7,8 +7,9 @@
 //! within a block.
 //!
 //! ## Key Components
-//! - `Composer`: A struct that orchestrates specialized inspectors. It waits
-//!   for all results and then proceeds to compose and deduplicate MEV data.
+//! - [`run_block_inspection`]: Runs the specialized inspectors of a block on
+//!   the rayon pool, waits for all results and then composes and deduplicates
+//!   the MEV data.
 //! - `MEV_COMPOSABILITY_FILTER` and `MEV_DEDUPLICATION_FILTER`: These filters,
 //!   defined using the `mev_composability` and `define_mev_precedence` macros,
 //!   respectively, establish rules for composing multiple MEV types and setting
@@ -16,24 +17,31 @@
 //! - Utility Functions: A collection of functions designed to assist in the
 //!   composition and deduplication processes of MEV data.
 //!
-//! ## Usage
-//! The `Composer` struct is central to this module. It processes a list of
-//! `Inspector` futures to extract MEV data, which is then composed and
-//! deduplicated based on the rules defined in the `MEV_COMPOSABILITY_FILTER`
-//! and `MEV_DEDUPLICATION_FILTER`.
+//! ## Embedding
+//! The composer is synchronous and free of unsafe code. It borrows the
+//! inspectors for the duration of the call, so any handle that derefs to an
+//! inspector works: the leaked `&'static` inspectors brontes runs with, or
+//! `Arc<dyn Inspector>`s owned by the embedding application. To run it from an
+//! async runtime, move the handles into a blocking task or a rayon spawn.
 //!
 //! ### Example
 //! ```ignore
-//! let composer = Composer::new(&orchestra, tree, metadata);
-//! // Future execution of the composer to process MEV data
+//! let inspectors: Vec<Arc<dyn Inspector<Result = Vec<Bundle>>>> = ..;
+//! let results = tokio::task::spawn_blocking(move || {
+//!     run_block_inspection(&inspectors, data, db)
+//! })
+//! .await?;
 //! ```
-use std::sync::Arc;
+#![forbid(unsafe_code)]
 
-use alloy_primitives::Address;
+use std::{ops::Deref, sync::Arc, time::Instant};
+
+use alloy_primitives::{Address, B256};
 use brontes_types::{
-    db::{block_analysis::BlockAnalysis, traits::LibmdbxReader},
+    db::{block_analysis::BlockAnalysis, bundle_tags::BundleTag, traits::LibmdbxReader},
+    live_events::LiveEvent,
     mev::Mev,
-    BlockData, FastHashMap, MultiBlockData,
+    BlockData, FastHashMap, FastHashSet, MultiBlockData,
 };
 use itertools::Itertools;
 use tracing::{span, Level};
@@ -51,8 +59,10 @@ use composer_filters::{ComposeFunction, MEV_COMPOSABILITY_FILTER};
 use mev_filters::{FilterFn, MEV_DEDUPLICATION_FILTER};
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use utils::{
-    build_mev_header, filter_and_count_bundles, find_mev_with_matching_tx_hashes, sort_mev_by_type,
-    try_deduping_mev,
+    annotate_merged_submissions, build_mev_header, detect_bundle_tags, down_rank_spam_profit,
+    filter_and_count_bundles, find_mev_with_matching_tx_hashes, reclassify_rebalances,
+    scope_bundles, set_action_confidence, set_bundle_ids, set_capital_usage, set_value_split,
+    sort_mev_by_type, suppress_bundles, try_deduping_mev,
 };
 
 const DISCOVERY_PRIORITY_FEE_MULTIPLIER: f64 = 2.0;
@@ -66,54 +76,130 @@ pub struct ComposerResults {
     /// all txes with coinbase.transfers that weren't classified
     pub possible_mev_txes: PossibleMevCollection,
     pub block_analysis:    BlockAnalysis,
+    pub bundle_tags:       Vec<BundleTag>,
 }
 
-pub fn run_block_inspection<DB: LibmdbxReader>(
-    orchestra: &[&dyn Inspector<Result = Vec<Bundle>>],
+/// Runs the inspectors on the most recent block of `data` and composes their
+/// results. The inspectors can be any handle to them, e.g. `&dyn Inspector` or
+/// `Arc<dyn Inspector>`
+pub fn run_block_inspection<'a, DB, I>(
+    orchestra: &[I],
     data: MultiBlockData,
     db: &'static DB,
-) -> ComposerResults {
+) -> ComposerResults
+where
+    DB: LibmdbxReader,
+    I: Deref<Target = dyn Inspector<Result = Vec<Bundle>> + 'a> + Sync,
+{
     let this_data = data.get_most_recent_block().clone();
     let BlockData { metadata, tree } = this_data;
 
-    let (possible_mev_txes, classified_mev) = run_inspectors(orchestra, data);
+    let (possible_mev_txes, classified_mev, skipped_inspectors) = run_inspectors(orchestra, data);
 
     let possible_arbs = possible_mev_txes.clone();
 
     let quote_token = orchestra[0].get_quote_token();
 
-    let (block_details, mev_details) =
-        on_orchestra_resolution(tree, possible_mev_txes, metadata, classified_mev, quote_token, db);
+    let (mut block_details, mev_details) = on_orchestra_resolution(
+        tree.clone(),
+        possible_mev_txes,
+        metadata.clone(),
+        classified_mev,
+        quote_token,
+        db,
+    );
+
+    block_details.skipped_inspectors = skipped_inspectors;
 
     let block_analysis = BlockAnalysis::new(&block_details, &mev_details);
+    let bundle_tags = detect_bundle_tags(&tree, &metadata, &mev_details);
+
+    ComposerResults {
+        block_details,
+        mev_details,
+        possible_mev_txes: possible_arbs,
+        block_analysis,
+        bundle_tags,
+    }
+}
+
+fn inspect_block_data<'a, I>(orchestra: &[&I], data: &MultiBlockData, block_num: u64) -> Vec<Bundle>
+where
+    I: Deref<Target = dyn Inspector<Result = Vec<Bundle>> + 'a> + Sync,
+{
+    let metadata = &data.get_most_recent_block().metadata;
 
-    ComposerResults { block_details, mev_details, possible_mev_txes: possible_arbs, block_analysis }
+    orchestra
+        .par_iter()
+        .flat_map(|inspector| {
+            let data = data.split_to_size(inspector.block_window());
+            let span =
+                span!(Level::ERROR, "Inspector", inspector = %inspector.get_id(),block=&block_num);
+
+            let start = Instant::now();
+            let bundles = span.in_scope(|| inspector.inspect_block(data));
+            let elapsed = start.elapsed().as_micros() as u64;
+            metadata.record_cost(|cost| cost.inspection_us += elapsed);
+            metadata.emit_live_event(|| LiveEvent::InspectorDone {
+                inspector:  inspector.get_id().to_string(),
+                candidates: bundles.len(),
+            });
+
+            bundles
+        })
+        .collect::<Vec<_>>()
 }
 
-fn run_inspectors(
-    orchestra: &[&dyn Inspector<Result = Vec<Bundle>>],
+fn run_inspectors<'a, I>(
+    orchestra: &[I],
     data: MultiBlockData,
-) -> (PossibleMevCollection, Vec<Bundle>) {
+) -> (PossibleMevCollection, Vec<Bundle>, Vec<String>)
+where
+    I: Deref<Target = dyn Inspector<Result = Vec<Bundle>> + 'a> + Sync,
+{
     let this_data = data.get_most_recent_block().clone();
     let BlockData { metadata, tree } = this_data;
     let mut possible_mev_txes =
         DiscoveryInspector::new(DISCOVERY_PRIORITY_FEE_MULTIPLIER).find_possible_mev(tree.clone());
 
-    let results = orchestra
-        .par_iter()
-        .flat_map(|inspector| {
-            let window = inspector.block_window();
-            // not sufficient size yet
-            if data.blocks < window {
-                return vec![]
-            };
-            let data = data.split_to_size(window);
-            let span =
-                span!(Level::ERROR, "Inspector", inspector = %inspector.get_id(),block=&metadata.block_num);
-
-            span.in_scope(|| inspector.inspect_block(data))
-        })
+    // skips the inspectors that can't find anything in any block of their window
+    let (orchestra, skipped): (Vec<_>, Vec<_>) = orchestra
+        .iter()
+        // not sufficient size yet
+        .filter(|inspector| data.blocks >= inspector.block_window())
+        .partition(|inspector| {
+            data.per_block_data
+                .iter()
+                .rev()
+                .take(inspector.block_window())
+                .any(|block| inspector.should_inspect(&block.tree.action_summary))
+        });
+    let skipped = skipped
+        .into_iter()
+        .map(|inspector| inspector.get_id().to_string())
         .collect::<Vec<_>>();
+    if !skipped.is_empty() {
+        tracing::debug!(block = metadata.block_num, ?skipped, "skipped inspectors");
+        metadata.record_cost(|cost| cost.inspectors_skipped += skipped.len() as u64);
+    }
+
+    // large blocks are inspected in overlapping chunks so only one chunk's
+    // roots are cloned at a time. Bundles found in the overlap of two chunks
+    // are found twice, possibly cut short by the chunk boundary
+    let results = match &tree.memory_bound {
+        Some(bound) => merge_chunk_bundles(
+            tree.chunks(bound.max_txs_in_memory, bound.chunk_overlap)
+                .flat_map(|chunk| {
+                    inspect_block_data(
+                        &orchestra,
+                        &data.with_most_recent_tree(Arc::new(chunk)),
+                        metadata.block_num,
+                    )
+                })
+                .collect(),
+        ),
+        None => inspect_block_data(&orchestra, &data, metadata.block_num),
+    };
 
     results.iter().for_each(|bundle| {
         bundle
@@ -131,7 +217,35 @@ fn run_inspectors(
         .0
         .sort_by(|a, b| a.tx_idx.cmp(&b.tx_idx));
 
-    (possible_mev_collection, results)
+    (possible_mev_collection, results, skipped)
+}
+
+/// Merges the bundles found in overlapping chunks. A bundle that spans a chunk
+/// boundary is found whole in one chunk and cut short in the other, so a bundle
+/// whose transactions are all part of a larger bundle of the same type is
+/// dropped
+fn merge_chunk_bundles(mut bundles: Vec<Bundle>) -> Vec<Bundle> {
+    bundles
+        .sort_by_cached_key(|bundle| std::cmp::Reverse(bundle.data.mev_transaction_hashes().len()));
+
+    let mut kept: Vec<(MevType, FastHashSet<B256>)> = Vec::with_capacity(bundles.len());
+    bundles
+        .into_iter()
+        .filter(|bundle| {
+            let txes = bundle
+                .data
+                .mev_transaction_hashes()
+                .into_iter()
+                .collect::<FastHashSet<_>>();
+            if kept.iter().any(|(mev_type, kept_txes)| {
+                *mev_type == bundle.header.mev_type && txes.is_subset(kept_txes)
+            }) {
+                return false
+            }
+            kept.push((bundle.header.mev_type, txes));
+            true
+        })
+        .collect()
 }
 
 fn on_orchestra_resolution<DB: LibmdbxReader>(
@@ -143,6 +257,8 @@ fn on_orchestra_resolution<DB: LibmdbxReader>(
     db: &'static DB,
 ) -> (MevBlock, Vec<Bundle>) {
     let mut sorted_mev = sort_mev_by_type(orchestra_data);
+    suppress_bundles(db, &mut sorted_mev);
+    scope_bundles(tree.clone(), &metadata, &mut sorted_mev);
 
     MEV_COMPOSABILITY_FILTER
         .iter()
@@ -163,7 +279,22 @@ fn on_orchestra_resolution<DB: LibmdbxReader>(
         },
     );
 
-    let (mev_count, mut filtered_bundles) = filter_and_count_bundles(sorted_mev);
+    let (mut mev_count, mut filtered_bundles) = filter_and_count_bundles(sorted_mev);
+    set_bundle_ids(&mut filtered_bundles);
+    set_action_confidence(tree.clone(), &mut filtered_bundles);
+    annotate_merged_submissions(&tree, &mut filtered_bundles);
+    // the value split is made from the profit left once spam is taken out
+    down_rank_spam_profit(db, &mut filtered_bundles);
+    set_value_split(tree.clone(), &metadata, quote_token, db, &mut filtered_bundles);
+    set_capital_usage(tree.clone(), &metadata, quote_token, db, &mut filtered_bundles);
+    let rebalances = reclassify_rebalances(db, &mut filtered_bundles);
+    if rebalances > 0 {
+        mev_count.bundle_count -= rebalances;
+        mev_count.atomic_backrun_count = mev_count
+            .atomic_backrun_count
+            .map(|count| count - rebalances)
+            .filter(|count| *count > 0);
+    }
 
     let header = build_mev_header(
         &metadata,
@@ -228,18 +359,18 @@ fn deduplicate_mev<DB: LibmdbxReader>(
 ///
 /// # Functionality:
 ///
-/// The function first checks if there are any MEV of the first type in
-/// `composable_types` in `sorted_mev`. If there are, it iterates over them.
-/// For each MEV, it gets the transaction hashes associated with that MEV
-/// and attempts to find other MEV in `sorted_mev` that have matching
-/// transaction hashes. If it finds matching MEV for all types in
-/// `composable_types`, it uses the `compose` function to create a new MEV
-/// and adds it to `sorted_mev` under `parent_mev_type`. It also records the
-/// indices of the composed MEV in `removal_indices`.
+/// The components are gathered by an ordered reduction over `child_mev_type`.
+/// Each MEV of the first type is the seed of a composition, the transaction
+/// hashes of the components gathered so far are then used to find an MEV of
+/// the next type that shares a transaction with them, whose hashes are added
+/// to the set in turn. This way a component only needs to overlap with one of
+/// the components before it, e.g. a backrun that shares a transaction with the
+/// jit of a jit sandwich but not with the sandwich itself.
 ///
-/// After attempting to compose MEV for all MEV of the first type in
-/// `composable_types`, it removes all the composed MEV from `sorted_mev`
-/// using the indices stored in `removal_indices`.
+/// If an MEV of every type in `child_mev_type` is found, the `compose`
+/// function is called with the components in the order of `child_mev_type`.
+/// The composed MEV is added to `sorted_mev` under `parent_mev_type` and the
+/// components are removed. A component is used in at most one composition.
 ///
 /// This function does not return any value. Its purpose is to modify
 /// `sorted_mev` by composing new MEV and removing the composed MEV.
@@ -249,48 +380,53 @@ fn try_compose_mev(
     compose: &ComposeFunction,
     sorted_mev: &mut FastHashMap<MevType, Vec<Bundle>>,
 ) {
-    let first_mev_type = child_mev_type[0];
+    let Some((&first_mev_type, other_mev_types)) = child_mev_type.split_first() else { return };
     let mut removal_indices: FastHashMap<MevType, Vec<usize>> = FastHashMap::default();
+    let mut composed_mev = Vec::new();
 
-    if let Some(first_mev_list) = sorted_mev.remove(&first_mev_type) {
+    if let Some(first_mev_list) = sorted_mev.get(&first_mev_type) {
         for (first_i, bundle) in first_mev_list.iter().enumerate() {
-            let tx_hashes = bundle.data.mev_transaction_hashes();
-            let mut to_compose = vec![bundle.clone()];
-            let mut temp_removal_indices = Vec::new();
-
-            for &other_mev_type in child_mev_type.iter().skip(1) {
-                if let Some(other_mev_data_list) = sorted_mev.get(&other_mev_type) {
-                    for index in find_mev_with_matching_tx_hashes(other_mev_data_list, &tx_hashes) {
-                        let other_bundle = &other_mev_data_list[index];
-
-                        to_compose.push(other_bundle.clone());
-                        temp_removal_indices.push((other_mev_type, index));
-                    }
-                } else {
+            let mut tx_hashes = bundle.data.mev_transaction_hashes();
+            let mut components = vec![(first_mev_type, first_i)];
+
+            for &other_mev_type in other_mev_types {
+                let Some(other_mev_data_list) = sorted_mev.get(&other_mev_type) else { break };
+                let used = removal_indices.get(&other_mev_type);
+                let Some(index) = find_mev_with_matching_tx_hashes(other_mev_data_list, &tx_hashes)
+                    .find(|index| {
+                        used.map_or(true, |used| !used.contains(index))
+                            && !components.contains(&(other_mev_type, *index))
+                    })
+                else {
                     break
-                }
+                };
+
+                tx_hashes.extend(other_mev_data_list[index].data.mev_transaction_hashes());
+                components.push((other_mev_type, index));
+            }
+
+            if components.len() != child_mev_type.len() {
+                continue
             }
 
-            if to_compose.len() == child_mev_type.len() {
-                if let Some(composed) = compose(to_compose) {
-                    sorted_mev
-                        .entry(*parent_mev_type)
-                        .or_default()
-                        .push(composed);
-
-                    for (mev_type, index) in temp_removal_indices {
-                        removal_indices.entry(mev_type).or_default().push(index);
-                    }
-
-                    removal_indices
-                        .entry(first_mev_type)
-                        .or_default()
-                        .push(first_i)
+            let to_compose = components
+                .iter()
+                .map(|(mev_type, index)| sorted_mev[mev_type][*index].clone())
+                .collect_vec();
+            if let Some(composed) = compose(&to_compose) {
+                composed_mev.push(composed);
+                for (mev_type, index) in components {
+                    removal_indices.entry(mev_type).or_default().push(index);
                 }
             }
         }
+    }
 
-        sorted_mev.insert(first_mev_type, first_mev_list);
+    if !composed_mev.is_empty() {
+        sorted_mev
+            .entry(*parent_mev_type)
+            .or_default()
+            .extend(composed_mev);
     }
 
     // Remove the mev data that was composed from the sorted mev list
@@ -333,4 +469,113 @@ pub mod tests {
 
         inspector_util.run_composer(config, None).await.unwrap();
     }
+
+    #[test]
+    fn test_compose_more_than_two_types() {
+        use alloy_primitives::B256;
+        use brontes_types::mev::{AtomicArb, BundleData, BundleHeader, JitLiquidity, Sandwich};
+
+        let [a, b, c, d, z] = [1, 2, 3, 4, 5].map(B256::repeat_byte);
+        let bundle = |mev_type, data| Bundle {
+            header: BundleHeader { mev_type, ..Default::default() },
+            data,
+        };
+
+        let mut sorted_mev = FastHashMap::default();
+        sorted_mev.insert(
+            MevType::Sandwich,
+            vec![bundle(
+                MevType::Sandwich,
+                BundleData::Sandwich(Sandwich {
+                    frontrun_tx_hash: vec![a],
+                    victim_swaps_tx_hashes: vec![vec![b]],
+                    backrun_tx_hash: c,
+                    ..Default::default()
+                }),
+            )],
+        );
+        sorted_mev.insert(
+            MevType::Jit,
+            vec![bundle(
+                MevType::Jit,
+                BundleData::Jit(JitLiquidity {
+                    frontrun_mint_tx_hash: a,
+                    backrun_burn_tx_hash: d,
+                    ..Default::default()
+                }),
+            )],
+        );
+        // the first arb only shares a transaction with the jit
+        sorted_mev.insert(
+            MevType::AtomicArb,
+            [d, z]
+                .map(|tx_hash| {
+                    bundle(
+                        MevType::AtomicArb,
+                        BundleData::AtomicArb(AtomicArb { tx_hash, ..Default::default() }),
+                    )
+                })
+                .to_vec(),
+        );
+
+        let compose: ComposeFunction = Box::new(|components: &[Bundle]| {
+            assert_eq!(
+                components.iter().map(|c| c.header.mev_type).collect_vec(),
+                vec![MevType::Sandwich, MevType::Jit, MevType::AtomicArb]
+            );
+            Some(Bundle {
+                header: BundleHeader { mev_type: MevType::JitSandwich, ..Default::default() },
+                data:   BundleData::default(),
+            })
+        });
+        try_compose_mev(
+            &MevType::JitSandwich,
+            &[MevType::Sandwich, MevType::Jit, MevType::AtomicArb],
+            &compose,
+            &mut sorted_mev,
+        );
+
+        assert_eq!(sorted_mev[&MevType::JitSandwich].len(), 1);
+        assert!(sorted_mev[&MevType::Sandwich].is_empty());
+        assert!(sorted_mev[&MevType::Jit].is_empty());
+        assert_eq!(
+            sorted_mev[&MevType::AtomicArb]
+                .iter()
+                .map(|arb| arb.data.mev_transaction_hashes())
+                .collect_vec(),
+            vec![vec![z]]
+        );
+    }
+
+    #[test]
+    fn test_merge_chunk_bundles() {
+        use brontes_types::mev::{AtomicArb, BundleData, BundleHeader, Sandwich};
+
+        let [a, b, c, d] = [1, 2, 3, 4].map(B256::repeat_byte);
+        let sandwich = |victims: Vec<B256>| Bundle {
+            header: BundleHeader { mev_type: MevType::Sandwich, ..Default::default() },
+            data:   BundleData::Sandwich(Sandwich {
+                frontrun_tx_hash: vec![a],
+                victim_swaps_tx_hashes: vec![victims],
+                backrun_tx_hash: d,
+                ..Default::default()
+            }),
+        };
+        let arb = Bundle {
+            header: BundleHeader { mev_type: MevType::AtomicArb, ..Default::default() },
+            data:   BundleData::AtomicArb(AtomicArb { tx_hash: a, ..Default::default() }),
+        };
+
+        // the first chunk cut the sandwich short, the second one found it whole
+        let merged = merge_chunk_bundles(vec![
+            sandwich(vec![b]),
+            arb.clone(),
+            sandwich(vec![b, c]),
+            sandwich(vec![b, c]),
+        ]);
+
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].data.mev_transaction_hashes(), vec![a, b, c, d]);
+        assert_eq!(merged[1].header.mev_type, MevType::AtomicArb);
+    }
 }
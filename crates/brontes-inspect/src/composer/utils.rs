@@ -1,16 +1,34 @@
 use std::sync::Arc;
 
-use alloy_primitives::{Address, FixedBytes};
+use alloy_primitives::{Address, FixedBytes, B256};
 use brontes_types::{
-    db::{builder::BuilderInfo, metadata::Metadata, traits::LibmdbxReader},
-    mev::{Bundle, Mev, MevBlock, MevCount, MevType, PossibleMevCollection},
-    normalized_actions::Action,
-    tree::BlockTree,
-    FastHashMap, GasDetails, ToFloatNearest, ToScaledRational, TreeSearchBuilder,
+    db::{
+        builder::BuilderInfo,
+        bundle_tags::{self, BundleTag, TAG_FAILED_LEG, TAG_FLASHLOAN, TAG_SANITIZED_QUOTES},
+        dex::PriceAt,
+        metadata::Metadata,
+        searcher::ArbRecord,
+        traits::LibmdbxReader,
+    },
+    mev::{
+        AtomicArbType, Bundle, BundleData, CapitalSource, CapitalUsage, Mev, MevBlock, MevCount,
+        MevType, PossibleMevCollection, ProtocolFee, ValueSplit,
+    },
+    normalized_actions::{Action, ActionConfidence, NormalizedAction, NormalizedSwap, TokenAmount},
+    pair::Pair,
+    structured_trace::TraceActions,
+    tree::{BlockTree, Node, Root},
+    FastHashMap, FastHashSet, GasDetails, Protocol, ToFloatNearest, ToScaledRational,
+    TreeSearchBuilder,
+};
+use itertools::Itertools;
+use malachite::{
+    num::{basic::traits::Zero, conversion::traits::RoundingFrom},
+    rounding_modes::RoundingMode,
+    Rational,
 };
-use malachite::{num::conversion::traits::RoundingFrom, rounding_modes::RoundingMode};
 
-use crate::composer::FilterFn;
+use crate::{composer::FilterFn, shared_utils::SharedInspectorUtils};
 
 pub(crate) fn build_mev_header<DB: LibmdbxReader>(
     metadata: &Arc<Metadata>,
@@ -29,6 +47,7 @@ pub(crate) fn build_mev_header<DB: LibmdbxReader>(
 
     let eth_price = metadata.get_eth_price(quote_token);
 
+    let pre_merge = tree.is_pre_merge();
     let pre_processing = pre_process(tree.clone());
 
     let block_pnl = calculate_builder_profit(tree, metadata, orchestra_data, &pre_processing);
@@ -50,6 +69,8 @@ pub(crate) fn build_mev_header<DB: LibmdbxReader>(
         .try_fetch_builder_info(pre_processing.builder_address)
         .unwrap()
         .and_then(|b| b.name);
+    // a mined block has no slot, whatever the metadata source says
+    let beacon = metadata.beacon.as_ref().filter(|_| !pre_merge);
 
     MevBlock {
         block_hash: metadata.block_hash.into(),
@@ -77,9 +98,519 @@ pub(crate) fn build_mev_header<DB: LibmdbxReader>(
         proposer_fee_recipient,
         proposer_mev_reward,
         proposer_profit_usd,
+        slot: beacon.map(|beacon| beacon.slot),
+        proposer_index: beacon.map(|beacon| beacon.proposer_index),
+        proposer_pubkey: beacon.and_then(|beacon| beacon.proposer_pubkey),
+        missed_slots: beacon.map(|beacon| beacon.missed_slot_count()),
+        pre_merge,
         total_mev_profit_usd,
         possible_mev,
+        skipped_inspectors: vec![],
+    }
+}
+
+/// Sets the id of each bundle. Done once composition and deduplication are
+/// over, as composing changes the type and transactions of a bundle.
+pub(crate) fn set_bundle_ids(bundles: &mut [Bundle]) {
+    bundles
+        .iter_mut()
+        .for_each(|bundle| bundle.header.bundle_id = bundle.compute_bundle_id());
+}
+
+/// Sets the classification confidence of the actions in each bundle's
+/// transactions on the bundle header.
+pub(crate) fn set_action_confidence(tree: Arc<BlockTree<Action>>, bundles: &mut [Bundle]) {
+    bundles.iter_mut().for_each(|bundle| {
+        let tx_hashes = bundle.data.mev_transaction_hashes();
+        let confidences = tree
+            .tx_roots
+            .iter()
+            .filter(|root| tx_hashes.contains(&root.tx_hash))
+            .flat_map(|root| root.action_confidences());
+
+        bundle
+            .header
+            .set_action_confidence(ActionConfidence::from_confidences(confidences));
+    });
+}
+
+/// Annotates bundles that landed as part of a builder-merged submission.
+///
+/// Relays don't expose the bundles a builder received, so submissions are
+/// approximated by the runs of consecutive private transactions in the block.
+/// A run that contains bundles of more than one searcher was merged by the
+/// builder. Every bundle in such a run is flagged and the other transactions of
+/// the run, those of the other bundles included, are recorded as co-bundled.
+pub(crate) fn annotate_merged_submissions(tree: &BlockTree<Action>, bundles: &mut [Bundle]) {
+    let block_txs = tree
+        .tx_roots
+        .iter()
+        .map(|root| (root.tx_hash, root.private))
+        .collect::<Vec<_>>();
+
+    annotate_private_runs(&block_txs, bundles);
+}
+
+fn annotate_private_runs(block_txs: &[(B256, bool)], bundles: &mut [Bundle]) {
+    let bundle_txs = bundles
+        .iter()
+        .map(|bundle| {
+            bundle
+                .data
+                .mev_transaction_hashes()
+                .into_iter()
+                .collect::<FastHashSet<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    block_txs
+        .split(|(_, private)| !private)
+        .filter(|run| run.len() > 1)
+        .for_each(|run| {
+            let in_run = bundle_txs
+                .iter()
+                .enumerate()
+                .filter(|(_, txs)| run.iter().any(|(tx, _)| txs.contains(tx)))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+
+            let searchers = in_run
+                .iter()
+                .map(|&i| bundles[i].header.eoa)
+                .collect::<FastHashSet<_>>();
+            if searchers.len() < 2 {
+                return
+            }
+
+            for i in in_run {
+                let header = &mut bundles[i].header;
+                header.merged_submission = true;
+                header.co_bundled_txs.extend(
+                    run.iter()
+                        .map(|(tx, _)| *tx)
+                        .filter(|tx| !bundle_txs[i].contains(tx)),
+                );
+            }
+        });
+}
+
+/// Splits the value each bundle extracted between the searcher, the builder,
+/// the liquidity providers of the pools the searcher swapped through and the
+/// protocols that took a fee on the bundle's swaps.
+pub(crate) fn set_value_split<DB: LibmdbxReader>(
+    tree: Arc<BlockTree<Action>>,
+    metadata: &Arc<Metadata>,
+    quote_token: Address,
+    db: &'static DB,
+    bundles: &mut [Bundle],
+) {
+    let utils = SharedInspectorUtils::new(quote_token, db, None);
+
+    bundles.iter_mut().for_each(|bundle| {
+        let usd_value = usd_pricer(&utils, bundle.header.tx_index as usize, metadata);
+
+        let mut lp_fees: FastHashMap<Protocol, f64> = FastHashMap::default();
+        for swap in bundle.data.searcher_swaps() {
+            let Some(rate) =
+                swap.protocol
+                    .lp_fee_rate(swap.pool, swap.token_in.address, swap.token_out.address)
+            else {
+                continue
+            };
+            let fee = TokenAmount::from_scaled(&swap.token_in, &swap.amount_in * rate);
+            *lp_fees.entry(swap.protocol).or_default() += usd_value(fee);
+        }
+
+        let protocol_fees_usd = tree
+            .clone()
+            .collect_txes(
+                &bundle.data.mev_transaction_hashes(),
+                TreeSearchBuilder::default()
+                    .with_actions([Action::is_swap_with_fee, Action::is_nested_action]),
+            )
+            .flat_map(|actions| {
+                utils
+                    .flatten_nested_actions(actions.into_iter(), &Action::is_swap_with_fee)
+                    .collect::<Vec<_>>()
+            })
+            .filter_map(|action| match action {
+                Action::SwapWithFee(swap) => {
+                    Some(usd_value(TokenAmount::from_scaled(&swap.fee_token, swap.fee_amount)))
+                }
+                _ => None,
+            })
+            .sum();
+
+        bundle.header.value_split = ValueSplit {
+            searcher_usd: bundle.header.profit_usd,
+            builder_usd: bundle.header.bribe_usd,
+            lp_fees: lp_fees
+                .into_iter()
+                .map(|(protocol, usd)| ProtocolFee { protocol, usd })
+                .sorted_by(|a, b| b.usd.total_cmp(&a.usd))
+                .collect(),
+            protocol_fees_usd,
+        };
+    });
+}
+
+/// Classifies where the searcher of each bundle got the capital it moved. A
+/// bundle that took a flash loan borrowed it, one that swapped out of a v2
+/// pair and paid for it in the pair's callback flash-swapped it, anything else
+/// is the searcher's own inventory.
+pub(crate) fn set_capital_usage<DB: LibmdbxReader>(
+    tree: Arc<BlockTree<Action>>,
+    metadata: &Arc<Metadata>,
+    quote_token: Address,
+    db: &'static DB,
+    bundles: &mut [Bundle],
+) {
+    let utils = SharedInspectorUtils::new(quote_token, db, None);
+
+    bundles.iter_mut().for_each(|bundle| {
+        let usd_value = usd_pricer(&utils, bundle.header.tx_index as usize, metadata);
+        let tx_hashes = bundle.data.mev_transaction_hashes();
+        let swaps = bundle.data.searcher_swaps();
+
+        let (mut borrowed_usd, mut flashloan_fee_usd) = (0.0, 0.0);
+        tree.clone()
+            .collect_txes(
+                &tx_hashes,
+                TreeSearchBuilder::default().with_action(Action::is_flash_loan),
+            )
+            .flatten()
+            .for_each(|action| {
+                let Action::FlashLoan(loan) = action else { return };
+                for (i, asset) in loan.assets.iter().enumerate() {
+                    if let Some(amount) = loan.amounts.get(i) {
+                        borrowed_usd += usd_value(TokenAmount::from_scaled(asset, amount.clone()));
+                    }
+                    if let Some(fee) = loan.fees_paid.get(i) {
+                        flashloan_fee_usd +=
+                            usd_value(TokenAmount::from_scaled(asset, fee.clone()));
+                    }
+                }
+            });
+
+        let flash_swapped_usd: f64 = swaps
+            .iter()
+            .filter(|swap| {
+                tx_hashes
+                    .iter()
+                    .filter_map(|tx| tree.get_root(*tx))
+                    .any(|root| is_flash_swap(root, &root.head, swap))
+            })
+            .map(|swap| {
+                usd_value(TokenAmount::from_scaled(&swap.token_out, swap.amount_out.clone()))
+            })
+            .sum();
+
+        let (source, capital_deployed_usd) = if borrowed_usd > 0.0 || flashloan_fee_usd > 0.0 {
+            (CapitalSource::Flashloan, borrowed_usd)
+        } else if flash_swapped_usd > 0.0 {
+            (CapitalSource::FlashSwap, flash_swapped_usd)
+        } else {
+            (CapitalSource::Inventory, inventory_outlay(&swaps).into_iter().map(&usd_value).sum())
+        };
+        let volume_usd = swaps
+            .iter()
+            .map(|swap| usd_value(TokenAmount::from_scaled(&swap.token_in, swap.amount_in.clone())))
+            .sum();
+
+        bundle.header.capital = CapitalUsage::new(
+            source,
+            flashloan_fee_usd,
+            capital_deployed_usd,
+            bundle.header.bribe_usd,
+            volume_usd,
+        );
+    });
+}
+
+/// Relabels the atomic arbs that the searcher's arb history shows to be
+/// inventory rebalancing as [`MevType::Rebalance`] bundles, see
+/// [`ArbHistory`]. Returns how many were relabeled, they are no longer counted
+/// as arbs.
+///
+/// [`ArbHistory`]: brontes_types::db::searcher::ArbHistory
+pub(crate) fn reclassify_rebalances<DB: LibmdbxReader>(
+    db: &'static DB,
+    bundles: &mut [Bundle],
+) -> u64 {
+    let mut rebalances = 0;
+    for bundle in bundles.iter_mut() {
+        let Some(record) = ArbRecord::from_bundle(bundle) else { continue };
+        let Ok((eoa_info, contract_info)) =
+            db.try_fetch_searcher_info(bundle.header.eoa, bundle.header.mev_contract)
+        else {
+            continue
+        };
+
+        // the contract's history is the strategy's, the eoa may run several
+        let Some(history) = contract_info.or(eoa_info).map(|info| info.arb_history) else {
+            continue
+        };
+        if !history.is_rebalance(&record) {
+            continue
+        }
+
+        // the bundle id stays the one of the arb, so overrides made on it keep
+        // applying whichever way the history leans on a rerun
+        if let BundleData::AtomicArb(arb) = &mut bundle.data {
+            arb.arb_type = AtomicArbType::Rebalance;
+            bundle.header.mev_type = MevType::Rebalance;
+            rebalances += 1;
+        }
     }
+
+    rebalances
+}
+
+/// Takes the searcher's gains in tokens scored as untradeable spam out of the
+/// profit of each bundle, see [`SpamAssessment`]. A bundle whose profit is
+/// only in such tokens is left with a loss, as it received nothing that can be
+/// sold. Runs before the value split and the block's totals are computed, so
+/// they're made from the lowered profit.
+///
+/// [`SpamAssessment`]: brontes_types::db::token_metadata::SpamAssessment
+pub(crate) fn down_rank_spam_profit<DB: LibmdbxReader>(db: &'static DB, bundles: &mut [Bundle]) {
+    down_rank_untradeable_profit(bundles, |token| {
+        db.try_fetch_token_metadata(token)
+            .ok()
+            .flatten()
+            .is_some_and(|metadata| metadata.spam.is_untradeable())
+    })
+}
+
+fn down_rank_untradeable_profit(bundles: &mut [Bundle], is_untradeable: impl Fn(Address) -> bool) {
+    let mut untradeable: FastHashMap<Address, bool> = FastHashMap::default();
+    for bundle in bundles.iter_mut() {
+        let searcher = [Some(bundle.header.eoa), bundle.header.mev_contract];
+        let spam_profit_usd = bundle
+            .header
+            .balance_deltas
+            .iter()
+            .flat_map(|tx| tx.address_deltas.iter())
+            .filter(|deltas| searcher.contains(&Some(deltas.address)))
+            .flat_map(|deltas| deltas.token_deltas.iter())
+            .filter(|delta| delta.usd_value > 0.0)
+            .filter(|delta| {
+                *untradeable
+                    .entry(delta.token.address)
+                    .or_insert_with(|| is_untradeable(delta.token.address))
+            })
+            .map(|delta| delta.usd_value)
+            .sum::<f64>();
+
+        if spam_profit_usd == 0.0 {
+            continue
+        }
+        tracing::debug!(
+            tx_hash=?bundle.header.tx_hash,
+            profit_usd=bundle.header.profit_usd,
+            spam_profit_usd,
+            "bundle profit includes spam tokens"
+        );
+        bundle.header.spam_profit_usd = spam_profit_usd;
+        bundle.header.profit_usd -= spam_profit_usd;
+    }
+}
+
+/// Drops the bundles matching a rule of the suppression list, see
+/// [`SuppressionList`]. Suppressed bundles are dropped before composition so
+/// they can't be merged into or dedupe other bundles. Rules take effect from
+/// the next block without a restart
+///
+/// [`SuppressionList`]: brontes_types::db::suppression::SuppressionList
+pub(crate) fn suppress_bundles<DB: LibmdbxReader>(
+    db: &'static DB,
+    sorted_mev: &mut FastHashMap<MevType, Vec<Bundle>>,
+) {
+    let suppressions = match db.fetch_suppression_list() {
+        Ok(suppressions) => suppressions,
+        Err(e) => {
+            tracing::warn!(error=%e, "failed to read the suppression list");
+            return
+        }
+    };
+    if suppressions.is_empty() {
+        return
+    }
+
+    for bundles in sorted_mev.values_mut() {
+        for (rule, count) in suppressions.apply(bundles) {
+            tracing::debug!(rule, count, "suppressed bundles");
+        }
+    }
+}
+
+/// Drops the bundles outside of the scope of the block's action filter, see
+/// [`ActionFilter`]. A bundle is matched on every action of its transactions,
+/// so it's kept or dropped whole rather than found from part of its actions.
+/// Runs before composition so a dropped bundle can't be merged into or dedupe
+/// other bundles
+///
+/// [`ActionFilter`]: brontes_types::action_filter::ActionFilter
+pub(crate) fn scope_bundles(
+    tree: Arc<BlockTree<Action>>,
+    metadata: &Metadata,
+    sorted_mev: &mut FastHashMap<MevType, Vec<Bundle>>,
+) {
+    let Some(filter) = metadata.live_config.action_filter.as_ref() else { return };
+
+    for bundles in sorted_mev.values_mut() {
+        bundles.retain(|bundle| {
+            let actions = tree
+                .clone()
+                .collect_txes(
+                    &bundle.data.mev_transaction_hashes(),
+                    TreeSearchBuilder::default().with_action(Action::is_classified),
+                )
+                .flatten()
+                .collect::<Vec<_>>();
+
+            filter.allows_actions(&actions)
+        });
+    }
+}
+
+/// Prices token amounts in usd at the average dex price of the bundle's
+/// transaction, an amount without a price is worth nothing
+fn usd_pricer<'a, DB: LibmdbxReader>(
+    utils: &'a SharedInspectorUtils<'_, DB>,
+    tx_index: usize,
+    metadata: &'a Arc<Metadata>,
+) -> impl Fn(TokenAmount) -> f64 + 'a {
+    move |amount| {
+        utils
+            .get_token_value_dex(tx_index, PriceAt::Average, &amount, metadata)
+            .map(|value| value.to_float())
+            .unwrap_or_default()
+    }
+}
+
+/// The amounts the searcher paid into its swaps out of its own balance. A
+/// swap paid for with the output of an earlier swap in the bundle, the later
+/// legs of an arb or a sandwich's back run, only draws on inventory for what
+/// the earlier swaps didn't cover
+fn inventory_outlay(swaps: &[NormalizedSwap]) -> Vec<TokenAmount> {
+    let mut received: FastHashMap<Address, Rational> = FastHashMap::default();
+    let mut outlay = vec![];
+    for swap in swaps {
+        let covered = received
+            .entry(swap.token_in.address)
+            .or_insert(Rational::ZERO);
+        if swap.amount_in > *covered {
+            outlay.push(TokenAmount::from_scaled(&swap.token_in, &swap.amount_in - &*covered));
+            *covered = Rational::ZERO;
+        } else {
+            *covered -= &swap.amount_in;
+        }
+        *received
+            .entry(swap.token_out.address)
+            .or_insert(Rational::ZERO) += &swap.amount_out;
+    }
+
+    outlay
+}
+
+/// `uniswapV2Call(address,uint256,uint256,bytes)` and
+/// `pancakeCall(address,uint256,uint256,bytes)`, the callbacks a v2 pair makes
+/// into the recipient of a swap called with data
+const V2_SWAP_CALLBACKS: [[u8; 4]; 2] = [[0x10, 0xd1, 0xe8, 0x5c], [0x84, 0x80, 0x08, 0x12]];
+
+/// Whether the swap is on a v2 pair that called back into its recipient, so
+/// the recipient got the tokens out before paying for them
+fn is_flash_swap(root: &Root<Action>, node: &Node, swap: &NormalizedSwap) -> bool {
+    if node.index != swap.trace_index {
+        return node
+            .inner
+            .iter()
+            .any(|inner| is_flash_swap(root, inner, swap))
+    }
+
+    node.inner
+        .iter()
+        .filter(|inner| inner.address == swap.pool)
+        .filter_map(|inner| root.data_store.get_ref(inner.data))
+        .flatten()
+        .any(|action| {
+            let Action::Unclassified(trace) = action else { return false };
+            trace.get_to_address() == swap.recipient
+                && trace
+                    .get_calldata()
+                    .get(..4)
+                    .is_some_and(|selector| V2_SWAP_CALLBACKS.iter().any(|cb| cb == selector))
+        })
+}
+
+/// Whether a call the searcher made in the transaction reverted while the
+/// transaction went through. Reverts deeper in the call stack, a token or a
+/// router catching a failed call, aren't the searcher's legs
+fn has_failed_leg(root: &Root<Action>, searcher: &[Address]) -> bool {
+    fn any_node(node: &Node, f: &impl Fn(&Node) -> bool) -> bool {
+        node.inner
+            .iter()
+            .any(|inner| f(inner) || any_node(inner, f))
+    }
+    let reverted = |node: &Node| {
+        root.data_store
+            .get_ref(node.data)
+            .is_some_and(|actions| actions.iter().any(Action::is_revert))
+    };
+
+    !reverted(&root.head)
+        && any_node(&root.head, &|node| searcher.contains(&node.address) && reverted(node))
+}
+
+/// Detects the tags of the block's bundles. On top of the tags that follow
+/// from the bundle itself, a bundle is tagged when any of its transactions took
+/// a flash loan or had one of the searcher's calls revert, and cex-dex bundles
+/// are tagged when they swapped a pair that had cex quotes rejected.
+pub(crate) fn detect_bundle_tags(
+    tree: &BlockTree<Action>,
+    metadata: &Metadata,
+    bundles: &[Bundle],
+) -> Vec<BundleTag> {
+    bundles
+        .iter()
+        .flat_map(|bundle| {
+            let tx_hashes = bundle.data.mev_transaction_hashes();
+            let any_tx_contains = |f: fn(&Action) -> bool| {
+                tx_hashes
+                    .iter()
+                    .any(|tx| tree.tx_must_contain_action(*tx, f).unwrap_or_default())
+            };
+
+            let mut tags = bundle_tags::detect_bundle_tags(bundle);
+            if any_tx_contains(Action::is_flash_loan) {
+                tags.push(TAG_FLASHLOAN);
+            }
+            let searcher = [Some(bundle.header.eoa), bundle.header.mev_contract]
+                .into_iter()
+                .flatten()
+                .collect_vec();
+            if tx_hashes
+                .iter()
+                .filter_map(|tx| tree.get_root(*tx))
+                .any(|root| has_failed_leg(root, &searcher))
+            {
+                tags.push(TAG_FAILED_LEG);
+            }
+            if matches!(bundle.data, BundleData::CexDexQuote(_))
+                && bundle.data.searcher_swaps().iter().any(|swap| {
+                    metadata
+                        .cex_quote_sanity
+                        .is_flagged(&Pair(swap.token_in.address, swap.token_out.address))
+                })
+            {
+                tags.push(TAG_SANITIZED_QUOTES);
+            }
+
+            tags.into_iter()
+                .map(|tag| BundleTag::detected(bundle.header.tx_hash, tag))
+        })
+        .collect()
 }
 
 /// Sorts the given MEV data by type.
@@ -186,12 +717,15 @@ fn update_mev_count(mev_count: &mut MevCount, mev_type: MevType, count: u64) {
         MevType::CexDexQuotes => mev_count.cex_dex_quote_count = Some(count),
         MevType::JitCexDex => mev_count.jit_cex_dex_count = Some(count),
         MevType::CexDexRfq => mev_count.cex_dex_rfq_count = Some(count),
-        MevType::Jit => mev_count.jit_count = Some(count),
+        // extended jit is counted with jit
+        MevType::Jit | MevType::JitExtended => {
+            mev_count.jit_count = Some(mev_count.jit_count.unwrap_or_default() + count)
+        }
         MevType::JitSandwich => mev_count.jit_sandwich_count = Some(count),
         MevType::AtomicArb => mev_count.atomic_backrun_count = Some(count),
         MevType::Liquidation => mev_count.liquidation_count = Some(count),
         MevType::SearcherTx => mev_count.searcher_tx_count = Some(count),
-        MevType::Unknown => (),
+        MevType::Rebalance | MevType::Unknown => (),
     }
 }
 
@@ -250,6 +784,27 @@ pub fn calculate_builder_profit(
     let builder_payments: i128 =
         (pre_processing.total_priority_fee + pre_processing.total_bribe) as i128;
 
+    // Before the merge the coinbase is the miner. It keeps every payment and has
+    // no proposer to pay, and its transfers out of the coinbase are pool payouts
+    // rather than a bid or a sponsorship
+    if tree.is_pre_merge() {
+        let (mev_searching_profit, searcher_tip) = metadata
+            .builder_info
+            .as_ref()
+            .map(|builder_info| calculate_mev_searching_profit(bundles, builder_info))
+            .unwrap_or_default();
+
+        return BlockPnL::new(
+            builder_payments,
+            0,
+            mev_searching_profit,
+            None,
+            None,
+            searcher_tip,
+            false,
+        )
+    }
+
     let proposer_mev_reward;
     let proposer_fee_recipient;
     let bid_adjusted;
@@ -346,7 +901,7 @@ fn calculate_mev_searching_profit(bundles: &[Bundle], builder_info: &BuilderInfo
                     .unwrap_or(false)
         })
         .fold((0.0, 0), |(accumulated_profit, accumulated_gas), bundle| {
-            let profit = if bundle.mev_type() != MevType::SearcherTx {
+            let profit = if !matches!(bundle.mev_type(), MevType::SearcherTx | MevType::Rebalance) {
                 bundle.header.profit_usd
             } else {
                 0.0
@@ -411,8 +966,12 @@ pub struct BlockPreprocessing {
 }
 
 /// Pre-processes the block data for the Builder PNL calculation
+///
+/// Before the merge, the gas the miner pays on its own transactions, like pool
+/// payouts, goes back to itself so it isn't counted as a priority fee.
 pub(crate) fn pre_process(tree: Arc<BlockTree<Action>>) -> BlockPreprocessing {
     let builder_address = tree.header.beneficiary;
+    let pre_merge = tree.is_pre_merge();
 
     let (gas_details_by_address, total_gas_used, total_priority_fee, total_bribe) =
         tree.tx_roots.iter().fold(
@@ -428,7 +987,11 @@ pub(crate) fn pre_process(tree: Arc<BlockTree<Action>>) -> BlockPreprocessing {
                     .or_insert_with(|| *gas_details_item);
 
                 let gas_used = gas_details_item.gas_used;
-                let priority_fee = gas_details_item.priority_fee;
+                let priority_fee = if pre_merge && address == builder_address {
+                    0
+                } else {
+                    gas_details_item.priority_fee
+                };
                 let coinbase_transfer = gas_details_item.coinbase_transfer.unwrap_or(0);
 
                 (
@@ -452,17 +1015,19 @@ pub(crate) fn pre_process(tree: Arc<BlockTree<Action>>) -> BlockPreprocessing {
 /// Calculates the Mev gas & profit stats for the block
 ///
 /// Returns the total priority fee, tips & profit of mev bundles in the block
-/// Ignores the profit of SearcherTx bundles as they are not considered MEV.
+/// Ignores the profit of SearcherTx and Rebalance bundles as they are not
+/// considered MEV.
 fn calculate_block_mev_stats(orchestra_data: &[Bundle], base_fee: u128) -> (u128, f64, u128) {
     orchestra_data.iter().fold(
         (0u128, 0.0, 0u128),
         |(total_fee_paid, total_profit_usd, mev_bribe), bundle| {
             let fee_paid = bundle.data.total_priority_fee_paid(base_fee);
-            let profit_usd = if bundle.mev_type() != MevType::SearcherTx {
-                bundle.header.profit_usd
-            } else {
-                0.0
-            };
+            let profit_usd =
+                if !matches!(bundle.mev_type(), MevType::SearcherTx | MevType::Rebalance) {
+                    bundle.header.profit_usd
+                } else {
+                    0.0
+                };
             (
                 total_fee_paid + fee_paid,
                 total_profit_usd + profit_usd,
@@ -471,3 +1036,159 @@ fn calculate_block_mev_stats(orchestra_data: &[Bundle], base_fee: u128) -> (u128
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::{
+        db::metadata::BlockMetadata,
+        mev::{BundleData, BundleHeader, Sandwich},
+    };
+
+    use super::*;
+
+    fn sandwich(eoa: Address, frontrun: u8, backrun: u8) -> Bundle {
+        Bundle {
+            header: BundleHeader { eoa, ..Default::default() },
+            data:   BundleData::Sandwich(Sandwich {
+                frontrun_tx_hash: vec![B256::repeat_byte(frontrun)],
+                backrun_tx_hash: B256::repeat_byte(backrun),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn test_annotate_merged_submissions() {
+        // txs 1..=5 form one private run, tx 6 is public and 7..=8 are private
+        let block_txs = (1..=8u8)
+            .map(|i| (B256::repeat_byte(i), i != 6))
+            .collect::<Vec<_>>();
+
+        let mut bundles = vec![
+            sandwich(Address::repeat_byte(1), 1, 3),
+            sandwich(Address::repeat_byte(2), 4, 5),
+            sandwich(Address::repeat_byte(3), 7, 8),
+        ];
+        annotate_private_runs(&block_txs, &mut bundles);
+
+        assert!(bundles[0].header.merged_submission);
+        assert_eq!(bundles[0].header.co_bundled_txs, [2, 4, 5].map(B256::repeat_byte).to_vec());
+        assert!(bundles[1].header.merged_submission);
+        assert!(!bundles[2].header.merged_submission);
+        assert!(bundles[2].header.co_bundled_txs.is_empty());
+    }
+
+    #[test]
+    fn test_down_rank_untradeable_profit() {
+        use brontes_types::{
+            db::token_info::TokenInfoWithAddress,
+            mev::{AddressBalanceDeltas, TokenBalanceDelta, TransactionAccounting},
+        };
+
+        let searcher = Address::repeat_byte(1);
+        let victim = Address::repeat_byte(2);
+        let (weth, spam) = (Address::repeat_byte(0xee), Address::repeat_byte(0x5a));
+        let delta = |token, usd_value| TokenBalanceDelta {
+            token: TokenInfoWithAddress { address: token, ..Default::default() },
+            usd_value,
+            ..Default::default()
+        };
+        let deltas = |address, token_deltas| AddressBalanceDeltas {
+            address,
+            token_deltas,
+            ..Default::default()
+        };
+
+        let mut bundle = sandwich(searcher, 1, 2);
+        bundle.header.profit_usd = 150.0;
+        bundle.header.balance_deltas = vec![TransactionAccounting {
+            address_deltas: vec![
+                deltas(searcher, vec![delta(weth, 50.0), delta(spam, 100.0), delta(spam, -10.0)]),
+                // the spam the victim received isn't the searcher's profit
+                deltas(victim, vec![delta(spam, 30.0)]),
+            ],
+            ..Default::default()
+        }];
+        let mut clean = sandwich(searcher, 3, 4);
+        clean.header.profit_usd = 20.0;
+        clean.header.balance_deltas = vec![TransactionAccounting {
+            address_deltas: vec![deltas(searcher, vec![delta(weth, 20.0)])],
+            ..Default::default()
+        }];
+        let mut bundles = vec![bundle, clean];
+
+        down_rank_untradeable_profit(&mut bundles, |token| token == spam);
+
+        assert_eq!(bundles[0].header.spam_profit_usd, 100.0);
+        assert_eq!(bundles[0].header.profit_usd, 50.0);
+        assert_eq!(bundles[1].header.spam_profit_usd, 0.0);
+        assert_eq!(bundles[1].header.profit_usd, 20.0);
+        // the block's total is made from the lowered profits
+        assert_eq!(calculate_block_mev_stats(&bundles, 0).1, 70.0);
+    }
+
+    #[test]
+    fn test_pre_merge_builder_profit() {
+        let miner = Address::repeat_byte(1);
+        let tree = |difficulty: u64| {
+            Arc::new(BlockTree::<Action>::new(
+                reth_primitives::Header {
+                    beneficiary: miner,
+                    difficulty: alloy_primitives::U256::from(difficulty),
+                    ..Default::default()
+                },
+                0,
+            ))
+        };
+        // relay data for a mined block is bogus and ignored
+        let metadata = Arc::new(Metadata {
+            block_metadata: BlockMetadata {
+                proposer_fee_recipient: Some(Address::repeat_byte(2)),
+                proposer_mev_reward: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        let mined = tree(1);
+        assert!(mined.is_pre_merge());
+        let pnl = calculate_builder_profit(mined.clone(), &metadata, &[], &pre_process(mined));
+        assert_eq!(pnl.mev_reward, None);
+        assert_eq!(pnl.proposer_fee_recipient, None);
+        assert_eq!(pnl.builder_eth_profit, 0);
+
+        assert!(!tree(0).is_pre_merge());
+    }
+
+    #[test]
+    fn test_inventory_outlay() {
+        use brontes_types::db::token_info::TokenInfoWithAddress;
+
+        let token = |byte| TokenInfoWithAddress {
+            address: Address::repeat_byte(byte),
+            ..Default::default()
+        };
+        let swap = |token_in: u8, amount_in: u64, token_out: u8, amount_out: u64| NormalizedSwap {
+            token_in: token(token_in),
+            amount_in: Rational::from(amount_in),
+            token_out: token(token_out),
+            amount_out: Rational::from(amount_out),
+            ..Default::default()
+        };
+
+        // a cycle only draws on inventory for its first leg and for what the
+        // earlier legs came short of
+        let outlay = inventory_outlay(&[swap(1, 10, 2, 20), swap(2, 25, 3, 5), swap(3, 5, 1, 12)]);
+        assert_eq!(
+            outlay,
+            vec![
+                TokenAmount::from_scaled(&token(1), Rational::from(10)),
+                TokenAmount::from_scaled(&token(2), Rational::from(5)),
+            ]
+        );
+
+        // unrelated swaps are each paid out of inventory
+        let outlay = inventory_outlay(&[swap(1, 10, 2, 20), swap(3, 7, 4, 1)]);
+        assert_eq!(outlay.len(), 2);
+    }
+}
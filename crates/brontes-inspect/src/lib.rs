@@ -40,22 +40,12 @@
 //!
 //! ## Composer
 //!
-//! The `Composer` is a special type of inspector that combines the results of
-//! individual inspectors to identify more complex MEV strategies. It takes an
-//! array of individual inspectors and a `BlockTree` and `Metadata` as input,
-//! running each inspector on the block and collecting their results.
-//!
-//! ```ignore
-//! pub struct Composer<'a, const N: usize> {
-//!     inspectors_execution: InspectorFut<'a>,
-//!     pre_processing:       BlockPreprocessing,
-//! }
-//! ```
-//!
-//! The `Composer` uses  to define a filter that
-//! orders results from individual inspectors. This ensures that lower-level
-//! actions are composed before higher-level actions, which could affect the
-//! composition.
+//! The [`composer`] combines the results of individual inspectors to identify
+//! more complex MEV strategies. It takes the inspectors and the block data as
+//! input, runs each inspector on the block and then composes and deduplicates
+//! their results, using filters that order the results so lower-level actions
+//! are composed before higher-level ones. The inspectors are passed as any
+//! handle that derefs to them, so it can be embedded without leaking them.
 
 pub mod composer;
 pub mod discovery;
@@ -77,11 +67,11 @@ use brontes_types::{
     },
     mev::{Bundle, BundleData},
     normalized_actions::Action,
-    tree::BlockTree,
+    tree::{BlockActionSummary, BlockTree},
     MultiBlockData,
 };
 use cex_dex::{markout::CexDexMarkoutInspector, quotes::CexDexQuotesInspector};
-use jit::JitCexDex;
+use jit::{JitCexDex, JitExtendedInspector};
 use liquidations::LiquidationInspector;
 use sandwich::SandwichInspector;
 
@@ -96,6 +86,12 @@ pub trait Inspector: Send + Sync {
     }
     /// Used for log span so we know which errors come from which inspector
     fn get_id(&self) -> &str;
+    /// Cheap check on a block's summary of whether the block can contain the
+    /// mev the inspector looks for. If it returns false for every block of the
+    /// window the inspector isn't run
+    fn should_inspect(&self, _summary: &BlockActionSummary) -> bool {
+        true
+    }
     fn inspect_block(&self, data: MultiBlockData) -> Self::Result;
     fn get_quote_token(&self) -> Address;
 }
@@ -112,6 +108,7 @@ pub enum Inspectors {
     SearcherActivity,
     CexDexMarkout,
     JitCexDex,
+    JitExtended,
 }
 
 type DynMevInspector = &'static (dyn Inspector<Result = Vec<Bundle>> + 'static);
@@ -123,6 +120,7 @@ impl Inspectors {
         db: &'static DB,
         cex_exchanges: &[CexExchange],
         trade_config: CexDexTradeConfig,
+        jit_max_hold_blocks: usize,
         metrics: Option<OutlierMetrics>,
     ) -> DynMevInspector {
         match &self {
@@ -167,6 +165,12 @@ impl Inspectors {
                 ),
                 jit:     JitInspector::new(quote_token, db, metrics),
             }) as DynMevInspector,
+            Self::JitExtended => static_object(JitExtendedInspector::new(
+                quote_token,
+                db,
+                jit_max_hold_blocks,
+                metrics,
+            )) as DynMevInspector,
         }
     }
 }
@@ -4,14 +4,15 @@ use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
     constants::{get_stable_type, is_euro_stable, is_gold_stable, is_usd_stable, StableType},
-    db::dex::PriceAt,
+    db::{dex::PriceAt, inspector_artifacts::ArtifactKind},
     mev::{AtomicArb, AtomicArbType, Bundle, BundleData, MevType},
     normalized_actions::{
         accounting::ActionAccounting, Action, NormalizedEthTransfer, NormalizedSwap,
         NormalizedTransfer,
     },
-    BlockData, FastHashSet, IntoZip, MultiBlockData, ToFloatNearest, TreeBase, TreeCollector,
-    TreeSearchBuilder, TxInfo,
+    tree::collect_address_set_for_accounting,
+    BlockActionSummary, BlockData, FastHashSet, IntoZip, MultiBlockData, ToFloatNearest, TreeBase,
+    TreeCollector, TreeSearchBuilder, TxInfo,
 };
 use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
@@ -19,6 +20,8 @@ use reth_primitives::{Address, B256};
 
 use crate::{shared_utils::SharedInspectorUtils, BlockTree, Inspector, Metadata, MAX_PROFIT};
 
+type ArbActions = (Vec<NormalizedSwap>, Vec<NormalizedTransfer>, Vec<NormalizedEthTransfer>);
+
 const MAX_PRICE_DIFF: Rational = Rational::const_from_unsigneds(99995, 100000);
 
 // figure out why
@@ -44,6 +47,10 @@ impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
         "AtomicArb"
     }
 
+    fn should_inspect(&self, summary: &BlockActionSummary) -> bool {
+        summary.possible_swap_txs() > 0
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -52,11 +59,20 @@ impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
         let BlockData { metadata, tree } = data.get_most_recent_block();
 
         let execution = || {
-            tree.clone()
+            let trees = data
+                .per_block_data
+                .iter()
+                .map(|inner| inner.tree.clone())
+                .collect_vec();
+
+            let candidates = tree
+                .clone()
                 .collect_all(TreeSearchBuilder::default().with_actions([
                     Action::is_swap,
+                    Action::is_fill,
                     Action::is_transfer,
                     Action::is_eth_transfer,
+                    Action::is_collect,
                     Action::is_nested_action,
                 ]))
                 .t_full_map(|(tree, v)| {
@@ -74,24 +90,42 @@ impl<DB: LibmdbxReader> Inspector for AtomicArbInspector<'_, DB> {
                 .filter_map(|(info, action)| {
                     let info = info??;
                     let actions = action?;
+                    if self.is_fee_collection(&info, &actions) {
+                        return None
+                    }
 
-                    self.process_swaps(
-                        data.per_block_data
-                            .iter()
-                            .map(|inner| inner.tree.clone())
-                            .collect_vec(),
+                    Some((
                         info,
-                        metadata.clone(),
-                        actions
-                            .into_iter()
-                            .split_actions::<(Vec<_>, Vec<_>, Vec<_>), _>((
-                                Action::try_swaps_merged,
-                                Action::try_transfer,
-                                Action::try_eth_transfer,
-                            )),
-                    )
+                        actions.into_iter().split_actions::<ArbActions, _>((
+                            Action::try_swaps_merged,
+                            Action::try_transfer,
+                            Action::try_eth_transfer,
+                        )),
+                    ))
                 })
-                .collect::<Vec<_>>()
+                .collect_vec();
+
+            // txs of searcher contracts that aren't an arb on their own could be
+            // one leg of an arb that is split across transactions
+            let mut legs = vec![];
+            let mut bundles = candidates
+                .into_iter()
+                .filter_map(|(info, actions)| {
+                    let leg = info
+                        .mev_contract
+                        .is_some()
+                        .then(|| (info.clone(), actions.clone()));
+
+                    let bundle = self.process_swaps(trees.clone(), info, metadata.clone(), actions);
+                    if bundle.is_none() {
+                        legs.extend(leg);
+                    }
+                    bundle
+                })
+                .collect::<Vec<_>>();
+
+            bundles.extend(self.process_cross_tx_legs(&trees, metadata.clone(), legs));
+            bundles
         };
 
         self.utils
@@ -107,22 +141,31 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
         trees: Vec<Arc<BlockTree<Action>>>,
         info: TxInfo,
         metadata: Arc<Metadata>,
-        data: (Vec<NormalizedSwap>, Vec<NormalizedTransfer>, Vec<NormalizedEthTransfer>),
+        data: ArbActions,
     ) -> Option<Bundle> {
         tracing::trace!(?info, "trying atomic");
-        let (mut swaps, transfers, eth_transfers) = data;
+        let (swaps, transfers, eth_transfers) = data;
         let mev_addresses: FastHashSet<Address> = info.collect_address_set_for_accounting();
 
-        let mut ignore_addresses = mev_addresses.clone();
+        let swaps = self.merge_transfer_swaps(&mev_addresses, swaps, &transfers);
+        let swap_count = swaps.len();
+
+        let Some((possible_arb_type, swaps)) =
+            self.is_possible_arb_in_operation(trees.last()?, &info, swaps)
+        else {
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::Rejection,
+                &[info.tx_hash],
+                || format!("{swap_count} swaps don't form an arb"),
+            );
+            return None
+        };
 
-        swaps.iter().for_each(|s| {
-            ignore_addresses.insert(s.pool);
+        metadata.record_artifact(self.get_id(), ArtifactKind::Candidate, &[info.tx_hash], || {
+            format!("{possible_arb_type:?} over {} swaps", swaps.len())
         });
 
-        swaps.extend(self.utils.try_create_swaps(&transfers, ignore_addresses));
-
-        let possible_arb_type = self.is_possible_arb(&swaps)?;
-
         let account_deltas = transfers
             .into_iter()
             .map(Action::from)
@@ -193,6 +236,8 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
                 && is_profitable
                 || self.is_long_tail(&info, requirement_multiplier) & !has_dex_price)
                 .then_some(profit),
+            // only set by the composer, from the searcher's history
+            AtomicArbType::Rebalance => None,
         }?;
 
         // given we have a atomic arb now, we will go and try to find the trigger
@@ -206,6 +251,8 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
             gas_details: info.gas_details,
             swaps,
             arb_type: possible_arb_type,
+            leg_tx_hashes: vec![],
+            leg_gas_details: vec![],
         };
         let data = BundleData::AtomicArb(backrun);
 
@@ -218,12 +265,11 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
             metadata.clone(),
             MevType::AtomicArb,
             !has_dex_price,
-            |this, token, amount| {
+            |this, amount| {
                 this.get_token_value_dex(
                     info.tx_index as usize,
                     PriceAt::Average,
-                    token,
-                    &amount,
+                    amount,
                     &metadata,
                 )
             },
@@ -232,6 +278,210 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
         Some(Bundle { header, data })
     }
 
+    /// Some searchers split the legs of an arb across two adjacent
+    /// transactions (buy in tx N, sell in tx N + 1) to reduce the cost of a
+    /// failed leg. Links such pairs of the same searcher into a single arb
+    /// with the combined profit.
+    fn process_cross_tx_legs(
+        &self,
+        trees: &[Arc<BlockTree<Action>>],
+        metadata: Arc<Metadata>,
+        mut legs: Vec<(TxInfo, ArbActions)>,
+    ) -> Vec<Bundle> {
+        legs.sort_unstable_by_key(|(info, _)| info.tx_index);
+
+        let mut bundles = vec![];
+        let mut i = 0;
+        while i + 1 < legs.len() {
+            let (first, second) = (&legs[i], &legs[i + 1]);
+            if !is_next_leg(&first.0, &second.0) {
+                i += 1;
+                continue
+            }
+
+            if let Some(bundle) =
+                self.process_leg_pair(trees.to_vec(), metadata.clone(), first, second)
+            {
+                bundles.push(bundle);
+                i += 2;
+            } else {
+                i += 1;
+            }
+        }
+
+        bundles
+    }
+
+    fn process_leg_pair(
+        &self,
+        trees: Vec<Arc<BlockTree<Action>>>,
+        metadata: Arc<Metadata>,
+        (first_info, first_actions): &(TxInfo, ArbActions),
+        (second_info, second_actions): &(TxInfo, ArbActions),
+    ) -> Option<Bundle> {
+        tracing::trace!(?first_info, ?second_info, "trying cross tx atomic");
+        let mev_addresses: FastHashSet<Address> =
+            collect_address_set_for_accounting(&[first_info.clone(), second_info.clone()]);
+
+        let first_swaps =
+            self.merge_transfer_swaps(&mev_addresses, first_actions.0.clone(), &first_actions.1);
+        let second_swaps =
+            self.merge_transfer_swaps(&mev_addresses, second_actions.0.clone(), &second_actions.1);
+
+        // each leg has to trade, and the second leg has to unwind what the
+        // first leg bought
+        let (first_out, second_in) = (first_swaps.last()?, second_swaps.first()?);
+        if first_out.token_out.address != second_in.token_in.address {
+            return None
+        }
+
+        let swaps = first_swaps
+            .into_iter()
+            .chain(second_swaps)
+            .collect::<Vec<_>>();
+        let arb_type = self
+            .is_possible_arb(&swaps)
+            .filter(|arb_type| !matches!(arb_type, AtomicArbType::LongTail))?;
+
+        let leg_deltas = |info: &TxInfo, actions: &ArbActions| {
+            actions
+                .1
+                .iter()
+                .cloned()
+                .map(Action::from)
+                .chain(actions.2.iter().cloned().map(Action::from))
+                .chain(info.get_total_eth_value().iter().cloned().map(Action::from))
+                .account_for_actions()
+        };
+        let first_deltas = leg_deltas(first_info, first_actions);
+        let second_deltas = leg_deltas(second_info, second_actions);
+
+        let combined_deltas = first_actions
+            .1
+            .iter()
+            .chain(&second_actions.1)
+            .cloned()
+            .map(Action::from)
+            .chain(
+                first_actions
+                    .2
+                    .iter()
+                    .chain(&second_actions.2)
+                    .cloned()
+                    .map(Action::from),
+            )
+            .chain(
+                first_info
+                    .get_total_eth_value()
+                    .iter()
+                    .chain(second_info.get_total_eth_value())
+                    .cloned()
+                    .map(Action::from),
+            )
+            .account_for_actions();
+
+        // the arb is only complete after the second leg, so it is priced there
+        let tx_index = second_info.tx_index;
+        let has_dex_price = self.utils.valid_pricing(
+            metadata.clone(),
+            &swaps,
+            combined_deltas
+                .values()
+                .flat_map(|k| {
+                    k.iter()
+                        .filter(|(_, v)| *v != &Rational::ZERO)
+                        .map(|(k, _)| k)
+                })
+                .unique(),
+            tx_index as usize,
+            MAX_PRICE_DIFF,
+            MevType::AtomicArb,
+        );
+
+        let rev = self.utils.get_deltas_usd(
+            tx_index,
+            PriceAt::Average,
+            &mev_addresses,
+            &combined_deltas,
+            metadata.clone(),
+            false,
+        )?;
+
+        let gas_used_usd = metadata.get_gas_price_usd(
+            first_info.gas_details.gas_paid() + second_info.gas_details.gas_paid(),
+            self.utils.quote,
+        );
+
+        // adjacent txs of the same searcher aren't necessarily related, so
+        // unlike single tx arbs, we require the pair to be profitable
+        let profit = rev - gas_used_usd;
+        if !has_dex_price || profit <= Rational::ZERO || profit >= MAX_PROFIT {
+            return None
+        }
+
+        let trigger_tx = self.find_trigger_tx(first_info, trees, &swaps);
+
+        let backrun = AtomicArb {
+            block_number: metadata.block_num,
+            trigger_tx,
+            tx_hash: first_info.tx_hash,
+            gas_details: first_info.gas_details,
+            swaps,
+            arb_type,
+            leg_tx_hashes: vec![second_info.tx_hash],
+            leg_gas_details: vec![second_info.gas_details],
+        };
+        let data = BundleData::AtomicArb(backrun);
+
+        let header = self.utils.build_bundle_header(
+            vec![first_deltas, second_deltas],
+            vec![first_info.tx_hash, second_info.tx_hash],
+            first_info,
+            profit.to_float(),
+            &[first_info.gas_details, second_info.gas_details],
+            metadata.clone(),
+            MevType::AtomicArb,
+            false,
+            |this, amount| {
+                this.get_token_value_dex(tx_index as usize, PriceAt::Average, amount, &metadata)
+            },
+        );
+
+        Some(Bundle { header, data })
+    }
+
+    /// Protocol fee sweeps move tokens out of pools into the collector and
+    /// often swap them right after, which looks like a profitable arb.
+    fn is_fee_collection(&self, info: &TxInfo, actions: &[Action]) -> bool {
+        let is_fee_collection = is_fee_collection(info, actions);
+
+        if is_fee_collection {
+            self.utils
+                .get_metrics()
+                .inspect(|m| m.branch_filtering_trigger(MevType::AtomicArb, "is_fee_collection"));
+        }
+
+        is_fee_collection
+    }
+
+    /// Adds the swaps that can be inferred from transfers that aren't
+    /// part of an already classified swap.
+    fn merge_transfer_swaps(
+        &self,
+        mev_addresses: &FastHashSet<Address>,
+        mut swaps: Vec<NormalizedSwap>,
+        transfers: &[NormalizedTransfer],
+    ) -> Vec<NormalizedSwap> {
+        let mut ignore_addresses = mev_addresses.clone();
+
+        swaps.iter().for_each(|s| {
+            ignore_addresses.insert(s.pool);
+        });
+
+        swaps.extend(self.utils.try_create_swaps(transfers, ignore_addresses));
+        swaps
+    }
+
     /// goes back through the tree until it finds a transaction that occurred
     /// before the atomic arb that use the same liquidity pool for a swap.
     fn find_trigger_tx(
@@ -336,6 +586,29 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
         }
     }
 
+    /// Multicalls can batch a number of unrelated operations into a single
+    /// tx. When the swaps span multiple operations of a multicall, we only
+    /// consider the tx if the swaps of a single operation form an arb, and
+    /// continue with just those swaps.
+    fn is_possible_arb_in_operation(
+        &self,
+        tree: &BlockTree<Action>,
+        info: &TxInfo,
+        swaps: Vec<NormalizedSwap>,
+    ) -> Option<(AtomicArbType, Vec<NormalizedSwap>)> {
+        let Some(groups) = tree
+            .operation_groups(info.tx_hash)
+            .filter(|groups| !groups.same_operation(swaps.iter().map(|s| s.trace_index)))
+        else {
+            return self.is_possible_arb(&swaps).map(|arb| (arb, swaps))
+        };
+
+        groups
+            .split(swaps, |s| s.trace_index)
+            .into_iter()
+            .find_map(|group| self.is_possible_arb(&group).map(|arb| (arb, group)))
+    }
+
     fn process_triangle_arb(&self, tx_info: &TxInfo, multiplier: u64) -> bool {
         let res = tx_info
             .is_searcher_of_type_with_count_threshold(MevType::AtomicArb, 20 * multiplier)
@@ -393,6 +666,43 @@ impl<DB: LibmdbxReader> AtomicArbInspector<'_, DB> {
     }
 }
 
+/// Whether `second` is the transaction directly after `first`, sent by the
+/// same searcher.
+fn is_next_leg(first: &TxInfo, second: &TxInfo) -> bool {
+    first.tx_index + 1 == second.tx_index
+        && first.eoa == second.eoa
+        && first.mev_contract == second.mev_contract
+}
+
+/// Whether the transaction sweeps collected fees: it's sent by a known fee
+/// collector, or it only collects the fees of positions the searcher owns.
+/// An arb that collects on the way, e.g. to unwind a position it used, still
+/// swaps and isn't a sweep
+fn is_fee_collection(info: &TxInfo, actions: &[Action]) -> bool {
+    if info
+        .contract_type
+        .as_ref()
+        .is_some_and(|contract_type| contract_type.is_fee_collector())
+    {
+        return true
+    }
+    if actions.iter().any(Action::is_swap) {
+        return false
+    }
+
+    let is_owner = |address: Address| address == info.eoa || Some(address) == info.mev_contract;
+    let mut collects = actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::Collect(collect) => Some(collect),
+            _ => None,
+        })
+        .peekable();
+
+    collects.peek().is_some()
+        && collects.all(|collect| is_owner(collect.from) || is_owner(collect.recipient))
+}
+
 fn identify_arb_sequence(swaps: &[NormalizedSwap]) -> Option<AtomicArbType> {
     let start_token = &swaps.first().unwrap().token_in.symbol;
     let end_token = &swaps.last().unwrap().token_out.symbol;
@@ -434,9 +744,15 @@ pub fn is_stable_pair(token_in: &str, token_out: &str) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use alloy_primitives::hex;
-    use brontes_types::constants::USDT_ADDRESS;
+    use alloy_primitives::{hex, Address, B256};
+    use brontes_types::{
+        constants::USDT_ADDRESS,
+        db::address_metadata::ContractType,
+        normalized_actions::{Action, NormalizedCollect, NormalizedSwap},
+        GasDetails, TxInfo,
+    };
 
+    use super::{is_fee_collection, is_next_leg};
     use crate::{
         test_utils::{InspectorTestUtils, InspectorTxRunConfig, USDC_ADDRESS, WETH_ADDRESS},
         Inspectors,
@@ -726,4 +1042,83 @@ mod tests {
 
         inspector_util.run_inspector(config, None).await.unwrap();
     }
+
+    #[test]
+    fn test_next_leg_is_adjacent_tx_of_same_searcher() {
+        let searcher = Address::repeat_byte(1);
+        let contract = Some(Address::repeat_byte(2));
+        let tx = |tx_index, eoa, mev_contract| {
+            TxInfo::new(
+                18_000_000,
+                tx_index,
+                eoa,
+                mev_contract,
+                None,
+                B256::repeat_byte(tx_index as u8),
+                GasDetails::default(),
+                false,
+                false,
+                false,
+                false,
+                None,
+                None,
+                vec![],
+            )
+        };
+
+        let first = tx(4, searcher, contract);
+        assert!(is_next_leg(&first, &tx(5, searcher, contract)));
+        // a tx in between, or the legs in the wrong order
+        assert!(!is_next_leg(&first, &tx(6, searcher, contract)));
+        assert!(!is_next_leg(&tx(5, searcher, contract), &first));
+        // another searcher, or the same eoa through another contract
+        assert!(!is_next_leg(&first, &tx(5, Address::repeat_byte(3), contract)));
+        assert!(!is_next_leg(&first, &tx(5, searcher, Some(Address::repeat_byte(3)))));
+    }
+
+    #[test]
+    fn test_fee_collection_is_owner_collect_without_swaps() {
+        let searcher = Address::repeat_byte(1);
+        let info = TxInfo::new(
+            18_000_000,
+            0,
+            searcher,
+            None,
+            None,
+            B256::ZERO,
+            GasDetails::default(),
+            false,
+            false,
+            false,
+            false,
+            None,
+            None,
+            vec![],
+        );
+        let collect = |from| {
+            Action::Collect(NormalizedCollect {
+                from,
+                recipient: from,
+                pool: Address::repeat_byte(9),
+                ..Default::default()
+            })
+        };
+
+        assert!(is_fee_collection(&info, &[collect(searcher)]));
+        // collects of someone else's positions
+        assert!(!is_fee_collection(&info, &[collect(Address::repeat_byte(2))]));
+        // an arb that collects on the way
+        assert!(!is_fee_collection(
+            &info,
+            &[collect(searcher), Action::Swap(NormalizedSwap::default())]
+        ));
+        assert!(!is_fee_collection(&info, &[]));
+
+        let mut fee_collector = info.clone();
+        fee_collector.contract_type = Some(ContractType::FeeCollector);
+        assert!(is_fee_collection(
+            &fee_collector,
+            &[collect(Address::repeat_byte(2)), Action::Swap(NormalizedSwap::default())]
+        ));
+    }
 }
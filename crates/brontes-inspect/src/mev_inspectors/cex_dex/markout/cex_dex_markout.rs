@@ -7,13 +7,16 @@ use alloy_primitives::FixedBytes;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
-    db::cex::{
-        trades::{
-            config::CexDexTradeConfig,
-            optimistic::OptimisticPrice,
-            time_window_vwam::{ExchangePath, WindowExchangePrice},
+    db::{
+        cex::{
+            trades::{
+                config::CexDexTradeConfig,
+                optimistic::OptimisticPrice,
+                time_window_vwam::{ExchangePath, WindowExchangePrice},
+            },
+            CexExchange,
         },
-        CexExchange,
+        inspector_artifacts::ArtifactKind,
     },
     display::utils::format_etherscan_url,
     mev::{Bundle, BundleData, MevType, OptimisticTrade},
@@ -23,8 +26,8 @@ use brontes_types::{
     },
     pair::Pair,
     tree::{BlockTree, GasDetails},
-    BlockData, FastHashMap, MultiBlockData, ToFloatNearest, TreeCollector, TreeSearchBuilder,
-    TxInfo,
+    BlockActionSummary, BlockData, FastHashMap, MultiBlockData, ToFloatNearest, TreeCollector,
+    TreeSearchBuilder, TxInfo,
 };
 use itertools::multizip;
 use malachite::{
@@ -77,6 +80,10 @@ impl<DB: LibmdbxReader> Inspector for CexDexMarkoutInspector<'_, DB> {
         "CexDexMarkout"
     }
 
+    fn should_inspect(&self, summary: &BlockActionSummary) -> bool {
+        summary.possible_swap_txs() > 0
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -198,6 +205,12 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
             self.utils.get_metrics().inspect(|m| {
                 m.branch_filtering_trigger(MevType::CexDexTrades, "is_triangular_arb")
             });
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::Rejection,
+                &[tx_info.tx_hash],
+                || "triangular arb".to_string(),
+            );
             return None
         }
 
@@ -251,7 +264,14 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
             return None
         }
 
-        let mut possible_cex_dex: CexDexProcessing = self.detect_cex_dex(
+        metadata.record_artifact(
+            self.get_id(),
+            ArtifactKind::Candidate,
+            &[tx_info.tx_hash],
+            || format!("{} dex swaps, batch: {batch_swap}", dex_swaps.len()),
+        );
+
+        let Some(mut possible_cex_dex): Option<CexDexProcessing> = self.detect_cex_dex(
             dex_swaps,
             &metadata,
             tx_info.is_searcher_of_type(MevType::CexDexTrades)
@@ -259,12 +279,29 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
                 || tx_info.is_labelled_searcher_of_type(MevType::CexDexRfq)
                 || tx_info.is_searcher_of_type(MevType::JitCexDex),
             &tx_info,
-        )?;
+        ) else {
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::PriceInput,
+                &[tx_info.tx_hash],
+                || "no cex trades to price the swaps".to_string(),
+            );
+            return None
+        };
 
         self.gas_accounting(&mut possible_cex_dex, &tx_info.gas_details, metadata.clone());
 
-        let (profit_usd, cex_dex, trade_prices) =
-            self.filter_possible_cex_dex(possible_cex_dex, &tx_info, metadata.clone())?;
+        let Some((profit_usd, cex_dex, trade_prices)) =
+            self.filter_possible_cex_dex(possible_cex_dex, &tx_info, metadata.clone())
+        else {
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::Rejection,
+                &[tx_info.tx_hash],
+                || "filtered out as a cex dex arb".to_string(),
+            );
+            return None
+        };
 
         let price_map = trade_prices
             .into_iter()
@@ -283,7 +320,7 @@ impl<DB: LibmdbxReader> CexDexMarkoutInspector<'_, DB> {
             metadata.clone(),
             if batch_swap { MevType::CexDexRfq } else { MevType::CexDexTrades },
             false,
-            |_, token, amount| Some(price_map.get(&token)? * &amount),
+            |_, amount| Some(amount.usd_value(price_map.get(&amount.token)?)),
         );
 
         Some(Bundle { header, data: cex_dex })
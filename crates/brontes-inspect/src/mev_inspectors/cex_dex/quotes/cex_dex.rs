@@ -50,14 +50,17 @@ use alloy_primitives::Address;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
-    db::cex::{quotes::FeeAdjustedQuote, CexExchange},
+    db::{
+        cex::{quotes::FeeAdjustedQuote, CexExchange},
+        inspector_artifacts::ArtifactKind,
+    },
     display::utils::format_etherscan_url,
     mev::{Bundle, BundleData, MevType},
     normalized_actions::{accounting::ActionAccounting, Action, NormalizedSwap},
     pair::Pair,
     tree::{BlockTree, GasDetails},
-    BlockData, FastHashMap, MultiBlockData, ToFloatNearest, TreeCollector, TreeSearchBuilder,
-    TxInfo,
+    BlockActionSummary, BlockData, FastHashMap, MultiBlockData, ToFloatNearest, TreeCollector,
+    TreeSearchBuilder, TxInfo,
 };
 use malachite::{
     num::{arithmetic::traits::Reciprocal, basic::traits::Zero},
@@ -111,6 +114,10 @@ impl<DB: LibmdbxReader> Inspector for CexDexQuotesInspector<'_, DB> {
         "CexDex"
     }
 
+    fn should_inspect(&self, summary: &BlockActionSummary) -> bool {
+        summary.possible_swap_txs() > 0
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -208,6 +215,12 @@ impl<DB: LibmdbxReader> CexDexQuotesInspector<'_, DB> {
                 if dex_swaps.is_empty() {
                     trace!(    target: "brontes::cex-dex-quotes",
                 "no dex swaps found\n Tx: {}", format_etherscan_url(&tx_info.tx_hash));
+                    metadata.record_artifact(
+                        self.get_id(),
+                        ArtifactKind::Rejection,
+                        &[tx_info.tx_hash],
+                        || "no dex swaps".to_string(),
+                    );
                     return None
                 }
 
@@ -217,6 +230,12 @@ impl<DB: LibmdbxReader> CexDexQuotesInspector<'_, DB> {
                         "Filtered out CexDex because it is a triangular arb\n Tx: {}",
                         format_etherscan_url(&tx_info.tx_hash)
                     );
+                    metadata.record_artifact(
+                        self.get_id(),
+                        ArtifactKind::Rejection,
+                        &[tx_info.tx_hash],
+                        || "triangular arb".to_string(),
+                    );
                     self.utils.get_metrics().inspect(|m| {
                         m.branch_filtering_trigger(MevType::CexDexQuotes, "is_triangular_arb")
                     });
@@ -250,7 +269,7 @@ impl<DB: LibmdbxReader> CexDexQuotesInspector<'_, DB> {
                     metadata.clone(),
                     MevType::CexDexQuotes,
                     false,
-                    |_, token, amount| Some(price_map.get(&token)? * amount),
+                    |_, amount| Some(amount.usd_value(price_map.get(&amount.token)?)),
                 );
 
                 Some(Bundle { header, data: cex_dex })
@@ -403,7 +422,7 @@ impl<DB: LibmdbxReader> CexDexQuotesInspector<'_, DB> {
             .map(|dex_swap| {
                 let pair = Pair(dex_swap.token_in.address, dex_swap.token_out.address);
 
-                metadata
+                let quote = metadata
                     .cex_quotes
                     .get_quote_from_most_liquid_exchange(
                         &pair,
@@ -417,7 +436,26 @@ impl<DB: LibmdbxReader> CexDexQuotesInspector<'_, DB> {
                             dex_swap.token_out_symbol(),
                         );
                         None
-                    })
+                    });
+
+                metadata.record_artifact(self.get_id(), ArtifactKind::PriceInput, &[], || {
+                    match &quote {
+                        Some(quote) => format!(
+                            "{}-{} quoted at {} by {}",
+                            dex_swap.token_in_symbol(),
+                            dex_swap.token_out_symbol(),
+                            quote.price_maker.1.clone().to_float(),
+                            quote.exchange
+                        ),
+                        None => format!(
+                            "no cex quote for {}-{}",
+                            dex_swap.token_in_symbol(),
+                            dex_swap.token_out_symbol(),
+                        ),
+                    }
+                });
+
+                quote
             })
             .collect()
     }
@@ -2,12 +2,15 @@ use std::sync::Arc;
 
 use alloy_primitives::Address;
 use brontes_types::{
-    db::{metadata::Metadata, token_info::TokenInfoWithAddress, traits::LibmdbxReader},
+    db::{
+        inspector_artifacts::ArtifactKind, metadata::Metadata, token_info::TokenInfoWithAddress,
+        traits::LibmdbxReader,
+    },
     display::utils::format_etherscan_url,
     mev::{Bundle, BundleData, MevType},
     normalized_actions::{accounting::ActionAccounting, Action, NormalizedSwap},
     tree::BlockTree,
-    BlockData, FastHashMap, MultiBlockData,
+    BlockActionSummary, BlockData, FastHashMap, MultiBlockData,
 };
 use itertools::multizip;
 use malachite::{num::basic::traits::Zero, Rational};
@@ -39,6 +42,10 @@ impl<DB: LibmdbxReader> Inspector for JitCexDex<'_, DB> {
         "JitCexDex"
     }
 
+    fn should_inspect(&self, summary: &BlockActionSummary) -> bool {
+        summary.jit_shaped
+    }
+
     fn get_quote_token(&self) -> Address {
         self.jit.utils.quote
     }
@@ -68,8 +75,12 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
             tracing::warn!("no cex trades for block");
             return vec![]
         }
+        // the jit and cex dex inspectors record the artifacts of their own runs
+        let inner_metadata = metadata.without_artifacts();
         // call inner to avoid metrics
-        let jit_bundles = self.jit.inspect_block_inner(tree.clone(), metadata.clone());
+        let jit_bundles = self
+            .jit
+            .inspect_block_inner(tree.clone(), inner_metadata.clone());
         jit_bundles
             .into_iter()
             .filter_map(|jits| {
@@ -80,8 +91,20 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
                 let BundleData::Jit(jit) = jits.data else { return None };
                 let details = [jit.backrun_burn_gas_details, jit.frontrun_mint_gas_details];
                 let tx_info = tree.get_tx_info(jits.header.tx_hash, self.jit.utils.db)?;
+                metadata.record_artifact(
+                    self.get_id(),
+                    ArtifactKind::Candidate,
+                    &[tx_info.tx_hash],
+                    || format!("jit over {} pools", jit.frontrun_mints.len()),
+                );
 
                 if !tx_info.is_searcher_of_type_with_count_threshold(MevType::JitCexDex, 10) {
+                    metadata.record_artifact(
+                        self.get_id(),
+                        ArtifactKind::Rejection,
+                        &[tx_info.tx_hash],
+                        || "not a jit cex dex searcher".to_string(),
+                    );
                     return None
                 }
 
@@ -152,6 +175,12 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
                         "Filtered out CexDex because it is a triangular arb\n Tx: {}",
                         format_etherscan_url(&tx_info.tx_hash)
                     );
+                    metadata.record_artifact(
+                        self.get_id(),
+                        ArtifactKind::Rejection,
+                        &[tx_info.tx_hash],
+                        || "triangular arb".to_string(),
+                    );
                     self.cex_dex.utils.get_metrics().inspect(|m| {
                         m.branch_filtering_trigger(MevType::JitCexDex, "is_triangular_arb")
                     });
@@ -159,13 +188,23 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
                     return None
                 }
 
-                let mut possible_cex_dex: CexDexProcessing = self.cex_dex.detect_cex_dex(
-                    dex_swaps.clone(),
-                    &metadata,
-                    tx_info.is_searcher_of_type(MevType::JitCexDex)
-                        || tx_info.is_labelled_searcher_of_type(MevType::JitCexDex),
-                    &tx_info,
-                )?;
+                let Some(mut possible_cex_dex): Option<CexDexProcessing> =
+                    self.cex_dex.detect_cex_dex(
+                        dex_swaps.clone(),
+                        &inner_metadata,
+                        tx_info.is_searcher_of_type(MevType::JitCexDex)
+                            || tx_info.is_labelled_searcher_of_type(MevType::JitCexDex),
+                        &tx_info,
+                    )
+                else {
+                    metadata.record_artifact(
+                        self.get_id(),
+                        ArtifactKind::PriceInput,
+                        &[tx_info.tx_hash],
+                        || "no cex trades to price the swaps".to_string(),
+                    );
+                    return None
+                };
 
                 self.cex_dex.gas_accounting(
                     &mut possible_cex_dex,
@@ -173,11 +212,18 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
                     metadata.clone(),
                 );
 
-                let (profit_usd, cex_dex, trade_prices) = self.cex_dex.filter_possible_cex_dex(
-                    possible_cex_dex,
-                    &tx_info,
-                    metadata.clone(),
-                )?;
+                let Some((profit_usd, cex_dex, trade_prices)) = self
+                    .cex_dex
+                    .filter_possible_cex_dex(possible_cex_dex, &tx_info, inner_metadata.clone())
+                else {
+                    metadata.record_artifact(
+                        self.get_id(),
+                        ArtifactKind::Rejection,
+                        &[tx_info.tx_hash],
+                        || "filtered out as a cex dex arb".to_string(),
+                    );
+                    return None
+                };
 
                 let price_map =
                     trade_prices
@@ -202,7 +248,7 @@ impl<DB: LibmdbxReader> JitCexDex<'_, DB> {
                     metadata.clone(),
                     MevType::JitCexDex,
                     false,
-                    |_, token, amount| Some(price_map.get(&token)? * amount),
+                    |_, amount| Some(amount.usd_value(price_map.get(&amount.token)?)),
                 );
 
                 Some(Bundle { header, data: cex_dex })
@@ -0,0 +1,440 @@
+//! Jit liquidity held across a few blocks.
+//!
+//! Some jit providers mint a position in one block and burn it a few blocks
+//! later, so the mint, the swaps and the burn are never in the same block and
+//! the [`JitInspector`](super::JitInspector) doesn't see them. Positions
+//! managed by a position manager are identified by their token id, which lets
+//! the inspector follow them across its window: a position burnt in the most
+//! recent block by the eoa or contract that minted it at most the inspector's
+//! max hold blocks earlier, with swaps of other accounts through
+//! the pool in between, is a [`MevType::JitExtended`] bundle. The mint is
+//! priced with the prices of the block it's in and the burn with the prices of
+//! the most recent block.
+use alloy_primitives::{Address, U256};
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_metrics::inspectors::OutlierMetrics;
+use brontes_types::{
+    collect_address_set_for_accounting,
+    db::{dex::PriceAt, inspector_artifacts::ArtifactKind},
+    mev::{Bundle, JitLiquidity, MevType},
+    normalized_actions::{
+        accounting::{ActionAccounting, AddressDeltas},
+        NormalizedBurn, NormalizedMint, NormalizedSwap,
+    },
+    BlockActionSummary, BlockData, MultiBlockData, ToFloatNearest, TreeSearchBuilder, TxInfo,
+};
+use itertools::{multizip, Itertools};
+use malachite::{num::basic::traits::Zero, Rational};
+
+use crate::{shared_utils::SharedInspectorUtils, Action, BundleData, Inspector, MAX_PROFIT};
+
+/// Blocks a position can be held for and still be jit liquidity, unless the
+/// run sets another bound
+pub const DEFAULT_JIT_MAX_HOLD_BLOCKS: usize = 3;
+
+pub struct JitExtendedInspector<'db, DB: LibmdbxReader> {
+    utils:           SharedInspectorUtils<'db, DB>,
+    max_hold_blocks: usize,
+}
+
+impl<'db, DB: LibmdbxReader> JitExtendedInspector<'db, DB> {
+    /// `max_hold_blocks` is at least one, a position held for zero blocks is
+    /// in a single block and found by the [`JitInspector`](super::JitInspector)
+    pub fn new(
+        quote: Address,
+        db: &'db DB,
+        max_hold_blocks: usize,
+        metrics: Option<OutlierMetrics>,
+    ) -> Self {
+        Self {
+            utils:           SharedInspectorUtils::new(quote, db, metrics),
+            max_hold_blocks: max_hold_blocks.max(1),
+        }
+    }
+}
+
+impl<DB: LibmdbxReader> Inspector for JitExtendedInspector<'_, DB> {
+    type Result = Vec<Bundle>;
+
+    // the block of the burn and the blocks the position could have been minted in
+    fn block_window(&self) -> usize {
+        self.max_hold_blocks + 1
+    }
+
+    fn get_id(&self) -> &str {
+        "JitExtended"
+    }
+
+    fn should_inspect(&self, summary: &BlockActionSummary) -> bool {
+        summary.lp_exit_txs > 0
+    }
+
+    fn get_quote_token(&self) -> Address {
+        self.utils.quote
+    }
+
+    fn inspect_block(&self, data: MultiBlockData) -> Self::Result {
+        let execution = || self.inspect_window(&data.per_block_data);
+
+        self.utils
+            .get_metrics()
+            .map(|m| m.run_inspector(MevType::JitExtended, execution))
+            .unwrap_or_else(execution)
+    }
+}
+
+/// A transaction that mints, burns or collects liquidity, with its transfers
+struct LpTx {
+    info:    TxInfo,
+    actions: Vec<Action>,
+}
+
+impl LpTx {
+    fn mints(&self, pool: Address) -> impl Iterator<Item = &NormalizedMint> + '_ {
+        self.actions
+            .iter()
+            .filter_map(Action::try_mint_ref)
+            .filter(move |m| m.pool == pool)
+    }
+
+    fn exited_positions(&self) -> impl Iterator<Item = (U256, Address)> + '_ {
+        exited_positions(&self.actions)
+    }
+
+    fn mints_position(&self, position_id: U256, pool: Address) -> bool {
+        self.mints(pool).any(|m| m.position_id == Some(position_id))
+    }
+
+    fn same_searcher(&self, info: &TxInfo) -> bool {
+        self.info.eoa == info.eoa
+            || (self.info.mev_contract.is_some() && self.info.mev_contract == info.mev_contract)
+    }
+
+    fn deltas(&self) -> AddressDeltas {
+        self.actions
+            .iter()
+            .filter(|a| a.is_transfer() || a.is_eth_transfer())
+            .cloned()
+            .chain(
+                self.info
+                    .get_total_eth_value()
+                    .iter()
+                    .cloned()
+                    .map(Action::from),
+            )
+            .account_for_actions()
+    }
+}
+
+/// Positions whose liquidity is burnt, as their token id and pool. A collect
+/// on its own only takes the fees out and leaves the position open
+fn exited_positions(actions: &[Action]) -> impl Iterator<Item = (U256, Address)> + '_ {
+    actions
+        .iter()
+        .filter_map(Action::try_burn_ref)
+        .filter_map(|b| Some((b.position_id?, b.pool)))
+        .unique()
+}
+
+impl<DB: LibmdbxReader> JitExtendedInspector<'_, DB> {
+    fn inspect_window(&self, blocks: &[BlockData]) -> Vec<Bundle> {
+        let Some((exit_block, entry_blocks)) = blocks.split_last() else { return vec![] };
+
+        let exits = self
+            .lp_txs(exit_block)
+            .into_iter()
+            .filter(|tx| tx.exited_positions().next().is_some())
+            .collect_vec();
+        if exits.is_empty() {
+            return vec![]
+        }
+
+        // mints of the earlier blocks, oldest first
+        let entries = entry_blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(block, data)| {
+                self.lp_txs(data)
+                    .into_iter()
+                    .filter(|tx| tx.actions.iter().any(|a| a.is_mint()))
+                    .map(move |tx| (block, tx))
+            })
+            .collect_vec();
+
+        exits
+            .iter()
+            .filter_map(|exit| {
+                exit.exited_positions().find_map(|(position_id, pool)| {
+                    let (entry_block, entry) = entries.iter().find(|(_, entry)| {
+                        entry.same_searcher(&exit.info) && entry.mints_position(position_id, pool)
+                    })?;
+                    tracing::trace!(
+                        entry = ?entry.info.tx_hash,
+                        exit = ?exit.info.tx_hash,
+                        %position_id,
+                        "checking if extended jit"
+                    );
+                    exit_block.metadata.record_artifact(
+                        self.get_id(),
+                        ArtifactKind::Candidate,
+                        &[entry.info.tx_hash, exit.info.tx_hash],
+                        || {
+                            format!(
+                                "position {position_id} held over {} blocks",
+                                blocks.len() - entry_block
+                            )
+                        },
+                    );
+
+                    self.calculate_jit(blocks, *entry_block, entry, exit, pool)
+                })
+            })
+            .collect()
+    }
+
+    fn lp_txs(&self, block: &BlockData) -> Vec<LpTx> {
+        let (hashes, actions): (Vec<_>, Vec<_>) = block
+            .tree
+            .clone()
+            .collect_all(TreeSearchBuilder::default().with_actions([
+                Action::is_mint,
+                Action::is_burn,
+                Action::is_collect,
+                Action::is_transfer,
+                Action::is_eth_transfer,
+                Action::is_nested_action,
+            ]))
+            .unzip();
+        let tx_info = block.tree.get_tx_info_batch(&hashes, self.utils.db);
+
+        multizip((actions, tx_info))
+            .filter_map(|(actions, info)| {
+                let info = info?;
+                let actions = self
+                    .utils
+                    .flatten_nested_actions(actions.into_iter(), &|action| {
+                        action.is_mint()
+                            || action.is_burn()
+                            || action.is_collect()
+                            || action.is_transfer()
+                            || action.is_eth_transfer()
+                    })
+                    .collect_vec();
+
+                actions
+                    .iter()
+                    .any(|a| a.is_mint() || a.is_burn() || a.is_collect())
+                    .then_some(LpTx { info, actions })
+            })
+            .collect()
+    }
+
+    /// Swaps of other accounts through the pool after the mint and before the
+    /// burn, by transaction
+    fn victim_swaps(
+        &self,
+        blocks: &[BlockData],
+        entry_block: usize,
+        entry: &LpTx,
+        exit: &LpTx,
+        pool: Address,
+    ) -> Vec<(TxInfo, Vec<NormalizedSwap>)> {
+        let exit_block = blocks.len() - 1;
+
+        blocks[entry_block..]
+            .iter()
+            .enumerate()
+            .flat_map(|(offset, data)| {
+                let block = entry_block + offset;
+                let (hashes, actions): (Vec<_>, Vec<_>) = data
+                    .tree
+                    .clone()
+                    .collect_all(
+                        TreeSearchBuilder::default()
+                            .with_actions([Action::is_swap, Action::is_nested_action]),
+                    )
+                    .unzip();
+                let tx_info = data.tree.get_tx_info_batch(&hashes, self.utils.db);
+
+                multizip((actions, tx_info))
+                    .filter_map(|(actions, info)| {
+                        let info = info?;
+                        if (block == entry_block && info.tx_index <= entry.info.tx_index)
+                            || (block == exit_block && info.tx_index >= exit.info.tx_index)
+                            || entry.same_searcher(&info)
+                        {
+                            return None
+                        }
+
+                        let swaps = self
+                            .utils
+                            .flatten_nested_actions(actions.into_iter(), &|action| action.is_swap())
+                            .filter_map(Action::try_swaps_merged)
+                            .filter(|swap| swap.pool == pool)
+                            .collect_vec();
+
+                        (!swaps.is_empty()).then_some((info, swaps))
+                    })
+                    .collect_vec()
+            })
+            .collect()
+    }
+
+    fn calculate_jit(
+        &self,
+        blocks: &[BlockData],
+        entry_block: usize,
+        entry: &LpTx,
+        exit: &LpTx,
+        pool: Address,
+    ) -> Option<Bundle> {
+        let victims = self.victim_swaps(blocks, entry_block, entry, exit, pool);
+        if victims.is_empty() {
+            tracing::trace!("no swaps through the pool while the position was held");
+            blocks.last()?.metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::Rejection,
+                &[entry.info.tx_hash, exit.info.tx_hash],
+                || "no swaps through the pool while the position was held".to_string(),
+            );
+            return None
+        }
+
+        let entry_metadata = blocks[entry_block].metadata.clone();
+        let exit_data = blocks.last()?;
+        let exit_metadata = exit_data.metadata.clone();
+
+        let mev_addresses =
+            collect_address_set_for_accounting(&[entry.info.clone(), exit.info.clone()]);
+        let entry_deltas = entry.deltas();
+        let exit_deltas = exit.deltas();
+
+        // each leg is priced with the prices of its own block
+        let entry_rev = self.utils.get_deltas_usd(
+            entry.info.tx_index,
+            PriceAt::After,
+            &mev_addresses,
+            &entry_deltas,
+            entry_metadata.clone(),
+            true,
+        );
+        let exit_rev = self.utils.get_deltas_usd(
+            exit.info.tx_index,
+            PriceAt::After,
+            &mev_addresses,
+            &exit_deltas,
+            exit_metadata.clone(),
+            true,
+        );
+        let (rev, mut has_dex_price) = match (entry_rev, exit_rev) {
+            (Some(entry_rev), Some(exit_rev)) => (entry_rev + exit_rev, true),
+            _ => (Rational::ZERO, false),
+        };
+
+        let bribe = entry_metadata
+            .get_gas_price_usd(entry.info.gas_details.gas_paid(), self.utils.quote)
+            + exit_metadata.get_gas_price_usd(exit.info.gas_details.gas_paid(), self.utils.quote);
+        let mut profit = if has_dex_price { rev - bribe } else { Rational::ZERO };
+
+        if profit >= MAX_PROFIT {
+            has_dex_price = false;
+            profit = Rational::ZERO;
+        }
+
+        let bundle_hashes = std::iter::once(entry.info.tx_hash)
+            .chain(victims.iter().map(|(info, _)| info.tx_hash))
+            .chain(std::iter::once(exit.info.tx_hash))
+            .collect_vec();
+        let gas_details = [entry.info.gas_details, exit.info.gas_details];
+
+        let header = self.utils.build_bundle_header(
+            vec![entry_deltas, exit_deltas],
+            bundle_hashes,
+            &exit.info,
+            profit.to_float(),
+            &gas_details,
+            exit_metadata.clone(),
+            MevType::JitExtended,
+            !has_dex_price,
+            |this, amount| {
+                this.get_token_value_dex(
+                    exit.info.tx_index as usize,
+                    PriceAt::Average,
+                    amount,
+                    &exit_metadata,
+                )
+            },
+        );
+
+        let collects = exit
+            .actions
+            .iter()
+            .filter_map(Action::try_collect_ref)
+            .filter(|c| c.pool == pool)
+            .cloned()
+            .map(NormalizedBurn::from)
+            .collect_vec();
+        let backrun_burns = if collects.is_empty() {
+            exit.actions
+                .iter()
+                .filter_map(Action::try_burn_ref)
+                .filter(|b| b.pool == pool)
+                .cloned()
+                .collect_vec()
+        } else {
+            collects
+        };
+
+        let victim_hashes = victims.iter().map(|(info, _)| info.tx_hash).collect_vec();
+        let victim_gas_details = victims
+            .iter()
+            .map(|(info, _)| info.gas_details)
+            .collect_vec();
+
+        let jit_details = JitLiquidity {
+            frontrun_mint_tx_hash: entry.info.tx_hash,
+            block_number: blocks[entry_block].block_number(),
+            frontrun_mints: entry.mints(pool).cloned().collect(),
+            frontrun_mint_gas_details: entry.info.gas_details,
+            victim_swaps_tx_hashes: victim_hashes.clone(),
+            victim_swaps: victims.into_iter().map(|(_, swaps)| swaps).collect(),
+            victim_swaps_gas_details_tx_hashes: victim_hashes,
+            victim_swaps_gas_details: victim_gas_details,
+            backrun_burn_tx_hash: exit.info.tx_hash,
+            backrun_burns,
+            backrun_burn_gas_details: exit.info.gas_details,
+            backrun_block_number: exit_data.block_number(),
+        };
+
+        Some(Bundle { header, data: BundleData::Jit(jit_details) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brontes_types::normalized_actions::NormalizedCollect;
+
+    use super::*;
+
+    #[test]
+    fn test_only_burns_exit_positions() {
+        let pool = Address::repeat_byte(1);
+        let collect = Action::Collect(NormalizedCollect {
+            pool,
+            position_id: Some(U256::from(7)),
+            ..Default::default()
+        });
+        assert_eq!(exited_positions(std::slice::from_ref(&collect)).count(), 0);
+
+        let burn = Action::Burn(NormalizedBurn {
+            pool,
+            position_id: Some(U256::from(7)),
+            ..Default::default()
+        });
+        let unmanaged = Action::Burn(NormalizedBurn { pool, ..Default::default() });
+        assert_eq!(
+            exited_positions(&[burn.clone(), collect, burn, unmanaged]).collect_vec(),
+            vec![(U256::from(7), pool)]
+        );
+    }
+}
@@ -5,13 +5,13 @@ use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
     collect_address_set_for_accounting,
-    db::dex::PriceAt,
+    db::{dex::PriceAt, inspector_artifacts::ArtifactKind},
     mev::{Bundle, JitLiquidity, MevType},
     normalized_actions::{
         accounting::ActionAccounting, NormalizedBurn, NormalizedCollect, NormalizedMint,
     },
-    ActionIter, BlockData, FastHashMap, FastHashSet, GasDetails, MultiBlockData, ToFloatNearest,
-    TreeSearchBuilder, TxInfo,
+    ActionIter, BlockActionSummary, BlockData, FastHashMap, FastHashSet, GasDetails,
+    MultiBlockData, ToFloatNearest, TreeSearchBuilder, TxInfo,
 };
 use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
@@ -40,6 +40,10 @@ impl<DB: LibmdbxReader> Inspector for JitInspector<'_, DB> {
         "Jit"
     }
 
+    fn should_inspect(&self, summary: &BlockActionSummary) -> bool {
+        summary.jit_shaped
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -83,9 +87,26 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
                         );
 
                         tracing::trace!(?frontrun_txes, ?backrun_tx, "checking if jit");
+                        let searcher_txes = frontrun_txes
+                            .iter()
+                            .copied()
+                            .chain([backrun_tx])
+                            .collect_vec();
+                        metadata.record_artifact(
+                            self.get_id(),
+                            ArtifactKind::Candidate,
+                            &searcher_txes,
+                            || format!("{} possible victims", victims.len()),
+                        );
 
                         if searcher_actions.is_empty() {
                             tracing::trace!("no searcher actions found");
+                            metadata.record_artifact(
+                                self.get_id(),
+                                ArtifactKind::Rejection,
+                                &searcher_txes,
+                                || "no searcher actions".to_string(),
+                            );
                             return None
                         }
 
@@ -193,11 +214,38 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             .flatten()
             .action_split_out((Action::try_mint, Action::try_burn, Action::try_collect));
 
+        let searcher_txes = || {
+            frontrun_info
+                .iter()
+                .chain([&backrun_info])
+                .map(|info| info.tx_hash)
+                .collect_vec()
+        };
         if mints.is_empty() || (burns.is_empty() && collect.is_empty()) {
             tracing::trace!("missing mints & burns");
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::Rejection,
+                &searcher_txes(),
+                || "missing mints & burns".to_string(),
+            );
+            return None
+        }
+        if self
+            .ensure_valid_structure(&mints, &burns, &victim_actions)
+            .is_none()
+        {
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::Rejection,
+                &searcher_txes(),
+                || {
+                    "the burns don't match the mints or no victim swapped through the pool"
+                        .to_string()
+                },
+            );
             return None
         }
-        self.ensure_valid_structure(&mints, &burns, &victim_actions)?;
 
         let mut info_set = frontrun_info.clone();
         info_set.push(backrun_info.clone());
@@ -265,12 +313,11 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             metadata.clone(),
             MevType::Jit,
             !has_dex_price,
-            |this, token, amount| {
+            |this, amount| {
                 this.get_token_value_dex(
                     info_set.last()?.tx_index as usize,
                     PriceAt::Average,
-                    token,
-                    &amount,
+                    amount,
                     &metadata,
                 )
             },
@@ -327,21 +374,9 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             backrun_burn_gas_details: gas_details.pop()?,
             backrun_burns: Some(collect)
                 .filter(|f| !f.is_empty())
-                .map(|collect| {
-                    collect
-                        .into_iter()
-                        .map(|c| NormalizedBurn {
-                            recipient:   c.recipient,
-                            trace_index: c.trace_index,
-                            protocol:    c.protocol,
-                            amount:      c.amount,
-                            token:       c.token,
-                            pool:        c.pool,
-                            from:        c.from,
-                        })
-                        .collect_vec()
-                })
+                .map(|collect| collect.into_iter().map(NormalizedBurn::from).collect_vec())
                 .unwrap_or(burns),
+            backrun_block_number: block_number,
         })
     }
 
@@ -362,6 +397,21 @@ impl<DB: LibmdbxReader> JitInspector<'_, DB> {
             return None
         }
 
+        // positions managed by a position manager are identified by their token
+        // id, so the burnt position must be one that was minted
+        let minted_positions = mints
+            .iter()
+            .filter_map(|m| m.position_id)
+            .collect::<FastHashSet<_>>();
+        let mut burnt_positions = burns.iter().filter_map(|b| b.position_id).peekable();
+        if !minted_positions.is_empty()
+            && burnt_positions.peek().is_some()
+            && !burnt_positions.any(|id| minted_positions.contains(&id))
+        {
+            tracing::trace!("burnt position wasn't minted");
+            return None
+        }
+
         // ensure we have overlap
         let v_swaps = victim_actions
             .iter()
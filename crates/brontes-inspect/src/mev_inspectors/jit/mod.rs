@@ -1,7 +1,9 @@
 pub mod jit_cex_dex;
+pub mod jit_extended;
 pub mod jit_liquidity;
 
 mod types;
 
 pub use jit_cex_dex::JitCexDex;
+pub use jit_extended::{JitExtendedInspector, DEFAULT_JIT_MAX_HOLD_BLOCKS};
 pub use jit_liquidity::JitInspector;
@@ -3,10 +3,11 @@ use std::sync::Arc;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
-    db::dex::PriceAt,
+    db::{dex::PriceAt, inspector_artifacts::ArtifactKind},
     mev::{Bundle, BundleData, Liquidation, MevType},
     normalized_actions::{accounting::ActionAccounting, Action},
-    ActionIter, BlockData, FastHashSet, MultiBlockData, ToFloatNearest, TreeSearchBuilder, TxInfo,
+    ActionIter, BlockActionSummary, BlockData, FastHashSet, MultiBlockData, ToFloatNearest,
+    TreeSearchBuilder, TxInfo,
 };
 use itertools::multizip;
 use malachite::{num::basic::traits::Zero, Rational};
@@ -32,6 +33,10 @@ impl<DB: LibmdbxReader> Inspector for LiquidationInspector<'_, DB> {
         "Liquidation"
     }
 
+    fn should_inspect(&self, summary: &BlockActionSummary) -> bool {
+        summary.liquidation_txs > 0
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -86,8 +91,17 @@ impl<DB: LibmdbxReader> LiquidationInspector<'_, DB> {
 
         if liqs.is_empty() {
             tracing::debug!("no liquidation events");
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::Rejection,
+                &[info.tx_hash],
+                || "no liquidation events".to_string(),
+            );
             return None
         }
+        metadata.record_artifact(self.get_id(), ArtifactKind::Candidate, &[info.tx_hash], || {
+            format!("{} liquidations and {} swaps", liqs.len(), swaps.len())
+        });
 
         let mev_addresses: FastHashSet<Address> = info.collect_address_set_for_accounting();
 
@@ -119,8 +133,21 @@ impl<DB: LibmdbxReader> LiquidationInspector<'_, DB> {
             .unwrap_or_default();
 
         if profit_usd >= MAX_PROFIT || profit_usd <= -MAX_PROFIT {
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::PriceInput,
+                &[info.tx_hash],
+                || format!("profit of {} usd is out of bounds", profit_usd.clone().to_float()),
+            );
             has_dex_price = false;
             profit_usd = Rational::ZERO;
+        } else if !has_dex_price {
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::PriceInput,
+                &[info.tx_hash],
+                || "no dex price for the deltas".to_string(),
+            );
         }
 
         let header = self.utils.build_bundle_header(
@@ -132,12 +159,11 @@ impl<DB: LibmdbxReader> LiquidationInspector<'_, DB> {
             metadata.clone(),
             MevType::Liquidation,
             !has_dex_price,
-            |this, token, amount| {
+            |this, amount| {
                 this.get_token_value_dex(
                     info.tx_index as usize,
                     PriceAt::Average,
-                    token,
-                    &amount,
+                    amount,
                     &metadata,
                 )
             },
@@ -3,6 +3,7 @@ pub mod cex_dex;
 
 pub mod jit;
 pub mod liquidations;
+pub mod price_cache;
 pub mod sandwich;
 pub mod searcher_activity;
 pub mod shared_utils;
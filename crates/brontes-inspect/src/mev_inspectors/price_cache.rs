@@ -0,0 +1,133 @@
+//! Memoized dex pricing.
+//!
+//! Resolving a dex price walks back through the dex quotes of the block until
+//! a quote for the pair is found, and the same (token, tx index) pairs get
+//! priced over and over, once per token per action per bundle. The cache
+//! resolves each pair once per block and shares the result across all
+//! bundles of the block.
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use alloy_primitives::Address;
+use brontes_types::{
+    db::{dex::DexPrices, metadata::Metadata},
+    pair::Pair,
+    FastHashMap, FastHashSet,
+};
+use parking_lot::Mutex;
+
+/// Blocks are inspected concurrently, so we keep the prices of a few blocks
+/// around before evicting the oldest.
+const MAX_CACHED_BLOCKS: usize = 8;
+
+type PriceKey = (Address, usize);
+
+#[derive(Debug, Default)]
+pub struct DexPriceCache {
+    blocks: Mutex<FastHashMap<u64, BlockPrices>>,
+}
+
+#[derive(Debug, Default)]
+struct BlockPrices {
+    /// Identifies the quotes the prices were resolved from, see [`prices_id`]
+    id:     u64,
+    prices: FastHashMap<PriceKey, Option<DexPrices>>,
+}
+
+impl DexPriceCache {
+    /// Resolves the dex prices of all `(token, tx_index)` pairs against
+    /// `quote`. Only pairs that weren't resolved before for this block are
+    /// looked up in the dex quotes.
+    pub fn get_prices(
+        &self,
+        metadata: &Arc<Metadata>,
+        quote: Address,
+        keys: impl IntoIterator<Item = PriceKey>,
+    ) -> FastHashMap<PriceKey, Option<DexPrices>> {
+        let block = metadata.block_num;
+        let id = prices_id(metadata, quote);
+
+        let mut resolved = FastHashMap::default();
+        let mut missing = FastHashSet::default();
+        {
+            let blocks = self.blocks.lock();
+            let cached = blocks.get(&block).filter(|cached| cached.id == id);
+
+            for key in keys {
+                match cached.and_then(|cached| cached.prices.get(&key)) {
+                    Some(price) => {
+                        resolved.insert(key, price.clone());
+                    }
+                    None => {
+                        missing.insert(key);
+                    }
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return resolved
+        }
+
+        // resolve outside of the lock so that other inspectors aren't blocked
+        let fetched = missing
+            .into_iter()
+            .map(|(token, tx_index)| {
+                let price = metadata
+                    .dex_quotes
+                    .as_ref()
+                    .and_then(|quotes| quotes.price_at(Pair(token, quote), tx_index));
+                ((token, tx_index), price)
+            })
+            .collect::<Vec<_>>();
+
+        let mut blocks = self.blocks.lock();
+        if !blocks.contains_key(&block) && blocks.len() >= MAX_CACHED_BLOCKS {
+            if let Some(oldest) = blocks.keys().min().copied() {
+                blocks.remove(&oldest);
+            }
+        }
+
+        let cached = blocks.entry(block).or_default();
+        if cached.id != id {
+            *cached = BlockPrices { id, prices: FastHashMap::default() };
+        }
+
+        for (key, price) in fetched {
+            cached.prices.insert(key, price.clone());
+            resolved.insert(key, price);
+        }
+
+        resolved
+    }
+}
+
+/// Identifies prices by what they're resolved from, the block, the quote asset
+/// and the dex quotes, so that a block that is re-inspected with different
+/// quotes isn't served stale prices.
+fn prices_id(metadata: &Metadata, quote: Address) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (metadata.block_hash, quote).hash(&mut hasher);
+
+    for (tx_index, quotes) in metadata
+        .dex_quotes
+        .iter()
+        .flat_map(|quotes| quotes.0.iter().enumerate())
+    {
+        let Some(quotes) = quotes else { continue };
+        // the iteration order of a map depends on how it was built, so its
+        // entries are combined regardless of order
+        let entries = quotes.iter().fold(0u64, |acc, (pair, price)| {
+            let mut entry = DefaultHasher::new();
+            (pair, &price.pre_state, &price.post_state, price.goes_through, price.is_transfer)
+                .hash(&mut entry);
+            acc.wrapping_add(entry.finish())
+        });
+        (tx_index, entries).hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
@@ -0,0 +1,362 @@
+use std::sync::Arc;
+
+use alloy_primitives::Address;
+use brontes_database::libmdbx::LibmdbxReader;
+use brontes_types::{
+    db::dex::PriceAt,
+    mev::execution_quality::VictimExecution,
+    normalized_actions::{Action, NormalizedAggregator, NormalizedMint, NormalizedSwap},
+    pair::Pair,
+    tree::BlockTree,
+    ToFloatNearest, TreeSearchBuilder, TxInfo,
+};
+use malachite::{num::basic::traits::Zero, Rational};
+
+use super::SandwichInspector;
+use crate::Metadata;
+
+/// Max time in microseconds between the block and a CEX quote for it to be
+/// used as a baseline
+const MAX_CEX_QUOTE_TIME_DIFF: u64 = 2_000_000;
+
+impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
+    /// Compares each victim swap against what it would have received at the
+    /// pool state before the first frontrun and at the best CEX quote.
+    ///
+    /// Dex quotes are pair prices rather than pool simulations, so the
+    /// pre-frontrun baseline scales the victim's actual output by how far the
+    /// frontruns moved the price. This keeps the victim's own price impact in
+    /// the baseline.
+    ///
+    /// When the victim went through an aggregator, the swap ending the route
+    /// also gets the minimum amount out the aggregator was called with and
+    /// the amount of the output token the victim received.
+    ///
+    /// The victim's loss is the shortfall against the pre-frontrun baseline,
+    /// valued at the dex price of the output token at the victim's
+    /// transaction.
+    ///
+    /// Victim mints follow the swaps of their transaction, see
+    /// [`Self::mint_execution`].
+    pub(super) fn victim_execution(
+        &self,
+        tree: &BlockTree<Action>,
+        metadata: &Arc<Metadata>,
+        first_frontrun_idx: u64,
+        victim_info: &[TxInfo],
+        victim_swaps: &[Vec<NormalizedSwap>],
+        victim_mints: &[Vec<NormalizedMint>],
+    ) -> Vec<VictimExecution> {
+        victim_info
+            .iter()
+            .zip(victim_swaps)
+            .zip(victim_mints)
+            .flat_map(|((info, swaps), mints)| {
+                let root = tree.get_root(info.tx_hash);
+                let router = root.map(|root| root.get_to_address()).unwrap_or_default();
+                let route = root.and_then(|root| {
+                    root.collect(&TreeSearchBuilder::default().with_action(Action::is_aggregator))
+                        .into_iter()
+                        .find_map(Action::try_aggregator)
+                });
+                let quote = route
+                    .as_ref()
+                    .and_then(|route| aggregator_quote(route, swaps));
+
+                let mints = mints.iter().filter_map(move |mint| {
+                    self.mint_execution(metadata, first_frontrun_idx, info, router, mint)
+                });
+
+                swaps
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, swap)| {
+                        let pre_frontrun_amount_out = self.pre_frontrun_amount_out(
+                            metadata,
+                            first_frontrun_idx,
+                            info.tx_index,
+                            swap,
+                        );
+                        let loss_usd = pre_frontrun_amount_out.as_ref().and_then(|baseline| {
+                            self.shortfall_usd(metadata, info.tx_index, swap, baseline)
+                        });
+
+                        VictimExecution {
+                            tx_hash: info.tx_hash,
+                            router,
+                            pool: swap.pool,
+                            token_in: swap.token_in.address,
+                            token_out: swap.token_out.address,
+                            amount_in: swap.amount_in.clone().to_float(),
+                            amount_out: swap.amount_out.clone().to_float(),
+                            pre_frontrun_amount_out: pre_frontrun_amount_out
+                                .map(|amount| amount.to_float()),
+                            cex_amount_out: self
+                                .cex_amount_out(metadata, swap)
+                                .map(|amount| amount.to_float()),
+                            quoted_min_amount_out: quote
+                                .filter(|(last_hop, ..)| *last_hop == i)
+                                .and_then(|(_, min_out, _)| min_out),
+                            realized_amount_out: quote
+                                .filter(|(last_hop, ..)| *last_hop == i)
+                                .map(|(.., realized)| realized),
+                            loss_usd,
+                        }
+                    })
+                    .chain(mints)
+            })
+            .collect()
+    }
+
+    /// Compares a two sided victim mint against the price before the first
+    /// frontrun. The liquidity is added at the price the frontrun moved to and
+    /// the backrun moves it back, which costs the position the impermanent
+    /// loss of that move. The loss is the one of a full range position, so
+    /// it's a lower bound for a concentrated one.
+    ///
+    /// The deposited tokens and amounts take the place of the swapped ones.
+    fn mint_execution(
+        &self,
+        metadata: &Arc<Metadata>,
+        first_frontrun_idx: u64,
+        info: &TxInfo,
+        router: Address,
+        mint: &NormalizedMint,
+    ) -> Option<VictimExecution> {
+        let ([token0, token1], [amount0, amount1]) =
+            (mint.token.as_slice(), mint.amount.as_slice())
+        else {
+            return None
+        };
+        let dex_quotes = metadata.dex_quotes.as_ref()?;
+        let pair = Pair(token0.address, token1.address);
+
+        let pre_frontrun = dex_quotes
+            .price_at(pair, first_frontrun_idx as usize)?
+            .get_price(PriceAt::Before)
+            .to_float();
+        let at_mint = dex_quotes
+            .price_at(pair, info.tx_index as usize)?
+            .get_price(PriceAt::Before)
+            .to_float();
+
+        let deposit_usd = [(token0, amount0), (token1, amount1)]
+            .into_iter()
+            .map(|(token, amount)| {
+                self.utils
+                    .get_token_price_on_dex(
+                        first_frontrun_idx as usize,
+                        PriceAt::Before,
+                        token.address,
+                        metadata,
+                    )
+                    .map(|price| (amount * price).to_float())
+            })
+            .sum::<Option<f64>>();
+        let loss_usd = deposit_usd
+            .zip(impermanent_loss(pre_frontrun, at_mint))
+            .map(|(deposit, loss)| deposit * loss);
+
+        Some(VictimExecution {
+            tx_hash: info.tx_hash,
+            router,
+            pool: mint.pool,
+            token_in: token0.address,
+            token_out: token1.address,
+            amount_in: amount0.clone().to_float(),
+            amount_out: amount1.clone().to_float(),
+            loss_usd,
+            ..Default::default()
+        })
+    }
+
+    /// Usd value of what the swap received below its pre-frontrun baseline
+    fn shortfall_usd(
+        &self,
+        metadata: &Arc<Metadata>,
+        victim_idx: u64,
+        swap: &NormalizedSwap,
+        baseline: &Rational,
+    ) -> Option<f64> {
+        if *baseline <= swap.amount_out {
+            return Some(0.0)
+        }
+        let price = self.utils.get_token_price_on_dex(
+            victim_idx as usize,
+            PriceAt::After,
+            swap.token_out.address,
+            metadata,
+        )?;
+
+        Some(((baseline - &swap.amount_out) * price).to_float())
+    }
+
+    fn pre_frontrun_amount_out(
+        &self,
+        metadata: &Metadata,
+        first_frontrun_idx: u64,
+        victim_idx: u64,
+        swap: &NormalizedSwap,
+    ) -> Option<Rational> {
+        let dex_quotes = metadata.dex_quotes.as_ref()?;
+        let pair = Pair(swap.token_in.address, swap.token_out.address);
+
+        let pre_frontrun = dex_quotes
+            .price_at(pair, first_frontrun_idx as usize)?
+            .get_price(PriceAt::Before);
+        let pre_victim = dex_quotes
+            .price_at(pair, victim_idx as usize)?
+            .get_price(PriceAt::Before);
+
+        scale_to_pre_frontrun(&swap.amount_out, &pre_frontrun, &pre_victim)
+    }
+
+    /// Amount out at the fee adjusted taker price of the exchange that would
+    /// have given the victim the most
+    fn cex_amount_out(&self, metadata: &Metadata, swap: &NormalizedSwap) -> Option<Rational> {
+        // quoted in token in per token out
+        let pair = Pair(swap.token_in.address, swap.token_out.address);
+
+        metadata
+            .cex_quotes
+            .quotes
+            .keys()
+            .filter_map(|exchange| {
+                metadata.cex_quotes.get_quote_at(
+                    &pair,
+                    exchange,
+                    metadata.microseconds_block_timestamp(),
+                    Some(MAX_CEX_QUOTE_TIME_DIFF),
+                )
+            })
+            .map(|quote| quote.maker_taker_mid().1)
+            .filter(|price| *price != Rational::ZERO)
+            .min()
+            .map(|price| &swap.amount_in / price)
+    }
+}
+
+/// Scales the victim's amount out by how far the price moved between the
+/// first frontrun and the victim
+fn scale_to_pre_frontrun(
+    amount_out: &Rational,
+    pre_frontrun: &Rational,
+    pre_victim: &Rational,
+) -> Option<Rational> {
+    (*pre_victim != Rational::ZERO).then(|| amount_out * pre_frontrun / pre_victim)
+}
+
+/// Share of the value of a full range position lost when the price moves
+/// from the one it was added at back to the fair one
+fn impermanent_loss(fair_price: f64, mint_price: f64) -> Option<f64> {
+    if fair_price <= 0.0 || mint_price <= 0.0 {
+        return None
+    }
+    let ratio = fair_price / mint_price;
+
+    Some(1.0 - 2.0 * ratio.sqrt() / (1.0 + ratio))
+}
+
+/// Index of the victim swap that ends the aggregator route, with the quoted
+/// minimum and the realized amount of the route's output token
+fn aggregator_quote(
+    route: &NormalizedAggregator,
+    swaps: &[NormalizedSwap],
+) -> Option<(usize, Option<f64>, f64)> {
+    let (token_out, realized) = route.realized_amount_out()?;
+    let last_hop = swaps
+        .iter()
+        .rposition(|swap| swap.token_out.address == token_out.address)?;
+
+    Some((
+        last_hop,
+        route
+            .quoted_min_amount_out(&token_out)
+            .map(|amount| amount.to_float()),
+        realized.to_float(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_to_pre_frontrun() {
+        // the frontrun moved the price from 2 to 1.6 token out per token in, so
+        // the victim's 80 would have been 100 before it
+        let baseline = scale_to_pre_frontrun(
+            &Rational::from(80),
+            &Rational::from(2),
+            &Rational::from_signeds(8, 5),
+        );
+        assert_eq!(baseline, Some(Rational::from(100)));
+
+        assert_eq!(
+            scale_to_pre_frontrun(&Rational::from(80), &Rational::from(2), &Rational::ZERO),
+            None
+        );
+    }
+
+    #[test]
+    fn test_impermanent_loss() {
+        assert_eq!(impermanent_loss(2_000.0, 2_000.0), Some(0.0));
+        // a 2x move costs a full range position about 5.7%
+        let loss = impermanent_loss(2_000.0, 4_000.0).unwrap();
+        assert!((loss - 0.0572).abs() < 1e-4);
+        assert!((impermanent_loss(2_000.0, 1_000.0).unwrap() - loss).abs() < 1e-12);
+        assert_eq!(impermanent_loss(0.0, 1_000.0), None);
+    }
+
+    #[test]
+    fn test_aggregator_quote() {
+        use alloy_primitives::{Address, U256};
+        use brontes_types::{
+            db::token_info::{TokenInfo, TokenInfoWithAddress},
+            normalized_actions::NormalizedTransfer,
+            Protocol,
+        };
+
+        let user = Address::repeat_byte(1);
+        let token = |byte| TokenInfoWithAddress {
+            address: Address::repeat_byte(byte),
+            inner:   TokenInfo { decimals: 6, ..Default::default() },
+        };
+        let swap = |token_in: u8, token_out: u8, amount_out: u64| NormalizedSwap {
+            token_in: token(token_in),
+            token_out: token(token_out),
+            amount_out: Rational::from(amount_out),
+            ..Default::default()
+        };
+        let hops = vec![swap(1, 2, 50), swap(2, 3, 100)];
+        let route = NormalizedAggregator {
+            protocol:       Protocol::OneInchV5,
+            trace_index:    0,
+            from:           user,
+            to:             Address::repeat_byte(2),
+            recipient:      user,
+            child_actions:  vec![
+                Action::Swap(hops[0].clone()),
+                Action::Swap(hops[1].clone()),
+                Action::Transfer(NormalizedTransfer {
+                    to: user,
+                    token: token(3),
+                    amount: Rational::from(99),
+                    ..Default::default()
+                }),
+            ],
+            min_amount_out: Some(U256::from(95_000_000u64)),
+            referrer:       None,
+            msg_value:      U256::ZERO,
+        };
+
+        // the quote lands on the victim swap that ends the route
+        assert_eq!(aggregator_quote(&route, &hops), Some((1, Some(95.0), 99.0)));
+
+        // the victim's swaps don't reach the route's output token
+        assert_eq!(aggregator_quote(&route, &hops[..1]), None);
+
+        let no_min = NormalizedAggregator { min_amount_out: None, ..route };
+        assert_eq!(aggregator_quote(&no_min, &hops), Some((1, None, 99.0)));
+    }
+}
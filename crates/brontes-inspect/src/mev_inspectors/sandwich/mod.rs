@@ -5,18 +5,21 @@ use std::{
 
 use alloy_primitives::TxHash;
 use tracing::trace;
+mod execution_quality;
 mod types;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
-    db::dex::PriceAt,
-    mev::{Bundle, BundleData, MevType, Sandwich},
+    db::{dex::PriceAt, inspector_artifacts::ArtifactKind},
+    mev::{Bundle, BundleData, MevType, Sandwich, VictimActionType},
     normalized_actions::{
-        accounting::ActionAccounting, Action, NormalizedSwap, NormalizedTransfer,
+        accounting::ActionAccounting, Action, NormalizedLiquidation, NormalizedMint,
+        NormalizedSwap, NormalizedTransfer,
     },
     tree::{collect_address_set_for_accounting, BlockTree, GasDetails},
-    ActionIter, BlockData, FastHashMap, FastHashSet, IntoZipTree, MultiBlockData, ToFloatNearest,
-    TreeBase, TreeCollector, TreeIter, TreeSearchBuilder, TxInfo, UnzipPadded,
+    ActionIter, BlockActionSummary, BlockData, FastHashMap, FastHashSet, IntoZipTree,
+    MultiBlockData, ToFloatNearest, TreeBase, TreeCollector, TreeIter, TreeSearchBuilder, TxInfo,
+    UnzipPadded,
 };
 use itertools::Itertools;
 use malachite::{num::basic::traits::Zero, Rational};
@@ -26,9 +29,13 @@ use types::{PossibleSandwich, PossibleSandwichWithTxInfo};
 use super::MAX_PROFIT;
 use crate::{shared_utils::SharedInspectorUtils, Inspector, Metadata};
 
-type GroupedVictims<'a> = HashMap<Address, Vec<&'a (Vec<NormalizedSwap>, Vec<NormalizedTransfer>)>>;
+/// The price sensitive actions of a victim transaction and its transfers
+type VictimActions =
+    (Vec<NormalizedSwap>, Vec<NormalizedTransfer>, Vec<NormalizedLiquidation>, Vec<NormalizedMint>);
 
-type VictimSetActions = Option<Vec<Vec<(Vec<NormalizedSwap>, Vec<NormalizedTransfer>)>>>;
+type GroupedVictims<'a> = HashMap<Address, Vec<&'a VictimActions>>;
+
+type VictimSetActions = Option<Vec<Vec<VictimActions>>>;
 
 /// the price difference was more than 90% between dex pricing and effective
 /// price, we put this so high due to the inner swap price manipulation
@@ -53,6 +60,11 @@ impl<DB: LibmdbxReader> Inspector for SandwichInspector<'_, DB> {
         "Sandwich"
     }
 
+    fn should_inspect(&self, summary: &BlockActionSummary) -> bool {
+        // needs at least a frontrun and a backrun
+        summary.possible_swap_txs() >= 2
+    }
+
     fn get_quote_token(&self) -> Address {
         self.utils.quote
     }
@@ -80,8 +92,11 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         tracing::trace!("starting sandwich");
         let search_args = TreeSearchBuilder::default().with_actions([
             Action::is_swap,
+            Action::is_fill,
             Action::is_transfer,
             Action::is_eth_transfer,
+            Action::is_liquidation,
+            Action::is_mint,
             Action::is_nested_action,
         ]);
 
@@ -122,6 +137,17 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             possible_backrun_info,
         } = ps;
 
+        metadata.record_artifact(
+            self.get_id(),
+            ArtifactKind::Candidate,
+            &possible_frontruns
+                .iter()
+                .copied()
+                .chain(std::iter::once(possible_backrun))
+                .collect::<Vec<_>>(),
+            || format!("{} possible victims", victims.iter().flatten().count()),
+        );
+
         if victims.iter().flatten().count() == 0 {
             return None
         };
@@ -175,7 +201,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         backrun_info: TxInfo,
         mut searcher_actions: Vec<Vec<Action>>,
         victim_info: Vec<Vec<TxInfo>>,
-        victim_actions: Vec<Vec<(Vec<NormalizedSwap>, Vec<NormalizedTransfer>)>>,
+        victim_actions: Vec<Vec<VictimActions>>,
         black_list: FastHashSet<Address>,
         recusive: u8,
     ) -> Option<Vec<Bundle>> {
@@ -194,6 +220,16 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                 == 1)
         {
             tracing::debug!(target: "brontes_inspect::sandwich", "all sandwiches don't have same eoa and aren't all verified contracts");
+            metadata.record_artifact(
+                self.get_id(),
+                ArtifactKind::Rejection,
+                &possible_front_runs_info
+                    .iter()
+                    .chain(std::iter::once(&backrun_info))
+                    .map(|info| info.tx_hash)
+                    .collect::<Vec<_>>(),
+                || "the legs don't share an eoa and aren't all mev contracts".to_string(),
+            );
             return None
         }
 
@@ -256,7 +292,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
 
         // if we reach this part of the code, we have found a sandwich and
         // are now going to collect the details for the given sandwich
-        let victim_swaps = victim_actions.into_iter().flatten().collect::<Vec<_>>();
+        let victim_actions = victim_actions.into_iter().flatten().collect::<Vec<_>>();
         let back_run_swaps = back_run_actions
             .clone()
             .into_iter()
@@ -410,18 +446,33 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             metadata.clone(),
             MevType::Sandwich,
             !has_dex_price,
-            |this, token, amount| {
+            |this, amount| {
                 this.get_token_value_dex(
                     backrun_info.tx_index as usize,
                     PriceAt::Average,
-                    token,
-                    &amount,
+                    amount,
                     &metadata,
                 )
             },
         );
 
-        let victim_swaps = victim_swaps.into_iter().map(|(s, _)| s).collect_vec();
+        let victim_action_types = victim_actions
+            .iter()
+            .map(|(swaps, _, liquidations, mints)| VictimActionType::of(swaps, liquidations, mints))
+            .collect_vec();
+        let (victim_swaps, victim_liquidations, victim_mints): (Vec<_>, Vec<_>, Vec<_>) =
+            victim_actions
+                .into_iter()
+                .map(|(swaps, _, liquidations, mints)| (swaps, liquidations, mints))
+                .multiunzip();
+        let victim_execution = self.victim_execution(
+            &tree,
+            &metadata,
+            possible_front_runs_info.first()?.tx_index,
+            &victim_info.iter().flatten().cloned().collect_vec(),
+            &victim_swaps,
+            &victim_mints,
+        );
 
         let sandwich = Sandwich {
             block_number: metadata.block_num,
@@ -434,6 +485,10 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
             backrun_tx_hash: backrun_info.tx_hash,
             backrun_swaps: back_run_swaps,
             backrun_gas_details: backrun_info.gas_details,
+            victim_execution,
+            victim_liquidations,
+            victim_mints,
+            victim_action_types,
         };
         tracing::debug!("{:#?}\n{:#?}", header, sandwich);
 
@@ -455,7 +510,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         back_run_actions: &[Action],
         searcher_actions: &[Vec<Action>],
         victim_info: &[Vec<TxInfo>],
-        victim_actions: &[Vec<(Vec<NormalizedSwap>, Vec<NormalizedTransfer>)>],
+        victim_actions: &[Vec<VictimActions>],
         black_list: FastHashSet<Address>,
         mut recursive: u8,
     ) -> Option<Vec<Bundle>> {
@@ -483,11 +538,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                 if victim_actions
                     .iter()
                     .flatten()
-                    .filter_map(
-                        |(s, t)| if s.is_empty() && t.is_empty() { None } else { Some(true) },
-                    )
-                    .count()
-                    == 0
+                    .all(|actions| !Self::has_victim_actions(actions))
                 {
                     return None
                 }
@@ -521,11 +572,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                 if victim_actions
                     .iter()
                     .flatten()
-                    .filter_map(
-                        |(s, t)| if s.is_empty() && t.is_empty() { None } else { Some(true) },
-                    )
-                    .count()
-                    == 0
+                    .all(|actions| !Self::has_victim_actions(actions))
                 {
                     return None
                 }
@@ -558,7 +605,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
     fn has_pool_overlap(
         front_run_swaps: &[Vec<Action>],
         back_run_swaps: &[Action],
-        victim_actions: &[Vec<(Vec<NormalizedSwap>, Vec<NormalizedTransfer>)>],
+        victim_actions: &[Vec<VictimActions>],
         victim_info: &[Vec<TxInfo>],
         black_list: &FastHashSet<Address>,
     ) -> bool {
@@ -674,7 +721,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                 );
 
                 let generated_pool_overlap = Self::generate_possible_pools_from_transfers(
-                    v.into_iter().flat_map(|(_, t)| t),
+                    v.into_iter().flat_map(|(_, t, ..)| t),
                     black_list,
                 )
                 .any(|pool| {
@@ -684,9 +731,15 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
 
                     front_run_pools.contains(&pool) || back_run_pools.contains(&pool)
                 });
-                has_sandwich |= pools_overlap || token_overlap;
+                // liquidations are only victims through the swaps selling their
+                // collateral, lending protocols don't price off the pools
+                let mint_overlap = v
+                    .iter()
+                    .flat_map(|(.., mints)| mints)
+                    .any(|mint| is_sandwiched_mint(mint, &front_run_tokens, &back_run_tokens));
+                has_sandwich |= pools_overlap || token_overlap || mint_overlap;
 
-                pools_overlap || token_overlap || generated_pool_overlap
+                pools_overlap || token_overlap || generated_pool_overlap || mint_overlap
             })
             .map(|was_victim| was_victim as usize)
             .sum();
@@ -701,7 +754,7 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
 
     /// returns pool address, and token_address
     fn check_for_overlap(
-        victim_actions: &[&(Vec<NormalizedSwap>, Vec<NormalizedTransfer>)],
+        victim_actions: &[&VictimActions],
         tokens: &FastHashSet<(Address, Address, bool)>,
         pools: &FastHashSet<Address>,
         is_frontrun: bool,
@@ -712,8 +765,8 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         victim_actions
             .iter()
             .cloned()
-            .filter(|(swap, transfer)| !(swap.is_empty() && transfer.is_empty()))
-            .for_each(|(swaps, transfers)| {
+            .filter(|actions| Self::has_victim_actions(actions))
+            .for_each(|(swaps, transfers, ..)| {
                 matched_pools.extend(
                     swaps
                         .iter()
@@ -738,6 +791,10 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
         (matched_pools, matched_tokens)
     }
 
+    fn has_victim_actions((swaps, transfers, liquidations, mints): &VictimActions) -> bool {
+        !(swaps.is_empty() && transfers.is_empty() && liquidations.is_empty() && mints.is_empty())
+    }
+
     // collect all addresses that have exactly two transfers two and from.
     // this should cover all pools that we didn't have classified
     fn collect_frontrun_data(
@@ -967,8 +1024,15 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                     tree.clone()
                         .collect_txes(&victim, search_args.clone())
                         .t_map(|actions| {
+                            // a victim's order is filled at the maker's price, so the maker
+                            // is the one being sandwiched rather than the filler
                             self.utils
-                                .flatten_nested_actions_default(actions.into_iter())
+                                .flatten_nested_actions_default(actions.into_iter().map(|action| {
+                                    match action {
+                                        Action::Fill(fill) => Action::Swap(fill.maker_swap()),
+                                        action => action,
+                                    }
+                                }))
                         }),
                     victim,
                 )
@@ -977,10 +1041,13 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
                 let tree = victim_set.tree();
                 let actions = victim_set
                     .map(|s| {
-                        s.into_iter().split_actions::<(Vec<_>, Vec<_>), _>((
-                            Action::try_swaps_merged,
-                            Action::try_transfer,
-                        ))
+                        s.into_iter()
+                            .split_actions::<(Vec<_>, Vec<_>, Vec<_>, Vec<_>), _>((
+                                Action::try_swaps_merged,
+                                Action::try_transfer,
+                                Action::try_liquidation,
+                                Action::try_mint,
+                            ))
                     })
                     .into_zip_tree(tree)
                     .tree_zip_with(hashes.into_iter())
@@ -1013,6 +1080,24 @@ impl<DB: LibmdbxReader> SandwichInspector<'_, DB> {
     }
 }
 
+/// Liquidity added on both sides of a pool the frontrun pushed the price of
+/// and the backrun pushed back. The deposit is made at the manipulated ratio
+/// and loses to the backrun, a single sided deposit is out of range of the
+/// price and isn't affected
+fn is_sandwiched_mint(
+    mint: &NormalizedMint,
+    front_run_tokens: &FastHashSet<(Address, Address, bool)>,
+    back_run_tokens: &FastHashSet<(Address, Address, bool)>,
+) -> bool {
+    let two_sided = mint.amount.len() == 2 && mint.amount.iter().all(|a| *a > Rational::ZERO);
+
+    two_sided
+        && mint.token.iter().any(|token| {
+            front_run_tokens.contains(&(token.address, mint.pool, true))
+                && back_run_tokens.contains(&(token.address, mint.pool, false))
+        })
+}
+
 fn get_possible_sandwich_duplicate_senders(tree: Arc<BlockTree<Action>>) -> Vec<PossibleSandwich> {
     let mut duplicate_senders: FastHashMap<Address, B256> = FastHashMap::default();
     let mut possible_victims: FastHashMap<B256, Vec<B256>> = FastHashMap::default();
@@ -1139,7 +1224,10 @@ fn get_possible_sandwich_duplicate_contracts(
 mod tests {
 
     use alloy_primitives::hex;
-    use brontes_types::constants::{DAI_ADDRESS, USDT_ADDRESS, WETH_ADDRESS};
+    use brontes_types::{
+        constants::{DAI_ADDRESS, USDT_ADDRESS, WETH_ADDRESS},
+        db::token_info::TokenInfoWithAddress,
+    };
 
     use super::*;
     use crate::{
@@ -1507,4 +1595,57 @@ mod tests {
 
         inspector_util.assert_no_mev(config).await.unwrap();
     }
+
+    fn mint(pool: Address, amounts: [u64; 2]) -> NormalizedMint {
+        NormalizedMint {
+            pool,
+            token: [WETH_ADDRESS, DAI_ADDRESS]
+                .map(|address| TokenInfoWithAddress { address, ..Default::default() })
+                .to_vec(),
+            amount: amounts.map(Rational::from).to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sandwiched_mint() {
+        let pool = Address::repeat_byte(1);
+        // the frontrun buys dai with weth and the backrun sells it back
+        let front_run_tokens: FastHashSet<_> =
+            [(WETH_ADDRESS, pool, true), (DAI_ADDRESS, pool, false)]
+                .into_iter()
+                .collect();
+        let back_run_tokens: FastHashSet<_> =
+            [(DAI_ADDRESS, pool, true), (WETH_ADDRESS, pool, false)]
+                .into_iter()
+                .collect();
+
+        assert!(is_sandwiched_mint(&mint(pool, [1, 2_000]), &front_run_tokens, &back_run_tokens));
+        // out of range of the price
+        assert!(!is_sandwiched_mint(&mint(pool, [1, 0]), &front_run_tokens, &back_run_tokens));
+        // a pool the sandwich didn't trade through
+        assert!(!is_sandwiched_mint(
+            &mint(Address::repeat_byte(2), [1, 2_000]),
+            &front_run_tokens,
+            &back_run_tokens
+        ));
+        // only the frontrun moved the price
+        assert!(!is_sandwiched_mint(
+            &mint(pool, [1, 2_000]),
+            &front_run_tokens,
+            &FastHashSet::default()
+        ));
+    }
+
+    #[test]
+    fn test_victim_action_type() {
+        let swaps = vec![NormalizedSwap::default()];
+        let liquidations = vec![NormalizedLiquidation::default()];
+        let mints = vec![NormalizedMint::default()];
+
+        assert_eq!(VictimActionType::of(&swaps, &[], &mints), VictimActionType::Swap);
+        // matched through the swap selling the seized collateral
+        assert_eq!(VictimActionType::of(&swaps, &liquidations, &[]), VictimActionType::Liquidation);
+        assert_eq!(VictimActionType::of(&[], &[], &mints), VictimActionType::Mint);
+    }
 }
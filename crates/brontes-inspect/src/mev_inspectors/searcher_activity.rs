@@ -3,7 +3,7 @@ use std::sync::Arc;
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
-    db::dex::BlockPrice,
+    db::{dex::BlockPrice, inspector_artifacts::ArtifactKind},
     mev::{Bundle, BundleData, MevType, SearcherTx},
     normalized_actions::{accounting::ActionAccounting, Action},
     tree::BlockTree,
@@ -92,6 +92,12 @@ impl<DB: LibmdbxReader> SearcherActivity<'_, DB> {
                             ) {
                             (Some(rev), true)
                         } else {
+                            metadata.record_artifact(
+                                self.get_id(),
+                                ArtifactKind::PriceInput,
+                                &[tx_hash],
+                                || "no dex price for the searcher's deltas".to_string(),
+                            );
                             (Some(Rational::ZERO), false)
                         };
 
@@ -104,6 +110,12 @@ impl<DB: LibmdbxReader> SearcherActivity<'_, DB> {
                             .unwrap_or_default();
 
                         if profit >= MAX_PROFIT || profit <= -MAX_PROFIT {
+                            metadata.record_artifact(
+                                self.get_id(),
+                                ArtifactKind::PriceInput,
+                                &[tx_hash],
+                                || format!("profit of {} usd is out of bounds", profit.to_float()),
+                            );
                             has_dex_price = false;
                             profit = Rational::ZERO;
                         }
@@ -4,18 +4,20 @@ use alloy_primitives::{Address, FixedBytes};
 use brontes_database::libmdbx::LibmdbxReader;
 use brontes_metrics::inspectors::OutlierMetrics;
 use brontes_types::{
+    constants::WETH_ADDRESS,
     db::{
         dex::{BlockPrice, PriceAt},
+        lst::is_liquid_staking_token,
         metadata::Metadata,
         token_info::TokenInfoWithAddress,
     },
     mev::{
         AddressBalanceDeltas, Bundle, BundleHeader, Mev, MevType, TokenBalanceDelta,
-        TransactionAccounting,
+        TransactionAccounting, ValuationMethod, ValueSplit,
     },
     normalized_actions::{
-        Action, NormalizedAggregator, NormalizedBatch, NormalizedFlashLoan, NormalizedSwap,
-        NormalizedTransfer,
+        Action, NormalizedAggregator, NormalizedBatch, NormalizedFill, NormalizedFlashLoan,
+        NormalizedSwap, NormalizedTransfer, TokenAmount, FULL_CONFIDENCE,
     },
     pair::Pair,
     utils::ToFloatNearest,
@@ -31,16 +33,24 @@ use malachite::{
 };
 use reth_primitives::TxHash;
 
+use super::price_cache::DexPriceCache;
+
 #[derive(Debug)]
 pub struct SharedInspectorUtils<'db, DB: LibmdbxReader> {
     pub(crate) quote: Address,
     pub(crate) db:    &'db DB,
     pub metrics:      Option<OutlierMetrics>,
+    price_cache:      DexPriceCache,
 }
 
 impl<'db, DB: LibmdbxReader> SharedInspectorUtils<'db, DB> {
     pub fn new(quote_address: Address, db: &'db DB, metrics: Option<OutlierMetrics>) -> Self {
-        SharedInspectorUtils { quote: quote_address, db, metrics }
+        SharedInspectorUtils {
+            quote: quote_address,
+            db,
+            metrics,
+            price_cache: DexPriceCache::default(),
+        }
     }
 }
 type TokenDeltas = FastHashMap<Address, Rational>;
@@ -64,14 +74,39 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
     ) -> Option<FastHashMap<Address, Rational>> {
         let mut usd_deltas = FastHashMap::default();
 
+        let dex_prices = (!cex && !at_or_before).then(|| {
+            self.get_token_prices_on_dex(
+                at,
+                deltas
+                    .values()
+                    .flatten()
+                    .filter(|(_, amount)| *amount != &Rational::ZERO)
+                    .map(|(token, _)| (*token, tx_position as usize)),
+                &metadata,
+            )
+        });
+
         for (address, token_deltas) in deltas {
             for (token_addr, amount) in token_deltas {
                 if amount == &Rational::ZERO {
                     continue
                 }
 
+                if metadata.is_nft_collection(*token_addr) {
+                    // nfts without a floor price are left out rather than failing the bundle
+                    let Some(floor) = metadata.get_nft_floor_price(*token_addr, self.quote) else {
+                        continue
+                    };
+                    *usd_deltas.entry(*address).or_insert(Rational::ZERO) += amount * floor;
+                    continue
+                }
+
                 let pair = Pair(*token_addr, self.quote);
-                let price = if cex {
+                let price = if let Some(dex_prices) = &dex_prices {
+                    dex_prices
+                        .get(&(*token_addr, tx_position as usize))?
+                        .clone()
+                } else if cex {
                     metadata
                         .cex_quotes
                         .get_quote_from_most_liquid_exchange(
@@ -81,23 +116,16 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
                         )?
                         .price_maker
                         .1
-                } else if at_or_before {
-                    metadata
-                        .dex_quotes
-                        .as_ref()?
-                        .price_at_or_before(pair, tx_position as usize)
-                        .map(|price| price.get_price(at))?
-                        .clone()
                 } else {
                     metadata
                         .dex_quotes
                         .as_ref()?
-                        .price_at(pair, tx_position as usize)
+                        .price_at_or_before(pair, tx_position as usize)
                         .map(|price| price.get_price(at))?
                         .clone()
                 };
 
-                let usd_amount = amount.clone() * price.clone();
+                let usd_amount = amount.clone() * price;
 
                 *usd_deltas.entry(*address).or_insert(Rational::ZERO) += usd_amount;
             }
@@ -144,6 +172,13 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
                 .filter(&filter_actions)
                 .collect::<Vec<_>>()
         })
+        // a filled order is a swap of the taker against the order's maker
+        .flatten_specified(Action::try_fill_ref, move |fill: NormalizedFill| {
+            Some(Action::Swap(fill.taker_swap()))
+                .into_iter()
+                .filter(&filter_actions)
+                .collect::<Vec<_>>()
+        })
     }
 
     /// defaults to zero for price if doesn't exist
@@ -157,20 +192,25 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
     ) -> Rational {
         let mut usd_deltas = FastHashMap::default();
 
+        let prices = self.get_token_prices_on_dex(
+            at,
+            deltas
+                .values()
+                .flatten()
+                .filter(|(_, amount)| *amount != &Rational::ZERO)
+                .map(|(token, _)| (*token, tx_index as usize)),
+            &metadata,
+        );
+
         for (address, token_deltas) in deltas {
             for (token_addr, amount) in token_deltas {
                 if amount == &Rational::ZERO {
                     continue
                 }
 
-                let pair = Pair(*token_addr, self.quote);
-                let price = metadata
-                    .dex_quotes
-                    .as_ref()
-                    .and_then(|dq| {
-                        dq.price_at(pair, tx_index as usize)
-                            .map(|price| price.get_price(at))
-                    })
+                let price = prices
+                    .get(&(*token_addr, tx_index as usize))
+                    .cloned()
                     .unwrap_or_default();
 
                 let usd_amount = amount.clone() * price;
@@ -259,29 +299,27 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         &self,
         tx_index: usize,
         at: PriceAt,
-        token_address: Address,
-        amount: &Rational,
+        amount: &TokenAmount,
         metadata: &Arc<Metadata>,
     ) -> Option<Rational> {
-        if token_address == self.quote {
-            return Some(amount.clone())
+        if amount.token == self.quote {
+            return Some(amount.amount().clone())
         }
-        let price = self.get_token_price_on_dex(tx_index, at, token_address, metadata)?;
-        Some(price * amount)
+        let price = self.get_token_price_on_dex(tx_index, at, amount.token, metadata)?;
+        Some(amount.usd_value(&price))
     }
 
     pub fn get_token_value_dex_block(
         &self,
         block_price: BlockPrice,
-        token_address: Address,
-        amount: &Rational,
+        amount: &TokenAmount,
         metadata: &Arc<Metadata>,
     ) -> Option<Rational> {
-        if token_address == self.quote {
-            return Some(amount.clone())
+        if amount.token == self.quote {
+            return Some(amount.amount().clone())
         }
-        let price = self.get_token_price_on_dex_block(block_price, token_address, metadata)?;
-        Some(price * amount)
+        let price = self.get_token_price_on_dex_block(block_price, amount.token, metadata)?;
+        Some(amount.usd_value(&price))
     }
 
     pub fn get_token_price_on_dex(
@@ -295,15 +333,157 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
             return Some(Rational::ONE)
         }
 
-        let pair = Pair(token_address, self.quote);
+        self.get_token_prices_on_dex(at, [(token_address, tx_index)], metadata)
+            .remove(&(token_address, tx_index))
+    }
 
-        Some(
-            metadata
-                .dex_quotes
-                .as_ref()?
-                .price_at(pair, tx_index)?
-                .get_price(at),
-        )
+    /// Batched version of [`Self::get_token_price_on_dex`]. Resolves the
+    /// prices of all `(token, tx_index)` pairs at once, memoizing them for the
+    /// rest of the block. Pairs without a price are omitted.
+    pub fn get_token_prices_on_dex(
+        &self,
+        at: PriceAt,
+        tokens: impl IntoIterator<Item = (Address, usize)>,
+        metadata: &Arc<Metadata>,
+    ) -> FastHashMap<(Address, usize), Rational> {
+        let (nfts, tokens): (Vec<_>, Vec<_>) = tokens
+            .into_iter()
+            .partition(|(token, _)| metadata.is_nft_collection(*token));
+        let (shares, tokens): (Vec<_>, Vec<_>) = tokens
+            .into_iter()
+            .partition(|(token, _)| metadata.get_vault_rate(*token).is_some());
+        let share_prices = self.get_vault_share_prices(at, &shares, metadata);
+        let (lsts, mut tokens): (Vec<_>, Vec<_>) = tokens
+            .into_iter()
+            .partition(|(token, _)| is_liquid_staking_token(*token));
+
+        // liquid staking tokens without a rate fall back to their dex price
+        let lst_prices = self.get_lst_prices(at, &lsts, metadata);
+        tokens.extend(lsts.into_iter().filter(|key| !lst_prices.contains_key(key)));
+
+        self.price_cache
+            .get_prices(metadata, self.quote, tokens)
+            .into_iter()
+            .filter_map(|(key, price)| Some((key, price?.get_price(at))))
+            .chain(
+                nfts.into_iter().filter_map(|key| {
+                    Some((key, metadata.get_nft_floor_price(key.0, self.quote)?))
+                }),
+            )
+            .chain(lst_prices)
+            .chain(share_prices)
+            .collect()
+    }
+
+    /// Prices vault shares at their exchange rate to the vault's underlying
+    /// asset times the asset's price at the same transaction
+    fn get_vault_share_prices(
+        &self,
+        at: PriceAt,
+        shares: &[(Address, usize)],
+        metadata: &Arc<Metadata>,
+    ) -> FastHashMap<(Address, usize), Rational> {
+        if shares.is_empty() {
+            return FastHashMap::default()
+        }
+
+        let underlying_prices = self.get_token_prices_on_dex(
+            at,
+            shares
+                .iter()
+                .filter_map(|(share, tx_index)| {
+                    Some((metadata.get_vault_rate(*share)?.underlying, *tx_index))
+                })
+                .filter(|(underlying, _)| *underlying != self.quote),
+            metadata,
+        );
+
+        shares
+            .iter()
+            .filter_map(|&(share, tx_index)| {
+                let vault = metadata.get_vault_rate(share)?;
+                let underlying_price = if vault.underlying == self.quote {
+                    Rational::ONE
+                } else {
+                    underlying_prices
+                        .get(&(vault.underlying, tx_index))?
+                        .clone()
+                };
+
+                Some(((share, tx_index), &vault.rate * underlying_price))
+            })
+            .collect()
+    }
+
+    /// Prices liquid staking tokens at their exchange rate to ETH times the
+    /// ETH price at the same transaction, as their own pools drift from the
+    /// rate they can be redeemed at
+    fn get_lst_prices(
+        &self,
+        at: PriceAt,
+        lsts: &[(Address, usize)],
+        metadata: &Arc<Metadata>,
+    ) -> FastHashMap<(Address, usize), Rational> {
+        if lsts.is_empty() {
+            return FastHashMap::default()
+        }
+
+        let eth_prices = if self.quote == WETH_ADDRESS {
+            FastHashMap::default()
+        } else {
+            self.price_cache.get_prices(
+                metadata,
+                self.quote,
+                lsts.iter().map(|(_, tx_index)| (WETH_ADDRESS, *tx_index)),
+            )
+        };
+
+        lsts.iter()
+            .filter_map(|&(token, tx_index)| {
+                let rate = metadata.get_lst_rate(token)?;
+                let eth_price = if self.quote == WETH_ADDRESS {
+                    Rational::ONE
+                } else {
+                    eth_prices
+                        .get(&(WETH_ADDRESS, tx_index))?
+                        .as_ref()?
+                        .get_price(at)
+                };
+
+                Some(((token, tx_index), rate * eth_price))
+            })
+            .collect()
+    }
+
+    /// Batched version of [`Self::get_token_value_dex`]. Values all
+    /// `(tx_index, amount)` entries in a single pass, with `None` for entries
+    /// whose token has no price.
+    pub fn get_token_values_dex(
+        &self,
+        at: PriceAt,
+        amounts: &[(usize, &TokenAmount)],
+        metadata: &Arc<Metadata>,
+    ) -> Vec<Option<Rational>> {
+        let prices = self.get_token_prices_on_dex(
+            at,
+            amounts
+                .iter()
+                .filter(|(_, amount)| amount.token != self.quote)
+                .map(|(tx_index, amount)| (amount.token, *tx_index)),
+            metadata,
+        );
+
+        amounts
+            .iter()
+            .map(|(tx_index, amount)| {
+                if amount.token == self.quote {
+                    return Some(amount.amount().clone())
+                }
+                prices
+                    .get(&(amount.token, *tx_index))
+                    .map(|price| amount.usd_value(price))
+            })
+            .collect()
     }
 
     pub fn get_token_price_on_dex_block(
@@ -338,8 +518,8 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         }
 
         let balance_deltas =
-            self.get_bundle_accounting(bundle_txes, bundle_deltas, |this, token, amount| {
-                this.get_token_value_dex_block(price_type, token, &amount, &metadata)
+            self.get_bundle_accounting(bundle_txes, bundle_deltas, &metadata, |this, amount| {
+                this.get_token_value_dex_block(price_type, amount, &metadata)
             });
 
         let bribe_usd = gas_details
@@ -361,6 +541,8 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
             block_number: metadata.block_num,
             tx_index: info.tx_index,
             tx_hash: info.tx_hash,
+            // set by the composer once the bundle is finalized
+            bundle_id: FixedBytes::ZERO,
             eoa: info.eoa,
             fund,
             mev_contract: info.mev_contract,
@@ -369,6 +551,15 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
             mev_type,
             no_pricing_calculated,
             balance_deltas,
+            // set by the composer once the bundle is finalized
+            min_action_confidence: FULL_CONFIDENCE,
+            mean_action_confidence: FULL_CONFIDENCE,
+            // set by the composer once all bundles of the block are known
+            merged_submission: false,
+            co_bundled_txs: vec![],
+            value_split: ValueSplit::default(),
+            capital: Default::default(),
+            spam_profit_usd: 0.0,
         }
     }
 
@@ -382,13 +573,14 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         metadata: Arc<Metadata>,
         mev_type: MevType,
         no_pricing_calculated: bool,
-        price_f: impl Fn(&Self, Address, Rational) -> Option<Rational>,
+        price_f: impl Fn(&Self, &TokenAmount) -> Option<Rational>,
     ) -> BundleHeader {
         if no_pricing_calculated {
             profit_usd = 0.0;
         }
 
-        let balance_deltas = self.get_bundle_accounting(bundle_txes, bundle_deltas, price_f);
+        let balance_deltas =
+            self.get_bundle_accounting(bundle_txes, bundle_deltas, &metadata, price_f);
 
         let bribe_usd = gas_details
             .iter()
@@ -415,6 +607,8 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
             block_number: metadata.block_num,
             tx_index: info.tx_index,
             tx_hash: info.tx_hash,
+            // set by the composer once the bundle is finalized
+            bundle_id: FixedBytes::ZERO,
             fund,
             eoa: info.eoa,
             mev_contract: info.mev_contract,
@@ -423,6 +617,15 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
             mev_type,
             no_pricing_calculated,
             balance_deltas,
+            // set by the composer once the bundle is finalized
+            min_action_confidence: FULL_CONFIDENCE,
+            mean_action_confidence: FULL_CONFIDENCE,
+            // set by the composer once all bundles of the block are known
+            merged_submission: false,
+            co_bundled_txs: vec![],
+            value_split: ValueSplit::default(),
+            capital: Default::default(),
+            spam_profit_usd: 0.0,
         }
     }
 
@@ -480,7 +683,8 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         &self,
         bundle_txes: Vec<FixedBytes<32>>,
         bundle_deltas: Vec<AddressDeltas>,
-        price_f: impl Fn(&Self, Address, Rational) -> Option<Rational>,
+        metadata: &Metadata,
+        price_f: impl Fn(&Self, &TokenAmount) -> Option<Rational>,
     ) -> Vec<TransactionAccounting> {
         bundle_txes
             .into_iter()
@@ -492,18 +696,33 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
                         let deltas: Vec<TokenBalanceDelta> = token_deltas
                             .into_iter()
                             .map(|(token, amount)| {
+                                let token = self.db.try_fetch_token_info(token).ok().unwrap_or(
+                                    TokenInfoWithAddress { address: token, ..Default::default() },
+                                );
+                                let amount = TokenAmount::from_scaled(&token, amount);
                                 //TODO: For cex-dex if we merge swap we won't have the intermediary
                                 //TODO: price so it will be marked as zero in the deltas
-                                let usd_value =
-                                    price_f(self, token, amount.clone()).unwrap_or(Rational::ZERO);
+                                let (usd_value, valuation) =
+                                    if metadata.is_nft_collection(amount.token) {
+                                        metadata
+                                            .get_nft_floor_price(amount.token, self.quote)
+                                            .map(|floor| {
+                                                (
+                                                    amount.usd_value(&floor),
+                                                    ValuationMethod::NftFloorPrice,
+                                                )
+                                            })
+                                            .unwrap_or((Rational::ZERO, ValuationMethod::Unpriced))
+                                    } else {
+                                        price_f(self, &amount)
+                                            .map(|usd_value| (usd_value, ValuationMethod::Market))
+                                            .unwrap_or((Rational::ZERO, ValuationMethod::Unpriced))
+                                    };
                                 TokenBalanceDelta {
-                                    token:     self
-                                        .db
-                                        .try_fetch_token_info(token)
-                                        .ok()
-                                        .unwrap_or_default(),
-                                    amount:    amount.to_float(),
+                                    amount: amount.to_float(),
                                     usd_value: usd_value.to_float(),
+                                    token,
+                                    valuation,
                                 }
                             })
                             .collect();
@@ -573,6 +792,14 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
         if swaps.is_empty() {
             return true
         }
+        let prices = self.price_cache.get_prices(
+            &metadata,
+            self.quote,
+            swaps
+                .iter()
+                .flat_map(|swap| [(swap.token_in.address, idx), (swap.token_out.address, idx)]),
+        );
+
         let pcts = tokens
             .flat_map(|token| {
                 swaps
@@ -583,15 +810,10 @@ impl<DB: LibmdbxReader> SharedInspectorUtils<'_, DB> {
                     .filter_map(|swap| {
                         let effective_price = swap.swap_rate();
 
-                        let am_in_price = metadata
-                            .dex_quotes
-                            .as_ref()?
-                            .price_at(Pair(swap.token_in.address, self.quote), idx)?;
-
-                        let am_out_price = metadata
-                            .dex_quotes
-                            .as_ref()?
-                            .price_at(Pair(swap.token_out.address, self.quote), idx)?;
+                        let am_in_price =
+                            prices.get(&(swap.token_in.address, idx))?.clone()?;
+                        let am_out_price =
+                            prices.get(&(swap.token_out.address, idx))?.clone()?;
 
                         // we reciprocal amount out because we won't have pricing for quote <> token
                         // out but we will have flipped
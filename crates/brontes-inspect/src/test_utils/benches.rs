@@ -1,18 +1,22 @@
-use std::sync::Arc;
+use std::{ops::Deref, sync::Arc};
 
 use alloy_primitives::{Address, TxHash};
 use brontes_classifier::test_utils::ClassifierTestUtils;
+use brontes_database::libmdbx::LibmdbxReadWriter;
 use brontes_types::{
     db::{
         cex::{trades::CexDexTradeConfig, CexExchange},
         metadata::Metadata,
     },
+    mev::Bundle,
     BlockData, MultiBlockData,
 };
 use criterion::{black_box, Criterion};
 
 use super::InspectorTestUtilsError;
-use crate::{composer::run_block_inspection, Inspectors};
+use crate::{
+    composer::run_block_inspection, jit::DEFAULT_JIT_MAX_HOLD_BLOCKS, Inspector, Inspectors,
+};
 
 pub struct InspectorBenchUtils {
     classifier_inspector: ClassifierTestUtils,
@@ -48,6 +52,7 @@ impl InspectorBenchUtils {
                     self.classifier_inspector.libmdbx,
                     &[CexExchange::Binance],
                     CexDexTradeConfig::default(),
+                    DEFAULT_JIT_MAX_HOLD_BLOCKS,
                     None,
                 )
             })
@@ -99,6 +104,7 @@ impl InspectorBenchUtils {
             self.classifier_inspector.libmdbx,
             &[CexExchange::Binance],
             CexDexTradeConfig::default(),
+            DEFAULT_JIT_MAX_HOLD_BLOCKS,
             None,
         );
 
@@ -162,6 +168,7 @@ impl InspectorBenchUtils {
             self.classifier_inspector.libmdbx,
             &[CexExchange::Binance],
             CexDexTradeConfig::default(),
+            DEFAULT_JIT_MAX_HOLD_BLOCKS,
             None,
         );
 
@@ -216,6 +223,7 @@ impl InspectorBenchUtils {
             self.classifier_inspector.libmdbx,
             &[CexExchange::Binance],
             CexDexTradeConfig::default(),
+            DEFAULT_JIT_MAX_HOLD_BLOCKS,
             None,
         );
 
@@ -255,18 +263,46 @@ impl InspectorBenchUtils {
         needed_tokens: Vec<Address>,
         c: &mut Criterion,
     ) -> Result<(), InspectorTestUtilsError> {
-        let inspectors = inspectors
-            .into_iter()
-            .map(|i| {
-                i.init_mev_inspector(
-                    self.quote_address,
-                    self.classifier_inspector.libmdbx,
-                    &[CexExchange::Binance],
-                    CexDexTradeConfig::default(),
-                    None,
-                )
-            })
-            .collect::<Vec<_>>();
+        self.bench_composer_with_handles(
+            bench_name,
+            tx_hashes,
+            iters,
+            |quote, db| {
+                inspectors
+                    .into_iter()
+                    .map(|i| {
+                        i.init_mev_inspector(
+                            quote,
+                            db,
+                            &[CexExchange::Binance],
+                            CexDexTradeConfig::default(),
+                            DEFAULT_JIT_MAX_HOLD_BLOCKS,
+                            None,
+                        )
+                    })
+                    .collect()
+            },
+            needed_tokens,
+            c,
+        )
+    }
+
+    /// Benches the composer with the inspector handles `init` builds from the
+    /// quote address and the db, e.g. `Arc<dyn Inspector>`s owned by an
+    /// embedding application instead of the leaked ones brontes runs with
+    pub fn bench_composer_with_handles<I>(
+        &self,
+        bench_name: &str,
+        tx_hashes: Vec<TxHash>,
+        iters: usize,
+        init: impl FnOnce(Address, &'static LibmdbxReadWriter) -> Vec<I>,
+        needed_tokens: Vec<Address>,
+        c: &mut Criterion,
+    ) -> Result<(), InspectorTestUtilsError>
+    where
+        I: Deref<Target = dyn Inspector<Result = Vec<Bundle>>> + Sync + 'static,
+    {
+        let inspectors = init(self.quote_address, self.classifier_inspector.libmdbx);
 
         let mut trees =
             self.rt
@@ -322,6 +358,7 @@ impl InspectorBenchUtils {
                     self.classifier_inspector.libmdbx,
                     &[CexExchange::Binance],
                     CexDexTradeConfig::default(),
+                    DEFAULT_JIT_MAX_HOLD_BLOCKS,
                     None,
                 )
             })
@@ -38,7 +38,7 @@ use brontes_types::{
 };
 use thiserror::Error;
 
-use crate::{composer::run_block_inspection, Inspectors};
+use crate::{composer::run_block_inspection, jit::DEFAULT_JIT_MAX_HOLD_BLOCKS, Inspectors};
 
 type StateTests = Option<Box<dyn for<'a> Fn(&'a Bundle)>>;
 
@@ -168,6 +168,7 @@ impl InspectorTestUtils {
                 CexExchange::Kucoin,
             ],
             CexDexTradeConfig::default(),
+            DEFAULT_JIT_MAX_HOLD_BLOCKS,
             None,
         );
         let data = BlockData { metadata: metadata.into(), tree: tree.into() };
@@ -270,13 +271,77 @@ impl InspectorTestUtils {
                 CexExchange::Upbit,
             ],
             cex_trade_config,
+            DEFAULT_JIT_MAX_HOLD_BLOCKS,
             None,
         );
 
         let data = BlockData { metadata: metadata.into(), tree: tree.into() };
         let multi = MultiBlockData { per_block_data: vec![data], blocks: 1 };
-        let mut results = inspector.inspect_block(multi);
+        let results = inspector.inspect_block(multi);
+
+        self.assert_single_bundle(results, gas_used_usd, profit_usd, specific_state_tests);
+
+        Ok(())
+    }
+
+    /// Runs an inspector that looks across blocks, such as
+    /// [`Inspectors::JitExtended`], over the blocks of the config's tx hashes,
+    /// oldest first. Only the given transactions are in the trees, so they
+    /// need to include the victims of the blocks in between
+    pub async fn run_multi_block_inspector(
+        &self,
+        config: InspectorTxRunConfig,
+        specific_state_tests: StateTests,
+    ) -> Result<(), InspectorTestUtilsError> {
+        let copied = config.clone();
+        let err = || InspectorTestUtilsError::InspectorConfig(Box::new(copied.clone()));
+
+        let profit_usd = config.expected_profit_usd.ok_or_else(err)?;
+        let gas_used_usd = config.expected_gas_usd.ok_or_else(err)?;
+        let tx_hashes = config.mev_tx_hashes.clone().ok_or_else(err)?;
 
+        let mut trees = self
+            .classifier_inspector
+            .build_tree_txes_with_pricing(tx_hashes, self.quote_address, config.needs_tokens)
+            .await?;
+        trees.sort_by_key(|(tree, _)| tree.header.number);
+
+        let mut per_block_data = Vec::with_capacity(trees.len());
+        for (tree, quotes) in trees {
+            let mut metadata = self
+                .classifier_inspector
+                .get_metadata(tree.header.number, false)
+                .await
+                .unwrap_or_default();
+            metadata.dex_quotes = Some(quotes);
+
+            per_block_data.push(BlockData { metadata: metadata.into(), tree: tree.into() });
+        }
+
+        let inspector = config.expected_mev_type.init_mev_inspector(
+            self.quote_address,
+            self.classifier_inspector.libmdbx,
+            &[],
+            CexDexTradeConfig::default(),
+            DEFAULT_JIT_MAX_HOLD_BLOCKS,
+            None,
+        );
+
+        let multi = MultiBlockData { blocks: per_block_data.len(), per_block_data };
+        let results = inspector.inspect_block(multi);
+
+        self.assert_single_bundle(results, gas_used_usd, profit_usd, specific_state_tests);
+
+        Ok(())
+    }
+
+    fn assert_single_bundle(
+        &self,
+        mut results: Vec<Bundle>,
+        gas_used_usd: f64,
+        profit_usd: f64,
+        specific_state_tests: StateTests,
+    ) {
         assert_eq!(
             results.len(),
             1,
@@ -305,8 +370,6 @@ impl InspectorTestUtils {
             bundle.header.profit_usd,
             profit_usd
         );
-
-        Ok(())
     }
 
     pub async fn run_composer(
@@ -390,6 +453,7 @@ impl InspectorTestUtils {
                     self.classifier_inspector.libmdbx,
                     &[CexExchange::Binance],
                     CexDexTradeConfig::default(),
+                    DEFAULT_JIT_MAX_HOLD_BLOCKS,
                     None,
                 )
             })
@@ -48,6 +48,7 @@ impl ActionDispatch {
                             &self,
                             call_info: ::brontes_types::structured_trace::CallFrameInfo<'_>,
                             db_tx: &DB,
+                            bindings: &crate::binding_cache::ProtocolBindings,
                             block: u64,
                             tx_idx: u64,
                         ) -> Option<(
@@ -56,8 +57,9 @@ impl ActionDispatch {
                             )> {
 
 
-                            let protocol_byte = db_tx.get_protocol(call_info.target_address)
-                                .ok()?.to_byte();
+                            let protocol_byte = bindings
+                                .protocol(db_tx, call_info.target_address)?
+                                .to_byte();
 
                             if call_info.call_data.len() < 4 {
                                 return None
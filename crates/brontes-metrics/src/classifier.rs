@@ -4,7 +4,7 @@ use alloy_primitives::Address;
 use brontes_types::Protocol;
 use dashmap::DashMap;
 use metrics::{Counter, Gauge, Histogram};
-use prometheus::IntCounterVec;
+use prometheus::{IntCounter, IntCounterVec, IntGauge};
 
 #[derive(Clone)]
 pub struct ClassificationMetrics {
@@ -35,3 +35,51 @@ impl ClassificationMetrics {
             .inc()
     }
 }
+
+/// Hit rate of the classifier's protocol binding cache
+#[derive(Clone)]
+pub struct BindingCacheMetrics {
+    hits:    IntCounter,
+    misses:  IntCounter,
+    entries: IntGauge,
+}
+
+impl Default for BindingCacheMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BindingCacheMetrics {
+    pub fn new() -> Self {
+        let lookups = prometheus::register_int_counter_vec!(
+            "brontes_protocol_binding_lookups",
+            "protocol lookups made by the classifier, by whether the binding cache had the address",
+            &["result"]
+        )
+        .unwrap();
+        let entries = prometheus::register_int_gauge!(
+            "brontes_protocol_binding_entries",
+            "addresses held in the classifier's protocol binding cache"
+        )
+        .unwrap();
+
+        Self {
+            hits: lookups.with_label_values(&["hit"]),
+            misses: lookups.with_label_values(&["miss"]),
+            entries,
+        }
+    }
+
+    pub fn hit(&self) {
+        self.hits.inc()
+    }
+
+    pub fn miss(&self) {
+        self.misses.inc()
+    }
+
+    pub fn set_entries(&self, entries: usize) {
+        self.entries.set(entries as i64)
+    }
+}
@@ -0,0 +1,248 @@
+//! Snapshots of the running process metrics for the terminal dashboard.
+//!
+//! Rather than introducing a second set of counters, the dashboard reads the
+//! same prometheus registry that is served on the metrics endpoint and diffs
+//! consecutive snapshots to derive rates.
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use brontes_types::mev::MevType;
+use once_cell::sync::Lazy;
+use prometheus::{proto::MetricFamily, IntCounterVec};
+use tracing::{field::Visit, Level, Subscriber};
+use tracing_subscriber::Layer;
+
+/// Amount of log lines kept around for the recent errors pane.
+const MAX_RECENT_ERRORS: usize = 50;
+
+static BUNDLES_FOUND: Lazy<IntCounterVec> = Lazy::new(|| {
+    prometheus::register_int_counter_vec!(
+        "brontes_bundles_found",
+        "the amount of bundles found per mev type",
+        &["mev_type"]
+    )
+    .unwrap()
+});
+
+/// Records a bundle that has been written to the database.
+pub fn record_bundle(mev_type: MevType) {
+    BUNDLES_FOUND.with_label_values(&[mev_type.as_ref()]).inc();
+}
+
+/// Point in time view of the metrics shown on the dashboard.
+#[derive(Debug, Clone, Default)]
+pub struct DashboardSnapshot {
+    pub completed_blocks:      u64,
+    pub total_blocks:          u64,
+    pub blocks_per_sec:        f64,
+    /// trees waiting on metadata and dex pricing
+    pub pending_trees:         i64,
+    /// inspector runs currently in flight
+    pub active_inspectors:     i64,
+    /// messages waiting to be written to libmdbx
+    pub db_write_queue:        i64,
+    /// average block tracing time in ms
+    pub avg_tracing_ms:        f64,
+    /// average tree building time in ms
+    pub avg_classification_ms: f64,
+    /// average runtime in ms per inspector
+    pub inspector_timings:     Vec<(String, f64)>,
+    pub bundle_counts:         Vec<(String, u64)>,
+    pub taken_at:              Option<Instant>,
+}
+
+impl DashboardSnapshot {
+    /// Gathers a new snapshot from the global prometheus registry. The
+    /// previous snapshot is used to derive the block throughput.
+    pub fn gather(prev: Option<&Self>) -> Self {
+        let families = prometheus::gather();
+        let find = |name: &str| families.iter().find(|family| family.get_name() == name);
+
+        let completed_blocks = find("brontes_range_specific_completed_blocks")
+            .map(sum_counter)
+            .unwrap_or_default() as u64;
+        let now = Instant::now();
+
+        let blocks_per_sec = prev
+            .and_then(|prev| Some((prev.completed_blocks, prev.taken_at?)))
+            .map(|(prev_completed, prev_time)| {
+                let elapsed = now.duration_since(prev_time).as_secs_f64();
+                if elapsed == 0.0 {
+                    return 0.0
+                }
+                completed_blocks.saturating_sub(prev_completed) as f64 / elapsed
+            })
+            .unwrap_or_default();
+
+        let mut bundle_counts = find("brontes_bundles_found")
+            .map(|family| labelled(family, "mev_type", |m| m.get_counter().get_value() as u64))
+            .unwrap_or_default();
+        bundle_counts.sort_unstable_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        let mut inspector_timings = find("inspector_runtime_ms")
+            .map(|family| labelled(family, "mev_type", |m| histogram_avg(m.get_histogram())))
+            .unwrap_or_default();
+        inspector_timings.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+        Self {
+            completed_blocks,
+            total_blocks: find("brontes_range_specific_total_blocks")
+                .map(sum_counter)
+                .unwrap_or_default() as u64,
+            blocks_per_sec,
+            pending_trees: find("range_pending_trees")
+                .map(sum_gauge)
+                .unwrap_or_default() as i64,
+            active_inspectors: find("range_active_inspector_processing")
+                .map(sum_gauge)
+                .unwrap_or_default() as i64,
+            db_write_queue: find("libmdbx_queue_size")
+                .map(sum_gauge)
+                .unwrap_or_default() as i64,
+            avg_tracing_ms: find("block_tracing_throughput")
+                .map(merged_histogram_avg)
+                .unwrap_or_default(),
+            avg_classification_ms: find("tree_builder_throughput")
+                .map(merged_histogram_avg)
+                .unwrap_or_default(),
+            inspector_timings,
+            bundle_counts,
+            taken_at: Some(now),
+        }
+    }
+
+    /// Fraction of the set range that has been processed, `None` when running
+    /// without a fixed range.
+    pub fn progress(&self) -> Option<f64> {
+        (self.total_blocks != 0)
+            .then(|| (self.completed_blocks as f64 / self.total_blocks as f64).min(1.0))
+    }
+}
+
+fn sum_counter(family: &MetricFamily) -> f64 {
+    family
+        .get_metric()
+        .iter()
+        .map(|m| m.get_counter().get_value())
+        .sum()
+}
+
+fn sum_gauge(family: &MetricFamily) -> f64 {
+    family
+        .get_metric()
+        .iter()
+        .map(|m| m.get_gauge().get_value())
+        .sum()
+}
+
+fn histogram_avg(histogram: &prometheus::proto::Histogram) -> f64 {
+    let count = histogram.get_sample_count();
+    if count == 0 {
+        return 0.0
+    }
+    histogram.get_sample_sum() / count as f64
+}
+
+fn merged_histogram_avg(family: &MetricFamily) -> f64 {
+    let (sum, count) = family
+        .get_metric()
+        .iter()
+        .map(|m| m.get_histogram())
+        .fold((0.0, 0u64), |(sum, count), h| {
+            (sum + h.get_sample_sum(), count + h.get_sample_count())
+        });
+
+    if count == 0 {
+        return 0.0
+    }
+    sum / count as f64
+}
+
+fn labelled<T>(
+    family: &MetricFamily,
+    label: &str,
+    value: impl Fn(&prometheus::proto::Metric) -> T,
+) -> Vec<(String, T)> {
+    family
+        .get_metric()
+        .iter()
+        .filter_map(|m| {
+            let name = m
+                .get_label()
+                .iter()
+                .find(|pair| pair.get_name() == label)?
+                .get_value()
+                .to_string();
+            Some((name, value(m)))
+        })
+        .collect()
+}
+
+/// Tracing layer that keeps the most recent warnings and errors around so
+/// they can be shown while stdout is taken over by the dashboard.
+#[derive(Debug, Clone, Default)]
+pub struct RecentErrors {
+    events: Arc<Mutex<VecDeque<String>>>,
+}
+
+impl RecentErrors {
+    /// Returns the recent events, newest first.
+    pub fn events(&self) -> Vec<String> {
+        self.events.lock().unwrap().iter().rev().cloned().collect()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for RecentErrors {
+    fn on_event(&self, event: &tracing::Event<'_>, _: tracing_subscriber::layer::Context<'_, S>) {
+        let level = *event.metadata().level();
+        if level > Level::WARN {
+            return
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let line = format!(
+            "{} {} {}: {}",
+            utc_time_of_day(),
+            level,
+            event.metadata().target(),
+            visitor.message
+        );
+
+        let mut events = self.events.lock().unwrap();
+        if events.len() == MAX_RECENT_ERRORS {
+            events.pop_front();
+        }
+        events.push_back(line);
+    }
+}
+
+/// `HH:MM:SS` in UTC, good enough to order events on screen.
+fn utc_time_of_day() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+        % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message.insert_str(0, &format!("{value:?}"));
+        } else {
+            self.message
+                .push_str(&format!(" {}={value:?}", field.name()));
+        }
+    }
+}
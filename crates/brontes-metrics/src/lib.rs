@@ -13,6 +13,7 @@ use tracing::trace;
 
 use crate::trace::{types::TraceMetricEvent, TraceMetrics};
 pub mod classifier;
+pub mod dashboard;
 pub mod db_cache;
 pub mod db_initialization;
 pub mod db_reads;
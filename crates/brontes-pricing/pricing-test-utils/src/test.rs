@@ -83,7 +83,7 @@ impl PricingTestUtils {
         let pricer = self.init_dex_pricer(block, None, rx).await?;
 
         let classifier = Classifier::new(self.tracer.libmdbx, tx, self.tracer.get_provider());
-        let tree = classifier.build_block_tree(traces, header, true).await;
+        let tree = classifier.build_block_tree(traces, header, true).await?;
         Ok((pricer, tree))
     }
 
@@ -98,7 +98,9 @@ impl PricingTestUtils {
         let classifier = Classifier::new(self.tracer.libmdbx, tx, self.tracer.get_provider());
         let pricer = self.init_dex_pricer(block, None, rx).await?;
 
-        let _tree = classifier.build_block_tree(vec![trace], header, true).await;
+        let _tree = classifier
+            .build_block_tree(vec![trace], header, true)
+            .await?;
 
         Ok(pricer)
     }
@@ -110,4 +112,12 @@ pub enum PricingTestError {
     TraceError(#[from] TraceLoaderError),
     #[error("libmdbx error")]
     LibmdbxError,
+    #[error("failed to build the block tree: {0}")]
+    TreeBuilderError(String),
+}
+
+impl From<eyre::Report> for PricingTestError {
+    fn from(value: eyre::Report) -> Self {
+        Self::TreeBuilderError(value.to_string())
+    }
 }
@@ -0,0 +1,223 @@
+"""Type stubs of the brontes python module.
+
+Addresses and hashes are lowercase hex strings and mev types their name.
+"""
+
+from typing import Any, Dict, List, Optional, Tuple
+
+def run(
+    db_path: str,
+    start_block: int,
+    end_block: int,
+    inspectors: Optional[List[str]] = None,
+    args: Optional[List[str]] = None,
+) -> None:
+    """Runs brontes over the block range, the same as `brontes run`.
+
+    Raises `ValueError` on invalid arguments and `KeyboardInterrupt` once an
+    interrupted run has shut down.
+    """
+
+class Database:
+    """The db of a brontes run, opened read only."""
+
+    def __init__(self, path: str) -> None: ...
+    def mev_blocks(self, start_block: int, end_block: int) -> List[MevBlockWithBundles]:
+        """The blocks of the range and the bundles found in them."""
+    def mev_block(self, block_number: int) -> Optional[MevBlockWithBundles]:
+        """The block and its bundles, `None` if the block wasn't run."""
+    def bundles(
+        self,
+        start_block: int,
+        end_block: int,
+        mev_type: Optional[str] = None,
+        eoa: Optional[str] = None,
+    ) -> List[Bundle]:
+        """The bundles of the range, optionally only those of a mev type or eoa."""
+    def mev_receipt(self, tx_hash: str, block_number: Optional[int] = None) -> Dict[str, Any]:
+        """Whether the transaction extracted mev, was a victim or neither.
+
+        Without a receipt the verdict is `neither` if the transaction's block
+        was processed and `unknown` otherwise.
+        """
+
+class MevBlockWithBundles:
+    """A block and the bundles found in it."""
+
+    @property
+    def block(self) -> MevBlock: ...
+    @property
+    def mev(self) -> List[Bundle]: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class MevBlock:
+    """The block level summary of the mev of a block."""
+
+    @property
+    def block_hash(self) -> str: ...
+    @property
+    def block_number(self) -> int: ...
+    @property
+    def bundle_count(self) -> int: ...
+    @property
+    def sandwich_count(self) -> int: ...
+    @property
+    def cex_dex_trade_count(self) -> int: ...
+    @property
+    def cex_dex_quote_count(self) -> int: ...
+    @property
+    def cex_dex_rfq_count(self) -> int: ...
+    @property
+    def jit_count(self) -> int: ...
+    @property
+    def jit_sandwich_count(self) -> int: ...
+    @property
+    def atomic_backrun_count(self) -> int: ...
+    @property
+    def liquidation_count(self) -> int: ...
+    @property
+    def eth_price(self) -> float: ...
+    @property
+    def total_gas_used(self) -> int: ...
+    @property
+    def total_priority_fee(self) -> int: ...
+    @property
+    def total_bribe(self) -> int: ...
+    @property
+    def total_mev_bribe(self) -> int: ...
+    @property
+    def total_mev_priority_fee_paid(self) -> int: ...
+    @property
+    def builder_address(self) -> str: ...
+    @property
+    def builder_name(self) -> Optional[str]: ...
+    @property
+    def builder_eth_profit(self) -> float: ...
+    @property
+    def builder_profit_usd(self) -> float: ...
+    @property
+    def builder_mev_profit_usd(self) -> float: ...
+    @property
+    def builder_searcher_bribes(self) -> int: ...
+    @property
+    def builder_searcher_bribes_usd(self) -> float: ...
+    @property
+    def builder_sponsorship_amount(self) -> int: ...
+    @property
+    def ultrasound_bid_adjusted(self) -> bool: ...
+    @property
+    def proposer_fee_recipient(self) -> Optional[str]: ...
+    @property
+    def proposer_mev_reward(self) -> Optional[int]: ...
+    @property
+    def proposer_profit_usd(self) -> Optional[float]: ...
+    @property
+    def slot(self) -> Optional[int]: ...
+    @property
+    def proposer_index(self) -> Optional[int]: ...
+    @property
+    def proposer_pubkey(self) -> Optional[str]: ...
+    @property
+    def missed_slots(self) -> Optional[int]: ...
+    @property
+    def pre_merge(self) -> bool: ...
+    @property
+    def total_mev_profit_usd(self) -> float: ...
+    @property
+    def possible_mev_tx_hash(self) -> List[str]: ...
+    @property
+    def possible_mev_tx_idx(self) -> List[int]: ...
+    @property
+    def possible_mev_coinbase_transfer(self) -> List[Optional[int]]: ...
+    @property
+    def possible_mev_priority_fee(self) -> List[int]: ...
+    @property
+    def possible_mev_gas_used(self) -> List[int]: ...
+    @property
+    def possible_mev_effective_gas_price(self) -> List[int]: ...
+    @property
+    def possible_mev_is_private(self) -> List[bool]: ...
+    @property
+    def possible_mev_triggered_by_coinbase_transfer(self) -> List[bool]: ...
+    @property
+    def possible_mev_high_priority_fee(self) -> List[bool]: ...
+    @property
+    def skipped_inspectors(self) -> List[str]: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class Bundle:
+    """A bundle, its header and the columns of its mev type's data."""
+
+    @property
+    def header(self) -> BundleHeader: ...
+    @property
+    def data(self) -> Dict[str, Any]:
+        """The mev type specific data, as a dict of its columns."""
+    def to_dict(self) -> Dict[str, Any]: ...
+
+class BundleHeader:
+    """The fields every bundle has, whatever its type."""
+
+    @property
+    def block_number(self) -> int: ...
+    @property
+    def tx_index(self) -> int: ...
+    @property
+    def tx_hash(self) -> str: ...
+    @property
+    def bundle_id(self) -> str: ...
+    @property
+    def eoa(self) -> str: ...
+    @property
+    def mev_contract(self) -> Optional[str]: ...
+    @property
+    def fund(self) -> str: ...
+    @property
+    def profit_usd(self) -> float: ...
+    @property
+    def bribe_usd(self) -> float: ...
+    @property
+    def mev_type(self) -> str: ...
+    @property
+    def no_pricing_calculated(self) -> bool: ...
+    @property
+    def balance_deltas_tx_hash(self) -> List[str]: ...
+    @property
+    def balance_deltas_address(self) -> List[str]: ...
+    @property
+    def balance_deltas_name(self) -> List[Optional[str]]: ...
+    @property
+    def balance_deltas_token_deltas(self) -> List[List[Tuple[Tuple[str, int, str], float, float]]]: ...
+    @property
+    def balance_deltas_token_valuations(self) -> List[List[str]]: ...
+    @property
+    def min_action_confidence(self) -> float: ...
+    @property
+    def mean_action_confidence(self) -> float: ...
+    @property
+    def merged_submission(self) -> bool: ...
+    @property
+    def co_bundled_txs(self) -> List[str]: ...
+    @property
+    def searcher_value_usd(self) -> float: ...
+    @property
+    def builder_value_usd(self) -> float: ...
+    @property
+    def lp_fees_protocol(self) -> List[str]: ...
+    @property
+    def lp_fees_usd(self) -> List[float]: ...
+    @property
+    def protocol_fees_usd(self) -> float: ...
+    @property
+    def capital_source(self) -> str: ...
+    @property
+    def flashloan_fee_usd(self) -> float: ...
+    @property
+    def capital_deployed_usd(self) -> float: ...
+    @property
+    def capital_at_risk_usd(self) -> float: ...
+    @property
+    def capital_turnover(self) -> float: ...
+    @property
+    def spam_profit_usd(self) -> float: ...
+    def to_dict(self) -> Dict[str, Any]: ...
@@ -0,0 +1,106 @@
+use std::str::FromStr;
+
+use alloy_primitives::{Address, B256};
+use brontes_database::libmdbx::{LibmdbxReadWriter, LibmdbxReader};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::types::{py_err, Bundle, MevBlockWithBundles, ToPy};
+
+/// A brontes libmdbx db, opened read only to query the mev found by `brontes
+/// run`. It can be opened while a run writes to the db.
+///
+/// The queries return the curated results, with the repricings and overrides
+/// applied, the same as the exports and the api. Their end block is
+/// inclusive.
+#[pyclass(frozen, module = "brontes")]
+pub struct Database {
+    db: LibmdbxReadWriter,
+}
+
+#[pymethods]
+impl Database {
+    #[new]
+    fn new(py: Python<'_>, path: String) -> PyResult<Self> {
+        let db = py
+            .allow_threads(|| LibmdbxReadWriter::init_db_read_only(&path))
+            .map_err(py_err)?;
+
+        Ok(Self { db })
+    }
+
+    /// The blocks of the range and the bundles found in them
+    fn mev_blocks(
+        &self,
+        py: Python<'_>,
+        start_block: u64,
+        end_block: u64,
+    ) -> PyResult<Vec<MevBlockWithBundles>> {
+        let blocks = py
+            .allow_threads(|| {
+                self.db
+                    .try_fetch_curated_mev_blocks(Some(start_block), end_block)
+            })
+            .map_err(py_err)?;
+
+        blocks
+            .iter()
+            .map(|block| block.to_lite().map(MevBlockWithBundles).map_err(py_err))
+            .collect()
+    }
+
+    /// The block and its bundles, `None` if the block wasn't run
+    fn mev_block(
+        &self,
+        py: Python<'_>,
+        block_number: u64,
+    ) -> PyResult<Option<MevBlockWithBundles>> {
+        Ok(self.mev_blocks(py, block_number, block_number)?.pop())
+    }
+
+    /// The bundles of the range, optionally only those of a mev type, by its
+    /// name such as `Sandwich`, or of a searcher eoa
+    #[pyo3(signature = (start_block, end_block, mev_type = None, eoa = None))]
+    fn bundles(
+        &self,
+        py: Python<'_>,
+        start_block: u64,
+        end_block: u64,
+        mev_type: Option<&str>,
+        eoa: Option<&str>,
+    ) -> PyResult<Vec<Bundle>> {
+        let eoa = eoa
+            .map(Address::from_str)
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("invalid eoa: {e}")))?;
+
+        Ok(self
+            .mev_blocks(py, start_block, end_block)?
+            .into_iter()
+            .flat_map(|block| block.0.mev)
+            .filter(|bundle| {
+                mev_type.map_or(true, |mev_type| bundle.header.mev_type.to_string() == mev_type)
+                    && eoa.map_or(true, |eoa| bundle.header.eoa == eoa)
+            })
+            .map(Bundle)
+            .collect())
+    }
+
+    /// Whether the transaction extracted mev, was a victim or neither, as a
+    /// dict. Without a receipt, the block the transaction landed in tells a
+    /// transaction that was neither from one whose block wasn't processed
+    #[pyo3(signature = (tx_hash, block_number = None))]
+    fn mev_receipt(
+        &self,
+        py: Python<'_>,
+        tx_hash: &str,
+        block_number: Option<u64>,
+    ) -> PyResult<PyObject> {
+        let tx_hash = B256::from_str(tx_hash)
+            .map_err(|e| PyValueError::new_err(format!("invalid tx hash: {e}")))?;
+        let receipt = py
+            .allow_threads(|| self.db.fetch_mev_receipt(tx_hash, block_number))
+            .map_err(py_err)?;
+
+        Ok(serde_json::to_value(receipt).map_err(py_err)?.to_py(py))
+    }
+}
@@ -0,0 +1,44 @@
+//! Python bindings of brontes.
+//!
+//! The `brontes` python module runs brontes over a block range and queries the
+//! results from its db, without the api server or parsing the exports. The
+//! classes mirror the exported result types of [`brontes_types::lite`]: a
+//! [`MevBlockWithBundles`] has the [`MevBlock`] summary and the [`Bundle`]s of
+//! the block, each with its [`BundleHeader`] and the columns of its mev type's
+//! data.
+//!
+//! The module is built with maturin, `maturin develop --release` from this
+//! crate installs it in the active virtualenv.
+//!
+//! ### Example
+//! ```python
+//! import brontes
+//!
+//! brontes.run("/data/brontes", 19_000_000, 19_000_100, inspectors=["Sandwich"])
+//!
+//! db = brontes.Database("/data/brontes")
+//! for bundle in db.bundles(19_000_000, 19_000_100, mev_type="Sandwich"):
+//!     print(bundle.header.tx_hash, bundle.header.profit_usd, bundle.data["victim_swaps.tx_hash"])
+//! ```
+use pyo3::prelude::*;
+
+mod db;
+mod run;
+mod types;
+
+pub use db::Database;
+pub use run::run;
+pub use types::{Bundle, BundleHeader, MevBlock, MevBlockWithBundles};
+
+#[pymodule]
+#[pyo3(name = "brontes")]
+fn brontes_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Database>()?;
+    m.add_class::<MevBlockWithBundles>()?;
+    m.add_class::<MevBlock>()?;
+    m.add_class::<Bundle>()?;
+    m.add_class::<BundleHeader>()?;
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use brontes::{
+    cli::{Args, Commands, RunArgs},
+    runner,
+};
+use clap::Parser;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::types::py_err;
+
+/// How often a run checks for a `KeyboardInterrupt`
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Runs brontes over the block range, the same as `brontes run`, and returns
+/// once the range is done.
+///
+/// The results are written to the db at `db_path`, query them with a
+/// `Database` opened after the run. `inspectors` are the names of the
+/// inspectors to run, all of them if unset, and `args` are further arguments
+/// of `brontes run`. The run needs the same environment as the cli, the
+/// clickhouse and node settings are read from the environment.
+///
+/// The run leaves python's panic hook and signal handlers as they are, a
+/// `KeyboardInterrupt` stops it and is raised once it has shut down.
+#[pyfunction]
+#[pyo3(signature = (db_path, start_block, end_block, inspectors = None, args = None))]
+pub fn run(
+    py: Python<'_>,
+    db_path: String,
+    start_block: u64,
+    end_block: u64,
+    inspectors: Option<Vec<String>>,
+    args: Option<Vec<String>>,
+) -> PyResult<()> {
+    let command = parse_run_args(start_block, end_block, inspectors, args.unwrap_or_default())
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    let mut interrupted = None;
+    let res = py.allow_threads(|| {
+        // the future is polled on this thread, the one python called from, as
+        // only the main thread runs python's signal handlers
+        let interrupt = async {
+            loop {
+                tokio::time::sleep(SIGNAL_CHECK_INTERVAL).await;
+                if let Err(e) = Python::with_gil(|py| py.check_signals()) {
+                    interrupted = Some(e);
+                    break
+                }
+            }
+        };
+        runner::run_command_embedded(Duration::from_secs(5), interrupt, |ctx| {
+            command.execute(db_path, ctx)
+        })
+    });
+    if let Some(e) = interrupted {
+        return Err(e)
+    }
+
+    res.map_err(py_err)
+}
+
+/// The `brontes run` arguments of the call
+fn parse_run_args(
+    start_block: u64,
+    end_block: u64,
+    inspectors: Option<Vec<String>>,
+    args: Vec<String>,
+) -> Result<RunArgs, clap::Error> {
+    let mut argv = vec![
+        "brontes".to_string(),
+        "run".to_string(),
+        "--start-block".to_string(),
+        start_block.to_string(),
+        "--end-block".to_string(),
+        end_block.to_string(),
+    ];
+    if let Some(inspectors) = inspectors {
+        argv.push("--inspectors".to_string());
+        argv.push(inspectors.join(","));
+    }
+    argv.extend(args);
+
+    match Args::try_parse_from(argv)?.command {
+        Commands::Run(command) => Ok(command),
+        _ => unreachable!("parsed a run command"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_run_args() {
+        let command = parse_run_args(
+            10,
+            20,
+            Some(vec!["Sandwich".to_string(), "AtomicArb".to_string()]),
+            vec!["--jit-max-hold-blocks".to_string(), "5".to_string()],
+        )
+        .unwrap();
+        assert_eq!(command.start_block, Some(10));
+        assert_eq!(command.end_block, Some(20));
+        assert_eq!(command.inspectors.map(|inspectors| inspectors.len()), Some(2));
+        assert_eq!(command.jit_max_hold_blocks, 5);
+
+        assert!(parse_run_args(10, 20, Some(vec!["NotAnInspector".to_string()]), vec![]).is_err());
+        assert!(parse_run_args(10, 20, None, vec!["--not-a-flag".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,332 @@
+//! The result types, as python objects. Each class wraps its
+//! [`brontes_types::lite`] type and has a read only attribute for each of its
+//! fields. Addresses and hashes are lowercase hex strings and mev types their
+//! name.
+use alloy_primitives::{Address, B256};
+use brontes_types::lite;
+use pyo3::{
+    exceptions::PyRuntimeError,
+    prelude::*,
+    types::{PyDict, PyList, PyTuple},
+};
+use serde_json::{Map, Value};
+
+pub(crate) fn py_err(e: impl std::fmt::Display) -> PyErr {
+    PyRuntimeError::new_err(format!("{e:#}"))
+}
+
+/// Converts a value to the python object it's exposed as
+pub(crate) trait ToPy {
+    fn to_py(&self, py: Python<'_>) -> PyObject;
+}
+
+macro_rules! to_py_via_to_object {
+    ($($ty:ty),*) => {
+        $(
+            impl ToPy for $ty {
+                fn to_py(&self, py: Python<'_>) -> PyObject {
+                    self.to_object(py)
+                }
+            }
+        )*
+    };
+}
+
+to_py_via_to_object!(bool, u8, u64, u128, f64, String);
+
+impl ToPy for B256 {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        format!("{self:?}").into_py(py)
+    }
+}
+
+impl ToPy for Address {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        format!("{self:?}").into_py(py)
+    }
+}
+
+impl ToPy for lite::MevType {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        self.to_string().into_py(py)
+    }
+}
+
+impl<T: ToPy> ToPy for Option<T> {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        self.as_ref().map_or_else(|| py.None(), |v| v.to_py(py))
+    }
+}
+
+impl<T: ToPy> ToPy for Vec<T> {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        PyList::new_bound(py, self.iter().map(|v| v.to_py(py))).into()
+    }
+}
+
+impl<A: ToPy, B: ToPy, C: ToPy> ToPy for (A, B, C) {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        PyTuple::new_bound(py, [self.0.to_py(py), self.1.to_py(py), self.2.to_py(py)]).into()
+    }
+}
+
+impl ToPy for Value {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        match self {
+            Value::Null => py.None(),
+            Value::Bool(b) => b.to_py(py),
+            Value::Number(n) => {
+                if let Some(n) = n.as_u64() {
+                    n.to_object(py)
+                } else if let Some(n) = n.as_i64() {
+                    n.to_object(py)
+                } else {
+                    n.as_f64().unwrap_or_default().to_object(py)
+                }
+            }
+            Value::String(s) => s.to_py(py),
+            Value::Array(values) => values.to_py(py),
+            Value::Object(map) => map.to_py(py),
+        }
+    }
+}
+
+impl ToPy for Map<String, Value> {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        let dict = PyDict::new_bound(py);
+        for (key, value) in self {
+            dict.set_item(key, value.to_py(py))
+                .expect("str keys are hashable");
+        }
+
+        dict.into()
+    }
+}
+
+/// Defines the class wrapping a lite type, with an attribute for each of the
+/// fields, `to_dict` and `__repr__`
+macro_rules! py_class {
+    (
+        $(#[$meta:meta])*
+        $name:ident($inner:ty) {
+            $($field:ident),* $(,)?
+        }
+        repr(|$this:ident| $repr:expr)
+    ) => {
+        $(#[$meta])*
+        #[pyclass(frozen, module = "brontes")]
+        #[derive(Clone)]
+        pub struct $name(pub $inner);
+
+        #[pymethods]
+        impl $name {
+            $(
+                #[getter]
+                fn $field(&self, py: Python<'_>) -> PyObject {
+                    self.0.$field.to_py(py)
+                }
+            )*
+
+            /// The fields as a dict
+            fn to_dict(&self, py: Python<'_>) -> PyObject {
+                ToPy::to_py(self, py)
+            }
+
+            fn __repr__(&self) -> String {
+                let $this = &self.0;
+                $repr
+            }
+        }
+
+        impl ToPy for $name {
+            fn to_py(&self, py: Python<'_>) -> PyObject {
+                let dict = PyDict::new_bound(py);
+                $(
+                    dict.set_item(stringify!($field), self.0.$field.to_py(py))
+                        .expect("str keys are hashable");
+                )*
+
+                dict.into()
+            }
+        }
+    };
+}
+
+py_class!(
+    /// The block level summary of the mev of a block
+    MevBlock(lite::MevBlock) {
+        block_hash,
+        block_number,
+        bundle_count,
+        sandwich_count,
+        cex_dex_trade_count,
+        cex_dex_quote_count,
+        cex_dex_rfq_count,
+        jit_count,
+        jit_sandwich_count,
+        atomic_backrun_count,
+        liquidation_count,
+        eth_price,
+        total_gas_used,
+        total_priority_fee,
+        total_bribe,
+        total_mev_bribe,
+        total_mev_priority_fee_paid,
+        builder_address,
+        builder_name,
+        builder_eth_profit,
+        builder_profit_usd,
+        builder_mev_profit_usd,
+        builder_searcher_bribes,
+        builder_searcher_bribes_usd,
+        builder_sponsorship_amount,
+        ultrasound_bid_adjusted,
+        proposer_fee_recipient,
+        proposer_mev_reward,
+        proposer_profit_usd,
+        slot,
+        proposer_index,
+        proposer_pubkey,
+        missed_slots,
+        pre_merge,
+        total_mev_profit_usd,
+        possible_mev_tx_hash,
+        possible_mev_tx_idx,
+        possible_mev_coinbase_transfer,
+        possible_mev_priority_fee,
+        possible_mev_gas_used,
+        possible_mev_effective_gas_price,
+        possible_mev_is_private,
+        possible_mev_triggered_by_coinbase_transfer,
+        possible_mev_high_priority_fee,
+        skipped_inspectors,
+    }
+    repr(|block| format!(
+        "MevBlock(block_number={}, bundle_count={}, total_mev_profit_usd={})",
+        block.block_number, block.bundle_count, block.total_mev_profit_usd
+    ))
+);
+
+py_class!(
+    /// The fields every bundle has, whatever its type
+    BundleHeader(lite::BundleHeader) {
+        block_number,
+        tx_index,
+        tx_hash,
+        bundle_id,
+        eoa,
+        mev_contract,
+        fund,
+        profit_usd,
+        bribe_usd,
+        mev_type,
+        no_pricing_calculated,
+        balance_deltas_tx_hash,
+        balance_deltas_address,
+        balance_deltas_name,
+        balance_deltas_token_deltas,
+        balance_deltas_token_valuations,
+        min_action_confidence,
+        mean_action_confidence,
+        merged_submission,
+        co_bundled_txs,
+        searcher_value_usd,
+        builder_value_usd,
+        lp_fees_protocol,
+        lp_fees_usd,
+        protocol_fees_usd,
+        capital_source,
+        flashloan_fee_usd,
+        capital_deployed_usd,
+        capital_at_risk_usd,
+        capital_turnover,
+        spam_profit_usd,
+    }
+    repr(|header| format!(
+        "BundleHeader(mev_type={}, tx_hash={:?}, profit_usd={})",
+        header.mev_type, header.tx_hash, header.profit_usd
+    ))
+);
+
+/// A bundle, its header and the columns of its mev type's data
+#[pyclass(frozen, module = "brontes")]
+#[derive(Clone)]
+pub struct Bundle(pub lite::Bundle);
+
+#[pymethods]
+impl Bundle {
+    #[getter]
+    fn header(&self) -> BundleHeader {
+        BundleHeader(self.0.header.clone())
+    }
+
+    /// The mev type specific data, as a dict of its columns
+    #[getter]
+    fn data(&self, py: Python<'_>) -> PyObject {
+        self.0.data.row().to_py(py)
+    }
+
+    /// The bundle as a dict, with the header and data as nested dicts
+    fn to_dict(&self, py: Python<'_>) -> PyObject {
+        ToPy::to_py(self, py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Bundle(mev_type={}, tx_hash={:?}, profit_usd={})",
+            self.0.header.mev_type, self.0.header.tx_hash, self.0.header.profit_usd
+        )
+    }
+}
+
+impl ToPy for Bundle {
+    fn to_py(&self, py: Python<'_>) -> PyObject {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("header", self.header().to_py(py))
+            .expect("str keys are hashable");
+        dict.set_item("data", self.data(py))
+            .expect("str keys are hashable");
+
+        dict.into()
+    }
+}
+
+/// A block and the bundles found in it
+#[pyclass(frozen, module = "brontes")]
+#[derive(Clone)]
+pub struct MevBlockWithBundles(pub lite::MevBlockWithBundles);
+
+#[pymethods]
+impl MevBlockWithBundles {
+    #[getter]
+    fn block(&self) -> MevBlock {
+        MevBlock(self.0.block.clone())
+    }
+
+    #[getter]
+    fn mev(&self) -> Vec<Bundle> {
+        self.0.mev.iter().cloned().map(Bundle).collect()
+    }
+
+    /// The block and its bundles as nested dicts
+    fn to_dict(&self, py: Python<'_>) -> PyObject {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("block", self.block().to_py(py))
+            .expect("str keys are hashable");
+        dict.set_item(
+            "mev",
+            PyList::new_bound(py, self.mev().iter().map(|bundle| bundle.to_py(py))),
+        )
+        .expect("str keys are hashable");
+
+        dict.into()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MevBlockWithBundles(block_number={}, bundles={})",
+            self.0.block.block_number,
+            self.0.mev.len()
+        )
+    }
+}
@@ -0,0 +1,29 @@
+"""Tests of the python bindings, run with `maturin develop && pytest tests`."""
+
+import pytest
+
+import brontes
+
+
+def test_database_missing_path(tmp_path):
+    with pytest.raises(RuntimeError):
+        brontes.Database(str(tmp_path / "missing"))
+
+
+def test_database_is_read_only(tmp_path):
+    # opening a db read only doesn't create one
+    with pytest.raises(RuntimeError):
+        brontes.Database(str(tmp_path))
+    assert not any(tmp_path.iterdir())
+
+
+def test_run_invalid_args(tmp_path):
+    with pytest.raises(ValueError):
+        brontes.run(str(tmp_path), 10, 20, inspectors=["NotAnInspector"])
+    with pytest.raises(ValueError):
+        brontes.run(str(tmp_path), 10, 20, args=["--not-a-flag"])
+
+
+def test_classes_exported():
+    for name in ["Database", "MevBlockWithBundles", "MevBlock", "Bundle", "BundleHeader", "run"]:
+        assert hasattr(brontes, name)
@@ -0,0 +1,127 @@
+use alloy_primitives::{Address, B256};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Bundle;
+
+/// A block and the bundles found in it, one line of the json export
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MevBlockWithBundles {
+    pub block: MevBlock,
+    pub mev:   Vec<Bundle>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MevBlock {
+    pub block_hash: B256,
+    pub block_number: u64,
+    #[serde(rename = "mev_count.mev_count", with = "single")]
+    pub bundle_count: u64,
+    #[serde(rename = "mev_count.sandwich_count", with = "single")]
+    pub sandwich_count: u64,
+    #[serde(rename = "mev_count.cex_dex_trade_count", with = "single")]
+    pub cex_dex_trade_count: u64,
+    #[serde(rename = "mev_count.cex_dex_quote_count", with = "single")]
+    pub cex_dex_quote_count: u64,
+    #[serde(rename = "mev_count.cex_dex_rfq_count", with = "single")]
+    pub cex_dex_rfq_count: u64,
+    #[serde(rename = "mev_count.jit_count", with = "single")]
+    pub jit_count: u64,
+    #[serde(rename = "mev_count.jit_sandwich_count", with = "single")]
+    pub jit_sandwich_count: u64,
+    #[serde(rename = "mev_count.atomic_backrun_count", with = "single")]
+    pub atomic_backrun_count: u64,
+    #[serde(rename = "mev_count.liquidation_count", with = "single")]
+    pub liquidation_count: u64,
+    pub eth_price: f64,
+    pub total_gas_used: u128,
+    pub total_priority_fee: u128,
+    pub total_bribe: u128,
+    pub total_mev_bribe: u128,
+    pub total_mev_priority_fee_paid: u128,
+    pub builder_address: Address,
+    pub builder_name: Option<String>,
+    pub builder_eth_profit: f64,
+    pub builder_profit_usd: f64,
+    pub builder_mev_profit_usd: f64,
+    pub builder_searcher_bribes: u128,
+    pub builder_searcher_bribes_usd: f64,
+    pub builder_sponsorship_amount: u128,
+    pub ultrasound_bid_adjusted: bool,
+    pub proposer_fee_recipient: Option<Address>,
+    pub proposer_mev_reward: Option<u128>,
+    pub proposer_profit_usd: Option<f64>,
+    pub slot: Option<u64>,
+    pub proposer_index: Option<u64>,
+    pub proposer_pubkey: Option<String>,
+    pub missed_slots: Option<u64>,
+    #[serde(default)]
+    pub pre_merge: bool,
+    pub total_mev_profit_usd: f64,
+    // transactions that looked like mev but weren't found in a bundle, as
+    // columns
+    #[serde(rename = "possible_mev.tx_hash")]
+    pub possible_mev_tx_hash: Vec<B256>,
+    #[serde(rename = "possible_mev.tx_idx")]
+    pub possible_mev_tx_idx: Vec<u64>,
+    #[serde(rename = "possible_mev.gas_details.coinbase_transfer")]
+    pub possible_mev_coinbase_transfer: Vec<Option<u128>>,
+    #[serde(rename = "possible_mev.gas_details.priority_fee")]
+    pub possible_mev_priority_fee: Vec<u128>,
+    #[serde(rename = "possible_mev.gas_details.gas_used")]
+    pub possible_mev_gas_used: Vec<u128>,
+    #[serde(rename = "possible_mev.gas_details.effective_gas_price")]
+    pub possible_mev_effective_gas_price: Vec<u128>,
+    #[serde(rename = "possible_mev.triggers.is_private")]
+    pub possible_mev_is_private: Vec<bool>,
+    #[serde(rename = "possible_mev.triggers.coinbase_transfer")]
+    pub possible_mev_triggered_by_coinbase_transfer: Vec<bool>,
+    #[serde(rename = "possible_mev.triggers.high_priority_fee")]
+    pub possible_mev_high_priority_fee: Vec<bool>,
+    /// Inspectors that weren't run on the block as its action summary ruled
+    /// out their mev
+    #[serde(default)]
+    pub skipped_inspectors: Vec<String>,
+}
+
+impl MevBlock {
+    /// Gas details of the i-th possible mev transaction
+    pub fn possible_mev_gas_details(&self, i: usize) -> Option<GasDetails> {
+        Some(GasDetails {
+            coinbase_transfer:   *self.possible_mev_coinbase_transfer.get(i)?,
+            priority_fee:        *self.possible_mev_priority_fee.get(i)?,
+            gas_used:            *self.possible_mev_gas_used.get(i)?,
+            effective_gas_price: *self.possible_mev_effective_gas_price.get(i)?,
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasDetails {
+    pub coinbase_transfer:   Option<u128>,
+    pub priority_fee:        u128,
+    pub gas_used:            u128,
+    pub effective_gas_price: u128,
+}
+
+impl GasDetails {
+    /// Gas paid by the transaction in wei, including the coinbase transfer
+    pub fn gas_paid(&self) -> u128 {
+        self.gas_used * self.effective_gas_price + self.coinbase_transfer.unwrap_or_default()
+    }
+}
+
+/// Counts are stored as single element arrays of a nested clickhouse column
+mod single {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        [*value].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        Ok(Vec::<u64>::deserialize(deserializer)?
+            .first()
+            .copied()
+            .unwrap_or_default())
+    }
+}
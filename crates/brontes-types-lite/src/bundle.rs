@@ -0,0 +1,233 @@
+use std::fmt;
+
+use alloy_primitives::{Address, B256};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+/// The columns of a bundle's mev specific data, by name
+pub type Row = Map<String, Value>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MevType {
+    CexDexTrades,
+    CexDexQuotes,
+    CexDexRfq,
+    Sandwich,
+    Jit,
+    JitCexDex,
+    JitSandwich,
+    Liquidation,
+    AtomicArb,
+    SearcherTx,
+    #[default]
+    #[serde(other)]
+    Unknown,
+    JitExtended,
+    Rebalance,
+}
+
+impl fmt::Display for MevType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct Bundle {
+    pub header: BundleHeader,
+    pub data:   BundleData,
+}
+
+impl<'de> Deserialize<'de> for Bundle {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // the data is stored without a tag, its variant follows from the header
+        #[derive(Deserialize)]
+        struct Untagged {
+            header: BundleHeader,
+            data:   Row,
+        }
+
+        let Untagged { header, data } = Untagged::deserialize(deserializer)?;
+        let data = BundleData::from_row(header.mev_type, data);
+
+        Ok(Self { header, data })
+    }
+}
+
+/// The mev specific data of a bundle. Each variant holds the columns of its
+/// type's table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BundleData {
+    Sandwich(Row),
+    AtomicArb(Row),
+    JitSandwich(Row),
+    Jit(Row),
+    CexDexQuote(Row),
+    CexDex(Row),
+    Liquidation(Row),
+    Unknown(Row),
+}
+
+impl Default for BundleData {
+    fn default() -> Self {
+        Self::Unknown(Row::default())
+    }
+}
+
+impl BundleData {
+    pub fn from_row(mev_type: MevType, row: Row) -> Self {
+        match mev_type {
+            MevType::Sandwich => Self::Sandwich(row),
+            MevType::AtomicArb | MevType::Rebalance => Self::AtomicArb(row),
+            MevType::JitSandwich => Self::JitSandwich(row),
+            MevType::Jit | MevType::JitExtended => Self::Jit(row),
+            MevType::CexDexQuotes | MevType::CexDexRfq => Self::CexDexQuote(row),
+            MevType::CexDexTrades | MevType::JitCexDex => Self::CexDex(row),
+            MevType::Liquidation => Self::Liquidation(row),
+            MevType::SearcherTx | MevType::Unknown => Self::Unknown(row),
+        }
+    }
+
+    pub fn row(&self) -> &Row {
+        match self {
+            Self::Sandwich(row)
+            | Self::AtomicArb(row)
+            | Self::JitSandwich(row)
+            | Self::Jit(row)
+            | Self::CexDexQuote(row)
+            | Self::CexDex(row)
+            | Self::Liquidation(row)
+            | Self::Unknown(row) => row,
+        }
+    }
+
+    /// The column of the data, parsed as `T`. `None` if the column is missing
+    /// or of another type
+    pub fn get<T: serde::de::DeserializeOwned>(&self, column: &str) -> Option<T> {
+        serde_json::from_value(self.row().get(column)?.clone()).ok()
+    }
+}
+
+/// A token delta as it is exported: `((token, decimals, symbol), amount,
+/// usd_value)`
+pub type TokenDelta = ((Address, u8, String), f64, f64);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleHeader {
+    pub block_number: u64,
+    pub tx_index: u64,
+    pub tx_hash: B256,
+    #[serde(default)]
+    pub bundle_id: B256,
+    pub eoa: Address,
+    pub mev_contract: Option<Address>,
+    #[serde(default)]
+    pub fund: String,
+    pub profit_usd: f64,
+    pub bribe_usd: f64,
+    pub mev_type: MevType,
+    #[serde(default)]
+    pub no_pricing_calculated: bool,
+    #[serde(rename = "balance_deltas.tx_hash", default)]
+    pub balance_deltas_tx_hash: Vec<B256>,
+    #[serde(rename = "balance_deltas.address", default)]
+    pub balance_deltas_address: Vec<Address>,
+    #[serde(rename = "balance_deltas.name", default)]
+    pub balance_deltas_name: Vec<Option<String>>,
+    #[serde(rename = "balance_deltas.token_deltas", default)]
+    pub balance_deltas_token_deltas: Vec<Vec<TokenDelta>>,
+    #[serde(rename = "balance_deltas.token_valuations", default)]
+    pub balance_deltas_token_valuations: Vec<Vec<String>>,
+    #[serde(default = "full_confidence")]
+    pub min_action_confidence: f64,
+    #[serde(default = "full_confidence")]
+    pub mean_action_confidence: f64,
+    #[serde(default)]
+    pub merged_submission: bool,
+    #[serde(default)]
+    pub co_bundled_txs: Vec<B256>,
+    #[serde(default)]
+    pub searcher_value_usd: f64,
+    #[serde(default)]
+    pub builder_value_usd: f64,
+    #[serde(rename = "lp_fees.protocol", default)]
+    pub lp_fees_protocol: Vec<String>,
+    #[serde(rename = "lp_fees.usd", default)]
+    pub lp_fees_usd: Vec<f64>,
+    #[serde(default)]
+    pub protocol_fees_usd: f64,
+    #[serde(default = "inventory")]
+    pub capital_source: String,
+    #[serde(default)]
+    pub flashloan_fee_usd: f64,
+    #[serde(default)]
+    pub capital_deployed_usd: f64,
+    #[serde(default)]
+    pub capital_at_risk_usd: f64,
+    #[serde(default)]
+    pub capital_turnover: f64,
+    #[serde(default)]
+    pub spam_profit_usd: f64,
+}
+
+impl Default for BundleHeader {
+    fn default() -> Self {
+        Self {
+            block_number: 0,
+            tx_index: 0,
+            tx_hash: B256::ZERO,
+            bundle_id: B256::ZERO,
+            eoa: Address::ZERO,
+            mev_contract: None,
+            fund: String::new(),
+            profit_usd: 0.0,
+            bribe_usd: 0.0,
+            mev_type: MevType::Unknown,
+            no_pricing_calculated: false,
+            balance_deltas_tx_hash: vec![],
+            balance_deltas_address: vec![],
+            balance_deltas_name: vec![],
+            balance_deltas_token_deltas: vec![],
+            balance_deltas_token_valuations: vec![],
+            min_action_confidence: full_confidence(),
+            mean_action_confidence: full_confidence(),
+            merged_submission: false,
+            co_bundled_txs: vec![],
+            searcher_value_usd: 0.0,
+            builder_value_usd: 0.0,
+            lp_fees_protocol: vec![],
+            lp_fees_usd: vec![],
+            protocol_fees_usd: 0.0,
+            capital_source: inventory(),
+            flashloan_fee_usd: 0.0,
+            capital_deployed_usd: 0.0,
+            capital_at_risk_usd: 0.0,
+            capital_turnover: 0.0,
+            spam_profit_usd: 0.0,
+        }
+    }
+}
+
+impl BundleHeader {
+    /// The token deltas of each address in the bundle, with the transaction
+    /// they happened in
+    pub fn balance_deltas(&self) -> impl Iterator<Item = (B256, Address, &[TokenDelta])> + '_ {
+        self.balance_deltas_tx_hash
+            .iter()
+            .zip(&self.balance_deltas_address)
+            .zip(&self.balance_deltas_token_deltas)
+            .map(|((tx, address), deltas)| (*tx, *address, deltas.as_slice()))
+    }
+}
+
+fn full_confidence() -> f64 {
+    1.0
+}
+
+fn inventory() -> String {
+    "Inventory".to_string()
+}
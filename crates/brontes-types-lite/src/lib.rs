@@ -0,0 +1,26 @@
+//! The result types of brontes, as they are exported.
+//!
+//! `brontes-types` pulls in reth, malachite and the rest of the node stack,
+//! which is a lot to build just to read an export. This crate has the types
+//! of the json exports and clickhouse rows, [`MevBlock`], [`Bundle`],
+//! [`BundleHeader`], [`BundleData`] and [`GasDetails`], with nothing but serde
+//! behind them. They follow the exported layout, so nested fields are flattened
+//! into columns the way they are stored.
+//!
+//! The conversions from the full types live in `brontes-types`, which
+//! re-exports this crate as `brontes_types::lite`.
+//!
+//! ### Example
+//! ```ignore
+//! for line in std::io::stdin().lines() {
+//!     let block: MevBlockWithBundles = serde_json::from_str(&line?)?;
+//!     for bundle in block.mev {
+//!         println!("{} {}", bundle.header.mev_type, bundle.header.profit_usd);
+//!     }
+//! }
+//! ```
+mod block;
+mod bundle;
+
+pub use block::*;
+pub use bundle::*;
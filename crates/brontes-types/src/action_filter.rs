@@ -0,0 +1,233 @@
+//! Scoping the inspectors to a subset of protocols, pools and tokens.
+//!
+//! An [`ActionFilter`] drops the bundles whose transactions fall outside of
+//! its include lists or inside of its exclude lists, so a run can be limited to
+//! e.g. the Curve ecosystem, or skip pools known to produce noise. The actions
+//! of a bundle's transactions are matched as a whole, the inspectors always see
+//! every action of a transaction, as an arb missing a leg or a sandwich missing
+//! its victim would be found as something it isn't.
+//!
+//! A bundle is dropped if one of its actions is excluded, and kept if one of
+//! them is included. Exclusions take precedence over inclusions, and an empty
+//! include list includes everything. Actions without a protocol or pool, such
+//! as transfers, are only matched on their tokens. The filter is part of the
+//! [`LiveConfig`](crate::live_config::LiveConfig), so its lists can be changed
+//! while the indexer runs.
+use std::{fmt::Debug, hash::Hash};
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    normalized_actions::{Action, NormalizedSwap},
+    FastHashSet, Protocol,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionFilterList {
+    pub protocols: FastHashSet<Protocol>,
+    pub pools:     FastHashSet<Address>,
+    pub tokens:    FastHashSet<Address>,
+}
+
+impl ActionFilterList {
+    pub fn is_empty(&self) -> bool {
+        self.protocols.is_empty() && self.pools.is_empty() && self.tokens.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionFilter {
+    pub include: ActionFilterList,
+    pub exclude: ActionFilterList,
+}
+
+impl ActionFilter {
+    /// Rejects filters that both include and exclude a protocol, pool or token
+    pub fn validate(&self) -> eyre::Result<()> {
+        fn overlap<T: Debug + Eq + Hash>(
+            list: &str,
+            include: &FastHashSet<T>,
+            exclude: &FastHashSet<T>,
+        ) -> eyre::Result<()> {
+            match include.intersection(exclude).next() {
+                Some(item) => Err(eyre::eyre!("{list} {item:?} is both included and excluded")),
+                None => Ok(()),
+            }
+        }
+
+        overlap("protocol", &self.include.protocols, &self.exclude.protocols)?;
+        overlap("pool", &self.include.pools, &self.exclude.pools)?;
+        overlap("token", &self.include.tokens, &self.exclude.tokens)
+    }
+
+    /// Whether the transaction or bundle made of the actions is in scope
+    pub fn allows_actions<'a>(&self, actions: impl IntoIterator<Item = &'a Action>) -> bool {
+        let mut scopes = Vec::new();
+        actions
+            .into_iter()
+            .for_each(|action| ActionScope::collect(action, &mut scopes));
+
+        if scopes.iter().any(|scope| self.excludes(scope)) {
+            return false
+        }
+
+        self.include.is_empty() || scopes.iter().any(|scope| self.includes(scope))
+    }
+
+    fn excludes(&self, scope: &ActionScope) -> bool {
+        scope
+            .protocol
+            .is_some_and(|p| self.exclude.protocols.contains(&p))
+            || scope.pool.is_some_and(|p| self.exclude.pools.contains(&p))
+            || scope.tokens.iter().any(|t| self.exclude.tokens.contains(t))
+    }
+
+    /// An action without a protocol or pool is only included by its tokens,
+    /// when there are no protocols or pools to include
+    fn includes(&self, scope: &ActionScope) -> bool {
+        let include = &self.include;
+
+        scope.protocol.map_or(include.protocols.is_empty(), |p| {
+            include.protocols.is_empty() || include.protocols.contains(&p)
+        }) && scope.pool.map_or(include.pools.is_empty(), |p| {
+            include.pools.is_empty() || include.pools.contains(&p)
+        }) && (include.tokens.is_empty() || scope.tokens.iter().any(|t| include.tokens.contains(t)))
+    }
+}
+
+/// What an action trades on, the actions nested in aggregators, batches, flash
+/// loans and fills are taken individually
+struct ActionScope {
+    protocol: Option<Protocol>,
+    pool:     Option<Address>,
+    tokens:   Vec<Address>,
+}
+
+impl ActionScope {
+    fn token(token: Address) -> Self {
+        Self { protocol: None, pool: None, tokens: vec![token] }
+    }
+
+    fn swap(s: &NormalizedSwap) -> Self {
+        Self {
+            protocol: Some(s.protocol),
+            pool:     Some(s.pool),
+            tokens:   vec![s.token_in.address, s.token_out.address],
+        }
+    }
+
+    fn collect(action: &Action, scopes: &mut Vec<Self>) {
+        match action {
+            Action::Swap(s) => scopes.push(Self::swap(s)),
+            Action::SwapWithFee(s) => scopes.push(Self::swap(s)),
+            Action::Fill(f) => scopes.push(Self::swap(&f.taker_swap())),
+            Action::Mint(m) => scopes.push(Self {
+                protocol: Some(m.protocol),
+                pool:     Some(m.pool),
+                tokens:   m.token.iter().map(|t| t.address).collect(),
+            }),
+            Action::Burn(b) => scopes.push(Self {
+                protocol: Some(b.protocol),
+                pool:     Some(b.pool),
+                tokens:   b.token.iter().map(|t| t.address).collect(),
+            }),
+            Action::Liquidation(l) => scopes.push(Self {
+                protocol: Some(l.protocol),
+                pool:     Some(l.pool),
+                tokens:   vec![l.collateral_asset.address, l.debt_asset.address],
+            }),
+            Action::Transfer(t) => scopes.push(Self::token(t.token.address)),
+            Action::Aggregator(a) => a
+                .child_actions
+                .iter()
+                .for_each(|action| Self::collect(action, scopes)),
+            Action::FlashLoan(f) => {
+                f.child_actions
+                    .iter()
+                    .for_each(|action| Self::collect(action, scopes));
+                scopes.extend(f.repayments.iter().map(|t| Self::token(t.token.address)));
+            }
+            Action::Batch(b) => scopes.extend(
+                b.user_swaps
+                    .iter()
+                    .chain(b.solver_swaps.iter().flatten())
+                    .map(Self::swap),
+            ),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::token_info::TokenInfoWithAddress,
+        normalized_actions::{NormalizedBatch, NormalizedSwap},
+    };
+
+    fn normalized_swap(protocol: Protocol, pool: Address, token_in: Address) -> NormalizedSwap {
+        NormalizedSwap {
+            protocol,
+            pool,
+            token_in: TokenInfoWithAddress { address: token_in, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    fn swap(protocol: Protocol, pool: Address, token_in: Address) -> Action {
+        Action::Swap(normalized_swap(protocol, pool, token_in))
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let noisy_pool = Address::repeat_byte(1);
+        let filter = ActionFilter {
+            include: ActionFilterList {
+                protocols: [Protocol::CurveBasePool2].into_iter().collect(),
+                ..Default::default()
+            },
+            exclude: ActionFilterList {
+                pools: [noisy_pool].into_iter().collect(),
+                ..Default::default()
+            },
+        };
+
+        let curve = swap(Protocol::CurveBasePool2, Address::ZERO, Address::ZERO);
+        let noisy = swap(Protocol::CurveBasePool2, noisy_pool, Address::ZERO);
+        let uniswap = swap(Protocol::UniswapV2, Address::ZERO, Address::ZERO);
+        let transfer = Action::Transfer(Default::default());
+
+        assert!(filter.allows_actions([&curve]));
+        assert!(!filter.allows_actions([&noisy]));
+        assert!(!filter.allows_actions([&uniswap]));
+        assert!(!filter.allows_actions([&transfer]));
+        assert!(ActionFilter::default().allows_actions([&noisy]));
+
+        // a transaction is kept or dropped whole, with every one of its actions
+        assert!(filter.allows_actions([&curve, &uniswap, &transfer]));
+        assert!(!filter.allows_actions([&curve, &noisy]));
+    }
+
+    #[test]
+    fn test_matches_nested_actions() {
+        let noisy_pool = Address::repeat_byte(1);
+        let filter = ActionFilter {
+            exclude: ActionFilterList {
+                pools: [noisy_pool].into_iter().collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let batch = Action::Batch(NormalizedBatch {
+            user_swaps: vec![normalized_swap(Protocol::UniswapV2, noisy_pool, Address::ZERO)],
+            ..Default::default()
+        });
+        assert!(!filter.allows_actions([&batch]));
+        assert!(filter.allows_actions([&swap(Protocol::UniswapV2, Address::ZERO, Address::ZERO)]));
+    }
+}
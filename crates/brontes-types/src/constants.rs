@@ -38,6 +38,10 @@ pub const PYUSD_ADDRESS: Address = Address::new(hex!("6c3ea9036406852006290770be
 pub const KCS_ADDRESS: Address = Address::new(hex!("f34960d9d60be18cc1d5afc1a6f012a723a28811"));
 pub const EURT_ADDRESS: Address = Address::new(hex!("c581b735a1688071a1746c968e0798d642ede491"));
 pub const LINK_ADDRESS: Address = Address::new(hex!("514910771af9ca656af840dff83e8264ecf986ca"));
+pub const STETH_ADDRESS: Address = Address::new(hex!("ae7ab96520DE3A18E5e111B5EaAb095312D7fE84"));
+pub const WSTETH_ADDRESS: Address = Address::new(hex!("7f39C581F595B53c5cb19bD0b3f8dA6c935E2Ca0"));
+pub const RETH_ADDRESS: Address = Address::new(hex!("ae78736Cd615f374D3085123A210448E74Fc6393"));
+pub const CBETH_ADDRESS: Address = Address::new(hex!("Be9895146f7AF43049ca1c1AE358B0541Ea49704"));
 pub const UNI_TOKEN: Address = Address::new(hex!("1f9840a85d5af5bf1d1762f925bdaddc4201f984"));
 pub const XAUT_ADDRESS: Address = Address::new(hex!("68749665ff8d2d112fa859aa293f07a622782f38"));
 
@@ -150,3 +154,8 @@ pub const GOLD_STABLES_BY_ADDRESS: [Address; 2] = [
     XAUT_ADDRESS, // Tether Gold
     PAXG_ADDRESS, // Paxos Gold
 ];
+
+/// Liquid staking tokens, their value tracks ETH at an exchange rate that
+/// drifts as staking rewards accrue
+pub const LIQUID_STAKING_TOKENS: [Address; 4] =
+    [STETH_ADDRESS, WSTETH_ADDRESS, RETH_ADDRESS, CBETH_ADDRESS];
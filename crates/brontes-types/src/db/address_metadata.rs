@@ -12,6 +12,7 @@ use crate::{
 
 #[derive(Debug, Default, Row, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct AddressMetadata {
     pub entity_name:     Option<String>,
     pub nametag:         Option<String>,
@@ -57,6 +58,10 @@ impl AddressMetadata {
             return ContractType::DefiAutomation;
         }
 
+        if self.is_fee_collector() {
+            return ContractType::FeeCollector;
+        }
+
         if self.is_cex() {
             return ContractType::Cex;
         }
@@ -79,6 +84,14 @@ impl AddressMetadata {
             .any(|label| label.to_lowercase().contains("automation"))
     }
 
+    /// Protocol treasuries and router fee wallets that sweep collected fees
+    fn is_fee_collector(&self) -> bool {
+        self.labels.iter().any(|label| {
+            let label = label.to_lowercase();
+            label.contains("fee collector") || label.contains("fee recipient")
+        })
+    }
+
     fn is_settlement_contract(&self) -> bool {
         if let Some(nametag) = &self.nametag {
             if nametag.eq_ignore_ascii_case("UniswapX")
@@ -93,7 +106,7 @@ impl AddressMetadata {
             .any(|label| label.eq_ignore_ascii_case("settlement"))
     }
 
-    fn is_cex(&self) -> bool {
+    pub fn is_cex(&self) -> bool {
         self.address_type
             .as_deref()
             .map_or(false, |t| t.eq_ignore_ascii_case("cex"))
@@ -184,6 +197,7 @@ pub enum ContractType {
     Bridge,
     SolverSettlement,
     DefiAutomation,
+    FeeCollector,
     Unknown,
 }
 
@@ -203,12 +217,17 @@ impl ContractType {
     pub fn is_defi_automation(&self) -> bool {
         matches!(self, ContractType::DefiAutomation)
     }
+
+    pub fn is_fee_collector(&self) -> bool {
+        matches!(self, ContractType::FeeCollector)
+    }
 }
 
 implement_table_value_codecs_with_zc!(AddressMetadataRedefined);
 
 #[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct ContractInfo {
     pub verified_contract: Option<bool>,
     pub contract_creator:  Option<Address>,
@@ -234,6 +253,7 @@ impl ContractInfo {
 #[derive(
     Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, rSerialize, rDeserialize, Archive,
 )]
+#[archive(check_bytes)]
 pub struct Socials {
     pub twitter:           Option<String>,
     pub twitter_followers: Option<u64>,
@@ -24,6 +24,7 @@ use crate::{
     Archive,
     Hash
 ))]
+#[redefined_attr(archive(check_bytes))]
 pub struct ProtocolInfo {
     #[serde(with = "protocol")]
     #[redefined(same_fields)]
@@ -0,0 +1,39 @@
+//! Append only logs of manual corrections.
+//!
+//! The bundle overrides and the suppression list are both kept as audit logs:
+//! every change is appended under the next id with its author and reason and
+//! is never edited. A change is undone by appending one that revokes it, so
+//! the log is the full history of the corrections and the corrections in
+//! effect are derived from it with [`entries_in_effect`].
+use crate::FastHashSet;
+
+/// A change of an audit log
+pub trait AuditEntry {
+    /// Id of the change this one revokes, `None` unless it's a revocation
+    fn revokes(&self) -> Option<u64>;
+}
+
+/// The changes in effect, in the order they were made. Takes the changes with
+/// their ids, in any order, and drops the revocations and the changes they
+/// revoke
+pub fn entries_in_effect<T: AuditEntry>(mut entries: Vec<(u64, T)>) -> Vec<(u64, T)> {
+    entries.sort_unstable_by_key(|(id, _)| *id);
+
+    let revoked = entries
+        .iter()
+        .filter_map(|(_, entry)| entry.revokes())
+        .collect::<FastHashSet<_>>();
+
+    entries
+        .into_iter()
+        .filter(|(id, entry)| entry.revokes().is_none() && !revoked.contains(id))
+        .collect()
+}
+
+/// Unix timestamp in seconds, the creation time of a new change
+pub fn created_at() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,30 @@
+use redefined::Redefined;
+use reth_rpc_types::beacon::BlsPublicKey;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::redefined_types::primitives::BlsPublicKeyRedefined, implement_table_value_codecs_with_zc,
+};
+
+/// Consensus layer data for the slot in which a block was proposed.
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BeaconBlockInfo {
+    pub slot:            u64,
+    pub proposer_index:  u64,
+    /// `None` if the beacon node couldn't resolve the validator
+    pub proposer_pubkey: Option<BlsPublicKey>,
+    /// Slots between the parent block and this block that didn't produce a
+    /// block
+    pub missed_slots:    Vec<u64>,
+}
+
+impl BeaconBlockInfo {
+    pub fn missed_slot_count(&self) -> u64 {
+        self.missed_slots.len() as u64
+    }
+}
+
+implement_table_value_codecs_with_zc!(BeaconBlockInfoRedefined);
@@ -653,29 +653,39 @@ pub struct BlockAnalysis {
     pub proposer_profit_eth:    Option<f64>,
 }
 
+/// Searcher transactions and inventory rebalances extract no mev
+fn is_mev(mev_type: MevType) -> bool {
+    !matches!(mev_type, MevType::SearcherTx | MevType::Rebalance)
+}
+
+/// Jit liquidity, whether the position was held within a block or across blocks
+fn is_jit(mev_type: MevType) -> bool {
+    matches!(mev_type, MevType::Jit | MevType::JitExtended)
+}
+
 impl BlockAnalysis {
     #[rustfmt::skip]
     pub fn new(block: &MevBlock, bundles: &[Bundle]) -> Self {
         // All fields
         let (all_profit_addr, all_profit_am) =
-            Self::top_searcher_by_profit(|b| b != MevType::SearcherTx && b!= MevType::CexDexTrades, bundles).unzip();
+            Self::top_searcher_by_profit(|b| is_mev(b) && b!= MevType::CexDexTrades, bundles).unzip();
         let (all_rev_addr, all_rev_am) =
-            Self::top_searcher_by_rev(|b| b != MevType::SearcherTx  && b!= MevType::CexDexTrades, bundles).unzip();
+            Self::top_searcher_by_rev(|b| is_mev(b)  && b!= MevType::CexDexTrades, bundles).unzip();
 
         let (all_biggest_tx_prof, all_biggest_prof) =
-            Self::biggest_arb_profit(|b| b != MevType::SearcherTx  && b!= MevType::CexDexTrades, bundles).unzip();
+            Self::biggest_arb_profit(|b| is_mev(b)  && b!= MevType::CexDexTrades, bundles).unzip();
 
         let (all_biggest_tx_rev, all_biggest_rev) =
-            Self::biggest_arb_revenue(|b| b != MevType::SearcherTx  && b!= MevType::CexDexTrades, bundles).unzip();
+            Self::biggest_arb_revenue(|b| is_mev(b)  && b!= MevType::CexDexTrades, bundles).unzip();
 
         let (fund_rev, fund_rev_am) =
-            Self::top_fund_by_type_rev(|b| b != MevType::SearcherTx && b!= MevType::CexDexTrades, bundles).unzip();
+            Self::top_fund_by_type_rev(|b| is_mev(b) && b!= MevType::CexDexTrades, bundles).unzip();
         let (fund_profit, fund_profit_am) =
-            Self::top_fund_by_type_rev(|b| b != MevType::SearcherTx && b!= MevType::CexDexTrades, bundles).unzip();
+            Self::top_fund_by_type_rev(|b| is_mev(b) && b!= MevType::CexDexTrades, bundles).unzip();
 
         let (all_pool_addr_prof, all_pool_addr_rev, all_pool_prof, all_pool_rev) =
             Self::most_transacted_pool(
-                |b| b != MevType::SearcherTx && b != MevType::Liquidation && b!= MevType::CexDexTrades,
+                |b| is_mev(b) && b != MevType::Liquidation && b!= MevType::CexDexTrades,
                 bundles,
                 Self::get_pool_fn,
             )
@@ -683,7 +693,7 @@ impl BlockAnalysis {
 
         let (all_pair_addr_prof, all_pair_addr_rev, all_pair_prof, all_pair_rev) =
             Self::most_transacted_pair(
-                |b| b != MevType::SearcherTx && b != MevType::Liquidation &&  b!= MevType::CexDexTrades,
+                |b| is_mev(b) && b != MevType::Liquidation &&  b!= MevType::CexDexTrades,
                 bundles,
                 Self::get_pair_fn,
             )
@@ -691,7 +701,7 @@ impl BlockAnalysis {
 
         let (all_dex_addr_prof, all_dex_addr_rev, all_dex_prof, all_dex_rev) =
             Self::most_transacted_dex(
-                |b| b != MevType::SearcherTx && b != MevType::Liquidation  || b!= MevType::CexDexTrades,
+                |b| is_mev(b) && b != MevType::Liquidation  || b!= MevType::CexDexTrades,
                 bundles,
                 Self::get_dex_fn,
             )
@@ -861,54 +871,54 @@ impl BlockAnalysis {
 
         // Jit Fields
         let (jit_searcher_prof_addr, jit_searcher_prof) =
-            Self::top_searcher_by_profit(|b| b == MevType::Jit, bundles).unzip();
+            Self::top_searcher_by_profit(is_jit, bundles).unzip();
         let (jit_searcher_rev_addr, jit_searcher_rev) =
-            Self::top_searcher_by_rev(|b| b == MevType::Jit, bundles).unzip();
+            Self::top_searcher_by_rev(is_jit, bundles).unzip();
 
         let (jit_all_searcher_prof_addr_eoa, jit_all_searcher_prof_eoa) =
-            Self::all_searchers_by_profit(|b| b == MevType::Jit, bundles, false)
+            Self::all_searchers_by_profit(is_jit, bundles, false)
                 .into_iter()
                 .unzip();
         let (jit_all_searcher_rev_addr_eoa, jit_all_searcher_rev_eoa) =
-            Self::all_searchers_by_rev(|b| b == MevType::Jit, bundles, false)
+            Self::all_searchers_by_rev(is_jit, bundles, false)
                 .into_iter()
                 .unzip();
         let (jit_all_searcher_prof_addr_contract, jit_all_searcher_prof_contract) =
-            Self::all_searchers_by_profit(|b| b == MevType::Jit, bundles, true)
+            Self::all_searchers_by_profit(is_jit, bundles, true)
                 .into_iter()
                 .unzip();
         let (jit_all_searcher_rev_addr_contract, jit_all_searcher_rev_contract) =
-            Self::all_searchers_by_rev(|b| b == MevType::Jit, bundles, true)
+            Self::all_searchers_by_rev(is_jit, bundles, true)
                 .into_iter()
                 .unzip();
 
         let (jit_biggest_tx_prof, jit_biggest_prof) =
-            Self::biggest_arb_profit(|b| b == MevType::Jit, bundles).unzip();
+            Self::biggest_arb_profit(is_jit, bundles).unzip();
 
         let (jit_biggest_tx_rev, jit_biggest_rev) =
-            Self::biggest_arb_revenue(|b| b == MevType::Jit, bundles).unzip();
+            Self::biggest_arb_revenue(is_jit, bundles).unzip();
 
         let (jit_pool_addr_prof, jit_pool_addr_rev, jit_pool_prof, jit_pool_rev) =
-            Self::most_transacted_pool(|b| b == MevType::Jit, bundles, Self::get_pool_fn)
+            Self::most_transacted_pool(is_jit, bundles, Self::get_pool_fn)
                 .four_unzip();
         let (jit_pair_addr_prof, jit_pair_addr_rev, jit_pair_prof, jit_pair_rev) =
-            Self::most_transacted_pair(|b| b == MevType::Jit, bundles, Self::get_pair_fn)
+            Self::most_transacted_pair(is_jit, bundles, Self::get_pair_fn)
                 .unwrap_or_default();
         let (jit_dex_addr_prof, jit_dex_addr_rev, jit_dex_prof, jit_dex_rev) =
-            Self::most_transacted_dex(|b| b == MevType::Jit, bundles, Self::get_dex_fn)
+            Self::most_transacted_dex(is_jit, bundles, Self::get_dex_fn)
                 .four_unzip();
 
         let (jit_fund_rev_addr, jit_fund_rev) =
-            Self::top_fund_by_type_rev(|b| b == MevType::Jit, bundles).unzip();
+            Self::top_fund_by_type_rev(is_jit, bundles).unzip();
         let (jit_fund_profit_addr, jit_fund_profit) =
-            Self::top_fund_by_type_profit(|b| b == MevType::Jit, bundles).unzip();
+            Self::top_fund_by_type_profit(is_jit, bundles).unzip();
 
         let (jit_all_funds_rev_addr, jit_all_funds_rev) =
-            Self::all_funds_by_type_rev(|b| b == MevType::Jit, bundles)
+            Self::all_funds_by_type_rev(is_jit, bundles)
                 .into_iter()
                 .unzip();
         let (jit_all_funds_profit_addr, jit_all_funds_profit) =
-            Self::all_funds_by_type_profit(|b| b == MevType::Jit, bundles)
+            Self::all_funds_by_type_profit(is_jit, bundles)
                 .into_iter()
                 .unzip();
 
@@ -917,7 +927,7 @@ impl BlockAnalysis {
             jit_all_pools_prof,
             jit_all_pools_addr_rev,
             jit_all_pools_rev,
-        ) = Self::all_transacted_pools(|b| b == MevType::Jit, bundles, Self::get_pool_fn)
+        ) = Self::all_transacted_pools(is_jit, bundles, Self::get_pool_fn)
             .four_unzip();
 
         let (
@@ -925,7 +935,7 @@ impl BlockAnalysis {
             jit_all_pairs_prof,
             jit_all_pairs_addr_rev,
             jit_all_pairs_rev,
-        ) = Self::all_transacted_pairs(|b| b == MevType::Jit, bundles, Self::get_pair_fn)
+        ) = Self::all_transacted_pairs(is_jit, bundles, Self::get_pair_fn)
             .four_unzip();
 
         let (
@@ -933,7 +943,7 @@ impl BlockAnalysis {
             jit_all_dexes_prof,
             jit_all_dexes_addr_rev,
             jit_all_dexes_rev,
-        ) = Self::all_transacted_dexes(|b| b == MevType::Jit, bundles, Self::get_dex_fn)
+        ) = Self::all_transacted_dexes(is_jit, bundles, Self::get_dex_fn)
             .four_unzip();
 
         // Jit Sando Fields
@@ -1174,15 +1184,15 @@ impl BlockAnalysis {
         Self {
             block_number: block.block_number,
             eth_price: block.eth_price,
-            all_bundle_count: Self::total_count_by_type(|f| f != MevType::SearcherTx, bundles),
-            all_total_profit: Self::total_profit_by_type(|f| f != MevType::SearcherTx, bundles),
-            all_total_revenue: Self::total_revenue_by_type(|f| f != MevType::SearcherTx, bundles),
+            all_bundle_count: Self::total_count_by_type(is_mev, bundles),
+            all_total_profit: Self::total_profit_by_type(is_mev, bundles),
+            all_total_revenue: Self::total_revenue_by_type(is_mev, bundles),
             all_average_profit_margin: Self::average_profit_margin(
-                |f| f != MevType::SearcherTx,
+                is_mev,
                 bundles,
             )
             .unwrap_or_default(),
-            all_searcher_count: Self::unique_eoa(|b| b != MevType::SearcherTx, bundles),
+            all_searcher_count: Self::unique_eoa(is_mev, bundles),
             all_top_searcher_revenue: all_rev_addr,
             all_top_searcher_revenue_amt: all_rev_am,
             all_top_searcher_profit: all_profit_addr,
@@ -1191,7 +1201,7 @@ impl BlockAnalysis {
             all_top_fund_revenue_amt: fund_rev_am,
             all_top_fund_profit_amt: fund_profit_am,
             all_top_fund_profit: fund_profit,
-            all_fund_count: Self::unique_funds(|b| b != MevType::SearcherTx, bundles),
+            all_fund_count: Self::unique_funds(is_mev, bundles),
             all_most_arbed_pool_profit: all_pool_addr_prof,
             all_most_arbed_pool_profit_amt: all_pool_prof,
             all_most_arbed_dex_revenue: all_dex_addr_rev,
@@ -1369,16 +1379,16 @@ impl BlockAnalysis {
 
             // jit
             jit_bundle_count:                 Self::total_count_by_type(
-                |b| b == MevType::Jit,
+                is_jit,
                 bundles,
             ),
-            jit_fund_count:                   Self::unique_funds(|b| b == MevType::Jit, bundles),
+            jit_fund_count:                   Self::unique_funds(is_jit, bundles),
             jit_total_profit:                 Self::total_profit_by_type(
-                |b| b == MevType::Jit,
+                is_jit,
                 bundles,
             ),
             jit_total_revenue:                Self::total_revenue_by_type(
-                |b| b == MevType::Jit,
+                is_jit,
                 bundles,
             ),
             jit_top_searcher_profit:          jit_searcher_prof_addr,
@@ -1398,7 +1408,7 @@ impl BlockAnalysis {
             jit_most_arbed_pool_profit:       jit_pool_addr_prof,
             jit_most_arbed_pool_revenue:      jit_pool_addr_rev,
             jit_average_profit_margin:        Self::average_profit_margin(
-                |f| f == MevType::Jit,
+                is_jit,
                 bundles,
             )
             .unwrap_or_default(),
@@ -1414,12 +1424,12 @@ impl BlockAnalysis {
             jit_searcher_eoa_all_profit_amt:  jit_all_searcher_prof_eoa,
             jit_searcher_eoa_all_revenue:     jit_all_searcher_rev_addr_eoa,
             jit_searcher_eoa_all_revenue_amt: jit_all_searcher_rev_eoa,
-            jit_searcher_eoa_count:           Self::unique_eoa(|b| b == MevType::Jit, bundles),
+            jit_searcher_eoa_count:           Self::unique_eoa(is_jit, bundles),
             jit_mev_contract_all_profit:      jit_all_searcher_prof_addr_contract,
             jit_mev_contract_all_profit_amt:  jit_all_searcher_prof_contract,
             jit_mev_contract_all_revenue:     jit_all_searcher_rev_addr_contract,
             jit_mev_contract_all_revenue_amt: jit_all_searcher_rev_contract,
-            jit_mev_contract_count:           Self::unique_contract(|b| b == MevType::Jit, bundles),
+            jit_mev_contract_count:           Self::unique_contract(is_jit, bundles),
             jit_fund_all_profit:              jit_all_funds_profit_addr,
             jit_fund_all_profit_amt:          jit_all_funds_profit,
             jit_fund_all_revenue:             jit_all_funds_rev_addr,
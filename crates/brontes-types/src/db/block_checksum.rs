@@ -0,0 +1,78 @@
+//! Checksums of the results of a block.
+//!
+//! Replicas running over the same range should produce the same bundles and
+//! mev block. A checksum is stored next to each block's results when they are
+//! written, hashing their canonical form: the mev block and the bundles
+//! serialized, with the bundles sorted so the order the inspectors returned
+//! them in doesn't matter. Recomputing the checksum from the stored results
+//! finds corrupted entries, comparing it across dbs finds replicas or runs
+//! that disagree.
+//!
+//! The checksum covers the results as they were classified. Re-pricings and
+//! overrides are stored apart and applied when the block is read, so they
+//! leave it valid. Rows stored in an older layout are upcast when read and
+//! no longer hash to the checksum written with them, upgrading the rows
+//! rewrites their checksum as well.
+use alloy_primitives::{keccak256, B256};
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::{mev_block::MevBlockWithClassified, redefined_types::primitives::*},
+    implement_table_value_codecs_with_zc,
+    mev::{Bundle, MevBlock},
+};
+
+#[derive(Debug, Default, PartialEq, Clone, Copy, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockChecksum {
+    pub checksum:     B256,
+    pub bundle_count: u64,
+}
+
+implement_table_value_codecs_with_zc!(BlockChecksumRedefined);
+
+impl BlockChecksum {
+    pub fn new(block: &MevBlock, mev: &[Bundle]) -> serde_json::Result<Self> {
+        let mut bundles = mev
+            .iter()
+            .map(serde_json::to_vec)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        bundles.sort_unstable();
+
+        let mut buf = serde_json::to_vec(block)?;
+        for bundle in bundles {
+            buf.extend_from_slice(&(bundle.len() as u64).to_be_bytes());
+            buf.extend(bundle);
+        }
+
+        Ok(Self { checksum: keccak256(buf), bundle_count: mev.len() as u64 })
+    }
+
+    pub fn of(block: &MevBlockWithClassified) -> serde_json::Result<Self> {
+        Self::new(&block.block, &block.mev)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::bundle;
+
+    #[test]
+    fn test_checksum_ignores_bundle_order() {
+        let at = |tx_index| bundle().tx_index(tx_index).build();
+        let block = MevBlock { block_number: 18_000_000, ..Default::default() };
+        let checksum = BlockChecksum::new(&block, &[at(1), at(2)]).unwrap();
+
+        assert_eq!(checksum, BlockChecksum::new(&block, &[at(2), at(1)]).unwrap());
+        assert_eq!(checksum.bundle_count, 2);
+        assert_ne!(checksum, BlockChecksum::new(&block, &[at(1), at(3)]).unwrap());
+        assert_ne!(
+            checksum,
+            BlockChecksum::new(&MevBlock { eth_price: 1.0, ..block }, &[at(1), at(2)]).unwrap()
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Processing cost of each block.
+//!
+//! Sizing hardware for a full history backfill needs to know what a block
+//! costs to process. The run gives each block a [`BlockCostRecorder`] when it
+//! starts fetching it, the stages record their share into it as they go, and
+//! the cost is taken and stored once the block's results are written. A block
+//! that fails drops its recorder along with everything else about it.
+use std::{future::Future, sync::Arc};
+
+use parking_lot::Mutex;
+use redefined::Redefined;
+use reth_rpc_types::trace::parity::{Action, TraceOutput};
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{implement_table_value_codecs_with_zc, structured_trace::TxTrace};
+
+#[derive(Debug, Default, PartialEq, Clone, Copy, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockCost {
+    /// Rpc calls made to the node for the block's traces, receipts and
+    /// header. Traces read from the db or from a local node aren't counted
+    pub trace_rpc_calls:    u64,
+    /// Size of the calldata, return data and logs of the block's traces
+    pub trace_bytes:        u64,
+    /// Time spent building the block tree, in microseconds
+    pub classification_us:  u64,
+    /// Time the inspectors spent on the block summed over all inspectors, in
+    /// microseconds. As the inspectors run in parallel this is the cpu time
+    /// they used rather than the time the block took
+    pub inspection_us:      u64,
+    /// Inspectors that weren't run on the block as its action summary ruled
+    /// out their mev
+    pub inspectors_skipped: u64,
+    /// Compressed size of the block's traces, dex prices and mev written to
+    /// the db
+    pub db_bytes_written:   u64,
+}
+
+implement_table_value_codecs_with_zc!(BlockCostRedefined);
+
+impl BlockCost {
+    pub fn add(&mut self, other: &BlockCost) {
+        self.trace_rpc_calls += other.trace_rpc_calls;
+        self.trace_bytes += other.trace_bytes;
+        self.classification_us += other.classification_us;
+        self.inspection_us += other.inspection_us;
+        self.inspectors_skipped += other.inspectors_skipped;
+        self.db_bytes_written += other.db_bytes_written;
+    }
+
+    fn max(&mut self, other: &BlockCost) {
+        self.trace_rpc_calls = self.trace_rpc_calls.max(other.trace_rpc_calls);
+        self.trace_bytes = self.trace_bytes.max(other.trace_bytes);
+        self.classification_us = self.classification_us.max(other.classification_us);
+        self.inspection_us = self.inspection_us.max(other.inspection_us);
+        self.inspectors_skipped = self.inspectors_skipped.max(other.inspectors_skipped);
+        self.db_bytes_written = self.db_bytes_written.max(other.db_bytes_written);
+    }
+}
+
+/// Accumulates the cost of a block while it's processed. Shared by the
+/// stages that process the block, it's carried on the block's [`Metadata`]
+/// once the block is loaded
+///
+/// [`Metadata`]: super::metadata::Metadata
+#[derive(Debug, Default, Clone)]
+pub struct BlockCostRecorder(Arc<Mutex<BlockCost>>);
+
+tokio::task_local! {
+    /// Cost of the block the task is processing, for what's shared by all
+    /// blocks such as the tracing provider and the db writer
+    static BLOCK_COST: BlockCostRecorder;
+}
+
+impl BlockCostRecorder {
+    /// Adds to the cost of the block
+    pub fn record(&self, f: impl FnOnce(&mut BlockCost)) {
+        f(&mut self.0.lock())
+    }
+
+    /// Returns the cost recorded so far, resetting it
+    pub fn take(&self) -> BlockCost {
+        std::mem::take(&mut *self.0.lock())
+    }
+
+    /// Runs the future as processing the block, the rpc calls it makes and
+    /// the bytes it writes to the db are counted against the block's cost
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        BLOCK_COST.scope(self, fut).await
+    }
+
+    /// The cost of the block the current task is processing, if it's
+    /// processing one
+    pub fn current() -> Option<Self> {
+        BLOCK_COST.try_with(Clone::clone).ok()
+    }
+}
+
+/// Adds to the cost of the block the current task is processing. Does
+/// nothing outside of a block's processing, so commands that trace or write
+/// blocks outside of a run don't record anything
+pub fn record_block_cost(f: impl FnOnce(&mut BlockCost)) {
+    let _ = BLOCK_COST.try_with(|cost| cost.record(f));
+}
+
+/// Size of the calldata, return data and logs of the traces
+pub fn trace_payload_bytes(traces: &[TxTrace]) -> u64 {
+    traces
+        .iter()
+        .flat_map(|tx| tx.trace.iter())
+        .map(|trace| {
+            let input = match &trace.trace.action {
+                Action::Call(call) => call.input.len(),
+                Action::Create(create) => create.init.len(),
+                _ => 0,
+            };
+            let output = match &trace.trace.result {
+                Some(TraceOutput::Call(call)) => call.output.len(),
+                Some(TraceOutput::Create(create)) => create.code.len(),
+                None => 0,
+            };
+            let logs = trace
+                .logs
+                .iter()
+                .map(|log| log.data.data.len() + log.topics().len() * 32)
+                .sum::<usize>();
+
+            (input + output + logs) as u64
+        })
+        .sum()
+}
+
+/// Totals and per block averages of the costs of a range of blocks
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct BlockCostSummary {
+    pub blocks: u64,
+    pub total:  BlockCost,
+    pub max:    BlockCost,
+}
+
+impl BlockCostSummary {
+    pub fn new<'a>(costs: impl IntoIterator<Item = &'a BlockCost>) -> Self {
+        let mut summary = Self::default();
+        for cost in costs {
+            summary.blocks += 1;
+            summary.total.add(cost);
+            summary.max.max(cost);
+        }
+        summary
+    }
+
+    /// Average per block of a field of the costs
+    pub fn mean(&self, field: impl Fn(&BlockCost) -> u64) -> f64 {
+        if self.blocks == 0 {
+            return 0.0
+        }
+        field(&self.total) as f64 / self.blocks as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_cost_summary() {
+        let costs = [
+            BlockCost {
+                trace_rpc_calls: 3,
+                trace_bytes: 100,
+                inspection_us: 10,
+                ..Default::default()
+            },
+            BlockCost {
+                trace_rpc_calls: 1,
+                trace_bytes: 300,
+                db_bytes_written: 50,
+                ..Default::default()
+            },
+        ];
+        let summary = BlockCostSummary::new(&costs);
+
+        assert_eq!(summary.blocks, 2);
+        assert_eq!(summary.total.trace_rpc_calls, 4);
+        assert_eq!(summary.max.trace_bytes, 300);
+        assert_eq!(summary.max.db_bytes_written, 50);
+        assert_eq!(summary.mean(|c| c.trace_bytes), 200.0);
+        assert_eq!(summary.mean(|c| c.inspection_us), 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_records_the_cost_of_the_scoped_block() {
+        record_block_cost(|cost| cost.trace_rpc_calls += 1);
+
+        let recorder = BlockCostRecorder::default();
+        recorder
+            .clone()
+            .scope(async {
+                record_block_cost(|cost| cost.trace_rpc_calls += 3);
+                BlockCostRecorder::current()
+                    .unwrap()
+                    .record(|cost| cost.db_bytes_written += 10);
+            })
+            .await;
+        recorder.record(|cost| cost.classification_us += 7);
+        assert!(BlockCostRecorder::current().is_none());
+
+        let cost = recorder.take();
+        assert_eq!(cost.trace_rpc_calls, 3);
+        assert_eq!(cost.db_bytes_written, 10);
+        assert_eq!(cost.classification_us, 7);
+        assert_eq!(recorder.take(), BlockCost::default());
+    }
+}
@@ -18,6 +18,7 @@ use crate::{
 
 #[derive(Debug, Default, Row, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct BuilderInfo {
     pub name: Option<String>,
     #[redefined(same_fields)]
@@ -0,0 +1,279 @@
+//! Fill rates of submitted bundles.
+//!
+//! Brontes only sees the bundles that landed. Given the bundles searchers
+//! submitted to builders or relays, this matches each submission against the
+//! transactions of the block it targeted, so the bundles that failed to land
+//! can be counted per searcher and per detected strategy.
+use std::{io::BufRead, path::Path};
+
+use alloy_primitives::{Address, B256};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mev::{Bundle, Mev, MevType},
+    FastHashMap, FastHashSet,
+};
+
+/// A bundle as it was submitted to a builder or relay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmittedBundle {
+    /// Block the bundle targeted
+    pub block_number: u64,
+    /// Signer of the bundle's transactions
+    pub searcher:     Address,
+    pub tx_hashes:    Vec<B256>,
+    #[serde(default)]
+    pub builder:      Option<Address>,
+    #[serde(default)]
+    pub relay:        Option<String>,
+}
+
+impl SubmittedBundle {
+    /// Reads submissions from a file with one json encoded bundle per line
+    pub fn from_json_lines_file(path: impl AsRef<Path>) -> eyre::Result<Vec<Self>> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .map_err(|e| eyre::eyre!("failed to open submissions {}: {e}", path.display()))?;
+
+        std::io::BufReader::new(file)
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+            .map(|(i, line)| {
+                serde_json::from_str(&line?)
+                    .map_err(|e| eyre::eyre!("invalid submission on line {}: {e}", i + 1))
+            })
+            .collect()
+    }
+
+    /// A bundle landed if all of its transactions are in the targeted block
+    pub fn landed_in(&self, block_txs: &FastHashSet<B256>) -> bool {
+        !self.tx_hashes.is_empty() && self.tx_hashes.iter().all(|tx| block_txs.contains(tx))
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct FillRate {
+    pub submitted: u64,
+    pub landed:    u64,
+}
+
+impl FillRate {
+    fn add(&mut self, landed: bool) {
+        self.submitted += 1;
+        self.landed += landed as u64;
+    }
+
+    pub fn fill_rate(&self) -> f64 {
+        if self.submitted == 0 {
+            return 0.0
+        }
+        self.landed as f64 / self.submitted as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearcherFillRate {
+    pub searcher:  Address,
+    #[serde(flatten)]
+    pub fill_rate: FillRate,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StrategyFillRate {
+    /// `None` for submissions of searchers brontes never detected mev for
+    pub mev_type:  Option<MevType>,
+    #[serde(flatten)]
+    pub fill_rate: FillRate,
+}
+
+#[derive(Debug, Clone)]
+struct SubmissionOutcome {
+    searcher: Address,
+    landed:   bool,
+    /// Type of the detected bundle the submission landed as
+    mev_type: Option<MevType>,
+}
+
+/// Fill rates of submitted bundles over a block range.
+///
+/// Landed submissions take the strategy of the detected bundle they landed
+/// as. Submissions that didn't land are attributed to the strategy brontes
+/// detected most often for their searcher over the range.
+#[derive(Debug, Default, Clone)]
+pub struct FillRateReport {
+    outcomes:           Vec<SubmissionOutcome>,
+    searcher_mev_types: FastHashMap<Address, FastHashMap<MevType, u64>>,
+}
+
+impl FillRateReport {
+    /// Matches the submissions that targeted a block against the block's
+    /// transactions and the mev detected in it
+    pub fn add_block<'a>(
+        &mut self,
+        submissions: impl IntoIterator<Item = &'a SubmittedBundle>,
+        block_txs: &FastHashSet<B256>,
+        mev: &[Bundle],
+    ) {
+        for bundle in mev {
+            *self
+                .searcher_mev_types
+                .entry(bundle.header.eoa)
+                .or_default()
+                .entry(bundle.header.mev_type)
+                .or_default() += 1;
+        }
+
+        let mev_txs = mev
+            .iter()
+            .map(|bundle| {
+                (
+                    bundle
+                        .data
+                        .mev_transaction_hashes()
+                        .into_iter()
+                        .collect::<FastHashSet<_>>(),
+                    bundle.header.mev_type,
+                )
+            })
+            .collect_vec();
+
+        for submission in submissions {
+            let landed = submission.landed_in(block_txs);
+            let mev_type = landed
+                .then(|| {
+                    mev_txs
+                        .iter()
+                        .find(|(txs, _)| submission.tx_hashes.iter().any(|tx| txs.contains(tx)))
+                        .map(|(_, mev_type)| *mev_type)
+                })
+                .flatten();
+
+            self.outcomes.push(SubmissionOutcome {
+                searcher: submission.searcher,
+                landed,
+                mev_type,
+            });
+        }
+    }
+
+    pub fn total(&self) -> FillRate {
+        let mut total = FillRate::default();
+        self.outcomes.iter().for_each(|o| total.add(o.landed));
+        total
+    }
+
+    /// Searchers sorted by the number of bundles they submitted
+    pub fn by_searcher(&self) -> Vec<SearcherFillRate> {
+        let mut searchers: FastHashMap<Address, FillRate> = FastHashMap::default();
+        for outcome in &self.outcomes {
+            searchers
+                .entry(outcome.searcher)
+                .or_default()
+                .add(outcome.landed);
+        }
+
+        searchers
+            .into_iter()
+            .map(|(searcher, fill_rate)| SearcherFillRate { searcher, fill_rate })
+            .sorted_by(|a, b| {
+                b.fill_rate
+                    .submitted
+                    .cmp(&a.fill_rate.submitted)
+                    .then(a.searcher.cmp(&b.searcher))
+            })
+            .collect()
+    }
+
+    /// Strategies sorted by the number of bundles submitted for them
+    pub fn by_strategy(&self) -> Vec<StrategyFillRate> {
+        let mut strategies: FastHashMap<Option<MevType>, FillRate> = FastHashMap::default();
+        for outcome in &self.outcomes {
+            let mev_type = outcome
+                .mev_type
+                .or_else(|| self.main_strategy(outcome.searcher));
+            strategies.entry(mev_type).or_default().add(outcome.landed);
+        }
+
+        strategies
+            .into_iter()
+            .map(|(mev_type, fill_rate)| StrategyFillRate { mev_type, fill_rate })
+            .sorted_by(|a, b| {
+                b.fill_rate
+                    .submitted
+                    .cmp(&a.fill_rate.submitted)
+                    .then_with(|| {
+                        let name = |s: &StrategyFillRate| s.mev_type.map(|t| t.to_string());
+                        name(a).cmp(&name(b))
+                    })
+            })
+            .collect()
+    }
+
+    fn main_strategy(&self, searcher: Address) -> Option<MevType> {
+        self.searcher_mev_types
+            .get(&searcher)?
+            .iter()
+            .max_by(|(a_type, a), (b_type, b)| {
+                a.cmp(b).then_with(|| b_type.as_ref().cmp(a_type.as_ref()))
+            })
+            .map(|(mev_type, _)| *mev_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mev::{BundleData, BundleHeader, Sandwich};
+
+    fn submission(searcher: u8, txs: &[u8]) -> SubmittedBundle {
+        SubmittedBundle {
+            block_number: 1,
+            searcher:     Address::repeat_byte(searcher),
+            tx_hashes:    txs.iter().copied().map(B256::repeat_byte).collect(),
+            builder:      None,
+            relay:        None,
+        }
+    }
+
+    #[test]
+    fn test_fill_rate_report() {
+        let sandwich = Bundle {
+            header: BundleHeader {
+                eoa: Address::repeat_byte(1),
+                mev_type: MevType::Sandwich,
+                ..Default::default()
+            },
+            data:   BundleData::Sandwich(Sandwich {
+                frontrun_tx_hash: vec![B256::repeat_byte(1)],
+                backrun_tx_hash: B256::repeat_byte(3),
+                ..Default::default()
+            }),
+        };
+        let block_txs = [1u8, 2, 3].map(B256::repeat_byte).into_iter().collect();
+
+        let submissions = [
+            submission(1, &[1, 3]),
+            // lost to the landed bundle
+            submission(1, &[4, 5]),
+            submission(2, &[2]),
+            submission(2, &[6]),
+        ];
+        let mut report = FillRateReport::default();
+        report.add_block(&submissions, &block_txs, &[sandwich]);
+
+        assert_eq!(report.total(), FillRate { submitted: 4, landed: 2 });
+
+        let strategies = report.by_strategy();
+        assert_eq!(strategies.len(), 2);
+        let sandwiches = strategies
+            .iter()
+            .find(|s| s.mev_type == Some(MevType::Sandwich))
+            .unwrap();
+        // the failed submission is attributed to the searcher's sandwiching
+        assert_eq!(sandwiches.fill_rate, FillRate { submitted: 2, landed: 1 });
+        let unknown = strategies.iter().find(|s| s.mev_type.is_none()).unwrap();
+        assert_eq!(unknown.fill_rate.fill_rate(), 0.5);
+    }
+}
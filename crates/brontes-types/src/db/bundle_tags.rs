@@ -0,0 +1,241 @@
+//! Free form tags on bundles.
+//!
+//! Tags mark bundles with properties that aren't part of the bundle schema, so
+//! cohorts like "arbs that used a flashloan" can be queried without adding a
+//! column for each of them. Detector tags are derived from the bundle and its
+//! transactions when the block is processed and are replaced when the block is
+//! reprocessed. Manual tags are added and removed by hand and are kept across
+//! reprocessing.
+use alloy_primitives::B256;
+use clap::ValueEnum;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+use crate::{
+    db::{mev_block::MevBlockWithClassified, redefined_types::primitives::*},
+    implement_table_value_codecs_with_zc,
+    mev::{Bundle, BundleData},
+    normalized_actions::NormalizedSwap,
+    FastHashMap, FastHashSet,
+};
+
+/// One of the bundle's transactions borrowed from a flash loan
+pub const TAG_FLASHLOAN: &str = "flashloan";
+/// The bundle swapped the same pair through more than one pool
+pub const TAG_CROSS_POOL: &str = "cross_pool";
+/// A call the searcher made in one of the bundle's transactions reverted
+/// without reverting the transaction
+pub const TAG_FAILED_LEG: &str = "failed_leg";
+/// The arb's legs are split across transactions
+pub const TAG_CROSS_TX: &str = "cross_tx";
+/// The bundle landed in a builder-merged submission
+pub const TAG_MERGED_SUBMISSION: &str = "merged_submission";
+/// The bundle's profit couldn't be priced
+pub const TAG_UNPRICED: &str = "unpriced";
+/// The bundle was priced from cex quotes that had outliers rejected
+pub const TAG_SANITIZED_QUOTES: &str = "sanitized_quotes";
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+    ValueEnum,
+    AsRefStr,
+)]
+#[archive(check_bytes)]
+pub enum TagSource {
+    /// Derived from the bundle when its block was processed
+    Detector,
+    /// Added by hand
+    Manual,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BundleTag {
+    /// Transaction hash of the bundle, as in its header
+    pub tx_hash: B256,
+    pub tag:     String,
+    #[redefined(same_fields)]
+    pub source:  TagSource,
+    /// Who added a manual tag
+    pub author:  Option<String>,
+}
+
+impl BundleTag {
+    pub fn detected(tx_hash: B256, tag: &str) -> Self {
+        Self { tx_hash, tag: tag.to_string(), source: TagSource::Detector, author: None }
+    }
+
+    pub fn manual(tx_hash: B256, tag: String, author: String) -> Self {
+        Self { tx_hash, tag, source: TagSource::Manual, author: Some(author) }
+    }
+}
+
+/// The tags of the bundles of a block
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockBundleTags {
+    pub tags: Vec<BundleTag>,
+}
+
+implement_table_value_codecs_with_zc!(BlockBundleTagsRedefined);
+
+impl BlockBundleTags {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+
+    /// Replaces the detector tags of the block, manual tags are kept
+    pub fn replace_detected(&mut self, detected: Vec<BundleTag>) {
+        self.tags.retain(|tag| tag.source != TagSource::Detector);
+        detected.into_iter().for_each(|tag| {
+            self.add(tag);
+        });
+    }
+
+    /// Adds the tag, returns false if the bundle already has it
+    pub fn add(&mut self, tag: BundleTag) -> bool {
+        if self
+            .tags
+            .iter()
+            .any(|t| t.tx_hash == tag.tx_hash && t.tag == tag.tag)
+        {
+            return false
+        }
+        self.tags.push(tag);
+        true
+    }
+
+    /// Removes the tag from the bundle, returns false if it didn't have it
+    pub fn remove(&mut self, tx_hash: B256, tag: &str) -> bool {
+        let len = self.tags.len();
+        self.tags
+            .retain(|t| !(t.tx_hash == tx_hash && t.tag == tag));
+        self.tags.len() != len
+    }
+
+    pub fn tags_of(&self, tx_hash: B256) -> impl Iterator<Item = &str> + '_ {
+        self.tags
+            .iter()
+            .filter(move |t| t.tx_hash == tx_hash)
+            .map(|t| t.tag.as_str())
+    }
+}
+
+/// The tags that follow from the bundle itself. Tags that need the block's
+/// transactions are detected by the composer
+pub fn detect_bundle_tags(bundle: &Bundle) -> Vec<&'static str> {
+    let mut tags = vec![];
+
+    if let BundleData::AtomicArb(arb) = &bundle.data {
+        if arb.is_cross_tx() {
+            tags.push(TAG_CROSS_TX);
+        }
+    }
+    if is_cross_pool(bundle.data.searcher_swaps()) {
+        tags.push(TAG_CROSS_POOL);
+    }
+    if bundle.header.merged_submission {
+        tags.push(TAG_MERGED_SUBMISSION);
+    }
+    if bundle.header.no_pricing_calculated {
+        tags.push(TAG_UNPRICED);
+    }
+
+    tags
+}
+
+fn is_cross_pool(swaps: Vec<&NormalizedSwap>) -> bool {
+    let mut pools_per_pair: FastHashMap<_, FastHashSet<_>> = FastHashMap::default();
+    swaps.into_iter().any(|swap| {
+        let pair = if swap.token_in.address < swap.token_out.address {
+            (swap.token_in.address, swap.token_out.address)
+        } else {
+            (swap.token_out.address, swap.token_in.address)
+        };
+        let pools = pools_per_pair.entry(pair).or_default();
+        pools.insert(swap.pool);
+        pools.len() > 1
+    })
+}
+
+/// Selects bundles by their tags
+#[derive(Debug, Default, Clone)]
+pub struct TagFilter {
+    /// Tags the bundle must all have
+    pub all:  Vec<String>,
+    /// Tags the bundle must not have
+    pub none: Vec<String>,
+}
+
+impl TagFilter {
+    pub fn is_empty(&self) -> bool {
+        self.all.is_empty() && self.none.is_empty()
+    }
+
+    pub fn matches<'a>(&self, tags: impl IntoIterator<Item = &'a str>) -> bool {
+        let tags = tags.into_iter().collect::<FastHashSet<_>>();
+        self.all.iter().all(|tag| tags.contains(tag.as_str()))
+            && !self.none.iter().any(|tag| tags.contains(tag.as_str()))
+    }
+
+    /// Drops the bundles of the block that don't match
+    pub fn apply(&self, block: &mut MevBlockWithClassified, tags: Option<&BlockBundleTags>) {
+        let empty = BlockBundleTags::default();
+        let tags = tags.unwrap_or(&empty);
+        block
+            .mev
+            .retain(|bundle| self.matches(tags.tags_of(bundle.header.tx_hash)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mev::{AtomicArb, BundleHeader};
+
+    #[test]
+    fn test_tags_and_filter() {
+        let tx_hash = B256::repeat_byte(1);
+        let mut tags = BlockBundleTags::default();
+        assert!(tags.add(BundleTag::manual(tx_hash, "whale".into(), "alice".into())));
+        assert!(!tags.add(BundleTag::manual(tx_hash, "whale".into(), "bob".into())));
+        tags.replace_detected(vec![BundleTag::detected(tx_hash, TAG_FLASHLOAN)]);
+        tags.replace_detected(vec![BundleTag::detected(tx_hash, TAG_CROSS_TX)]);
+
+        let mut found = tags.tags_of(tx_hash).collect::<Vec<_>>();
+        found.sort_unstable();
+        assert_eq!(found, vec![TAG_CROSS_TX, "whale"]);
+
+        let filter = TagFilter { all: vec!["whale".into()], none: vec![TAG_FLASHLOAN.into()] };
+        assert!(filter.matches(tags.tags_of(tx_hash)));
+        assert!(tags.remove(tx_hash, "whale"));
+        assert!(!filter.matches(tags.tags_of(tx_hash)));
+    }
+
+    #[test]
+    fn test_detect_cross_tx_arb() {
+        let bundle = Bundle {
+            header: BundleHeader { no_pricing_calculated: true, ..Default::default() },
+            data:   BundleData::AtomicArb(AtomicArb {
+                leg_tx_hashes: vec![B256::repeat_byte(2)],
+                ..Default::default()
+            }),
+        };
+
+        assert_eq!(detect_bundle_tags(&bundle), vec![TAG_CROSS_TX, TAG_UNPRICED]);
+    }
+}
@@ -0,0 +1,517 @@
+//! Frozen copy of the mev block layout stored before blocks were versioned.
+//!
+//! These rows carry no prefix and no version, so they can't be read with the
+//! current types: every field added since shifts the archived layout. The
+//! definitions here must never change, they are how the unprefixed rows are
+//! read and converted to the current layout.
+use redefined::RedefinedConvert;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+
+use crate::{
+    db::{
+        mev_block::MevBlockWithClassifiedRedefined,
+        redefined_types::{
+            malachite::RationalRedefined,
+            primitives::{AddressRedefined, B256Redefined},
+        },
+        searcher::Fund,
+        token_info::TokenInfoWithAddressRedefined,
+    },
+    mev::{
+        AddressBalanceDeltasRedefined, AtomicArbRedefined, AtomicArbType, Bundle,
+        BundleDataRedefined, BundleHeaderRedefined, BundleRedefined, CapitalUsageRedefined,
+        CexDexQuoteRedefined, CexDexRedefined, JitLiquidityRedefined,
+        JitLiquiditySandwichRedefined, LiquidationRedefined, MevBlockRedefined, MevCount, MevType,
+        PossibleMevCollectionRedefined, SandwichRedefined, SearcherTxRedefined,
+        TokenBalanceDeltaRedefined, TransactionAccountingRedefined, ValuationMethod,
+        ValueSplitRedefined, VictimActionType,
+    },
+    normalized_actions::{
+        NormalizedBurnRedefined, NormalizedMintRedefined, NormalizedSwapRedefined, FULL_CONFIDENCE,
+    },
+    GasDetails, Protocol,
+};
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+pub(super) struct MevBlockWithClassifiedV0 {
+    block: MevBlockV0,
+    mev:   Vec<BundleV0>,
+}
+
+impl From<MevBlockWithClassifiedV0> for MevBlockWithClassifiedRedefined {
+    fn from(v0: MevBlockWithClassifiedV0) -> Self {
+        Self { block: v0.block.into(), mev: v0.mev.into_iter().map(Into::into).collect() }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct MevBlockV0 {
+    block_hash:                  B256Redefined,
+    block_number:                u64,
+    mev_count:                   MevCount,
+    eth_price:                   f64,
+    total_gas_used:              u128,
+    total_priority_fee:          u128,
+    total_bribe:                 u128,
+    total_mev_bribe:             u128,
+    total_mev_priority_fee_paid: u128,
+    builder_address:             AddressRedefined,
+    builder_name:                Option<String>,
+    builder_eth_profit:          f64,
+    builder_profit_usd:          f64,
+    builder_mev_profit_usd:      f64,
+    builder_searcher_bribes:     u128,
+    builder_searcher_bribes_usd: f64,
+    builder_sponsorship_amount:  u128,
+    ultrasound_bid_adjusted:     bool,
+    proposer_fee_recipient:      Option<AddressRedefined>,
+    proposer_mev_reward:         Option<u128>,
+    proposer_profit_usd:         Option<f64>,
+    total_mev_profit_usd:        f64,
+    possible_mev:                PossibleMevCollectionRedefined,
+}
+
+/// The slot fields weren't recorded
+impl From<MevBlockV0> for MevBlockRedefined {
+    fn from(v0: MevBlockV0) -> Self {
+        Self {
+            block_hash: v0.block_hash,
+            block_number: v0.block_number,
+            mev_count: v0.mev_count,
+            eth_price: v0.eth_price,
+            total_gas_used: v0.total_gas_used,
+            total_priority_fee: v0.total_priority_fee,
+            total_bribe: v0.total_bribe,
+            total_mev_bribe: v0.total_mev_bribe,
+            total_mev_priority_fee_paid: v0.total_mev_priority_fee_paid,
+            builder_address: v0.builder_address,
+            builder_name: v0.builder_name,
+            builder_eth_profit: v0.builder_eth_profit,
+            builder_profit_usd: v0.builder_profit_usd,
+            builder_mev_profit_usd: v0.builder_mev_profit_usd,
+            builder_searcher_bribes: v0.builder_searcher_bribes,
+            builder_searcher_bribes_usd: v0.builder_searcher_bribes_usd,
+            builder_sponsorship_amount: v0.builder_sponsorship_amount,
+            ultrasound_bid_adjusted: v0.ultrasound_bid_adjusted,
+            proposer_fee_recipient: v0.proposer_fee_recipient,
+            proposer_mev_reward: v0.proposer_mev_reward,
+            proposer_profit_usd: v0.proposer_profit_usd,
+            slot: None,
+            proposer_index: None,
+            proposer_pubkey: None,
+            missed_slots: None,
+            pre_merge: false,
+            total_mev_profit_usd: v0.total_mev_profit_usd,
+            possible_mev: v0.possible_mev,
+            skipped_inspectors: vec![],
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct BundleV0 {
+    header: BundleHeaderV0,
+    data:   BundleDataV0,
+}
+
+/// The id wasn't stored, it's computed from the bundle's transactions the
+/// same way the composer does
+impl From<BundleV0> for BundleRedefined {
+    fn from(v0: BundleV0) -> Self {
+        let bundle = BundleRedefined { header: v0.header.into(), data: v0.data.into() };
+        let mut bundle: Bundle = bundle.to_source();
+        bundle.header.bundle_id = bundle.compute_bundle_id();
+
+        BundleRedefined::from_source(bundle)
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct BundleHeaderV0 {
+    block_number:          u64,
+    tx_index:              u64,
+    tx_hash:               B256Redefined,
+    eoa:                   AddressRedefined,
+    mev_contract:          Option<AddressRedefined>,
+    fund:                  Fund,
+    profit_usd:            f64,
+    bribe_usd:             f64,
+    mev_type:              MevType,
+    no_pricing_calculated: bool,
+    balance_deltas:        Vec<TransactionAccountingV0>,
+}
+
+impl From<BundleHeaderV0> for BundleHeaderRedefined {
+    fn from(v0: BundleHeaderV0) -> Self {
+        Self {
+            block_number:           v0.block_number,
+            tx_index:               v0.tx_index,
+            tx_hash:                v0.tx_hash,
+            bundle_id:              B256Redefined::default(),
+            eoa:                    v0.eoa,
+            mev_contract:           v0.mev_contract,
+            fund:                   v0.fund,
+            profit_usd:             v0.profit_usd,
+            bribe_usd:              v0.bribe_usd,
+            mev_type:               v0.mev_type,
+            no_pricing_calculated:  v0.no_pricing_calculated,
+            balance_deltas:         v0.balance_deltas.into_iter().map(Into::into).collect(),
+            min_action_confidence:  FULL_CONFIDENCE,
+            mean_action_confidence: FULL_CONFIDENCE,
+            merged_submission:      false,
+            co_bundled_txs:         vec![],
+            value_split:            ValueSplitRedefined::from_source(Default::default()),
+            capital:                CapitalUsageRedefined::from_source(Default::default()),
+            spam_profit_usd:        0.0,
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct TransactionAccountingV0 {
+    tx_hash:        B256Redefined,
+    address_deltas: Vec<AddressBalanceDeltasV0>,
+}
+
+impl From<TransactionAccountingV0> for TransactionAccountingRedefined {
+    fn from(v0: TransactionAccountingV0) -> Self {
+        Self {
+            tx_hash:        v0.tx_hash,
+            address_deltas: v0.address_deltas.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct AddressBalanceDeltasV0 {
+    address:      AddressRedefined,
+    name:         Option<String>,
+    token_deltas: Vec<TokenBalanceDeltaV0>,
+}
+
+impl From<AddressBalanceDeltasV0> for AddressBalanceDeltasRedefined {
+    fn from(v0: AddressBalanceDeltasV0) -> Self {
+        Self {
+            address:      v0.address,
+            name:         v0.name,
+            token_deltas: v0
+                .token_deltas
+                .into_iter()
+                .map(|delta| TokenBalanceDeltaRedefined {
+                    token:     delta.token,
+                    amount:    delta.amount,
+                    usd_value: delta.usd_value,
+                    valuation: ValuationMethod::Market,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct TokenBalanceDeltaV0 {
+    token:     TokenInfoWithAddressRedefined,
+    amount:    f64,
+    usd_value: f64,
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+enum BundleDataV0 {
+    Sandwich(SandwichV0),
+    AtomicArb(AtomicArbV0),
+    JitSandwich(JitLiquiditySandwichV0),
+    Jit(JitLiquidityV0),
+    CexDexQuote(CexDexQuoteRedefined),
+    CexDex(CexDexRedefined),
+    Liquidation(LiquidationRedefined),
+    Unknown(SearcherTxRedefined),
+}
+
+impl From<BundleDataV0> for BundleDataRedefined {
+    fn from(v0: BundleDataV0) -> Self {
+        match v0 {
+            BundleDataV0::Sandwich(d) => BundleDataRedefined::Sandwich(d.into()),
+            BundleDataV0::AtomicArb(d) => BundleDataRedefined::AtomicArb(d.into()),
+            BundleDataV0::JitSandwich(d) => BundleDataRedefined::JitSandwich(d.into()),
+            BundleDataV0::Jit(d) => BundleDataRedefined::Jit(d.into()),
+            BundleDataV0::CexDexQuote(d) => BundleDataRedefined::CexDexQuote(d),
+            BundleDataV0::CexDex(d) => BundleDataRedefined::CexDex(d),
+            BundleDataV0::Liquidation(d) => BundleDataRedefined::Liquidation(d),
+            BundleDataV0::Unknown(d) => BundleDataRedefined::Unknown(d),
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct SandwichV0 {
+    block_number:             u64,
+    frontrun_tx_hash:         Vec<B256Redefined>,
+    frontrun_swaps:           Vec<Vec<NormalizedSwapRedefined>>,
+    frontrun_gas_details:     Vec<GasDetails>,
+    victim_swaps_tx_hashes:   Vec<Vec<B256Redefined>>,
+    victim_swaps:             Vec<Vec<NormalizedSwapRedefined>>,
+    victim_swaps_gas_details: Vec<GasDetails>,
+    backrun_tx_hash:          B256Redefined,
+    backrun_swaps:            Vec<NormalizedSwapRedefined>,
+    backrun_gas_details:      GasDetails,
+}
+
+/// Victim execution wasn't measured and every victim was a swap
+impl From<SandwichV0> for SandwichRedefined {
+    fn from(v0: SandwichV0) -> Self {
+        let victim_txs = v0.victim_swaps.len();
+
+        Self {
+            block_number:             v0.block_number,
+            frontrun_tx_hash:         v0.frontrun_tx_hash,
+            frontrun_swaps:           v0.frontrun_swaps,
+            frontrun_gas_details:     v0.frontrun_gas_details,
+            victim_swaps_tx_hashes:   v0.victim_swaps_tx_hashes,
+            victim_swaps:             v0.victim_swaps,
+            victim_swaps_gas_details: v0.victim_swaps_gas_details,
+            backrun_tx_hash:          v0.backrun_tx_hash,
+            backrun_swaps:            v0.backrun_swaps,
+            backrun_gas_details:      v0.backrun_gas_details,
+            victim_execution:         vec![],
+            victim_liquidations:      vec![vec![]; victim_txs],
+            victim_mints:             vec![vec![]; victim_txs],
+            victim_action_types:      vec![VictimActionType::Swap; victim_txs],
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct AtomicArbV0 {
+    tx_hash:      B256Redefined,
+    trigger_tx:   B256Redefined,
+    block_number: u64,
+    swaps:        Vec<NormalizedSwapRedefined>,
+    gas_details:  GasDetails,
+    arb_type:     AtomicArbType,
+}
+
+/// The arb was always a single transaction
+impl From<AtomicArbV0> for AtomicArbRedefined {
+    fn from(v0: AtomicArbV0) -> Self {
+        Self {
+            tx_hash:         v0.tx_hash,
+            trigger_tx:      v0.trigger_tx,
+            block_number:    v0.block_number,
+            swaps:           v0.swaps,
+            gas_details:     v0.gas_details,
+            arb_type:        v0.arb_type,
+            leg_tx_hashes:   vec![],
+            leg_gas_details: vec![],
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct JitLiquiditySandwichV0 {
+    block_number:             u64,
+    frontrun_tx_hash:         Vec<B256Redefined>,
+    frontrun_swaps:           Vec<Vec<NormalizedSwapRedefined>>,
+    frontrun_mints:           Vec<Option<Vec<NormalizedMintV0>>>,
+    frontrun_gas_details:     Vec<GasDetails>,
+    victim_swaps_tx_hashes:   Vec<Vec<B256Redefined>>,
+    victim_swaps:             Vec<Vec<NormalizedSwapRedefined>>,
+    victim_swaps_gas_details: Vec<GasDetails>,
+    backrun_tx_hash:          B256Redefined,
+    backrun_swaps:            Vec<NormalizedSwapRedefined>,
+    backrun_burns:            Vec<NormalizedBurnV0>,
+    backrun_gas_details:      GasDetails,
+}
+
+impl From<JitLiquiditySandwichV0> for JitLiquiditySandwichRedefined {
+    fn from(v0: JitLiquiditySandwichV0) -> Self {
+        Self {
+            block_number:             v0.block_number,
+            frontrun_tx_hash:         v0.frontrun_tx_hash,
+            frontrun_swaps:           v0.frontrun_swaps,
+            frontrun_mints:           v0
+                .frontrun_mints
+                .into_iter()
+                .map(|mints| mints.map(|mints| mints.into_iter().map(Into::into).collect()))
+                .collect(),
+            frontrun_gas_details:     v0.frontrun_gas_details,
+            victim_swaps_tx_hashes:   v0.victim_swaps_tx_hashes,
+            victim_swaps:             v0.victim_swaps,
+            victim_swaps_gas_details: v0.victim_swaps_gas_details,
+            backrun_tx_hash:          v0.backrun_tx_hash,
+            backrun_swaps:            v0.backrun_swaps,
+            backrun_burns:            v0.backrun_burns.into_iter().map(Into::into).collect(),
+            backrun_gas_details:      v0.backrun_gas_details,
+            victim_execution:         vec![],
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct JitLiquidityV0 {
+    frontrun_mint_tx_hash: B256Redefined,
+    block_number: u64,
+    frontrun_mints: Vec<NormalizedMintV0>,
+    frontrun_mint_gas_details: GasDetails,
+    victim_swaps_tx_hashes: Vec<B256Redefined>,
+    victim_swaps: Vec<Vec<NormalizedSwapRedefined>>,
+    victim_swaps_gas_details_tx_hashes: Vec<B256Redefined>,
+    victim_swaps_gas_details: Vec<GasDetails>,
+    backrun_burn_tx_hash: B256Redefined,
+    backrun_burns: Vec<NormalizedBurnV0>,
+    backrun_burn_gas_details: GasDetails,
+}
+
+/// The position was always burnt in the block it was minted in
+impl From<JitLiquidityV0> for JitLiquidityRedefined {
+    fn from(v0: JitLiquidityV0) -> Self {
+        Self {
+            frontrun_mint_tx_hash: v0.frontrun_mint_tx_hash,
+            block_number: v0.block_number,
+            frontrun_mints: v0.frontrun_mints.into_iter().map(Into::into).collect(),
+            frontrun_mint_gas_details: v0.frontrun_mint_gas_details,
+            victim_swaps_tx_hashes: v0.victim_swaps_tx_hashes,
+            victim_swaps: v0.victim_swaps,
+            victim_swaps_gas_details_tx_hashes: v0.victim_swaps_gas_details_tx_hashes,
+            victim_swaps_gas_details: v0.victim_swaps_gas_details,
+            backrun_burn_tx_hash: v0.backrun_burn_tx_hash,
+            backrun_burns: v0.backrun_burns.into_iter().map(Into::into).collect(),
+            backrun_burn_gas_details: v0.backrun_burn_gas_details,
+            backrun_block_number: v0.block_number,
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct NormalizedMintV0 {
+    protocol:    Protocol,
+    trace_index: u64,
+    from:        AddressRedefined,
+    recipient:   AddressRedefined,
+    pool:        AddressRedefined,
+    token:       Vec<TokenInfoWithAddressRedefined>,
+    amount:      Vec<RationalRedefined>,
+}
+
+impl From<NormalizedMintV0> for NormalizedMintRedefined {
+    fn from(v0: NormalizedMintV0) -> Self {
+        Self {
+            protocol:    v0.protocol,
+            trace_index: v0.trace_index,
+            from:        v0.from,
+            recipient:   v0.recipient,
+            pool:        v0.pool,
+            token:       v0.token,
+            amount:      v0.amount,
+            position_id: None,
+        }
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct NormalizedBurnV0 {
+    protocol:    Protocol,
+    trace_index: u64,
+    from:        AddressRedefined,
+    recipient:   AddressRedefined,
+    pool:        AddressRedefined,
+    token:       Vec<TokenInfoWithAddressRedefined>,
+    amount:      Vec<RationalRedefined>,
+}
+
+impl From<NormalizedBurnV0> for NormalizedBurnRedefined {
+    fn from(v0: NormalizedBurnV0) -> Self {
+        Self {
+            protocol:    v0.protocol,
+            trace_index: v0.trace_index,
+            from:        v0.from,
+            recipient:   v0.recipient,
+            pool:        v0.pool,
+            token:       v0.token,
+            amount:      v0.amount,
+            position_id: None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// A block as written before blocks were versioned
+    pub(in super::super) fn baseline_block() -> MevBlockWithClassifiedV0 {
+        let frontrun = B256Redefined::from_source([1; 32].into());
+        let victim = B256Redefined::from_source([2; 32].into());
+        let backrun = B256Redefined::from_source([3; 32].into());
+
+        MevBlockWithClassifiedV0 {
+            block: MevBlockV0 {
+                block_hash:                  B256Redefined::default(),
+                block_number:                18_000_000,
+                mev_count:                   MevCount::default(),
+                eth_price:                   2_000.0,
+                total_gas_used:              0,
+                total_priority_fee:          0,
+                total_bribe:                 0,
+                total_mev_bribe:             0,
+                total_mev_priority_fee_paid: 0,
+                builder_address:             AddressRedefined::default(),
+                builder_name:                Some("beaver".to_string()),
+                builder_eth_profit:          0.0,
+                builder_profit_usd:          0.0,
+                builder_mev_profit_usd:      0.0,
+                builder_searcher_bribes:     0,
+                builder_searcher_bribes_usd: 0.0,
+                builder_sponsorship_amount:  0,
+                ultrasound_bid_adjusted:     false,
+                proposer_fee_recipient:      None,
+                proposer_mev_reward:         None,
+                proposer_profit_usd:         None,
+                total_mev_profit_usd:        12.5,
+                possible_mev:                PossibleMevCollectionRedefined::from_source(
+                    Default::default(),
+                ),
+            },
+            mev:   vec![BundleV0 {
+                header: BundleHeaderV0 {
+                    block_number:          18_000_000,
+                    tx_index:              4,
+                    tx_hash:               frontrun.clone(),
+                    eoa:                   AddressRedefined::default(),
+                    mev_contract:          None,
+                    fund:                  Fund::None,
+                    profit_usd:            12.5,
+                    bribe_usd:             1.0,
+                    mev_type:              MevType::Sandwich,
+                    no_pricing_calculated: false,
+                    balance_deltas:        vec![],
+                },
+                data:   BundleDataV0::Sandwich(SandwichV0 {
+                    block_number:             18_000_000,
+                    frontrun_tx_hash:         vec![frontrun],
+                    frontrun_swaps:           vec![vec![]],
+                    frontrun_gas_details:     vec![GasDetails::default()],
+                    victim_swaps_tx_hashes:   vec![vec![victim]],
+                    victim_swaps:             vec![vec![]],
+                    victim_swaps_gas_details: vec![GasDetails::default()],
+                    backrun_tx_hash:          backrun,
+                    backrun_swaps:            vec![],
+                    backrun_gas_details:      GasDetails::default(),
+                }),
+            }],
+        }
+    }
+}
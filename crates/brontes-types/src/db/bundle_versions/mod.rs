@@ -0,0 +1,427 @@
+//! Versioned layouts of the stored mev blocks.
+//!
+//! Mev blocks are stored in rkyv's layout, which changes whenever a field is
+//! added to one of the stored types, leaving rows written by older releases
+//! undecodable. The block, each bundle's header and each bundle's data are
+//! therefore stored in envelopes tagged with the version of their layout they
+//! were written with, the data's additionally with its [`BundleDataKind`].
+//! Bumping one version leaves every other layout untouched.
+//!
+//! To change a layout once it has been released, keep its previous definition
+//! frozen in this module, e.g. as `SandwichV1`, bump its version
+//! ([`MEV_BLOCK_VERSION`], [`BUNDLE_HEADER_VERSION`] or
+//! [`BundleDataKind::current_version`]) and decode envelopes of the previous
+//! version with the frozen layout, converting them to the current one.
+//!
+//! Rows are validated before they're read, a row that doesn't match its
+//! layout is an error rather than garbage. Blocks stored before the envelopes
+//! were introduced carry no prefix and are read through the frozen copy of
+//! that layout in [`baseline`]. `brontes db upgrade-bundles` rewrites every
+//! row in the current format.
+use std::collections::BTreeMap;
+
+use eyre::{eyre, WrapErr};
+use rkyv::{
+    bytecheck::CheckBytes, ser::serializers::AllocSerializer,
+    validation::validators::DefaultValidator, AlignedVec, Archive, Deserialize as rDeserialize,
+    Infallible, Serialize as rSerialize,
+};
+
+use crate::{
+    db::mev_block::MevBlockWithClassifiedRedefined,
+    mev::{
+        AtomicArbRedefined, BundleDataRedefined, BundleHeaderRedefined, BundleRedefined,
+        CexDexQuoteRedefined, CexDexRedefined, JitLiquidityRedefined,
+        JitLiquiditySandwichRedefined, LiquidationRedefined, MevBlockRedefined, SandwichRedefined,
+        SearcherTxRedefined,
+    },
+};
+
+mod baseline;
+
+/// Prefix of the mev blocks stored with versioned blocks, headers and data
+const VERSIONED_MAGIC: &[u8; 8] = b"BRNTSV01";
+
+/// Version of the stored layout of [`MevBlockRedefined`]
+pub const MEV_BLOCK_VERSION: u16 = 1;
+/// Version of the stored layout of [`BundleHeaderRedefined`]
+pub const BUNDLE_HEADER_VERSION: u16 = 1;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+pub enum BundleDataKind {
+    Sandwich,
+    AtomicArb,
+    JitSandwich,
+    Jit,
+    CexDexQuote,
+    CexDex,
+    Liquidation,
+    Unknown,
+}
+
+impl BundleDataKind {
+    pub const ALL: [BundleDataKind; 8] = [
+        BundleDataKind::Sandwich,
+        BundleDataKind::AtomicArb,
+        BundleDataKind::JitSandwich,
+        BundleDataKind::Jit,
+        BundleDataKind::CexDexQuote,
+        BundleDataKind::CexDex,
+        BundleDataKind::Liquidation,
+        BundleDataKind::Unknown,
+    ];
+
+    /// Version of the stored layout of the kind's data
+    pub const fn current_version(self) -> u16 {
+        match self {
+            BundleDataKind::Sandwich => 1,
+            BundleDataKind::AtomicArb => 1,
+            BundleDataKind::JitSandwich => 1,
+            BundleDataKind::Jit => 1,
+            BundleDataKind::CexDexQuote => 1,
+            BundleDataKind::CexDex => 1,
+            BundleDataKind::Liquidation => 1,
+            BundleDataKind::Unknown => 1,
+        }
+    }
+}
+
+/// The current version of each stored layout, by the layout's name. A db
+/// written with versions up to these is readable
+pub fn layout_versions() -> BTreeMap<String, u16> {
+    [
+        ("mev_block".to_string(), MEV_BLOCK_VERSION),
+        ("bundle_header".to_string(), BUNDLE_HEADER_VERSION),
+    ]
+    .into_iter()
+    .chain(
+        BundleDataKind::ALL
+            .into_iter()
+            .map(|kind| (format!("bundle_data.{kind:?}"), kind.current_version())),
+    )
+    .collect()
+}
+
+/// Fails on a version written by a newer release
+pub(crate) fn check_version(what: &str, version: u16, current: u16) -> eyre::Result<()> {
+    if version > current {
+        return Err(eyre!(
+            "{what} of version {version} was written by a newer release, this release reads up to \
+             version {current}"
+        ))
+    }
+
+    Ok(())
+}
+
+/// A layout tagged with the version it was written with
+#[derive(Debug, PartialEq, Clone, rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+pub struct Versioned {
+    pub version: u16,
+    pub payload: Vec<u8>,
+}
+
+impl Versioned {
+    fn block(block: &MevBlockRedefined) -> Self {
+        Self { version: MEV_BLOCK_VERSION, payload: encode(block) }
+    }
+
+    fn header(header: &BundleHeaderRedefined) -> Self {
+        Self { version: BUNDLE_HEADER_VERSION, payload: encode(header) }
+    }
+
+    fn into_block(self) -> eyre::Result<MevBlockRedefined> {
+        check_version("mev block", self.version, MEV_BLOCK_VERSION)?;
+        match self.version {
+            MEV_BLOCK_VERSION => decode::<MevBlockRedefined>(&self.payload),
+            version => Err(eyre!("no upcaster from version {version} of mev blocks")),
+        }
+    }
+
+    fn into_header(self) -> eyre::Result<BundleHeaderRedefined> {
+        check_version("bundle header", self.version, BUNDLE_HEADER_VERSION)?;
+        match self.version {
+            BUNDLE_HEADER_VERSION => decode::<BundleHeaderRedefined>(&self.payload),
+            version => Err(eyre!("no upcaster from version {version} of bundle headers")),
+        }
+    }
+}
+
+/// The data of a bundle in the layout of the version it was written with
+#[derive(Debug, PartialEq, Clone, rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+pub struct VersionedBundleData {
+    pub kind:    BundleDataKind,
+    pub version: u16,
+    pub payload: Vec<u8>,
+}
+
+impl VersionedBundleData {
+    pub fn new(data: &BundleDataRedefined) -> Self {
+        let (kind, payload) = match data {
+            BundleDataRedefined::Sandwich(d) => (BundleDataKind::Sandwich, encode(d)),
+            BundleDataRedefined::AtomicArb(d) => (BundleDataKind::AtomicArb, encode(d)),
+            BundleDataRedefined::JitSandwich(d) => (BundleDataKind::JitSandwich, encode(d)),
+            BundleDataRedefined::Jit(d) => (BundleDataKind::Jit, encode(d)),
+            BundleDataRedefined::CexDexQuote(d) => (BundleDataKind::CexDexQuote, encode(d)),
+            BundleDataRedefined::CexDex(d) => (BundleDataKind::CexDex, encode(d)),
+            BundleDataRedefined::Liquidation(d) => (BundleDataKind::Liquidation, encode(d)),
+            BundleDataRedefined::Unknown(d) => (BundleDataKind::Unknown, encode(d)),
+        };
+
+        Self { kind, version: kind.current_version(), payload }
+    }
+
+    /// Decodes the data in the current layout
+    pub fn into_current(self) -> eyre::Result<BundleDataRedefined> {
+        let current = self.kind.current_version();
+        check_version(&format!("{:?} data", self.kind), self.version, current)?;
+        if self.version != current {
+            return Err(eyre!("no upcaster from version {} of {:?} data", self.version, self.kind))
+        }
+
+        let payload = &self.payload;
+        Ok(match self.kind {
+            BundleDataKind::Sandwich => {
+                BundleDataRedefined::Sandwich(decode::<SandwichRedefined>(payload)?)
+            }
+            BundleDataKind::AtomicArb => {
+                BundleDataRedefined::AtomicArb(decode::<AtomicArbRedefined>(payload)?)
+            }
+            BundleDataKind::JitSandwich => {
+                BundleDataRedefined::JitSandwich(decode::<JitLiquiditySandwichRedefined>(payload)?)
+            }
+            BundleDataKind::Jit => {
+                BundleDataRedefined::Jit(decode::<JitLiquidityRedefined>(payload)?)
+            }
+            BundleDataKind::CexDexQuote => {
+                BundleDataRedefined::CexDexQuote(decode::<CexDexQuoteRedefined>(payload)?)
+            }
+            BundleDataKind::CexDex => {
+                BundleDataRedefined::CexDex(decode::<CexDexRedefined>(payload)?)
+            }
+            BundleDataKind::Liquidation => {
+                BundleDataRedefined::Liquidation(decode::<LiquidationRedefined>(payload)?)
+            }
+            BundleDataKind::Unknown => {
+                BundleDataRedefined::Unknown(decode::<SearcherTxRedefined>(payload)?)
+            }
+        })
+    }
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct VersionedBundle {
+    header: Versioned,
+    data:   VersionedBundleData,
+}
+
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+struct VersionedMevBlock {
+    block: Versioned,
+    mev:   Vec<VersionedBundle>,
+}
+
+/// Encodes the block, its headers and their data each in a versioned envelope
+pub fn encode_mev_block(value: &MevBlockWithClassifiedRedefined) -> Vec<u8> {
+    let versioned = VersionedMevBlock {
+        block: Versioned::block(&value.block),
+        mev:   value
+            .mev
+            .iter()
+            .map(|bundle| VersionedBundle {
+                header: Versioned::header(&bundle.header),
+                data:   VersionedBundleData::new(&bundle.data),
+            })
+            .collect(),
+    };
+
+    let mut out = VERSIONED_MAGIC.to_vec();
+    out.extend(encode(&versioned));
+    out
+}
+
+/// Decodes a block written by [`encode_mev_block`] or by an older release,
+/// upcasting it to the current layout
+pub fn decode_mev_block(buf: &[u8]) -> eyre::Result<MevBlockWithClassifiedRedefined> {
+    if let Some(buf) = buf.strip_prefix(VERSIONED_MAGIC) {
+        let versioned = decode::<VersionedMevBlock>(buf)?;
+        return Ok(MevBlockWithClassifiedRedefined {
+            block: versioned.block.into_block()?,
+            mev:   versioned
+                .mev
+                .into_iter()
+                .map(|bundle| {
+                    Ok(BundleRedefined {
+                        header: bundle.header.into_header()?,
+                        data:   bundle.data.into_current()?,
+                    })
+                })
+                .collect::<eyre::Result<_>>()?,
+        })
+    }
+
+    decode::<baseline::MevBlockWithClassifiedV0>(buf)
+        .map(Into::into)
+        .wrap_err("unversioned mev block doesn't match the baseline layout")
+}
+
+/// The versions of the layouts a stored block was written with, keyed like
+/// [`layout_versions`]. Blocks stored before the envelopes were introduced
+/// report version 0 for their block and headers
+pub fn row_layout_versions(buf: &[u8]) -> eyre::Result<BTreeMap<String, u16>> {
+    let mut versions = BTreeMap::new();
+    let mut record = |layout: String, version: u16| {
+        let entry = versions.entry(layout).or_insert(version);
+        *entry = (*entry).max(version);
+    };
+
+    if let Some(buf) = buf.strip_prefix(VERSIONED_MAGIC) {
+        let versioned = decode::<VersionedMevBlock>(buf)?;
+        record("mev_block".to_string(), versioned.block.version);
+        for bundle in versioned.mev {
+            record("bundle_header".to_string(), bundle.header.version);
+            record(format!("bundle_data.{:?}", bundle.data.kind), bundle.data.version);
+        }
+    } else {
+        record("mev_block".to_string(), 0);
+        record("bundle_header".to_string(), 0);
+    }
+
+    Ok(versions)
+}
+
+pub(crate) fn encode<T: rSerialize<AllocSerializer<256>>>(value: &T) -> Vec<u8> {
+    rkyv::to_bytes::<_, 256>(value).unwrap().into_vec()
+}
+
+/// Validates the payload against the layout of `T` before reading it
+pub(crate) fn decode<T>(payload: &[u8]) -> eyre::Result<T>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<DefaultValidator<'a>> + rDeserialize<T, Infallible>,
+{
+    // the payload sits at an arbitrary offset of the row, rkyv needs it aligned
+    let mut aligned = AlignedVec::with_capacity(payload.len());
+    aligned.extend_from_slice(payload);
+    let archived = rkyv::check_archived_root::<T>(&aligned)
+        .map_err(|e| eyre!("invalid {}: {e}", std::any::type_name::<T>()))?;
+
+    Ok(archived.deserialize(&mut Infallible).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use redefined::RedefinedConvert;
+
+    use super::*;
+    use crate::{
+        db::mev_block::MevBlockWithClassified,
+        mev::{
+            Bundle, BundleData, BundleHeader, JitLiquidity, MevType, Sandwich, VictimActionType,
+        },
+        normalized_actions::FULL_CONFIDENCE,
+    };
+
+    fn block() -> MevBlockWithClassifiedRedefined {
+        let mut block = MevBlockWithClassified::default();
+        block.block.block_number = 18_000_000;
+        block.mev.push(Bundle {
+            header: BundleHeader {
+                block_number: 18_000_000,
+                mev_type: MevType::Sandwich,
+                profit_usd: 12.5,
+                ..Default::default()
+            },
+            data:   BundleData::Sandwich(Sandwich {
+                block_number: 18_000_000,
+                ..Default::default()
+            }),
+        });
+        block
+            .mev
+            .push(Bundle { header: BundleHeader::default(), data: BundleData::default() });
+
+        MevBlockWithClassifiedRedefined::from_source(block)
+    }
+
+    #[test]
+    fn test_versioned_mev_block_round_trip() {
+        let block = block();
+        let encoded = encode_mev_block(&block);
+        assert!(encoded.starts_with(VERSIONED_MAGIC));
+        assert_eq!(decode_mev_block(&encoded).unwrap(), block);
+    }
+
+    #[test]
+    fn test_rejects_invalid_rows() {
+        assert!(decode_mev_block(&[0xff; 64]).is_err());
+
+        let mut prefixed = VERSIONED_MAGIC.to_vec();
+        prefixed.extend([0xff; 64]);
+        assert!(decode_mev_block(&prefixed).is_err());
+
+        let mut header = Versioned::header(&block().mev[0].header);
+        header.version = BUNDLE_HEADER_VERSION + 1;
+        assert!(header.into_header().is_err());
+    }
+
+    #[test]
+    fn test_decodes_baseline_rows() {
+        let row = encode(&baseline::tests::baseline_block());
+        let block = decode_mev_block(&row).unwrap();
+
+        assert_eq!(block.block.block_number, 18_000_000);
+        assert_eq!(block.block.builder_name.as_deref(), Some("beaver"));
+        assert_eq!(block.block.slot, None);
+        assert_eq!(block.mev.len(), 1);
+
+        let bundle: Bundle = block.mev[0].clone().to_source();
+        assert_eq!(bundle.header.profit_usd, 12.5);
+        assert_eq!(bundle.header.min_action_confidence, FULL_CONFIDENCE);
+        assert_eq!(bundle.header.bundle_id, bundle.compute_bundle_id());
+        let BundleData::Sandwich(sandwich) = bundle.data else { panic!("expected sandwich data") };
+        assert_eq!(sandwich.victim_action_types, vec![VictimActionType::Swap]);
+        assert_eq!(sandwich.backrun_tx_hash, [3; 32].into());
+    }
+
+    #[test]
+    fn test_row_layout_versions() {
+        let versions = row_layout_versions(&encode_mev_block(&block())).unwrap();
+        assert_eq!(versions["mev_block"], MEV_BLOCK_VERSION);
+        assert_eq!(versions["bundle_header"], BUNDLE_HEADER_VERSION);
+        assert_eq!(versions["bundle_data.Sandwich"], BundleDataKind::Sandwich.current_version());
+        assert_eq!(versions.len(), 3);
+
+        let baseline = row_layout_versions(&encode(&baseline::tests::baseline_block())).unwrap();
+        assert_eq!(baseline["mev_block"], 0);
+    }
+
+    #[test]
+    fn test_rejects_newer_versions() {
+        let block = block();
+        let mut data = VersionedBundleData::new(&block.mev[0].data);
+        assert_eq!(data.kind, BundleDataKind::Sandwich);
+        assert_eq!(data.clone().into_current().unwrap(), block.mev[0].data);
+
+        data.version = BundleDataKind::Sandwich.current_version() + 1;
+        assert!(data.into_current().is_err());
+    }
+
+    #[test]
+    fn test_jit_held_across_blocks_round_trips() {
+        let jit = JitLiquidityRedefined::from_source(JitLiquidity {
+            block_number: 18_000_000,
+            backrun_block_number: 18_000_002,
+            ..Default::default()
+        });
+        let data = VersionedBundleData::new(&BundleDataRedefined::Jit(jit.clone()));
+        assert_eq!(data.kind, BundleDataKind::Jit);
+        assert_eq!(data.version, 1);
+        assert_eq!(data.into_current().unwrap(), BundleDataRedefined::Jit(jit));
+    }
+}
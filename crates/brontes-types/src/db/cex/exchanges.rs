@@ -23,6 +23,7 @@ use crate::constants::*;
     rkyv::Deserialize,
     rkyv::Archive,
 )]
+#[archive(check_bytes)]
 #[archive_attr(derive(Eq, PartialEq, Hash))]
 pub enum CexExchange {
     Binance,
@@ -54,6 +54,7 @@ pub struct CexPriceMap {
 #[derive(
     Debug, PartialEq, Clone, serde::Serialize, rSerialize, rDeserialize, Archive, Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(CexPriceMap)]
 #[redefined_attr(
     to_source = "CexPriceMap {
@@ -680,6 +681,7 @@ impl<'de> serde::Deserialize<'de> for CexPriceMap {
     rDeserialize,
     Archive
 ))]
+#[redefined_attr(archive(check_bytes))]
 pub struct FeeAdjustedQuote {
     #[redefined(same_fields)]
     pub exchange:    CexExchange,
@@ -1,7 +1,9 @@
 mod cex_quotes;
 mod download;
+mod sanity;
 mod types;
 
 pub use cex_quotes::*;
 pub use download::*;
+pub use sanity::*;
 pub use types::*;
@@ -0,0 +1,192 @@
+//! Cross-exchange sanity checks of cex quotes.
+//!
+//! A fat-finger tick or a stale feed on a single exchange is enough to produce
+//! an absurd cex-dex profit. Before a block is inspected its quotes are checked
+//! against each other: quotes without a price are dropped and, when enough
+//! exchanges quote a pair, so are quotes whose mid price is too far from the
+//! median across exchanges. The pairs that lost quotes are recorded in a
+//! [`QuoteSanityReport`] on the block's metadata, so bundles priced from them
+//! can be marked.
+use malachite::{num::basic::traits::Zero, Rational};
+use serde::{Deserialize, Serialize};
+
+use super::{CexPriceMap, CexQuote};
+use crate::{pair::Pair, utils::ToFloatNearest, FastHashMap, FastHashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuoteSanityConfig {
+    /// Largest relative deviation of a quote's mid price from the median mid
+    /// price of the pair across exchanges
+    pub max_deviation: f64,
+    /// Exchanges that need to quote a pair for its quotes to be cross-checked.
+    /// With fewer there is no majority to tell the bad quote apart
+    pub min_exchanges: usize,
+}
+
+impl Default for QuoteSanityConfig {
+    fn default() -> Self {
+        Self { max_deviation: 0.05, min_exchanges: 3 }
+    }
+}
+
+impl QuoteSanityConfig {
+    pub fn validate(&self) -> eyre::Result<()> {
+        if !(self.max_deviation.is_finite() && self.max_deviation > 0.0) {
+            return Err(eyre::eyre!(
+                "max quote deviation must be a positive fraction, got {}",
+                self.max_deviation
+            ))
+        }
+        // the median of two quotes can't tell which one is off
+        if self.min_exchanges < 3 {
+            return Err(eyre::eyre!(
+                "quotes need to be cross-checked across at least 3 exchanges, got {}",
+                self.min_exchanges
+            ))
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct QuoteSanityReport {
+    /// Ordered pairs that had quotes rejected
+    pub flagged_pairs:   FastHashSet<Pair>,
+    pub rejected_quotes: usize,
+}
+
+impl QuoteSanityReport {
+    pub fn is_empty(&self) -> bool {
+        self.rejected_quotes == 0
+    }
+
+    /// Whether quotes of the pair, in either direction, were rejected
+    pub fn is_flagged(&self, pair: &Pair) -> bool {
+        self.flagged_pairs.contains(&pair.ordered())
+    }
+
+    fn reject(&mut self, pair: &Pair, count: usize) {
+        if count == 0 {
+            return
+        }
+        self.rejected_quotes += count;
+        self.flagged_pairs.insert(pair.ordered());
+    }
+}
+
+impl CexPriceMap {
+    /// Drops the quotes that have no price or disagree with the other
+    /// exchanges
+    pub fn sanitize(&mut self, config: &QuoteSanityConfig) -> QuoteSanityReport {
+        let mut report = QuoteSanityReport::default();
+
+        for pairs in self.quotes.values_mut() {
+            for (pair, quotes) in pairs.iter_mut() {
+                let before = quotes.len();
+                quotes.retain(|quote| {
+                    quote.price.0 > Rational::ZERO && quote.price.1 > Rational::ZERO
+                });
+                report.reject(pair, before - quotes.len());
+            }
+        }
+
+        // the median mid price of each exchange is its vote for the pair, so a
+        // single bad tick doesn't move the reference
+        let mut exchange_mids: FastHashMap<Pair, Vec<f64>> = FastHashMap::default();
+        for pairs in self.quotes.values() {
+            for (pair, quotes) in pairs {
+                if let Some(mid) = median(quotes.iter().map(|q| ordered_mid(pair, q)).collect()) {
+                    exchange_mids.entry(pair.ordered()).or_default().push(mid);
+                }
+            }
+        }
+        let references = exchange_mids
+            .into_iter()
+            .filter(|(_, mids)| mids.len() >= config.min_exchanges)
+            .filter_map(|(pair, mids)| Some((pair, median(mids)?)))
+            .collect::<FastHashMap<_, _>>();
+
+        for pairs in self.quotes.values_mut() {
+            for (pair, quotes) in pairs.iter_mut() {
+                let Some(reference) = references.get(&pair.ordered()) else { continue };
+                let before = quotes.len();
+                quotes.retain(|quote| {
+                    ((ordered_mid(pair, quote) - reference) / reference).abs()
+                        <= config.max_deviation
+                });
+                report.reject(pair, before - quotes.len());
+            }
+            pairs.retain(|_, quotes| !quotes.is_empty());
+        }
+
+        report
+    }
+}
+
+/// Mid price of the quote in the direction of the ordered pair
+fn ordered_mid(pair: &Pair, quote: &CexQuote) -> f64 {
+    let mid = quote.avg().to_float();
+    if pair.ordered() == *pair {
+        mid
+    } else {
+        1.0 / mid
+    }
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+
+    Some(if values.len() % 2 == 0 { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Address;
+
+    use super::*;
+    use crate::db::cex::CexExchange;
+
+    fn quote(exchange: CexExchange, price: u64) -> CexQuote {
+        CexQuote {
+            exchange,
+            timestamp: 0,
+            price: (Rational::from(price), Rational::from(price)),
+            amount: (Rational::from(1), Rational::from(1)),
+        }
+    }
+
+    #[test]
+    fn test_rejects_outlier_quotes() {
+        let pair = Pair(Address::repeat_byte(1), Address::repeat_byte(2));
+        let mut map = CexPriceMap::new();
+        for (exchange, prices) in [
+            (CexExchange::Binance, vec![100, 101]),
+            (CexExchange::Coinbase, vec![100, 0]),
+            (CexExchange::Okex, vec![99, 1_000]),
+        ] {
+            map.quotes.entry(exchange).or_default().insert(
+                pair,
+                prices
+                    .into_iter()
+                    .map(|price| quote(exchange, price))
+                    .collect(),
+            );
+        }
+
+        let report = map.sanitize(&QuoteSanityConfig::default());
+        assert_eq!(report.rejected_quotes, 2);
+        assert!(report.is_flagged(&pair.flip()));
+        assert_eq!(map.quotes[&CexExchange::Okex][&pair].len(), 1);
+        assert_eq!(map.quotes[&CexExchange::Coinbase][&pair].len(), 1);
+
+        // too few exchanges to cross-check
+        let report = map.sanitize(&QuoteSanityConfig { min_exchanges: 4, ..Default::default() });
+        assert!(report.is_empty());
+    }
+}
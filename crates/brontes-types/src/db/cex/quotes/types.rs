@@ -29,6 +29,7 @@ use crate::{
     rDeserialize,
     Archive
 ))]
+#[redefined_attr(archive(check_bytes))]
 pub struct CexQuote {
     #[redefined(same_fields)]
     pub exchange:  CexExchange,
@@ -125,6 +125,7 @@ impl<'de> Deserialize<'de> for CexTradeMap {
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive, Redefined)]
+#[archive(check_bytes)]
 #[redefined(CexTradeMap)]
 #[redefined_attr(
     to_source = "CexTradeMap::from_redefined(self.map)",
@@ -172,6 +173,7 @@ implement_table_value_codecs_with_zc!(CexTradeMapRedefined);
     rDeserialize,
     Archive
 ))]
+#[redefined_attr(archive(check_bytes))]
 pub struct CexTrades {
     #[redefined(same_fields)]
     pub exchange:  CexExchange,
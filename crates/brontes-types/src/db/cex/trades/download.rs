@@ -39,6 +39,7 @@ pub struct RawCexTrades {
     rkyv::Archive,
     Default,
 )]
+#[archive(check_bytes)]
 pub enum TradeType {
     Maker,
     #[default]
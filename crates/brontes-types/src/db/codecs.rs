@@ -11,9 +11,13 @@ macro_rules! implement_table_value_codecs_with_zc {
 
         impl alloy_rlp::Decodable for $table_value {
             fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+                // the row is validated against the layout before it's read, so a row
+                // written with another layout fails to decode instead of being misread
+                let mut aligned = rkyv::AlignedVec::with_capacity(buf.len());
+                aligned.extend_from_slice(buf);
                 let archived: &paste::paste!([<Archived $table_value>]) =
-                unsafe { rkyv::archived_root::<Self>(&buf[..]) };
-
+                    rkyv::check_archived_root::<Self>(&aligned)
+                        .map_err(|_| alloy_rlp::Error::Custom("row doesn't match the layout"))?;
 
                 let this = rkyv::Deserialize::deserialize(archived, &mut rkyv::Infallible).unwrap();
 
@@ -37,7 +41,8 @@ macro_rules! implement_table_value_codecs_with_zc {
             fn decompress<B: AsRef<[u8]>>(value: B) -> Result<Self, reth_db::DatabaseError> {
                 let binding = value.as_ref().to_vec();
 
-                let encoded_decompressed = zstd::decode_all(&*binding).unwrap();
+                let encoded_decompressed =
+                    zstd::decode_all(&*binding).map_err(|_| reth_db::DatabaseError::Decode)?;
                 let buf = &mut encoded_decompressed.as_slice();
 
                 alloy_rlp::Decodable::decode(buf).map_err(|_| reth_db::DatabaseError::Decode)
@@ -0,0 +1,31 @@
+//! Stubs of block ranges whose heavy data has been moved to cold storage.
+//!
+//! Traces and cex data make up most of the size of the db but are only read
+//! again when a block is re-inspected. Ranges older than a configured number of
+//! blocks are moved to an object store as a libmdbx segment, leaving a
+//! [`ColdSegment`] behind that says where the range's data can be fetched from.
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct ColdSegment {
+    /// Exclusive end of the segment's block range, the start is the table key
+    pub end_block: u64,
+    /// Name of the segment's tarball on the remote
+    pub object:    String,
+    /// Size of the tarball in bytes
+    pub bytes:     u64,
+}
+
+implement_table_value_codecs_with_zc!(ColdSegmentRedefined);
+
+impl ColdSegment {
+    pub fn contains(&self, start_block: u64, block: u64) -> bool {
+        (start_block..self.end_block).contains(&block)
+    }
+}
@@ -0,0 +1,35 @@
+//! Audit trail of the reloads of the live config, see
+//! [`LiveConfig`](crate::live_config::LiveConfig).
+//!
+//! Every reload is stored under a sequence number the writer assigns, whether
+//! it was applied or rejected, so a change in the results can be traced back
+//! to the settings that were changed.
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct ConfigReload {
+    /// Unix time in milliseconds the reload was made at
+    pub reloaded_at: u64,
+    /// What triggered the reload, `sighup` or `api`
+    pub trigger:     String,
+    /// File the config was read from
+    pub path:        String,
+    /// Settings the reload changed, as `setting: old -> new`
+    pub changes:     Vec<String>,
+    /// Why the config was rejected, the active config was kept
+    pub error:       Option<String>,
+}
+
+implement_table_value_codecs_with_zc!(ConfigReloadRedefined);
+
+impl ConfigReload {
+    pub fn applied(&self) -> bool {
+        self.error.is_none()
+    }
+}
@@ -44,6 +44,7 @@ use crate::{
     rSerialize,
     Archive
 ))]
+#[redefined_attr(archive(check_bytes))]
 pub struct DexPrices {
     pub pre_state:    Rational,
     pub post_state:   Rational,
@@ -304,6 +305,7 @@ impl From<DexQuoteWithIndex> for DexQuote {
     rSerialize,
     Archive
 ))]
+#[redefined_attr(archive(check_bytes))]
 pub struct DexQuoteWithIndex {
     pub tx_idx: u16,
     pub quote:  Vec<(Pair, DexPrices)>,
@@ -0,0 +1,239 @@
+//! Redaction of exported mev data.
+//!
+//! Deployments with legal constraints can't serve or export data of some
+//! addresses, e.g. sanctioned ones. An [`ExportFilter`] drops the bundles an
+//! excluded address took part in and replaces hashed addresses with a salted
+//! hash. It is applied to the mev blocks right before they leave brontes, in
+//! the json and parquet exports and the clickhouse inserts, so the stored
+//! libmdbx data stays complete.
+use std::path::Path;
+
+use alloy_primitives::{keccak256, Address, B256};
+
+use crate::{
+    db::mev_block::MevBlockWithClassified,
+    mev::{Bundle, BundleData, MevBlock},
+    normalized_actions::{NormalizedBurn, NormalizedMint, NormalizedSwap},
+    FastHashSet,
+};
+
+/// Salted keccak hash of the address, stable for a given salt so activity of
+/// the same address can still be linked
+pub fn hash_address(salt: B256, address: Address) -> Address {
+    let mut preimage = [0u8; 52];
+    preimage[..32].copy_from_slice(salt.as_slice());
+    preimage[32..].copy_from_slice(address.as_slice());
+
+    Address::from_slice(&keccak256(preimage)[12..])
+}
+
+/// Calls `f` with every searcher, victim and counterparty address of the
+/// bundle. Pool and token addresses are public protocol data and are skipped
+pub fn for_each_participant(bundle: &mut Bundle, mut f: impl FnMut(&mut Address)) {
+    let f = &mut f;
+    f(&mut bundle.header.eoa);
+    if let Some(contract) = bundle.header.mev_contract.as_mut() {
+        f(contract);
+    }
+    bundle
+        .header
+        .balance_deltas
+        .iter_mut()
+        .flat_map(|tx| tx.address_deltas.iter_mut())
+        .for_each(|deltas| f(&mut deltas.address));
+
+    match &mut bundle.data {
+        BundleData::Sandwich(s) => {
+            swaps(s.frontrun_swaps.iter_mut().flatten(), f);
+            swaps(s.victim_swaps.iter_mut().flatten(), f);
+            swaps(&mut s.backrun_swaps, f);
+        }
+        BundleData::AtomicArb(a) => swaps(&mut a.swaps, f),
+        BundleData::JitSandwich(j) => {
+            swaps(j.frontrun_swaps.iter_mut().flatten(), f);
+            mints(j.frontrun_mints.iter_mut().flatten().flatten(), f);
+            swaps(j.victim_swaps.iter_mut().flatten(), f);
+            swaps(&mut j.backrun_swaps, f);
+            burns(&mut j.backrun_burns, f);
+        }
+        BundleData::Jit(j) => {
+            mints(&mut j.frontrun_mints, f);
+            swaps(j.victim_swaps.iter_mut().flatten(), f);
+            burns(&mut j.backrun_burns, f);
+        }
+        BundleData::CexDexQuote(c) => swaps(&mut c.swaps, f),
+        BundleData::CexDex(c) => swaps(&mut c.swaps, f),
+        BundleData::Liquidation(l) => {
+            swaps(&mut l.liquidation_swaps, f);
+            l.liquidations.iter_mut().for_each(|liq| {
+                f(&mut liq.liquidator);
+                f(&mut liq.debtor);
+            });
+        }
+        BundleData::Unknown(s) => s.transfers.iter_mut().for_each(|transfer| {
+            f(&mut transfer.from);
+            f(&mut transfer.to);
+        }),
+    }
+}
+
+fn swaps<'a>(
+    swaps: impl IntoIterator<Item = &'a mut NormalizedSwap>,
+    f: &mut impl FnMut(&mut Address),
+) {
+    swaps.into_iter().for_each(|swap| {
+        f(&mut swap.from);
+        f(&mut swap.recipient);
+    });
+}
+
+fn mints<'a>(
+    mints: impl IntoIterator<Item = &'a mut NormalizedMint>,
+    f: &mut impl FnMut(&mut Address),
+) {
+    mints.into_iter().for_each(|mint| {
+        f(&mut mint.from);
+        f(&mut mint.recipient);
+    });
+}
+
+fn burns<'a>(
+    burns: impl IntoIterator<Item = &'a mut NormalizedBurn>,
+    f: &mut impl FnMut(&mut Address),
+) {
+    burns.into_iter().for_each(|burn| {
+        f(&mut burn.from);
+        f(&mut burn.recipient);
+    });
+}
+
+/// Loads a list of addresses from a file with one address per line. Empty
+/// lines and lines starting with `#` are skipped
+pub fn load_address_list(path: impl AsRef<Path>) -> eyre::Result<FastHashSet<Address>> {
+    let path = path.as_ref();
+    let file = std::fs::read_to_string(path)
+        .map_err(|e| eyre::eyre!("failed to read address list {}: {e}", path.display()))?;
+
+    file.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.parse::<Address>()
+                .map_err(|e| eyre::eyre!("invalid address {line} in {}: {e}", path.display()))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    /// Bundles any of these addresses took part in are dropped
+    excluded: FastHashSet<Address>,
+    /// These addresses are replaced with their salted hash
+    hashed:   FastHashSet<Address>,
+    salt:     B256,
+}
+
+impl ExportFilter {
+    pub fn new(excluded: FastHashSet<Address>, hashed: FastHashSet<Address>, salt: B256) -> Self {
+        Self { excluded, hashed, salt }
+    }
+
+    pub fn from_files(
+        excluded: Option<&Path>,
+        hashed: Option<&Path>,
+        salt: B256,
+    ) -> eyre::Result<Self> {
+        Ok(Self::new(
+            excluded
+                .map(load_address_list)
+                .transpose()?
+                .unwrap_or_default(),
+            hashed
+                .map(load_address_list)
+                .transpose()?
+                .unwrap_or_default(),
+            salt,
+        ))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.excluded.is_empty() && self.hashed.is_empty()
+    }
+
+    pub fn apply(&self, mev_block: &mut MevBlockWithClassified) {
+        self.apply_parts(&mut mev_block.block, &mut mev_block.mev);
+    }
+
+    /// Applies the filter to a block and its bundles held separately
+    pub fn apply_parts(&self, block: &mut MevBlock, mev: &mut Vec<Bundle>) {
+        if self.is_empty() {
+            return
+        }
+
+        if !self.excluded.is_empty() {
+            mev.retain_mut(|bundle| {
+                let mut excluded = false;
+                for_each_participant(bundle, |address| excluded |= self.excluded.contains(address));
+                !excluded
+            });
+        }
+
+        if self.hashed.is_empty() {
+            return
+        }
+
+        self.hash(&mut block.builder_address);
+        if let Some(recipient) = block.proposer_fee_recipient.as_mut() {
+            self.hash(recipient);
+        }
+
+        for bundle in mev.iter_mut() {
+            // names are labels of the address, so would defeat the hashing
+            bundle
+                .header
+                .balance_deltas
+                .iter_mut()
+                .flat_map(|tx| tx.address_deltas.iter_mut())
+                .filter(|deltas| self.hashed.contains(&deltas.address))
+                .for_each(|deltas| deltas.name = None);
+            for_each_participant(bundle, |address| self.hash(address));
+        }
+    }
+
+    fn hash(&self, address: &mut Address) {
+        if self.hashed.contains(address) {
+            *address = hash_address(self.salt, *address);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::bundle;
+
+    #[test]
+    fn test_export_filter() {
+        let sanctioned = Address::repeat_byte(1);
+        let private = Address::repeat_byte(2);
+        let other = Address::repeat_byte(3);
+        let salt = B256::repeat_byte(9);
+
+        let filter = ExportFilter::new(
+            FastHashSet::from_iter([sanctioned]),
+            FastHashSet::from_iter([private]),
+            salt,
+        );
+        let mut block = MevBlockWithClassified {
+            block: MevBlock { builder_address: private, ..Default::default() },
+            mev:   [sanctioned, private, other]
+                .map(|eoa| bundle().eoa(eoa).build())
+                .to_vec(),
+        };
+        filter.apply(&mut block);
+
+        let eoas = block.mev.iter().map(|b| b.header.eoa).collect::<Vec<_>>();
+        assert_eq!(eoas, vec![hash_address(salt, private), other]);
+        assert_eq!(block.block.builder_address, hash_address(salt, private));
+    }
+}
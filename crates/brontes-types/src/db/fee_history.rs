@@ -0,0 +1,80 @@
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    implement_table_value_codecs_with_zc, normalized_actions::NormalizedAction, tree::BlockTree,
+};
+
+/// Compact fee summary of a processed block. All fees are in wei per gas.
+#[derive(Debug, Default, PartialEq, Clone, Copy, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockFees {
+    pub base_fee:            u128,
+    pub avg_priority_fee:    u128,
+    pub median_priority_fee: u128,
+    pub gas_used:            u64,
+    pub gas_limit:           u64,
+}
+
+impl BlockFees {
+    pub fn from_tree<V: NormalizedAction>(tree: &BlockTree<V>) -> Self {
+        let mut priority_fees = tree
+            .tx_roots
+            .iter()
+            .map(|root| root.gas_details.priority_fee)
+            .collect::<Vec<_>>();
+        priority_fees.sort_unstable();
+
+        Self {
+            base_fee:            tree.header.base_fee_per_gas.unwrap_or_default() as u128,
+            avg_priority_fee:    average(&priority_fees),
+            median_priority_fee: median(&priority_fees),
+            gas_used:            tree.header.gas_used,
+            gas_limit:           tree.header.gas_limit,
+        }
+    }
+
+    pub fn gas_used_ratio(&self) -> f64 {
+        if self.gas_limit == 0 {
+            return 0.0
+        }
+        self.gas_used as f64 / self.gas_limit as f64
+    }
+}
+
+fn average(values: &[u128]) -> u128 {
+    if values.is_empty() {
+        return 0
+    }
+    values.iter().sum::<u128>() / values.len() as u128
+}
+
+/// Expects the values to be sorted.
+fn median(values: &[u128]) -> u128 {
+    let len = values.len();
+    match len {
+        0 => 0,
+        _ if len % 2 == 0 => (values[len / 2 - 1] + values[len / 2]) / 2,
+        _ => values[len / 2],
+    }
+}
+
+implement_table_value_codecs_with_zc!(BlockFeesRedefined);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_stats() {
+        assert_eq!(median(&[]), 0);
+        assert_eq!(median(&[1, 3, 10]), 3);
+        assert_eq!(median(&[1, 3, 5, 10]), 4);
+        assert_eq!(average(&[1, 3, 5, 11]), 5);
+
+        let fees = BlockFees { gas_used: 15, gas_limit: 30, ..Default::default() };
+        assert_eq!(fees.gas_used_ratio(), 0.5);
+    }
+}
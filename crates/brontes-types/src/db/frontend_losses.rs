@@ -0,0 +1,165 @@
+//! Sandwich victim losses attributed to the frontends the victims swapped
+//! through.
+//!
+//! A victim transaction is attributed to the contract it was sent to, the
+//! router of the wallet, dex or aggregator frontend the user swapped on, and,
+//! when the transaction called an aggregator directly, to the integrator the
+//! aggregator routed it for, see
+//! [`NormalizedAggregator::frontend_referrer`](crate::normalized_actions::NormalizedAggregator::frontend_referrer).
+//! Each processed block keeps the victim count and loss of every frontend as
+//! [`BlockFrontendLosses`], [`frontend_loss_summary`] sums them over a block
+//! range.
+//!
+//! Losses follow the victim attestations, see [`victim_attestations`].
+use std::cmp::Ordering;
+
+use alloy_primitives::Address;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::redefined_types::primitives::*,
+    implement_table_value_codecs_with_zc,
+    mev::{attestation::victim_attestations, Bundle},
+    normalized_actions::Action,
+    tree::BlockTree,
+    FastHashMap, TreeSearchBuilder,
+};
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct FrontendLoss {
+    /// Contract the victims sent their transactions to
+    pub router:     Address,
+    /// Affiliate the frontend tagged the aggregator call with
+    pub referrer:   Option<Address>,
+    pub victim_txs: u64,
+    pub loss_usd:   f64,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockFrontendLosses {
+    pub losses: Vec<FrontendLoss>,
+}
+
+implement_table_value_codecs_with_zc!(BlockFrontendLossesRedefined);
+
+impl BlockFrontendLosses {
+    pub fn from_bundles(tree: &BlockTree<Action>, bundles: &[Bundle]) -> Self {
+        let mut frontends: FastHashMap<(Address, Option<Address>), FrontendLoss> =
+            FastHashMap::default();
+
+        for (attestation, loss_usd) in bundles.iter().flat_map(victim_attestations) {
+            let root = tree.get_root(attestation.victimTxHash);
+            let router = root.map(|root| root.get_to_address()).unwrap_or_default();
+            let referrer = root.and_then(|root| {
+                root.collect(&TreeSearchBuilder::default().with_action(Action::is_aggregator))
+                    .into_iter()
+                    .filter_map(Action::try_aggregator)
+                    .find(|route| route.trace_index == 0)?
+                    .frontend_referrer()
+            });
+
+            let frontend = frontends
+                .entry((router, referrer))
+                .or_insert_with(|| FrontendLoss { router, referrer, ..Default::default() });
+            frontend.victim_txs += 1;
+            frontend.loss_usd += loss_usd;
+        }
+
+        Self { losses: frontends.into_values().collect() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.losses.is_empty()
+    }
+}
+
+/// Losses of a frontend over an interval of blocks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FrontendLossSummary {
+    /// First block of the interval
+    pub start_block: u64,
+    pub router:      Address,
+    pub referrer:    Option<Address>,
+    pub victim_txs:  u64,
+    pub loss_usd:    f64,
+}
+
+/// Sums the losses of each frontend over intervals of `interval` blocks,
+/// counted from the first block, or over all blocks without an interval. The
+/// blocks should be ordered by block number. Sorted by interval, then by loss
+pub fn frontend_loss_summary(
+    blocks: &[(u64, BlockFrontendLosses)],
+    interval: Option<u64>,
+) -> Vec<FrontendLossSummary> {
+    let Some((first_block, _)) = blocks.first() else { return vec![] };
+    let interval_start = |block: u64| match interval.filter(|interval| *interval > 0) {
+        Some(interval) => first_block + (block - first_block) / interval * interval,
+        None => *first_block,
+    };
+
+    let mut summaries: FastHashMap<(u64, Address, Option<Address>), FrontendLossSummary> =
+        FastHashMap::default();
+    for (block, losses) in blocks {
+        let start_block = interval_start(*block);
+        for loss in &losses.losses {
+            let summary = summaries
+                .entry((start_block, loss.router, loss.referrer))
+                .or_insert_with(|| FrontendLossSummary {
+                    start_block,
+                    router: loss.router,
+                    referrer: loss.referrer,
+                    victim_txs: 0,
+                    loss_usd: 0.0,
+                });
+            summary.victim_txs += loss.victim_txs;
+            summary.loss_usd += loss.loss_usd;
+        }
+    }
+
+    let mut summaries = summaries.into_values().collect::<Vec<_>>();
+    summaries.sort_by(|a, b| {
+        a.start_block.cmp(&b.start_block).then(
+            b.loss_usd
+                .partial_cmp(&a.loss_usd)
+                .unwrap_or(Ordering::Equal),
+        )
+    });
+
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frontend_loss_summary() {
+        let (router, referrer) = (Address::repeat_byte(1), Some(Address::repeat_byte(2)));
+        let loss = |referrer, loss_usd| FrontendLoss { router, referrer, victim_txs: 1, loss_usd };
+        let blocks = vec![
+            (10, BlockFrontendLosses { losses: vec![loss(None, 5.0), loss(referrer, 1.0)] }),
+            (11, BlockFrontendLosses { losses: vec![loss(referrer, 2.0)] }),
+            (13, BlockFrontendLosses { losses: vec![loss(None, 4.0)] }),
+        ];
+
+        let total = frontend_loss_summary(&blocks, None);
+        assert_eq!(total.len(), 2);
+        assert_eq!((total[0].referrer, total[0].victim_txs, total[0].loss_usd), (None, 2, 9.0));
+        assert_eq!((total[1].referrer, total[1].victim_txs, total[1].loss_usd), (referrer, 2, 3.0));
+
+        let by_interval = frontend_loss_summary(&blocks, Some(2));
+        assert_eq!(
+            by_interval
+                .iter()
+                .map(|s| (s.start_block, s.loss_usd))
+                .collect::<Vec<_>>(),
+            vec![(10, 5.0), (10, 3.0), (12, 4.0)]
+        );
+    }
+}
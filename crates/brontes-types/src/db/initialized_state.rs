@@ -33,6 +33,7 @@ pub const DATA_PRESENT: u16 = 0b11;
     rkyv::Deserialize,
     rkyv::Archive,
 )]
+#[archive(check_bytes)]
 #[repr(transparent)]
 /// InitializedState allows for us to mark up to 8 fields in
 /// the database as initialized
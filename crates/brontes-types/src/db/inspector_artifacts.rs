@@ -0,0 +1,133 @@
+//! Structured debug artifacts of the inspectors.
+//!
+//! Working out why an inspector missed or misclassified a bundle used to mean
+//! rerunning the block with trace logs on. When debug artifacts are enabled,
+//! the block's metadata carries an [`ArtifactRecorder`] and inspectors record
+//! what they looked at along the way, the candidates they considered, why they
+//! rejected them and the prices they used, with [`Metadata::record_artifact`].
+//! The artifacts are taken once the block is inspected and stored next to its
+//! results, so they can be queried after the fact. The recorder lives and dies
+//! with the block's metadata, without one recording is a no-op.
+//!
+//! [`Metadata::record_artifact`]: super::metadata::Metadata::record_artifact
+use alloy_primitives::B256;
+use clap::ValueEnum;
+use parking_lot::Mutex;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+use crate::{db::redefined_types::primitives::*, implement_table_value_codecs_with_zc};
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+    ValueEnum,
+    AsRefStr,
+)]
+#[archive(check_bytes)]
+pub enum ArtifactKind {
+    /// A set of transactions the inspector considered
+    Candidate,
+    /// Why a candidate was dropped
+    Rejection,
+    /// A price the inspector used, or failed to find
+    PriceInput,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct InspectorArtifact {
+    /// Id of the inspector that recorded the artifact
+    pub inspector: String,
+    #[redefined(same_fields)]
+    pub kind:      ArtifactKind,
+    /// Transactions the artifact is about, if any
+    pub tx_hashes: Vec<B256>,
+    pub detail:    String,
+}
+
+/// The artifacts recorded while inspecting a block
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockInspectorArtifacts {
+    pub artifacts: Vec<InspectorArtifact>,
+}
+
+implement_table_value_codecs_with_zc!(BlockInspectorArtifactsRedefined);
+
+impl BlockInspectorArtifacts {
+    pub fn is_empty(&self) -> bool {
+        self.artifacts.is_empty()
+    }
+
+    pub fn of_inspector<'a>(
+        &'a self,
+        inspector: &'a str,
+    ) -> impl Iterator<Item = &'a InspectorArtifact> + 'a {
+        self.artifacts
+            .iter()
+            .filter(move |artifact| artifact.inspector == inspector)
+    }
+}
+
+/// Collects the artifacts recorded while inspecting a block
+#[derive(Debug, Default)]
+pub struct ArtifactRecorder {
+    artifacts: Mutex<Vec<InspectorArtifact>>,
+}
+
+impl ArtifactRecorder {
+    pub fn record(
+        &self,
+        inspector: &str,
+        kind: ArtifactKind,
+        tx_hashes: &[B256],
+        detail: impl FnOnce() -> String,
+    ) {
+        let artifact = InspectorArtifact {
+            inspector: inspector.to_string(),
+            kind,
+            tx_hashes: tx_hashes.to_vec(),
+            detail: detail(),
+        };
+        self.artifacts.lock().push(artifact);
+    }
+
+    /// Removes and returns the artifacts recorded so far
+    pub fn take(&self) -> BlockInspectorArtifacts {
+        BlockInspectorArtifacts { artifacts: std::mem::take(&mut *self.artifacts.lock()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_and_takes_block_artifacts() {
+        let tx = B256::repeat_byte(1);
+        let recorder = ArtifactRecorder::default();
+        recorder.record("Sandwich", ArtifactKind::Candidate, &[tx], || "2 victims".into());
+        recorder.record("CexDex", ArtifactKind::Rejection, &[tx], || "no dex swaps".into());
+        recorder.record("CexDex", ArtifactKind::PriceInput, &[], || "no quote".into());
+
+        let artifacts = recorder.take();
+        assert_eq!(artifacts.artifacts.len(), 3);
+        assert_eq!(artifacts.of_inspector("CexDex").count(), 2);
+        assert_eq!(artifacts.artifacts[2].kind, ArtifactKind::PriceInput);
+        assert!(recorder.take().is_empty());
+    }
+}
@@ -0,0 +1,247 @@
+//! Leaderboards over the classified mev of a block range.
+//!
+//! Rankings are computed from the stored [`MevBlockWithClassified`] summaries,
+//! so dashboards can query the top searchers and most victimized protocols
+//! over any block range without re-implementing the aggregation.
+use alloy_primitives::Address;
+use serde::Serialize;
+
+use crate::{
+    db::{mev_block::MevBlockWithClassified, searcher_stats::SearcherStats},
+    mev::{Bundle, BundleData, MevType},
+    FastHashMap, FastHashSet, Protocol,
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct SearcherRanking {
+    pub searcher:   Address,
+    pub bundles:    u64,
+    /// Net profit in usd, after bribes
+    pub profit_usd: f64,
+    pub bribe_usd:  f64,
+}
+
+impl SearcherRanking {
+    fn add_bundle(&mut self, bundle: &Bundle) {
+        self.bundles += 1;
+        self.profit_usd += bundle.header.profit_usd;
+        self.bribe_usd += bundle.header.bribe_usd;
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ProtocolRanking {
+    pub protocol:      Protocol,
+    /// Bundles that had at least one victim action on the protocol
+    pub bundles:       u64,
+    pub victim_txs:    u64,
+    /// Searcher profit of the bundles, split evenly across the protocols the
+    /// victims of a bundle interacted with
+    pub extracted_usd: f64,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct MevLeaderboard {
+    pub start_block:   Option<u64>,
+    pub end_block:     Option<u64>,
+    searchers:         FastHashMap<Address, SearcherRanking>,
+    searchers_by_type: FastHashMap<MevType, FastHashMap<Address, SearcherRanking>>,
+    protocols:         FastHashMap<Protocol, ProtocolRanking>,
+}
+
+impl MevLeaderboard {
+    pub fn from_blocks<'a>(blocks: impl IntoIterator<Item = &'a MevBlockWithClassified>) -> Self {
+        let mut this = Self::default();
+        blocks.into_iter().for_each(|block| this.add_block(block));
+        this
+    }
+
+    /// Searcher rankings over all processed blocks from the running searcher
+    /// statistics. These don't track victims, so the protocol rankings are
+    /// empty
+    pub fn from_searcher_stats(stats: impl IntoIterator<Item = (Address, SearcherStats)>) -> Self {
+        let mut this = Self::default();
+        for (searcher, stats) in stats {
+            this.start_block = Some(
+                this.start_block
+                    .map_or(stats.first_active_block, |start| start.min(stats.first_active_block)),
+            );
+            this.end_block = Some(
+                this.end_block
+                    .map_or(stats.last_active_block, |end| end.max(stats.last_active_block)),
+            );
+
+            for by_type in &stats.totals.by_type {
+                if let Some(ranking) = stats.ranking_for_type(searcher, by_type.mev_type) {
+                    this.searchers_by_type
+                        .entry(by_type.mev_type)
+                        .or_default()
+                        .insert(searcher, ranking);
+                }
+            }
+            this.searchers.insert(searcher, stats.ranking(searcher));
+        }
+
+        this
+    }
+
+    pub fn add_block(&mut self, block: &MevBlockWithClassified) {
+        let block_number = block.block.block_number;
+        self.start_block = Some(
+            self.start_block
+                .map_or(block_number, |start| start.min(block_number)),
+        );
+        self.end_block = Some(
+            self.end_block
+                .map_or(block_number, |end| end.max(block_number)),
+        );
+
+        block.mev.iter().for_each(|bundle| self.add_bundle(bundle));
+    }
+
+    fn add_bundle(&mut self, bundle: &Bundle) {
+        let searcher = bundle.header.eoa;
+        self.searchers
+            .entry(searcher)
+            .or_insert_with(|| SearcherRanking { searcher, ..Default::default() })
+            .add_bundle(bundle);
+        self.searchers_by_type
+            .entry(bundle.header.mev_type)
+            .or_default()
+            .entry(searcher)
+            .or_insert_with(|| SearcherRanking { searcher, ..Default::default() })
+            .add_bundle(bundle);
+
+        let victims = victim_protocols(&bundle.data);
+        if victims.is_empty() {
+            return
+        }
+
+        let share = bundle.header.profit_usd / victims.len() as f64;
+        for (protocol, victim_txs) in victims {
+            let ranking = self
+                .protocols
+                .entry(protocol)
+                .or_insert_with(|| ProtocolRanking { protocol, ..Default::default() });
+            ranking.bundles += 1;
+            ranking.victim_txs += victim_txs;
+            ranking.extracted_usd += share;
+        }
+    }
+
+    pub fn top_searchers_by_profit(&self, limit: usize) -> Vec<SearcherRanking> {
+        top_by(self.searchers.values(), limit, |s| s.profit_usd)
+    }
+
+    pub fn top_searchers_by_bundles(&self, limit: usize) -> Vec<SearcherRanking> {
+        top_by(self.searchers.values(), limit, |s| s.bundles as f64)
+    }
+
+    /// Top searchers by net profit for the given mev type
+    pub fn top_searchers_by_type(&self, mev_type: MevType, limit: usize) -> Vec<SearcherRanking> {
+        self.searchers_by_type
+            .get(&mev_type)
+            .map(|searchers| top_by(searchers.values(), limit, |s| s.profit_usd))
+            .unwrap_or_default()
+    }
+
+    pub fn top_victimized_protocols(&self, limit: usize) -> Vec<ProtocolRanking> {
+        top_by(self.protocols.values(), limit, |p| p.extracted_usd)
+    }
+}
+
+fn top_by<'a, T: Clone + 'a>(
+    values: impl Iterator<Item = &'a T>,
+    limit: usize,
+    key: impl Fn(&T) -> f64,
+) -> Vec<T> {
+    let mut values = values.cloned().collect::<Vec<_>>();
+    values.sort_by(|a, b| key(b).total_cmp(&key(a)));
+    values.truncate(limit);
+    values
+}
+
+/// The protocols the victims of the bundle interacted with, with the amount of
+/// victim txs per protocol
+fn victim_protocols(data: &BundleData) -> FastHashMap<Protocol, u64> {
+    let victim_swaps = match data {
+        BundleData::Sandwich(s) => &s.victim_swaps,
+        BundleData::JitSandwich(s) => &s.victim_swaps,
+        BundleData::Jit(j) => &j.victim_swaps,
+        BundleData::Liquidation(l) => {
+            let protocols = l
+                .liquidations
+                .iter()
+                .map(|liquidation| liquidation.protocol)
+                .collect::<FastHashSet<_>>();
+            return protocols
+                .into_iter()
+                .map(|protocol| (protocol, 1))
+                .collect()
+        }
+        _ => return FastHashMap::default(),
+    };
+
+    let mut protocols = FastHashMap::default();
+    for tx_swaps in victim_swaps {
+        tx_swaps
+            .iter()
+            .map(|swap| swap.protocol)
+            .collect::<FastHashSet<_>>()
+            .into_iter()
+            .for_each(|protocol| *protocols.entry(protocol).or_default() += 1);
+    }
+
+    protocols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::searcher_bundle,
+        mev::{MevBlock, Sandwich},
+        normalized_actions::NormalizedSwap,
+    };
+
+    #[test]
+    fn test_leaderboard_rankings() {
+        let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let sandwich = BundleData::Sandwich(Sandwich {
+            victim_swaps: vec![
+                vec![NormalizedSwap { protocol: Protocol::UniswapV2, ..Default::default() }],
+                vec![NormalizedSwap { protocol: Protocol::UniswapV3, ..Default::default() }],
+            ],
+            ..Default::default()
+        });
+
+        let block = MevBlockWithClassified {
+            block: MevBlock { block_number: 10, ..Default::default() },
+            mev:   vec![
+                searcher_bundle(1, MevType::Sandwich, 100.0)
+                    .data(sandwich)
+                    .build(),
+                searcher_bundle(2, MevType::AtomicArb, 30.0).build(),
+                searcher_bundle(2, MevType::AtomicArb, 30.0).build(),
+            ],
+        };
+        let leaderboard = MevLeaderboard::from_blocks([&block]);
+
+        assert_eq!(leaderboard.start_block, Some(10));
+        assert_eq!(leaderboard.top_searchers_by_profit(1)[0].searcher, a);
+        assert_eq!(leaderboard.top_searchers_by_bundles(1)[0].searcher, b);
+        assert_eq!(
+            leaderboard
+                .top_searchers_by_type(MevType::AtomicArb, 5)
+                .len(),
+            1
+        );
+        assert!(leaderboard
+            .top_searchers_by_type(MevType::Liquidation, 5)
+            .is_empty());
+
+        let protocols = leaderboard.top_victimized_protocols(5);
+        assert_eq!(protocols.len(), 2);
+        assert_eq!(protocols[0].extracted_usd, 50.0);
+    }
+}
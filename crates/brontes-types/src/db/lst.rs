@@ -0,0 +1,105 @@
+//! Exchange rates of liquid staking tokens.
+//!
+//! stETH, wstETH, rETH and cbETH track ETH at an exchange rate that drifts as
+//! staking rewards accrue, so pricing them off thin pools or cex quotes
+//! misprices them. Their value is instead taken as their rate in ETH times the
+//! ETH price. The canonical rate is read from the conversions made through
+//! the staking contracts in the block, see [`canonical_lst_rates`].
+use alloy_primitives::Address;
+use malachite::{num::basic::traits::Zero, Rational};
+
+use crate::{
+    constants::{ETH_ADDRESS, LIQUID_STAKING_TOKENS, STETH_ADDRESS, WETH_ADDRESS},
+    normalized_actions::NormalizedSwap,
+    FastHashMap, Protocol,
+};
+
+pub fn is_liquid_staking_token(token: Address) -> bool {
+    LIQUID_STAKING_TOKENS.contains(&token)
+}
+
+/// Rates in ETH per token of the liquid staking tokens converted through their
+/// staking contracts in the swaps, the last conversion of a token sets its
+/// rate
+pub fn canonical_lst_rates<'a>(
+    swaps: impl IntoIterator<Item = &'a NormalizedSwap>,
+) -> FastHashMap<Address, Rational> {
+    // stETH is minted and redeemed 1:1 for ETH
+    let mut rates = FastHashMap::from_iter([(STETH_ADDRESS, Rational::from(1))]);
+
+    for swap in swaps {
+        if !matches!(swap.protocol, Protocol::LidoWstEth | Protocol::RocketPool)
+            || swap.amount_in == Rational::ZERO
+            || swap.amount_out == Rational::ZERO
+        {
+            continue
+        }
+
+        let (lst, lst_amount, base, base_amount) =
+            if is_liquid_staking_token(swap.token_out.address)
+                && swap.token_out.address != STETH_ADDRESS
+            {
+                (swap.token_out.address, &swap.amount_out, swap.token_in.address, &swap.amount_in)
+            } else {
+                (swap.token_in.address, &swap.amount_in, swap.token_out.address, &swap.amount_out)
+            };
+
+        let base_rate = if base == ETH_ADDRESS || base == WETH_ADDRESS {
+            Rational::from(1)
+        } else {
+            let Some(rate) = rates.get(&base) else { continue };
+            rate.clone()
+        };
+
+        rates.insert(lst, base_amount / lst_amount * base_rate);
+    }
+
+    rates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::{RETH_ADDRESS, WSTETH_ADDRESS},
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+    };
+
+    fn token(address: Address) -> TokenInfoWithAddress {
+        TokenInfoWithAddress { address, inner: TokenInfo { decimals: 18, ..Default::default() } }
+    }
+
+    fn swap(
+        protocol: Protocol,
+        token_in: Address,
+        amount_in: u64,
+        token_out: Address,
+        amount_out: u64,
+    ) -> NormalizedSwap {
+        NormalizedSwap {
+            protocol,
+            token_in: token(token_in),
+            amount_in: Rational::from(amount_in),
+            token_out: token(token_out),
+            amount_out: Rational::from(amount_out),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_canonical_lst_rates() {
+        let swaps = [
+            // unwrapping 4 wstETH for 5 stETH
+            swap(Protocol::LidoWstEth, WSTETH_ADDRESS, 4, STETH_ADDRESS, 5),
+            // burning 10 rETH for 11 ETH
+            swap(Protocol::RocketPool, RETH_ADDRESS, 10, ETH_ADDRESS, 11),
+            // dex swaps don't set the rate
+            swap(Protocol::UniswapV3, RETH_ADDRESS, 1, WETH_ADDRESS, 2),
+        ];
+        let rates = canonical_lst_rates(&swaps);
+
+        assert_eq!(rates[&STETH_ADDRESS], Rational::from(1));
+        assert_eq!(rates[&WSTETH_ADDRESS], Rational::from_unsigneds(5u64, 4));
+        assert_eq!(rates[&RETH_ADDRESS], Rational::from_unsigneds(11u64, 10));
+    }
+}
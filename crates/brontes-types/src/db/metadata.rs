@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use alloy_primitives::{Address, TxHash, U256};
 use clickhouse::Row;
 use malachite::{num::basic::traits::Zero, Rational};
@@ -7,18 +9,29 @@ use serde::Serialize;
 use serde_with::serde_as;
 
 use super::{
+    beacon::BeaconBlockInfo,
+    block_costs::{BlockCost, BlockCostRecorder},
     builder::BuilderInfo,
-    cex::{quotes::CexPriceMap, trades::CexTradeMap},
+    cex::{
+        quotes::{CexPriceMap, QuoteSanityReport},
+        trades::CexTradeMap,
+    },
     dex::DexQuotes,
+    inspector_artifacts::{ArtifactKind, ArtifactRecorder, BlockInspectorArtifacts},
+    lst::is_liquid_staking_token,
+    metadata_override::CustomMetadata,
     traits::LibmdbxReader,
+    vault::VaultShareRate,
 };
 use crate::{
     constants::WETH_ADDRESS,
     db::{dex::BlockPrice, redefined_types::primitives::*},
     implement_table_value_codecs_with_zc,
+    live_config::{live_config, LiveConfig},
+    live_events::{LiveEvent, LiveEvents},
     pair::Pair,
     serde_utils::{option_addresss, u256, vec_txhash},
-    FastHashSet,
+    FastHashMap, FastHashSet,
 };
 #[allow(unused_imports)]
 use crate::{db::cex::CexExchange, normalized_actions::NormalizedSwap};
@@ -38,6 +51,7 @@ use crate::{db::cex::CexExchange, normalized_actions::NormalizedSwap};
     rSerialize,
     Archive
 ))]
+#[redefined_attr(archive(check_bytes))]
 pub struct BlockMetadataInner {
     #[serde(with = "u256")]
     pub block_hash:             U256,
@@ -58,14 +72,84 @@ implement_table_value_codecs_with_zc!(BlockMetadataInnerRedefined);
 pub struct Metadata {
     #[deref]
     #[as_ref]
-    pub block_metadata: BlockMetadata,
-    pub cex_quotes:     CexPriceMap,
-    pub dex_quotes:     Option<DexQuotes>,
-    pub builder_info:   Option<BuilderInfo>,
-    pub cex_trades:     Option<CexTradeMap>,
+    pub block_metadata:   BlockMetadata,
+    pub cex_quotes:       CexPriceMap,
+    pub dex_quotes:       Option<DexQuotes>,
+    pub builder_info:     Option<BuilderInfo>,
+    pub cex_trades:       Option<CexTradeMap>,
+    /// NFT collections that moved in the block, with their floor price in
+    /// ETH if one is known
+    pub nft_floor_prices: FastHashMap<Address, Option<Rational>>,
+    /// Canonical exchange rates in ETH per token of the liquid staking tokens
+    /// converted through their staking contracts in the block
+    pub lst_rates:        FastHashMap<Address, Rational>,
+    /// Rates of the vault share tokens as of their last deposit into or
+    /// redemption from their vault, up to the block
+    pub vault_rates:      FastHashMap<Address, VaultShareRate>,
+    /// Cex quotes rejected by the cross-exchange sanity checks
+    pub cex_quote_sanity: QuoteSanityReport,
+    /// Values attached by the [`MetadataOverride`]s of the run
+    ///
+    /// [`MetadataOverride`]: super::metadata_override::MetadataOverride
+    pub custom:           CustomMetadata,
+    /// The live config when the block was loaded, the block is inspected with
+    /// it even if the config is reloaded meanwhile
+    pub live_config:      Arc<LiveConfig>,
+    /// Collects the inspectors' debug artifacts when the run records them
+    pub artifacts:        Option<Arc<ArtifactRecorder>>,
+    /// Accumulates the block's processing cost when the run records it
+    pub cost:             Option<BlockCostRecorder>,
+    /// Sends the block's partial results when the run serves live events
+    pub live_events:      Option<LiveEvents>,
 }
 
 impl Metadata {
+    /// Records a debug artifact of the inspector for the block, a no-op
+    /// unless the run records them. The detail is only built when it does
+    pub fn record_artifact(
+        &self,
+        inspector: &str,
+        kind: ArtifactKind,
+        tx_hashes: &[TxHash],
+        detail: impl FnOnce() -> String,
+    ) {
+        if let Some(artifacts) = &self.artifacts {
+            artifacts.record(inspector, kind, tx_hashes, detail);
+        }
+    }
+
+    /// Adds to the block's processing cost, a no-op unless the run records
+    /// it
+    pub fn record_cost(&self, f: impl FnOnce(&mut BlockCost)) {
+        if let Some(cost) = &self.cost {
+            cost.record(f);
+        }
+    }
+
+    /// Emits a live event of the block, a no-op unless the run serves them
+    /// and someone is subscribed. The event is only built if it's sent
+    pub fn emit_live_event(&self, event: impl FnOnce() -> LiveEvent) {
+        if let Some(live_events) = &self.live_events {
+            live_events.emit(self.block_num, event);
+        }
+    }
+
+    /// The artifacts recorded for the block, if the run records them
+    pub fn take_inspector_artifacts(&self) -> Option<BlockInspectorArtifacts> {
+        self.artifacts.as_ref().map(|artifacts| artifacts.take())
+    }
+
+    /// The metadata without the artifact recorder, for an inspector that
+    /// reruns the search of another one, which records its own artifacts.
+    /// Only copied when the run records artifacts
+    pub fn without_artifacts(self: &Arc<Self>) -> Arc<Self> {
+        if self.artifacts.is_none() {
+            return self.clone()
+        }
+
+        Arc::new(Self { artifacts: None, ..(**self).clone() })
+    }
+
     pub fn display_pairs_quotes<DB: LibmdbxReader>(&self, db: &DB) {
         self.cex_quotes.quotes.iter().for_each(|(exchange, pairs)| {
             pairs.keys().for_each(|key| {
@@ -106,6 +190,45 @@ impl Metadata {
             .unwrap_or(Rational::ZERO)
     }
 
+    /// Floor price of a token of the NFT collection in terms of the quote
+    /// token, `None` if the collection has no floor price or there is no
+    /// ETH price for the block
+    pub fn get_nft_floor_price(
+        &self,
+        collection: Address,
+        quote_token: Address,
+    ) -> Option<Rational> {
+        let floor = self.nft_floor_prices.get(&collection)?.as_ref()?;
+        let eth_price = self.get_eth_price(quote_token);
+
+        (eth_price != Rational::ZERO).then(|| floor * eth_price)
+    }
+
+    pub fn is_nft_collection(&self, token: Address) -> bool {
+        self.nft_floor_prices.contains_key(&token)
+    }
+
+    /// Exchange rate of a liquid staking token in ETH per token. Uses the
+    /// canonical rate of the block if the token was converted through its
+    /// staking contract, otherwise the rate implied by its ETH pools
+    pub fn get_lst_rate(&self, token: Address) -> Option<Rational> {
+        if !is_liquid_staking_token(token) {
+            return None
+        }
+
+        self.lst_rates.get(&token).cloned().or_else(|| {
+            self.dex_quotes
+                .as_ref()?
+                .price_for_block(Pair(token, WETH_ADDRESS), BlockPrice::Average)
+        })
+    }
+
+    /// Underlying asset and exchange rate of a vault share token, if it was
+    /// converted through its vault in the block or before it in the run
+    pub fn get_vault_rate(&self, share: Address) -> Option<&VaultShareRate> {
+        self.vault_rates.get(&share)
+    }
+
     pub fn into_full_metadata(mut self, dex_quotes: DexQuotes) -> Self {
         self.dex_quotes = Some(dex_quotes);
         self
@@ -135,6 +258,8 @@ pub struct BlockMetadata {
     pub eth_prices:             Rational,
     /// Tx
     pub private_flow:           FastHashSet<TxHash>,
+    /// Consensus layer info for the slot the block was proposed in
+    pub beacon:                 Option<BeaconBlockInfo>,
 }
 
 impl BlockMetadata {
@@ -160,9 +285,15 @@ impl BlockMetadata {
             proposer_mev_reward,
             private_flow,
             block_timestamp,
+            beacon: None,
         }
     }
 
+    pub fn with_beacon_info(mut self, beacon: Option<BeaconBlockInfo>) -> Self {
+        self.beacon = beacon;
+        self
+    }
+
     pub fn microseconds_block_timestamp(&self) -> u64 {
         self.block_timestamp * 1_000_000
     }
@@ -174,6 +305,21 @@ impl BlockMetadata {
         builder_info: Option<BuilderInfo>,
         cex_trades: Option<CexTradeMap>,
     ) -> Metadata {
-        Metadata { block_metadata: self, cex_quotes, dex_quotes, builder_info, cex_trades }
+        Metadata {
+            block_metadata: self,
+            cex_quotes,
+            dex_quotes,
+            builder_info,
+            cex_trades,
+            nft_floor_prices: FastHashMap::default(),
+            lst_rates: FastHashMap::default(),
+            vault_rates: FastHashMap::default(),
+            cex_quote_sanity: QuoteSanityReport::default(),
+            custom: CustomMetadata::default(),
+            live_config: live_config(),
+            artifacts: None,
+            cost: None,
+            live_events: None,
+        }
     }
 }
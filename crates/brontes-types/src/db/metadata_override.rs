@@ -0,0 +1,129 @@
+//! Custom metadata for research experiments.
+//!
+//! Counterfactual experiments, like inspecting a range with an alternative
+//! price feed or tagging blocks with the experiment they were run for, need
+//! data on the block's metadata that brontes doesn't produce. A
+//! [`MetadataOverride`] is applied to the [`Metadata`] of every block after it
+//! is loaded and before it is inspected. It can replace any of the loaded data
+//! or attach its own typed values to [`Metadata::custom`], which inspectors
+//! read back by type.
+//!
+//! ### Example
+//! ```ignore
+//! #[derive(Debug)]
+//! struct ShockedEthPrice(f64);
+//!
+//! impl MetadataOverride for ShockedEthPrice {
+//!     fn apply(&self, metadata: &mut Metadata) {
+//!         metadata.custom.insert(EthPriceShock(self.0));
+//!     }
+//! }
+//!
+//! // in an inspector
+//! let shock = metadata.custom.get::<EthPriceShock>();
+//! ```
+use std::{
+    any::{Any, TypeId},
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use super::metadata::Metadata;
+use crate::FastHashMap;
+
+pub trait MetadataOverride: Debug + Send + Sync {
+    /// Changes the metadata of a block before it is inspected
+    fn apply(&self, metadata: &mut Metadata);
+}
+
+/// Typed values attached to a block's metadata, at most one per type
+#[derive(Clone, Default)]
+pub struct CustomMetadata {
+    values: FastHashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl CustomMetadata {
+    /// Attaches the value, replacing the value of the same type if there is
+    /// one
+    pub fn insert<T: Any + Send + Sync>(&mut self, value: T) {
+        self.values.insert(TypeId::of::<T>(), Arc::new(value));
+    }
+
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn remove<T: Any + Send + Sync>(&mut self) -> bool {
+        self.values.remove(&TypeId::of::<T>()).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl Debug for CustomMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomMetadata")
+            .field("values", &self.values.len())
+            .finish()
+    }
+}
+
+/// Label of the experiment a block was inspected for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExperimentLabel(pub String);
+
+/// Labels every block with the experiment
+#[derive(Debug, Clone)]
+pub struct LabelExperiment(pub String);
+
+impl MetadataOverride for LabelExperiment {
+    fn apply(&self, metadata: &mut Metadata) {
+        metadata.custom.insert(ExperimentLabel(self.0.clone()));
+    }
+}
+
+/// Applies the override only to the blocks in `start..=end`
+#[derive(Debug, Clone)]
+pub struct BlockRangeOverride<O> {
+    pub start: u64,
+    pub end:   u64,
+    pub inner: O,
+}
+
+impl<O: MetadataOverride> MetadataOverride for BlockRangeOverride<O> {
+    fn apply(&self, metadata: &mut Metadata) {
+        if (self.start..=self.end).contains(&metadata.block_num) {
+            self.inner.apply(metadata)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_attach_typed_values() {
+        let label = BlockRangeOverride {
+            start: 10,
+            end:   20,
+            inner: LabelExperiment("shocked-eth".to_string()),
+        };
+
+        let mut metadata = Metadata::default();
+        metadata.block_metadata.block_num = 15;
+        label.apply(&mut metadata);
+        assert_eq!(
+            metadata.custom.get::<ExperimentLabel>(),
+            Some(&ExperimentLabel("shocked-eth".to_string()))
+        );
+        assert!(metadata.custom.get::<u64>().is_none());
+
+        let mut outside = Metadata::default();
+        outside.block_metadata.block_num = 21;
+        label.apply(&mut outside);
+        assert!(outside.custom.is_empty());
+    }
+}
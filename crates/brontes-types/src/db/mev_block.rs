@@ -1,14 +1,116 @@
+use std::collections::BTreeMap;
+
 use redefined::Redefined;
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
-use crate::{implement_table_value_codecs_with_zc, mev::*};
+use crate::{
+    db::{bundle_versions, overrides::BundleOverrides, repricing::BlockRepricings},
+    lite,
+    mev::*,
+};
 
 #[derive(Debug, Default, Serialize, PartialEq, Deserialize, Clone, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct MevBlockWithClassified {
     pub block: MevBlock,
     pub mev:   Vec<Bundle>,
 }
 
-implement_table_value_codecs_with_zc!(MevBlockWithClassifiedRedefined);
+// bundle data is stored versioned, see [`bundle_versions`]
+impl alloy_rlp::Encodable for MevBlockWithClassifiedRedefined {
+    fn encode(&self, out: &mut dyn bytes::BufMut) {
+        out.put_slice(&bundle_versions::encode_mev_block(self))
+    }
+}
+
+impl alloy_rlp::Decodable for MevBlockWithClassifiedRedefined {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        bundle_versions::decode_mev_block(buf).map_err(|e| {
+            tracing::error!(%e, "failed to decode mev block");
+            alloy_rlp::Error::Custom("undecodable bundle data")
+        })
+    }
+}
+
+impl reth_db::table::Compress for MevBlockWithClassifiedRedefined {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: reth_primitives::bytes::BufMut + AsMut<[u8]>>(self, buf: &mut B) {
+        let mut encoded = Vec::new();
+        alloy_rlp::Encodable::encode(&self, &mut encoded);
+        let encoded_compressed = zstd::encode_all(&*encoded, 0).unwrap();
+
+        buf.put_slice(&encoded_compressed);
+    }
+}
+
+impl reth_db::table::Decompress for MevBlockWithClassifiedRedefined {
+    fn decompress<B: AsRef<[u8]>>(value: B) -> Result<Self, reth_db::DatabaseError> {
+        let encoded_decompressed =
+            zstd::decode_all(value.as_ref()).map_err(|_| reth_db::DatabaseError::Decode)?;
+        let buf = &mut encoded_decompressed.as_slice();
+
+        alloy_rlp::Decodable::decode(buf).map_err(|_| reth_db::DatabaseError::Decode)
+    }
+}
+
+impl MevBlockWithClassifiedRedefined {
+    /// The layout versions a stored row was written with, see
+    /// [`bundle_versions::row_layout_versions`]
+    pub fn stored_layout_versions(row: &[u8]) -> eyre::Result<BTreeMap<String, u16>> {
+        let decompressed = zstd::decode_all(row)?;
+        bundle_versions::row_layout_versions(&decompressed)
+    }
+}
+
+impl MevBlockWithClassified {
+    /// Converts the block to the [`lite`] types through its exported layout,
+    /// which is what the lite types follow
+    pub fn to_lite(&self) -> serde_json::Result<lite::MevBlockWithBundles> {
+        serde_json::from_slice(&serde_json::to_vec(self)?)
+    }
+
+    /// Applies the block's latest re-pricing and the manual overrides, which
+    /// is the view of the block readers are served
+    pub fn curate(&mut self, repricings: Option<&BlockRepricings>, overrides: &BundleOverrides) {
+        if let Some(repricings) = repricings {
+            repricings.apply(self);
+        }
+        overrides.apply(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Address;
+
+    use super::*;
+
+    #[test]
+    fn test_lite_types_follow_export_layout() {
+        let mut block = MevBlockWithClassified::default();
+        block.block.block_number = 18_000_000;
+        block.block.mev_count.bundle_count = 1;
+        block.block.builder_sponsorship_amount = u64::MAX as u128 + 1;
+        block.mev.push(Bundle {
+            header: BundleHeader {
+                block_number: 18_000_000,
+                eoa: Address::repeat_byte(1),
+                mev_type: MevType::Sandwich,
+                profit_usd: 12.5,
+                ..Default::default()
+            },
+            data:   BundleData::default(),
+        });
+
+        let lite = block.to_lite().unwrap();
+        assert_eq!(lite.block.block_number, 18_000_000);
+        assert_eq!(lite.block.bundle_count, 1);
+        assert_eq!(lite.block.builder_sponsorship_amount, u64::MAX as u128 + 1);
+        assert_eq!(lite.mev[0].header.eoa, Address::repeat_byte(1));
+        assert_eq!(lite.mev[0].header.mev_type, lite::MevType::Sandwich);
+        assert!(matches!(lite.mev[0].data, lite::BundleData::Sandwich(_)));
+    }
+}
@@ -0,0 +1,294 @@
+//! Compact per transaction verdicts for wallet and explorer integrations.
+//!
+//! Showing a user whether their transaction was sandwiched shouldn't require
+//! fetching and walking the block's bundles. When a block's results are
+//! written, a [`MevReceipt`] is kept for every transaction that was part of a
+//! bundle, as its searcher or as a victim, keyed by the transaction hash so it
+//! can be served with a single lookup. A transaction without a receipt was
+//! neither if its block was processed, otherwise its verdict is unknown.
+//!
+//! Receipts follow the curated view of the block, see
+//! [`MevBlockWithClassified::curate`]. They're rewritten whenever the block,
+//! its re-pricing or its overrides change, so a dropped bundle's transactions
+//! lose their receipts and a re-priced victim's loss is updated.
+//!
+//! A victim's estimated loss follows the victim attestations, see
+//! [`victim_attestations`].
+
+use std::collections::hash_map::Entry;
+
+use alloy_primitives::B256;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::{
+        mev_block::MevBlockWithClassified,
+        overrides::{BundleOverride, BundleOverrides},
+        redefined_types::primitives::*,
+        repricing::BlockRepricings,
+    },
+    implement_table_value_codecs_with_zc,
+    mev::{attestation::victim_attestations, Bundle, Mev, MevType},
+    normalized_actions::FULL_CONFIDENCE,
+    FastHashMap, FastHashSet,
+};
+
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+)]
+#[archive(check_bytes)]
+#[serde(rename_all = "lowercase")]
+pub enum MevVerdict {
+    /// The transaction extracted mev
+    Mev,
+    /// The transaction was sandwiched
+    Victim,
+    #[default]
+    Neither,
+    /// The transaction has no receipt and its block isn't known to be
+    /// processed
+    Unknown,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct MevReceipt {
+    pub tx_hash:      B256,
+    /// Block the transaction landed in, unset for transactions without a
+    /// receipt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[redefined(same_fields)]
+    pub verdict:      MevVerdict,
+    /// Type of the bundle the transaction was part of
+    #[redefined(same_fields)]
+    pub mev_type:     MevType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_id:    Option<B256>,
+    /// Estimated usd loss of a victim
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loss_usd:     Option<f64>,
+    /// Lowest classification confidence of the actions of the bundle
+    pub confidence:   f64,
+}
+
+implement_table_value_codecs_with_zc!(MevReceiptRedefined);
+
+impl MevReceipt {
+    /// Receipt of a transaction of a processed block that wasn't part of any
+    /// bundle
+    pub fn neither(tx_hash: B256, block_number: u64) -> Self {
+        Self {
+            tx_hash,
+            block_number: Some(block_number),
+            confidence: FULL_CONFIDENCE,
+            ..Default::default()
+        }
+    }
+
+    /// Receipt of a transaction whose block isn't known to be processed
+    pub fn unknown(tx_hash: B256) -> Self {
+        Self { tx_hash, verdict: MevVerdict::Unknown, confidence: 0.0, ..Default::default() }
+    }
+
+    /// The receipts of the block's curated view, with its latest re-pricing
+    /// and the overrides applied, and the transactions of the block whose
+    /// receipts are to be deleted as they aren't part of a curated bundle
+    /// anymore. `raw` is the block as it was classified
+    pub fn for_block(
+        raw: &MevBlockWithClassified,
+        repricings: Option<&BlockRepricings>,
+        overrides: Vec<(u64, BundleOverride)>,
+    ) -> (Vec<MevReceipt>, FastHashSet<B256>) {
+        let block_number = raw.block.block_number;
+        // transactions of missed bundles only have a receipt through an override
+        let mut stale = overrides
+            .iter()
+            .filter(|(_, o)| o.block_number == block_number)
+            .map(|(_, o)| o.tx_hash)
+            .chain(
+                Self::from_bundles(&raw.mev)
+                    .into_iter()
+                    .map(|receipt| receipt.tx_hash),
+            )
+            .collect::<FastHashSet<_>>();
+
+        let mut curated = raw.clone();
+        curated.curate(repricings, &BundleOverrides::new(overrides));
+        let receipts = Self::from_bundles(&curated.mev);
+        for receipt in &receipts {
+            stale.remove(&receipt.tx_hash);
+        }
+
+        (receipts, stale)
+    }
+
+    /// The receipts of the searcher and victim transactions of the block's
+    /// bundles. A transaction that extracted mev in one bundle and was a
+    /// victim in another gets the mev receipt
+    pub fn from_bundles(bundles: &[Bundle]) -> Vec<MevReceipt> {
+        let mut receipts: Vec<MevReceipt> = Vec::new();
+        let mut index: FastHashMap<B256, usize> = FastHashMap::default();
+
+        for bundle in bundles {
+            let header = &bundle.header;
+            if matches!(
+                header.mev_type,
+                MevType::Unknown | MevType::SearcherTx | MevType::Rebalance
+            ) {
+                continue
+            }
+
+            let receipt = |tx_hash, verdict, loss_usd| MevReceipt {
+                tx_hash,
+                block_number: Some(header.block_number),
+                verdict,
+                mev_type: header.mev_type,
+                bundle_id: Some(header.bundle_id),
+                loss_usd,
+                confidence: header.min_action_confidence,
+            };
+
+            for tx_hash in bundle.data.mev_transaction_hashes() {
+                let receipt = receipt(tx_hash, MevVerdict::Mev, None);
+                match index.entry(tx_hash) {
+                    Entry::Occupied(i) => receipts[*i.get()] = receipt,
+                    Entry::Vacant(v) => {
+                        v.insert(receipts.len());
+                        receipts.push(receipt);
+                    }
+                }
+            }
+            for (attestation, loss_usd) in victim_attestations(bundle) {
+                let tx_hash = attestation.victimTxHash;
+                if let Entry::Vacant(v) = index.entry(tx_hash) {
+                    v.insert(receipts.len());
+                    receipts.push(receipt(tx_hash, MevVerdict::Victim, Some(loss_usd)));
+                }
+            }
+        }
+
+        receipts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Address;
+
+    use super::*;
+    use crate::{
+        db::repricing::{BundlePricing, BundleRepricing},
+        mev::{BundleData, BundleHeader, MevBlock, Sandwich},
+    };
+
+    fn sandwich(frontrun: B256, victim: B256, backrun: B256) -> Bundle {
+        Bundle {
+            header: BundleHeader {
+                block_number: 10,
+                tx_hash: frontrun,
+                mev_type: MevType::Sandwich,
+                profit_usd: 30.0,
+                bribe_usd: 10.0,
+                min_action_confidence: 0.9,
+                ..Default::default()
+            },
+            data:   BundleData::Sandwich(Sandwich {
+                frontrun_tx_hash: vec![frontrun],
+                victim_swaps_tx_hashes: vec![vec![victim]],
+                backrun_tx_hash: backrun,
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn test_receipts_from_sandwich() {
+        let (frontrun, victim, backrun) =
+            (B256::repeat_byte(1), B256::repeat_byte(2), B256::repeat_byte(3));
+        let receipts = MevReceipt::from_bundles(&[sandwich(frontrun, victim, backrun)]);
+        assert_eq!(receipts.len(), 3);
+
+        let victim = receipts.iter().find(|r| r.tx_hash == victim).unwrap();
+        assert_eq!(victim.verdict, MevVerdict::Victim);
+        assert_eq!(victim.loss_usd, Some(40.0));
+        assert_eq!(victim.confidence, 0.9);
+
+        let backrun = receipts.iter().find(|r| r.tx_hash == backrun).unwrap();
+        assert_eq!(backrun.verdict, MevVerdict::Mev);
+        assert_eq!(backrun.block_number, Some(10));
+        assert!(backrun.loss_usd.is_none());
+    }
+
+    #[test]
+    fn test_receipts_follow_curated_block() {
+        let (frontrun, victim, backrun) =
+            (B256::repeat_byte(1), B256::repeat_byte(2), B256::repeat_byte(3));
+        let missed = B256::repeat_byte(4);
+        let raw = MevBlockWithClassified {
+            block: MevBlock { block_number: 10, ..Default::default() },
+            mev:   vec![sandwich(frontrun, victim, backrun)],
+        };
+        let by = || "alice".to_string();
+
+        // a re-priced sandwich changes the victim's loss
+        let repricings = BlockRepricings {
+            repricings: vec![BundleRepricing {
+                bundle_id:   raw.mev[0].header.bundle_id,
+                methodology: "test".to_string(),
+                repriced_at: 0,
+                previous:    BundlePricing::default(),
+                repriced:    BundlePricing {
+                    revenue_usd: 60.0,
+                    bribe_usd:   10.0,
+                    profit_usd:  50.0,
+                },
+            }],
+        };
+        let (receipts, stale) = MevReceipt::for_block(&raw, Some(&repricings), vec![]);
+        assert!(stale.is_empty());
+        let loss = receipts
+            .iter()
+            .find(|r| r.tx_hash == victim)
+            .unwrap()
+            .loss_usd;
+        assert_eq!(loss, Some(60.0));
+
+        // a dropped sandwich leaves its transactions without receipts, a missed
+        // bundle gets one
+        let overrides = vec![
+            (0, BundleOverride::false_positive(10, frontrun, by(), by())),
+            (
+                1,
+                BundleOverride::false_negative(
+                    10,
+                    missed,
+                    MevType::AtomicArb,
+                    Address::repeat_byte(4),
+                    5.0,
+                    by(),
+                    by(),
+                ),
+            ),
+        ];
+        let (receipts, stale) = MevReceipt::for_block(&raw, None, overrides);
+        assert_eq!(stale, FastHashSet::from_iter([frontrun, victim, backrun]));
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].tx_hash, missed);
+        assert_eq!(receipts[0].verdict, MevVerdict::Mev);
+    }
+}
@@ -3,27 +3,61 @@ use std::fmt::Debug;
 use ::clickhouse::{DbRow, InsertRow};
 pub mod address_metadata;
 pub mod address_to_protocol_info;
+pub mod audit_log;
+pub mod beacon;
 
 #[rustfmt::skip]
 pub mod block_analysis;
+pub mod block_checksum;
+pub mod block_costs;
 pub mod block_times;
 pub mod builder;
+pub mod bundle_submissions;
+pub mod bundle_tags;
+pub mod bundle_versions;
 pub mod cex;
 
 pub mod clickhouse;
 pub mod clickhouse_serde;
 pub mod codecs;
+pub mod cold_segment;
+pub mod config_reload;
 pub mod dex;
+pub mod export_filter;
+pub mod fee_history;
+pub mod frontend_losses;
 pub mod initialized_state;
+pub mod inspector_artifacts;
+pub mod leaderboard;
+pub mod lst;
 pub mod metadata;
+pub mod metadata_override;
 pub mod mev_block;
+pub mod mev_receipt;
+pub mod nft_floor;
 pub mod normalized_actions;
+pub mod order_flow;
+pub mod overrides;
 pub mod pool_creation_block;
+pub mod pool_prices;
+pub mod publish_checkpoint;
 pub mod redefined_types;
+pub mod repricing;
+pub mod run_provenance;
+pub mod scheduled_job;
 pub mod searcher;
+pub mod searcher_stats;
+pub mod searcher_versions;
+pub mod suppression;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub mod token_info;
+pub mod token_metadata;
 pub mod traces;
 pub mod traits;
+pub mod value_split;
+pub mod vault;
+pub mod wallet_funding;
 
 /// This table is used to add run id inserts for each clickhouse table in order
 /// for us to not have to clear runs multiple times
@@ -0,0 +1,53 @@
+//! Floor prices for NFT collections.
+//!
+//! NFTs have no dex or cex quotes, so when they move as part of a bundle's
+//! profit they are valued at the floor price of their collection. Where the
+//! floor prices come from is left to a [`NftFloorPriceSource`].
+use std::{fmt::Debug, path::Path};
+
+use alloy_primitives::Address;
+use malachite::Rational;
+
+use crate::FastHashMap;
+
+pub trait NftFloorPriceSource: Debug + Send + Sync {
+    /// Floor price of a single token of the collection in ETH at the given
+    /// block, `None` if the collection has no known floor
+    fn floor_price_eth(&self, collection: Address, block: u64) -> Option<Rational>;
+}
+
+/// Fixed floor prices that apply to every block, loaded from a json object
+/// of collection address to floor price in ETH
+#[derive(Debug, Clone, Default)]
+pub struct StaticNftFloorPrices {
+    floors: FastHashMap<Address, Rational>,
+}
+
+impl StaticNftFloorPrices {
+    pub fn new(floors: FastHashMap<Address, Rational>) -> Self {
+        Self { floors }
+    }
+
+    pub fn from_json_file(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("failed to read nft floor prices {}: {e}", path.display()))?;
+        let floors: FastHashMap<Address, f64> = serde_json::from_str(&file)?;
+
+        floors
+            .into_iter()
+            .map(|(collection, floor)| {
+                Rational::try_from_float_simplest(floor)
+                    .map(|floor| (collection, floor))
+                    .map_err(|_| eyre::eyre!("invalid floor price {floor} for {collection:?}"))
+            })
+            .collect::<eyre::Result<_>>()
+            .map(Self::new)
+    }
+}
+
+impl NftFloorPriceSource for StaticNftFloorPrices {
+    fn floor_price_eth(&self, collection: Address, _: u64) -> Option<Rational> {
+        self.floors.get(&collection).cloned()
+    }
+}
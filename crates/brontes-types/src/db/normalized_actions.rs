@@ -154,10 +154,12 @@ pub enum ActionKind {
     Unclassified,
     SelfDestruct,
     EthTransfer,
+    Fill,
     NewPool,
     PoolConfigUpdate,
     Aggregator,
     Revert,
+    NftTransfer,
 }
 
 impl From<&Action> for ActionKind {
@@ -174,6 +176,8 @@ impl From<&Action> for ActionKind {
             Action::Collect(_) => ActionKind::Collect,
             Action::SelfDestruct(_) => ActionKind::SelfDestruct,
             Action::EthTransfer(_) => ActionKind::EthTransfer,
+            Action::NftTransfer(_) => ActionKind::NftTransfer,
+            Action::Fill(_) => ActionKind::Fill,
             Action::Unclassified(_) => ActionKind::Unclassified,
             Action::NewPool(_) => ActionKind::NewPool,
             Action::PoolConfigUpdate(_) => ActionKind::PoolConfigUpdate,
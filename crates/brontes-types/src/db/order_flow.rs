@@ -0,0 +1,222 @@
+//! Market structure summaries: how builders pay proposers and how searchers
+//! route their bundles to builders.
+//!
+//! Each processed block is recorded as a [`BlockOrderFlow`]. The per builder
+//! and per searcher summaries are maintained incrementally from these records,
+//! so reprocessing a block replaces its previous contribution instead of
+//! counting it twice.
+use alloy_primitives::Address;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::redefined_types::primitives::AddressRedefined,
+    implement_table_value_codecs_with_zc,
+    mev::{Bundle, MevBlock},
+    FastHashMap,
+};
+
+/// The builder, proposer payment and searcher bundles of a single block.
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockOrderFlow {
+    pub builder:                Address,
+    pub proposer_fee_recipient: Option<Address>,
+    /// Payment from the builder to the proposer in wei
+    pub proposer_payment:       Option<u128>,
+    pub searcher_bundles:       Vec<SearcherBundleCount>,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct SearcherBundleCount {
+    pub searcher: Address,
+    pub bundles:  u64,
+}
+
+impl BlockOrderFlow {
+    pub fn new(block: &MevBlock, bundles: &[Bundle]) -> Self {
+        let mut counts: FastHashMap<Address, u64> = FastHashMap::default();
+        bundles
+            .iter()
+            .for_each(|bundle| *counts.entry(bundle.header.eoa).or_default() += 1);
+
+        let mut searcher_bundles = counts
+            .into_iter()
+            .map(|(searcher, bundles)| SearcherBundleCount { searcher, bundles })
+            .collect::<Vec<_>>();
+        searcher_bundles.sort_unstable_by_key(|count| count.searcher);
+
+        Self {
+            builder: block.builder_address,
+            proposer_fee_recipient: block.proposer_fee_recipient,
+            proposer_payment: block.proposer_mev_reward,
+            searcher_bundles,
+        }
+    }
+}
+
+implement_table_value_codecs_with_zc!(BlockOrderFlowRedefined);
+
+/// Payments of a builder to the proposers of the blocks it built.
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BuilderProposerPayments {
+    pub proposers: Vec<ProposerPayments>,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct ProposerPayments {
+    pub fee_recipient: Address,
+    /// Blocks of the builder that were proposed by this proposer
+    pub blocks:        u64,
+    /// Total paid to the proposer in wei
+    pub total_paid:    u128,
+}
+
+impl BuilderProposerPayments {
+    pub fn add_block(&mut self, flow: &BlockOrderFlow) {
+        let Some(fee_recipient) = flow.proposer_fee_recipient else { return };
+        let payment = flow.proposer_payment.unwrap_or_default();
+
+        if let Some(proposer) = self
+            .proposers
+            .iter_mut()
+            .find(|p| p.fee_recipient == fee_recipient)
+        {
+            proposer.blocks += 1;
+            proposer.total_paid += payment;
+        } else {
+            self.proposers
+                .push(ProposerPayments { fee_recipient, blocks: 1, total_paid: payment });
+        }
+    }
+
+    pub fn remove_block(&mut self, flow: &BlockOrderFlow) {
+        let Some(fee_recipient) = flow.proposer_fee_recipient else { return };
+        let payment = flow.proposer_payment.unwrap_or_default();
+
+        if let Some(proposer) = self
+            .proposers
+            .iter_mut()
+            .find(|p| p.fee_recipient == fee_recipient)
+        {
+            proposer.blocks = proposer.blocks.saturating_sub(1);
+            proposer.total_paid = proposer.total_paid.saturating_sub(payment);
+        }
+        self.proposers.retain(|p| p.blocks != 0);
+    }
+
+    pub fn total_paid(&self) -> u128 {
+        self.proposers.iter().map(|p| p.total_paid).sum()
+    }
+}
+
+implement_table_value_codecs_with_zc!(BuilderProposerPaymentsRedefined);
+
+/// The builders a searcher's bundles landed through.
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct SearcherOrderFlow {
+    pub builders: Vec<BuilderBundleCount>,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BuilderBundleCount {
+    pub builder: Address,
+    pub bundles: u64,
+}
+
+impl SearcherOrderFlow {
+    pub fn add_bundles(&mut self, builder: Address, bundles: u64) {
+        if let Some(count) = self.builders.iter_mut().find(|b| b.builder == builder) {
+            count.bundles += bundles;
+        } else {
+            self.builders.push(BuilderBundleCount { builder, bundles });
+        }
+    }
+
+    pub fn remove_bundles(&mut self, builder: Address, bundles: u64) {
+        if let Some(count) = self.builders.iter_mut().find(|b| b.builder == builder) {
+            count.bundles = count.bundles.saturating_sub(bundles);
+        }
+        self.builders.retain(|b| b.bundles != 0);
+    }
+
+    pub fn total_bundles(&self) -> u64 {
+        self.builders.iter().map(|b| b.bundles).sum()
+    }
+
+    /// The builder that landed most of the searcher's bundles
+    pub fn dominant_builder(&self) -> Option<&BuilderBundleCount> {
+        self.builders.iter().max_by_key(|b| b.bundles)
+    }
+
+    /// Share of the searcher's bundles that landed through the dominant
+    /// builder. A searcher with exclusive order flow has an exclusivity of 1.
+    pub fn exclusivity(&self) -> f64 {
+        let total = self.total_bundles();
+        if total == 0 {
+            return 0.0
+        }
+
+        self.dominant_builder()
+            .map(|b| b.bundles as f64 / total as f64)
+            .unwrap_or_default()
+    }
+}
+
+implement_table_value_codecs_with_zc!(SearcherOrderFlowRedefined);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_searcher_exclusivity() {
+        let (a, b) = (Address::repeat_byte(1), Address::repeat_byte(2));
+
+        let mut flow = SearcherOrderFlow::default();
+        assert_eq!(flow.exclusivity(), 0.0);
+
+        flow.add_bundles(a, 3);
+        assert_eq!(flow.exclusivity(), 1.0);
+
+        flow.add_bundles(b, 1);
+        assert_eq!(flow.total_bundles(), 4);
+        assert_eq!(flow.dominant_builder().unwrap().builder, a);
+        assert_eq!(flow.exclusivity(), 0.75);
+
+        flow.remove_bundles(b, 1);
+        assert_eq!(flow.builders.len(), 1);
+        assert_eq!(flow.exclusivity(), 1.0);
+    }
+
+    #[test]
+    fn test_proposer_payments() {
+        let flow = BlockOrderFlow {
+            proposer_fee_recipient: Some(Address::repeat_byte(1)),
+            proposer_payment: Some(10),
+            ..Default::default()
+        };
+
+        let mut payments = BuilderProposerPayments::default();
+        payments.add_block(&flow);
+        payments.add_block(&flow);
+        assert_eq!(payments.total_paid(), 20);
+        assert_eq!(payments.proposers[0].blocks, 2);
+
+        payments.remove_block(&flow);
+        payments.remove_block(&flow);
+        assert!(payments.proposers.is_empty());
+    }
+}
@@ -0,0 +1,519 @@
+//! Manual corrections of classified bundles.
+//!
+//! Operators correct bundles by appending [`BundleOverride`]s, each with the
+//! author and the reason for the correction. Overrides are never edited or
+//! deleted, a mistaken override is undone by appending a revocation, so the
+//! table is an audit log of every correction made, see [`audit_log`]. The raw
+//! mev blocks are left untouched, [`BundleOverrides::apply`] applies the
+//! corrections as a view on top of them.
+//!
+//! A corrected bundle's data always matches its header: a bundle reclassified
+//! as a type its data can't hold, or one brontes missed, gets data of the new
+//! type holding only its transactions and their gas, as there are no swaps
+//! or other actions of that type to carry over.
+use std::fmt::Display;
+
+use alloy_primitives::{Address, B256};
+use clap::ValueEnum;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+use crate::{
+    db::{
+        audit_log::{self, entries_in_effect, AuditEntry},
+        mev_block::MevBlockWithClassified,
+        redefined_types::primitives::*,
+    },
+    implement_table_value_codecs_with_zc,
+    mev::{
+        bundle_id, AtomicArb, AtomicArbType, Bundle, BundleData, BundleHeader, CexDex, CexDexQuote,
+        JitLiquidity, JitLiquiditySandwich, Liquidation, Mev, MevType, Sandwich, SearcherTx,
+    },
+    FastHashMap, FastHashSet, GasDetails,
+};
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+    ValueEnum,
+    AsRefStr,
+)]
+#[archive(check_bytes)]
+pub enum OverrideKind {
+    /// The bundle isn't mev and is dropped
+    FalsePositive,
+    /// Brontes missed the bundle, it's added with the override's mev type,
+    /// searcher and profit
+    FalseNegative,
+    /// The bundle is of a different mev type
+    Reclassify,
+    /// The bundle belongs to a different searcher cluster
+    Recluster,
+    /// Revokes an earlier override
+    Revoke,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BundleOverride {
+    pub block_number: u64,
+    /// Transaction hash of the bundle, as in its header
+    pub tx_hash:      B256,
+    #[redefined(same_fields)]
+    pub kind:         OverrideKind,
+    /// Mev type of a reclassified or missed bundle
+    #[redefined(same_fields)]
+    pub mev_type:     MevType,
+    /// Searcher of a reclustered or missed bundle
+    pub searcher:     Option<Address>,
+    /// Profit of a missed bundle
+    pub profit_usd:   Option<f64>,
+    /// Id of the override a revocation revokes
+    pub revokes:      Option<u64>,
+    pub author:       String,
+    pub reason:       String,
+    /// Unix timestamp in seconds of when the override was made
+    pub created_at:   u64,
+}
+
+implement_table_value_codecs_with_zc!(BundleOverrideRedefined);
+
+impl BundleOverride {
+    fn new(
+        block_number: u64,
+        tx_hash: B256,
+        kind: OverrideKind,
+        author: String,
+        reason: String,
+    ) -> Self {
+        Self {
+            block_number,
+            tx_hash,
+            kind,
+            mev_type: MevType::default(),
+            searcher: None,
+            profit_usd: None,
+            revokes: None,
+            author,
+            reason,
+            created_at: audit_log::created_at(),
+        }
+    }
+
+    pub fn false_positive(
+        block_number: u64,
+        tx_hash: B256,
+        author: String,
+        reason: String,
+    ) -> Self {
+        Self::new(block_number, tx_hash, OverrideKind::FalsePositive, author, reason)
+    }
+
+    pub fn false_negative(
+        block_number: u64,
+        tx_hash: B256,
+        mev_type: MevType,
+        searcher: Address,
+        profit_usd: f64,
+        author: String,
+        reason: String,
+    ) -> Self {
+        Self {
+            mev_type,
+            searcher: Some(searcher),
+            profit_usd: Some(profit_usd),
+            ..Self::new(block_number, tx_hash, OverrideKind::FalseNegative, author, reason)
+        }
+    }
+
+    pub fn reclassify(
+        block_number: u64,
+        tx_hash: B256,
+        mev_type: MevType,
+        author: String,
+        reason: String,
+    ) -> Self {
+        Self {
+            mev_type,
+            ..Self::new(block_number, tx_hash, OverrideKind::Reclassify, author, reason)
+        }
+    }
+
+    pub fn recluster(
+        block_number: u64,
+        tx_hash: B256,
+        searcher: Address,
+        author: String,
+        reason: String,
+    ) -> Self {
+        Self {
+            searcher: Some(searcher),
+            ..Self::new(block_number, tx_hash, OverrideKind::Recluster, author, reason)
+        }
+    }
+
+    /// Revokes the override with the given id, the block and transaction are
+    /// copied from it
+    pub fn revoke(id: u64, revoked: &BundleOverride, author: String, reason: String) -> Self {
+        Self {
+            revokes: Some(id),
+            ..Self::new(revoked.block_number, revoked.tx_hash, OverrideKind::Revoke, author, reason)
+        }
+    }
+}
+
+impl AuditEntry for BundleOverride {
+    fn revokes(&self) -> Option<u64> {
+        (self.kind == OverrideKind::Revoke)
+            .then_some(self.revokes)
+            .flatten()
+    }
+}
+
+impl Display for BundleOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:?} in block {}", self.kind.as_ref(), self.tx_hash, self.block_number)?;
+        match self.kind {
+            OverrideKind::FalsePositive => {}
+            OverrideKind::FalseNegative => write!(
+                f,
+                " as {} by {:?} with ${:.2} profit",
+                self.mev_type,
+                self.searcher.unwrap_or_default(),
+                self.profit_usd.unwrap_or_default()
+            )?,
+            OverrideKind::Reclassify => write!(f, " as {}", self.mev_type)?,
+            OverrideKind::Recluster => write!(f, " to {:?}", self.searcher.unwrap_or_default())?,
+            OverrideKind::Revoke => write!(f, " revoking #{}", self.revokes.unwrap_or_default())?,
+        }
+        write!(f, " by {}: {}", self.author, self.reason)
+    }
+}
+
+/// The overrides in effect, with revoked overrides removed
+#[derive(Debug, Default, Clone)]
+pub struct BundleOverrides {
+    /// Overrides of each block in the order they were made
+    by_block: FastHashMap<u64, Vec<BundleOverride>>,
+}
+
+impl BundleOverrides {
+    /// Takes the overrides with their ids, in any order
+    pub fn new(overrides: Vec<(u64, BundleOverride)>) -> Self {
+        let mut by_block: FastHashMap<u64, Vec<BundleOverride>> = FastHashMap::default();
+        entries_in_effect(overrides)
+            .into_iter()
+            .for_each(|(_, o)| by_block.entry(o.block_number).or_default().push(o));
+
+        Self { by_block }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_block.is_empty()
+    }
+
+    /// Applies the overrides of the block to its bundles, later overrides of a
+    /// bundle take precedence over earlier ones
+    pub fn apply(&self, block: &mut MevBlockWithClassified) {
+        let Some(overrides) = self.by_block.get(&block.block.block_number) else { return };
+
+        let mut dropped = FastHashSet::default();
+        for o in overrides {
+            let bundle = block.mev.iter_mut().find(|b| b.header.tx_hash == o.tx_hash);
+
+            match (o.kind, bundle) {
+                (OverrideKind::FalsePositive, Some(_)) => {
+                    dropped.insert(o.tx_hash);
+                }
+                (OverrideKind::FalseNegative, None) => block.mev.push(missed_bundle(o)),
+                (OverrideKind::FalseNegative, Some(_)) => {
+                    dropped.remove(&o.tx_hash);
+                }
+                (OverrideKind::Reclassify, Some(bundle)) => reclassify(bundle, o.mev_type),
+                (OverrideKind::Recluster, Some(bundle)) => {
+                    if let Some(searcher) = o.searcher {
+                        bundle.header.eoa = searcher;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        block
+            .mev
+            .retain(|bundle| !dropped.contains(&bundle.header.tx_hash));
+    }
+}
+
+fn reclassify(bundle: &mut Bundle, mev_type: MevType) {
+    if bundle.data.mev_type() != mev_type {
+        let txs = transactions(&bundle.data);
+        bundle.data = data_of_type(mev_type, bundle.header.block_number, &txs);
+    }
+    bundle.header.mev_type = mev_type;
+    bundle.header.bundle_id = bundle.compute_bundle_id();
+}
+
+fn missed_bundle(o: &BundleOverride) -> Bundle {
+    let data = data_of_type(o.mev_type, o.block_number, &[(o.tx_hash, GasDetails::default())]);
+
+    Bundle {
+        header: BundleHeader {
+            block_number: o.block_number,
+            tx_hash: o.tx_hash,
+            bundle_id: bundle_id(o.mev_type, &data.mev_transaction_hashes()),
+            eoa: o.searcher.unwrap_or_default(),
+            mev_type: o.mev_type,
+            profit_usd: o.profit_usd.unwrap_or_default(),
+            ..Default::default()
+        },
+        data,
+    }
+}
+
+/// The bundle's transactions with their gas, in the order they landed
+fn transactions(data: &BundleData) -> Vec<(B256, GasDetails)> {
+    match data {
+        BundleData::Sandwich(s) => s
+            .frontrun_tx_hash
+            .iter()
+            .copied()
+            .zip(s.frontrun_gas_details.iter().copied())
+            .chain([(s.backrun_tx_hash, s.backrun_gas_details)])
+            .collect(),
+        BundleData::JitSandwich(s) => s
+            .frontrun_tx_hash
+            .iter()
+            .copied()
+            .zip(s.frontrun_gas_details.iter().copied())
+            .chain([(s.backrun_tx_hash, s.backrun_gas_details)])
+            .collect(),
+        BundleData::AtomicArb(a) => [(a.tx_hash, a.gas_details)]
+            .into_iter()
+            .chain(
+                a.leg_tx_hashes
+                    .iter()
+                    .copied()
+                    .zip(a.leg_gas_details.iter().copied()),
+            )
+            .collect(),
+        BundleData::Jit(j) => vec![
+            (j.frontrun_mint_tx_hash, j.frontrun_mint_gas_details),
+            (j.backrun_burn_tx_hash, j.backrun_burn_gas_details),
+        ],
+        BundleData::CexDex(c) => vec![(c.tx_hash, c.gas_details)],
+        BundleData::CexDexQuote(c) => vec![(c.tx_hash, c.gas_details)],
+        BundleData::Liquidation(l) => vec![(l.liquidation_tx_hash, l.gas_details)],
+        BundleData::Unknown(s) => vec![(s.tx_hash, s.gas_details)],
+    }
+}
+
+/// Data of the mev type holding only the transactions. Types without data of
+/// their own are held as a searcher tx of the first transaction
+fn data_of_type(mev_type: MevType, block_number: u64, txs: &[(B256, GasDetails)]) -> BundleData {
+    let (first, first_gas) = txs.first().copied().unwrap_or_default();
+    let (last, last_gas) = txs.last().copied().unwrap_or_default();
+    // the gas of a single transaction is only counted once
+    let burn_gas = if txs.len() > 1 { last_gas } else { GasDetails::default() };
+    let (front, front_gas): (Vec<_>, Vec<_>) =
+        txs[..txs.len().saturating_sub(1)].iter().copied().unzip();
+
+    match mev_type {
+        MevType::Sandwich => BundleData::Sandwich(Sandwich {
+            block_number,
+            frontrun_tx_hash: front,
+            frontrun_gas_details: front_gas,
+            backrun_tx_hash: last,
+            backrun_gas_details: last_gas,
+            ..Default::default()
+        }),
+        MevType::JitSandwich => BundleData::JitSandwich(JitLiquiditySandwich {
+            block_number,
+            frontrun_tx_hash: front,
+            frontrun_gas_details: front_gas,
+            backrun_tx_hash: last,
+            backrun_gas_details: last_gas,
+            ..Default::default()
+        }),
+        MevType::Jit | MevType::JitExtended => BundleData::Jit(JitLiquidity {
+            block_number,
+            frontrun_mint_tx_hash: first,
+            frontrun_mint_gas_details: first_gas,
+            backrun_burn_tx_hash: last,
+            backrun_burn_gas_details: burn_gas,
+            // an extended jit's burn lands in a later block
+            backrun_block_number: block_number + (mev_type == MevType::JitExtended) as u64,
+            ..Default::default()
+        }),
+        MevType::AtomicArb | MevType::Rebalance => {
+            let (legs, leg_gas) = txs.iter().skip(1).copied().unzip();
+            BundleData::AtomicArb(AtomicArb {
+                tx_hash: first,
+                block_number,
+                gas_details: first_gas,
+                arb_type: if mev_type == MevType::Rebalance {
+                    AtomicArbType::Rebalance
+                } else {
+                    AtomicArbType::default()
+                },
+                leg_tx_hashes: legs,
+                leg_gas_details: leg_gas,
+                ..Default::default()
+            })
+        }
+        MevType::CexDexTrades => BundleData::CexDex(CexDex {
+            tx_hash: first,
+            block_number,
+            gas_details: first_gas,
+            ..Default::default()
+        }),
+        MevType::CexDexQuotes => BundleData::CexDexQuote(CexDexQuote {
+            tx_hash: first,
+            block_number,
+            gas_details: first_gas,
+            ..Default::default()
+        }),
+        MevType::Liquidation => BundleData::Liquidation(Liquidation {
+            liquidation_tx_hash: first,
+            block_number,
+            gas_details: first_gas,
+            ..Default::default()
+        }),
+        MevType::CexDexRfq | MevType::JitCexDex | MevType::SearcherTx | MevType::Unknown => {
+            BundleData::Unknown(SearcherTx {
+                tx_hash: first,
+                block_number,
+                gas_details: first_gas,
+                ..Default::default()
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::test_utils::bundle, mev::MevBlock};
+
+    #[test]
+    fn test_apply_overrides() {
+        let mut block = MevBlockWithClassified {
+            block: MevBlock { block_number: 1, ..Default::default() },
+            mev:   [1, 2, 3]
+                .map(|tx| {
+                    bundle()
+                        .block_number(1)
+                        .tx_hash(B256::repeat_byte(tx))
+                        .eoa(Address::repeat_byte(tx))
+                        .mev_type(MevType::Sandwich)
+                        .data(BundleData::Sandwich(Sandwich {
+                            block_number: 1,
+                            backrun_tx_hash: B256::repeat_byte(tx),
+                            ..Default::default()
+                        }))
+                        .build()
+                })
+                .to_vec(),
+        };
+        let by = || "alice".to_string();
+        let why = || "checked by hand".to_string();
+
+        let revoked = BundleOverride::false_positive(1, B256::repeat_byte(3), by(), why());
+        let overrides = BundleOverrides::new(vec![
+            (0, BundleOverride::false_positive(1, B256::repeat_byte(1), by(), why())),
+            (1, BundleOverride::reclassify(1, B256::repeat_byte(2), MevType::Jit, by(), why())),
+            (
+                2,
+                BundleOverride::recluster(
+                    1,
+                    B256::repeat_byte(2),
+                    Address::repeat_byte(9),
+                    by(),
+                    why(),
+                ),
+            ),
+            (4, BundleOverride::revoke(3, &revoked, by(), why())),
+            (3, revoked),
+            (
+                5,
+                BundleOverride::false_negative(
+                    1,
+                    B256::repeat_byte(4),
+                    MevType::AtomicArb,
+                    Address::repeat_byte(4),
+                    10.0,
+                    by(),
+                    why(),
+                ),
+            ),
+        ]);
+        overrides.apply(&mut block);
+
+        let txs = block
+            .mev
+            .iter()
+            .map(|b| b.header.tx_hash)
+            .collect::<Vec<_>>();
+        assert_eq!(txs, [2, 3, 4].map(B256::repeat_byte).to_vec());
+        assert_eq!(block.mev[0].header.mev_type, MevType::Jit);
+        assert_eq!(block.mev[0].header.eoa, Address::repeat_byte(9));
+        assert_eq!(block.mev[2].header.mev_type, MevType::AtomicArb);
+        assert_eq!(block.mev[2].header.profit_usd, 10.0);
+
+        // the data of the reclassified and missed bundles matches their headers
+        for bundle in &block.mev {
+            assert_eq!(bundle.data.mev_type(), bundle.header.mev_type);
+            assert_eq!(bundle.header.bundle_id, bundle.compute_bundle_id());
+            assert!(bundle
+                .data
+                .mev_transaction_hashes()
+                .iter()
+                .all(|tx| *tx == bundle.header.tx_hash));
+        }
+    }
+
+    #[test]
+    fn test_reclassify_keeps_transactions() {
+        let gas = |used| GasDetails { gas_used: used, ..Default::default() };
+        let txs = [1, 2, 3].map(B256::repeat_byte);
+        let mut bundle = Bundle {
+            header: BundleHeader {
+                block_number: 1,
+                mev_type: MevType::Sandwich,
+                ..Default::default()
+            },
+            data:   BundleData::Sandwich(Sandwich {
+                block_number: 1,
+                frontrun_tx_hash: txs[..2].to_vec(),
+                frontrun_gas_details: vec![gas(1), gas(2)],
+                backrun_tx_hash: txs[2],
+                backrun_gas_details: gas(3),
+                ..Default::default()
+            }),
+        };
+        let gas_paid = bundle.data.total_gas_paid();
+
+        // data already of the type is kept as it is
+        reclassify(&mut bundle, MevType::Sandwich);
+        assert!(matches!(bundle.data, BundleData::Sandwich(_)));
+
+        reclassify(&mut bundle, MevType::AtomicArb);
+        let BundleData::AtomicArb(arb) = &bundle.data else { panic!("not an arb") };
+        assert_eq!(arb.tx_hash, txs[0]);
+        assert_eq!(arb.leg_tx_hashes, txs[1..].to_vec());
+        assert_eq!(bundle.data.total_gas_paid(), gas_paid);
+        assert_eq!(bundle.header.bundle_id, bundle_id(MevType::AtomicArb, &txs));
+    }
+}
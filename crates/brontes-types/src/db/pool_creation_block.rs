@@ -8,6 +8,7 @@ use crate::{db::redefined_types::primitives::*, implement_table_value_codecs_wit
 
 #[derive(Debug, Default, Clone, Row, PartialEq, Serialize, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct PoolsToAddresses(pub Vec<Address>);
 
 implement_table_value_codecs_with_zc!(PoolsToAddressesRedefined);
@@ -0,0 +1,207 @@
+//! Per pool price series derived from the classified swaps.
+//!
+//! The dex pricing only keeps the prices it needs to value the tokens of a
+//! block, so price impact or manipulation over a longer window can't be
+//! measured from it. Each processed block records a [`PoolPriceObservation`]
+//! for every pool that was swapped through, from the execution prices of its
+//! swaps. [`pool_twap`] weights the observations of a pool by how long their
+//! price held, using the block timestamps.
+//!
+//! Prices are quoted in the pool's token1 per token0, with the tokens ordered
+//! by address as in uniswap pools, so observations of a pool stay comparable
+//! whatever the direction of the swaps.
+use std::sync::Arc;
+
+use alloy_primitives::Address;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::redefined_types::primitives::*,
+    implement_table_value_codecs_with_zc,
+    normalized_actions::{Action, NormalizedSwap},
+    tree::BlockTree,
+    FastHashMap, Protocol, ToFloatNearest, TreeSearchBuilder,
+};
+
+/// The swaps through a pool in a block
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct PoolPriceObservation {
+    pub pool:       Address,
+    #[redefined(same_fields)]
+    pub protocol:   Protocol,
+    pub token0:     Address,
+    pub token1:     Address,
+    /// Execution price of the last swap of the block, token1 per token0
+    pub close:      f64,
+    /// Volume weighted execution price of the block's swaps
+    pub vwap:       f64,
+    /// Amount of token0 swapped
+    pub volume0:    f64,
+    pub swap_count: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockPoolPrices {
+    pub timestamp:    u64,
+    pub observations: Vec<PoolPriceObservation>,
+}
+
+implement_table_value_codecs_with_zc!(BlockPoolPricesRedefined);
+
+impl BlockPoolPrices {
+    pub fn from_tree(tree: Arc<BlockTree<Action>>) -> Self {
+        let timestamp = tree.header.timestamp;
+        let mut pools: FastHashMap<Address, PoolPriceObservation> = FastHashMap::default();
+
+        // collected in tx order and in order within each tx, so the last swap
+        // seen of a pool is its close
+        for swap in tree
+            .collect_all(TreeSearchBuilder::default().with_action(Action::is_swap))
+            .flat_map(|(_, actions)| actions)
+            .filter_map(Action::try_swaps_merged)
+        {
+            let Some((token0, token1, amount0, price)) = swap_price(&swap) else { continue };
+
+            let observation = pools
+                .entry(swap.pool)
+                .or_insert_with(|| PoolPriceObservation {
+                    pool: swap.pool,
+                    protocol: swap.protocol,
+                    token0,
+                    token1,
+                    ..Default::default()
+                });
+            // batch settlements trade several pairs through the same address
+            if observation.token0 != token0 || observation.token1 != token1 {
+                continue
+            }
+
+            observation.vwap = (observation.vwap * observation.volume0 + price * amount0)
+                / (observation.volume0 + amount0);
+            observation.volume0 += amount0;
+            observation.close = price;
+            observation.swap_count += 1;
+        }
+
+        Self { timestamp, observations: pools.into_values().collect() }
+    }
+
+    pub fn observation(&self, pool: Address) -> Option<&PoolPriceObservation> {
+        self.observations.iter().find(|o| o.pool == pool)
+    }
+}
+
+/// The pool's tokens ordered by address, the amount of token0 swapped and the
+/// execution price in token1 per token0
+fn swap_price(swap: &NormalizedSwap) -> Option<(Address, Address, f64, f64)> {
+    let amount_in = swap.amount_in.clone().to_float();
+    let amount_out = swap.amount_out.clone().to_float();
+    if amount_in <= 0.0 || amount_out <= 0.0 {
+        return None
+    }
+
+    let (token_in, token_out) = (swap.token_in.address, swap.token_out.address);
+    if token_in < token_out {
+        Some((token_in, token_out, amount_in, amount_out / amount_in))
+    } else {
+        Some((token_out, token_in, amount_out, amount_in / amount_out))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PoolTwap {
+    pub pool:         Address,
+    pub token0:       Address,
+    pub token1:       Address,
+    /// Time weighted close price, token1 per token0
+    pub twap:         f64,
+    pub min:          f64,
+    pub max:          f64,
+    /// Blocks of the window the pool was swapped in
+    pub observations: usize,
+    /// Seconds from the first observation to the end of the window
+    pub duration:     u64,
+}
+
+/// Time weighted average of the pool's close prices over the blocks, which
+/// should be ordered by block number. Each close holds until the pool's next
+/// observation, the last one until the timestamp of the last block. Blocks
+/// before the pool's first observation in the window aren't weighted, as its
+/// price there is unknown
+pub fn pool_twap(blocks: &[(u64, BlockPoolPrices)], pool: Address) -> Option<PoolTwap> {
+    let window_end = blocks.last()?.1.timestamp;
+    let observations = blocks
+        .iter()
+        .filter_map(|(_, block)| Some((block.timestamp, block.observation(pool)?)))
+        .collect::<Vec<_>>();
+    let (_, first) = observations.first()?;
+
+    let mut weighted = 0.0;
+    let mut duration = 0;
+    let (mut min, mut max) = (f64::MAX, f64::MIN);
+    for (i, (timestamp, observation)) in observations.iter().enumerate() {
+        let until = observations
+            .get(i + 1)
+            .map_or(window_end, |(next, _)| *next);
+        let held = until.saturating_sub(*timestamp);
+
+        weighted += observation.close * held as f64;
+        duration += held;
+        min = min.min(observation.close);
+        max = max.max(observation.close);
+    }
+
+    // a single block window, nothing held for any time
+    let twap =
+        if duration == 0 { observations.last()?.1.close } else { weighted / duration as f64 };
+
+    Some(PoolTwap {
+        pool,
+        token0: first.token0,
+        token1: first.token1,
+        twap,
+        min,
+        max,
+        observations: observations.len(),
+        duration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_twap() {
+        let pool = Address::repeat_byte(1);
+        let block = |timestamp, close: Option<f64>| BlockPoolPrices {
+            timestamp,
+            observations: close
+                .map(|close| PoolPriceObservation { pool, close, ..Default::default() })
+                .into_iter()
+                .collect(),
+        };
+        let blocks = vec![
+            (1, block(0, None)),
+            (2, block(12, Some(10.0))),
+            (3, block(24, None)),
+            (4, block(36, Some(20.0))),
+            (5, block(48, None)),
+        ];
+
+        // 10 held for 24s, 20 for 12s
+        let twap = pool_twap(&blocks, pool).unwrap();
+        assert_eq!(twap.observations, 2);
+        assert_eq!(twap.duration, 36);
+        assert!((twap.twap - 40.0 / 3.0).abs() < 1e-9);
+        assert_eq!((twap.min, twap.max), (10.0, 20.0));
+
+        assert!(pool_twap(&blocks, Address::ZERO).is_none());
+    }
+}
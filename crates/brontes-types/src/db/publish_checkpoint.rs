@@ -0,0 +1,29 @@
+//! Progress of the sinks publishing the stored results to external systems.
+//!
+//! A sink publishes the committed blocks in order and stores the last block it
+//! published under the [`checkpoint_key`] of its name, so a restarted indexer
+//! continues from the next block instead of publishing everything again.
+use alloy_primitives::keccak256;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct PublishCheckpoint {
+    pub sink:            String,
+    /// Last block published, every block before it was published too
+    pub published_up_to: u64,
+    /// Unix time in seconds the checkpoint was written at
+    pub published_at:    u64,
+}
+
+implement_table_value_codecs_with_zc!(PublishCheckpointRedefined);
+
+/// Key of a sink's checkpoint in the db
+pub fn checkpoint_key(sink: &str) -> u64 {
+    u64::from_be_bytes(keccak256(sink.as_bytes())[..8].try_into().unwrap())
+}
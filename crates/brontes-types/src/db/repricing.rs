@@ -0,0 +1,202 @@
+//! Re-pricing of stored bundles.
+//!
+//! Price data keeps improving after a block is processed, as dex quotes are
+//! backfilled and token coverage grows, and rerunning the inspectors over
+//! history to pick it up is too expensive. [`reprice_bundle`] recomputes the
+//! usd values of a stored bundle from its balance deltas and gas with the
+//! block's current price tables instead. Each run is appended to the block's
+//! [`BlockRepricings`] tagged with its methodology and the values it replaced,
+//! so runs can be compared. Like the manual overrides the raw mev blocks are
+//! left untouched, so their checksums still hold, [`BlockRepricings::apply`]
+//! applies the latest prices, block totals included, as a view on top of them.
+use alloy_primitives::{Address, B256};
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::{
+        dex::BlockPrice, metadata::Metadata, mev_block::MevBlockWithClassified,
+        redefined_types::primitives::*,
+    },
+    implement_table_value_codecs_with_zc,
+    mev::{Bundle, BundleHeader, Mev, MevType},
+    pair::Pair,
+    ToFloatNearest,
+};
+
+/// Tag of the current re-pricing methodology: searcher token deltas valued at
+/// the block's average dex price, gas at the block's eth price
+pub const REPRICING_METHODOLOGY: &str = "dex-block-average-v1";
+
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BundlePricing {
+    pub revenue_usd: f64,
+    pub bribe_usd:   f64,
+    pub profit_usd:  f64,
+}
+
+impl BundlePricing {
+    pub fn of(header: &BundleHeader) -> Self {
+        Self {
+            revenue_usd: header.profit_usd + header.bribe_usd,
+            bribe_usd:   header.bribe_usd,
+            profit_usd:  header.profit_usd,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BundleRepricing {
+    /// Id of the bundle, as in its header
+    pub bundle_id:   B256,
+    pub methodology: String,
+    /// Unix timestamp in seconds of the run
+    pub repriced_at: u64,
+    /// Values before the run, from the previous run or the original
+    pub previous:    BundlePricing,
+    pub repriced:    BundlePricing,
+}
+
+/// The re-pricing runs over a block's bundles, in the order they were made
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockRepricings {
+    pub repricings: Vec<BundleRepricing>,
+}
+
+implement_table_value_codecs_with_zc!(BlockRepricingsRedefined);
+
+impl BlockRepricings {
+    pub fn latest(&self, bundle_id: B256) -> Option<&BundleRepricing> {
+        self.repricings
+            .iter()
+            .rev()
+            .find(|r| r.bundle_id == bundle_id)
+    }
+
+    /// Sets the usd values of the block's bundles to their latest re-pricing,
+    /// and moves the block's mev profit total by the change in the profits it
+    /// counts
+    pub fn apply(&self, block: &mut MevBlockWithClassified) {
+        for bundle in &mut block.mev {
+            let Some(repricing) = self.latest(bundle.header.bundle_id) else { continue };
+
+            if !matches!(bundle.mev_type(), MevType::SearcherTx | MevType::Rebalance) {
+                block.block.total_mev_profit_usd +=
+                    repricing.repriced.profit_usd - bundle.header.profit_usd;
+            }
+            bundle.header.profit_usd = repricing.repriced.profit_usd;
+            bundle.header.bribe_usd = repricing.repriced.bribe_usd;
+        }
+    }
+}
+
+/// Prices the bundle with the block's metadata. Bundles of cex priced mev
+/// types aren't repriced as their profit depends on cex quotes at the time of
+/// the trade. Deltas of tokens that still have no price keep their value
+pub fn reprice_bundle(
+    bundle: &Bundle,
+    metadata: &Metadata,
+    quote: Address,
+) -> Option<BundlePricing> {
+    if bundle.mev_type().use_cex_pricing_for_deltas() {
+        return None
+    }
+
+    let searcher = |address: Address| {
+        address == bundle.header.eoa || Some(address) == bundle.header.mev_contract
+    };
+    let price = |token: Address| {
+        if token == quote {
+            Some(1.0)
+        } else if metadata.is_nft_collection(token) {
+            metadata
+                .get_nft_floor_price(token, quote)
+                .map(|p| p.to_float())
+        } else {
+            metadata
+                .dex_quotes
+                .as_ref()?
+                .price_for_block(Pair(token, quote), BlockPrice::Average)
+                .map(|p| p.to_float())
+        }
+    };
+
+    let revenue_usd = bundle
+        .header
+        .balance_deltas
+        .iter()
+        .flat_map(|tx| &tx.address_deltas)
+        .filter(|deltas| searcher(deltas.address))
+        .flat_map(|deltas| &deltas.token_deltas)
+        .map(|delta| {
+            price(delta.token.address)
+                .map(|price| delta.amount * price)
+                .unwrap_or(delta.usd_value)
+        })
+        .sum::<f64>();
+    let bribe_usd = metadata
+        .get_gas_price_usd(bundle.data.total_gas_paid(), quote)
+        .to_float();
+
+    Some(BundlePricing { revenue_usd, bribe_usd, profit_usd: revenue_usd - bribe_usd })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mev::BundleData;
+
+    #[test]
+    fn test_applies_latest_repricing() {
+        let id = B256::repeat_byte(1);
+        let mut block = MevBlockWithClassified::default();
+        block.block.total_mev_profit_usd = 14.0;
+        block.mev.push(Bundle {
+            header: BundleHeader {
+                tx_hash: B256::repeat_byte(2),
+                bundle_id: id,
+                mev_type: MevType::AtomicArb,
+                profit_usd: 10.0,
+                bribe_usd: 2.0,
+                ..Default::default()
+            },
+            data:   BundleData::default(),
+        });
+        // searcher transactions don't count towards the block's mev profit
+        block.mev.push(Bundle {
+            header: BundleHeader {
+                bundle_id: B256::repeat_byte(3),
+                mev_type: MevType::SearcherTx,
+                profit_usd: 1.0,
+                ..Default::default()
+            },
+            data:   BundleData::default(),
+        });
+
+        let original = BundlePricing::of(&block.mev[0].header);
+        assert_eq!(original.revenue_usd, 12.0);
+
+        let run = |previous, profit_usd| BundleRepricing {
+            bundle_id: id,
+            methodology: REPRICING_METHODOLOGY.to_string(),
+            repriced_at: 0,
+            previous,
+            repriced: BundlePricing { revenue_usd: profit_usd + 3.0, bribe_usd: 3.0, profit_usd },
+        };
+        let first = run(original, 20.0);
+        let repricings =
+            BlockRepricings { repricings: vec![first.clone(), run(first.repriced, 15.0)] };
+
+        repricings.apply(&mut block);
+        assert_eq!(block.mev[0].header.profit_usd, 15.0);
+        assert_eq!(block.mev[0].header.bribe_usd, 3.0);
+        assert_eq!(block.block.total_mev_profit_usd, 19.0);
+    }
+}
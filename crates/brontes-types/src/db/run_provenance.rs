@@ -0,0 +1,29 @@
+//! How each run was configured.
+//!
+//! Results of runs configured differently aren't comparable, a run scoped to a
+//! subset of protocols finds less mev than a full one. Each `brontes run`
+//! stores its configuration under a run id, increasing with each run, so the
+//! results of a range can be traced back to the runs that produced them.
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct RunProvenance {
+    /// Unix time in seconds the run started at
+    pub started_at:    u64,
+    /// Version of brontes the run was made with
+    pub version:       String,
+    pub start_block:   Option<u64>,
+    pub end_block:     Option<u64>,
+    /// Inspectors the run was made with, empty if all of them ran
+    pub inspectors:    Vec<String>,
+    /// The action filter the inspectors were scoped with, as json
+    pub action_filter: Option<String>,
+}
+
+implement_table_value_codecs_with_zc!(RunProvenanceRedefined);
@@ -0,0 +1,76 @@
+//! Bookkeeping of the maintenance jobs run by the embedded scheduler.
+//!
+//! Each job's last run is stored under the [`job_key`] of its name, so a
+//! restarted indexer picks up the schedule where it left off instead of
+//! running every job again at startup.
+use alloy_primitives::keccak256;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::implement_table_value_codecs_with_zc;
+
+#[derive(Debug, Default, PartialEq, Clone, Eq, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct JobRun {
+    pub name:                 String,
+    /// Unix time in seconds the last run started at
+    pub last_started:         u64,
+    /// Unix time in seconds of the last run that succeeded
+    pub last_succeeded:       Option<u64>,
+    /// Runs that failed, after their retries, since the last success
+    pub consecutive_failures: u64,
+    /// Error of the last run, if it failed
+    pub last_error:           Option<String>,
+}
+
+implement_table_value_codecs_with_zc!(JobRunRedefined);
+
+impl JobRun {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), ..Default::default() }
+    }
+
+    /// Records the outcome of a run that started at `started`
+    pub fn record(&mut self, started: u64, result: &eyre::Result<()>) {
+        self.last_started = started;
+        match result {
+            Ok(()) => {
+                self.last_succeeded = Some(started);
+                self.consecutive_failures = 0;
+                self.last_error = None;
+            }
+            Err(e) => {
+                self.consecutive_failures += 1;
+                self.last_error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+/// Key of a job's runs in the db
+pub fn job_key(name: &str) -> u64 {
+    u64::from_be_bytes(keccak256(name.as_bytes())[..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_job_run_bookkeeping() {
+        let mut run = JobRun::new("test");
+        run.record(10, &Err(eyre::eyre!("db busy")));
+        run.record(20, &Err(eyre::eyre!("db busy")));
+        assert_eq!(run.consecutive_failures, 2);
+        assert_eq!(run.last_succeeded, None);
+        assert_eq!(run.last_error.as_deref(), Some("db busy"));
+
+        run.record(30, &Ok(()));
+        assert_eq!(run.last_started, 30);
+        assert_eq!(run.last_succeeded, Some(30));
+        assert_eq!(run.consecutive_failures, 0);
+        assert_eq!(run.last_error, None);
+    }
+}
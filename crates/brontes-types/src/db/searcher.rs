@@ -1,6 +1,6 @@
 use std::{fmt, ops::Add};
 
-use alloy_primitives::Address;
+use alloy_primitives::{keccak256, Address};
 use clickhouse::Row;
 use redefined::{self_convert_redefined, Redefined};
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
@@ -10,14 +10,17 @@ use serde_with::serde_as;
 use strum::AsRefStr;
 
 use crate::{
-    db::redefined_types::primitives::AddressRedefined,
-    implement_table_value_codecs_with_zc,
-    mev::{BundleHeader, MevCount, MevType},
+    db::{
+        redefined_types::primitives::AddressRedefined, searcher_versions,
+        wallet_funding::FundingTrace,
+    },
+    mev::{Bundle, BundleData, BundleHeader, MevCount, MevType},
     serde_utils::{addresss, option_addresss, vec_address},
 };
 
 #[derive(Debug, Default, Row, PartialEq, Clone, Serialize, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct SearcherInfo {
     #[serde(default)]
     pub name:              Option<String>,
@@ -45,6 +48,13 @@ pub struct SearcherInfo {
     #[serde(with = "vec_address")]
     #[serde(default)]
     pub sibling_searchers: Vec<Address>,
+    /// The searcher's recent atomic arbs
+    #[redefined(same_fields)]
+    #[serde(default)]
+    pub arb_history:       ArbHistory,
+    /// Where the eoa's funds came from, traced when it was first seen
+    #[serde(default)]
+    pub funding:           Option<FundingTrace>,
 }
 
 impl SearcherInfo {
@@ -69,12 +79,12 @@ impl SearcherInfo {
             MevType::CexDexRfq => self.mev_count.cex_dex_rfq_count,
             MevType::JitCexDex => self.mev_count.jit_cex_dex_count,
             MevType::Sandwich => self.mev_count.sandwich_count,
-            MevType::Jit => self.mev_count.jit_count,
+            MevType::Jit | MevType::JitExtended => self.mev_count.jit_count,
             MevType::JitSandwich => self.mev_count.jit_sandwich_count,
             MevType::AtomicArb => self.mev_count.atomic_backrun_count,
             MevType::Liquidation => self.mev_count.liquidation_count,
             MevType::SearcherTx => self.mev_count.searcher_tx_count,
-            MevType::Unknown => None,
+            MevType::Rebalance | MevType::Unknown => None,
         }
     }
 
@@ -167,14 +177,61 @@ impl SearcherInfo {
         parts.join(" ")
     }
 
-    pub fn update_with_bundle(&mut self, header: &BundleHeader) {
+    pub fn update_with_bundle(&mut self, bundle: &Bundle) {
+        let header = &bundle.header;
+        // a rebalance isn't mev, and keeping it out of the arb history keeps the
+        // history from confirming its own relabels
+        if header.mev_type == MevType::Rebalance {
+            return
+        }
+
         self.pnl.account_pnl(header);
         self.mev_count.increment_count(header.mev_type);
         self.gas_bids.account_gas(header);
+
+        if let Some(arb) = ArbRecord::from_bundle(bundle) {
+            self.arb_history.record(arb);
+        }
+    }
+}
+
+// searcher info is stored versioned, see [`searcher_versions`]
+impl alloy_rlp::Encodable for SearcherInfoRedefined {
+    fn encode(&self, out: &mut dyn bytes::BufMut) {
+        out.put_slice(&searcher_versions::encode_searcher_info(self))
     }
 }
 
-implement_table_value_codecs_with_zc!(SearcherInfoRedefined);
+impl alloy_rlp::Decodable for SearcherInfoRedefined {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        searcher_versions::decode_searcher_info(buf).map_err(|e| {
+            tracing::error!(%e, "failed to decode searcher info");
+            alloy_rlp::Error::Custom("undecodable searcher info")
+        })
+    }
+}
+
+impl reth_db::table::Compress for SearcherInfoRedefined {
+    type Compressed = Vec<u8>;
+
+    fn compress_to_buf<B: reth_primitives::bytes::BufMut + AsMut<[u8]>>(self, buf: &mut B) {
+        let mut encoded = Vec::new();
+        alloy_rlp::Encodable::encode(&self, &mut encoded);
+        let encoded_compressed = zstd::encode_all(&*encoded, 0).unwrap();
+
+        buf.put_slice(&encoded_compressed);
+    }
+}
+
+impl reth_db::table::Decompress for SearcherInfoRedefined {
+    fn decompress<B: AsRef<[u8]>>(value: B) -> Result<Self, reth_db::DatabaseError> {
+        let encoded_decompressed =
+            zstd::decode_all(value.as_ref()).map_err(|_| reth_db::DatabaseError::Decode)?;
+        let buf = &mut encoded_decompressed.as_slice();
+
+        alloy_rlp::Decodable::decode(buf).map_err(|_| reth_db::DatabaseError::Decode)
+    }
+}
 
 #[serde_as]
 #[derive(
@@ -189,6 +246,7 @@ implement_table_value_codecs_with_zc!(SearcherInfoRedefined);
     rDeserialize,
     Archive,
 )]
+#[archive(check_bytes)]
 pub struct TollByType {
     pub total:          f64,
     pub sandwich:       Option<f64>,
@@ -203,6 +261,132 @@ pub struct TollByType {
 
 self_convert_redefined!(TollByType);
 
+/// Arbs kept in a searcher's [`ArbHistory`]
+pub const ARB_HISTORY_LEN: usize = 32;
+/// Arbs in the history before it is used to tell rebalancing apart
+const MIN_ARB_HISTORY: usize = 8;
+/// Arbs of the same size or pools that make the searcher's trades repetitive
+const MIN_REPEATS: usize = 5;
+/// Relative difference under which two arbs are of the same size
+const SIZE_TOLERANCE: f64 = 0.01;
+/// Profit over size under which an arb made nothing after inventory
+/// accounting
+const MAX_REBALANCE_MARGIN: f64 = 0.001;
+
+/// An atomic arb as it is kept in the searcher's history
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+)]
+#[archive(check_bytes)]
+pub struct ArbRecord {
+    /// Usd value of the capital the arb moved
+    pub size_usd:   f64,
+    /// Key of the set of pools the arb swapped through
+    pub pools:      u64,
+    pub profit_usd: f64,
+}
+
+impl ArbRecord {
+    /// The record of an atomic arb, `None` for any other bundle including the
+    /// arbs relabeled as rebalances
+    pub fn from_bundle(bundle: &Bundle) -> Option<Self> {
+        if bundle.header.mev_type != MevType::AtomicArb {
+            return None
+        }
+        let BundleData::AtomicArb(arb) = &bundle.data else { return None };
+
+        Some(Self {
+            size_usd:   bundle.header.capital.capital_deployed_usd,
+            pools:      pool_set_key(arb.swaps.iter().map(|swap| swap.pool)),
+            profit_usd: bundle.header.profit_usd,
+        })
+    }
+
+    fn margin(&self) -> f64 {
+        if self.size_usd > 0.0 {
+            self.profit_usd / self.size_usd
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Order independent key of a set of pools
+fn pool_set_key(pools: impl Iterator<Item = Address>) -> u64 {
+    let mut pools = pools.collect::<Vec<_>>();
+    pools.sort_unstable();
+    pools.dedup();
+
+    let hash = keccak256(
+        pools
+            .iter()
+            .flat_map(|pool| pool.as_slice())
+            .copied()
+            .collect::<Vec<u8>>(),
+    );
+    u64::from_be_bytes(hash[..8].try_into().unwrap())
+}
+
+/// The recent atomic arbs of a searcher. Market makers rebalancing inventory
+/// produce swaps that look like arbs, but they repeat the same size or the
+/// same pools and net nothing over time, which a single bundle doesn't show
+#[derive(
+    Debug, Default, PartialEq, Clone, Serialize, Deserialize, rSerialize, rDeserialize, Archive,
+)]
+#[archive(check_bytes)]
+pub struct ArbHistory {
+    /// Oldest first, at most [`ARB_HISTORY_LEN`]
+    pub recent: Vec<ArbRecord>,
+}
+
+self_convert_redefined!(ArbHistory);
+
+impl ArbHistory {
+    pub fn record(&mut self, arb: ArbRecord) {
+        if self.recent.len() >= ARB_HISTORY_LEN {
+            self.recent.remove(0);
+        }
+        self.recent.push(arb);
+    }
+
+    /// Whether the arb is more likely the searcher rebalancing inventory: it
+    /// made nothing after inventory accounting, the searcher's recent arbs
+    /// netted nothing either, and they keep trading the same size or the same
+    /// pools
+    pub fn is_rebalance(&self, arb: &ArbRecord) -> bool {
+        if self.recent.len() < MIN_ARB_HISTORY || arb.margin() > MAX_REBALANCE_MARGIN {
+            return false
+        }
+
+        let (size, profit) = self.recent.iter().fold((0.0, 0.0), |(size, profit), past| {
+            (size + past.size_usd, profit + past.profit_usd)
+        });
+        if size <= 0.0 || profit / size > MAX_REBALANCE_MARGIN {
+            return false
+        }
+
+        let repeats = |same: &dyn Fn(&ArbRecord) -> bool| {
+            self.recent.iter().filter(|past| same(past)).count() >= MIN_REPEATS
+        };
+        let fixed_size = arb.size_usd > 0.0
+            && repeats(&|past| {
+                (past.size_usd - arb.size_usd).abs() <= arb.size_usd * SIZE_TOLERANCE
+            });
+        let same_pools = repeats(&|past| past.pools == arb.pools);
+
+        fixed_size || same_pools
+    }
+}
+
 impl TollByType {
     pub fn account_pnl(&mut self, header: &BundleHeader) {
         self.total += header.profit_usd;
@@ -231,7 +415,9 @@ impl TollByType {
                         .add(header.profit_usd),
                 )
             }
-            MevType::Jit => self.jit = Some(self.jit.unwrap_or_default().add(header.profit_usd)),
+            MevType::Jit | MevType::JitExtended => {
+                self.jit = Some(self.jit.unwrap_or_default().add(header.profit_usd))
+            }
             MevType::JitSandwich => {
                 self.jit_sandwich =
                     Some(self.jit_sandwich.unwrap_or_default().add(header.profit_usd))
@@ -273,7 +459,9 @@ impl TollByType {
                         .add(header.bribe_usd),
                 )
             }
-            MevType::Jit => self.jit = Some(self.jit.unwrap_or_default().add(header.bribe_usd)),
+            MevType::Jit | MevType::JitExtended => {
+                self.jit = Some(self.jit.unwrap_or_default().add(header.bribe_usd))
+            }
             MevType::JitSandwich => {
                 self.jit_sandwich =
                     Some(self.jit_sandwich.unwrap_or_default().add(header.bribe_usd))
@@ -303,6 +491,7 @@ impl TollByType {
     PartialOrd,
     Hash,
 )]
+#[archive(check_bytes)]
 pub enum Fund {
     #[default]
     None,
@@ -434,3 +623,53 @@ pub enum SearcherEoaContract {
     EOA      = 0,
     Contract = 1,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arb(size_usd: f64, pools: u64, profit_usd: f64) -> ArbRecord {
+        ArbRecord { size_usd, pools, profit_usd }
+    }
+
+    #[test]
+    fn test_detects_rebalancing_history() {
+        let mut history = ArbHistory::default();
+        for i in 0..10 {
+            history.record(arb(50_000.0, i, if i % 2 == 0 { 5.0 } else { -5.0 }));
+        }
+        assert!(history.is_rebalance(&arb(50_100.0, 99, 1.0)));
+        // an arb that made money is an arb
+        assert!(!history.is_rebalance(&arb(50_000.0, 99, 500.0)));
+        // neither the same size nor the same pools
+        assert!(!history.is_rebalance(&arb(10_000.0, 99, 0.0)));
+
+        let mut arbitrageur = ArbHistory::default();
+        for i in 0..10 {
+            arbitrageur.record(arb(50_000.0, i, 200.0));
+        }
+        assert!(!arbitrageur.is_rebalance(&arb(50_000.0, 99, 0.0)));
+
+        for _ in 0..ARB_HISTORY_LEN {
+            arbitrageur.record(arb(1.0, 1, 0.0));
+        }
+        assert_eq!(arbitrageur.recent.len(), ARB_HISTORY_LEN);
+    }
+
+    #[test]
+    fn test_rebalances_are_not_counted() {
+        let bundle = |mev_type| Bundle {
+            header: BundleHeader { mev_type, profit_usd: 1.0, ..Default::default() },
+            data:   BundleData::AtomicArb(Default::default()),
+        };
+
+        let mut info = SearcherInfo::default();
+        info.update_with_bundle(&bundle(MevType::AtomicArb));
+        info.update_with_bundle(&bundle(MevType::Rebalance));
+
+        assert_eq!(info.mev_count.bundle_count, 1);
+        assert_eq!(info.mev_count.atomic_backrun_count, Some(1));
+        assert_eq!(info.pnl.total, 1.0);
+        assert_eq!(info.arb_history.recent.len(), 1);
+    }
+}
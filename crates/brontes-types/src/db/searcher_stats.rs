@@ -0,0 +1,411 @@
+//! Running per searcher statistics.
+//!
+//! The statistics are updated in the transaction that writes each block, so
+//! leaderboards stay current while following the chain tip without
+//! re-aggregating the stored mev blocks. They count the block's curated view,
+//! with its re-pricing and the manual overrides applied, and are updated again
+//! whenever either changes. Like the order flow summaries, each block's
+//! contribution is recorded as a [`BlockSearcherStats`] so that reprocessing a
+//! block replaces its previous contribution instead of counting it twice.
+//! [`SearcherStatsAggregate`] recomputes the statistics from the curated
+//! blocks to rebuild or check the stored ones.
+use std::collections::BTreeMap;
+
+use alloy_primitives::Address;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::{
+        leaderboard::SearcherRanking, mev_block::MevBlockWithClassified,
+        redefined_types::primitives::AddressRedefined,
+    },
+    implement_table_value_codecs_with_zc,
+    mev::{Bundle, MevType},
+    FastHashMap, FastHashSet,
+};
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct MevTypeStats {
+    #[redefined(same_fields)]
+    pub mev_type:   MevType,
+    pub bundles:    u64,
+    /// Net profit in usd, after bribes
+    pub profit_usd: f64,
+    pub bribe_usd:  f64,
+}
+
+/// Bundles, profit and bribes of a searcher, in total and per mev type
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BundleTotals {
+    pub bundles:    u64,
+    pub profit_usd: f64,
+    pub bribe_usd:  f64,
+    pub by_type:    Vec<MevTypeStats>,
+}
+
+impl BundleTotals {
+    fn add_bundle(&mut self, bundle: &Bundle) {
+        self.bundles += 1;
+        self.profit_usd += bundle.header.profit_usd;
+        self.bribe_usd += bundle.header.bribe_usd;
+
+        let mev_type = bundle.header.mev_type;
+        let stats = match self.by_type.iter_mut().position(|t| t.mev_type == mev_type) {
+            Some(idx) => &mut self.by_type[idx],
+            None => {
+                self.by_type
+                    .push(MevTypeStats { mev_type, ..Default::default() });
+                self.by_type.last_mut().unwrap()
+            }
+        };
+        stats.bundles += 1;
+        stats.profit_usd += bundle.header.profit_usd;
+        stats.bribe_usd += bundle.header.bribe_usd;
+    }
+
+    fn merge(&mut self, other: &BundleTotals, sign: f64) {
+        let count = |a: u64, b: u64| if sign > 0.0 { a + b } else { a.saturating_sub(b) };
+
+        self.bundles = count(self.bundles, other.bundles);
+        self.profit_usd += sign * other.profit_usd;
+        self.bribe_usd += sign * other.bribe_usd;
+
+        for other in &other.by_type {
+            match self
+                .by_type
+                .iter_mut()
+                .find(|t| t.mev_type == other.mev_type)
+            {
+                Some(stats) => {
+                    stats.bundles = count(stats.bundles, other.bundles);
+                    stats.profit_usd += sign * other.profit_usd;
+                    stats.bribe_usd += sign * other.bribe_usd;
+                }
+                None if sign > 0.0 => self.by_type.push(other.clone()),
+                None => {}
+            }
+        }
+        self.by_type.retain(|t| t.bundles != 0);
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct SearcherBlockStats {
+    pub searcher: Address,
+    pub totals:   BundleTotals,
+}
+
+/// The bundles of each searcher in a single block
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct BlockSearcherStats {
+    pub searchers: Vec<SearcherBlockStats>,
+}
+
+impl BlockSearcherStats {
+    pub fn new(bundles: &[Bundle]) -> Self {
+        let mut searchers: FastHashMap<Address, BundleTotals> = FastHashMap::default();
+        bundles.iter().for_each(|bundle| {
+            searchers
+                .entry(bundle.header.eoa)
+                .or_default()
+                .add_bundle(bundle)
+        });
+
+        let mut searchers = searchers
+            .into_iter()
+            .map(|(searcher, totals)| SearcherBlockStats { searcher, totals })
+            .collect::<Vec<_>>();
+        searchers.sort_unstable_by_key(|s| s.searcher);
+
+        Self { searchers }
+    }
+}
+
+implement_table_value_codecs_with_zc!(BlockSearcherStatsRedefined);
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct SearcherStats {
+    pub totals:             BundleTotals,
+    /// Blocks the searcher landed a bundle in
+    pub active_blocks:      u64,
+    /// First block the searcher landed a bundle in
+    pub first_active_block: u64,
+    /// Last block the searcher landed a bundle in
+    pub last_active_block:  u64,
+}
+
+impl SearcherStats {
+    pub fn add_block(&mut self, block_number: u64, stats: &SearcherBlockStats) {
+        if self.is_empty() {
+            self.first_active_block = block_number;
+            self.last_active_block = block_number;
+        } else {
+            self.first_active_block = self.first_active_block.min(block_number);
+            self.last_active_block = self.last_active_block.max(block_number);
+        }
+        self.active_blocks += 1;
+        self.totals.merge(&stats.totals, 1.0);
+    }
+
+    /// Removes the block's contribution. The active range is reset once the
+    /// searcher has no blocks left, otherwise it's kept as is since the
+    /// blocks inside it aren't known here
+    pub fn remove_block(&mut self, stats: &SearcherBlockStats) {
+        self.active_blocks = self.active_blocks.saturating_sub(1);
+        self.totals.merge(&stats.totals, -1.0);
+        if self.is_empty() {
+            self.first_active_block = 0;
+            self.last_active_block = 0;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active_blocks == 0
+    }
+
+    pub fn ranking(&self, searcher: Address) -> SearcherRanking {
+        SearcherRanking {
+            searcher,
+            bundles: self.totals.bundles,
+            profit_usd: self.totals.profit_usd,
+            bribe_usd: self.totals.bribe_usd,
+        }
+    }
+
+    pub fn ranking_for_type(
+        &self,
+        searcher: Address,
+        mev_type: MevType,
+    ) -> Option<SearcherRanking> {
+        self.totals
+            .by_type
+            .iter()
+            .find(|t| t.mev_type == mev_type)
+            .map(|t| SearcherRanking {
+                searcher,
+                bundles: t.bundles,
+                profit_usd: t.profit_usd,
+                bribe_usd: t.bribe_usd,
+            })
+    }
+
+    /// Whether the statistics match up to float rounding of the sums
+    pub fn approx_eq(&self, other: &SearcherStats) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= 1e-6 * a.abs().max(b.abs()).max(1.0);
+        let mut types = self.totals.by_type.clone();
+        let mut other_types = other.totals.by_type.clone();
+        types.sort_unstable_by_key(|t| t.mev_type.to_string());
+        other_types.sort_unstable_by_key(|t| t.mev_type.to_string());
+
+        self.active_blocks == other.active_blocks
+            && self.first_active_block == other.first_active_block
+            && self.last_active_block == other.last_active_block
+            && self.totals.bundles == other.totals.bundles
+            && close(self.totals.profit_usd, other.totals.profit_usd)
+            && close(self.totals.bribe_usd, other.totals.bribe_usd)
+            && types.len() == other_types.len()
+            && types.iter().zip(&other_types).all(|(a, b)| {
+                a.mev_type == b.mev_type
+                    && a.bundles == b.bundles
+                    && close(a.profit_usd, b.profit_usd)
+                    && close(a.bribe_usd, b.bribe_usd)
+            })
+    }
+}
+
+implement_table_value_codecs_with_zc!(SearcherStatsRedefined);
+
+/// Blocks rolled up into each [`MevRollup`], about a day of blocks
+pub const ROLLUP_BLOCKS: u64 = 7200;
+
+/// The bundles of every searcher over a window of [`ROLLUP_BLOCKS`] blocks,
+/// keyed by the window's first block. Rolled up from the per block statistics
+/// so it counts the curated view of the blocks
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct MevRollup {
+    /// Blocks in the window that had bundles
+    pub blocks:    u64,
+    /// Searchers that landed a bundle in the window
+    pub searchers: u64,
+    pub totals:    BundleTotals,
+}
+
+implement_table_value_codecs_with_zc!(MevRollupRedefined);
+
+impl MevRollup {
+    /// First block of the window the block is rolled up in
+    pub fn window_start(block_number: u64) -> u64 {
+        block_number - block_number % ROLLUP_BLOCKS
+    }
+
+    /// Rolls up the statistics of each block into the window it falls in
+    pub fn roll_up<'a>(
+        blocks: impl IntoIterator<Item = (u64, &'a BlockSearcherStats)>,
+    ) -> BTreeMap<u64, MevRollup> {
+        let mut rollups: BTreeMap<u64, (MevRollup, FastHashSet<Address>)> = BTreeMap::new();
+        for (block_number, stats) in blocks {
+            if stats.searchers.is_empty() {
+                continue
+            }
+            let (rollup, searchers) = rollups.entry(Self::window_start(block_number)).or_default();
+            rollup.blocks += 1;
+            for stats in &stats.searchers {
+                searchers.insert(stats.searcher);
+                rollup.totals.merge(&stats.totals, 1.0);
+            }
+        }
+
+        rollups
+            .into_iter()
+            .map(|(start, (mut rollup, searchers))| {
+                rollup.searchers = searchers.len() as u64;
+                (start, rollup)
+            })
+            .collect()
+    }
+}
+
+/// Searcher statistics recomputed from mev blocks
+#[derive(Debug, Default, Clone)]
+pub struct SearcherStatsAggregate {
+    pub blocks:    Vec<(u64, BlockSearcherStats)>,
+    pub searchers: FastHashMap<Address, SearcherStats>,
+}
+
+impl SearcherStatsAggregate {
+    /// Adds the block's bundles, the blocks can be added in any order
+    pub fn add_block(&mut self, block: &MevBlockWithClassified) {
+        self.add_block_stats(block.block.block_number, BlockSearcherStats::new(&block.mev));
+    }
+
+    /// Adds the statistics of a block's bundles
+    pub fn add_block_stats(&mut self, block_number: u64, block_stats: BlockSearcherStats) {
+        for stats in &block_stats.searchers {
+            self.searchers
+                .entry(stats.searcher)
+                .or_default()
+                .add_block(block_number, stats);
+        }
+        self.blocks.push((block_number, block_stats));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::searcher_bundle;
+
+    #[test]
+    fn test_searcher_stats_replace_block() {
+        let searcher = Address::repeat_byte(1);
+        let first = BlockSearcherStats::new(&[
+            searcher_bundle(1, MevType::Sandwich, 10.0)
+                .bribe_usd(1.0)
+                .build(),
+            searcher_bundle(1, MevType::AtomicArb, 5.0)
+                .bribe_usd(1.0)
+                .build(),
+            searcher_bundle(2, MevType::Sandwich, 3.0)
+                .bribe_usd(1.0)
+                .build(),
+        ]);
+        assert_eq!(first.searchers.len(), 2);
+
+        let mut stats = SearcherStats::default();
+        stats.add_block(10, &first.searchers[0]);
+        assert_eq!(stats.totals.bundles, 2);
+        assert_eq!(stats.totals.profit_usd, 15.0);
+        assert_eq!(stats.totals.by_type.len(), 2);
+
+        // reprocessing the block without the arb
+        let second = BlockSearcherStats::new(&[searcher_bundle(1, MevType::Sandwich, 12.0)
+            .bribe_usd(1.0)
+            .build()]);
+        stats.remove_block(&first.searchers[0]);
+        stats.add_block(10, &second.searchers[0]);
+
+        assert_eq!(stats.active_blocks, 1);
+        assert_eq!(stats.totals.bundles, 1);
+        assert_eq!(stats.totals.profit_usd, 12.0);
+        assert_eq!(
+            stats
+                .ranking_for_type(searcher, MevType::Sandwich)
+                .unwrap()
+                .profit_usd,
+            12.0
+        );
+        assert!(stats
+            .ranking_for_type(searcher, MevType::AtomicArb)
+            .is_none());
+        assert_eq!((stats.first_active_block, stats.last_active_block), (10, 10));
+    }
+
+    #[test]
+    fn test_searcher_stats_active_range() {
+        let block = BlockSearcherStats::new(&[searcher_bundle(1, MevType::Sandwich, 10.0)
+            .bribe_usd(1.0)
+            .build()]);
+        let stats = &block.searchers[0];
+
+        let mut searcher = SearcherStats::default();
+        searcher.add_block(20, stats);
+        searcher.add_block(10, stats);
+        searcher.add_block(15, stats);
+        assert_eq!((searcher.first_active_block, searcher.last_active_block), (10, 20));
+
+        for _ in 0..3 {
+            searcher.remove_block(stats);
+        }
+        assert!(searcher.is_empty());
+        searcher.add_block(5, stats);
+        assert_eq!((searcher.first_active_block, searcher.last_active_block), (5, 5));
+    }
+
+    #[test]
+    fn test_mev_rollup() {
+        let first = BlockSearcherStats::new(&[
+            searcher_bundle(1, MevType::Sandwich, 10.0)
+                .bribe_usd(1.0)
+                .build(),
+            searcher_bundle(2, MevType::AtomicArb, 5.0)
+                .bribe_usd(1.0)
+                .build(),
+        ]);
+        let second = BlockSearcherStats::new(&[searcher_bundle(1, MevType::Sandwich, 2.0)
+            .bribe_usd(1.0)
+            .build()]);
+        let empty = BlockSearcherStats::default();
+
+        let rollups = MevRollup::roll_up([
+            (ROLLUP_BLOCKS + 1, &first),
+            (ROLLUP_BLOCKS + 2, &second),
+            (ROLLUP_BLOCKS + 3, &empty),
+            (2 * ROLLUP_BLOCKS, &second),
+        ]);
+
+        assert_eq!(
+            rollups.keys().copied().collect::<Vec<_>>(),
+            vec![ROLLUP_BLOCKS, 2 * ROLLUP_BLOCKS]
+        );
+        let day = &rollups[&ROLLUP_BLOCKS];
+        assert_eq!((day.blocks, day.searchers), (2, 2));
+        assert_eq!(day.totals.bundles, 3);
+        assert_eq!(day.totals.profit_usd, 17.0);
+        assert_eq!(day.totals.by_type.len(), 2);
+        assert_eq!(rollups[&(2 * ROLLUP_BLOCKS)].totals.bundles, 1);
+    }
+}
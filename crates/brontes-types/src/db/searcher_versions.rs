@@ -0,0 +1,150 @@
+//! Versioned layout of the stored searcher info.
+//!
+//! Searcher info is stored in rkyv's layout like the mev blocks, see
+//! [`bundle_versions`](super::bundle_versions), so adding a field leaves the
+//! rows of older releases undecodable. It is therefore stored in an envelope
+//! tagged with the version of its layout. Rows stored before the envelope was
+//! introduced carry no prefix and are read through [`SearcherInfoV0`], the
+//! fields added since start out empty and fill in as the searcher's bundles
+//! come in.
+//!
+//! To change the layout, keep its previous definition frozen in this module,
+//! bump [`SEARCHER_INFO_VERSION`] and add its conversion to
+//! [`decode_searcher_info`].
+use eyre::{eyre, WrapErr};
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+
+use super::{
+    bundle_versions::{check_version, decode, encode, Versioned},
+    redefined_types::primitives::AddressRedefined,
+    searcher::{Fund, SearcherInfoRedefined, TollByType},
+};
+use crate::mev::{MevCount, MevType};
+
+/// Prefix of the searcher info stored in a versioned envelope
+const VERSIONED_MAGIC: &[u8; 8] = b"BRNTSI01";
+
+/// Version of the stored layout of [`SearcherInfoRedefined`]
+pub const SEARCHER_INFO_VERSION: u16 = 1;
+
+/// Searcher info before the arb history and the wallet funding were kept
+#[derive(rSerialize, rDeserialize, Archive)]
+#[archive(check_bytes)]
+pub(crate) struct SearcherInfoV0 {
+    pub name:              Option<String>,
+    pub fund:              Fund,
+    pub mev_count:         MevCount,
+    pub pnl:               TollByType,
+    pub gas_bids:          TollByType,
+    pub builder:           Option<AddressRedefined>,
+    pub config_labels:     Vec<MevType>,
+    pub sibling_searchers: Vec<AddressRedefined>,
+}
+
+impl From<SearcherInfoV0> for SearcherInfoRedefined {
+    fn from(v0: SearcherInfoV0) -> Self {
+        Self {
+            name:              v0.name,
+            fund:              v0.fund,
+            mev_count:         v0.mev_count,
+            pnl:               v0.pnl,
+            gas_bids:          v0.gas_bids,
+            builder:           v0.builder,
+            config_labels:     v0.config_labels,
+            sibling_searchers: v0.sibling_searchers,
+            arb_history:       Default::default(),
+            funding:           None,
+        }
+    }
+}
+
+/// Encodes the searcher info in a versioned envelope
+pub fn encode_searcher_info(value: &SearcherInfoRedefined) -> Vec<u8> {
+    let versioned = Versioned { version: SEARCHER_INFO_VERSION, payload: encode(value) };
+
+    let mut out = VERSIONED_MAGIC.to_vec();
+    out.extend(encode(&versioned));
+    out
+}
+
+/// Decodes searcher info written by [`encode_searcher_info`] or by an older
+/// release, upcasting it to the current layout
+pub fn decode_searcher_info(buf: &[u8]) -> eyre::Result<SearcherInfoRedefined> {
+    if let Some(buf) = buf.strip_prefix(VERSIONED_MAGIC) {
+        let versioned = decode::<Versioned>(buf)?;
+        check_version("searcher info", versioned.version, SEARCHER_INFO_VERSION)?;
+        return match versioned.version {
+            SEARCHER_INFO_VERSION => decode::<SearcherInfoRedefined>(&versioned.payload),
+            version => Err(eyre!("no upcaster from version {version} of searcher info")),
+        }
+    }
+
+    decode::<SearcherInfoV0>(buf)
+        .map(Into::into)
+        .wrap_err("unversioned searcher info doesn't match the baseline layout")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Address;
+    use redefined::RedefinedConvert;
+
+    use super::*;
+    use crate::db::{
+        searcher::{ArbHistory, ArbRecord, SearcherInfo},
+        wallet_funding::{FundingEdge, FundingSource, FundingTrace},
+    };
+
+    #[test]
+    fn test_searcher_info_round_trip() {
+        let mut info = SearcherInfo {
+            name: Some("searcher".to_string()),
+            sibling_searchers: vec![Address::with_last_byte(1)],
+            ..Default::default()
+        };
+        info.mev_count.increment_count(MevType::Sandwich);
+        info.arb_history =
+            ArbHistory { recent: vec![ArbRecord { size_usd: 1_000.0, pools: 7, profit_usd: 1.0 }] };
+        info.funding = Some(FundingTrace {
+            path:   vec![FundingEdge { funder: Address::with_last_byte(2), ..Default::default() }],
+            source: FundingSource::Tornado,
+        });
+        let info = SearcherInfoRedefined::from_source(info);
+
+        assert_eq!(decode_searcher_info(&encode_searcher_info(&info)).unwrap(), info);
+    }
+
+    #[test]
+    fn test_decodes_baseline_searcher_info() {
+        let v0 = SearcherInfoV0 {
+            name:              Some("searcher".to_string()),
+            fund:              Fund::None,
+            mev_count:         MevCount { bundle_count: 3, ..Default::default() },
+            pnl:               TollByType { total: 10.0, ..Default::default() },
+            gas_bids:          TollByType::default(),
+            builder:           None,
+            config_labels:     vec![MevType::AtomicArb],
+            sibling_searchers: vec![AddressRedefined::from_source(Address::with_last_byte(2))],
+        };
+
+        let info = decode_searcher_info(&encode(&v0)).unwrap().to_source();
+        assert_eq!(info.name.as_deref(), Some("searcher"));
+        assert_eq!(info.mev_count.bundle_count, 3);
+        assert_eq!(info.config_labels, vec![MevType::AtomicArb]);
+        assert_eq!(info.sibling_searchers, vec![Address::with_last_byte(2)]);
+        assert!(info.arb_history.recent.is_empty());
+        assert!(info.funding.is_none());
+    }
+
+    #[test]
+    fn test_rejects_newer_searcher_info() {
+        let versioned = Versioned {
+            version: SEARCHER_INFO_VERSION + 1,
+            payload: encode(&SearcherInfoRedefined::from_source(SearcherInfo::default())),
+        };
+        let mut buf = VERSIONED_MAGIC.to_vec();
+        buf.extend(encode(&versioned));
+
+        assert!(decode_searcher_info(&buf).is_err());
+    }
+}
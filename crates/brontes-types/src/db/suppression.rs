@@ -0,0 +1,263 @@
+//! Curated suppression of recurring false positives.
+//!
+//! Some contracts keep producing bundles that aren't mev, e.g. fee routers
+//! that look like atomic arbs or rebasing keepers that look like backruns.
+//! Instead of special casing them in the inspectors, operators add a
+//! [`SuppressionRule`] for the contract, optionally scoped to the protocols it
+//! trades through and to the mev types it's misclassified as. The composer
+//! drops the bundles matching an active rule before the block's results are
+//! counted and stored.
+//!
+//! Like the bundle overrides, changes to the list are kept as an audit log, see
+//! [`audit_log`]: [`SuppressionChange`]s are appended with their author and
+//! reason and a rule is lifted by appending a removal. [`SuppressionList`] is
+//! the set of rules in effect.
+use std::fmt::Display;
+
+use alloy_primitives::Address;
+use itertools::Itertools;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+use crate::{
+    db::{
+        audit_log::{self, entries_in_effect, AuditEntry},
+        redefined_types::primitives::*,
+    },
+    implement_table_value_codecs_with_zc,
+    mev::{Bundle, Mev, MevType},
+    FastHashMap, Protocol,
+};
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+    AsRefStr,
+)]
+#[archive(check_bytes)]
+pub enum SuppressionKind {
+    /// Adds a rule to the list
+    Add,
+    /// Lifts an earlier rule
+    Remove,
+}
+
+/// The bundles a rule suppresses
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct SuppressionRule {
+    /// Matched against the bundle's eoa, its mev contract and the pools of
+    /// the searcher's swaps
+    pub address:   Address,
+    /// Only bundles trading through one of these protocols, any if empty
+    #[redefined(same_fields)]
+    pub protocols: Vec<Protocol>,
+    /// Only bundles of these mev types, any if empty
+    #[redefined(same_fields)]
+    pub mev_types: Vec<MevType>,
+}
+
+impl SuppressionRule {
+    pub fn matches(&self, bundle: &Bundle) -> bool {
+        if !self.mev_types.is_empty() && !self.mev_types.contains(&bundle.header.mev_type) {
+            return false
+        }
+
+        if !self.protocols.is_empty() {
+            let protocols = bundle.data.protocols();
+            if !self.protocols.iter().any(|p| protocols.contains(p)) {
+                return false
+            }
+        }
+
+        bundle.header.eoa == self.address
+            || bundle.header.mev_contract == Some(self.address)
+            || bundle
+                .data
+                .searcher_swaps()
+                .iter()
+                .any(|swap| swap.pool == self.address)
+    }
+}
+
+impl Display for SuppressionRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.address)?;
+        if !self.protocols.is_empty() {
+            write!(f, " on {}", self.protocols.iter().join(", "))?;
+        }
+        if !self.mev_types.is_empty() {
+            write!(f, " as {}", self.mev_types.iter().join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct SuppressionChange {
+    #[redefined(same_fields)]
+    pub kind:       SuppressionKind,
+    /// The added rule, or for a removal the rule it lifts
+    pub rule:       SuppressionRule,
+    /// Id of the change a removal lifts
+    pub removes:    Option<u64>,
+    pub author:     String,
+    pub reason:     String,
+    /// Unix timestamp in seconds of when the change was made
+    pub created_at: u64,
+}
+
+implement_table_value_codecs_with_zc!(SuppressionChangeRedefined);
+
+impl SuppressionChange {
+    fn new(kind: SuppressionKind, rule: SuppressionRule, author: String, reason: String) -> Self {
+        Self { kind, rule, removes: None, author, reason, created_at: audit_log::created_at() }
+    }
+
+    pub fn add(rule: SuppressionRule, author: String, reason: String) -> Self {
+        Self::new(SuppressionKind::Add, rule, author, reason)
+    }
+
+    /// Lifts the rule added by the change with the given id
+    pub fn remove(id: u64, removed: &SuppressionChange, author: String, reason: String) -> Self {
+        Self {
+            removes: Some(id),
+            ..Self::new(SuppressionKind::Remove, removed.rule.clone(), author, reason)
+        }
+    }
+}
+
+impl AuditEntry for SuppressionChange {
+    fn revokes(&self) -> Option<u64> {
+        (self.kind == SuppressionKind::Remove)
+            .then_some(self.removes)
+            .flatten()
+    }
+}
+
+impl Display for SuppressionChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind.as_ref(), self.rule)?;
+        if let Some(removes) = self.removes {
+            write!(f, " lifting #{removes}")?;
+        }
+        write!(f, " by {}: {}", self.author, self.reason)
+    }
+}
+
+/// The rules in effect, with lifted rules removed
+#[derive(Debug, Default, Clone)]
+pub struct SuppressionList {
+    /// Active rules by their id
+    rules: Vec<(u64, SuppressionRule)>,
+}
+
+impl SuppressionList {
+    /// Takes the changes with their ids, in any order
+    pub fn new(changes: Vec<(u64, SuppressionChange)>) -> Self {
+        let rules = entries_in_effect(changes)
+            .into_iter()
+            .map(|(id, c)| (id, c.rule))
+            .collect();
+
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn rules(&self) -> &[(u64, SuppressionRule)] {
+        &self.rules
+    }
+
+    /// Id of the first active rule the bundle matches
+    pub fn suppressed_by(&self, bundle: &Bundle) -> Option<u64> {
+        self.rules
+            .iter()
+            .find(|(_, rule)| rule.matches(bundle))
+            .map(|(id, _)| *id)
+    }
+
+    /// Drops the suppressed bundles, returns the number dropped by each rule
+    pub fn apply(&self, bundles: &mut Vec<Bundle>) -> FastHashMap<u64, usize> {
+        let mut suppressed = FastHashMap::default();
+        if self.is_empty() {
+            return suppressed
+        }
+
+        bundles.retain(|bundle| match self.suppressed_by(bundle) {
+            Some(id) => {
+                *suppressed.entry(id).or_default() += 1;
+                false
+            }
+            None => true,
+        });
+
+        suppressed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_utils::searcher_bundle;
+
+    #[test]
+    fn test_suppression_list() {
+        let by = || "alice".to_string();
+        let why = || "fee router".to_string();
+
+        let router = SuppressionRule {
+            address: Address::repeat_byte(9),
+            mev_types: vec![MevType::AtomicArb],
+            ..Default::default()
+        };
+        let keeper = SuppressionRule { address: Address::repeat_byte(1), ..Default::default() };
+        let lifted = SuppressionChange::add(keeper.clone(), by(), why());
+        let list = SuppressionList::new(vec![
+            (2, SuppressionChange::remove(1, &lifted, by(), why())),
+            (0, SuppressionChange::add(router, by(), why())),
+            (1, lifted),
+            (3, SuppressionChange::add(keeper, by(), why())),
+        ]);
+        assert_eq!(list.rules().iter().map(|(id, _)| *id).collect::<Vec<_>>(), vec![0, 3]);
+
+        let mut bundles = vec![
+            searcher_bundle(2, MevType::AtomicArb, 0.0)
+                .mev_contract(Address::repeat_byte(9))
+                .build(),
+            searcher_bundle(2, MevType::Sandwich, 0.0)
+                .mev_contract(Address::repeat_byte(9))
+                .build(),
+            searcher_bundle(1, MevType::Jit, 0.0).build(),
+            searcher_bundle(3, MevType::AtomicArb, 0.0).build(),
+        ];
+        let suppressed = list.apply(&mut bundles);
+
+        assert_eq!(suppressed.get(&0), Some(&1));
+        assert_eq!(suppressed.get(&3), Some(&1));
+        assert_eq!(
+            bundles
+                .iter()
+                .map(|b| b.header.mev_type)
+                .collect::<Vec<_>>(),
+            vec![MevType::Sandwich, MevType::AtomicArb]
+        );
+    }
+}
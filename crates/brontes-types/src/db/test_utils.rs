@@ -0,0 +1,79 @@
+//! Fixtures shared by the tests of the stored types.
+use alloy_primitives::{Address, B256};
+
+use crate::mev::{Bundle, BundleData, BundleHeader, MevType, ValueSplit};
+
+/// Starts a bundle with a default header and data
+pub fn bundle() -> TestBundle {
+    TestBundle(Bundle { header: BundleHeader::default(), data: BundleData::default() })
+}
+
+/// Starts a bundle of `mev_type` by the searcher whose address repeats the
+/// `searcher` byte
+pub fn searcher_bundle(searcher: u8, mev_type: MevType, profit_usd: f64) -> TestBundle {
+    bundle()
+        .eoa(Address::repeat_byte(searcher))
+        .mev_type(mev_type)
+        .profit_usd(profit_usd)
+}
+
+/// Bundle fixture, only the fields a test sets differ from the defaults
+pub struct TestBundle(Bundle);
+
+impl TestBundle {
+    pub fn block_number(mut self, block_number: u64) -> Self {
+        self.0.header.block_number = block_number;
+        self
+    }
+
+    pub fn tx_index(mut self, tx_index: u64) -> Self {
+        self.0.header.tx_index = tx_index;
+        self
+    }
+
+    pub fn tx_hash(mut self, tx_hash: B256) -> Self {
+        self.0.header.tx_hash = tx_hash;
+        self
+    }
+
+    pub fn eoa(mut self, eoa: Address) -> Self {
+        self.0.header.eoa = eoa;
+        self
+    }
+
+    pub fn mev_contract(mut self, mev_contract: Address) -> Self {
+        self.0.header.mev_contract = Some(mev_contract);
+        self
+    }
+
+    pub fn mev_type(mut self, mev_type: MevType) -> Self {
+        self.0.header.mev_type = mev_type;
+        self
+    }
+
+    pub fn profit_usd(mut self, profit_usd: f64) -> Self {
+        self.0.header.profit_usd = profit_usd;
+        self
+    }
+
+    pub fn bribe_usd(mut self, bribe_usd: f64) -> Self {
+        self.0.header.bribe_usd = bribe_usd;
+        self
+    }
+
+    pub fn value_split(mut self, value_split: ValueSplit) -> Self {
+        self.0.header.value_split = value_split;
+        self
+    }
+
+    pub fn data(mut self, data: BundleData) -> Self {
+        self.0.data = data;
+        self
+    }
+
+    /// Identifies the bundle by its content, as the composer does
+    pub fn build(mut self) -> Bundle {
+        self.0.header.bundle_id = self.0.compute_bundle_id();
+        self.0
+    }
+}
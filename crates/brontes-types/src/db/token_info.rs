@@ -19,6 +19,7 @@ use crate::{
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct TokenInfoWithAddress {
     #[serde(with = "addresss")]
     pub address: Address,
@@ -114,6 +115,7 @@ impl DbRow for TokenInfoWithAddress {
     Eq,
     Hash,
 )]
+#[archive(check_bytes)]
 pub struct TokenInfo {
     pub decimals: u8,
     pub symbol:   String,
@@ -0,0 +1,231 @@
+//! Token metadata beyond the decimals and symbol the classifier needs, and an
+//! assessment of whether the token is spam.
+//!
+//! Spam tokens are airdropped to look like a searcher or victim holds value,
+//! and honeypots can be bought but not sold. A bundle that only gained such
+//! tokens made no profit, however much its pool prices them at. A token is
+//! scored from the [`SpamSignal`]s raised against it:
+//!
+//! - its name or symbol advertises a url or imitates another token with non
+//!   ascii characters
+//! - a transfer out of its largest pool fails when simulated
+//! - its pools hold little or none of its supply, so it can't be sold in size
+//!
+//! Tokens on a curated token list are never scored as spam. The name signal is
+//! raised when the token is first seen, the others when the token is scored
+//! against the node with `brontes db token-metadata score`.
+use std::path::Path;
+
+use alloy_primitives::Address;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::redefined_types::primitives::*, implement_table_value_codecs_with_zc, FastHashMap,
+};
+
+/// Score from which a token is treated as untradeable
+pub const UNTRADEABLE_SPAM_SCORE: u8 = 60;
+/// Share of the supply, in basis points, the pools of a token need to hold for
+/// its liquidity not to count as thin
+pub const THIN_LIQUIDITY_BPS: u16 = 100;
+
+/// Words spam tokens use in their name to send holders to a phishing site
+const URL_MARKERS: &[&str] =
+    &["http", "www.", ".com", ".io", ".org", ".net", ".xyz", "t.me", "claim", "visit", "reward"];
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+)]
+#[archive(check_bytes)]
+pub enum SpamSignal {
+    /// The name or symbol advertises a url or imitates another token
+    SuspiciousName,
+    /// A transfer out of the token's largest pool reverted or returned false
+    TransferReverts,
+    /// None of the token's pools hold any of it
+    NoLiquidity,
+    /// The token's pools hold less than [`THIN_LIQUIDITY_BPS`] of its supply
+    ThinLiquidity { pooled_bps: u16 },
+}
+
+impl SpamSignal {
+    fn weight(&self) -> u8 {
+        match self {
+            Self::SuspiciousName => 40,
+            Self::TransferReverts => 60,
+            Self::NoLiquidity => 40,
+            Self::ThinLiquidity { .. } => 25,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct SpamAssessment {
+    /// From 0 for a token without signals to 100
+    pub score:   u8,
+    #[redefined(same_fields)]
+    pub signals: Vec<SpamSignal>,
+}
+
+impl SpamAssessment {
+    /// Scores the signals, a listed token scores 0 whatever its signals
+    pub fn new(signals: Vec<SpamSignal>, listed: bool) -> Self {
+        let score = if listed {
+            0
+        } else {
+            signals
+                .iter()
+                .fold(0u8, |score, signal| score.saturating_add(signal.weight()))
+                .min(100)
+        };
+
+        Self { score, signals }
+    }
+
+    pub fn is_untradeable(&self) -> bool {
+        self.score >= UNTRADEABLE_SPAM_SCORE
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct TokenMetadata {
+    pub symbol:   String,
+    pub name:     String,
+    pub decimals: u8,
+    pub logo_uri: Option<String>,
+    /// Whether the token is on the curated token list it was scored with
+    pub listed:   bool,
+    pub spam:     SpamAssessment,
+    /// Block the token was last assessed at
+    pub block:    u64,
+}
+
+implement_table_value_codecs_with_zc!(TokenMetadataRedefined);
+
+impl TokenMetadata {
+    /// Metadata of a token seen for the first time, assessed on its name only
+    pub fn from_name(symbol: String, name: String, decimals: u8, block: u64) -> Self {
+        let signals = suspicious_name(&symbol, &name)
+            .then_some(SpamSignal::SuspiciousName)
+            .into_iter()
+            .collect();
+
+        Self {
+            spam: SpamAssessment::new(signals, false),
+            symbol,
+            name,
+            decimals,
+            block,
+            ..Default::default()
+        }
+    }
+}
+
+/// Whether the name or symbol advertises a url or uses non ascii characters to
+/// imitate another token
+pub fn suspicious_name(symbol: &str, name: &str) -> bool {
+    [symbol, name].into_iter().any(|text| {
+        let lower = text.to_lowercase();
+        URL_MARKERS.iter().any(|marker| lower.contains(marker))
+            || text.chars().any(|c| !c.is_ascii() || c.is_ascii_control())
+    })
+}
+
+/// Signal raised from the share of the supply held by the token's pools
+pub fn liquidity_signal(pooled: u128, total_supply: u128) -> Option<SpamSignal> {
+    if pooled == 0 {
+        return Some(SpamSignal::NoLiquidity)
+    }
+    if total_supply == 0 {
+        return None
+    }
+
+    let pooled_bps = (pooled.min(total_supply) as f64 / total_supply as f64 * 10_000.0) as u16;
+    (pooled_bps < THIN_LIQUIDITY_BPS).then_some(SpamSignal::ThinLiquidity { pooled_bps })
+}
+
+/// Token of a curated list in the token lists format
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListEntry {
+    pub chain_id: u64,
+    pub address:  Address,
+    pub name:     String,
+    pub symbol:   String,
+    pub decimals: u8,
+    #[serde(default, rename = "logoURI")]
+    pub logo_uri: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TokenList {
+    tokens: FastHashMap<Address, TokenListEntry>,
+}
+
+impl TokenList {
+    /// Loads the tokens of the chain from a list in the token lists format
+    pub fn from_json_file(path: impl AsRef<Path>, chain_id: u64) -> eyre::Result<Self> {
+        #[derive(Deserialize)]
+        struct List {
+            tokens: Vec<TokenListEntry>,
+        }
+
+        let path = path.as_ref();
+        let file = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("failed to read token list {}: {e}", path.display()))?;
+        let list: List = serde_json::from_str(&file)?;
+
+        Ok(Self {
+            tokens: list
+                .tokens
+                .into_iter()
+                .filter(|token| token.chain_id == chain_id)
+                .map(|token| (token.address, token))
+                .collect(),
+        })
+    }
+
+    pub fn get(&self, token: &Address) -> Option<&TokenListEntry> {
+        self.tokens.get(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spam_assessment() {
+        assert!(!suspicious_name("USDC", "USD Coin"));
+        assert!(suspicious_name("USDC", "Visit usdc-airdrop.com to claim"));
+        assert!(suspicious_name("USDС", "USD Coin"));
+
+        let airdrop = TokenMetadata::from_name("$ETH".into(), "eth-gift.io".into(), 18, 1);
+        assert_eq!(airdrop.spam.score, 40);
+        assert!(!airdrop.spam.is_untradeable());
+
+        let signals = vec![SpamSignal::SuspiciousName, liquidity_signal(1, 1_000).unwrap()];
+        assert_eq!(signals[1], SpamSignal::ThinLiquidity { pooled_bps: 10 });
+        assert!(SpamAssessment::new(signals.clone(), false).is_untradeable());
+        assert_eq!(SpamAssessment::new(signals, true).score, 0);
+
+        assert_eq!(liquidity_signal(0, 1_000), Some(SpamSignal::NoLiquidity));
+        assert_eq!(liquidity_signal(500, 1_000), None);
+    }
+}
@@ -16,6 +16,7 @@ use crate::{
 
 #[derive(Debug, Default, PartialEq, Row, Clone, Serialize, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct TxTracesInner {
     pub traces: Option<Vec<TxTrace>>,
 }
@@ -39,6 +40,7 @@ implement_table_value_codecs_with_zc!(TxTracesInnerRedefined);
     rkyv::Archive,
     Default,
 )]
+#[archive(check_bytes)]
 #[redefined(TxTrace)]
 pub struct TxTraceRedefined {
     pub block_number:    u64,
@@ -54,6 +56,7 @@ pub struct TxTraceRedefined {
 #[derive(
     Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
 )]
+#[archive(check_bytes)]
 #[redefined(TransactionTraceWithLogs)]
 pub struct TransactionTraceWithLogsRedefined {
     pub trace:        TransactionTraceRedefined,
@@ -66,6 +69,7 @@ pub struct TransactionTraceWithLogsRedefined {
 #[derive(
     Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
 )]
+#[archive(check_bytes)]
 #[redefined(Log)]
 pub struct LogRedefined {
     pub address: AddressRedefined,
@@ -75,6 +79,7 @@ pub struct LogRedefined {
 #[derive(
     Debug, Clone, Redefined, PartialEq, serde::Serialize, rSerialize, rDeserialize, rkyv::Archive,
 )]
+#[archive(check_bytes)]
 #[redefined(LogData)]
 #[redefined_attr(to_source = "LogData::new_unchecked(self.topics.iter().copied().map(Into::into).\
                               collect(), self.data.into())")]
@@ -95,6 +100,7 @@ pub struct LogDataRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(TransactionTrace)]
 pub struct TransactionTraceRedefined {
     pub action:        ActionRedefined,
@@ -115,6 +121,7 @@ pub struct TransactionTraceRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(Action)]
 pub enum ActionRedefined {
     Call(CallActionRedefined),
@@ -134,6 +141,7 @@ pub enum ActionRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(CallAction)]
 pub struct CallActionRedefined {
     pub from:      AddressRedefined,
@@ -155,6 +163,7 @@ pub struct CallActionRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(CreateAction)]
 pub struct CreateActionRedefined {
     pub from:  AddressRedefined,
@@ -174,6 +183,7 @@ pub struct CreateActionRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(SelfdestructAction)]
 pub struct SelfdestructActionRedefined {
     pub address:        AddressRedefined,
@@ -192,6 +202,7 @@ pub struct SelfdestructActionRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(RewardAction)]
 pub struct RewardActionRedefined {
     pub author:      AddressRedefined,
@@ -210,6 +221,7 @@ pub struct RewardActionRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(RewardType)]
 pub enum RewardTypeRedefined {
     Block,
@@ -227,6 +239,7 @@ pub enum RewardTypeRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(CallType)]
 pub enum CallTypeRedefined {
     None,
@@ -247,6 +260,7 @@ pub enum CallTypeRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(TraceOutput)]
 pub enum TraceOutputRedefined {
     Call(CallOutputRedefined),
@@ -264,6 +278,7 @@ pub enum TraceOutputRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(CallOutput)]
 pub struct CallOutputRedefined {
     pub gas_used: U64Redefined,
@@ -281,6 +296,7 @@ pub struct CallOutputRedefined {
     rkyv::Archive,
     Redefined,
 )]
+#[archive(check_bytes)]
 #[redefined(CreateOutput)]
 pub struct CreateOutputRedefined {
     pub address:  AddressRedefined,
@@ -1,11 +1,33 @@
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 
 use crate::{
     db::{
-        address_metadata::AddressMetadata, address_to_protocol_info::ProtocolInfo,
-        builder::BuilderInfo, cex::trades::CexTradeMap, dex::DexQuotes, metadata::Metadata,
-        mev_block::MevBlockWithClassified, searcher::SearcherInfo,
+        address_metadata::AddressMetadata,
+        address_to_protocol_info::ProtocolInfo,
+        beacon::BeaconBlockInfo,
+        block_costs::BlockCost,
+        builder::BuilderInfo,
+        bundle_tags::{BlockBundleTags, TagFilter},
+        cex::trades::CexTradeMap,
+        dex::DexQuotes,
+        fee_history::BlockFees,
+        frontend_losses::BlockFrontendLosses,
+        inspector_artifacts::BlockInspectorArtifacts,
+        leaderboard::MevLeaderboard,
+        metadata::Metadata,
+        mev_block::MevBlockWithClassified,
+        mev_receipt::MevReceipt,
+        order_flow::{BlockOrderFlow, BuilderProposerPayments, SearcherOrderFlow},
+        overrides::{BundleOverride, BundleOverrides},
+        pool_prices::{pool_twap, BlockPoolPrices, PoolTwap},
+        repricing::BlockRepricings,
+        searcher::SearcherInfo,
+        searcher_stats::SearcherStats,
+        suppression::{SuppressionChange, SuppressionList},
         token_info::TokenInfoWithAddress,
+        token_metadata::TokenMetadata,
+        value_split::ValueSplitReport,
+        wallet_funding::FundingEdge,
     },
     pair::Pair,
     structured_trace::TxTrace,
@@ -97,6 +119,81 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
 
     fn get_metadata(&self, block_num: u64, quote_asset: Address) -> eyre::Result<Metadata>;
 
+    fn try_fetch_beacon_info(&self, block_num: u64) -> eyre::Result<Option<BeaconBlockInfo>>;
+
+    fn try_fetch_block_fees(&self, block_num: u64) -> eyre::Result<Option<BlockFees>>;
+
+    /// Returns the fee summary of all processed blocks in the range
+    /// `start_block..end_block`.
+    fn fetch_fee_history(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFees)>>;
+
+    /// Returns the pool prices observed in the swaps of all processed blocks in
+    /// the range `start_block..end_block`.
+    fn fetch_pool_prices(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockPoolPrices)>>;
+
+    /// Returns the sandwich victim losses per frontend of all processed blocks
+    /// in the range `start_block..end_block` that had victims.
+    fn fetch_frontend_losses(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockFrontendLosses)>>;
+
+    /// Time weighted average price of the pool over the range
+    /// `start_block..end_block`, from the prices of its swaps
+    fn fetch_pool_twap(
+        &self,
+        pool: Address,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Option<PoolTwap>> {
+        let blocks = self.fetch_pool_prices(start_block, end_block)?;
+        Ok(pool_twap(&blocks, pool))
+    }
+
+    /// Returns the processing cost of all blocks in the range
+    /// `start_block..end_block` that have one recorded.
+    fn fetch_block_costs(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockCost)>>;
+
+    /// Returns the proposers the builder paid, with the number of blocks and
+    /// the total paid to each.
+    fn try_fetch_builder_proposer_payments(
+        &self,
+        builder: Address,
+    ) -> eyre::Result<Option<BuilderProposerPayments>>;
+
+    /// Returns the builders the searcher's bundles landed through.
+    fn try_fetch_searcher_order_flow(
+        &self,
+        searcher: Address,
+    ) -> eyre::Result<Option<SearcherOrderFlow>>;
+
+    /// Returns the running statistics of the searcher's bundles.
+    fn try_fetch_searcher_stats(&self, searcher: Address) -> eyre::Result<Option<SearcherStats>>;
+
+    /// Returns the running statistics of every searcher that landed a bundle.
+    fn fetch_all_searcher_stats(&self) -> eyre::Result<Vec<(Address, SearcherStats)>>;
+
+    /// Returns the order flow of all processed blocks in the range
+    /// `start_block..end_block`.
+    fn fetch_block_order_flows(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockOrderFlow)>>;
+
     fn get_cex_trades(&self, block: u64) -> eyre::Result<CexTradeMap>;
 
     fn try_fetch_address_metadata(&self, address: Address)
@@ -112,6 +209,10 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
         self.try_fetch_token_info(address).map(|info| info.decimals)
     }
 
+    fn try_fetch_token_metadata(&self, address: Address) -> eyre::Result<Option<TokenMetadata>>;
+
+    fn fetch_all_token_metadata(&self) -> eyre::Result<Vec<(Address, TokenMetadata)>>;
+
     fn try_fetch_mev_blocks(
         &self,
         start_block: Option<u64>,
@@ -123,6 +224,143 @@ pub trait LibmdbxReader: Send + Sync + Unpin + 'static {
         start_block: Option<u64>,
     ) -> eyre::Result<Vec<MevBlockWithClassified>>;
 
+    /// All manual overrides with their ids, including revoked ones
+    fn fetch_bundle_overrides(&self) -> eyre::Result<Vec<(u64, BundleOverride)>>;
+
+    /// All changes to the suppression list with their ids, including lifted
+    /// rules
+    fn fetch_suppression_changes(&self) -> eyre::Result<Vec<(u64, SuppressionChange)>>;
+
+    /// The suppression rules in effect
+    fn fetch_suppression_list(&self) -> eyre::Result<SuppressionList> {
+        Ok(SuppressionList::new(self.fetch_suppression_changes()?))
+    }
+
+    /// Re-pricing runs over the bundles of the blocks in the range. The end
+    /// block is inclusive
+    fn fetch_bundle_repricings(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockRepricings)>>;
+
+    /// The first eth transfer into the address seen while processing blocks
+    fn try_fetch_wallet_funding(&self, address: Address) -> eyre::Result<Option<FundingEdge>>;
+
+    /// The receipt of a transaction that was part of a bundle
+    fn try_fetch_mev_receipt(&self, tx_hash: B256) -> eyre::Result<Option<MevReceipt>>;
+
+    /// Whether the transaction extracted mev, was a victim or neither. A
+    /// transaction without a receipt was neither if it landed in a processed
+    /// block, without its block or if the block wasn't processed it's
+    /// unknown
+    fn fetch_mev_receipt(
+        &self,
+        tx_hash: B256,
+        block_number: Option<u64>,
+    ) -> eyre::Result<MevReceipt> {
+        if let Some(receipt) = self.try_fetch_mev_receipt(tx_hash)? {
+            return Ok(receipt)
+        }
+
+        Ok(match block_number {
+            Some(block_number) if self.has_mev_block(block_number)? => {
+                MevReceipt::neither(tx_hash, block_number)
+            }
+            _ => MevReceipt::unknown(tx_hash),
+        })
+    }
+
+    /// Whether the block was processed
+    fn has_mev_block(&self, block_number: u64) -> eyre::Result<bool> {
+        Ok(!self
+            .try_fetch_mev_blocks(Some(block_number), block_number)?
+            .is_empty())
+    }
+
+    /// Mev blocks of the range with their latest re-pricing and the manual
+    /// overrides applied. The end block is inclusive
+    fn try_fetch_curated_mev_blocks(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+    ) -> eyre::Result<Vec<MevBlockWithClassified>> {
+        let mut blocks = self.try_fetch_mev_blocks(start_block, end_block)?;
+        let repricings = self
+            .fetch_bundle_repricings(start_block, end_block)?
+            .into_iter()
+            .collect::<FastHashMap<_, _>>();
+        let overrides = BundleOverrides::new(self.fetch_bundle_overrides()?);
+        blocks
+            .iter_mut()
+            .for_each(|block| block.curate(repricings.get(&block.block.block_number), &overrides));
+
+        Ok(blocks)
+    }
+
+    /// Returns the bundle tags of the blocks in the range
+    /// `start_block..end_block`.
+    fn fetch_bundle_tags(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockBundleTags)>>;
+
+    /// Returns the inspector debug artifacts of the blocks in the range
+    /// `start_block..end_block` that have any recorded.
+    fn fetch_inspector_artifacts(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> eyre::Result<Vec<(u64, BlockInspectorArtifacts)>>;
+
+    /// Curated mev blocks of the range keeping only the bundles that match the
+    /// tag filter. The end block is inclusive
+    fn fetch_tagged_mev_blocks(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+        filter: &TagFilter,
+    ) -> eyre::Result<Vec<MevBlockWithClassified>> {
+        let mut blocks = self.try_fetch_curated_mev_blocks(start_block, end_block)?;
+        if filter.is_empty() {
+            return Ok(blocks)
+        }
+
+        let tags = self
+            .fetch_bundle_tags(start_block.unwrap_or_default(), end_block + 1)?
+            .into_iter()
+            .collect::<FastHashMap<_, _>>();
+        blocks.iter_mut().for_each(|block| {
+            filter.apply(block, tags.get(&block.block.block_number));
+        });
+        blocks.retain(|block| !block.mev.is_empty());
+
+        Ok(blocks)
+    }
+
+    /// Leaderboards over the curated mev of the block range. The end block is
+    /// inclusive
+    fn fetch_mev_leaderboard(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+    ) -> eyre::Result<MevLeaderboard> {
+        let blocks = self.try_fetch_curated_mev_blocks(start_block, end_block)?;
+        Ok(MevLeaderboard::from_blocks(&blocks))
+    }
+
+    /// Where the value extracted over the block range went. The end block is
+    /// inclusive
+    fn fetch_value_split_report(
+        &self,
+        start_block: Option<u64>,
+        end_block: u64,
+    ) -> eyre::Result<ValueSplitReport> {
+        let blocks = self.try_fetch_curated_mev_blocks(start_block, end_block)?;
+        Ok(ValueSplitReport::from_blocks(&blocks))
+    }
+
     fn protocols_created_before(
         &self,
         start_block: u64,
@@ -1,10 +1,15 @@
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256};
 use futures::Future;
 
 use crate::{
     db::{
-        address_metadata::AddressMetadata, block_analysis::BlockAnalysis, builder::BuilderInfo,
-        dex::DexQuotes, searcher::SearcherInfo,
+        address_metadata::AddressMetadata, beacon::BeaconBlockInfo, block_analysis::BlockAnalysis,
+        block_costs::BlockCostRecorder, builder::BuilderInfo, bundle_tags::BundleTag,
+        config_reload::ConfigReload, dex::DexQuotes, fee_history::BlockFees,
+        frontend_losses::BlockFrontendLosses, inspector_artifacts::BlockInspectorArtifacts,
+        overrides::BundleOverride, pool_prices::BlockPoolPrices,
+        publish_checkpoint::PublishCheckpoint, searcher::SearcherInfo,
+        suppression::SuppressionChange, token_metadata::TokenMetadata, wallet_funding::FundingEdge,
     },
     mev::{Bundle, MevBlock},
     normalized_actions::Action,
@@ -15,15 +20,23 @@ use crate::{
 #[auto_impl::auto_impl(&)]
 pub trait DBWriter: Send + Unpin + 'static {
     /// allows for writing results to multiple databases
-    type Inner: DBWriter;
+    type Inner: DBWriter + Sync;
 
-    fn inner(&self) -> &Self::Inner;
+    /// The writer the default methods forward to. `None` for writers that
+    /// store the results themselves, the methods they don't implement return
+    /// an error instead of forwarding
+    fn inner(&self) -> Option<&Self::Inner>;
 
     fn write_block_analysis(
         &self,
         block_analysis: BlockAnalysis,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner().write_block_analysis(block_analysis)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_block_analysis")?
+                .write_block_analysis(block_analysis)
+                .await
+        }
     }
 
     fn write_dex_quotes(
@@ -31,7 +44,12 @@ pub trait DBWriter: Send + Unpin + 'static {
         block_number: u64,
         quotes: Option<DexQuotes>,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner().write_dex_quotes(block_number, quotes)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_dex_quotes")?
+                .write_dex_quotes(block_number, quotes)
+                .await
+        }
     }
 
     fn write_token_info(
@@ -40,7 +58,25 @@ pub trait DBWriter: Send + Unpin + 'static {
         decimals: u8,
         symbol: String,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner().write_token_info(address, decimals, symbol)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_token_info")?
+                .write_token_info(address, decimals, symbol)
+                .await
+        }
+    }
+
+    fn write_token_metadata(
+        &self,
+        address: Address,
+        metadata: TokenMetadata,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_token_metadata")?
+                .write_token_metadata(address, metadata)
+                .await
+        }
     }
 
     fn save_mev_blocks(
@@ -49,7 +85,12 @@ pub trait DBWriter: Send + Unpin + 'static {
         block: MevBlock,
         mev: Vec<Bundle>,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner().save_mev_blocks(block_number, block, mev)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "save_mev_blocks")?
+                .save_mev_blocks(block_number, block, mev)
+                .await
+        }
     }
 
     fn write_searcher_info(
@@ -59,8 +100,12 @@ pub trait DBWriter: Send + Unpin + 'static {
         eoa_info: SearcherInfo,
         contract_info: Option<SearcherInfo>,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner()
-            .write_searcher_info(eoa_address, contract_address, eoa_info, contract_info)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_searcher_info")?
+                .write_searcher_info(eoa_address, contract_address, eoa_info, contract_info)
+                .await
+        }
     }
 
     fn write_searcher_eoa_info(
@@ -68,8 +113,12 @@ pub trait DBWriter: Send + Unpin + 'static {
         searcher_eoa: Address,
         searcher_info: SearcherInfo,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner()
-            .write_searcher_eoa_info(searcher_eoa, searcher_info)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_searcher_eoa_info")?
+                .write_searcher_eoa_info(searcher_eoa, searcher_info)
+                .await
+        }
     }
 
     fn write_searcher_contract_info(
@@ -77,8 +126,12 @@ pub trait DBWriter: Send + Unpin + 'static {
         searcher_contract: Address,
         searcher_info: SearcherInfo,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner()
-            .write_searcher_contract_info(searcher_contract, searcher_info)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_searcher_contract_info")?
+                .write_searcher_contract_info(searcher_contract, searcher_info)
+                .await
+        }
     }
 
     fn write_builder_info(
@@ -86,8 +139,211 @@ pub trait DBWriter: Send + Unpin + 'static {
         builder_address: Address,
         builder_info: BuilderInfo,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner()
-            .write_builder_info(builder_address, builder_info)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_builder_info")?
+                .write_builder_info(builder_address, builder_info)
+                .await
+        }
+    }
+
+    fn write_beacon_info(
+        &self,
+        block_number: u64,
+        beacon_info: BeaconBlockInfo,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_beacon_info")?
+                .write_beacon_info(block_number, beacon_info)
+                .await
+        }
+    }
+
+    fn write_block_fees(
+        &self,
+        block_number: u64,
+        fees: BlockFees,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_block_fees")?
+                .write_block_fees(block_number, fees)
+                .await
+        }
+    }
+
+    fn write_pool_prices(
+        &self,
+        block_number: u64,
+        prices: BlockPoolPrices,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_pool_prices")?
+                .write_pool_prices(block_number, prices)
+                .await
+        }
+    }
+
+    fn write_frontend_losses(
+        &self,
+        block_number: u64,
+        losses: BlockFrontendLosses,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_frontend_losses")?
+                .write_frontend_losses(block_number, losses)
+                .await
+        }
+    }
+
+    /// Keeps the first funding seen of each address, later ones are ignored
+    fn write_wallet_fundings(
+        &self,
+        fundings: Vec<(Address, FundingEdge)>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_wallet_fundings")?
+                .write_wallet_fundings(fundings)
+                .await
+        }
+    }
+
+    /// Takes the processing cost recorded for the block and writes it, should
+    /// be called after all of the block's other results are written so their
+    /// size is included
+    fn write_block_cost(
+        &self,
+        block_number: u64,
+        cost: BlockCostRecorder,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_block_cost")?
+                .write_block_cost(block_number, cost)
+                .await
+        }
+    }
+
+    /// Appends the reload to the audit trail of the live config
+    fn write_config_reload(
+        &self,
+        reload: ConfigReload,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_config_reload")?
+                .write_config_reload(reload)
+                .await
+        }
+    }
+
+    /// Records the last block a sink published
+    fn write_publish_checkpoint(
+        &self,
+        checkpoint: PublishCheckpoint,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_publish_checkpoint")?
+                .write_publish_checkpoint(checkpoint)
+                .await
+        }
+    }
+
+    /// Appends a manual override and returns its id
+    fn append_bundle_override(
+        &self,
+        bundle_override: BundleOverride,
+    ) -> impl Future<Output = eyre::Result<u64>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "append_bundle_override")?
+                .append_bundle_override(bundle_override)
+                .await
+        }
+    }
+
+    /// Returns once the writes made before it are committed, writes are
+    /// otherwise queued and batched
+    fn flush_writes(&self) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move { forward_to(inner, "flush_writes")?.flush_writes().await }
+    }
+
+    /// Appends a change to the suppression list and returns its id
+    fn append_suppression_change(
+        &self,
+        change: SuppressionChange,
+    ) -> impl Future<Output = eyre::Result<u64>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "append_suppression_change")?
+                .append_suppression_change(change)
+                .await
+        }
+    }
+
+    /// Replaces the detector tags of the block's bundles, manual tags are kept
+    fn write_detected_bundle_tags(
+        &self,
+        block_number: u64,
+        tags: Vec<BundleTag>,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_detected_bundle_tags")?
+                .write_detected_bundle_tags(block_number, tags)
+                .await
+        }
+    }
+
+    /// Writes the inspector debug artifacts recorded for the block, replacing
+    /// the ones of a previous run
+    fn write_inspector_artifacts(
+        &self,
+        block_number: u64,
+        artifacts: BlockInspectorArtifacts,
+    ) -> impl Future<Output = eyre::Result<()>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_inspector_artifacts")?
+                .write_inspector_artifacts(block_number, artifacts)
+                .await
+        }
+    }
+
+    /// Tags a bundle of the block, returns false if it already had the tag
+    fn tag_bundle(
+        &self,
+        block_number: u64,
+        tag: BundleTag,
+    ) -> impl Future<Output = eyre::Result<bool>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "tag_bundle")?
+                .tag_bundle(block_number, tag)
+                .await
+        }
+    }
+
+    /// Removes a tag from a bundle of the block, returns false if it didn't
+    /// have the tag
+    fn untag_bundle(
+        &self,
+        block_number: u64,
+        tx_hash: B256,
+        tag: String,
+    ) -> impl Future<Output = eyre::Result<bool>> + Send {
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "untag_bundle")?
+                .untag_bundle(block_number, tx_hash, tag)
+                .await
+        }
     }
 
     fn write_address_meta(
@@ -95,7 +351,12 @@ pub trait DBWriter: Send + Unpin + 'static {
         address: Address,
         metadata: AddressMetadata,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner().write_address_meta(address, metadata)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "write_address_meta")?
+                .write_address_meta(address, metadata)
+                .await
+        }
     }
 
     fn insert_pool(
@@ -106,15 +367,20 @@ pub trait DBWriter: Send + Unpin + 'static {
         curve_lp_token: Option<Address>,
         classifier_name: Protocol,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner()
-            .insert_pool(block, address, tokens, curve_lp_token, classifier_name)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "insert_pool")?
+                .insert_pool(block, address, tokens, curve_lp_token, classifier_name)
+                .await
+        }
     }
 
     fn insert_tree(
         &self,
         tree: BlockTree<Action>,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner().insert_tree(tree)
+        let inner = self.inner();
+        async move { forward_to(inner, "insert_tree")?.insert_tree(tree).await }
     }
 
     fn save_traces(
@@ -122,6 +388,15 @@ pub trait DBWriter: Send + Unpin + 'static {
         block: u64,
         traces: Vec<TxTrace>,
     ) -> impl Future<Output = eyre::Result<()>> + Send {
-        self.inner().save_traces(block, traces)
+        let inner = self.inner();
+        async move {
+            forward_to(inner, "save_traces")?
+                .save_traces(block, traces)
+                .await
+        }
     }
 }
+
+fn forward_to<'a, I>(inner: Option<&'a I>, method: &str) -> eyre::Result<&'a I> {
+    inner.ok_or_else(|| eyre::eyre!("{method} isn't supported by this writer"))
+}
@@ -0,0 +1,134 @@
+//! Where the value extracted over a block range went.
+//!
+//! Sums the [`ValueSplit`](crate::mev::ValueSplit) of the stored bundles, so
+//! the share of mev captured by searchers can be compared to what was paid to
+//! builders, liquidity providers and protocols.
+use serde::Serialize;
+
+use crate::{db::mev_block::MevBlockWithClassified, mev::Bundle, FastHashMap, Protocol};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ProtocolRevenue {
+    pub protocol:    Protocol,
+    /// Bundles that paid lp fees on the protocol
+    pub bundles:     u64,
+    pub lp_fees_usd: f64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ValueSplitReport {
+    pub start_block:       Option<u64>,
+    pub end_block:         Option<u64>,
+    pub bundles:           u64,
+    pub searcher_usd:      f64,
+    pub builder_usd:       f64,
+    pub lp_fees_usd:       f64,
+    pub protocol_fees_usd: f64,
+    /// Lp fees per protocol, highest first
+    pub protocols:         Vec<ProtocolRevenue>,
+}
+
+impl ValueSplitReport {
+    pub fn from_blocks<'a>(blocks: impl IntoIterator<Item = &'a MevBlockWithClassified>) -> Self {
+        let mut this = Self::default();
+        let mut protocols: FastHashMap<Protocol, ProtocolRevenue> = FastHashMap::default();
+
+        for block in blocks {
+            let block_number = block.block.block_number;
+            this.start_block = Some(
+                this.start_block
+                    .map_or(block_number, |start| start.min(block_number)),
+            );
+            this.end_block = Some(
+                this.end_block
+                    .map_or(block_number, |end| end.max(block_number)),
+            );
+
+            for bundle in &block.mev {
+                this.add_bundle(bundle, &mut protocols);
+            }
+        }
+
+        this.protocols = protocols.into_values().collect();
+        this.protocols
+            .sort_by(|a, b| b.lp_fees_usd.total_cmp(&a.lp_fees_usd));
+
+        this
+    }
+
+    fn add_bundle(
+        &mut self,
+        bundle: &Bundle,
+        protocols: &mut FastHashMap<Protocol, ProtocolRevenue>,
+    ) {
+        let split = &bundle.header.value_split;
+        self.bundles += 1;
+        self.searcher_usd += split.searcher_usd;
+        self.builder_usd += split.builder_usd;
+        self.lp_fees_usd += split.lp_fees_usd();
+        self.protocol_fees_usd += split.protocol_fees_usd;
+
+        for fee in &split.lp_fees {
+            let revenue = protocols
+                .entry(fee.protocol)
+                .or_insert_with(|| ProtocolRevenue {
+                    protocol: fee.protocol,
+                    ..Default::default()
+                });
+            revenue.bundles += 1;
+            revenue.lp_fees_usd += fee.usd;
+        }
+    }
+
+    /// Total value extracted over the range
+    pub fn total_usd(&self) -> f64 {
+        self.searcher_usd + self.builder_usd + self.lp_fees_usd + self.protocol_fees_usd
+    }
+
+    /// Share of the total value kept by searchers
+    pub fn searcher_share(&self) -> f64 {
+        let total = self.total_usd();
+        if total == 0.0 {
+            return 0.0
+        }
+        self.searcher_usd / total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::test_utils::bundle,
+        mev::{MevBlock, ProtocolFee, ValueSplit},
+    };
+
+    #[test]
+    fn test_value_split_report() {
+        let uni = ProtocolFee { protocol: Protocol::UniswapV2, usd: 3.0 };
+        let sushi = ProtocolFee { protocol: Protocol::SushiSwapV2, usd: 7.0 };
+        let block = MevBlockWithClassified {
+            block: MevBlock { block_number: 5, ..Default::default() },
+            mev:   [(50.0, vec![uni.clone(), sushi]), (17.0, vec![uni])]
+                .map(|(searcher_usd, lp_fees)| {
+                    bundle()
+                        .value_split(ValueSplit {
+                            searcher_usd,
+                            builder_usd: 10.0,
+                            lp_fees,
+                            protocol_fees_usd: 0.0,
+                        })
+                        .build()
+                })
+                .to_vec(),
+        };
+        let report = ValueSplitReport::from_blocks([&block]);
+
+        assert_eq!(report.bundles, 2);
+        assert_eq!(report.total_usd(), 100.0);
+        assert_eq!(report.searcher_share(), 0.67);
+        assert_eq!(report.protocols[0].protocol, Protocol::SushiSwapV2);
+        assert_eq!(report.protocols[1].bundles, 2);
+        assert_eq!(report.protocols[1].lp_fees_usd, 6.0);
+    }
+}
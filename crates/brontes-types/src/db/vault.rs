@@ -0,0 +1,193 @@
+//! Exchange rates of vault share tokens.
+//!
+//! ERC-4626 vaults, which Yearn V3 vaults are, mint shares for deposits of
+//! their underlying asset and burn them on redemption. The shares rarely have
+//! pools of their own, so valuing them off dex quotes either fails or picks up
+//! a thin pool's noise. Shares are instead valued at their rate in the
+//! underlying asset times the asset's price, the rate being read from the
+//! deposits and redemptions made through the vault, see
+//! [`vault_share_rates`].
+use alloy_primitives::Address;
+use malachite::{num::basic::traits::Zero, Rational};
+
+use crate::{
+    normalized_actions::{Action, NormalizedTransfer},
+    FastHashMap, Protocol,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultShareRate {
+    /// Asset the vault holds
+    pub underlying: Address,
+    /// Amount of the underlying asset a share is worth
+    pub rate:       Rational,
+}
+
+/// Rates of the share tokens of the vaults deposited into or redeemed from in
+/// the transactions, given as the actions of each transaction. The shares of a
+/// conversion are the ones the vault mints or burns for it, the first share
+/// transfer from or to the zero address after it. The last conversion of a
+/// vault sets its rate
+pub fn vault_share_rates<'a>(
+    txes: impl IntoIterator<Item = &'a [Action]>,
+) -> FastHashMap<Address, VaultShareRate> {
+    let mut rates = FastHashMap::default();
+
+    for actions in txes {
+        let share_supply_changes = actions
+            .iter()
+            .filter_map(Action::try_transfer_ref)
+            .filter(|transfer| transfer.from == Address::ZERO || transfer.to == Address::ZERO)
+            .collect::<Vec<_>>();
+
+        for action in actions {
+            let (vault, trace_index, minted, underlying, assets) = match action {
+                Action::Mint(mint) if mint.protocol == Protocol::ERC4626Vault => {
+                    (mint.pool, mint.trace_index, true, &mint.token, &mint.amount)
+                }
+                Action::Burn(burn) if burn.protocol == Protocol::ERC4626Vault => {
+                    (burn.pool, burn.trace_index, false, &burn.token, &burn.amount)
+                }
+                _ => continue,
+            };
+            let (Some(underlying), Some(assets)) = (underlying.first(), assets.first()) else {
+                continue
+            };
+
+            let Some(shares) = share_supply_changes
+                .iter()
+                .filter(|transfer| {
+                    transfer.token.address == vault
+                        && transfer.trace_index > trace_index
+                        && is_supply_change(transfer, minted)
+                })
+                .min_by_key(|transfer| transfer.trace_index)
+                .map(|transfer| &transfer.amount)
+            else {
+                continue
+            };
+
+            if *assets == Rational::ZERO || *shares == Rational::ZERO {
+                continue
+            }
+
+            rates.insert(
+                vault,
+                VaultShareRate { underlying: underlying.address, rate: assets / shares },
+            );
+        }
+    }
+
+    rates
+}
+
+fn is_supply_change(transfer: &NormalizedTransfer, minted: bool) -> bool {
+    if minted {
+        transfer.from == Address::ZERO
+    } else {
+        transfer.to == Address::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::token_info::{TokenInfo, TokenInfoWithAddress},
+        normalized_actions::{NormalizedBurn, NormalizedMint, NormalizedSwap},
+    };
+
+    fn token(address: Address) -> TokenInfoWithAddress {
+        TokenInfoWithAddress { address, inner: TokenInfo { decimals: 18, ..Default::default() } }
+    }
+
+    fn mint(vault: Address, asset: Address, assets: u64, trace_index: u64) -> Action {
+        Action::Mint(NormalizedMint {
+            protocol: Protocol::ERC4626Vault,
+            trace_index,
+            pool: vault,
+            token: vec![token(asset)],
+            amount: vec![Rational::from(assets)],
+            ..Default::default()
+        })
+    }
+
+    fn burn(vault: Address, asset: Address, assets: u64, trace_index: u64) -> Action {
+        Action::Burn(NormalizedBurn {
+            protocol: Protocol::ERC4626Vault,
+            trace_index,
+            pool: vault,
+            token: vec![token(asset)],
+            amount: vec![Rational::from(assets)],
+            ..Default::default()
+        })
+    }
+
+    fn transfer(
+        token_address: Address,
+        from: Address,
+        to: Address,
+        amount: u64,
+        trace_index: u64,
+    ) -> Action {
+        Action::Transfer(NormalizedTransfer {
+            trace_index,
+            from,
+            to,
+            token: token(token_address),
+            amount: Rational::from(amount),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_vault_share_rates() {
+        let vault = Address::repeat_byte(1);
+        let other_vault = Address::repeat_byte(2);
+        let asset = Address::repeat_byte(3);
+        let user = Address::repeat_byte(4);
+
+        let deposit = vec![
+            // depositing 11 of the asset for 10 shares
+            mint(vault, asset, 11, 0),
+            transfer(asset, user, vault, 11, 1),
+            transfer(vault, Address::ZERO, user, 10, 2),
+        ];
+        let redemptions = vec![
+            // redeeming 4 shares for 5 of the asset, then 2 for 3, the last
+            // redemption sets the rate
+            burn(other_vault, asset, 5, 0),
+            transfer(other_vault, user, Address::ZERO, 4, 1),
+            transfer(asset, other_vault, user, 5, 2),
+            burn(other_vault, asset, 3, 3),
+            transfer(other_vault, user, Address::ZERO, 2, 4),
+            transfer(asset, other_vault, user, 3, 5),
+        ];
+        let no_shares = vec![
+            // a conversion without a share mint or a plain share transfer
+            // doesn't set a rate
+            mint(Address::repeat_byte(5), asset, 1, 0),
+            transfer(Address::repeat_byte(5), user, vault, 1, 1),
+        ];
+        let dex_swap = vec![Action::Swap(NormalizedSwap {
+            protocol: Protocol::UniswapV3,
+            token_in: token(vault),
+            amount_in: Rational::from(1),
+            token_out: token(asset),
+            amount_out: Rational::from(2),
+            ..Default::default()
+        })];
+
+        let rates = vault_share_rates([
+            deposit.as_slice(),
+            redemptions.as_slice(),
+            no_shares.as_slice(),
+            dex_swap.as_slice(),
+        ]);
+
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[&vault].underlying, asset);
+        assert_eq!(rates[&vault].rate, Rational::from_unsigneds(11u64, 10));
+        assert_eq!(rates[&other_vault].rate, Rational::from_unsigneds(3u64, 2));
+    }
+}
@@ -0,0 +1,331 @@
+//! Funding traces of searcher wallets.
+//!
+//! Searchers rotate through fresh eoas, funded just before their first bundle,
+//! so an eoa on its own says little about who runs it. The first transfer of
+//! eth, weth or a dollar stablecoin into each address seen while processing
+//! blocks is kept as its [`FundingEdge`], first in transaction and trace order
+//! within a block. Transfers below [`min_funding_amount`] are dust, not
+//! funding, and edges older than the funding retention are pruned, so the
+//! table only holds the recent fundings a new searcher eoa is traced through.
+//!
+//! When a new searcher eoa shows up, [`trace_wallet_funding`] walks these edges
+//! back from it, at most [`MAX_FUNDING_DEPTH`] hops, until it reaches a known
+//! source: a cex hot wallet, a Tornado Cash pool or a searcher already in the
+//! registry. The resulting [`FundingTrace`] is stored on the eoa's
+//! [`SearcherInfo`](crate::db::searcher::SearcherInfo).
+//!
+//! Addresses funded before the first processed block, or before the retention,
+//! have no edge, so their traces end at the last address that has one, as
+//! [`FundingSource::Unknown`].
+use std::sync::Arc;
+
+use alloy_primitives::{hex, Address, B256};
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{DAI_ADDRESS, ETH_ADDRESS, USDC_ADDRESS, USDT_ADDRESS, WETH_ADDRESS},
+    db::{redefined_types::primitives::*, traits::LibmdbxReader},
+    implement_table_value_codecs_with_zc,
+    normalized_actions::{Action, NormalizedEthTransfer, NormalizedTransfer},
+    tree::BlockTree,
+    FastHashMap, ToFloatNearest, ToScaledRational, TreeSearchBuilder,
+};
+
+/// Funding transfers walked back from a new searcher eoa
+pub const MAX_FUNDING_DEPTH: usize = 4;
+
+/// The Tornado Cash eth pools, withdrawals are paid out by them
+pub const TORNADO_CASH_POOLS: [Address; 4] = [
+    // 0.1 eth
+    Address::new(hex!("12D66f87A04A9E220743712cE6d9bB1B5616B8Fc")),
+    // 1 eth
+    Address::new(hex!("47CE0C6eD5B0Ce3d3A51fdb1C52DC66a7c3c2936")),
+    // 10 eth
+    Address::new(hex!("910Cbd523D972eb0a6f4cAe4618aD62622b39DbF")),
+    // 100 eth
+    Address::new(hex!("A160cdAB225685dA1d56aa342Ad8841c3b53f291")),
+];
+
+/// Smallest transfer of the token that counts as funding, `None` for the
+/// tokens wallets aren't funded with
+pub fn min_funding_amount(token: Address) -> Option<f64> {
+    match token {
+        ETH_ADDRESS | WETH_ADDRESS => Some(0.01),
+        USDC_ADDRESS | USDT_ADDRESS | DAI_ADDRESS => Some(10.0),
+        _ => None,
+    }
+}
+
+/// The first funding transfer into an address
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct FundingEdge {
+    pub funder:       Address,
+    pub tx_hash:      B256,
+    pub block_number: u64,
+    /// The token sent, [`ETH_ADDRESS`] for eth
+    pub token:        Address,
+    /// Amount of the token sent
+    pub value:        f64,
+}
+
+implement_table_value_codecs_with_zc!(FundingEdgeRedefined);
+
+impl FundingEdge {
+    /// The first funding transfer into each address funded in the block.
+    /// Coinbase transfers are builder payments, not funding
+    pub fn from_tree(tree: Arc<BlockTree<Action>>) -> FastHashMap<Address, FundingEdge> {
+        let block_number = tree.header.number;
+        let search = TreeSearchBuilder::default()
+            .with_actions([Action::is_eth_transfer, Action::is_transfer]);
+        let mut edges = FastHashMap::default();
+
+        for root in &tree.tx_roots {
+            // sorted by trace index
+            for action in root.collect(&search) {
+                let Some((from, to, token, value)) = funding_transfer(action) else { continue };
+                if from == to || min_funding_amount(token).map_or(true, |min| value < min) {
+                    continue
+                }
+
+                edges.entry(to).or_insert_with(|| FundingEdge {
+                    funder: from,
+                    tx_hash: root.tx_hash,
+                    block_number,
+                    token,
+                    value,
+                });
+            }
+        }
+
+        edges
+    }
+}
+
+fn funding_transfer(action: Action) -> Option<(Address, Address, Address, f64)> {
+    match action {
+        Action::EthTransfer(NormalizedEthTransfer {
+            from,
+            to,
+            value,
+            coinbase_transfer: false,
+            ..
+        }) => Some((from, to, ETH_ADDRESS, value.to_scaled_rational(18).to_float())),
+        Action::Transfer(NormalizedTransfer { from, to, token, amount, .. }) => {
+            Some((from, to, token.address, amount.to_float()))
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub enum FundingSource {
+    /// A cex hot wallet, with the name of the exchange if it's known
+    Cex(String),
+    Tornado,
+    /// A searcher eoa already in the registry, which runs the funded eoa too
+    Searcher(Address),
+    Unknown,
+}
+
+impl FundingSource {
+    /// Name of the source's kind, as used to filter funding traces
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FundingSource::Cex(_) => "cex",
+            FundingSource::Tornado => "tornado",
+            FundingSource::Searcher(_) => "searcher",
+            FundingSource::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct FundingTrace {
+    /// The funding transfers from the eoa back to the source, the first one
+    /// funded the eoa
+    pub path:   Vec<FundingEdge>,
+    pub source: FundingSource,
+}
+
+impl FundingTrace {
+    /// The address the funds came from
+    pub fn origin(&self) -> Option<Address> {
+        self.path.last().map(|edge| edge.funder)
+    }
+}
+
+/// Where a funding trace reads the funding edges and sources from
+pub trait FundingLookup {
+    fn funding_edge(&self, address: Address) -> eyre::Result<Option<FundingEdge>>;
+
+    /// The known source the funder is, if it's one
+    fn funding_source(&self, funder: Address) -> eyre::Result<Option<FundingSource>>;
+}
+
+/// Reads the funding from the db, along with the edges of the block being
+/// processed, which are still queued for the writer when its bundles are
+pub struct DbFundingLookup<'a, DB> {
+    pub db:          &'a DB,
+    pub block_edges: &'a FastHashMap<Address, FundingEdge>,
+}
+
+impl<DB: LibmdbxReader> FundingLookup for DbFundingLookup<'_, DB> {
+    fn funding_edge(&self, address: Address) -> eyre::Result<Option<FundingEdge>> {
+        // a stored edge is from an earlier block, so it's the first funding
+        Ok(self
+            .db
+            .try_fetch_wallet_funding(address)?
+            .or_else(|| self.block_edges.get(&address).cloned()))
+    }
+
+    fn funding_source(&self, funder: Address) -> eyre::Result<Option<FundingSource>> {
+        if TORNADO_CASH_POOLS.contains(&funder) {
+            return Ok(Some(FundingSource::Tornado))
+        }
+        if let Some(metadata) = self.db.try_fetch_address_metadata(funder)? {
+            if metadata.is_cex() {
+                let name = metadata
+                    .entity_name
+                    .or(metadata.nametag)
+                    .unwrap_or_default();
+                return Ok(Some(FundingSource::Cex(name)))
+            }
+        }
+        if self.db.try_fetch_searcher_eoa_info(funder)?.is_some() {
+            return Ok(Some(FundingSource::Searcher(funder)))
+        }
+
+        Ok(None)
+    }
+}
+
+/// Walks the funding edges back from the eoa until it reaches a known source
+/// or [`MAX_FUNDING_DEPTH`] edges
+pub fn trace_wallet_funding<L: FundingLookup>(
+    lookup: &L,
+    eoa: Address,
+) -> eyre::Result<FundingTrace> {
+    let mut path: Vec<FundingEdge> = Vec::new();
+    let mut address = eoa;
+
+    while path.len() < MAX_FUNDING_DEPTH {
+        let Some(edge) = lookup.funding_edge(address)? else { break };
+        let funder = edge.funder;
+        // funding loops back on itself, e.g. a searcher topping up its own wallets
+        if funder == eoa || path.iter().any(|e| e.funder == funder) {
+            break
+        }
+        path.push(edge);
+
+        if let Some(source) = lookup.funding_source(funder)? {
+            return Ok(FundingTrace { path, source })
+        }
+        address = funder;
+    }
+
+    Ok(FundingTrace { path, source: FundingSource::Unknown })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MockLookup {
+        edges:   FastHashMap<Address, FundingEdge>,
+        sources: FastHashMap<Address, FundingSource>,
+    }
+
+    impl MockLookup {
+        fn fund(mut self, to: u8, funder: Address) -> Self {
+            self.edges.insert(
+                Address::with_last_byte(to),
+                FundingEdge { funder, token: ETH_ADDRESS, value: 1.0, ..Default::default() },
+            );
+            self
+        }
+    }
+
+    impl FundingLookup for MockLookup {
+        fn funding_edge(&self, address: Address) -> eyre::Result<Option<FundingEdge>> {
+            Ok(self.edges.get(&address).cloned())
+        }
+
+        fn funding_source(&self, funder: Address) -> eyre::Result<Option<FundingSource>> {
+            if TORNADO_CASH_POOLS.contains(&funder) {
+                return Ok(Some(FundingSource::Tornado))
+            }
+            Ok(self.sources.get(&funder).cloned())
+        }
+    }
+
+    fn addr(byte: u8) -> Address {
+        Address::with_last_byte(byte)
+    }
+
+    #[test]
+    fn test_traces_to_tornado() {
+        let lookup = MockLookup::default()
+            .fund(1, addr(2))
+            .fund(2, TORNADO_CASH_POOLS[1]);
+
+        let trace = trace_wallet_funding(&lookup, addr(1)).unwrap();
+        assert_eq!(trace.source, FundingSource::Tornado);
+        assert_eq!(trace.path.len(), 2);
+        assert_eq!(trace.origin(), Some(TORNADO_CASH_POOLS[1]));
+    }
+
+    #[test]
+    fn test_traces_to_cex() {
+        let mut lookup = MockLookup::default().fund(1, addr(9));
+        lookup
+            .sources
+            .insert(addr(9), FundingSource::Cex("Binance".to_string()));
+
+        let trace = trace_wallet_funding(&lookup, addr(1)).unwrap();
+        assert_eq!(trace.source, FundingSource::Cex("Binance".to_string()));
+        assert_eq!(trace.path.len(), 1);
+    }
+
+    #[test]
+    fn test_stops_at_funding_cycle() {
+        // 1 <- 2 <- 3 <- 2
+        let lookup = MockLookup::default()
+            .fund(1, addr(2))
+            .fund(2, addr(3))
+            .fund(3, addr(2));
+
+        let trace = trace_wallet_funding(&lookup, addr(1)).unwrap();
+        assert_eq!(trace.source, FundingSource::Unknown);
+        assert_eq!(trace.path.iter().map(|e| e.funder).collect::<Vec<_>>(), vec![addr(2), addr(3)]);
+
+        // funded back by the eoa itself
+        let lookup = MockLookup::default().fund(1, addr(2)).fund(2, addr(1));
+        let trace = trace_wallet_funding(&lookup, addr(1)).unwrap();
+        assert_eq!(trace.path.len(), 1);
+    }
+
+    #[test]
+    fn test_stops_at_max_depth() {
+        // a chain longer than the depth that ends at a cex
+        let mut lookup = MockLookup::default();
+        for i in 1..=MAX_FUNDING_DEPTH as u8 + 1 {
+            lookup = lookup.fund(i, addr(i + 1));
+        }
+        lookup
+            .sources
+            .insert(addr(MAX_FUNDING_DEPTH as u8 + 2), FundingSource::Cex("Binance".to_string()));
+
+        let trace = trace_wallet_funding(&lookup, addr(1)).unwrap();
+        assert_eq!(trace.source, FundingSource::Unknown);
+        assert_eq!(trace.path.len(), MAX_FUNDING_DEPTH);
+    }
+}
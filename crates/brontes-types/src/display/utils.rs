@@ -384,6 +384,9 @@ pub fn display_atomic_backrun(bundle: &Bundle, f: &mut fmt::Formatter) -> fmt::R
                 writeln!(f, "{}", line.bright_green())?;
             }
         }
+        AtomicArbType::Rebalance => {
+            writeln!(f, "{}\n", "Liquidity Rebalance".bold().bright_black())?;
+        }
     }
 
     // Tx details
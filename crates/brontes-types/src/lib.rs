@@ -17,6 +17,7 @@ pub mod hasher;
 pub mod rayon_utils;
 pub use hasher::*;
 pub use rayon_utils::*;
+pub mod action_filter;
 pub mod action_iter;
 pub use action_iter::*;
 pub mod executor;
@@ -41,6 +42,11 @@ pub use tree::*;
 pub mod utils;
 pub use utils::*;
 pub mod protocol;
+/// The exported result types without the node stack, see
+/// [`MevBlockWithClassified::to_lite`](db::mev_block::MevBlockWithClassified::to_lite)
+pub use brontes_types_lite as lite;
 pub use protocol::*;
 pub mod channel_alerts;
 pub use channel_alerts::*;
+pub mod live_config;
+pub mod live_events;
@@ -0,0 +1,231 @@
+//! Settings of the indexer that can be changed while it runs.
+//!
+//! The [`LiveConfig`] holds the settings that are read again for every block
+//! rather than fixed when the pipeline is built: the action filter's allow and
+//! deny lists, the cex quote sanity thresholds, and the watchlists of
+//! searchers with the destinations their bundles are alerted to. `brontes
+//! run` sets it from its arguments, or from the toml file passed with
+//! `--live-config`, which is read again on `SIGHUP` or a request to the reload
+//! endpoint. A reloaded file is validated before it replaces the active
+//! config, and it replaces it as a whole. Each block takes a snapshot of the
+//! active config into its [`Metadata`] when its data is loaded and is
+//! inspected and alerted on with that snapshot, so a block never sees half of
+//! a reload, and blocks already loaded finish with the config they were
+//! loaded with.
+//!
+//! [`Metadata`]: crate::db::metadata::Metadata
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use alloy_primitives::Address;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::{action_filter::ActionFilter, db::cex::quotes::QuoteSanityConfig, mev::BundleHeader};
+
+static LIVE_CONFIG: Lazy<RwLock<Arc<LiveConfig>>> = Lazy::new(Default::default);
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveConfig {
+    /// Protocols, pools and tokens the inspectors are scoped to, see
+    /// [`ActionFilter`]
+    pub action_filter:       Option<ActionFilter>,
+    /// Thresholds of the cross-exchange checks of cex quotes
+    pub cex_quote_sanity:    QuoteSanityConfig,
+    /// Inspect with the cex quotes as stored, without the cross-exchange
+    /// checks
+    pub no_cex_quote_sanity: bool,
+    /// Searchers whose bundles are alerted on
+    pub watchlists:          Vec<Watchlist>,
+    /// Destinations the watchlists alert to, by name
+    pub alerts:              BTreeMap<String, AlertDestination>,
+}
+
+/// Searcher eoas and contracts whose bundles are sent to the watchlist's alert
+/// destinations
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Watchlist {
+    pub name:      String,
+    pub addresses: Vec<Address>,
+    /// Names of the [`LiveConfig::alerts`] destinations
+    pub alerts:    Vec<String>,
+}
+
+impl Watchlist {
+    pub fn watches(&self, bundle: &BundleHeader) -> bool {
+        self.addresses
+            .iter()
+            .any(|address| bundle.eoa == *address || bundle.mev_contract == Some(*address))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlertDestination {
+    /// Logs the bundle at warn level
+    Log,
+    /// Posts the bundle as json to the url
+    Webhook { url: String },
+}
+
+impl LiveConfig {
+    /// Reads and validates the config in the file
+    pub fn from_toml_file(path: impl AsRef<Path>) -> eyre::Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("failed to read config {}: {e}", path.display()))?;
+        let config: Self = toml::from_str(&file)?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> eyre::Result<()> {
+        if let Some(filter) = &self.action_filter {
+            filter.validate()?;
+        }
+        self.cex_quote_sanity.validate()?;
+
+        for (name, destination) in &self.alerts {
+            if let AlertDestination::Webhook { url } = destination {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    eyre::bail!("alert {name} has a webhook url that isn't http: {url}");
+                }
+            }
+        }
+        for (i, watchlist) in self.watchlists.iter().enumerate() {
+            if watchlist.name.is_empty() {
+                eyre::bail!("watchlist {i} has no name");
+            }
+            if self.watchlists[..i]
+                .iter()
+                .any(|other| other.name == watchlist.name)
+            {
+                eyre::bail!("watchlist {} is defined twice", watchlist.name);
+            }
+            if let Some(alert) = watchlist
+                .alerts
+                .iter()
+                .find(|alert| !self.alerts.contains_key(*alert))
+            {
+                eyre::bail!("watchlist {} alerts to {alert}, which isn't defined", watchlist.name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The watchlists watching the bundle, with the destinations to alert
+    pub fn watch_alerts<'a>(
+        &'a self,
+        bundle: &'a BundleHeader,
+    ) -> impl Iterator<Item = (&'a Watchlist, &'a AlertDestination)> + 'a {
+        self.watchlists
+            .iter()
+            .filter(|watchlist| watchlist.watches(bundle))
+            .flat_map(move |watchlist| {
+                watchlist
+                    .alerts
+                    .iter()
+                    .filter_map(move |alert| Some((watchlist, self.alerts.get(alert)?)))
+            })
+    }
+
+    /// Thresholds of the cex quote checks, if they're enabled
+    pub fn quote_sanity(&self) -> Option<QuoteSanityConfig> {
+        (!self.no_cex_quote_sanity).then_some(self.cex_quote_sanity)
+    }
+
+    /// Settings that differ between the configs, as `setting: old -> new`
+    pub fn changes(&self, new: &Self) -> Vec<String> {
+        let (Ok(serde_json::Value::Object(old)), Ok(serde_json::Value::Object(new))) =
+            (serde_json::to_value(self), serde_json::to_value(new))
+        else {
+            return vec![]
+        };
+
+        old.iter()
+            .filter_map(|(setting, old_value)| {
+                let new_value = new.get(setting)?;
+                (old_value != new_value).then(|| format!("{setting}: {old_value} -> {new_value}"))
+            })
+            .collect()
+    }
+}
+
+/// The active config, a snapshot that a reload doesn't change
+pub fn live_config() -> Arc<LiveConfig> {
+    LIVE_CONFIG.read().clone()
+}
+
+/// Replaces the active config, returning the one it replaced
+pub fn set_live_config(config: LiveConfig) -> Arc<LiveConfig> {
+    std::mem::replace(&mut *LIVE_CONFIG.write(), Arc::new(config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Protocol;
+
+    #[test]
+    fn test_changes_and_validation() {
+        let old = LiveConfig::default();
+        let mut new = LiveConfig { no_cex_quote_sanity: true, ..Default::default() };
+        assert_eq!(old.changes(&new), vec!["no_cex_quote_sanity: false -> true".to_string()]);
+        assert_eq!(new.quote_sanity(), None);
+
+        new.cex_quote_sanity.min_exchanges = 1;
+        assert!(new.validate().is_err());
+
+        let mut filter = ActionFilter::default();
+        filter.include.protocols.insert(Protocol::UniswapV2);
+        filter.exclude.protocols.insert(Protocol::UniswapV2);
+        let new = LiveConfig { action_filter: Some(filter), ..Default::default() };
+        assert!(new.validate().is_err());
+    }
+
+    #[test]
+    fn test_watchlists() {
+        let config: LiveConfig = toml::from_str(
+            r#"
+            [alerts.ops]
+            kind = "webhook"
+            url = "https://alerts.example/hook"
+
+            [alerts.logs]
+            kind = "log"
+
+            [[watchlists]]
+            name = "rivals"
+            addresses = ["0x0000000000000000000000000000000000000001"]
+            alerts = ["ops", "logs"]
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let mut bundle = BundleHeader::default();
+        assert_eq!(config.watch_alerts(&bundle).count(), 0);
+        bundle.mev_contract = Some(Address::with_last_byte(1));
+        assert_eq!(
+            config
+                .watch_alerts(&bundle)
+                .map(|(_, destination)| destination)
+                .collect::<Vec<_>>(),
+            vec![&config.alerts["ops"], &config.alerts["logs"]]
+        );
+
+        let mut undefined = config.clone();
+        undefined.watchlists[0].alerts.push("pager".to_string());
+        assert!(undefined.validate().is_err());
+        let mut bad_url = config;
+        bad_url.alerts.insert(
+            "ops".to_string(),
+            AlertDestination::Webhook { url: "alerts.example".to_string() },
+        );
+        assert!(bad_url.validate().is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Partial results of the blocks being processed, for live consumers.
+//!
+//! Alerting and other latency sensitive consumers don't need to wait for a
+//! block's results to be composed and written before acting on them. When
+//! live events are enabled, each stage of a block emits an event as it
+//! completes: the tree was built, an inspector finished with its candidates,
+//! the results were composed and finally the [`LiveEvent::Committed`] marker
+//! once they were committed to the db. Events carry a sequence number that
+//! increases by one per event, so a consumer that fell behind sees the gap.
+//! The run's [`LiveEvents`] travel with each block's metadata, when nobody is
+//! subscribed emitting is a no-op.
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use crate::mev::{Bundle, MevBlock};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum LiveEvent {
+    /// The block's transactions were classified into its tree
+    TreeBuilt { transactions: usize },
+    /// An inspector finished, with the bundles it found before composition
+    InspectorDone { inspector: String, candidates: usize },
+    /// The final results of the block
+    Composed { mev_block: Box<MevBlock>, bundles: Vec<Bundle> },
+    /// The results of the block were committed to the db, no more events
+    /// follow for it
+    Committed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LiveEventMessage {
+    pub seq:          u64,
+    pub block_number: u64,
+    #[serde(flatten)]
+    pub event:        LiveEvent,
+}
+
+/// Sender of the live events of a run, clones share the subscribers and the
+/// sequence
+#[derive(Debug, Clone)]
+pub struct LiveEvents {
+    events:   broadcast::Sender<LiveEventMessage>,
+    /// Next sequence number, held while sending so events are sent in order
+    sequence: Arc<Mutex<u64>>,
+}
+
+impl LiveEvents {
+    /// Subscribers that fall more than `capacity` events behind miss the
+    /// oldest ones
+    pub fn new(capacity: usize) -> Self {
+        Self { events: broadcast::channel(capacity).0, sequence: Arc::default() }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEventMessage> {
+        self.events.subscribe()
+    }
+
+    pub fn has_subscribers(&self) -> bool {
+        self.events.receiver_count() != 0
+    }
+
+    /// Emits an event of the block. The event is only built if anyone is
+    /// subscribed
+    pub fn emit(&self, block_number: u64, event: impl FnOnce() -> LiveEvent) {
+        if !self.has_subscribers() {
+            return
+        }
+
+        let event = event();
+        let mut seq = self.sequence.lock();
+        let _ = self
+            .events
+            .send(LiveEventMessage { seq: *seq, block_number, event });
+        *seq += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_events_are_sequenced() {
+        let live_events = LiveEvents::new(16);
+        live_events.emit(1, || panic!("built without subscribers"));
+
+        let mut events = live_events.subscribe();
+        live_events.emit(1, || LiveEvent::TreeBuilt { transactions: 3 });
+        // clones share the sequence
+        live_events.clone().emit(1, || LiveEvent::Committed);
+
+        let first = events.try_recv().unwrap();
+        let second = events.try_recv().unwrap();
+        assert_eq!(first.seq, 0);
+        assert_eq!(second.seq, 1);
+        assert!(matches!(second.event, LiveEvent::Committed));
+        assert_eq!(
+            serde_json::to_value(&first).unwrap(),
+            serde_json::json!({
+                "seq": 0, "block_number": 1, "stage": "tree_built", "transactions": 3
+            })
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Signed attestations of sandwich victim losses.
+//!
+//! Integrations that compensate victims need a verifiable claim of what a
+//! victim lost. For every victim transaction of a sandwich an EIP-712 typed
+//! [`VictimAttestation`] is signed with a configurable key, so downstream
+//! claims systems can verify the attestation against the signer address
+//! on-chain or off-chain.
+//!
+//! Methodology version 2 attests the loss measured for each victim, the usd
+//! value of what its swaps received below their pre-frontrun baseline. The
+//! revenue of the sandwich (profit plus bribe) left after the measured losses
+//! is split evenly across the victims without a measurement, such as victims
+//! that weren't swaps or swaps without a price.
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_sol_types::{eip712_domain, Eip712Domain, SolStruct};
+use reth_primitives::sign_message;
+use serde::Serialize;
+
+use crate::{
+    mev::{Bundle, BundleData},
+    FastHashMap,
+};
+
+pub const METHODOLOGY_VERSION: u16 = 2;
+
+/// Losses are attested in micro usd, as EIP-712 has no decimals
+const USD_DECIMALS: f64 = 1_000_000.0;
+
+alloy_sol_macro::sol! {
+    #[derive(Debug, PartialEq)]
+    struct VictimAttestation {
+        uint64 blockNumber;
+        bytes32 victimTxHash;
+        bytes32[] frontrunTxHashes;
+        bytes32 backrunTxHash;
+        uint256 lossUsdMicros;
+        uint16 methodologyVersion;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignedVictimAttestation {
+    pub block_number:        u64,
+    pub victim_tx_hash:      B256,
+    pub frontrun_tx_hashes:  Vec<B256>,
+    pub backrun_tx_hash:     B256,
+    pub loss_usd:            f64,
+    pub loss_usd_micros:     U256,
+    pub methodology_version: u16,
+    pub signer:              Address,
+    /// EIP-712 signing hash of the attestation
+    pub digest:              B256,
+    /// 65 byte `r | s | v` signature over the digest
+    pub signature:           Bytes,
+}
+
+pub struct AttestationSigner {
+    secret: B256,
+    signer: Address,
+    domain: Eip712Domain,
+}
+
+impl AttestationSigner {
+    pub fn new(secret: B256, chain_id: u64) -> eyre::Result<Self> {
+        let domain = eip712_domain! {
+            name: "Brontes Victim Attestation",
+            version: "1",
+            chain_id: chain_id,
+        };
+
+        // recover the signer address from a signature so we don't have to
+        // derive the public key ourselves
+        let signature = sign_message(secret, B256::ZERO)?;
+        let signer = signature
+            .recover_signer(B256::ZERO)
+            .ok_or_else(|| eyre::eyre!("invalid attestation signer key"))?;
+
+        Ok(Self { secret, signer, domain })
+    }
+
+    pub fn signer(&self) -> Address {
+        self.signer
+    }
+
+    /// Signs an attestation for every victim transaction of the bundle.
+    /// Returns nothing for bundles that aren't sandwiches.
+    pub fn attest_bundle(&self, bundle: &Bundle) -> eyre::Result<Vec<SignedVictimAttestation>> {
+        victim_attestations(bundle)
+            .into_iter()
+            .map(|(attestation, loss_usd)| self.sign(attestation, loss_usd))
+            .collect()
+    }
+
+    fn sign(
+        &self,
+        attestation: VictimAttestation,
+        loss_usd: f64,
+    ) -> eyre::Result<SignedVictimAttestation> {
+        let digest = attestation.eip712_signing_hash(&self.domain);
+        let signature = sign_message(self.secret, digest)?;
+
+        let mut bytes = Vec::with_capacity(65);
+        bytes.extend_from_slice(&signature.r.to_be_bytes::<32>());
+        bytes.extend_from_slice(&signature.s.to_be_bytes::<32>());
+        bytes.push(27 + signature.odd_y_parity as u8);
+
+        Ok(SignedVictimAttestation {
+            block_number: attestation.blockNumber,
+            victim_tx_hash: attestation.victimTxHash,
+            frontrun_tx_hashes: attestation.frontrunTxHashes,
+            backrun_tx_hash: attestation.backrunTxHash,
+            loss_usd,
+            loss_usd_micros: attestation.lossUsdMicros,
+            methodology_version: attestation.methodologyVersion,
+            signer: self.signer,
+            digest,
+            signature: bytes.into(),
+        })
+    }
+}
+
+/// The unsigned attestations of the bundle together with the attested loss in
+/// usd
+pub fn victim_attestations(bundle: &Bundle) -> Vec<(VictimAttestation, f64)> {
+    let (block_number, victim_txs, frontrun_txs, backrun_tx, executions) = match &bundle.data {
+        BundleData::Sandwich(s) => (
+            s.block_number,
+            &s.victim_swaps_tx_hashes,
+            &s.frontrun_tx_hash,
+            s.backrun_tx_hash,
+            &s.victim_execution,
+        ),
+        BundleData::JitSandwich(s) => (
+            s.block_number,
+            &s.victim_swaps_tx_hashes,
+            &s.frontrun_tx_hash,
+            s.backrun_tx_hash,
+            &s.victim_execution,
+        ),
+        _ => return vec![],
+    };
+
+    let victim_txs = victim_txs.iter().flatten().copied().collect::<Vec<_>>();
+    if victim_txs.is_empty() {
+        return vec![]
+    }
+
+    let mut measured: FastHashMap<B256, f64> = FastHashMap::default();
+    for execution in executions {
+        if let Some(loss_usd) = execution.loss_usd {
+            *measured.entry(execution.tx_hash).or_default() += loss_usd;
+        }
+    }
+    let unmeasured = victim_txs
+        .iter()
+        .filter(|tx| !measured.contains_key(tx))
+        .count();
+    let revenue = (bundle.header.profit_usd + bundle.header.bribe_usd).max(0.0);
+    let unmeasured_loss_usd = (unmeasured > 0)
+        .then(|| (revenue - measured.values().sum::<f64>()).max(0.0) / unmeasured as f64)
+        .unwrap_or_default();
+
+    victim_txs
+        .into_iter()
+        .map(|victim_tx_hash| {
+            let loss_usd = measured
+                .get(&victim_tx_hash)
+                .copied()
+                .unwrap_or(unmeasured_loss_usd);
+            (
+                VictimAttestation {
+                    blockNumber:        block_number,
+                    victimTxHash:       victim_tx_hash,
+                    frontrunTxHashes:   frontrun_txs.clone(),
+                    backrunTxHash:      backrun_tx,
+                    lossUsdMicros:      U256::from((loss_usd * USD_DECIMALS) as u128),
+                    methodologyVersion: METHODOLOGY_VERSION,
+                },
+                loss_usd,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::Signature;
+
+    use super::*;
+    use crate::mev::{execution_quality::VictimExecution, BundleHeader, Sandwich};
+
+    #[test]
+    fn test_sandwich_attestations() {
+        let bundle = Bundle {
+            header: BundleHeader { profit_usd: 80.0, bribe_usd: 20.0, ..Default::default() },
+            data:   BundleData::Sandwich(Sandwich {
+                block_number: 10,
+                frontrun_tx_hash: vec![B256::repeat_byte(1)],
+                victim_swaps_tx_hashes: vec![vec![B256::repeat_byte(2), B256::repeat_byte(3)]],
+                backrun_tx_hash: B256::repeat_byte(4),
+                ..Default::default()
+            }),
+        };
+
+        let signer = AttestationSigner::new(B256::repeat_byte(7), 1).unwrap();
+        let attestations = signer.attest_bundle(&bundle).unwrap();
+
+        assert_eq!(attestations.len(), 2);
+        assert_eq!(attestations[0].loss_usd, 50.0);
+        assert_eq!(attestations[0].loss_usd_micros, U256::from(50_000_000u64));
+        assert_eq!(attestations[1].victim_tx_hash, B256::repeat_byte(3));
+        assert_eq!(attestations[0].signature.len(), 65);
+        assert_ne!(attestations[0].digest, attestations[1].digest);
+
+        // the signature recovers to the signer over the EIP-712 digest of the
+        // attested fields
+        let domain = eip712_domain! {
+            name: "Brontes Victim Attestation",
+            version: "1",
+            chain_id: 1,
+        };
+        for attestation in &attestations {
+            let digest = VictimAttestation {
+                blockNumber:        attestation.block_number,
+                victimTxHash:       attestation.victim_tx_hash,
+                frontrunTxHashes:   attestation.frontrun_tx_hashes.clone(),
+                backrunTxHash:      attestation.backrun_tx_hash,
+                lossUsdMicros:      attestation.loss_usd_micros,
+                methodologyVersion: attestation.methodology_version,
+            }
+            .eip712_signing_hash(&domain);
+            assert_eq!(digest, attestation.digest);
+
+            let bytes = &attestation.signature;
+            let signature = Signature {
+                r:            U256::from_be_slice(&bytes[..32]),
+                s:            U256::from_be_slice(&bytes[32..64]),
+                odd_y_parity: bytes[64] == 28,
+            };
+            assert_eq!(signature.recover_signer(digest), Some(signer.signer()));
+        }
+
+        assert!(signer.attest_bundle(&Bundle::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_signer_address() {
+        let signer = AttestationSigner::new(B256::with_last_byte(1), 1).unwrap();
+        assert_eq!(
+            signer.signer(),
+            "0x7E5F4552091A69125d5DfCdf7b3c9D7E6D3a6A8A"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_measured_losses() {
+        let (measured, unmeasured, other) =
+            (B256::repeat_byte(2), B256::repeat_byte(3), B256::repeat_byte(5));
+        let execution =
+            |loss_usd| VictimExecution { tx_hash: measured, loss_usd, ..Default::default() };
+        let bundle = Bundle {
+            header: BundleHeader { profit_usd: 80.0, bribe_usd: 20.0, ..Default::default() },
+            data:   BundleData::Sandwich(Sandwich {
+                victim_swaps_tx_hashes: vec![vec![measured, unmeasured], vec![other]],
+                // a victim swapping on two pools of the sandwich
+                victim_execution: vec![execution(Some(30.0)), execution(Some(10.0))],
+                ..Default::default()
+            }),
+        };
+
+        let losses = victim_attestations(&bundle)
+            .into_iter()
+            .map(|(attestation, loss_usd)| (attestation.victimTxHash, loss_usd))
+            .collect::<Vec<_>>();
+        assert_eq!(losses, vec![(measured, 40.0), (unmeasured, 30.0), (other, 30.0)]);
+    }
+}
@@ -18,21 +18,41 @@ use super::{Mev, MevType};
 use crate::{
     db::redefined_types::primitives::B256Redefined,
     normalized_actions::{ClickhouseVecNormalizedSwap, NormalizedSwap, NormalizedSwapRedefined},
-    GasDetails, Protocol,
+    ClickhouseVecGasDetails, GasDetails, Protocol,
 };
 
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct AtomicArb {
-    pub tx_hash:      B256,
-    pub trigger_tx:   B256,
-    pub block_number: u64,
-    pub swaps:        Vec<NormalizedSwap>,
+    pub tx_hash:         B256,
+    pub trigger_tx:      B256,
+    pub block_number:    u64,
+    pub swaps:           Vec<NormalizedSwap>,
     #[redefined(same_fields)]
-    pub gas_details:  GasDetails,
+    pub gas_details:     GasDetails,
     #[redefined(same_fields)]
-    pub arb_type:     AtomicArbType,
+    pub arb_type:        AtomicArbType,
+    /// Transactions of the searcher that continue the arb started in
+    /// `tx_hash`, for arbs whose legs are split across adjacent
+    /// transactions. Empty for single transaction arbs.
+    #[serde(default)]
+    pub leg_tx_hashes:   Vec<B256>,
+    /// Gas details for each of the `leg_tx_hashes`.
+    #[serde(default)]
+    #[redefined(same_fields)]
+    pub leg_gas_details: Vec<GasDetails>,
+}
+
+impl AtomicArb {
+    pub fn is_cross_tx(&self) -> bool {
+        !self.leg_tx_hashes.is_empty()
+    }
+
+    fn all_gas_details(&self) -> impl Iterator<Item = &GasDetails> {
+        std::iter::once(&self.gas_details).chain(self.leg_gas_details.iter())
+    }
 }
 /// Represents the different types of atomic arb
 /// A triangle arb is a simple arb that goes from token A -> B -> C -> A
@@ -51,12 +71,16 @@ pub struct AtomicArb {
     Archive,
     Copy,
 )]
+#[archive(check_bytes)]
 pub enum AtomicArbType {
     #[default]
     Triangle,
     CrossPair(usize),
     StablecoinArb,
     LongTail,
+    /// Swaps of a market maker rebalancing inventory that look like an arb,
+    /// see [`ArbHistory`](crate::db::searcher::ArbHistory)
+    Rebalance,
 }
 impl Display for AtomicArbType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -65,6 +89,7 @@ impl Display for AtomicArbType {
             AtomicArbType::CrossPair(_) => writeln!(f, "Cross Pair Arbitrage"),
             AtomicArbType::StablecoinArb => writeln!(f, "Stablecoin Arbitrage"),
             AtomicArbType::LongTail => writeln!(f, "LongTail Arbitrage"),
+            AtomicArbType::Rebalance => writeln!(f, "Liquidity Rebalance"),
         }
     }
 }
@@ -75,23 +100,33 @@ self_convert_redefined!(AtomicArbType);
 
 impl Mev for AtomicArb {
     fn total_gas_paid(&self) -> u128 {
-        self.gas_details.gas_paid()
+        self.all_gas_details().map(|gd| gd.gas_paid()).sum()
     }
 
     fn total_priority_fee_paid(&self, base_fee: u128) -> u128 {
-        self.gas_details.priority_fee(base_fee) * self.gas_details.gas_used
+        self.all_gas_details()
+            .map(|gd| gd.priority_fee(base_fee) * gd.gas_used)
+            .sum()
     }
 
     fn bribe(&self) -> u128 {
-        self.gas_details.coinbase_transfer.unwrap_or(0)
+        self.all_gas_details()
+            .filter_map(|gd| gd.coinbase_transfer)
+            .sum()
     }
 
     fn mev_transaction_hashes(&self) -> Vec<B256> {
-        vec![self.tx_hash]
+        let mut txs = vec![self.tx_hash];
+        txs.extend(self.leg_tx_hashes.iter().copied());
+        txs
     }
 
     fn mev_type(&self) -> MevType {
-        MevType::AtomicArb
+        if self.arb_type == AtomicArbType::Rebalance {
+            MevType::Rebalance
+        } else {
+            MevType::AtomicArb
+        }
     }
 
     fn protocols(&self) -> HashSet<Protocol> {
@@ -104,7 +139,7 @@ impl Serialize for AtomicArb {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("AtomicArb", 37)?;
+        let mut ser_struct = serializer.serialize_struct("AtomicArb", 18)?;
         ser_struct.serialize_field("tx_hash", &format!("{:?}", self.tx_hash))?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
         ser_struct.serialize_field("trigger_tx", &format!("{:?}", self.trigger_tx))?;
@@ -129,6 +164,21 @@ impl Serialize for AtomicArb {
         );
         ser_struct.serialize_field("gas_details", &gas_details)?;
         ser_struct.serialize_field("arb_type", &self.arb_type.to_string())?;
+
+        let leg_gas_details: ClickhouseVecGasDetails =
+            (self.leg_tx_hashes.clone(), self.leg_gas_details.clone()).into();
+        ser_struct.serialize_field("leg_gas_details.tx_hash", &leg_gas_details.tx_hash)?;
+        ser_struct.serialize_field(
+            "leg_gas_details.coinbase_transfer",
+            &leg_gas_details.coinbase_transfer,
+        )?;
+        ser_struct
+            .serialize_field("leg_gas_details.priority_fee", &leg_gas_details.priority_fee)?;
+        ser_struct.serialize_field("leg_gas_details.gas_used", &leg_gas_details.gas_used)?;
+        ser_struct.serialize_field(
+            "leg_gas_details.effective_gas_price",
+            &leg_gas_details.effective_gas_price,
+        )?;
         ser_struct.end()
     }
 }
@@ -148,5 +198,10 @@ impl DbRow for AtomicArb {
         "swaps.amount_out",
         "gas_details",
         "arb_type",
+        "leg_gas_details.tx_hash",
+        "leg_gas_details.coinbase_transfer",
+        "leg_gas_details.priority_fee",
+        "leg_gas_details.gas_used",
+        "leg_gas_details.effective_gas_price",
     ];
 }
@@ -10,13 +10,14 @@ use colored::Colorize;
 use indoc::indoc;
 use redefined::{self_convert_redefined, Redefined};
 use reth_primitives::B256;
+use reth_rpc_types::beacon::BlsPublicKey;
 use rkyv::{Archive, Deserialize as rDeser, Serialize as rSer};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use serde_with::serde_as;
 
 use super::MevType;
 use crate::{
-    db::redefined_types::primitives::{AddressRedefined, B256Redefined},
+    db::redefined_types::primitives::{AddressRedefined, B256Redefined, BlsPublicKeyRedefined},
     display::utils::formate_etherscan_address_url,
     ToFloatNearest, ToScaledRational,
 };
@@ -30,34 +31,47 @@ use crate::{
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct MevBlock {
-    pub block_hash:                  B256,
-    pub block_number:                u64,
+    pub block_hash: B256,
+    pub block_number: u64,
     #[redefined(same_fields)]
-    pub mev_count:                   MevCount,
-    pub eth_price:                   f64,
-    pub total_gas_used:              u128,
-    pub total_priority_fee:          u128,
-    pub total_bribe:                 u128,
-    pub total_mev_bribe:             u128,
+    pub mev_count: MevCount,
+    pub eth_price: f64,
+    pub total_gas_used: u128,
+    pub total_priority_fee: u128,
+    pub total_bribe: u128,
+    pub total_mev_bribe: u128,
     pub total_mev_priority_fee_paid: u128,
-    pub builder_address:             Address,
-    pub builder_name:                Option<String>,
-    pub builder_eth_profit:          f64,
-    pub builder_profit_usd:          f64,
+    pub builder_address: Address,
+    pub builder_name: Option<String>,
+    pub builder_eth_profit: f64,
+    pub builder_profit_usd: f64,
     // Builder MEV profit from their vertically integrated searchers (in USD)
-    pub builder_mev_profit_usd:      f64,
+    pub builder_mev_profit_usd: f64,
     // Bribes paid to the builder by their own searchers
-    pub builder_searcher_bribes:     u128,
+    pub builder_searcher_bribes: u128,
     // Bribes paid to the builder by their own searchers (in USD)
     pub builder_searcher_bribes_usd: f64,
-    pub builder_sponsorship_amount:  u128,
-    pub ultrasound_bid_adjusted:     bool,
-    pub proposer_fee_recipient:      Option<Address>,
-    pub proposer_mev_reward:         Option<u128>,
-    pub proposer_profit_usd:         Option<f64>,
-    pub total_mev_profit_usd:        f64,
-    pub possible_mev:                PossibleMevCollection,
+    pub builder_sponsorship_amount: u128,
+    pub ultrasound_bid_adjusted: bool,
+    pub proposer_fee_recipient: Option<Address>,
+    pub proposer_mev_reward: Option<u128>,
+    pub proposer_profit_usd: Option<f64>,
+    /// Beacon chain slot the block was proposed in
+    pub slot: Option<u64>,
+    pub proposer_index: Option<u64>,
+    pub proposer_pubkey: Option<BlsPublicKey>,
+    /// Amount of slots missed since the parent block
+    pub missed_slots: Option<u64>,
+    /// The block was mined before the merge. The builder fields describe the
+    /// miner and there is no proposer
+    pub pre_merge: bool,
+    pub total_mev_profit_usd: f64,
+    pub possible_mev: PossibleMevCollection,
+    /// Inspectors that weren't run on the block as its action summary ruled
+    /// out their mev
+    pub skipped_inspectors: Vec<String>,
 }
 
 impl fmt::Display for MevBlock {
@@ -150,7 +164,9 @@ impl fmt::Display for MevBlock {
         // Proposer section
         writeln!(f, "{}", "Proposer:".bold().red().underline())?;
 
-        if self.proposer_fee_recipient.is_none()
+        if self.pre_merge {
+            writeln!(f, "{}", "  - Mined before the merge".bold().red().underline())?;
+        } else if self.proposer_fee_recipient.is_none()
             || self.proposer_mev_reward.is_none()
             || self.proposer_profit_usd.is_none()
         {
@@ -170,6 +186,15 @@ impl fmt::Display for MevBlock {
             )?;
         }
 
+        if let (Some(slot), Some(proposer_index)) = (self.slot, self.proposer_index) {
+            writeln!(f, "  - Slot: {}", slot)?;
+            writeln!(f, "  - Proposer Index: {}", proposer_index)?;
+            if let Some(pubkey) = self.proposer_pubkey {
+                writeln!(f, "  - Proposer Pubkey: {:?}", pubkey)?;
+            }
+            writeln!(f, "  - Missed Slots: {}", self.missed_slots.unwrap_or_default())?;
+        }
+
         writeln!(f, "\n{}: {}", "Missed Mev".bold().red().underline(), self.possible_mev)?;
 
         Ok(())
@@ -187,6 +212,7 @@ fn format_profit(value: f64) -> String {
 
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Serialize, Row, Clone, Default, rDeser, rSer, Archive)]
+#[archive(check_bytes)]
 pub struct MevCount {
     pub bundle_count:         u64,
     pub sandwich_count:       Option<u64>,
@@ -218,7 +244,9 @@ impl MevCount {
                 self.atomic_backrun_count =
                     Some(self.atomic_backrun_count.unwrap_or_default().add(1))
             }
-            MevType::Jit => self.jit_count = Some(self.jit_count.unwrap_or_default().add(1)),
+            MevType::Jit | MevType::JitExtended => {
+                self.jit_count = Some(self.jit_count.unwrap_or_default().add(1))
+            }
             MevType::JitSandwich => {
                 self.jit_sandwich_count = Some(self.jit_sandwich_count.unwrap_or_default().add(1))
             }
@@ -276,6 +304,7 @@ impl fmt::Display for MevCount {
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Row, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct PossibleMevCollection(pub Vec<PossibleMev>);
 
 impl fmt::Display for PossibleMevCollection {
@@ -311,6 +340,7 @@ impl fmt::Display for PossibleMev {
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Row, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSer, rDeser, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct PossibleMev {
     pub tx_hash:     B256,
     pub tx_idx:      u64,
@@ -322,6 +352,7 @@ pub struct PossibleMev {
 
 #[serde_as]
 #[derive(Debug, PartialEq, Deserialize, Row, Clone, Default, Serialize, rSer, rDeser, Archive)]
+#[archive(check_bytes)]
 pub struct PossibleMevTriggers {
     pub is_private:        bool,
     pub coinbase_transfer: bool,
@@ -358,7 +389,7 @@ impl Serialize for MevBlock {
     where
         S: serde::Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("MevBlock", 33)?;
+        let mut ser_struct = serializer.serialize_struct("MevBlock", 45)?;
 
         ser_struct.serialize_field("block_hash", &format!("{:?}", self.block_hash))?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
@@ -425,6 +456,14 @@ impl Serialize for MevBlock {
         )?;
         ser_struct.serialize_field("proposer_mev_reward", &self.proposer_mev_reward)?;
         ser_struct.serialize_field("proposer_profit_usd", &self.proposer_profit_usd)?;
+        ser_struct.serialize_field("slot", &self.slot)?;
+        ser_struct.serialize_field("proposer_index", &self.proposer_index)?;
+        ser_struct.serialize_field(
+            "proposer_pubkey",
+            &self.proposer_pubkey.map(|key| format!("{:?}", key)),
+        )?;
+        ser_struct.serialize_field("missed_slots", &self.missed_slots)?;
+        ser_struct.serialize_field("pre_merge", &self.pre_merge)?;
         ser_struct.serialize_field("total_mev_profit_usd", &self.total_mev_profit_usd)?;
 
         let mut possible_tx_hashes = Vec::new();
@@ -497,6 +536,7 @@ impl Serialize for MevBlock {
             "possible_mev.triggers.high_priority_fee",
             &possible_high_priority_fee,
         )?;
+        ser_struct.serialize_field("skipped_inspectors", &self.skipped_inspectors)?;
 
         ser_struct.end()
     }
@@ -533,6 +573,11 @@ impl DbRow for MevBlock {
         "proposer_fee_recipient",
         "proposer_mev_reward",
         "proposer_profit_usd",
+        "slot",
+        "proposer_index",
+        "proposer_pubkey",
+        "missed_slots",
+        "pre_merge",
         "total_mev_profit_usd",
         "possible_mev.tx_hash",
         "possible_mev.tx_idx",
@@ -543,5 +588,6 @@ impl DbRow for MevBlock {
         "possible_mev.triggers.is_private",
         "possible_mev.triggers.coinbase_transfer",
         "possible_mev.triggers.high_priority_fee",
+        "skipped_inspectors",
     ];
 }
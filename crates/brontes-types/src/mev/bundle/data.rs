@@ -23,6 +23,7 @@ pub struct BundleDataWithRevenue {
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Deserialize, PartialEq, EnumIter, Clone, Display, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub enum BundleData {
     Sandwich(Sandwich),
     AtomicArb(AtomicArb),
@@ -120,6 +121,31 @@ impl Mev for BundleData {
     }
 }
 
+impl BundleData {
+    /// Swaps made by the searcher, victim swaps are left out
+    pub fn searcher_swaps(&self) -> Vec<&NormalizedSwap> {
+        match self {
+            BundleData::Sandwich(s) => s
+                .frontrun_swaps
+                .iter()
+                .flatten()
+                .chain(&s.backrun_swaps)
+                .collect(),
+            BundleData::JitSandwich(s) => s
+                .frontrun_swaps
+                .iter()
+                .flatten()
+                .chain(&s.backrun_swaps)
+                .collect(),
+            BundleData::AtomicArb(a) => a.swaps.iter().collect(),
+            BundleData::CexDex(c) => c.swaps.iter().collect(),
+            BundleData::CexDexQuote(c) => c.swaps.iter().collect(),
+            BundleData::Liquidation(l) => l.liquidation_swaps.iter().collect(),
+            BundleData::Jit(_) | BundleData::Unknown(_) => vec![],
+        }
+    }
+}
+
 impl From<Sandwich> for BundleData {
     fn from(value: Sandwich) -> Self {
         Self::Sandwich(value)
@@ -1,6 +1,6 @@
 use std::fmt::{self, Debug, Display};
 
-use alloy_primitives::Address;
+use alloy_primitives::{keccak256, Address};
 use clickhouse::{DbRow, Row};
 use colored::Colorize;
 use itertools::Itertools;
@@ -9,6 +9,7 @@ use reth_primitives::B256;
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 use serde_with::serde_as;
+use strum::AsRefStr;
 
 use super::MevType;
 use crate::{
@@ -17,7 +18,9 @@ use crate::{
         searcher::Fund,
         token_info::{TokenInfoWithAddress, TokenInfoWithAddressRedefined},
     },
+    normalized_actions::{ActionConfidence, FULL_CONFIDENCE},
     serde_utils::{addresss, option_addresss, txhash},
+    Protocol,
 };
 #[allow(unused_imports)]
 use crate::{
@@ -29,33 +32,99 @@ use crate::{
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct BundleHeader {
     pub block_number: u64,
 
-    pub tx_index:              u64,
+    pub tx_index:               u64,
     #[serde(with = "txhash")]
     // For a sandwich this is always the first frontrun tx hash
     pub tx_hash: B256,
+    /// Content addressed id of the bundle, see [`bundle_id`]. Set by the
+    /// composer once the bundle is finalized
+    #[serde(default, with = "txhash")]
+    pub bundle_id:              B256,
     #[serde(with = "addresss")]
-    pub eoa:                   Address,
+    pub eoa:                    Address,
     #[serde(with = "option_addresss")]
-    pub mev_contract:          Option<Address>,
+    pub mev_contract:           Option<Address>,
     #[redefined(same_fields)]
     #[serde(default)]
-    pub fund:                  Fund,
-    pub profit_usd:            f64,
+    pub fund:                   Fund,
+    pub profit_usd:             f64,
     // Total tx cost in USD
-    pub bribe_usd:             f64,
+    pub bribe_usd:              f64,
     #[redefined(same_fields)]
-    pub mev_type:              MevType,
+    pub mev_type:               MevType,
     // if we generated this arb without pricing
-    pub no_pricing_calculated: bool,
-    pub balance_deltas:        Vec<TransactionAccounting>,
+    pub no_pricing_calculated:  bool,
+    pub balance_deltas:         Vec<TransactionAccounting>,
+    /// Lowest classification confidence of the actions in the bundle
+    #[serde(default = "full_confidence")]
+    pub min_action_confidence:  f64,
+    /// Mean classification confidence of the actions in the bundle
+    #[serde(default = "full_confidence")]
+    pub mean_action_confidence: f64,
+    /// Whether the bundle landed in a builder-merged submission alongside
+    /// bundles of other searchers
+    #[serde(default)]
+    pub merged_submission:      bool,
+    /// The other transactions of the merged submission the bundle landed in,
+    /// approximated by its run of consecutive private transactions. Empty
+    /// unless the bundle is flagged as a merged submission
+    #[serde(default)]
+    pub co_bundled_txs:         Vec<B256>,
+    /// Where the value the bundle extracted went
+    #[serde(default)]
+    pub value_split:            ValueSplit,
+    /// How the searcher sourced the capital the bundle moved
+    #[serde(default)]
+    pub capital:                CapitalUsage,
+    /// Part of the searcher's gains made in tokens scored as untradeable
+    /// spam, taken out of the bundle's profit
+    #[serde(default)]
+    pub spam_profit_usd:        f64,
+}
+
+/// Version of the classification methodology hashed into bundle ids. Bump it
+/// when a change to the inspectors or the composer changes which transactions
+/// make up a bundle, so ids of different versions don't join
+pub const BUNDLE_ID_VERSION: u16 = 1;
+
+/// Id of a bundle: the hash of the methodology version, its mev type and its
+/// sorted transaction hashes. Unlike row order or the first tx hash, it is
+/// stable across re-runs so bundles of different runs can be joined on it
+pub fn bundle_id(mev_type: MevType, tx_hashes: &[B256]) -> B256 {
+    let mut tx_hashes = tx_hashes.to_vec();
+    tx_hashes.sort_unstable();
+    tx_hashes.dedup();
+
+    let mev_type = mev_type.as_ref().as_bytes();
+    let mut preimage = Vec::with_capacity(2 + mev_type.len() + 32 * tx_hashes.len());
+    preimage.extend_from_slice(&BUNDLE_ID_VERSION.to_be_bytes());
+    preimage.extend_from_slice(mev_type);
+    tx_hashes
+        .iter()
+        .for_each(|tx| preimage.extend_from_slice(tx.as_slice()));
+
+    keccak256(preimage)
+}
+
+fn full_confidence() -> f64 {
+    FULL_CONFIDENCE
+}
+
+impl BundleHeader {
+    pub fn set_action_confidence(&mut self, confidence: ActionConfidence) {
+        self.min_action_confidence = confidence.min;
+        self.mean_action_confidence = confidence.mean;
+    }
 }
 
 #[serde_as]
 #[derive(Debug, Deserialize, Row, PartialEq, Clone, Default, Serialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct TransactionAccounting {
     pub tx_hash:        B256,
     pub address_deltas: Vec<AddressBalanceDeltas>,
@@ -75,6 +144,7 @@ impl Display for TransactionAccounting {
 #[serde_as]
 #[derive(Debug, Deserialize, Row, PartialEq, Clone, Default, Serialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct AddressBalanceDeltas {
     pub address:      Address,
     pub name:         Option<String>,
@@ -83,10 +153,42 @@ pub struct AddressBalanceDeltas {
 #[serde_as]
 #[derive(Debug, Deserialize, Row, PartialEq, Clone, Default, Serialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct TokenBalanceDelta {
     pub token:     TokenInfoWithAddress,
     pub amount:    f64,
     pub usd_value: f64,
+    #[redefined(same_fields)]
+    #[serde(default)]
+    pub valuation: ValuationMethod,
+}
+
+/// How the usd value of a token delta was derived
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+    AsRefStr,
+)]
+#[archive(check_bytes)]
+pub enum ValuationMethod {
+    /// Priced from dex or cex quotes
+    #[default]
+    Market,
+    /// NFT collection valued at its floor price, this is an estimate of what
+    /// the tokens could be sold for, not a price they traded at
+    NftFloorPrice,
+    /// No price was available, the usd value is zero
+    Unpriced,
 }
 
 impl Display for AddressBalanceDeltas {
@@ -106,12 +208,19 @@ impl Display for AddressBalanceDeltas {
                 format!("{:+.7}", delta.amount).red()
             };
 
+            let valuation = match delta.valuation {
+                ValuationMethod::Market => "",
+                ValuationMethod::NftFloorPrice => " at floor price",
+                ValuationMethod::Unpriced => " unpriced",
+            };
+
             writeln!(
                 f,
-                "  - {}: {} (USD Value: ${:.2})",
+                "  - {}: {} (USD Value: ${:.2}{})",
                 delta.token.inner.symbol.bold(),
                 amount_display,
-                delta.usd_value
+                delta.usd_value,
+                valuation
             )?;
         }
 
@@ -119,16 +228,123 @@ impl Display for AddressBalanceDeltas {
     }
 }
 
+/// Split of the value a bundle extracted between the searcher, the builder and
+/// proposer, the liquidity providers of the pools it swapped through and the
+/// protocols routing its swaps
+#[derive(Debug, Default, Deserialize, PartialEq, Clone, Serialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct ValueSplit {
+    /// The searcher's profit after costs
+    pub searcher_usd:      f64,
+    /// Gas and coinbase transfers paid by the bundle
+    pub builder_usd:       f64,
+    /// Swap fees paid to liquidity providers, per protocol. Only swaps on
+    /// protocols with a fixed fee tier are included
+    pub lp_fees:           Vec<ProtocolFee>,
+    /// Fees taken by aggregators, solvers and other protocols routing the
+    /// bundle's swaps
+    pub protocol_fees_usd: f64,
+}
+
+impl ValueSplit {
+    pub fn lp_fees_usd(&self) -> f64 {
+        self.lp_fees.iter().map(|fee| fee.usd).sum()
+    }
+
+    /// Total value extracted by the bundle
+    pub fn total_usd(&self) -> f64 {
+        self.searcher_usd + self.builder_usd + self.lp_fees_usd() + self.protocol_fees_usd
+    }
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Clone, Serialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct ProtocolFee {
+    #[redefined(same_fields)]
+    pub protocol: Protocol,
+    pub usd:      f64,
+}
+
+/// Where the searcher got the capital a bundle moved
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+    AsRefStr,
+)]
+#[archive(check_bytes)]
+pub enum CapitalSource {
+    /// The searcher's own tokens
+    #[default]
+    Inventory,
+    /// Borrowed from a flash loan provider, repaid with a fee in the same
+    /// transaction
+    Flashloan,
+    /// Tokens taken out of a v2 pair before paying for them, in the pair's
+    /// swap callback
+    FlashSwap,
+}
+
+/// The capital a bundle moved, where it came from and how hard it was worked
+#[derive(Debug, Default, Deserialize, PartialEq, Clone, Serialize, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct CapitalUsage {
+    #[redefined(same_fields)]
+    pub source:               CapitalSource,
+    /// Fees paid to the flash loan providers
+    pub flashloan_fee_usd:    f64,
+    /// Borrowed principal, or the input of the first swap when the searcher
+    /// used their own tokens
+    pub capital_deployed_usd: f64,
+    /// What the searcher stood to lose. Borrowed capital is repaid or the
+    /// transaction reverts, so only the costs of the bundle are at risk
+    pub capital_at_risk_usd:  f64,
+    /// Swap volume of the bundle over the capital deployed
+    pub turnover:             f64,
+}
+
+impl CapitalUsage {
+    pub fn new(
+        source: CapitalSource,
+        flashloan_fee_usd: f64,
+        capital_deployed_usd: f64,
+        bribe_usd: f64,
+        volume_usd: f64,
+    ) -> Self {
+        let capital_at_risk_usd = match source {
+            CapitalSource::Inventory => capital_deployed_usd + bribe_usd,
+            CapitalSource::Flashloan | CapitalSource::FlashSwap => bribe_usd + flashloan_fee_usd,
+        };
+        let turnover =
+            if capital_deployed_usd > 0.0 { volume_usd / capital_deployed_usd } else { 0.0 };
+
+        Self { source, flashloan_fee_usd, capital_deployed_usd, capital_at_risk_usd, turnover }
+    }
+}
+
 impl Serialize for BundleHeader {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("BundleHeader", 12)?;
+        let mut ser_struct = serializer.serialize_struct("BundleHeader", 31)?;
 
         ser_struct.serialize_field("block_number", &self.block_number)?;
         ser_struct.serialize_field("tx_index", &self.tx_index)?;
         ser_struct.serialize_field("tx_hash", &format!("{:?}", &self.tx_hash))?;
+        ser_struct.serialize_field("bundle_id", &format!("{:?}", &self.bundle_id))?;
         ser_struct.serialize_field("eoa", &format!("{:?}", &self.eoa))?;
         ser_struct
             .serialize_field("mev_contract", &self.mev_contract.map(|a| format!("{:?}", a)))?;
@@ -193,6 +409,60 @@ impl Serialize for BundleHeader {
             .collect_vec();
         ser_struct.serialize_field("balance_deltas.token_deltas", &balance_deltas_token_deltas)?;
 
+        let balance_deltas_token_valuations = self
+            .balance_deltas
+            .iter()
+            .flat_map(|b| {
+                b.address_deltas.iter().map(|delta| {
+                    delta
+                        .token_deltas
+                        .iter()
+                        .map(|token_delta| token_delta.valuation.as_ref().to_string())
+                        .collect_vec()
+                })
+            })
+            .collect_vec();
+        ser_struct
+            .serialize_field("balance_deltas.token_valuations", &balance_deltas_token_valuations)?;
+        ser_struct.serialize_field("min_action_confidence", &self.min_action_confidence)?;
+        ser_struct.serialize_field("mean_action_confidence", &self.mean_action_confidence)?;
+        ser_struct.serialize_field("merged_submission", &self.merged_submission)?;
+        ser_struct.serialize_field(
+            "co_bundled_txs",
+            &self
+                .co_bundled_txs
+                .iter()
+                .map(|tx| format!("{:?}", tx))
+                .collect_vec(),
+        )?;
+        ser_struct.serialize_field("searcher_value_usd", &self.value_split.searcher_usd)?;
+        ser_struct.serialize_field("builder_value_usd", &self.value_split.builder_usd)?;
+        ser_struct.serialize_field(
+            "lp_fees.protocol",
+            &self
+                .value_split
+                .lp_fees
+                .iter()
+                .map(|fee| fee.protocol.to_string())
+                .collect_vec(),
+        )?;
+        ser_struct.serialize_field(
+            "lp_fees.usd",
+            &self
+                .value_split
+                .lp_fees
+                .iter()
+                .map(|fee| fee.usd)
+                .collect_vec(),
+        )?;
+        ser_struct.serialize_field("protocol_fees_usd", &self.value_split.protocol_fees_usd)?;
+        ser_struct.serialize_field("capital_source", self.capital.source.as_ref())?;
+        ser_struct.serialize_field("flashloan_fee_usd", &self.capital.flashloan_fee_usd)?;
+        ser_struct.serialize_field("capital_deployed_usd", &self.capital.capital_deployed_usd)?;
+        ser_struct.serialize_field("capital_at_risk_usd", &self.capital.capital_at_risk_usd)?;
+        ser_struct.serialize_field("capital_turnover", &self.capital.turnover)?;
+        ser_struct.serialize_field("spam_profit_usd", &self.spam_profit_usd)?;
+
         ser_struct.end()
     }
 }
@@ -202,6 +472,7 @@ impl DbRow for BundleHeader {
         "block_number",
         "tx_index",
         "tx_hash",
+        "bundle_id",
         "eoa",
         "mev_contract",
         "fund",
@@ -213,5 +484,45 @@ impl DbRow for BundleHeader {
         "balance_deltas.address",
         "balance_deltas.name",
         "balance_deltas.token_deltas",
+        "balance_deltas.token_valuations",
+        "min_action_confidence",
+        "mean_action_confidence",
+        "merged_submission",
+        "co_bundled_txs",
+        "searcher_value_usd",
+        "builder_value_usd",
+        "lp_fees.protocol",
+        "lp_fees.usd",
+        "protocol_fees_usd",
+        "capital_source",
+        "flashloan_fee_usd",
+        "capital_deployed_usd",
+        "capital_at_risk_usd",
+        "capital_turnover",
+        "spam_profit_usd",
     ];
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_borrowed_capital_only_risks_costs() {
+        let borrowed = CapitalUsage::new(CapitalSource::Flashloan, 9.0, 10_000.0, 1.0, 20_000.0);
+        assert_eq!(borrowed.capital_at_risk_usd, 10.0);
+        assert_eq!(borrowed.turnover, 2.0);
+
+        let inventory = CapitalUsage::new(CapitalSource::Inventory, 0.0, 10_000.0, 1.0, 0.0);
+        assert_eq!(inventory.capital_at_risk_usd, 10_001.0);
+    }
+
+    #[test]
+    fn test_bundle_id_ignores_tx_order() {
+        let (a, b) = (B256::repeat_byte(1), B256::repeat_byte(2));
+
+        assert_eq!(bundle_id(MevType::Sandwich, &[a, b]), bundle_id(MevType::Sandwich, &[b, a, b]));
+        assert_ne!(bundle_id(MevType::Sandwich, &[a, b]), bundle_id(MevType::JitSandwich, &[a, b]));
+        assert_ne!(bundle_id(MevType::Sandwich, &[a, b]), bundle_id(MevType::Sandwich, &[a]));
+    }
+}
@@ -27,6 +27,7 @@ use crate::{
 #[serde_as]
 #[derive(Debug, Serialize, Deserialize, PartialEq, Row, Clone, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct Bundle {
     pub header: BundleHeader,
     pub data:   BundleData,
@@ -48,6 +49,11 @@ impl Bundle {
     pub fn mev_type(&self) -> MevType {
         self.header.mev_type
     }
+
+    /// The id of the bundle, from its type and transactions
+    pub fn compute_bundle_id(&self) -> B256 {
+        bundle_id(self.header.mev_type, &self.data.mev_transaction_hashes())
+    }
 }
 
 impl fmt::Display for Bundle {
@@ -63,8 +69,8 @@ impl fmt::Display for Bundle {
                     display_cex_dex_quotes(self, f)?
                 }
             }
-            MevType::Jit => display_jit_liquidity(self, f)?,
-            MevType::AtomicArb => display_atomic_backrun(self, f)?,
+            MevType::Jit | MevType::JitExtended => display_jit_liquidity(self, f)?,
+            MevType::AtomicArb | MevType::Rebalance => display_atomic_backrun(self, f)?,
             MevType::Liquidation => display_liquidation(self, f)?,
             MevType::JitSandwich => display_jit_liquidity_sandwich(self, f)?,
             MevType::SearcherTx => display_searcher_tx(self, f)?,
@@ -91,6 +97,7 @@ impl fmt::Display for Bundle {
     ValueEnum,
     AsRefStr,
 )]
+#[archive(check_bytes)]
 pub enum MevType {
     CexDexTrades,
     CexDexQuotes,
@@ -104,6 +111,11 @@ pub enum MevType {
     SearcherTx,
     #[default]
     Unknown,
+    /// Jit liquidity whose position is burnt a few blocks after it's minted
+    JitExtended,
+    /// Swaps of a market maker rebalancing inventory that were found as an
+    /// atomic arb, they aren't mev
+    Rebalance,
 }
 
 impl MevType {
@@ -112,7 +124,9 @@ impl MevType {
             MevType::Sandwich
             | MevType::JitSandwich
             | MevType::Jit
+            | MevType::JitExtended
             | MevType::AtomicArb
+            | MevType::Rebalance
             | MevType::Liquidation
             | MevType::SearcherTx
             | MevType::Unknown => false,
@@ -129,8 +143,8 @@ impl MevType {
             | MevType::CexDexQuotes
             | MevType::JitCexDex
             | MevType::CexDexTrades => "cex-dex",
-            MevType::AtomicArb => "atomic-arb",
-            MevType::Jit => "jit",
+            MevType::AtomicArb | MevType::Rebalance => "atomic-arb",
+            MevType::Jit | MevType::JitExtended => "jit",
             MevType::Sandwich => "sandwich",
             MevType::JitSandwich => "jit-sandwich",
             MevType::SearcherTx => "searcher-tx",
@@ -150,9 +164,11 @@ impl From<String> for MevType {
             "CexDexRfq" => MevType::CexDexRfq,
             "Sandwich" => MevType::Sandwich,
             "Jit" => MevType::Jit,
+            "JitExtended" => MevType::JitExtended,
             "Liquidation" => MevType::Liquidation,
             "JitSandwich" => MevType::JitSandwich,
             "AtomicArb" => MevType::AtomicArb,
+            "Rebalance" => MevType::Rebalance,
             "SearcherTx" => MevType::SearcherTx,
             _ => MevType::Unknown,
         }
@@ -33,6 +33,7 @@ use crate::{
 
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct OptimisticTrade {
     #[redefined(same_fields)]
     pub exchange:  CexExchange,
@@ -63,6 +64,7 @@ impl Serialize for OptimisticTrade {
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct CexDex {
     pub tx_hash: B256,
     pub block_timestamp: u64,
@@ -128,6 +130,7 @@ impl Mev for CexDex {
     rkyv::Deserialize,
     rkyv::Archive,
 )]
+#[archive(check_bytes)]
 pub enum CexMethodology {
     GlobalWWAP,
     OptimalRouteVWAP,
@@ -698,6 +701,7 @@ impl DbRow for CexDex {
     Debug, Deserialize, PartialEq, Clone, Default, Redefined, brontes_macros::Transposable,
 )]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct ArbDetails {
     pub pairs:            Vec<Pair>,
     pub trade_start_time: u64,
@@ -21,6 +21,7 @@ use crate::{
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct CexDexQuote {
     pub tx_hash:           B256,
     pub block_timestamp:   u64,
@@ -0,0 +1,271 @@
+//! Execution quality of sandwich victims.
+//!
+//! Every victim swap of a sandwich is compared against two baselines: what it
+//! would have received at the pool state before the first frontrun, and what
+//! it would have received at the best fee adjusted CEX quote of the block.
+//! Aggregated per router, this shows how the flow of a frontend executes
+//! relative to an unattacked swap and to the CEX price.
+//!
+//! Victims that routed through an aggregator also carry the minimum amount out
+//! the aggregator was called with, next to the amount of the route's output
+//! token they received. The distance between the two is the slippage tolerance
+//! the victim had left, see [`VictimExecution::slippage_consumed`].
+//!
+//! Victims that added liquidity instead of swapping get an execution for each
+//! mint, with the two deposited tokens and amounts in place of the swapped
+//! ones and the impermanent loss the backrun caused as their loss.
+use std::fmt::Debug;
+
+use alloy_primitives::{Address, B256};
+use itertools::Itertools;
+use redefined::Redefined;
+use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
+
+use crate::{db::redefined_types::primitives::*, FastHashMap};
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Default, Redefined)]
+#[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
+pub struct VictimExecution {
+    pub tx_hash:                 B256,
+    /// Contract the victim sent the transaction to, this is the router of
+    /// the frontend or wallet they swapped through
+    pub router:                  Address,
+    pub pool:                    Address,
+    pub token_in:                Address,
+    pub token_out:               Address,
+    pub amount_in:               f64,
+    pub amount_out:              f64,
+    /// Amount out the swap would have received at the pool state before the
+    /// first frontrun
+    pub pre_frontrun_amount_out: Option<f64>,
+    /// Amount out the swap would have received at the best fee adjusted CEX
+    /// quote
+    pub cex_amount_out:          Option<f64>,
+    /// Minimum amount out of the aggregator call the swap was routed through.
+    /// Only set on the swap that ends the route
+    #[serde(default)]
+    pub quoted_min_amount_out:   Option<f64>,
+    /// Amount of the route's output token the victim received from the
+    /// aggregator. Only set on the swap that ends the route
+    #[serde(default)]
+    pub realized_amount_out:     Option<f64>,
+    /// Usd value of the amount out the victim received below the
+    /// pre-frontrun baseline
+    #[serde(default)]
+    pub loss_usd:                Option<f64>,
+}
+
+impl VictimExecution {
+    /// Price improvement in bps over the pre-frontrun execution, negative when
+    /// the victim received less
+    pub fn improvement_vs_pre_frontrun_bps(&self) -> Option<f64> {
+        price_improvement_bps(self.amount_out, self.pre_frontrun_amount_out?)
+    }
+
+    /// Price improvement in bps over the best CEX quote, negative when the
+    /// victim received less
+    pub fn improvement_vs_cex_bps(&self) -> Option<f64> {
+        price_improvement_bps(self.amount_out, self.cex_amount_out?)
+    }
+
+    /// Share of the victim's slippage tolerance that was taken by the
+    /// sandwich. The unattacked output of the route is the realized output
+    /// scaled by the swap's pre-frontrun baseline, the tolerance is how far
+    /// that is above the quoted minimum. Capped to `[0, 1]`
+    pub fn slippage_consumed(&self) -> Option<f64> {
+        let (min_out, realized) = (self.quoted_min_amount_out?, self.realized_amount_out?);
+        if self.amount_out <= 0.0 {
+            return None
+        }
+        let unattacked = realized * self.pre_frontrun_amount_out? / self.amount_out;
+        let tolerance = unattacked - min_out;
+
+        (tolerance > 0.0).then(|| ((unattacked - realized) / tolerance).clamp(0.0, 1.0))
+    }
+}
+
+fn price_improvement_bps(amount_out: f64, baseline: f64) -> Option<f64> {
+    (baseline > 0.0).then(|| (amount_out - baseline) / baseline * 10_000.0)
+}
+
+/// Execution quality of all victim swaps that went through a router
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RouterExecutionQuality {
+    pub router: Address,
+    pub victim_swaps: usize,
+    /// Mean improvement over the pre-frontrun execution of the swaps that
+    /// have that baseline
+    pub mean_improvement_vs_pre_frontrun_bps: Option<f64>,
+    /// Mean improvement over the best CEX quote of the swaps that have that
+    /// baseline
+    pub mean_improvement_vs_cex_bps: Option<f64>,
+}
+
+/// Aggregates victim executions per router, sorted by the number of victim
+/// swaps
+pub fn execution_quality_by_router<'a>(
+    executions: impl IntoIterator<Item = &'a VictimExecution>,
+) -> Vec<RouterExecutionQuality> {
+    let mut by_router: FastHashMap<Address, Vec<&VictimExecution>> = FastHashMap::default();
+    for execution in executions {
+        by_router
+            .entry(execution.router)
+            .or_default()
+            .push(execution);
+    }
+
+    by_router
+        .into_iter()
+        .map(|(router, executions)| RouterExecutionQuality {
+            router,
+            victim_swaps: executions.len(),
+            mean_improvement_vs_pre_frontrun_bps: mean(
+                executions
+                    .iter()
+                    .filter_map(|e| e.improvement_vs_pre_frontrun_bps()),
+            ),
+            mean_improvement_vs_cex_bps: mean(
+                executions.iter().filter_map(|e| e.improvement_vs_cex_bps()),
+            ),
+        })
+        .sorted_by(|a, b| {
+            b.victim_swaps
+                .cmp(&a.victim_swaps)
+                .then(a.router.cmp(&b.router))
+        })
+        .collect()
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (count, sum) = values.fold((0usize, 0.0), |(count, sum), v| (count + 1, sum + v));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Writes the victim executions as the `victim_execution` nested columns
+pub(crate) fn serialize_victim_execution<S: SerializeStruct>(
+    ser_struct: &mut S,
+    executions: &[VictimExecution],
+) -> Result<(), S::Error> {
+    let addresses = |f: fn(&VictimExecution) -> Address| {
+        executions
+            .iter()
+            .map(|e| format!("{:?}", f(e)))
+            .collect_vec()
+    };
+
+    ser_struct.serialize_field(
+        "victim_execution.tx_hash",
+        &executions
+            .iter()
+            .map(|e| format!("{:?}", e.tx_hash))
+            .collect_vec(),
+    )?;
+    ser_struct.serialize_field("victim_execution.router", &addresses(|e| e.router))?;
+    ser_struct.serialize_field("victim_execution.pool", &addresses(|e| e.pool))?;
+    ser_struct.serialize_field("victim_execution.token_in", &addresses(|e| e.token_in))?;
+    ser_struct.serialize_field("victim_execution.token_out", &addresses(|e| e.token_out))?;
+    ser_struct.serialize_field(
+        "victim_execution.amount_in",
+        &executions.iter().map(|e| e.amount_in).collect_vec(),
+    )?;
+    ser_struct.serialize_field(
+        "victim_execution.amount_out",
+        &executions.iter().map(|e| e.amount_out).collect_vec(),
+    )?;
+    ser_struct.serialize_field(
+        "victim_execution.pre_frontrun_amount_out",
+        &executions
+            .iter()
+            .map(|e| e.pre_frontrun_amount_out)
+            .collect_vec(),
+    )?;
+    ser_struct.serialize_field(
+        "victim_execution.cex_amount_out",
+        &executions.iter().map(|e| e.cex_amount_out).collect_vec(),
+    )?;
+    ser_struct.serialize_field(
+        "victim_execution.quoted_min_amount_out",
+        &executions
+            .iter()
+            .map(|e| e.quoted_min_amount_out)
+            .collect_vec(),
+    )?;
+    ser_struct.serialize_field(
+        "victim_execution.realized_amount_out",
+        &executions
+            .iter()
+            .map(|e| e.realized_amount_out)
+            .collect_vec(),
+    )?;
+    ser_struct.serialize_field(
+        "victim_execution.loss_usd",
+        &executions.iter().map(|e| e.loss_usd).collect_vec(),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_quality_by_router() {
+        let router = Address::repeat_byte(1);
+        let execution = |amount_out, pre_frontrun, cex| VictimExecution {
+            router,
+            amount_out,
+            pre_frontrun_amount_out: pre_frontrun,
+            cex_amount_out: cex,
+            ..Default::default()
+        };
+
+        let executions = [
+            execution(99.0, Some(100.0), Some(98.0)),
+            execution(97.0, Some(100.0), None),
+            VictimExecution { router: Address::repeat_byte(2), ..Default::default() },
+        ];
+        let report = execution_quality_by_router(&executions);
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].router, router);
+        assert_eq!(report[0].victim_swaps, 2);
+        assert_eq!(report[0].mean_improvement_vs_pre_frontrun_bps, Some(-200.0));
+        assert!((report[0].mean_improvement_vs_cex_bps.unwrap() - 102.04).abs() < 0.01);
+        assert_eq!(report[1].mean_improvement_vs_cex_bps, None);
+    }
+
+    #[test]
+    fn test_price_improvement() {
+        let execution = VictimExecution {
+            amount_out: 101.0,
+            pre_frontrun_amount_out: Some(100.0),
+            cex_amount_out: Some(0.0),
+            ..Default::default()
+        };
+        assert!((execution.improvement_vs_pre_frontrun_bps().unwrap() - 100.0).abs() < 1e-9);
+        // a zero baseline has no meaningful improvement
+        assert_eq!(execution.improvement_vs_cex_bps(), None);
+
+        let no_baseline = VictimExecution { pre_frontrun_amount_out: None, ..execution };
+        assert_eq!(no_baseline.improvement_vs_pre_frontrun_bps(), None);
+    }
+
+    #[test]
+    fn test_slippage_consumed() {
+        let execution = VictimExecution {
+            amount_out: 98.0,
+            pre_frontrun_amount_out: Some(100.0),
+            quoted_min_amount_out: Some(194.0),
+            realized_amount_out: Some(196.0),
+            ..Default::default()
+        };
+        // unattacked output of 200 with a minimum of 194, 4 of the 6 were taken
+        assert!((execution.slippage_consumed().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+
+        let not_routed = VictimExecution { quoted_min_amount_out: None, ..execution };
+        assert_eq!(not_routed.slippage_consumed(), None);
+    }
+}
@@ -26,6 +26,7 @@ use crate::{
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct JitLiquidity {
     pub frontrun_mint_tx_hash: B256,
     pub block_number: u64,
@@ -41,11 +42,19 @@ pub struct JitLiquidity {
     pub backrun_burns: Vec<NormalizedBurn>,
     #[redefined(same_fields)]
     pub backrun_burn_gas_details: GasDetails,
+    /// Block of the burn, after `block_number` when the position was held
+    /// across blocks
+    #[serde(default)]
+    pub backrun_block_number: u64,
 }
 
 impl Mev for JitLiquidity {
     fn mev_type(&self) -> MevType {
-        MevType::Jit
+        if self.backrun_block_number > self.block_number {
+            MevType::JitExtended
+        } else {
+            MevType::Jit
+        }
     }
 
     fn mev_transaction_hashes(&self) -> Vec<B256> {
@@ -83,7 +92,7 @@ impl Serialize for JitLiquidity {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("JitLiquidity", 31)?;
+        let mut ser_struct = serializer.serialize_struct("JitLiquidity", 32)?;
 
         // frontrun mint
         ser_struct.serialize_field(
@@ -172,6 +181,7 @@ impl Serialize for JitLiquidity {
         );
 
         ser_struct.serialize_field("backrun_burn_gas_details", &(backrun_burn_gas_details))?;
+        ser_struct.serialize_field("backrun_block_number", &self.backrun_block_number)?;
 
         ser_struct.end()
     }
@@ -210,5 +220,6 @@ impl DbRow for JitLiquidity {
         "backrun_burns.tokens",
         "backrun_burns.amounts",
         "backrun_burn_gas_details",
+        "backrun_block_number",
     ];
 }
@@ -10,7 +10,10 @@ use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use super::{Bundle, BundleData, BundleHeader, JitLiquidity, Mev, MevType, Sandwich};
+use super::{
+    execution_quality::{serialize_victim_execution, VictimExecution, VictimExecutionRedefined},
+    Bundle, BundleData, BundleHeader, Mev, MevType, ValueSplit,
+};
 use crate::{
     db::redefined_types::primitives::*, normalized_actions::*, tree::ClickhouseVecGasDetails,
     Protocol,
@@ -25,6 +28,7 @@ use crate::{
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct JitLiquiditySandwich {
     pub block_number:         u64,
     pub frontrun_tx_hash:     Vec<B256>,
@@ -44,6 +48,9 @@ pub struct JitLiquiditySandwich {
     pub backrun_burns:       Vec<NormalizedBurn>,
     #[redefined(same_fields)]
     pub backrun_gas_details: GasDetails,
+    /// Execution of each victim swap compared to its baselines
+    #[serde(default)]
+    pub victim_execution:    Vec<VictimExecution>,
 }
 
 impl Mev for JitLiquiditySandwich {
@@ -106,31 +113,20 @@ impl Mev for JitLiquiditySandwich {
     }
 }
 
-pub fn compose_sandwich_jit(mev: Vec<Bundle>) -> Option<Bundle> {
-    let mut sandwich: Option<Sandwich> = None;
-    let mut jit: Option<JitLiquidity> = None;
-    let mut classified_sandwich: Option<BundleHeader> = None;
-    let mut jit_classified: Option<BundleHeader> = None;
-
-    for bundle in mev {
-        match bundle.data {
-            BundleData::Sandwich(s) => {
-                sandwich = Some(s);
-                classified_sandwich = Some(bundle.header);
-            }
-            BundleData::Jit(j) => {
-                jit = Some(j);
-                jit_classified = Some(bundle.header);
-            }
-            err => unreachable!("got bundle {err:?} in compose jit sandwich"),
-        }
-    }
+/// Composes a sandwich and the jit liquidity around its victims, given in
+/// that order
+pub fn compose_sandwich_jit(components: &[Bundle]) -> Option<Bundle> {
+    let [sandwich_bundle, jit_bundle] = components else { return None };
+    let (BundleData::Sandwich(sandwich), BundleData::Jit(jit)) =
+        (&sandwich_bundle.data, &jit_bundle.data)
+    else {
+        return None
+    };
 
-    let sandwich = sandwich.expect("Expected Sandwich MEV data");
-    let jit = jit.expect("Expected JIT MEV data");
-    let classified_sandwich =
-        classified_sandwich.expect("Expected Classified MEV data for Sandwich");
-    let jit_classified = jit_classified.expect("Expected Classified MEV data for JIT");
+    let sandwich = sandwich.clone();
+    let jit = jit.clone();
+    let classified_sandwich = sandwich_bundle.header.clone();
+    let jit_classified = jit_bundle.header.clone();
 
     let mut frontrun_mints: Vec<Option<Vec<NormalizedMint>>> =
         vec![None; sandwich.frontrun_tx_hash.len()];
@@ -170,21 +166,35 @@ pub fn compose_sandwich_jit(mev: Vec<Bundle>) -> Option<Bundle> {
         backrun_swaps: sandwich.backrun_swaps,
         backrun_burns: jit.backrun_burns,
         backrun_gas_details: sandwich.backrun_gas_details,
+        victim_execution: sandwich.victim_execution,
     };
 
     // Create new classified MEV data
     let new_classified = BundleHeader {
-        tx_index:              classified_sandwich.tx_index,
-        tx_hash:               *sandwich.frontrun_tx_hash.first().unwrap_or_default(),
-        mev_type:              MevType::JitSandwich,
-        fund:                  classified_sandwich.fund,
-        block_number:          classified_sandwich.block_number,
-        eoa:                   jit_classified.eoa,
-        mev_contract:          classified_sandwich.mev_contract,
-        profit_usd:            classified_sandwich.profit_usd,
-        balance_deltas:        classified_sandwich.balance_deltas,
-        bribe_usd:             classified_sandwich.bribe_usd,
-        no_pricing_calculated: classified_sandwich.no_pricing_calculated,
+        tx_index:               classified_sandwich.tx_index,
+        tx_hash:                *sandwich.frontrun_tx_hash.first().unwrap_or_default(),
+        // set by the composer once the bundle is finalized
+        bundle_id:              B256::ZERO,
+        mev_type:               MevType::JitSandwich,
+        fund:                   classified_sandwich.fund,
+        block_number:           classified_sandwich.block_number,
+        eoa:                    jit_classified.eoa,
+        mev_contract:           classified_sandwich.mev_contract,
+        profit_usd:             classified_sandwich.profit_usd,
+        balance_deltas:         classified_sandwich.balance_deltas,
+        bribe_usd:              classified_sandwich.bribe_usd,
+        no_pricing_calculated:  classified_sandwich.no_pricing_calculated,
+        min_action_confidence:  classified_sandwich
+            .min_action_confidence
+            .min(jit_classified.min_action_confidence),
+        mean_action_confidence: (classified_sandwich.mean_action_confidence
+            + jit_classified.mean_action_confidence)
+            / 2.0,
+        merged_submission:      false,
+        co_bundled_txs:         vec![],
+        value_split:            ValueSplit::default(),
+        capital:                Default::default(),
+        spam_profit_usd:        0.0,
     };
 
     Some(Bundle { header: new_classified, data: BundleData::JitSandwich(jit_sand) })
@@ -195,7 +205,7 @@ impl Serialize for JitLiquiditySandwich {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("JitLiquiditySandwich", 35)?;
+        let mut ser_struct = serializer.serialize_struct("JitLiquiditySandwich", 71)?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
 
         // frontruns
@@ -344,6 +354,8 @@ impl Serialize for JitLiquiditySandwich {
             &vec![self.backrun_gas_details.effective_gas_price],
         )?;
 
+        serialize_victim_execution(&mut ser_struct, &self.victim_execution)?;
+
         ser_struct.end()
     }
 }
@@ -409,5 +421,17 @@ impl DbRow for JitLiquiditySandwich {
         "backrun_gas_details.priority_fee",
         "backrun_gas_details.gas_used",
         "backrun_gas_details.effective_gas_price",
+        "victim_execution.tx_hash",
+        "victim_execution.router",
+        "victim_execution.pool",
+        "victim_execution.token_in",
+        "victim_execution.token_out",
+        "victim_execution.amount_in",
+        "victim_execution.amount_out",
+        "victim_execution.pre_frontrun_amount_out",
+        "victim_execution.cex_amount_out",
+        "victim_execution.quoted_min_amount_out",
+        "victim_execution.realized_amount_out",
+        "victim_execution.loss_usd",
     ];
 }
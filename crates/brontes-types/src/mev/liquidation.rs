@@ -19,6 +19,7 @@ use crate::{display::utils::display_sandwich, normalized_actions::*, GasDetails}
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct Liquidation {
     pub liquidation_tx_hash: B256,
     pub block_number:        u64,
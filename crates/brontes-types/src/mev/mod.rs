@@ -19,3 +19,5 @@ pub use searcher_tx::*;
 
 pub mod cex_dex_quotes;
 pub use cex_dex_quotes::*;
+pub mod attestation;
+pub mod execution_quality;
@@ -10,7 +10,10 @@ use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
-use super::{Mev, MevType};
+use super::{
+    execution_quality::{serialize_victim_execution, VictimExecution, VictimExecutionRedefined},
+    Mev, MevType,
+};
 use crate::{
     db::{redefined_types::primitives::*, token_info::TokenInfoWithAddress},
     normalized_actions::*,
@@ -59,6 +62,7 @@ use crate::{
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct Sandwich {
     pub block_number:             u64,
     /// Transaction hashes of the frontrunning transactions.
@@ -88,6 +92,75 @@ pub struct Sandwich {
     /// Gas details for each backrunning transaction.
     #[redefined(same_fields)]
     pub backrun_gas_details:      GasDetails,
+    /// Execution of each victim swap compared to its baselines
+    #[serde(default)]
+    pub victim_execution:         Vec<VictimExecution>,
+    /// Liquidations executed by victims, each outer vector corresponds to a
+    /// victim transaction.
+    #[serde(default)]
+    pub victim_liquidations:      Vec<Vec<NormalizedLiquidation>>,
+    /// Liquidity added by victims, each outer vector corresponds to a victim
+    /// transaction.
+    #[serde(default)]
+    pub victim_mints:             Vec<Vec<NormalizedMint>>,
+    /// The action each victim transaction was sandwiched on
+    #[serde(default)]
+    #[redefined(same_fields)]
+    pub victim_action_types:      Vec<VictimActionType>,
+}
+
+/// The price sensitive action of a victim transaction the frontrun moved the
+/// price of. Swaps get a worse rate, liquidations sell their seized
+/// collateral at a worse rate and mints add liquidity at a manipulated ratio
+#[derive(
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Serialize,
+    Deserialize,
+    rSerialize,
+    rDeserialize,
+    Archive,
+)]
+#[archive(check_bytes)]
+pub enum VictimActionType {
+    #[default]
+    Swap,
+    Liquidation,
+    Mint,
+}
+
+impl VictimActionType {
+    /// The action a victim transaction was sandwiched on. A liquidation is
+    /// only matched through the swaps selling its collateral, so it takes
+    /// precedence over them
+    pub fn of(
+        swaps: &[NormalizedSwap],
+        liquidations: &[NormalizedLiquidation],
+        mints: &[NormalizedMint],
+    ) -> Self {
+        if !liquidations.is_empty() {
+            VictimActionType::Liquidation
+        } else if swaps.is_empty() && !mints.is_empty() {
+            VictimActionType::Mint
+        } else {
+            VictimActionType::Swap
+        }
+    }
+}
+
+impl std::fmt::Display for VictimActionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VictimActionType::Swap => write!(f, "swap"),
+            VictimActionType::Liquidation => write!(f, "liquidation"),
+            VictimActionType::Mint => write!(f, "mint"),
+        }
+    }
 }
 
 /// calcuation for the loss per user
@@ -152,6 +225,15 @@ impl Mev for Sandwich {
         self.victim_swaps.iter().flatten().for_each(|swap| {
             protocols.insert(swap.protocol);
         });
+        self.victim_liquidations
+            .iter()
+            .flatten()
+            .for_each(|liquidation| {
+                protocols.insert(liquidation.protocol);
+            });
+        self.victim_mints.iter().flatten().for_each(|mint| {
+            protocols.insert(mint.protocol);
+        });
 
         self.backrun_swaps.iter().for_each(|swap| {
             protocols.insert(swap.protocol);
@@ -166,7 +248,7 @@ impl Serialize for Sandwich {
     where
         S: Serializer,
     {
-        let mut ser_struct = serializer.serialize_struct("Sandwich", 35)?;
+        let mut ser_struct = serializer.serialize_struct("Sandwich", 59)?;
         ser_struct.serialize_field("block_number", &self.block_number)?;
 
         // frontrun
@@ -282,6 +364,22 @@ impl Serialize for Sandwich {
             &vec![self.backrun_gas_details.effective_gas_price],
         )?;
 
+        serialize_victim_execution(&mut ser_struct, &self.victim_execution)?;
+
+        let victim_tx_hashes = self
+            .victim_swaps_tx_hashes
+            .iter()
+            .flatten()
+            .map(|tx| format!("{:?}", tx))
+            .collect::<Vec<_>>();
+        let victim_action_types = self
+            .victim_action_types
+            .iter()
+            .map(|action| action.to_string())
+            .collect::<Vec<_>>();
+        ser_struct.serialize_field("victim_actions.tx_hash", &victim_tx_hashes)?;
+        ser_struct.serialize_field("victim_actions.action_type", &victim_action_types)?;
+
         ser_struct.end()
     }
 }
@@ -333,5 +431,19 @@ impl DbRow for Sandwich {
         "backrun_gas_details.priority_fee",
         "backrun_gas_details.gas_used",
         "backrun_gas_details.effective_gas_price",
+        "victim_execution.tx_hash",
+        "victim_execution.router",
+        "victim_execution.pool",
+        "victim_execution.token_in",
+        "victim_execution.token_out",
+        "victim_execution.amount_in",
+        "victim_execution.amount_out",
+        "victim_execution.pre_frontrun_amount_out",
+        "victim_execution.cex_amount_out",
+        "victim_execution.quoted_min_amount_out",
+        "victim_execution.realized_amount_out",
+        "victim_execution.loss_usd",
+        "victim_actions.tx_hash",
+        "victim_actions.action_type",
     ];
 }
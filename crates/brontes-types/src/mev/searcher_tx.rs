@@ -21,6 +21,7 @@ use crate::{display::utils::display_sandwich, normalized_actions::NormalizedTran
 #[serde_as]
 #[derive(Debug, Deserialize, PartialEq, Clone, Default, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct SearcherTx {
     pub tx_hash:      B256,
     pub block_number: u64,
@@ -30,6 +30,15 @@ impl MultiBlockData {
     pub fn get_most_recent_block(&self) -> &BlockData {
         self.per_block_data.last().unwrap()
     }
+
+    /// Replaces the tree of the most recent block, keeping its metadata
+    pub fn with_most_recent_tree(&self, tree: Arc<BlockTree<Action>>) -> MultiBlockData {
+        let mut this = self.clone();
+        if let Some(block) = this.per_block_data.last_mut() {
+            block.tree = tree;
+        }
+        this
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,10 +1,12 @@
+use alloy_primitives::hex;
 use clickhouse::Row;
+use malachite::Rational;
 use reth_primitives::{Address, U256};
 use serde::{Deserialize, Serialize};
 
 use super::accounting::{AddressDeltas, TokenAccounting};
 pub use super::{Action, NormalizedSwap, NormalizedTransfer};
-use crate::Protocol;
+use crate::{db::token_info::TokenInfoWithAddress, FastHashSet, Protocol, ToScaledRational};
 
 #[derive(Debug, Serialize, Clone, Row, Deserialize, PartialEq, Eq)]
 pub struct NormalizedAggregator {
@@ -22,8 +24,114 @@ pub struct NormalizedAggregator {
     //  - Mints
     //  - Burns
     //  - Transfers
-    pub child_actions: Vec<Action>,
-    pub msg_value:     U256,
+    pub child_actions:  Vec<Action>,
+    /// Least amount of the output token the caller accepts, decoded from the
+    /// calldata. This is the frontend's quote less its slippage tolerance
+    #[serde(default)]
+    pub min_amount_out: Option<U256>,
+    /// Affiliate the frontend tagged the call with, see [`calldata_referrer`].
+    /// Only decoded when the aggregator is the transaction's top level call
+    #[serde(default)]
+    pub referrer:       Option<Address>,
+    pub msg_value:      U256,
+}
+
+/// Selector of the affiliate tag that 0x API integrators, and the frontends
+/// copying the convention, append to the calldata of the swaps they route
+pub const AFFILIATE_TAG_SELECTOR: [u8; 4] = hex!("869584cd");
+
+/// The affiliate address appended after the abi encoded arguments of a call,
+/// as the tag's selector followed by the padded address and a timestamp.
+/// Arguments are decoded ignoring trailing bytes, so the tag doesn't change
+/// the call itself
+pub fn calldata_referrer(call_data: &[u8]) -> Option<Address> {
+    // the call's own selector comes before the tag
+    let start = call_data
+        .len()
+        .checked_sub(68)
+        .filter(|start| *start >= 4)?;
+    let tag = &call_data[start..];
+    if tag[..4] != AFFILIATE_TAG_SELECTOR || tag[4..16].iter().any(|b| *b != 0) {
+        return None
+    }
+
+    let referrer = Address::from_slice(&tag[16..36]);
+    (!referrer.is_zero()).then_some(referrer)
+}
+
+impl NormalizedAggregator {
+    /// The token the route ends in and the amount of it the recipient got.
+    /// The output token is the token out of the last swap, the amount is what
+    /// was transferred of it to the recipient or, if the route unwraps to
+    /// ETH, the last swap's amount out
+    pub fn realized_amount_out(&self) -> Option<(TokenInfoWithAddress, Rational)> {
+        let token_out = self
+            .child_actions
+            .iter()
+            .rev()
+            .find_map(Action::try_swaps_merged_ref)?
+            .clone();
+
+        let received = self
+            .child_actions
+            .iter()
+            .filter_map(Action::try_transfer_ref)
+            .filter(|t| t.to == self.recipient && t.token == token_out.token_out)
+            .map(|t| t.amount.clone())
+            .reduce(|a, b| a + b)
+            .unwrap_or(token_out.amount_out);
+
+        Some((token_out.token_out, received))
+    }
+
+    /// The minimum amount out from the calldata, in units of the token
+    pub fn quoted_min_amount_out(&self, token: &TokenInfoWithAddress) -> Option<Rational> {
+        Some(self.min_amount_out?.to_scaled_rational(token.decimals))
+    }
+
+    /// The integrator the frontend routed the swap for. 0x integrators tag
+    /// the calldata, 1inch integrators aren't tagged but are paid their fee
+    /// out of the route, see [`Self::fee_recipient`]
+    pub fn frontend_referrer(&self) -> Option<Address> {
+        self.referrer.or_else(|| {
+            (self.protocol == Protocol::OneInchV5)
+                .then(|| self.fee_recipient())
+                .flatten()
+        })
+    }
+
+    /// Receiver of a transfer that takes no other part in the route: it isn't
+    /// the caller, the recipient, the router, a pool or the token, and it
+    /// never sends tokens on. That's the integrator fee the executor pays out
+    fn fee_recipient(&self) -> Option<Address> {
+        let swaps = self
+            .child_actions
+            .iter()
+            .filter_map(Action::try_swaps_merged_ref)
+            .collect::<Vec<_>>();
+        let transfers = self
+            .child_actions
+            .iter()
+            .filter_map(Action::try_transfer_ref)
+            .collect::<Vec<_>>();
+
+        let participants = [self.from, self.to, self.recipient, Address::ZERO]
+            .into_iter()
+            .chain(
+                swaps
+                    .iter()
+                    .flat_map(|swap| [swap.pool, swap.from, swap.recipient]),
+            )
+            .chain(transfers.iter().map(|transfer| transfer.from))
+            .collect::<FastHashSet<_>>();
+
+        transfers
+            .iter()
+            .find(|transfer| {
+                !participants.contains(&transfer.to) && transfer.to != transfer.token.address
+            })
+            .map(|transfer| transfer.to)
+    }
 }
 
 impl TokenAccounting for NormalizedAggregator {
@@ -33,3 +141,126 @@ impl TokenAccounting for NormalizedAggregator {
             .for_each(|action| action.apply_token_deltas(delta_map))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calldata_referrer() {
+        let referrer = Address::repeat_byte(7);
+        let mut call_data = vec![0xd9, 0x62, 0x7a, 0xa4];
+        call_data.extend([1u8; 64]);
+        assert_eq!(calldata_referrer(&call_data), None);
+
+        call_data.extend(AFFILIATE_TAG_SELECTOR);
+        call_data.extend([0u8; 12]);
+        call_data.extend(referrer.as_slice());
+        call_data.extend([0u8; 32]);
+        assert_eq!(calldata_referrer(&call_data), Some(referrer));
+
+        // a tag without the call's selector in front isn't one
+        assert_eq!(calldata_referrer(&call_data[68..]), None);
+    }
+
+    #[test]
+    fn test_one_inch_fee_recipient() {
+        let (user, router, executor, pool, integrator) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            Address::repeat_byte(4),
+            Address::repeat_byte(5),
+        );
+        let transfer = |from, to| {
+            Action::Transfer(NormalizedTransfer {
+                from,
+                to,
+                amount: Rational::from(1),
+                ..Default::default()
+            })
+        };
+        let mut route = NormalizedAggregator {
+            protocol:       Protocol::OneInchV5,
+            trace_index:    0,
+            from:           user,
+            to:             router,
+            recipient:      user,
+            child_actions:  vec![
+                transfer(user, executor),
+                transfer(executor, pool),
+                Action::Swap(NormalizedSwap {
+                    from: executor,
+                    recipient: executor,
+                    pool,
+                    ..Default::default()
+                }),
+                transfer(pool, executor),
+                transfer(executor, integrator),
+                transfer(executor, user),
+            ],
+            min_amount_out: None,
+            referrer:       None,
+            msg_value:      U256::ZERO,
+        };
+        assert_eq!(route.frontend_referrer(), Some(integrator));
+
+        // without a fee the route has no integrator
+        route.child_actions.remove(4);
+        assert_eq!(route.frontend_referrer(), None);
+
+        // a calldata tag takes precedence, other aggregators aren't paid out of the
+        // route
+        route.referrer = Some(Address::repeat_byte(6));
+        assert_eq!(route.frontend_referrer(), Some(Address::repeat_byte(6)));
+        route.child_actions.push(transfer(executor, integrator));
+        route.referrer = None;
+        route.protocol = Protocol::ZeroX;
+        assert_eq!(route.frontend_referrer(), None);
+    }
+
+    #[test]
+    fn test_realized_amount_out() {
+        let (user, executor) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let token = |byte, decimals| TokenInfoWithAddress {
+            address: Address::repeat_byte(byte),
+            inner:   crate::db::token_info::TokenInfo { decimals, ..Default::default() },
+        };
+        let (usdc, weth) = (token(0xa, 6), token(0xb, 18));
+        let mut route = NormalizedAggregator {
+            protocol:       Protocol::ZeroX,
+            trace_index:    0,
+            from:           user,
+            to:             executor,
+            recipient:      user,
+            child_actions:  vec![
+                Action::Swap(NormalizedSwap {
+                    token_in: weth.clone(),
+                    token_out: usdc.clone(),
+                    amount_out: Rational::from(3_000),
+                    ..Default::default()
+                }),
+                Action::Transfer(NormalizedTransfer {
+                    to: user,
+                    token: usdc.clone(),
+                    amount: Rational::from(2_990),
+                    ..Default::default()
+                }),
+            ],
+            min_amount_out: Some(U256::from(2_950_000_000u64)),
+            referrer:       None,
+            msg_value:      U256::ZERO,
+        };
+
+        // the recipient got the output net of the router's cut
+        assert_eq!(route.realized_amount_out(), Some((usdc.clone(), Rational::from(2_990))));
+        assert_eq!(route.quoted_min_amount_out(&usdc), Some(Rational::from(2_950)));
+
+        // a route that unwraps to eth falls back to the last swap's output
+        route.child_actions.pop();
+        assert_eq!(route.realized_amount_out(), Some((usdc, Rational::from(3_000))));
+
+        route.child_actions.clear();
+        assert_eq!(route.realized_amount_out(), None);
+    }
+}
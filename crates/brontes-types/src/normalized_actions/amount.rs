@@ -0,0 +1,226 @@
+//! Token amounts that carry the token they are denominated in.
+//!
+//! Raw `U256` amounts, decimal adjusted `Rational` amounts and usd values are
+//! easy to mix up. A [`TokenAmount`] is always decimal adjusted and knows its
+//! token and decimals, so converting back to raw units or into usd can't
+//! silently use the wrong scale.
+use std::fmt::{self, Display};
+
+use alloy_primitives::{Address, U256};
+use malachite::{
+    num::{arithmetic::traits::Pow, basic::traits::Zero},
+    Natural, Rational,
+};
+
+use crate::{db::token_info::TokenInfoWithAddress, ToFloatNearest, ToScaledRational};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TokenAmount {
+    pub token:    Address,
+    pub decimals: u8,
+    /// Decimal adjusted amount
+    amount:       Rational,
+}
+
+impl TokenAmount {
+    /// From an amount in the token's smallest unit
+    pub fn from_raw(token: &TokenInfoWithAddress, raw: U256) -> Self {
+        Self {
+            token:    token.address,
+            decimals: token.decimals,
+            amount:   raw.to_scaled_rational(token.decimals),
+        }
+    }
+
+    /// From an amount that has already been adjusted for the token's decimals
+    pub fn from_scaled(token: &TokenInfoWithAddress, amount: Rational) -> Self {
+        Self { token: token.address, decimals: token.decimals, amount }
+    }
+
+    pub fn zero(token: &TokenInfoWithAddress) -> Self {
+        Self::from_scaled(token, Rational::ZERO)
+    }
+
+    pub fn amount(&self) -> &Rational {
+        &self.amount
+    }
+
+    pub fn into_rational(self) -> Rational {
+        self.amount
+    }
+
+    pub fn to_float(&self) -> f64 {
+        self.amount.clone().to_float()
+    }
+
+    /// The amount in the token's smallest unit. Fails for negative amounts,
+    /// amounts with more precision than the token has decimals and amounts
+    /// that don't fit in a `U256`.
+    pub fn to_raw(&self) -> eyre::Result<U256> {
+        let raw = &self.amount * Rational::from(Natural::from(10u8).pow(self.decimals as u64));
+        let raw = Natural::try_from(&raw).map_err(|_| {
+            eyre::eyre!("{} of {:?} is not a whole amount of raw units", self.amount, self.token)
+        })?;
+
+        U256::checked_from_limbs_slice(&raw.to_limbs_asc())
+            .ok_or_else(|| eyre::eyre!("{} of {:?} overflows a U256", self.amount, self.token))
+    }
+
+    /// Values the amount given the usd price of one whole token
+    pub fn usd_value(&self, price: &Rational) -> Rational {
+        &self.amount * price
+    }
+
+    /// Adds two amounts of the same token, returns `None` if the tokens differ
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.same_token(other)
+            .then(|| Self { amount: &self.amount + &other.amount, ..self.clone() })
+    }
+
+    /// Subtracts two amounts of the same token, returns `None` if the tokens
+    /// differ
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.same_token(other)
+            .then(|| Self { amount: &self.amount - &other.amount, ..self.clone() })
+    }
+
+    fn same_token(&self, other: &Self) -> bool {
+        self.token == other.token && self.decimals == other.decimals
+    }
+}
+
+impl Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:?}", self.to_float(), self.token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clickhouse::Row;
+    use redefined::RedefinedConvert;
+
+    use super::*;
+    use crate::{
+        db::{
+            bundle_versions::{decode, encode},
+            token_info::TokenInfo,
+        },
+        normalized_actions::{
+            NormalizedLiquidation, NormalizedLiquidationRedefined, NormalizedSwap,
+            NormalizedSwapRedefined, NormalizedTransfer, NormalizedTransferRedefined,
+        },
+    };
+
+    fn usdc() -> TokenInfoWithAddress {
+        TokenInfoWithAddress {
+            address: Address::repeat_byte(1),
+            inner:   TokenInfo { decimals: 6, symbol: "USDC".to_string() },
+        }
+    }
+
+    #[test]
+    fn test_raw_round_trip() {
+        let amount = TokenAmount::from_raw(&usdc(), U256::from(1_500_000u64));
+        assert_eq!(amount.amount(), &Rational::from_unsigneds(3u8, 2u8));
+        assert_eq!(amount.to_raw().unwrap(), U256::from(1_500_000u64));
+
+        // more precision than the token has
+        let dust = TokenAmount::from_scaled(&usdc(), Rational::from_unsigneds(1u8, 10_000_000u32));
+        assert!(dust.to_raw().is_err());
+        assert!(TokenAmount::from_scaled(&usdc(), Rational::from(-1))
+            .to_raw()
+            .is_err());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let a = TokenAmount::from_raw(&usdc(), U256::from(2_000_000u64));
+        let b = TokenAmount::from_raw(&usdc(), U256::from(500_000u64));
+        assert_eq!(a.checked_sub(&b).unwrap().to_raw().unwrap(), U256::from(1_500_000u64));
+
+        let weth = TokenAmount::zero(&TokenInfoWithAddress::weth());
+        assert!(a.checked_add(&weth).is_none());
+        assert_eq!(a.usd_value(&Rational::from(2)), Rational::from(4));
+    }
+
+    /// The typed amounts are views over the stored actions, whose columns and
+    /// decimal adjusted amounts must not change
+    #[test]
+    fn test_stored_action_layout() {
+        assert_eq!(
+            NormalizedSwap::COLUMN_NAMES,
+            [
+                "protocol",
+                "trace_index",
+                "from",
+                "recipient",
+                "pool",
+                "token_in",
+                "token_out",
+                "amount_in",
+                "amount_out",
+                "msg_value"
+            ]
+        );
+        assert_eq!(
+            NormalizedTransfer::COLUMN_NAMES,
+            ["trace_index", "from", "to", "token", "amount", "fee", "msg_value"]
+        );
+        assert_eq!(
+            NormalizedLiquidation::COLUMN_NAMES,
+            [
+                "protocol",
+                "trace_index",
+                "pool",
+                "liquidator",
+                "debtor",
+                "collateral_asset",
+                "debt_asset",
+                "covered_debt",
+                "liquidated_collateral",
+                "msg_value"
+            ]
+        );
+
+        let weth = TokenInfoWithAddress::weth();
+        let swap = NormalizedSwap {
+            token_in: usdc(),
+            token_out: weth.clone(),
+            amount_in: Rational::from_unsigneds(3u8, 2u8),
+            amount_out: Rational::from_unsigneds(1u8, 2_000u16),
+            ..Default::default()
+        };
+        let stored: NormalizedSwapRedefined =
+            decode(&encode(&NormalizedSwapRedefined::from_source(swap.clone()))).unwrap();
+        let stored = stored.to_source();
+        assert_eq!(stored, swap);
+        assert_eq!(stored.token_amount_in().to_raw().unwrap(), U256::from(1_500_000u64));
+
+        let transfer = NormalizedTransfer {
+            token: usdc(),
+            amount: Rational::from(2),
+            fee: Rational::from_unsigneds(1u8, 100u8),
+            ..Default::default()
+        };
+        let stored: NormalizedTransferRedefined =
+            decode(&encode(&NormalizedTransferRedefined::from_source(transfer.clone()))).unwrap();
+        let stored = stored.to_source();
+        assert_eq!(stored, transfer);
+        assert_eq!(stored.token_fee().to_raw().unwrap(), U256::from(10_000u64));
+
+        let liquidation = NormalizedLiquidation {
+            collateral_asset: weth,
+            debt_asset: usdc(),
+            covered_debt: Rational::from(1_000),
+            liquidated_collateral: Rational::from_unsigneds(1u8, 2u8),
+            ..Default::default()
+        };
+        let stored: NormalizedLiquidationRedefined =
+            decode(&encode(&NormalizedLiquidationRedefined::from_source(liquidation.clone())))
+                .unwrap();
+        let stored = stored.to_source();
+        assert_eq!(stored, liquidation);
+        assert_eq!(stored.covered_debt_amount().to_raw().unwrap(), U256::from(1_000_000_000u64));
+    }
+}
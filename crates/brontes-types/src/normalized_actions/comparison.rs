@@ -1,6 +1,8 @@
 use std::fmt::Debug;
 
-use super::{Action, NormalizedCollect, NormalizedMint, NormalizedSwap, NormalizedTransfer};
+use super::{
+    Action, NormalizedCollect, NormalizedFill, NormalizedMint, NormalizedSwap, NormalizedTransfer,
+};
 
 impl<T: Sized + SubordinateAction<O>, O: ActionCmp<T>> ActionComparison<O> for T {}
 
@@ -21,6 +23,7 @@ impl ActionCmp<Action> for Action {
             Action::Mint(m) => m.is_superior_action(other),
             Action::Collect(c) => c.is_superior_action(other),
             Action::SwapWithFee(s) => s.swap.is_superior_action(other),
+            Action::Fill(f) => f.is_superior_action(other),
             Action::FlashLoan(f) => f.child_actions.iter().any(|a| a.is_superior_action(other)),
             Action::Batch(b) => {
                 let user = b.user_swaps.iter().any(|b| b.is_superior_action(other));
@@ -119,3 +122,23 @@ impl ActionCmp<Action> for NormalizedCollect {
         }
     }
 }
+
+impl ActionCmp<NormalizedTransfer> for NormalizedFill {
+    fn is_superior_action(&self, transfer: &NormalizedTransfer) -> bool {
+        (transfer.from == self.maker
+            && transfer.token == self.maker_token
+            && transfer.amount == self.maker_amount)
+            || (transfer.to == self.recipient
+                && transfer.token == self.taker_token
+                && transfer.amount == self.taker_amount)
+    }
+}
+
+impl ActionCmp<Action> for NormalizedFill {
+    fn is_superior_action(&self, other: &Action) -> bool {
+        match other {
+            Action::Transfer(t) => self.is_superior_action(t),
+            _ => false,
+        }
+    }
+}
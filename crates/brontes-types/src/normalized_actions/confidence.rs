@@ -0,0 +1,147 @@
+//! Classification confidence.
+//!
+//! Not every normalized action is decoded from a known protocol abi with
+//! complete token data. Transfers of tokens no classifier knows are decoded
+//! with the generic erc20 abi, some are only inferred from the transfer log
+//! the call emitted, and their amounts can be reconciled against the logs.
+//! The classifier records the fallbacks it needed for each trace as a
+//! [`DecodeOutcome`] on the transaction's root, and each fallback lowers the
+//! confidence in the trace's actions, so that consumers can filter out low
+//! quality classifications.
+use crate::db::token_info::TokenInfoWithAddress;
+
+pub const FULL_CONFIDENCE: f64 = 1.0;
+
+/// Decoded with the generic erc20 abi instead of the abi of a known protocol.
+const GENERIC_ABI_PENALTY: f64 = 0.1;
+/// The calldata couldn't be decoded, the action was built from the transfer
+/// log the call emitted.
+const TRANSFER_LOG_PENALTY: f64 = 0.4;
+/// Token info was loaded without a symbol or decimals, so amounts are likely
+/// scaled incorrectly.
+const PARTIAL_TOKEN_INFO_PENALTY: f64 = 0.3;
+/// The transfer amount didn't match the amount in the logs and was adjusted.
+const RECONCILED_AMOUNT_PENALTY: f64 = 0.1;
+
+/// What the actions of a trace were decoded from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeSource {
+    /// The abi of the protocol the contract is bound to
+    #[default]
+    Protocol,
+    /// The generic erc20 abi
+    GenericAbi,
+    /// The transfer log the call emitted
+    TransferLog,
+}
+
+/// The fallbacks the classifier needed to decode the actions of a trace.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOutcome {
+    pub source:             DecodeSource,
+    /// A token of the action was loaded without a symbol or decimals
+    pub partial_token_info: bool,
+    /// The amount decoded from the calldata was adjusted to the logs
+    pub reconciled_amount:  bool,
+}
+
+impl DecodeOutcome {
+    pub fn new(source: DecodeSource) -> Self {
+        Self { source, ..Default::default() }
+    }
+
+    /// Notes the token the action was decoded with, flagging it if its info
+    /// couldn't be loaded.
+    pub fn with_token(mut self, token: &TokenInfoWithAddress) -> Self {
+        self.partial_token_info |= token.symbol.is_empty() && token.decimals == 0;
+        self
+    }
+
+    /// Whether the trace was decoded without any fallback.
+    pub fn is_clean(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Confidence in the actions decoded from the trace, between 0 and 1.
+    pub fn confidence(&self) -> f64 {
+        let source_penalty = match self.source {
+            DecodeSource::Protocol => 0.0,
+            DecodeSource::GenericAbi => GENERIC_ABI_PENALTY,
+            DecodeSource::TransferLog => TRANSFER_LOG_PENALTY,
+        };
+        let mut confidence = FULL_CONFIDENCE * (1.0 - source_penalty);
+        if self.partial_token_info {
+            confidence *= 1.0 - PARTIAL_TOKEN_INFO_PENALTY;
+        }
+        if self.reconciled_amount {
+            confidence *= 1.0 - RECONCILED_AMOUNT_PENALTY;
+        }
+        confidence
+    }
+}
+
+/// Summary of the confidence of all classified actions in a set of
+/// transactions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionConfidence {
+    pub min:  f64,
+    pub mean: f64,
+}
+
+impl Default for ActionConfidence {
+    fn default() -> Self {
+        Self { min: FULL_CONFIDENCE, mean: FULL_CONFIDENCE }
+    }
+}
+
+impl ActionConfidence {
+    /// If there are no classified actions, full confidence is returned as
+    /// there is nothing that could have been misclassified.
+    pub fn from_confidences(confidences: impl IntoIterator<Item = f64>) -> Self {
+        let (min, sum, count) = confidences
+            .into_iter()
+            .fold((FULL_CONFIDENCE, 0.0, 0usize), |(min, sum, count), confidence| {
+                (min.min(confidence), sum + confidence, count + 1)
+            });
+
+        if count == 0 {
+            return Self::default()
+        }
+
+        Self { min, mean: sum / count as f64 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::Address;
+
+    use super::*;
+    use crate::db::token_info::TokenInfo;
+
+    fn token(symbol: &str, decimals: u8) -> TokenInfoWithAddress {
+        TokenInfoWithAddress {
+            address: Address::default(),
+            inner:   TokenInfo::new(decimals, symbol.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_decode_outcome_confidence() {
+        assert_eq!(DecodeOutcome::default().confidence(), FULL_CONFIDENCE);
+
+        let generic = DecodeOutcome::new(DecodeSource::GenericAbi).with_token(&token("USDC", 6));
+        assert!(!generic.is_clean());
+        assert!((generic.confidence() - 0.9).abs() < f64::EPSILON);
+
+        let inferred = DecodeOutcome::new(DecodeSource::TransferLog).with_token(&token("", 0));
+        assert!(inferred.partial_token_info);
+        assert!((inferred.confidence() - 0.42).abs() < 1e-9);
+
+        let summary = ActionConfidence::from_confidences([1.0, generic.confidence()]);
+        assert!((summary.min - 0.9).abs() < f64::EPSILON);
+        assert!((summary.mean - 0.95).abs() < 1e-9);
+
+        assert_eq!(ActionConfidence::from_confidences([]), ActionConfidence::default());
+    }
+}
@@ -0,0 +1,122 @@
+use std::fmt::Debug;
+
+use alloy_primitives::{Address, B256, U256};
+use clickhouse::Row;
+use malachite::Rational;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    accounting::{apply_delta, AddressDeltas, TokenAccounting},
+    NormalizedSwap,
+};
+use crate::{db::token_info::TokenInfoWithAddress, Protocol};
+
+/// The fill of a signed limit order or intent. Unlike a swap there is no pool,
+/// the maker signed the order off chain and the taker settles it against the
+/// maker's funds through the protocol's settlement contract.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Row, PartialEq, Eq)]
+pub struct NormalizedFill {
+    pub protocol:     Protocol,
+    pub trace_index:  u64,
+    pub order_hash:   B256,
+    /// Signer of the order
+    pub maker:        Address,
+    /// Receives the taker token, the maker unless the order sets a receiver
+    pub recipient:    Address,
+    /// Filler of the order
+    pub taker:        Address,
+    pub settlement:   Address,
+    /// Token and amount the maker sold
+    pub maker_token:  TokenInfoWithAddress,
+    pub maker_amount: Rational,
+    /// Token and amount the taker paid
+    pub taker_token:  TokenInfoWithAddress,
+    pub taker_amount: Rational,
+    pub msg_value:    U256,
+}
+
+impl NormalizedFill {
+    /// The fill as a swap of the taker against the maker, as the taker sees
+    /// it. This is how a fill shows up as a leg of the taker's arbitrage.
+    pub fn taker_swap(&self) -> NormalizedSwap {
+        NormalizedSwap {
+            protocol:    self.protocol,
+            trace_index: self.trace_index,
+            from:        self.taker,
+            recipient:   self.taker,
+            pool:        self.maker,
+            token_in:    self.taker_token.clone(),
+            token_out:   self.maker_token.clone(),
+            amount_in:   self.taker_amount.clone(),
+            amount_out:  self.maker_amount.clone(),
+            msg_value:   self.msg_value,
+        }
+    }
+
+    /// The fill as a swap of the maker through the settlement contract. This
+    /// is how the maker is attributed when the fill is a victim.
+    pub fn maker_swap(&self) -> NormalizedSwap {
+        NormalizedSwap {
+            protocol:    self.protocol,
+            trace_index: self.trace_index,
+            from:        self.maker,
+            recipient:   self.recipient,
+            pool:        self.settlement,
+            token_in:    self.maker_token.clone(),
+            token_out:   self.taker_token.clone(),
+            amount_in:   self.maker_amount.clone(),
+            amount_out:  self.taker_amount.clone(),
+            msg_value:   U256::ZERO,
+        }
+    }
+}
+
+impl TokenAccounting for NormalizedFill {
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
+        apply_delta(self.maker, self.maker_token.address, -self.maker_amount.clone(), delta_map);
+        apply_delta(self.taker, self.maker_token.address, self.maker_amount.clone(), delta_map);
+
+        apply_delta(self.taker, self.taker_token.address, -self.taker_amount.clone(), delta_map);
+        apply_delta(self.recipient, self.taker_token.address, self.taker_amount.clone(), delta_map);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToScaledRational;
+
+    #[test]
+    fn test_fill_swaps_and_deltas() {
+        let maker = Address::repeat_byte(1);
+        let taker = Address::repeat_byte(2);
+        let fill = NormalizedFill {
+            maker,
+            recipient: maker,
+            taker,
+            maker_token: TokenInfoWithAddress::weth(),
+            maker_amount: U256::from(2).to_scaled_rational(0),
+            taker_token: TokenInfoWithAddress::usdc(),
+            taker_amount: U256::from(6000).to_scaled_rational(0),
+            ..Default::default()
+        };
+
+        let taker_swap = fill.taker_swap();
+        assert_eq!((taker_swap.from, taker_swap.pool), (taker, maker));
+        assert_eq!(taker_swap.token_out, TokenInfoWithAddress::weth());
+        let maker_swap = fill.maker_swap();
+        assert_eq!(maker_swap.from, maker);
+        assert_eq!(maker_swap.amount_out, fill.taker_amount);
+
+        let mut deltas = AddressDeltas::default();
+        fill.apply_token_deltas(&mut deltas);
+        assert_eq!(
+            deltas[&taker][&TokenInfoWithAddress::weth().address],
+            U256::from(2).to_scaled_rational(0)
+        );
+        assert_eq!(
+            deltas[&maker][&TokenInfoWithAddress::usdc().address],
+            U256::from(6000).to_scaled_rational(0)
+        );
+    }
+}
@@ -9,7 +9,10 @@ use reth_primitives::Address;
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
-use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
+use super::{
+    accounting::{apply_delta, AddressDeltas, TokenAccounting},
+    TokenAmount,
+};
 pub use super::{Action, NormalizedSwap};
 use crate::{
     db::{
@@ -21,6 +24,7 @@ use crate::{
 
 #[derive(Default, Debug, Serialize, Clone, Row, PartialEq, Eq, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct NormalizedLiquidation {
     #[redefined(same_fields)]
     pub protocol:              Protocol,
@@ -92,6 +96,14 @@ impl fmt::Display for NormalizedLiquidation {
 }
 
 impl NormalizedLiquidation {
+    pub fn covered_debt_amount(&self) -> TokenAmount {
+        TokenAmount::from_scaled(&self.debt_asset, self.covered_debt.clone())
+    }
+
+    pub fn liquidated_collateral_amount(&self) -> TokenAmount {
+        TokenAmount::from_scaled(&self.collateral_asset, self.liquidated_collateral.clone())
+    }
+
     pub fn pretty_print(&self, f: &mut fmt::Formatter<'_>, spaces: usize) -> fmt::Result {
         let field_names = [
             "Protocol",
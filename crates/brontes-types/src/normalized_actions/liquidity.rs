@@ -1,6 +1,6 @@
 use std::fmt::{self, Debug};
 
-use alloy_primitives::TxHash;
+use alloy_primitives::{TxHash, U256};
 use clickhouse::Row;
 use colored::Colorize;
 use itertools::Itertools;
@@ -13,13 +13,17 @@ use serde::{Deserialize, Serialize};
 use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
 use crate::{
     db::{
-        redefined_types::{malachite::RationalRedefined, primitives::AddressRedefined},
+        redefined_types::{
+            malachite::RationalRedefined,
+            primitives::{AddressRedefined, U256Redefined},
+        },
         token_info::{TokenInfoWithAddress, TokenInfoWithAddressRedefined},
     },
     rational_to_u256_fraction, Protocol, ToFloatNearest,
 };
 #[derive(Debug, Default, Serialize, Clone, Row, PartialEq, Eq, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct NormalizedMint {
     #[redefined(same_fields)]
     pub protocol:    Protocol,
@@ -29,6 +33,10 @@ pub struct NormalizedMint {
     pub pool:        Address,
     pub token:       Vec<TokenInfoWithAddress>,
     pub amount:      Vec<Rational>,
+    /// Token id of the position NFT, if the liquidity is managed by a
+    /// position manager
+    #[serde(default)]
+    pub position_id: Option<U256>,
 }
 
 impl TokenAccounting for NormalizedMint {
@@ -43,6 +51,7 @@ impl TokenAccounting for NormalizedMint {
 
 #[derive(Debug, Default, Serialize, Clone, Row, PartialEq, Eq, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct NormalizedBurn {
     #[redefined(same_fields)]
     pub protocol:    Protocol,
@@ -52,6 +61,10 @@ pub struct NormalizedBurn {
     pub pool:        Address,
     pub token:       Vec<TokenInfoWithAddress>,
     pub amount:      Vec<Rational>,
+    /// Token id of the position NFT, if the liquidity is managed by a
+    /// position manager
+    #[serde(default)]
+    pub position_id: Option<U256>,
 }
 
 impl TokenAccounting for NormalizedBurn {
@@ -66,6 +79,7 @@ impl TokenAccounting for NormalizedBurn {
 
 #[derive(Debug, Default, Serialize, Clone, Row, PartialEq, Eq, Deserialize, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct NormalizedCollect {
     #[redefined(same_fields)]
     pub protocol:    Protocol,
@@ -75,6 +89,10 @@ pub struct NormalizedCollect {
     pub pool:        Address,
     pub token:       Vec<TokenInfoWithAddress>,
     pub amount:      Vec<Rational>,
+    /// Token id of the position NFT, if the liquidity is managed by a
+    /// position manager
+    #[serde(default)]
+    pub position_id: Option<U256>,
 }
 
 impl TokenAccounting for NormalizedCollect {
@@ -87,6 +105,23 @@ impl TokenAccounting for NormalizedCollect {
     }
 }
 
+/// A position's exit through a fee collection, which takes the burnt liquidity
+/// out with the fees
+impl From<NormalizedCollect> for NormalizedBurn {
+    fn from(c: NormalizedCollect) -> Self {
+        NormalizedBurn {
+            recipient:   c.recipient,
+            trace_index: c.trace_index,
+            protocol:    c.protocol,
+            amount:      c.amount,
+            token:       c.token,
+            pool:        c.pool,
+            from:        c.from,
+            position_id: c.position_id,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct ClickhouseVecNormalizedMintOrBurn {
     pub trace_index: Vec<u64>,
@@ -1,13 +1,17 @@
 pub mod accounting;
 pub mod aggregator;
+pub mod amount;
 pub mod batch;
 pub mod comparison;
+pub mod confidence;
 pub mod eth_transfer;
+pub mod fill;
 pub mod flashloan;
 pub mod lending;
 pub mod liquidation;
 pub mod liquidity;
 pub mod multi_callframe;
+pub mod nft;
 pub mod pool;
 pub mod self_destruct;
 pub mod swaps;
@@ -18,14 +22,18 @@ use ::clickhouse::DbRow;
 use accounting::{AddressDeltas, TokenAccounting};
 pub use aggregator::*;
 use alloy_primitives::{Address, Bytes, Log};
+pub use amount::TokenAmount;
 pub use batch::*;
 use clickhouse::InsertRow;
+pub use confidence::*;
 pub use eth_transfer::*;
+pub use fill::*;
 pub use flashloan::*;
 pub use lending::*;
 pub use liquidation::*;
 pub use liquidity::*;
 pub use multi_callframe::*;
+pub use nft::*;
 pub use pool::*;
 use reth_rpc_types::trace::parity::Action as TraceAction;
 pub use self_destruct::*;
@@ -93,6 +101,8 @@ impl NormalizedAction for Action {
             Self::Collect(c) => c.trace_index,
             Self::SelfDestruct(c) => c.trace_index,
             Self::EthTransfer(e) => e.trace_index,
+            Self::NftTransfer(n) => n.trace_index,
+            Self::Fill(f) => f.trace_index,
             Self::Unclassified(u) => u.trace_idx,
             Self::NewPool(p) => p.trace_index,
             Self::PoolConfigUpdate(p) => p.trace_index,
@@ -116,11 +126,13 @@ pub enum Action {
     Liquidation(NormalizedLiquidation),
     SelfDestruct(SelfdestructWithIndex),
     EthTransfer(NormalizedEthTransfer),
+    Fill(NormalizedFill),
     NewPool(NormalizedNewPool),
     PoolConfigUpdate(NormalizedPoolConfigUpdate),
     Aggregator(NormalizedAggregator),
     Unclassified(TransactionTraceWithLogs),
     Revert,
+    NftTransfer(NormalizedNftTransfer),
 }
 
 impl InsertRow for Action {
@@ -137,6 +149,8 @@ impl InsertRow for Action {
             Action::Liquidation(_) => NormalizedLiquidation::COLUMN_NAMES,
             Action::SelfDestruct(_) => todo!("joe pls dome this"),
             Action::EthTransfer(_) => todo!("joe pls dome this"),
+            Action::NftTransfer(_) => NormalizedNftTransfer::COLUMN_NAMES,
+            Action::Fill(_) => NormalizedFill::COLUMN_NAMES,
             Action::NewPool(_) => todo!(),
             Action::PoolConfigUpdate(_) => todo!(),
             Action::Unclassified(..) | Action::Revert => panic!(),
@@ -163,6 +177,8 @@ impl serde::Serialize for Action {
             Action::Liquidation(c) => c.serialize(serializer),
             Action::SelfDestruct(sd) => sd.serialize(serializer),
             Action::EthTransfer(et) => et.serialize(serializer),
+            Action::NftTransfer(n) => n.serialize(serializer),
+            Action::Fill(f) => f.serialize(serializer),
             Action::Unclassified(trace) => (trace).serialize(serializer),
             action => format!("{:?}", action).serialize(serializer),
             //action => unreachable!("no action serialization for {action:?}"),
@@ -218,6 +234,18 @@ impl Action {
                     from: a.from,
                     ..Default::default()
                 }),
+                Self::NftTransfer(n) => (!n.msg_value.is_zero()).then(|| NormalizedEthTransfer {
+                    value: n.msg_value,
+                    to: n.collection,
+                    from: n.from,
+                    ..Default::default()
+                }),
+                Self::Fill(f) => (!f.msg_value.is_zero()).then(|| NormalizedEthTransfer {
+                    value: f.msg_value,
+                    to: f.settlement,
+                    from: f.taker,
+                    ..Default::default()
+                }),
                 Self::Mint(_) => None,
                 Self::Burn(_) => None,
                 Self::Transfer(_) => None,
@@ -254,6 +282,8 @@ impl Action {
             Self::Collect(c) => c.trace_index,
             Self::SelfDestruct(c) => c.trace_index,
             Self::EthTransfer(e) => e.trace_index,
+            Self::NftTransfer(n) => n.trace_index,
+            Self::Fill(f) => f.trace_index,
             Self::Unclassified(u) => u.trace_idx,
             Self::NewPool(p) => p.trace_index,
             Self::PoolConfigUpdate(p) => p.trace_index,
@@ -333,6 +363,8 @@ impl Action {
                 reth_rpc_types::trace::parity::Action::Selfdestruct(s) => s.address,
             },
             Action::EthTransfer(t) => t.to,
+            Action::NftTransfer(n) => n.to,
+            Action::Fill(f) => f.settlement,
             Action::NewPool(p) => p.pool_address,
             Action::PoolConfigUpdate(p) => p.pool_address,
             Action::Revert => Address::ZERO,
@@ -359,6 +391,8 @@ impl Action {
                 reth_rpc_types::trace::parity::Action::Selfdestruct(s) => s.address,
             },
             Action::EthTransfer(t) => t.from,
+            Action::NftTransfer(n) => n.from,
+            Action::Fill(f) => f.taker,
             Action::Revert => unreachable!(),
             Action::NewPool(_) => Address::ZERO,
             Action::PoolConfigUpdate(_) => Address::ZERO,
@@ -415,6 +449,14 @@ impl Action {
         matches!(self, Action::Transfer(_))
     }
 
+    pub const fn is_nft_transfer(&self) -> bool {
+        matches!(self, Action::NftTransfer(_))
+    }
+
+    pub const fn is_fill(&self) -> bool {
+        matches!(self, Action::Fill(_))
+    }
+
     pub const fn is_collect(&self) -> bool {
         matches!(self, Action::Collect(_))
     }
@@ -448,6 +490,7 @@ impl Action {
             Action::NewPool(p) => p.protocol,
             Action::PoolConfigUpdate(p) => p.protocol,
             Action::Aggregator(a) => a.protocol,
+            Action::Fill(f) => f.protocol,
             _ => Protocol::Unknown,
         }
     }
@@ -522,6 +565,8 @@ extra_impls!(
     (SwapWithFee, NormalizedSwapWithFee),
     (Transfer, NormalizedTransfer),
     (EthTransfer, NormalizedEthTransfer),
+    (NftTransfer, NormalizedNftTransfer),
+    (Fill, NormalizedFill),
     (Liquidation, NormalizedLiquidation),
     (FlashLoan, NormalizedFlashLoan),
     (Aggregator, NormalizedAggregator),
@@ -578,6 +623,8 @@ impl TokenAccounting for Action {
             Action::SwapWithFee(swap_with_fee) => swap_with_fee.swap.apply_token_deltas(delta_map),
             Action::Collect(collect) => collect.apply_token_deltas(delta_map),
             Action::EthTransfer(eth_transfer) => eth_transfer.apply_token_deltas(delta_map),
+            Action::NftTransfer(nft_transfer) => nft_transfer.apply_token_deltas(delta_map),
+            Action::Fill(fill) => fill.apply_token_deltas(delta_map),
             Action::Unclassified(_) => (), /* Potentially no token deltas to apply, adjust as */
             // necessary
             Action::SelfDestruct(_self_destruct) => (),
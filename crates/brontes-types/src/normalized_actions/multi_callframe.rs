@@ -36,6 +36,7 @@ pub enum MultiFrameAction {
     Batch,
     Liquidation,
     Aggregator,
+    Liquidity,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -66,6 +67,21 @@ impl MultiFrameRequest {
                 call_type: MultiFrameAction::Aggregator,
                 trace_idx,
             }),
+            Action::Mint(m) if m.protocol == Protocol::UniswapV3PositionManager => Some(Self {
+                protocol: m.protocol,
+                call_type: MultiFrameAction::Liquidity,
+                trace_idx,
+            }),
+            Action::Burn(b) if b.protocol == Protocol::UniswapV3PositionManager => Some(Self {
+                protocol: b.protocol,
+                call_type: MultiFrameAction::Liquidity,
+                trace_idx,
+            }),
+            Action::Collect(c) if c.protocol == Protocol::UniswapV3PositionManager => Some(Self {
+                protocol: c.protocol,
+                call_type: MultiFrameAction::Liquidity,
+                trace_idx,
+            }),
             _ => None,
         }
     }
@@ -0,0 +1,65 @@
+use std::fmt::Debug;
+
+use alloy_primitives::{Address, U256};
+use clickhouse::Row;
+use malachite::Rational;
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, Display};
+
+use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
+use crate::ToScaledRational;
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Display, AsRefStr,
+)]
+pub enum NftStandard {
+    #[default]
+    Erc721,
+    Erc1155,
+}
+
+/// A transfer of one or more tokens of a single ERC-721 or ERC-1155
+/// collection. ERC-1155 batch transfers are kept as one action, with
+/// `token_ids` and `amounts` in the order they were emitted.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Row, PartialEq, Eq)]
+pub struct NormalizedNftTransfer {
+    pub trace_index: u64,
+    pub from:        Address,
+    pub to:          Address,
+    pub collection:  Address,
+    pub standard:    NftStandard,
+    pub token_ids:   Vec<U256>,
+    /// Always one per id for ERC-721
+    pub amounts:     Vec<U256>,
+    pub msg_value:   U256,
+}
+
+impl NormalizedNftTransfer {
+    /// Number of tokens of the collection that moved. NFTs have no decimals,
+    /// so this is also the amount used in the token deltas.
+    pub fn total_amount(&self) -> Rational {
+        self.amounts
+            .iter()
+            .fold(U256::ZERO, |acc, amount| acc.saturating_add(*amount))
+            .to_scaled_rational(0)
+    }
+
+    pub fn is_mint(&self) -> bool {
+        self.from == Address::ZERO
+    }
+
+    pub fn is_burn(&self) -> bool {
+        self.to == Address::ZERO
+    }
+}
+
+impl TokenAccounting for NormalizedNftTransfer {
+    /// Token ids are fungible for accounting purposes, a collection is valued
+    /// as a whole at its floor price.
+    fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
+        let amount = self.total_amount();
+
+        apply_delta(self.from, self.collection, -amount.clone(), delta_map);
+        apply_delta(self.to, self.collection, amount, delta_map);
+    }
+}
@@ -16,7 +16,7 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     accounting::{apply_delta, AddressDeltas, TokenAccounting},
-    Action,
+    Action, TokenAmount,
 };
 use crate::{
     db::{
@@ -49,6 +49,7 @@ impl DerefMut for NormalizedSwapWithFee {
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone, Row, PartialEq, Eq, Redefined, Hash)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct NormalizedSwap {
     #[redefined(same_fields)]
     pub protocol:    Protocol,
@@ -66,6 +67,14 @@ pub struct NormalizedSwap {
 }
 
 impl NormalizedSwap {
+    pub fn token_amount_in(&self) -> TokenAmount {
+        TokenAmount::from_scaled(&self.token_in, self.amount_in.clone())
+    }
+
+    pub fn token_amount_out(&self) -> TokenAmount {
+        TokenAmount::from_scaled(&self.token_out, self.amount_out.clone())
+    }
+
     /// Calculates the exchange rate for a given DEX swap
     pub fn swap_rate(&self) -> Rational {
         if self.amount_out == Rational::ZERO {
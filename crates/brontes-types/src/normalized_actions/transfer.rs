@@ -7,7 +7,10 @@ use reth_primitives::{Address, U256};
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
 
-use super::accounting::{apply_delta, AddressDeltas, TokenAccounting};
+use super::{
+    accounting::{apply_delta, AddressDeltas, TokenAccounting},
+    TokenAmount,
+};
 use crate::{
     db::{
         redefined_types::{malachite::*, primitives::*},
@@ -18,6 +21,7 @@ use crate::{
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone, Row, PartialEq, Eq, Redefined)]
 #[redefined_attr(derive(Debug, PartialEq, Clone, Serialize, rSerialize, rDeserialize, Archive))]
+#[redefined_attr(archive(check_bytes))]
 pub struct NormalizedTransfer {
     pub trace_index: u64,
     pub from:        Address,
@@ -28,6 +32,18 @@ pub struct NormalizedTransfer {
     pub msg_value:   U256,
 }
 
+impl NormalizedTransfer {
+    /// Amount received by `to`
+    pub fn token_amount(&self) -> TokenAmount {
+        TokenAmount::from_scaled(&self.token, self.amount.clone())
+    }
+
+    /// Fee taken on top of the amount, denominated in the transferred token
+    pub fn token_fee(&self) -> TokenAmount {
+        TokenAmount::from_scaled(&self.token, self.fee.clone())
+    }
+}
+
 impl TokenAccounting for NormalizedTransfer {
     fn apply_token_deltas(&self, delta_map: &mut AddressDeltas) {
         let amount_sent = &self.amount + &self.fee;
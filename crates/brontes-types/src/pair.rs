@@ -34,6 +34,7 @@ use crate::{constants::USD_STABLES_BY_ADDRESS, db::redefined_types::primitives::
     rSerialize,
     Archive,
 ))]
+#[redefined_attr(archive(check_bytes))]
 #[redefined_attr(other(
     #[archive_attr(derive(Hash, PartialEq, Eq))]
 ))]
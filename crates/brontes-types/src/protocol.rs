@@ -1,5 +1,7 @@
 use std::fmt;
 
+use alloy_primitives::{address, b256, keccak256, Address, B256};
+use malachite::Rational;
 use redefined::self_convert_redefined;
 use rkyv::{Archive, Deserialize as rDeserialize, Serialize as rSerialize};
 use serde::{Deserialize, Serialize};
@@ -63,6 +65,7 @@ utils!(
         Ord,
         strum::EnumString,
     )]
+    #[archive(check_bytes)]
     #[repr(u8)]
     pub enum Protocol {
         UniswapV2,
@@ -104,6 +107,13 @@ utils!(
         Dodo,
         #[default]
         Unknown,
+        UniswapV3PositionManager,
+        LidoStEth,
+        LidoWstEth,
+        RocketPool,
+        ERC4626Vault,
+        LidoWithdrawalQueue,
+        CoinbaseCbEth,
     }
 );
 
@@ -148,6 +158,13 @@ impl Protocol {
             Protocol::PropellerLabsSolver => ("Propeller Labs Solver", ""),
             Protocol::Dodo => ("Dodo", "V1/V2"),
             Protocol::Unknown => ("Unknown", "Unknown"),
+            Protocol::UniswapV3PositionManager => ("Uniswap", "V3 Position Manager"),
+            Protocol::LidoStEth => ("Lido", "stETH"),
+            Protocol::LidoWstEth => ("Lido", "wstETH"),
+            Protocol::RocketPool => ("RocketPool", "rETH"),
+            Protocol::ERC4626Vault => ("ERC4626", "Vault"),
+            Protocol::LidoWithdrawalQueue => ("Lido", "Withdrawal Queue"),
+            Protocol::CoinbaseCbEth => ("Coinbase", "cbETH"),
         }
     }
 
@@ -172,13 +189,84 @@ impl Protocol {
             "balancerv1smartpool" => Protocol::BalancerV1CRP,
             "balancerv2" => Protocol::BalancerV2,
             "dodov1/v2" => Protocol::Dodo,
+            "uniswapv3 position manager" => Protocol::UniswapV3PositionManager,
             "pancakeswapv2" => Protocol::PancakeSwapV2,
             "pancakeswapv3" => Protocol::PancakeSwapV3,
+            "lidosteth" => Protocol::LidoStEth,
+            "lidowsteth" => Protocol::LidoWstEth,
+            "rocketpoolreth" => Protocol::RocketPool,
+            "erc4626vault" => Protocol::ERC4626Vault,
+            "lidowithdrawalqueue" => Protocol::LidoWithdrawalQueue,
+            "coinbasecbeth" => Protocol::CoinbaseCbEth,
             _ => Protocol::Unknown,
         }
     }
+
+    /// Share of the input of a swap on the pool that goes to its liquidity
+    /// providers. Every pool of a v2 fork charges the same fee, v3 forks pick
+    /// one of a few tiers on creation, which is recovered from the pool's
+    /// address. `None` for the protocols whose fee is a setting of the pool
+    /// that can be changed, and for a v3 pool the fork's factory didn't deploy
+    pub fn lp_fee_rate(
+        &self,
+        pool: Address,
+        token_in: Address,
+        token_out: Address,
+    ) -> Option<Rational> {
+        match self {
+            Protocol::UniswapV2 | Protocol::SushiSwapV2 => {
+                Some(Rational::from_unsigneds(3u16, 1_000))
+            }
+            Protocol::PancakeSwapV2 => Some(Rational::from_unsigneds(25u16, 10_000)),
+            Protocol::UniswapV3 | Protocol::SushiSwapV3 | Protocol::PancakeSwapV3 => self
+                .v3_fee_tier(pool, token_in, token_out)
+                .map(|fee| Rational::from_unsigneds(fee, 1_000_000)),
+            _ => None,
+        }
+    }
+
+    /// Fee tier of a v3 pool in hundredths of a bip, found by deriving the
+    /// pool's create2 address for each of the fork's tiers
+    fn v3_fee_tier(&self, pool: Address, token_in: Address, token_out: Address) -> Option<u32> {
+        let (deployer, init_code_hash, tiers): (Address, B256, &[u32]) = match self {
+            Protocol::UniswapV3 => {
+                (UNISWAP_V3_FACTORY, UNISWAP_V3_POOL_INIT_CODE_HASH, &[100, 500, 3_000, 10_000])
+            }
+            // a fork of the v3 contracts, the pool's init code is unchanged
+            Protocol::SushiSwapV3 => {
+                (SUSHISWAP_V3_FACTORY, UNISWAP_V3_POOL_INIT_CODE_HASH, &[100, 500, 3_000, 10_000])
+            }
+            Protocol::PancakeSwapV3 => (
+                PANCAKESWAP_V3_POOL_DEPLOYER,
+                PANCAKESWAP_V3_POOL_INIT_CODE_HASH,
+                &[100, 500, 2_500, 10_000],
+            ),
+            _ => return None,
+        };
+        let (token0, token1) =
+            if token_in < token_out { (token_in, token_out) } else { (token_out, token_in) };
+
+        tiers.iter().copied().find(|fee| {
+            // abi encoded (token0, token1, fee)
+            let mut salt = [0u8; 96];
+            salt[12..32].copy_from_slice(token0.as_slice());
+            salt[44..64].copy_from_slice(token1.as_slice());
+            salt[92..96].copy_from_slice(&fee.to_be_bytes());
+
+            deployer.create2(keccak256(salt), init_code_hash) == pool
+        })
+    }
 }
 
+const UNISWAP_V3_FACTORY: Address = address!("1F98431c8aD98523631AE4a59f267346ea31F984");
+const UNISWAP_V3_POOL_INIT_CODE_HASH: B256 =
+    b256!("e34f199b19b2b4f47f68442619d555527d244f78a3297ea89325f843f87b8b54");
+const SUSHISWAP_V3_FACTORY: Address = address!("bACEB8eC6b9355Dfc0269C18bac9d6E2Bdc29C4F");
+/// PancakeSwap's factory has a separate contract deploy its pools
+const PANCAKESWAP_V3_POOL_DEPLOYER: Address = address!("41ff9AA7e16B8B1a8a8dc4f0eFacd93D02d071c9");
+const PANCAKESWAP_V3_POOL_INIT_CODE_HASH: B256 =
+    b256!("6ce8eb472fa82df5469c6ab6d485f17c3ad13c8cd7af59b3d4a8026c5ce0f7e2");
+
 impl fmt::Display for Protocol {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -223,6 +311,13 @@ impl fmt::Display for Protocol {
                 Protocol::PropellerLabsSolver => "Propeller Labs",
                 Protocol::Dodo => "Dodo",
                 Protocol::Unknown => "Unknown",
+                Protocol::UniswapV3PositionManager => "Uni V3 Position Manager",
+                Protocol::LidoStEth => "Lido stETH",
+                Protocol::LidoWstEth => "Lido wstETH",
+                Protocol::RocketPool => "Rocket Pool",
+                Protocol::ERC4626Vault => "ERC-4626 Vault",
+                Protocol::LidoWithdrawalQueue => "Lido Withdrawal Queue",
+                Protocol::CoinbaseCbEth => "Coinbase cbETH",
             }
         )
     }
@@ -230,3 +325,28 @@ impl fmt::Display for Protocol {
 
 self_convert_redefined!(Protocol);
 implement_table_value_codecs_with_zc!(Protocol);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USDC: Address = address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+    const WETH: Address = address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+
+    #[test]
+    fn test_v3_lp_fee_rate() {
+        let usdc_weth_5 = address!("88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        let usdc_weth_30 = address!("8ad599c3A0ff1De082011EFDDc58f1908eB6e6D8");
+
+        assert_eq!(
+            Protocol::UniswapV3.lp_fee_rate(usdc_weth_5, WETH, USDC),
+            Some(Rational::from_unsigneds(5u32, 10_000))
+        );
+        assert_eq!(
+            Protocol::UniswapV3.lp_fee_rate(usdc_weth_30, USDC, WETH),
+            Some(Rational::from_unsigneds(3u32, 1_000))
+        );
+        // not deployed by the fork's factory
+        assert_eq!(Protocol::SushiSwapV3.lp_fee_rate(usdc_weth_5, USDC, WETH), None);
+    }
+}
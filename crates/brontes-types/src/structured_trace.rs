@@ -13,6 +13,7 @@ use serde_with::serde_as;
 use crate::{
     constants::{EXECUTE_FFS_YO, SCP_MAIN_CEX_DEX_BOT},
     db::clickhouse_serde::tx_trace::*,
+    normalized_actions::calldata_referrer,
     serde_utils::u256,
 };
 pub trait TraceActions {
@@ -117,6 +118,7 @@ impl TraceActions for TransactionTraceWithLogs {
 #[derive(
     Debug, Clone, Serialize, Deserialize, PartialEq, Eq, rSerialize, rDeserialize, Archive,
 )]
+#[archive(check_bytes)]
 
 pub struct DecodedCallData {
     pub function_name: String,
@@ -129,6 +131,7 @@ self_convert_redefined!(DecodedCallData);
 #[derive(
     Debug, Clone, Serialize, Deserialize, PartialEq, Eq, rSerialize, rDeserialize, Archive,
 )]
+#[archive(check_bytes)]
 pub struct DecodedParams {
     pub field_name: String,
     pub field_type: String,
@@ -157,6 +160,9 @@ pub struct CallInfo {
     pub from_address:   Address,
     pub msg_sender:     Address,
     pub msg_value:      U256,
+    /// Affiliate tag appended to the calldata of the transaction's top level
+    /// call, if any
+    pub referrer:       Option<Address>,
 }
 
 impl CallFrameInfo<'_> {
@@ -167,6 +173,11 @@ impl CallFrameInfo<'_> {
             from_address:   self.from_address,
             msg_sender:     self.msg_sender,
             msg_value:      self.msg_value,
+            // frontends tag the call they send, tags deeper in the trace are
+            // never ours to attribute
+            referrer:       (self.trace_idx == 0)
+                .then(|| calldata_referrer(&self.call_data))
+                .flatten(),
         }
     }
 }
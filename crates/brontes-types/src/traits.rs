@@ -1,5 +1,6 @@
 use alloy_primitives::TxHash;
 use alloy_rpc_types::AnyReceiptEnvelope;
+use futures::{stream::BoxStream, StreamExt};
 use reth_primitives::{
     Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue, B256,
 };
@@ -9,6 +10,9 @@ use reth_rpc_types::{
 
 use crate::structured_trace::TxTrace;
 
+/// The traces of a block's transactions, in block order.
+pub type TxTraceStream = BoxStream<'static, eyre::Result<TxTrace>>;
+
 #[async_trait::async_trait]
 #[auto_impl::auto_impl(Box)]
 pub trait TracingProvider: Send + Sync + 'static {
@@ -43,6 +47,21 @@ pub trait TracingProvider: Send + Sync + 'static {
         block_id: BlockId,
     ) -> eyre::Result<Option<Vec<TxTrace>>>;
 
+    /// Replays the block's transactions, yielding each transaction's trace as
+    /// soon as it has been traced. Providers that can only trace a whole block
+    /// at once yield the traces when the block is done
+    async fn replay_block_transactions_streamed(
+        &self,
+        block_id: BlockId,
+    ) -> eyre::Result<TxTraceStream> {
+        let traces = self
+            .replay_block_transactions(block_id)
+            .await?
+            .ok_or_else(|| eyre::eyre!("no traces found for block {block_id:?}"))?;
+
+        Ok(futures::stream::iter(traces.into_iter().map(Ok)).boxed())
+    }
+
     async fn block_receipts(
         &self,
         number: BlockNumberOrTag,
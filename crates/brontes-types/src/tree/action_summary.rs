@@ -0,0 +1,134 @@
+use alloy_primitives::Address;
+
+use crate::normalized_actions::{Action, NormalizedAction};
+
+/// Cheap counts of what a block contains, computed when the tree is finalized.
+/// Inspectors use it to skip blocks that can't contain the mev they look for,
+/// so the counts are conservative: actions nested in flash loans and
+/// aggregators are counted for their transaction, and batches count as swaps.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BlockActionSummary {
+    /// Transactions with a swap or a limit order fill
+    pub swap_txs:          usize,
+    /// Transactions without a classified swap that transfer two different
+    /// tokens. Inspectors infer swaps on unclassified pools from such
+    /// transfers, so these can still hold a swap
+    pub inferred_swap_txs: usize,
+    /// Transactions with a liquidation
+    pub liquidation_txs:   usize,
+    /// Transactions that burn liquidity
+    pub lp_exit_txs:       usize,
+    /// Whether some transaction mints liquidity, a later transaction swaps and
+    /// a later one burns liquidity
+    pub jit_shaped:        bool,
+}
+
+#[derive(Default)]
+struct TxActions {
+    swap:                bool,
+    liquidation:         bool,
+    mint:                bool,
+    burn:                bool,
+    transfer_token:      Option<Address>,
+    two_token_transfers: bool,
+}
+
+impl TxActions {
+    fn add(&mut self, action: &Action) {
+        match action {
+            Action::Swap(_) | Action::SwapWithFee(_) | Action::Fill(_) | Action::Batch(_) => {
+                self.swap = true
+            }
+            Action::Liquidation(_) => self.liquidation = true,
+            Action::Mint(_) => self.mint = true,
+            Action::Burn(_) => self.burn = true,
+            Action::Transfer(t) => match self.transfer_token {
+                Some(token) => self.two_token_transfers |= token != t.token.address,
+                None => self.transfer_token = Some(t.token.address),
+            },
+            Action::FlashLoan(f) => f.child_actions.iter().for_each(|a| self.add(a)),
+            Action::Aggregator(a) => a.child_actions.iter().for_each(|a| self.add(a)),
+            _ => {}
+        }
+    }
+}
+
+impl BlockActionSummary {
+    /// Builds the summary from the actions of each transaction, in block order
+    pub fn new<'a, V: NormalizedAction + 'a>(
+        txs: impl IntoIterator<Item = impl IntoIterator<Item = &'a V>>,
+    ) -> Self {
+        let mut summary = Self::default();
+        let mut seen_mint = false;
+        let mut seen_swap_after_mint = false;
+
+        for tx in txs {
+            let mut actions = TxActions::default();
+            tx.into_iter().for_each(|a| actions.add(a.get_action()));
+
+            summary.swap_txs += actions.swap as usize;
+            summary.inferred_swap_txs += (!actions.swap && actions.two_token_transfers) as usize;
+            summary.liquidation_txs += actions.liquidation as usize;
+            summary.lp_exit_txs += actions.burn as usize;
+
+            if actions.burn && seen_swap_after_mint {
+                summary.jit_shaped = true;
+            }
+            if actions.swap && seen_mint {
+                seen_swap_after_mint = true;
+            }
+            seen_mint |= actions.mint;
+        }
+
+        summary
+    }
+
+    /// Transactions that hold a classified swap or one an inspector can infer
+    /// from the transaction's transfers
+    pub fn possible_swap_txs(&self) -> usize {
+        self.swap_txs + self.inferred_swap_txs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_actions::{
+        NormalizedBurn, NormalizedMint, NormalizedSwap, NormalizedTransfer,
+    };
+
+    #[test]
+    fn test_jit_shape_needs_mint_swap_burn_in_order() {
+        let mint = Action::Mint(NormalizedMint::default());
+        let swap = Action::Swap(NormalizedSwap::default());
+        let burn = Action::Burn(NormalizedBurn::default());
+
+        let summary = BlockActionSummary::new([vec![&mint], vec![&swap], vec![&burn]]);
+        assert!(summary.jit_shaped);
+        assert_eq!(summary.swap_txs, 1);
+        assert_eq!(summary.liquidation_txs, 0);
+        assert_eq!(summary.lp_exit_txs, 1);
+
+        let summary = BlockActionSummary::new([vec![&swap], vec![&mint], vec![&burn]]);
+        assert!(!summary.jit_shaped);
+    }
+
+    #[test]
+    fn test_transfers_of_two_tokens_can_hold_a_swap() {
+        let transfer = |token: u8| {
+            let mut transfer = NormalizedTransfer::default();
+            transfer.token.address = Address::repeat_byte(token);
+            Action::Transfer(transfer)
+        };
+        let (a, b) = (transfer(1), transfer(2));
+
+        let summary = BlockActionSummary::new([
+            vec![&a, &b],
+            vec![&a, &a],
+            vec![&Action::Swap(Default::default()), &a, &b],
+        ]);
+        assert_eq!(summary.swap_txs, 1);
+        assert_eq!(summary.inferred_swap_txs, 1);
+        assert_eq!(summary.possible_swap_txs(), 2);
+    }
+}
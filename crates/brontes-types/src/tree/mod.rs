@@ -7,10 +7,13 @@ use tracing::{error, info, span, Level};
 
 use crate::{normalized_actions::MultiCallFrameClassification, tree::types::NodeWithDataRef};
 
+pub mod action_summary;
 pub mod frontend_prunes;
+pub use action_summary::*;
 pub use frontend_prunes::*;
 
 use crate::db::traits::LibmdbxReader;
+pub mod multicall;
 pub mod node;
 mod types;
 #[allow(unused_parens)]
@@ -18,12 +21,17 @@ pub mod util;
 pub use util::*;
 pub mod root;
 pub mod tx_info;
+pub use multicall::*;
 pub use node::*;
 pub use root::*;
 pub use tx_info::*;
 pub mod search_args;
 pub use search_args::*;
+pub mod spill;
+pub mod visualize;
+pub use visualize::*;
 
+use self::spill::MemoryBound;
 use crate::{db::metadata::Metadata, normalized_actions::NormalizedAction};
 
 type SpansAll<V> = TreeIterator<V, std::vec::IntoIter<(B256, Vec<Vec<V>>)>>;
@@ -35,6 +43,11 @@ pub struct BlockTree<V: NormalizedAction> {
     pub header:               Header,
     pub priority_fee_std_dev: f64,
     pub avg_priority_fee:     f64,
+    /// What the block contains, set when the tree is finalized
+    pub action_summary:       BlockActionSummary,
+    /// The bound the tree was built under when the block was too large to
+    /// build in memory, it's then inspected in chunks, see [`Self::chunks`]
+    pub memory_bound:         Option<MemoryBound>,
 }
 
 impl<V: NormalizedAction> BlockTree<V> {
@@ -44,9 +57,18 @@ impl<V: NormalizedAction> BlockTree<V> {
             header,
             priority_fee_std_dev: 0.0,
             avg_priority_fee: 0.0,
+            action_summary: BlockActionSummary::default(),
+            memory_bound: None,
         }
     }
 
+    /// Whether the block was mined before the merge, so it has no proposer
+    /// and its coinbase is the miner. The merge zeroed the difficulty of every
+    /// block after it, whatever the chain
+    pub fn is_pre_merge(&self) -> bool {
+        !self.header.difficulty.is_zero()
+    }
+
     pub fn tx_must_contain_action(&self, tx_hash: B256, f: impl Fn(&V) -> bool) -> Option<bool> {
         self.tx_roots
             .iter()
@@ -159,6 +181,11 @@ impl<V: NormalizedAction> BlockTree<V> {
             this.avg_priority_fee = total_priority_fee / this.tx_roots.len() as f64;
             let std_dev = priority_fees.population_std_dev();
             this.priority_fee_std_dev = std_dev;
+            this.action_summary = BlockActionSummary::new(
+                this.tx_roots
+                    .iter()
+                    .map(|root| root.data_store.0.iter().flatten().flatten()),
+            );
         })
     }
 
@@ -0,0 +1,166 @@
+//! Multicall unwrapping.
+//!
+//! Multicall3 and the router `multicall` entrypoints let a single transaction
+//! batch a number of completely unrelated operations. Looking at such a
+//! transaction as a whole makes it easy to stitch swaps from different
+//! operations together into something that looks like an arbitrage. To avoid
+//! this, the direct children of a multicall root are split into logical
+//! operation groups, one per call element, that inspectors can opt into
+//! evaluating separately.
+use alloy_primitives::{hex, Address};
+use reth_primitives::B256;
+
+use super::{BlockTree, Root};
+use crate::{
+    normalized_actions::{Action, NormalizedAction},
+    structured_trace::TraceActions,
+};
+
+pub const MULTICALL3: Address = Address::new(hex!("cA11bde05977b3631167028862bE2a173976CA11"));
+
+/// Function selectors of the common multicall entrypoints.
+const MULTICALL_SELECTORS: [[u8; 4]; 8] = [
+    // Multicall3 aggregate((address,bytes)[])
+    [0x25, 0x2d, 0xba, 0x42],
+    // Multicall3 tryAggregate(bool,(address,bytes)[])
+    [0xbc, 0xe3, 0x8b, 0xd7],
+    // Multicall3 aggregate3((address,bool,bytes)[])
+    [0x82, 0xad, 0x56, 0xcb],
+    // Multicall3 aggregate3Value((address,bool,uint256,bytes)[])
+    [0x17, 0x4d, 0xea, 0x71],
+    // Multicall3 blockAndAggregate((address,bytes)[])
+    [0xc3, 0x07, 0x7f, 0xa9],
+    // multicall(bytes[])
+    [0xac, 0x96, 0x50, 0xd8],
+    // multicall(uint256,bytes[])
+    [0x5a, 0xe4, 0x01, 0xdc],
+    // multicall(bytes32,bytes[])
+    [0x1f, 0x04, 0x64, 0xd1],
+];
+
+pub fn is_multicall_selector(calldata: &[u8]) -> bool {
+    calldata.len() >= 4 && MULTICALL_SELECTORS.iter().any(|s| s == &calldata[0..4])
+}
+
+/// The logical operations of a multicall transaction. Each operation spans
+/// the trace indexes from its call element up to the next element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationGroups {
+    /// trace index of the first trace of each operation, sorted ascending
+    starts: Vec<u64>,
+}
+
+impl OperationGroups {
+    pub fn new(mut starts: Vec<u64>) -> Self {
+        starts.sort_unstable();
+        starts.dedup();
+        Self { starts }
+    }
+
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// Returns the operation the trace index belongs to. Traces that happen
+    /// before the first call element (the multicall frame itself) belong to
+    /// no operation.
+    pub fn group_of(&self, trace_index: u64) -> Option<usize> {
+        match self.starts.binary_search(&trace_index) {
+            Ok(idx) => Some(idx),
+            Err(0) => None,
+            Err(idx) => Some(idx - 1),
+        }
+    }
+
+    /// Splits the items into their operations. Items that aren't part of any
+    /// operation are dropped.
+    pub fn split<T>(&self, items: Vec<T>, trace_index: impl Fn(&T) -> u64) -> Vec<Vec<T>> {
+        let mut groups = (0..self.len()).map(|_| Vec::new()).collect::<Vec<_>>();
+        for item in items {
+            if let Some(group) = self.group_of(trace_index(&item)) {
+                groups[group].push(item);
+            }
+        }
+
+        groups
+    }
+
+    /// Splits normalized actions into their operations.
+    pub fn split_actions<V: NormalizedAction>(&self, actions: Vec<V>) -> Vec<Vec<V>> {
+        self.split(actions, |action| action.get_trace_index())
+    }
+
+    /// Returns true if all of the trace indexes fall within the same
+    /// operation.
+    pub fn same_operation(&self, mut trace_indexes: impl Iterator<Item = u64>) -> bool {
+        let Some(first) = trace_indexes.next() else { return true };
+        let group = self.group_of(first);
+        trace_indexes.all(|idx| self.group_of(idx) == group)
+    }
+}
+
+impl<V: NormalizedAction> Root<V> {
+    /// Returns true if the top level call of the transaction is a multicall.
+    pub fn is_multicall(&self) -> bool {
+        let Some(root) = self.data_store.get_ref(0).and_then(|data| data.first()) else {
+            return false
+        };
+
+        match root.get_action() {
+            Action::Unclassified(trace) => {
+                trace.get_to_address() == MULTICALL3 || is_multicall_selector(&trace.get_calldata())
+            }
+            action => action.get_to_address() == MULTICALL3,
+        }
+    }
+
+    /// Splits the multicall into operation groups, one per call element.
+    /// Returns `None` if the transaction isn't a multicall or only contains a
+    /// single operation, as there is nothing to unwrap in that case.
+    pub fn operation_groups(&self) -> Option<OperationGroups> {
+        if !self.is_multicall() || self.head.inner.len() < 2 {
+            return None
+        }
+
+        Some(OperationGroups::new(self.head.inner.iter().map(|node| node.index).collect()))
+    }
+}
+
+impl<V: NormalizedAction> BlockTree<V> {
+    pub fn operation_groups(&self, tx_hash: B256) -> Option<OperationGroups> {
+        self.get_root(tx_hash)?.operation_groups()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_grouping() {
+        let groups = OperationGroups::new(vec![7, 1, 4]);
+
+        assert_eq!(groups.group_of(0), None);
+        assert_eq!(groups.group_of(1), Some(0));
+        assert_eq!(groups.group_of(3), Some(0));
+        assert_eq!(groups.group_of(4), Some(1));
+        assert_eq!(groups.group_of(12), Some(2));
+
+        assert!(groups.same_operation([4, 5, 6].into_iter()));
+        assert!(!groups.same_operation([3, 4].into_iter()));
+
+        let split = groups.split(vec![0u64, 2, 5, 6, 9], |i| *i);
+        assert_eq!(split, vec![vec![2], vec![5, 6], vec![9]]);
+    }
+
+    #[test]
+    fn test_multicall_selector() {
+        assert!(is_multicall_selector(&[0x5a, 0xe4, 0x01, 0xdc, 0x00]));
+        assert!(!is_multicall_selector(&[0x5a, 0xe4, 0x01]));
+        assert!(!is_multicall_selector(&[0xa9, 0x05, 0x9c, 0xbb]));
+    }
+}
@@ -15,7 +15,8 @@ use crate::{
         traits::LibmdbxReader,
     },
     normalized_actions::{
-        Action, MultiCallFrameClassification, NormalizedAction, NormalizedEthTransfer,
+        Action, DecodeOutcome, MultiCallFrameClassification, NormalizedAction,
+        NormalizedEthTransfer,
     },
     tree::types::NodeWithDataRef,
     FastHashMap, FastHashSet, TreeSearchBuilder, TxInfo,
@@ -60,6 +61,9 @@ pub struct Root<V: NormalizedAction> {
     /// eth transfers
     pub total_msg_value_transfers: Vec<NormalizedEthTransfer>,
     pub data_store: NodeData<V>,
+    /// How the classifier decoded the traces it needed a fallback for, by
+    /// trace index
+    pub decode_outcomes: FastHashMap<u64, DecodeOutcome>,
 }
 
 impl<V: NormalizedAction> Root<V> {
@@ -241,6 +245,24 @@ impl<V: NormalizedAction> Root<V> {
         self.head.insert(node, data, &mut self.data_store);
     }
 
+    /// Classification confidence of each classified action of the
+    /// transaction, from how the classifier decoded its trace
+    pub fn action_confidences(&self) -> impl Iterator<Item = f64> + '_ {
+        self.data_store
+            .0
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|action| action.is_classified() && !action.get_action().is_revert())
+            .map(|action| {
+                self.decode_outcomes
+                    .get(&action.get_trace_index())
+                    .copied()
+                    .unwrap_or_default()
+                    .confidence()
+            })
+    }
+
     pub fn collect_spans(&self, call: &TreeSearchBuilder<V>) -> Vec<Vec<V>> {
         let mut result = Vec::new();
         self.head.collect_spans(&mut result, call, &self.data_store);
@@ -338,6 +360,7 @@ impl<V: NormalizedAction> Root<V> {
     rkyv::Deserialize,
     rkyv::Archive,
 )]
+#[archive(check_bytes)]
 pub struct GasDetails {
     pub coinbase_transfer:   Option<u128>,
     pub priority_fee:        u128,
@@ -0,0 +1,243 @@
+//! Bounded memory for very large blocks.
+//!
+//! Airdrop claims and inscription spam produce blocks whose traces and tree
+//! don't fit in memory next to each other. With a [`MemoryBound`] given to the
+//! classifier, blocks with more transactions than the bound are handled in
+//! chunks. Their traces
+//! are moved to a temp file with a [`TraceSpill`] as they're taken, a chunk is
+//! written and freed as soon as it's full, and are then classified one chunk
+//! at a time, so only the chunk being classified is held next to the finalized
+//! roots. The tree keeps the bound it was built under, and the inspectors then
+//! run over overlapping chunks of its roots, see [`BlockTree::chunks`]. This is
+//! slower and mev spanning more than the overlap is missed, in exchange for
+//! surviving the block.
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::PathBuf,
+};
+
+use redefined::RedefinedConvert;
+use reth_db::table::{Compress, Decompress};
+
+use super::BlockTree;
+use crate::{
+    db::traces::{TxTracesInner, TxTracesInnerRedefined},
+    normalized_actions::NormalizedAction,
+    structured_trace::TxTrace,
+    BlockActionSummary,
+};
+
+#[derive(Debug, Clone)]
+pub struct MemoryBound {
+    /// Transactions of a block held in memory at once, blocks with more are
+    /// processed in chunks of this size
+    pub max_txs_in_memory: usize,
+    /// Transactions of the previous chunk the inspectors see again at the
+    /// start of the next one, so mev crossing a chunk boundary is found
+    pub chunk_overlap:     usize,
+    /// Directory the traces of large blocks are spilled to
+    pub spill_dir:         PathBuf,
+}
+
+impl MemoryBound {
+    pub fn applies_to(&self, txs: usize) -> bool {
+        txs > self.max_txs_in_memory
+    }
+}
+
+/// The traces of a block written to a temp file in chunks. The file is removed
+/// when the spill is dropped
+pub struct TraceSpill {
+    path:       PathBuf,
+    file:       File,
+    /// Offset and length of each chunk in the file
+    chunks:     Vec<(u64, usize)>,
+    /// Traces of the chunk being filled
+    pending:    Vec<TxTrace>,
+    chunk_size: usize,
+    offset:     u64,
+}
+
+impl TraceSpill {
+    /// Creates the spill file of the block in the spill dir
+    pub fn create(bound: &MemoryBound, block: u64) -> eyre::Result<Self> {
+        std::fs::create_dir_all(&bound.spill_dir)?;
+        let path = bound
+            .spill_dir
+            .join(format!("brontes-spill-{block}-{}", std::process::id()));
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .read(true)
+            .write(true)
+            .open(&path)?;
+        let chunk_size = bound.max_txs_in_memory.max(1);
+
+        Ok(Self {
+            path,
+            file,
+            chunks: vec![],
+            pending: Vec::with_capacity(chunk_size),
+            chunk_size,
+            offset: 0,
+        })
+    }
+
+    /// Writes the traces to a new spill as they're taken from the iterator,
+    /// freeing each chunk once it's written
+    pub fn new(
+        bound: &MemoryBound,
+        block: u64,
+        traces: impl IntoIterator<Item = TxTrace>,
+    ) -> eyre::Result<Self> {
+        let mut this = Self::create(bound, block)?;
+        for trace in traces {
+            this.push(trace)?;
+        }
+        this.finish()?;
+
+        Ok(this)
+    }
+
+    /// Adds the trace to the chunk being filled, writing the chunk out once
+    /// it's full
+    pub fn push(&mut self, trace: TxTrace) -> eyre::Result<()> {
+        self.pending.push(trace);
+        if self.pending.len() >= self.chunk_size {
+            self.write_pending()?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes out the last, partially filled, chunk
+    pub fn finish(&mut self) -> eyre::Result<()> {
+        if !self.pending.is_empty() {
+            self.write_pending()?;
+        }
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    fn write_pending(&mut self) -> eyre::Result<()> {
+        let traces = std::mem::replace(&mut self.pending, Vec::with_capacity(self.chunk_size));
+        let chunk =
+            TxTracesInnerRedefined::from_source(TxTracesInner::new(Some(traces))).compress();
+        self.file.write_all(&chunk)?;
+        self.chunks.push((self.offset, chunk.len()));
+        self.offset += chunk.len() as u64;
+
+        Ok(())
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn read_chunk(&mut self, chunk: usize) -> eyre::Result<Vec<TxTrace>> {
+        let (offset, len) = *self
+            .chunks
+            .get(chunk)
+            .ok_or_else(|| eyre::eyre!("no chunk {chunk} in trace spill"))?;
+
+        let mut buf = vec![0u8; len];
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read_exact(&mut buf)?;
+
+        Ok(TxTracesInnerRedefined::decompress(buf)?
+            .to_source()
+            .traces
+            .unwrap_or_default())
+    }
+}
+
+impl Drop for TraceSpill {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::warn!(path = ?self.path, err = %e, "failed to remove trace spill");
+        }
+    }
+}
+
+impl<V: NormalizedAction> BlockTree<V> {
+    /// Splits the tree in chunks of `size` roots, each starting with the last
+    /// `overlap` roots of the chunk before it. The chunks keep the block's
+    /// header and fee stats, and are built one at a time as the iterator is
+    /// consumed so only one chunk's roots are cloned at once
+    pub fn chunks(&self, size: usize, overlap: usize) -> impl Iterator<Item = BlockTree<V>> + '_ {
+        let size = size.max(1);
+        (0..self.tx_roots.len()).step_by(size).map(move |start| {
+            let end = (start + size).min(self.tx_roots.len());
+            let tx_roots = self.tx_roots[start.saturating_sub(overlap)..end].to_vec();
+            let action_summary = BlockActionSummary::new(
+                tx_roots
+                    .iter()
+                    .map(|root| root.data_store.0.iter().flatten().flatten()),
+            );
+
+            BlockTree {
+                tx_roots,
+                header: self.header.clone(),
+                priority_fee_std_dev: self.priority_fee_std_dev,
+                avg_priority_fee: self.avg_priority_fee,
+                action_summary,
+                memory_bound: None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalized_actions::Action;
+
+    #[test]
+    fn test_trace_spill_round_trip() {
+        let bound = MemoryBound {
+            max_txs_in_memory: 2,
+            chunk_overlap:     0,
+            spill_dir:         std::env::temp_dir(),
+        };
+        let traces = (0..5)
+            .map(|i| TxTrace { tx_index: i, ..Default::default() })
+            .collect::<Vec<_>>();
+
+        let mut spill = TraceSpill::new(&bound, 1, traces).unwrap();
+        assert_eq!(spill.chunk_count(), 3);
+        assert!(spill.pending.is_empty());
+        assert_eq!(spill.read_chunk(2).unwrap()[0].tx_index, 4);
+        assert_eq!(spill.read_chunk(0).unwrap()[1].tx_index, 1);
+    }
+
+    #[test]
+    fn test_chunks_overlap() {
+        let mut tree = BlockTree::<Action>::new(Default::default(), 5);
+        for position in 0..5 {
+            tree.insert_root(crate::tree::Root {
+                head: crate::tree::Node::new(0, Default::default(), vec![]),
+                position,
+                tx_hash: Default::default(),
+                private: false,
+                gas_details: Default::default(),
+                total_msg_value_transfers: vec![],
+                data_store: crate::tree::NodeData(vec![]),
+                decode_outcomes: Default::default(),
+            });
+        }
+
+        let chunks = tree
+            .chunks(2, 1)
+            .map(|chunk| {
+                chunk
+                    .tx_roots
+                    .iter()
+                    .map(|r| r.position)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(chunks, vec![vec![0, 1], vec![1, 2, 3], vec![3, 4]]);
+    }
+}
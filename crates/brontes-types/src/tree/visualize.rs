@@ -0,0 +1,281 @@
+//! Call graph of a transaction for debugging classification.
+//!
+//! [`BlockTree::tx_graph`] flattens the tree of a transaction into nodes
+//! annotated with the classified action, the label of the called address and
+//! the gas the call used, which can be dumped as json or rendered with
+//! Graphviz through [`TxGraph::to_dot`].
+use std::fmt::Write;
+
+use alloy_primitives::{Address, B256};
+use serde::Serialize;
+
+use super::{BlockTree, GasDetails, Node, Root};
+use crate::{
+    normalized_actions::{Action, NormalizedAction},
+    ToFloatNearest,
+};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TxGraph {
+    pub tx_hash:     B256,
+    pub position:    usize,
+    pub gas_details: GasDetails,
+    pub nodes:       Vec<GraphNode>,
+    /// Parent and child trace index of every call
+    pub edges:       Vec<(u64, u64)>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub trace_index:   u64,
+    pub trace_address: Vec<usize>,
+    pub address:       Address,
+    /// Label of the called address, if it is known
+    pub label:         Option<String>,
+    /// Kind of the actions classified at the node, `Unclassified` if none were
+    pub action:        String,
+    /// Short description of the actions classified at the node
+    pub details:       Vec<String>,
+    /// Gas used by the call, only known for unclassified calls as
+    /// classification drops the trace
+    pub gas_used:      Option<u64>,
+}
+
+impl<V: NormalizedAction> BlockTree<V> {
+    /// Call graph of the transaction, labeling addresses with `label`
+    pub fn tx_graph(
+        &self,
+        tx_hash: B256,
+        label: impl Fn(Address) -> Option<String>,
+    ) -> Option<TxGraph> {
+        let root = self.get_root(tx_hash)?;
+
+        let mut graph = TxGraph {
+            tx_hash,
+            position: root.position,
+            gas_details: root.gas_details,
+            nodes: vec![],
+            edges: vec![],
+        };
+        add_node(root, &root.head, &label, &mut graph);
+
+        Some(graph)
+    }
+
+    /// Graphviz rendering of the call graph of the transaction
+    pub fn to_dot(&self, tx_hash: B256) -> Option<String> {
+        self.tx_graph(tx_hash, |_| None).map(|graph| graph.to_dot())
+    }
+}
+
+fn add_node<V: NormalizedAction>(
+    root: &Root<V>,
+    node: &Node,
+    label: &impl Fn(Address) -> Option<String>,
+    graph: &mut TxGraph,
+) {
+    let actions = root
+        .data_store
+        .get_ref(node.data)
+        .map(|actions| actions.iter().map(|a| a.get_action()).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let gas_used = actions.iter().find_map(|action| match action {
+        Action::Unclassified(trace) => trace
+            .trace
+            .result
+            .as_ref()
+            .map(|res| res.gas_used().to::<u64>()),
+        _ => None,
+    });
+    let mut kinds = actions.iter().map(|a| action_kind(a)).collect::<Vec<_>>();
+    kinds.dedup();
+
+    graph.nodes.push(GraphNode {
+        trace_index: node.index,
+        trace_address: node.trace_address.clone(),
+        address: node.address,
+        label: label(node.address),
+        action: if kinds.is_empty() { "Unclassified".to_string() } else { kinds.join(", ") },
+        details: actions
+            .iter()
+            .filter(|a| !a.is_unclassified())
+            .map(|a| describe(a))
+            .collect(),
+        gas_used,
+    });
+
+    for child in &node.inner {
+        graph.edges.push((node.index, child.index));
+        add_node(root, child, label, graph);
+    }
+}
+
+fn action_kind(action: &Action) -> &'static str {
+    match action {
+        Action::Swap(_) => "Swap",
+        Action::SwapWithFee(_) => "SwapWithFee",
+        Action::FlashLoan(_) => "FlashLoan",
+        Action::Batch(_) => "Batch",
+        Action::Transfer(_) => "Transfer",
+        Action::Mint(_) => "Mint",
+        Action::Burn(_) => "Burn",
+        Action::Collect(_) => "Collect",
+        Action::Liquidation(_) => "Liquidation",
+        Action::SelfDestruct(_) => "SelfDestruct",
+        Action::EthTransfer(_) => "EthTransfer",
+        Action::NftTransfer(_) => "NftTransfer",
+        Action::Fill(_) => "Fill",
+        Action::NewPool(_) => "NewPool",
+        Action::PoolConfigUpdate(_) => "PoolConfigUpdate",
+        Action::Aggregator(_) => "Aggregator",
+        Action::Unclassified(_) => "Unclassified",
+        Action::Revert => "Revert",
+    }
+}
+
+fn describe(action: &Action) -> String {
+    match action {
+        Action::Swap(s) => format!(
+            "{} {:.4} {} -> {:.4} {}",
+            s.protocol,
+            s.amount_in.clone().to_float(),
+            s.token_in.symbol,
+            s.amount_out.clone().to_float(),
+            s.token_out.symbol
+        ),
+        Action::SwapWithFee(s) => format!(
+            "{} {:.4} {} -> {:.4} {}",
+            s.protocol,
+            s.amount_in.clone().to_float(),
+            s.token_in.symbol,
+            s.amount_out.clone().to_float(),
+            s.token_out.symbol
+        ),
+        Action::Transfer(t) => format!(
+            "{:.4} {} {:?} -> {:?}",
+            t.amount.clone().to_float(),
+            t.token.symbol,
+            t.from,
+            t.to
+        ),
+        Action::EthTransfer(e) => format!("{} wei {:?} -> {:?}", e.value, e.from, e.to),
+        Action::Mint(m) => format!("{} {:?}", m.protocol, m.pool),
+        Action::Burn(b) => format!("{} {:?}", b.protocol, b.pool),
+        Action::Collect(c) => format!("{} {:?}", c.protocol, c.pool),
+        Action::Liquidation(l) => format!("{} debtor {:?}", l.protocol, l.debtor),
+        Action::Fill(f) => format!(
+            "{} {:.4} {} -> {:.4} {} maker {:?}",
+            f.protocol,
+            f.maker_amount.clone().to_float(),
+            f.maker_token.symbol,
+            f.taker_amount.clone().to_float(),
+            f.taker_token.symbol,
+            f.maker
+        ),
+        Action::NewPool(p) => format!("{} {:?}", p.protocol, p.pool_address),
+        action => action.get_protocol().to_string(),
+    }
+}
+
+impl TxGraph {
+    /// Graphviz dot rendering of the graph, nodes are keyed by trace index
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph \"{:?}\" {{", self.tx_hash);
+        let _ = writeln!(dot, "  node [shape=box, fontname=\"monospace\"];");
+        let _ = writeln!(
+            dot,
+            "  label=\"{:?} gas used: {}\";",
+            self.tx_hash, self.gas_details.gas_used
+        );
+
+        for node in &self.nodes {
+            let mut label = format!("[{}] {}", node.trace_index, node.action);
+            label.push_str("\\n");
+            if let Some(address_label) = &node.label {
+                let _ = write!(label, "{} ", escape(address_label));
+            }
+            let _ = write!(label, "{:?}", node.address);
+            for details in &node.details {
+                let _ = write!(label, "\\n{}", escape(details));
+            }
+            if let Some(gas) = node.gas_used {
+                let _ = write!(label, "\\ngas: {gas}");
+            }
+
+            let style = if node.action == "Unclassified" { "" } else { ", style=filled" };
+            let _ = writeln!(dot, "  n{} [label=\"{}\"{style}];", node.trace_index, label);
+        }
+
+        for (parent, child) in &self.edges {
+            let _ = writeln!(dot, "  n{parent} -> n{child};");
+        }
+        dot.push('}');
+
+        dot
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use malachite::Rational;
+
+    use super::*;
+    use crate::{normalized_actions::NormalizedSwap, Protocol};
+
+    #[test]
+    fn test_describe_swap() {
+        let mut swap = NormalizedSwap {
+            protocol: Protocol::UniswapV2,
+            amount_in: Rational::from(2),
+            amount_out: Rational::from_signeds(1, 2),
+            ..Default::default()
+        };
+        swap.token_in.inner.symbol = "WETH".to_string();
+        swap.token_out.inner.symbol = "USDC".to_string();
+
+        assert_eq!(describe(&Action::Swap(swap)), "Uni V2 2.0000 WETH -> 0.5000 USDC");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let node = |trace_index, action: &str| GraphNode {
+            trace_index,
+            trace_address: vec![],
+            address: Address::ZERO,
+            label: None,
+            action: action.to_string(),
+            details: vec![],
+            gas_used: None,
+        };
+        let graph = TxGraph {
+            tx_hash:     B256::ZERO,
+            position:    0,
+            gas_details: GasDetails { gas_used: 21_000, ..Default::default() },
+            nodes:       vec![
+                GraphNode { label: Some("Router \"v2\"".to_string()), ..node(0, "Swap") },
+                GraphNode { gas_used: Some(5_000), ..node(1, "Unclassified") },
+            ],
+            edges:       vec![(0, 1)],
+        };
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("gas used: 21000"));
+        // labels are escaped and classified nodes are filled
+        assert!(dot.contains(&format!(
+            "n0 [label=\"[0] Swap\\nRouter \\\"v2\\\" {:?}\", style=filled];",
+            Address::ZERO
+        )));
+        assert!(dot.contains(&format!(
+            "n1 [label=\"[1] Unclassified\\n{:?}\\ngas: 5000\"];",
+            Address::ZERO
+        )));
+        assert!(dot.contains("n0 -> n1;"));
+        assert!(dot.ends_with('}'));
+    }
+}
@@ -4,7 +4,8 @@ use std::{
     sync::Arc,
 };
 
-use brontes_types::{structured_trace::TxTrace, BrontesTaskExecutor};
+use brontes_types::{structured_trace::TxTrace, traits::TxTraceStream, BrontesTaskExecutor};
+use futures::StreamExt;
 use reth_beacon_consensus::BeaconConsensus;
 use reth_blockchain_tree::{
     externals::TreeExternals, BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree,
@@ -149,30 +150,74 @@ impl TracingClient {
         &self,
         block_id: BlockId,
     ) -> EthResult<Option<Vec<TxTrace>>> {
-        let insp_setup = || BrontesTracingInspector {
-            config:                TracingInspectorConfig {
-                record_logs:              true,
-                record_steps:             false,
-                record_state_diff:        false,
-                record_stack_snapshots:   StackSnapshotType::None,
-                record_memory_snapshots:  false,
-                record_call_return_data:  true,
-                exclude_precompile_calls: true,
-            },
-            traces:                CallTraceArena::default(),
-            trace_stack:           Vec::new(),
-            step_stack:            Vec::new(),
-            last_call_return_data: None,
-            gas_inspector:         GasInspector::default(),
-            spec_id:               None,
-        };
-
         self.api
-            .trace_block_with_inspector(block_id, insp_setup, move |tx_info, inspector, res, _, _| {
-                Ok(inspector.into_trace_results(tx_info, &res))
-            })
+            .trace_block_with_inspector(
+                block_id,
+                brontes_inspector,
+                move |tx_info, inspector, res, _, _| {
+                    Ok(inspector.into_trace_results(tx_info, &res))
+                },
+            )
             .await
     }
+
+    /// Replays all transactions in a block like
+    /// [`Self::replay_block_transactions_with_inspector`], but sends out each
+    /// transaction's trace as soon as it has been traced instead of once the
+    /// whole block is done
+    pub fn stream_block_transactions_with_inspector(&self, block_id: BlockId) -> TxTraceStream {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let api = self.api.clone();
+
+        tokio::spawn(async move {
+            let traces = tx.clone();
+            let res = api
+                .trace_block_with_inspector(
+                    block_id,
+                    brontes_inspector,
+                    move |tx_info, inspector, res, _, _| {
+                        let _ = traces.send(Ok(inspector.into_trace_results(tx_info, &res)));
+                        Ok(())
+                    },
+                )
+                .await;
+
+            // the stream ends with an error if the block couldn't be traced,
+            // so consumers never mistake a partial block for a complete one
+            let err = match res {
+                Ok(Some(_)) => return,
+                Ok(None) => eyre::eyre!("block {block_id:?} not found"),
+                Err(e) => e.into(),
+            };
+            let _ = tx.send(Err(err));
+        });
+
+        futures::stream::unfold(
+            rx,
+            |mut rx| async move { rx.recv().await.map(|trace| (trace, rx)) },
+        )
+        .boxed()
+    }
+}
+
+fn brontes_inspector() -> BrontesTracingInspector {
+    BrontesTracingInspector {
+        config:                TracingInspectorConfig {
+            record_logs:              true,
+            record_steps:             false,
+            record_state_diff:        false,
+            record_stack_snapshots:   StackSnapshotType::None,
+            record_memory_snapshots:  false,
+            record_call_return_data:  true,
+            exclude_precompile_calls: true,
+        },
+        traces:                CallTraceArena::default(),
+        trace_stack:           Vec::new(),
+        step_stack:            Vec::new(),
+        last_call_return_data: None,
+        gas_inspector:         GasInspector::default(),
+        spec_id:               None,
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -1,7 +1,10 @@
 use std::cmp::min;
 
 use alloy_rpc_types::AnyReceiptEnvelope;
-use brontes_types::{structured_trace::TxTrace, traits::TracingProvider};
+use brontes_types::{
+    structured_trace::TxTrace,
+    traits::{TracingProvider, TxTraceStream},
+};
 use eyre::eyre;
 use reth_primitives::{
     Address, BlockId, BlockNumber, BlockNumberOrTag, Bytecode, Bytes, Header, StorageValue, TxHash,
@@ -88,6 +91,13 @@ impl TracingProvider for TracingClient {
             .map_err(Into::into)
     }
 
+    async fn replay_block_transactions_streamed(
+        &self,
+        block_id: BlockId,
+    ) -> eyre::Result<TxTraceStream> {
+        Ok(self.stream_block_transactions_with_inspector(block_id))
+    }
+
     async fn block_receipts(
         &self,
         number: BlockNumberOrTag,